@@ -1,9 +1,399 @@
-use super::{cursor, ordering, pager::Pager};
+use super::{
+    asset_identifier, cursor, keys, multisig, network, ordering, passphrase, pager::Pager,
+    transactions,
+};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
 use clap::ArgMatches;
-use error::Result;
-use fmt::{Formatter, Simple};
+use error::{InvalidInputError, Result};
+use fmt::{Formatter, Simple, Table};
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use stellar_client::endpoint::{friendbot, ledger};
+use stellar_client::error::Error;
+use stellar_client::history_export::HistoryExport;
+use stellar_client::keypair::{Keypair, PublicKey};
+use stellar_client::keystore::KeyFile;
+use stellar_client::network::Network;
+use stellar_client::portfolio::{Portfolio, Pricing};
+use stellar_client::resources::{Account, Amount, AssetIdentifier, SignedAmount};
+use stellar_client::transaction::{Operation, SetOptions, SignerSetup, TransactionBuilder};
 use stellar_client::{sync, endpoint::account, sync::Client};
 
+pub fn create(client: &Client, matches: &ArgMatches) -> Result<()> {
+    if matches.is_present("dry-run") {
+        return Err(InvalidInputError::from_str(
+            "New accounts are funded by friendbot, which can't be dry run",
+        )
+        .into());
+    }
+    let trustlines = match matches.values_of("trust") {
+        Some(values) => values
+            .map(parse_trustline)
+            .collect::<::std::result::Result<Vec<_>, _>>()?,
+        None => Vec::new(),
+    };
+
+    let keystore = match matches.value_of("key") {
+        Some(name) => {
+            let keystore = keys::keystore()?;
+            if keystore.contains(name) {
+                return Err(format!("A key is already stored under {:?}", name).into());
+            }
+            Some((keystore, name))
+        }
+        None => None,
+    };
+
+    let keypair = Keypair::random();
+    let account_id = keypair.account_id();
+
+    // The secret is saved before anything touches the network so a failure
+    // further on never loses the only copy of it.
+    let passphrase = passphrase::prompt_new()?;
+    let file = KeyFile::encrypt(&keypair, &passphrase)?;
+    println!("Account:  {}", account_id);
+    match keystore {
+        Some((keystore, name)) => {
+            keystore.insert(name, &file)?;
+            println!("Key:      {}", name);
+        }
+        None => {
+            let path = matches
+                .value_of("keystore")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from(format!("{}.json", account_id)));
+            file.save(&path)?;
+            println!("Keystore: {}", path.display());
+        }
+    }
+
+    // Friendbot only runs on the test network, so that's the only network
+    // new accounts are funded and set up on.
+    if !client.is_horizon_test() {
+        print_funding_template(client, &account_id, trustlines.len());
+        return Ok(());
+    }
+
+    let funded = client.request(friendbot::Fund::new(&account_id))?;
+    println!("Funded by friendbot in ledger {}", funded.ledger());
+
+    if !trustlines.is_empty() {
+        let account = client.request(account::Details::new(&account_id))?;
+        let builder = trustlines.iter().fold(
+            TransactionBuilder::from_account(&account, Network::TESTNET),
+            |builder, asset| builder.with_operation(Operation::change_trust(asset.clone())),
+        );
+        let submitted = match transactions::submit(client, matches, builder.build()?, &keypair)? {
+            Some(submitted) => submitted,
+            None => return Ok(()),
+        };
+        for asset in &trustlines {
+            println!(
+                "Trusting {}-{} in ledger {}",
+                asset.code(),
+                asset.issuer(),
+                submitted.ledger()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_trustline(value: &str) -> Result<AssetIdentifier> {
+    let asset = asset_identifier::from_str(value)?;
+    if asset.is_native() {
+        return Err(
+            InvalidInputError::from_str("Accounts always hold lumens, they can't be trusted").into(),
+        );
+    }
+    Ok(asset)
+}
+
+/// Accounts on other networks have to be created by someone who already has
+/// lumens, so print what they need to send.
+fn print_funding_template(client: &Client, account_id: &str, trustlines: usize) {
+    // The key is already saved, so fall back to the usual half lumen
+    // reserve rather than failing if the latest ledger can't be fetched.
+    let base_reserve = client
+        .request(ledger::All::latest())
+        .ok()
+        .and_then(|ledgers| {
+            ledgers
+                .records()
+                .first()
+                .map(|ledger| ledger.base_reserve_in_stroops())
+        })
+        .unwrap_or(5_000_000);
+    // Two base reserves for the account and one for each trust line.
+    let starting_balance = Amount::new(base_reserve * (2 + trustlines as i64));
+
+    println!();
+    println!("The account doesn't exist until it is funded. From an account that");
+    println!("holds lumens, submit a create account operation with:");
+    println!();
+    println!("  Destination:      {}", account_id);
+    println!("  Starting balance: {} XLM or more to cover fees", starting_balance);
+    if trustlines > 0 {
+        println!();
+        println!("Trust lines can only be added once the account has been funded.");
+    }
+}
+
+pub fn merge(client: &Client, matches: &ArgMatches) -> Result<()> {
+    let network_passphrase = network::passphrase(client, matches)?;
+    let name = matches.value_of("from").expect("From is required");
+    let destination = matches.value_of("into").expect("Into is required");
+    PublicKey::from_account_id(destination).map_err(|_| {
+        InvalidInputError::from(format!("{} isn't a valid account id", destination))
+    })?;
+    let account_id = keys::keystore()?.get(name)?.account_id().to_string();
+    if account_id == destination {
+        return Err(InvalidInputError::from_str("An account can't be merged into itself").into());
+    }
+
+    let account = client.request(account::Details::new(&account_id))?;
+    let mut offer_ids = Vec::new();
+    for offer in sync::Iter::new(&client, account::Offers::new(&account_id)) {
+        offer_ids.push(offer?.id());
+    }
+    let mut blockers = merge_blockers(&account, &offer_ids);
+    match client.request(account::Details::new(destination)) {
+        Ok(_) => {}
+        Err(Error::BadResponse(ref err)) if err.is_not_found() => {
+            blockers.push(format!("{} doesn't exist yet", destination));
+        }
+        Err(err) => return Err(err.into()),
+    }
+    if !blockers.is_empty() {
+        eprintln!("{} can't be merged until:", account_id);
+        for blocker in &blockers {
+            eprintln!("  - {}", blocker);
+        }
+        return Err(InvalidInputError::from_str("The account was not merged").into());
+    }
+
+    let lumens = account
+        .balance(&AssetIdentifier::native())
+        .map(|balance| balance.balance())
+        .unwrap_or_else(|| Amount::new(0));
+    if !matches.is_present("yes") && !matches.is_present("dry-run") {
+        eprint!(
+            "Merging {} sends its {} XLM to {} and removes it for good. Type {} to confirm: ",
+            account_id, lumens, destination, name
+        );
+        io::stderr().flush()?;
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        if answer.trim() != name {
+            return Err(InvalidInputError::from_str("The account was not merged").into());
+        }
+    }
+    let keypair = keys::unlock(name)?;
+    let envelope = TransactionBuilder::from_account(&account, network_passphrase)
+        .with_operation(Operation::account_merge(destination))
+        .build()?;
+    let submitted = match transactions::submit(client, matches, envelope, &keypair)? {
+        Some(submitted) => submitted,
+        None => return Ok(()),
+    };
+    println!(
+        "Merged into {} in ledger {}",
+        destination,
+        submitted.ledger()
+    );
+    println!("Hash: {}", submitted.hash());
+    Ok(())
+}
+
+pub fn set(client: &Client, matches: &ArgMatches) -> Result<()> {
+    let network_passphrase = network::passphrase(client, matches)?;
+    let name = matches.value_of("key").expect("Key is required");
+    let (options, changes) = set_options(matches)?;
+    if changes.is_empty() {
+        return Err(InvalidInputError::from_str(
+            "Nothing to set, give --home-domain, --set-flag, --clear-flag, --master-weight, \
+             --thresholds or --inflation-dest",
+        )
+        .into());
+    }
+
+    let account_id = keys::keystore()?.get(name)?.account_id().to_string();
+    let account = client.request(account::Details::new(&account_id))?;
+    eprintln!("Setting options on {}:", account_id);
+    for change in &changes {
+        eprintln!("  {}", change);
+    }
+    if !matches.is_present("yes") && !matches.is_present("dry-run") {
+        eprint!("Submit these changes? [y/N] ");
+        io::stderr().flush()?;
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        if !["y", "yes"].contains(&answer.trim().to_lowercase().as_str()) {
+            return Err(InvalidInputError::from_str("The options were not set").into());
+        }
+    }
+    let keypair = keys::unlock(name)?;
+    let envelope = TransactionBuilder::from_account(&account, network_passphrase)
+        .with_operation(Operation::set_options(options))
+        .build()?;
+    if let Some(submitted) = transactions::submit(client, matches, envelope, &keypair)? {
+        println!("Submitted in ledger {}", submitted.ledger());
+        println!("Hash: {}", submitted.hash());
+    }
+    Ok(())
+}
+
+/// The account flags that can be set and cleared, by the names horizon
+/// gives them.
+const FLAGS: &[(&str, u32)] = &[
+    ("auth_required", 0x1),
+    ("auth_revocable", 0x2),
+    ("auth_immutable", 0x4),
+    ("auth_clawback_enabled", 0x8),
+];
+
+fn parse_flags(names: Option<::clap::Values>) -> Result<u32> {
+    let mut flags = 0;
+    for name in names.into_iter().flatten() {
+        match FLAGS.iter().find(|&&(flag, _)| flag == name) {
+            Some(&(_, bit)) => flags |= bit,
+            None => return Err(format!("{:?} isn't an account flag", name).into()),
+        }
+    }
+    Ok(flags)
+}
+
+fn flag_names(flags: u32) -> String {
+    let names: Vec<_> = FLAGS
+        .iter()
+        .filter(|&&(_, bit)| flags & bit != 0)
+        .map(|&(name, _)| name)
+        .collect();
+    names.join(", ")
+}
+
+/// The set options operation the arguments ask for, along with a line
+/// describing each change to confirm before it's submitted.
+fn set_options(matches: &ArgMatches) -> Result<(SetOptions, Vec<String>)> {
+    let mut options = SetOptions::default();
+    let mut changes = Vec::new();
+    if let Some(domain) = matches.value_of("home-domain") {
+        if domain.len() > 32 {
+            return Err(InvalidInputError::from_str(
+                "Home domains are at most 32 characters long",
+            )
+            .into());
+        }
+        options = options.with_home_domain(domain);
+        if domain.is_empty() {
+            changes.push("Home domain:    removed".to_string());
+        } else {
+            changes.push(format!("Home domain:    {}", domain));
+        }
+    }
+    let set_flags = parse_flags(matches.values_of("set-flag"))?;
+    let clear_flags = parse_flags(matches.values_of("clear-flag"))?;
+    if set_flags & clear_flags != 0 {
+        return Err(format!(
+            "{} can't be both set and cleared",
+            flag_names(set_flags & clear_flags)
+        )
+        .into());
+    }
+    if set_flags != 0 {
+        options = options.with_set_flags(set_flags);
+        changes.push(format!("Set flags:      {}", flag_names(set_flags)));
+        if set_flags & 0x4 != 0 {
+            changes.push(
+                "                auth_immutable can never be cleared, and the account can \
+                 never be merged"
+                    .to_string(),
+            );
+        }
+    }
+    if clear_flags != 0 {
+        options = options.with_clear_flags(clear_flags);
+        changes.push(format!("Clear flags:    {}", flag_names(clear_flags)));
+    }
+    if let Some(value) = matches.value_of("master-weight") {
+        let weight = multisig::parse_weight(value)?;
+        options = options.with_master_weight(weight);
+        if weight == 0 {
+            changes.push(
+                "Master weight:  0, the account's own key can no longer sign".to_string(),
+            );
+        } else {
+            changes.push(format!("Master weight:  {}", weight));
+        }
+    }
+    if let Some(value) = matches.value_of("thresholds") {
+        let (low, med, high) = multisig::parse_thresholds(value)?;
+        options = options.with_thresholds(low, med, high);
+        changes.push(format!("Thresholds:     {}/{}/{}", low, med, high));
+    }
+    if let Some(destination) = matches.value_of("inflation-dest") {
+        PublicKey::from_account_id(destination).map_err(|_| {
+            InvalidInputError::from(format!("{} isn't a valid account id", destination))
+        })?;
+        options = options.with_inflation_dest(destination);
+        changes.push(format!("Inflation dest: {}", destination));
+    }
+    Ok((options, changes))
+}
+
+/// The reasons the network would refuse to merge the account. Anything that
+/// takes up a reserve has to be removed first, and sponsored reserves have
+/// to be handed back.
+fn merge_blockers(account: &Account, offer_ids: &[u64]) -> Vec<String> {
+    let mut blockers = Vec::new();
+    for id in offer_ids {
+        blockers.push(format!("offer {} is cancelled", id));
+    }
+    let mut keys: Vec<_> = account.data().keys().collect();
+    keys.sort();
+    for key in &keys {
+        blockers.push(format!("data entry {:?} is removed", key));
+    }
+    // Trust lines to pool shares count as two subentries.
+    let mut trust_line_entries = 0;
+    for balance in account.balances() {
+        let (holding, entries) = match (balance.asset(), balance.liquidity_pool_id()) {
+            (Some(asset), _) if asset.is_native() => continue,
+            (Some(asset), _) => (format!("{}-{}", asset.code(), asset.issuer()), 1),
+            (None, Some(pool)) => (format!("liquidity pool {}", pool), 2),
+            (None, None) => continue,
+        };
+        trust_line_entries += entries;
+        if balance.balance() == Amount::new(0) {
+            blockers.push(format!("the trust line to {} is removed", holding));
+        } else {
+            blockers.push(format!(
+                "the {} {} is sent away and the trust line removed",
+                balance.balance(),
+                holding
+            ));
+        }
+    }
+    // Signers are the only other subentries, and horizon doesn't list them
+    // alongside balances.
+    let listed = (offer_ids.len() + keys.len() + trust_line_entries) as u64;
+    if account.subentry_count() > listed {
+        blockers.push(format!(
+            "{} other subentries, such as signers, are removed",
+            account.subentry_count() - listed
+        ));
+    }
+    if account.num_sponsoring() > 0 {
+        blockers.push(format!(
+            "the {} reserves it sponsors for other accounts are revoked or transferred",
+            account.num_sponsoring()
+        ));
+    }
+    blockers
+}
+
 pub fn data(client: &Client, matches: &ArgMatches) -> Result<()> {
     let id = matches.value_of("ID").expect("ID is required");
     let key = matches.value_of("key").expect("Key is required");
@@ -25,6 +415,235 @@ pub fn details(client: &Client, matches: &ArgMatches) -> Result<()> {
     Ok(())
 }
 
+/// Prints each balance of an account with the amount that can be spent, which
+/// leaves out what open offers are selling and, for lumens, the reserve. With
+/// `--quote` each balance is also valued in the quote asset by what selling
+/// it would fetch along the best path horizon finds.
+pub fn balance(client: &Client, matches: &ArgMatches) -> Result<()> {
+    let id = matches.value_of("ID").expect("ID is required");
+    let quote = match matches.value_of("quote") {
+        Some(quote) => Some(asset_identifier::from_str(quote)?),
+        None => None,
+    };
+    let account = client.request(account::Details::new(id))?;
+    let ledgers = client.request(ledger::All::latest())?;
+    let base_reserve = ledgers
+        .records()
+        .first()
+        .map(|ledger| ledger.base_reserve_as_amount())
+        .ok_or_else(|| {
+            InvalidInputError::from_str("Horizon has no ledgers to take the reserve from")
+        })?;
+    let portfolio = match quote {
+        Some(ref quote) => Some(Portfolio::value(
+            client,
+            account.balances(),
+            quote,
+            Pricing::Path,
+        )?),
+        None => None,
+    };
+    print!(
+        "{}",
+        describe_balances(&account, base_reserve, portfolio.as_ref())
+    );
+    Ok(())
+}
+
+fn describe_balances(
+    account: &Account,
+    base_reserve: Amount,
+    portfolio: Option<&Portfolio>,
+) -> String {
+    let mut rows = vec![(
+        "Asset".to_string(),
+        "Balance".to_string(),
+        "Available".to_string(),
+        portfolio.map_or(String::new(), |portfolio| {
+            format!("Value ({})", portfolio.quote().code())
+        }),
+    )];
+    for balance in account.balances() {
+        let (name, available) = match balance.asset() {
+            Some(asset) if asset.is_native() => (
+                "XLM".to_string(),
+                account.available_balance(base_reserve),
+            ),
+            Some(asset) => (
+                format!("{}-{}", asset.code(), asset.issuer()),
+                &SignedAmount::from(balance.balance()) - &balance.selling_liabilities(),
+            ),
+            None => (
+                format!(
+                    "pool {}",
+                    balance.liquidity_pool_id().unwrap_or_default()
+                ),
+                SignedAmount::from(balance.balance()),
+            ),
+        };
+        let value = match (portfolio, balance.asset()) {
+            (Some(portfolio), Some(asset)) => portfolio
+                .valuations()
+                .iter()
+                .find(|valuation| valuation.asset() == asset)
+                .and_then(|valuation| valuation.value())
+                .map_or("no market".to_string(), |value| value.to_string()),
+            _ => String::new(),
+        };
+        rows.push((
+            name,
+            balance.balance().to_string(),
+            available.to_string(),
+            value,
+        ));
+    }
+
+    let mut widths = (0, 0, 0);
+    for row in &rows {
+        widths.0 = widths.0.max(row.0.len());
+        widths.1 = widths.1.max(row.1.len());
+        widths.2 = widths.2.max(row.2.len());
+    }
+    let mut out = String::new();
+    for row in &rows {
+        let line = format!(
+            "{:<w0$}  {:>w1$}  {:>w2$}  {}",
+            row.0,
+            row.1,
+            row.2,
+            row.3,
+            w0 = widths.0,
+            w1 = widths.1,
+            w2 = widths.2
+        );
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    if let Some(portfolio) = portfolio {
+        out.push_str(&format!(
+            "\nTotal: {} {}{}\n",
+            portfolio.total(),
+            portfolio.quote().code(),
+            if portfolio.is_complete() {
+                ""
+            } else {
+                ", leaving out the balances with no market"
+            }
+        ));
+    }
+    out
+}
+
+/// Prints the signers of an account with their types and weights, and the
+/// thresholds they are measured against, noting when the master key can no
+/// longer sign.
+pub fn signers(client: &Client, matches: &ArgMatches) -> Result<()> {
+    let id = matches.value_of("ID").expect("ID is required");
+    let account = client.request(account::Details::new(id))?;
+    print!("{}", describe_signers(&account));
+    Ok(())
+}
+
+fn describe_signers(account: &Account) -> String {
+    let setup = SignerSetup::of(account);
+    let thresholds = setup.thresholds();
+    let mut out = String::new();
+    out.push_str(&format!(
+        "Thresholds:     low {}, medium {}, high {}\n",
+        thresholds.low(),
+        thresholds.med(),
+        thresholds.high()
+    ));
+    out.push_str(&match setup.master_weight() {
+        0 => "Master key:     disabled\n".to_string(),
+        weight => format!("Master key:     weight {}\n", weight),
+    });
+    out.push_str(&format!(
+        "Signing weight: {}{}\n",
+        setup.signing_weight(),
+        if setup.can_meet(thresholds.high()) {
+            ""
+        } else {
+            ", too little to meet the high threshold"
+        }
+    ));
+    out.push_str(&format!("Signers:        {}\n", setup.signers().len()));
+    for signer in setup.signers() {
+        let master = if signer.key() == account.account_id_ref() {
+            " (master)"
+        } else {
+            ""
+        };
+        out.push_str(&format!(
+            "  {} {:<22} {:>3}{}\n",
+            signer.key(),
+            signer.signer_type(),
+            signer.weight(),
+            master
+        ));
+    }
+    out
+}
+
+pub fn export(client: &Client, matches: &ArgMatches) -> Result<()> {
+    let id = matches.value_of("ID").expect("ID is required");
+    let start = parse_day(matches.value_of("from").expect("From is required"), false)?;
+    let now = Utc::now();
+    let end = match matches.value_of("to") {
+        Some(to) => parse_day(to, true)?.min(now),
+        None => now,
+    };
+    if start >= end {
+        return Err(InvalidInputError::from_str("The export has to start before it ends").into());
+    }
+
+    // Horizon only knows the balances the account holds now, so the ones it
+    // held at the end of the export are worked back from the changes since.
+    let account = client.request(account::Details::new(id))?;
+    let mut closing = HashMap::new();
+    for balance in account.balances() {
+        if let Some(asset) = balance.asset() {
+            closing.insert(asset.clone(), SignedAmount::from(balance.balance()));
+        }
+    }
+    if end < now {
+        closing = HistoryExport::fetch(client, id, end, now)?.balances_before(&closing);
+    }
+    let export = HistoryExport::fetch(client, id, start, end)?;
+    let opening = export.balances_before(&closing);
+    let export = export.with_opening_balances(opening);
+
+    let stdout = io::stdout();
+    match matches.value_of("output") {
+        Some("json") => {
+            let json = export.to_json().map_err(|err| err.to_string())?;
+            writeln!(stdout.lock(), "{}", json)?;
+        }
+        _ => export.write_csv(stdout.lock())?,
+    }
+    Ok(())
+}
+
+/// Parses a day such as `2023-01-01` as midnight UTC, or the midnight after
+/// it for the last day of a range, so that the whole day is included. Full
+/// ISO 8601 times are used as they are.
+fn parse_day(value: &str, last: bool) -> Result<DateTime<Utc>> {
+    if let Ok(time) = value.parse::<DateTime<Utc>>() {
+        return Ok(time);
+    }
+    let day = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+        InvalidInputError::from(format!(
+            "{} should be a day such as 2023-01-01, or a time in ISO 8601 format",
+            value
+        ))
+    })?;
+    let day = if last { day.succ_opt() } else { Some(day) };
+    let midnight = day
+        .and_then(|day| day.and_hms_opt(0, 0, 0))
+        .ok_or_else(|| InvalidInputError::from(format!("{} is out of range", value)))?;
+    Ok(Utc.from_utc_datetime(&midnight))
+}
+
 pub fn transactions(client: &Client, matches: &ArgMatches) -> Result<()> {
     let pager = Pager::from_arg(&matches);
 
@@ -37,7 +656,7 @@ pub fn transactions(client: &Client, matches: &ArgMatches) -> Result<()> {
     let iter = sync::Iter::new(&client, endpoint);
 
     let mut res = Ok(());
-    let mut fmt = Formatter::start_stdout(Simple::new());
+    let mut fmt = Formatter::start_stdout(Table::from_arg(matches)?);
     pager.paginate(iter, |result| match result {
         Ok(txn) => fmt.render(&txn),
         Err(err) => res = Err(err.into()),
@@ -58,7 +677,7 @@ pub fn effects(client: &Client, matches: &ArgMatches) -> Result<()> {
     let iter = sync::Iter::new(&client, endpoint);
 
     let mut res = Ok(());
-    let mut fmt = Formatter::start_stdout(Simple::new());
+    let mut fmt = Formatter::start_stdout(Table::from_arg(matches)?);
     pager.paginate(iter, |result| match result {
         Ok(effect) => fmt.render(&effect),
         Err(err) => res = Err(err.into()),
@@ -79,7 +698,7 @@ pub fn offers(client: &Client, matches: &ArgMatches) -> Result<()> {
     let iter = sync::Iter::new(&client, endpoint);
 
     let mut res = Ok(());
-    let mut fmt = Formatter::start_stdout(Simple::new());
+    let mut fmt = Formatter::start_stdout(Table::from_arg(matches)?);
     pager.paginate(iter, |result| match result {
         Ok(offer) => fmt.render(&offer),
         Err(err) => res = Err(err.into()),
@@ -100,7 +719,7 @@ pub fn operations(client: &Client, matches: &ArgMatches) -> Result<()> {
     let iter = sync::Iter::new(&client, endpoint);
 
     let mut res = Ok(());
-    let mut fmt = Formatter::start_stdout(Simple::new());
+    let mut fmt = Formatter::start_stdout(Table::from_arg(matches)?);
     pager.paginate(iter, |result| match result {
         Ok(op) => fmt.render(&op),
         Err(err) => res = Err(err.into()),
@@ -120,10 +739,209 @@ pub fn payments(client: &Client, matches: &ArgMatches) -> Result<()> {
     let iter = sync::Iter::new(&client, endpoint);
 
     let mut res = Ok(());
-    let mut fmt = Formatter::start_stdout(Simple::new());
+    let mut fmt = Formatter::start_stdout(Table::from_arg(matches)?);
     pager.paginate(iter, |result| match result {
         Ok(op) => fmt.render(&op),
         Err(err) => res = Err(err.into()),
     });
     res
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stellar_client::portfolio::Valuation;
+    use stellar_client::resources::{AccountSigner, AccountThresholds, Balance};
+
+    const ISSUER: &str = "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3";
+    const ACCOUNT: &str = "GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ6";
+
+    #[test]
+    fn it_describes_the_balances() {
+        let usd = AssetIdentifier::alphanum4("USD", ISSUER);
+        let eur = AssetIdentifier::alphanum4("EUR", ISSUER);
+        let account = Account::new(ACCOUNT, 1)
+            .with_subentry_count(2)
+            .with_balance(
+                Balance::new(AssetIdentifier::native(), Amount::new(100_000_000))
+                    .with_selling_liabilities(Amount::new(10_000_000)),
+            )
+            .with_balance(
+                Balance::new(usd.clone(), Amount::new(50_000_000))
+                    .with_selling_liabilities(Amount::new(20_000_000)),
+            )
+            .with_balance(Balance::new(eur.clone(), Amount::new(10_000_000)));
+
+        // Four reserves of 0.5 XLM for the account and its two subentries.
+        assert_eq!(
+            describe_balances(&account, Amount::new(5_000_000), None),
+            format!(
+                "Asset                                                            Balance  Available
+XLM                                                           10.0000000  7.0000000
+USD-{issuer}   5.0000000  3.0000000
+EUR-{issuer}   1.0000000  1.0000000
+",
+                issuer = ISSUER
+            )
+        );
+
+        let portfolio = Portfolio::new(
+            usd.clone(),
+            vec![
+                Valuation::new(
+                    AssetIdentifier::native(),
+                    Amount::new(100_000_000),
+                    Some(Amount::new(12_000_000)),
+                ),
+                Valuation::new(usd, Amount::new(50_000_000), Some(Amount::new(50_000_000))),
+                Valuation::new(eur, Amount::new(10_000_000), None),
+            ],
+        );
+        let described = describe_balances(&account, Amount::new(5_000_000), Some(&portfolio));
+        assert!(described.contains("7.0000000  1.2000000\n"));
+        assert!(described.contains("1.0000000  no market\n"));
+        assert!(described.contains(
+            "Total: 6.2000000 USD, leaving out the balances with no market\n"
+        ));
+    }
+
+    #[test]
+    fn it_describes_the_signers() {
+        let account = Account::new(ACCOUNT, 1)
+            .with_signer(AccountSigner::ed25519(ACCOUNT, 0))
+            .with_signer(AccountSigner::ed25519(ISSUER, 2))
+            .with_thresholds(AccountThresholds::new(1, 2, 3));
+        assert_eq!(
+            describe_signers(&account),
+            format!(
+                "Thresholds:     low 1, medium 2, high 3
+Master key:     disabled
+Signing weight: 2, too little to meet the high threshold
+Signers:        2
+  {} ed25519_public_key       0 (master)
+  {} ed25519_public_key       2
+",
+                ACCOUNT, ISSUER
+            )
+        );
+    }
+
+    #[test]
+    fn it_parses_days_for_exports() {
+        let first = parse_day("2023-01-01", false).unwrap();
+        assert_eq!(first.to_rfc3339(), "2023-01-01T00:00:00+00:00");
+        let last = parse_day("2023-12-31", true).unwrap();
+        assert_eq!(last.to_rfc3339(), "2024-01-01T00:00:00+00:00");
+        let time = parse_day("2023-06-01T12:30:00Z", true).unwrap();
+        assert_eq!(time.to_rfc3339(), "2023-06-01T12:30:00+00:00");
+        assert!(parse_day("01/01/2023", false).is_err());
+    }
+
+    fn set_matches(args: &[&str]) -> ::clap::ArgMatches<'static> {
+        let mut argv = vec!["stellar", "account", "set", "--key", "hot"];
+        argv.extend(args);
+        let matches = ::build_app().get_matches_from(argv);
+        let (_, sub_m) = ::command::Command::from_matches(&matches).unwrap();
+        sub_m.clone()
+    }
+
+    #[test]
+    fn it_describes_the_options_it_sets() {
+        let matches = set_matches(&[
+            "--home-domain",
+            "example.com",
+            "--set-flag",
+            "auth_required",
+            "--set-flag",
+            "auth_revocable",
+            "--master-weight",
+            "0",
+            "--thresholds",
+            "1/2/2",
+        ]);
+        let (options, changes) = set_options(&matches).unwrap();
+        assert_eq!(
+            options,
+            SetOptions::default()
+                .with_home_domain("example.com")
+                .with_set_flags(0x3)
+                .with_master_weight(0)
+                .with_thresholds(1, 2, 2)
+        );
+        assert_eq!(
+            changes,
+            vec![
+                "Home domain:    example.com",
+                "Set flags:      auth_required, auth_revocable",
+                "Master weight:  0, the account's own key can no longer sign",
+                "Thresholds:     1/2/2",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_refuses_conflicting_options() {
+        let matches = set_matches(&["--set-flag", "auth_required", "--clear-flag", "auth_required"]);
+        assert!(set_options(&matches).is_err());
+        let matches = set_matches(&["--home-domain", &"a".repeat(33)]);
+        assert!(set_options(&matches).is_err());
+        let (_, changes) = set_options(&set_matches(&[])).unwrap();
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn it_has_no_blockers_for_a_bare_account() {
+        let account = Account::new(ACCOUNT, 1).with_balance(Balance::new(
+            AssetIdentifier::native(),
+            Amount::new(100_000_000),
+        ));
+        assert!(merge_blockers(&account, &[]).is_empty());
+    }
+
+    #[test]
+    fn it_reports_everything_blocking_a_merge() {
+        let account = Account::new(ACCOUNT, 1)
+            .with_subentry_count(6)
+            .with_num_sponsoring(2)
+            .with_balance(Balance::new(
+                AssetIdentifier::native(),
+                Amount::new(100_000_000),
+            ))
+            .with_balance(Balance::new(
+                AssetIdentifier::alphanum4("USD", ISSUER),
+                Amount::new(10_000_000),
+            ))
+            .with_balance(Balance::new(
+                AssetIdentifier::alphanum4("EUR", ISSUER),
+                Amount::new(0),
+            ))
+            .with_data("name", "dGVzdA==");
+        let blockers = merge_blockers(&account, &[42]);
+        assert_eq!(
+            blockers,
+            vec![
+                "offer 42 is cancelled".to_string(),
+                "data entry \"name\" is removed".to_string(),
+                format!(
+                    "the 1.0000000 USD-{} is sent away and the trust line removed",
+                    ISSUER
+                ),
+                format!("the trust line to EUR-{} is removed", ISSUER),
+                "2 other subentries, such as signers, are removed".to_string(),
+                "the 2 reserves it sponsors for other accounts are revoked or transferred"
+                    .to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_counts_pool_share_trust_lines_as_two_subentries() {
+        let account = Account::new(ACCOUNT, 1)
+            .with_subentry_count(2)
+            .with_balance(Balance::liquidity_pool_shares("abcdef", Amount::new(0)));
+        assert_eq!(
+            merge_blockers(&account, &[]),
+            vec!["the trust line to liquidity pool abcdef is removed".to_string()]
+        );
+    }
+}
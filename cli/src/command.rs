@@ -0,0 +1,355 @@
+//! The typed model of every command the CLI can run. `main` maps the parsed
+//! arguments onto a `Command` and runs it, so this is the one place that knows
+//! the full set of commands and which function handles each of them.
+use account;
+use assets;
+use clap::ArgMatches;
+use completions;
+use effects;
+use error::Result;
+use fees;
+use keys;
+use ledgers;
+use multisig;
+use network;
+use offers;
+use operations;
+use orderbook;
+use payments;
+use pool;
+use stellar_client::sync::Client;
+use trades;
+use transactions;
+use xdr;
+
+/// Top level names clap accepts besides the commands' own, which aliases
+/// can't shadow either.
+const RESERVED: &[&str] = &["help", "tx"];
+
+/// A command, named by the subcommands leading to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    AccountBalance,
+    AccountCreate,
+    AccountData,
+    AccountDetails,
+    AccountEffects,
+    AccountExport,
+    AccountMerge,
+    AccountOffers,
+    AccountOperations,
+    AccountPayments,
+    AccountSet,
+    AccountSigners,
+    AccountTransactions,
+    AssetsAll,
+    Completions,
+    EffectsAll,
+    Fees,
+    KeyAdd,
+    KeyCheck,
+    KeyList,
+    KeyRemove,
+    KeyShow,
+    LedgersAll,
+    LedgersDetails,
+    LedgersEffects,
+    LedgersOperations,
+    LedgersPayments,
+    LedgersTransactions,
+    MultisigSetup,
+    MultisigSign,
+    NetworkStatus,
+    OfferCancel,
+    OfferCreate,
+    OfferList,
+    OfferUpdate,
+    OperationsAll,
+    OperationsEffects,
+    OrderbookDetails,
+    PaymentsAll,
+    PoolDeposit,
+    PoolList,
+    PoolShow,
+    PoolWithdraw,
+    TradesAggregations,
+    TradesAll,
+    TransactionsAll,
+    TransactionsDecode,
+    TransactionsDetails,
+    TransactionsEffects,
+    TransactionsOperations,
+    TransactionsPayments,
+    TransactionsStatus,
+    XdrDecode,
+}
+
+use self::Command::*;
+
+const ALL: &[Command] = &[
+    AccountBalance,
+    AccountCreate,
+    AccountData,
+    AccountDetails,
+    AccountEffects,
+    AccountExport,
+    AccountMerge,
+    AccountOffers,
+    AccountOperations,
+    AccountPayments,
+    AccountSet,
+    AccountSigners,
+    AccountTransactions,
+    AssetsAll,
+    Completions,
+    EffectsAll,
+    Fees,
+    KeyAdd,
+    KeyCheck,
+    KeyList,
+    KeyRemove,
+    KeyShow,
+    LedgersAll,
+    LedgersDetails,
+    LedgersEffects,
+    LedgersOperations,
+    LedgersPayments,
+    LedgersTransactions,
+    MultisigSetup,
+    MultisigSign,
+    NetworkStatus,
+    OfferCancel,
+    OfferCreate,
+    OfferList,
+    OfferUpdate,
+    OperationsAll,
+    OperationsEffects,
+    OrderbookDetails,
+    PaymentsAll,
+    PoolDeposit,
+    PoolList,
+    PoolShow,
+    PoolWithdraw,
+    TradesAggregations,
+    TradesAll,
+    TransactionsAll,
+    TransactionsDecode,
+    TransactionsDetails,
+    TransactionsEffects,
+    TransactionsOperations,
+    TransactionsPayments,
+    TransactionsStatus,
+    XdrDecode,
+];
+
+impl Command {
+    /// The subcommand names that select this command, such as
+    /// `["account", "create"]`.
+    pub fn path(&self) -> &'static [&'static str] {
+        match *self {
+            AccountBalance => &["account", "balance"],
+            AccountCreate => &["account", "create"],
+            AccountData => &["account", "data"],
+            AccountDetails => &["account", "details"],
+            AccountEffects => &["account", "effects"],
+            AccountExport => &["account", "export"],
+            AccountMerge => &["account", "merge"],
+            AccountOffers => &["account", "offers"],
+            AccountOperations => &["account", "operations"],
+            AccountPayments => &["account", "payments"],
+            AccountSet => &["account", "set"],
+            AccountSigners => &["account", "signers"],
+            AccountTransactions => &["account", "transactions"],
+            AssetsAll => &["assets", "all"],
+            Completions => &["completions"],
+            EffectsAll => &["effects", "all"],
+            Fees => &["fees"],
+            KeyAdd => &["key", "add"],
+            KeyCheck => &["key", "check"],
+            KeyList => &["key", "list"],
+            KeyRemove => &["key", "remove"],
+            KeyShow => &["key", "show"],
+            LedgersAll => &["ledgers", "all"],
+            LedgersDetails => &["ledgers", "details"],
+            LedgersEffects => &["ledgers", "effects"],
+            LedgersOperations => &["ledgers", "operations"],
+            LedgersPayments => &["ledgers", "payments"],
+            LedgersTransactions => &["ledgers", "transactions"],
+            MultisigSetup => &["multisig", "setup"],
+            MultisigSign => &["multisig", "sign"],
+            NetworkStatus => &["network", "status"],
+            OfferCancel => &["offer", "cancel"],
+            OfferCreate => &["offer", "create"],
+            OfferList => &["offer", "list"],
+            OfferUpdate => &["offer", "update"],
+            OperationsAll => &["operations", "all"],
+            OperationsEffects => &["operations", "effects"],
+            OrderbookDetails => &["orderbook", "details"],
+            PaymentsAll => &["payments", "all"],
+            PoolDeposit => &["pool", "deposit"],
+            PoolList => &["pool", "list"],
+            PoolShow => &["pool", "show"],
+            PoolWithdraw => &["pool", "withdraw"],
+            TradesAggregations => &["trades", "aggregations"],
+            TradesAll => &["trades", "all"],
+            TransactionsAll => &["transactions", "all"],
+            TransactionsDecode => &["transactions", "decode"],
+            TransactionsDetails => &["transactions", "details"],
+            TransactionsEffects => &["transactions", "effects"],
+            TransactionsOperations => &["transactions", "operations"],
+            TransactionsPayments => &["transactions", "payments"],
+            TransactionsStatus => &["transactions", "status"],
+            XdrDecode => &["xdr", "decode"],
+        }
+    }
+
+    /// Finds the command the arguments select, along with the matches of its
+    /// innermost subcommand, which hold the command's own arguments.
+    pub fn from_matches<'m, 'a>(
+        matches: &'m ArgMatches<'a>,
+    ) -> Option<(Command, &'m ArgMatches<'a>)> {
+        let mut names = Vec::new();
+        let mut current = matches;
+        while let (name, Some(sub_m)) = current.subcommand() {
+            names.push(name);
+            current = sub_m;
+        }
+        Self::from_path(&names).map(|command| (command, current))
+    }
+
+    /// The command selected by exactly these subcommand names.
+    pub fn from_path(names: &[&str]) -> Option<Command> {
+        ALL.iter().find(|command| command.path() == names).cloned()
+    }
+
+    /// Whether a top level argument is the name of a command group, or
+    /// anything else clap treats as a subcommand.
+    pub fn is_top_level(name: &str) -> bool {
+        RESERVED.contains(&name) || ALL.iter().any(|command| command.path()[0] == name)
+    }
+
+    /// Runs the command with the matches returned by `from_matches`.
+    pub fn run(self, client: &Client, matches: &ArgMatches) -> Result<()> {
+        match self {
+            AccountBalance => account::balance(client, matches),
+            AccountCreate => account::create(client, matches),
+            AccountData => account::data(client, matches),
+            AccountDetails => account::details(client, matches),
+            AccountEffects => account::effects(client, matches),
+            AccountExport => account::export(client, matches),
+            AccountMerge => account::merge(client, matches),
+            AccountOffers => account::offers(client, matches),
+            AccountOperations => account::operations(client, matches),
+            AccountPayments => account::payments(client, matches),
+            AccountSet => account::set(client, matches),
+            AccountSigners => account::signers(client, matches),
+            AccountTransactions => account::transactions(client, matches),
+            AssetsAll => assets::all(client, matches),
+            Completions => completions::generate(matches),
+            EffectsAll => effects::all(client, matches),
+            Fees => fees::stats(client, matches),
+            KeyAdd => keys::add(matches),
+            KeyCheck => keys::check(matches),
+            KeyList => keys::list(),
+            KeyRemove => keys::remove(matches),
+            KeyShow => keys::show(matches),
+            LedgersAll => ledgers::all(client, matches),
+            LedgersDetails => ledgers::details(client, matches),
+            LedgersEffects => ledgers::effects(client, matches),
+            LedgersOperations => ledgers::operations(client, matches),
+            LedgersPayments => ledgers::payments(client, matches),
+            LedgersTransactions => ledgers::transactions(client, matches),
+            MultisigSetup => multisig::setup(client, matches),
+            MultisigSign => multisig::sign(client, matches),
+            NetworkStatus => network::status(client),
+            OfferCancel => offers::cancel(client, matches),
+            OfferCreate => offers::create(client, matches),
+            OfferList => offers::list(client, matches),
+            OfferUpdate => offers::update(client, matches),
+            OperationsAll => operations::all(client, matches),
+            OperationsEffects => operations::effects(client, matches),
+            OrderbookDetails => orderbook::details(client, matches),
+            PaymentsAll => payments::all(client, matches),
+            PoolDeposit => pool::deposit(client, matches),
+            PoolList => pool::list(client, matches),
+            PoolShow => pool::show(client, matches),
+            PoolWithdraw => pool::withdraw(client, matches),
+            TradesAggregations => trades::aggregations(client, matches),
+            TradesAll => trades::all(client, matches),
+            TransactionsAll => transactions::all(client, matches),
+            TransactionsDecode => transactions::decode(client, matches),
+            TransactionsDetails => transactions::details(client, matches),
+            TransactionsEffects => transactions::effects(client, matches),
+            TransactionsOperations => transactions::operations(client, matches),
+            TransactionsPayments => transactions::payments(client, matches),
+            TransactionsStatus => transactions::status(client, matches),
+            XdrDecode => xdr::decode(matches),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_selects_commands_from_matches() {
+        let matches = ::build_app().get_matches_from(vec!["stellar", "tx", "decode", "AAAA"]);
+        let (command, sub_m) = Command::from_matches(&matches).unwrap();
+        assert_eq!(command, TransactionsDecode);
+        assert_eq!(sub_m.value_of("XDR"), Some("AAAA"));
+
+        let matches = ::build_app().get_matches_from(vec!["stellar", "completions", "fish"]);
+        assert_eq!(Command::from_matches(&matches).unwrap().0, Completions);
+    }
+
+    #[test]
+    fn every_command_parses() {
+        // Each path must name a real subcommand, so that clap reaches it
+        // rather than failing on an unknown name.
+        for command in ALL {
+            let mut args = vec!["stellar"];
+            args.extend(command.path());
+            args.push("--help");
+            let err = ::build_app().get_matches_from_safe(args).unwrap_err();
+            assert_eq!(err.kind, ::clap::ErrorKind::HelpDisplayed, "{:?}", command);
+        }
+    }
+
+    #[test]
+    fn it_passes_dry_run_to_every_command() {
+        let matches = ::build_app().get_matches_from(vec![
+            "stellar",
+            "offer",
+            "cancel",
+            "--key",
+            "hot",
+            "12",
+            "--dry-run",
+            "--unsigned",
+        ]);
+        let (_, sub_m) = Command::from_matches(&matches).unwrap();
+        assert!(sub_m.is_present("dry-run"));
+        assert!(sub_m.is_present("unsigned"));
+
+        let err = ::build_app()
+            .get_matches_from_safe(vec![
+                "stellar",
+                "offer",
+                "cancel",
+                "--key",
+                "hot",
+                "12",
+                "--unsigned",
+            ])
+            .unwrap_err();
+        assert_eq!(err.kind, ::clap::ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn it_knows_top_level_names() {
+        assert!(Command::is_top_level("account"));
+        assert!(Command::is_top_level("tx"));
+        assert!(!Command::is_top_level("bal"));
+    }
+}
@@ -0,0 +1,18 @@
+//! Shell completion scripts, generated from the same clap definitions the CLI
+//! parses its arguments with so that they never fall out of date.
+use clap::{ArgMatches, Shell};
+use error::Result;
+use std::io;
+
+/// The shells scripts can be generated for.
+pub const SHELLS: &[&str] = &["bash", "zsh", "fish"];
+
+/// Writes the completion script for the requested shell to stdout.
+pub fn generate(matches: &ArgMatches) -> Result<()> {
+    let shell: Shell = matches
+        .value_of("SHELL")
+        .expect("SHELL is required")
+        .parse()?;
+    ::build_app().gen_completions_to("stellar", shell, &mut io::stdout());
+    Ok(())
+}
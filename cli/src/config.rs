@@ -0,0 +1,276 @@
+//! The optional config file at `~/.stellar/config`, or wherever
+//! `STELLAR_CONFIG` points. For now it holds command aliases, written either
+//! in an `[alias]` section or with an `alias.` prefix:
+//!
+//! ```text
+//! [alias]
+//! bal = "account details --columns balances"
+//!
+//! alias.book = "orderbook details --selling XLM"
+//! ```
+//!
+//! An alias is expanded in place of its name, so any further arguments are
+//! passed on to the command it expands to.
+use command::Command;
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Overrides where the config file lives.
+const CONFIG_VAR: &str = "STELLAR_CONFIG";
+
+/// Global options that take a value, which may come before the command name.
+const VALUE_OPTIONS: &[&str] = &["-h", "--host", "--network-passphrase"];
+
+/// The `~/.stellar` directory that holds the config file and the keystore.
+pub fn stellar_dir() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(".stellar"))
+}
+
+/// The settings read from the config file.
+#[derive(Debug, Default)]
+pub struct Config {
+    aliases: BTreeMap<String, Vec<String>>,
+}
+
+impl Config {
+    /// Reads the config file. A missing file gives the default config.
+    pub fn load() -> Result<Config, String> {
+        let path = match env::var_os(CONFIG_VAR) {
+            Some(path) => PathBuf::from(path),
+            None => match stellar_dir() {
+                Some(dir) => dir.join("config"),
+                None => return Ok(Config::default()),
+            },
+        };
+        match fs::read_to_string(&path) {
+            Ok(source) => Config::parse(&source)
+                .map_err(|err| format!("Error in {}: {}", path.display(), err)),
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(err) => Err(format!("Can't read {}: {}", path.display(), err)),
+        }
+    }
+
+    /// Parses the contents of a config file.
+    pub fn parse(source: &str) -> Result<Config, String> {
+        let mut config = Config::default();
+        let mut section = String::new();
+        for (index, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let at_line = |message: String| format!("line {}: {}", index + 1, message);
+            if line.starts_with('[') && line.ends_with(']') {
+                section = line[1..line.len() - 1].trim().to_string();
+                continue;
+            }
+            let equals = line
+                .find('=')
+                .ok_or_else(|| at_line(format!("expected `key = \"value\"`, found {:?}", line)))?;
+            let key = line[..equals].trim();
+            let key = if section.is_empty() {
+                key.to_string()
+            } else {
+                format!("{}.{}", section, key)
+            };
+            let value = parse_string(line[equals + 1..].trim()).map_err(&at_line)?;
+            if let Some(name) = key.strip_prefix("alias.") {
+                let expansion = check_alias(name, &value).map_err(&at_line)?;
+                config.aliases.insert(name.to_string(), expansion);
+            } else {
+                return Err(at_line(format!("unknown setting {:?}", key)));
+            }
+        }
+        Ok(config)
+    }
+
+    /// The arguments an alias expands to, if it is defined.
+    pub fn alias(&self, name: &str) -> Option<&[String]> {
+        self.aliases.get(name).map(|expansion| &expansion[..])
+    }
+
+    /// Replaces an alias given as the command name with its expansion. The
+    /// first argument is the program name and is left alone, as are any
+    /// global options before the command name.
+    pub fn expand(&self, args: Vec<OsString>) -> Vec<OsString> {
+        let mut index = 1;
+        while index < args.len() {
+            let arg = match args[index].to_str() {
+                Some(arg) => arg,
+                None => return args,
+            };
+            if arg == "--" {
+                return args;
+            } else if VALUE_OPTIONS.contains(&arg) {
+                index += 2;
+            } else if arg.starts_with('-') {
+                index += 1;
+            } else {
+                break;
+            }
+        }
+        let expansion = match args.get(index).and_then(|arg| arg.to_str()) {
+            Some(name) => match self.alias(name) {
+                Some(expansion) => expansion,
+                None => return args,
+            },
+            None => return args,
+        };
+        let mut expanded = args[..index].to_vec();
+        expanded.extend(expansion.iter().map(OsString::from));
+        expanded.extend(args[index + 1..].iter().cloned());
+        expanded
+    }
+}
+
+/// Checks an alias can be used and splits its expansion into arguments.
+/// Aliases can't shadow or expand to other aliases, so they always lead
+/// straight to a built-in command.
+fn check_alias(name: &str, value: &str) -> Result<Vec<String>, String> {
+    if name.is_empty() || name.starts_with('-') || name.contains(char::is_whitespace) {
+        return Err(format!("{:?} can't be used as an alias name", name));
+    }
+    if Command::is_top_level(name) {
+        return Err(format!("alias {:?} would hide the built-in command", name));
+    }
+    let expansion = split_words(value)?;
+    match expansion.first() {
+        Some(first) if Command::is_top_level(first) => Ok(expansion),
+        Some(first) => Err(format!(
+            "alias {:?} must expand to a built-in command, not {:?}",
+            name, first
+        )),
+        None => Err(format!("alias {:?} is empty", name)),
+    }
+}
+
+/// Parses a double quoted string, allowing `\"` and `\\` escapes.
+fn parse_string(value: &str) -> Result<String, String> {
+    if value.len() < 2 || !value.starts_with('"') || !value.ends_with('"') {
+        return Err(format!("expected a quoted string, found {:?}", value));
+    }
+    let mut parsed = String::new();
+    let mut chars = value[1..value.len() - 1].chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(escaped @ '"') | Some(escaped @ '\\') => parsed.push(escaped),
+                _ => return Err(format!("unsupported escape in {}", value)),
+            },
+            '"' => return Err(format!("unescaped quote in {}", value)),
+            c => parsed.push(c),
+        }
+    }
+    Ok(parsed)
+}
+
+/// Splits a command line into arguments on whitespace, keeping quoted parts
+/// together the way a shell would.
+fn split_words(line: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('\''), c) => word.get_or_insert_with(String::new).push(c),
+            (_, '\\') => match chars.next() {
+                Some(escaped) => word.get_or_insert_with(String::new).push(escaped),
+                None => return Err(format!("trailing backslash in {:?}", line)),
+            },
+            (Some(_), c) => word.get_or_insert_with(String::new).push(c),
+            (None, '"') | (None, '\'') => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (None, c) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quote.is_some() {
+        return Err(format!("unclosed quote in {:?}", line));
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn it_parses_aliases() {
+        let config = Config::parse(
+            "# aliases\n\
+             alias.bal = \"account details --columns balances\"\n\
+             \n\
+             [alias]\n\
+             book = \"orderbook details --selling 'XLM'\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.alias("bal").unwrap(),
+            &["account", "details", "--columns", "balances"]
+        );
+        assert_eq!(
+            config.alias("book").unwrap(),
+            &["orderbook", "details", "--selling", "XLM"]
+        );
+        assert_eq!(config.alias("other"), None);
+    }
+
+    #[test]
+    fn it_rejects_bad_config() {
+        let err = Config::parse("colour = \"always\"").unwrap_err();
+        assert_eq!(err, "line 1: unknown setting \"colour\"");
+        let err = Config::parse("\n[alias]\nbal = account details").unwrap_err();
+        assert!(
+            err.starts_with("line 3: expected a quoted string"),
+            "{}",
+            err
+        );
+        let err = Config::parse("alias.account = \"key list\"").unwrap_err();
+        assert!(err.contains("would hide the built-in command"), "{}", err);
+        let err = Config::parse("alias.bal = \"bal\"").unwrap_err();
+        assert!(err.contains("must expand to a built-in command"), "{}", err);
+        assert!(Config::parse("alias.bal = \"\"").is_err());
+        assert!(Config::parse("alias.bal = \"key 'list\"").is_err());
+    }
+
+    #[test]
+    fn it_expands_the_command_name() {
+        let config = Config::parse("alias.bal = \"account details\"").unwrap();
+        assert_eq!(
+            config.expand(args(&["stellar", "bal", "GABC"])),
+            args(&["stellar", "account", "details", "GABC"])
+        );
+        assert_eq!(
+            config.expand(args(&["stellar", "--pub-net", "-h", "bal", "bal"])),
+            args(&["stellar", "--pub-net", "-h", "bal", "account", "details"])
+        );
+        assert_eq!(
+            config.expand(args(&["stellar", "key", "show", "bal"])),
+            args(&["stellar", "key", "show", "bal"])
+        );
+        assert_eq!(config.expand(args(&["stellar"])), args(&["stellar"]));
+    }
+
+    #[test]
+    fn it_splits_words() {
+        assert_eq!(
+            split_words(r#" a "b c"  'd "e"' f\ g "" "#).unwrap(),
+            vec!["a", "b c", "d \"e\"", "f g", ""]
+        );
+    }
+}
@@ -28,14 +28,15 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use stellar_client::endpoint::Position;
 
     struct Foo {
         cursor: Option<String>,
     }
 
     impl Cursor for Foo {
-        fn with_cursor(mut self, cursor: &str) -> Foo {
-            self.cursor = Some(cursor.to_owned());
+        fn with_cursor<P: Into<Position>>(mut self, cursor: P) -> Foo {
+            self.cursor = Some(cursor.into().to_string());
             self
         }
 
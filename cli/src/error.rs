@@ -1,8 +1,10 @@
 use resolution::ParseResolutionError;
 use std::error::Error as StdError;
 use std::fmt;
+use std::io;
 use std::num::ParseIntError;
 use stellar_client::error::Error;
+use stellar_client::{keystore, signer, transaction, xdr};
 
 /// A result including client specific errors.
 pub type Result<T> = ::std::result::Result<T, CliError>;
@@ -13,6 +15,10 @@ pub enum CliError {
     ClientError(Error),
     OperatorError(InvalidInputError),
     ParseResolutionError(ParseResolutionError),
+    KeystoreError(keystore::Error),
+    TransactionError(transaction::Error),
+    SignerError(signer::Error),
+    IoError(io::Error),
 }
 
 /// Errors resulting from incomplete or invalid user input
@@ -59,6 +65,36 @@ impl From<Error> for CliError {
     }
 }
 
+impl From<xdr::Error> for CliError {
+    fn from(err: xdr::Error) -> Self {
+        CliError::ClientError(err.into())
+    }
+}
+
+impl From<keystore::Error> for CliError {
+    fn from(err: keystore::Error) -> Self {
+        CliError::KeystoreError(err)
+    }
+}
+
+impl From<transaction::Error> for CliError {
+    fn from(err: transaction::Error) -> Self {
+        CliError::TransactionError(err)
+    }
+}
+
+impl From<signer::Error> for CliError {
+    fn from(err: signer::Error) -> Self {
+        CliError::SignerError(err)
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(err: io::Error) -> Self {
+        CliError::IoError(err)
+    }
+}
+
 impl From<String> for InvalidInputError {
     fn from(details: String) -> Self {
         InvalidInputError { details }
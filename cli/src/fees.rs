@@ -0,0 +1,129 @@
+//! The fees the network is charging, from horizon's fee stats, and what to
+//! offer for a transaction to get into a ledger.
+use clap::ArgMatches;
+use error::Result;
+use std::fmt::Write;
+use stellar_client::endpoint::fee;
+use stellar_client::resources::{FeeStats, Percentile};
+use stellar_client::sync::Client;
+use stellar_client::transaction::{FeeStrategy, PercentileFee};
+
+const PERCENTILES: &[(&str, Percentile)] = &[
+    ("p10", Percentile::P10),
+    ("p20", Percentile::P20),
+    ("p30", Percentile::P30),
+    ("p40", Percentile::P40),
+    ("p50", Percentile::P50),
+    ("p60", Percentile::P60),
+    ("p70", Percentile::P70),
+    ("p80", Percentile::P80),
+    ("p90", Percentile::P90),
+    ("p95", Percentile::P95),
+    ("p99", Percentile::P99),
+];
+
+/// Prints the base fee, how full recent ledgers were and the fees charged
+/// and offered at each percentile. With `--suggest N` it also prints the max
+/// fee to offer for a transaction of N operations.
+pub fn stats(client: &Client, matches: &ArgMatches) -> Result<()> {
+    let operations = match matches.value_of("suggest") {
+        Some(value) => Some(parse_operations(value)?),
+        None => None,
+    };
+    let stats = client.request(fee::Stats)?;
+    print!("{}", describe(&stats));
+    if let Some(operations) = operations {
+        let (per_operation, total) = suggest(&stats, operations);
+        println!();
+        println!(
+            "Suggested max fee for {} operation{}: {} stroops ({} per operation)",
+            operations,
+            if operations == 1 { "" } else { "s" },
+            total,
+            per_operation
+        );
+    }
+    Ok(())
+}
+
+fn parse_operations(value: &str) -> Result<u32> {
+    match value.parse::<u32>() {
+        Ok(operations) if (1..=100).contains(&operations) => Ok(operations),
+        _ => Err(format!(
+            "Transactions have between 1 and 100 operations, not {:?}",
+            value
+        ).into()),
+    }
+}
+
+fn describe(stats: &FeeStats) -> String {
+    let mut out = String::new();
+    let charged = stats.fee_charged();
+    let offered = stats.max_fee();
+    let _ = writeln!(
+        out,
+        "Fees in stroops per operation, over the ledgers up to {}:",
+        stats.last_ledger()
+    );
+    let _ = writeln!(out, "  Base fee: {}", stats.last_ledger_base_fee());
+    let _ = writeln!(
+        out,
+        "  Capacity: {:.0}% of recent ledgers used",
+        stats.ledger_capacity_usage() * 100.0
+    );
+    let _ = writeln!(out);
+    let _ = writeln!(out, "  {:<6} {:>10} {:>10}", "", "Charged", "Offered");
+    let mut row = |name: &str, charged: u64, offered: u64| {
+        let _ = writeln!(out, "  {:<6} {:>10} {:>10}", name, charged, offered);
+    };
+    row("min", charged.min(), offered.min());
+    row("mode", charged.mode(), offered.mode());
+    for &(name, percentile) in PERCENTILES {
+        row(
+            name,
+            charged.percentile(percentile),
+            offered.percentile(percentile),
+        );
+    }
+    row("max", charged.max(), offered.max());
+    out
+}
+
+/// What to offer per operation and in all: the fee 90% of recent
+/// transactions were charged no more than, and never less than the base fee.
+fn suggest(stats: &FeeStats, operations: u32) -> (u32, u64) {
+    let per_operation = PercentileFee::new(Percentile::P90).base_fee(stats);
+    (
+        per_operation,
+        u64::from(per_operation) * u64::from(operations),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stellar_client::resources::FeeDistribution;
+
+    fn stats() -> FeeStats {
+        FeeStats::new(1000, 100)
+            .with_fee_charged(FeeDistribution::flat(100).with_percentile(Percentile::P90, 250))
+    }
+
+    #[test]
+    fn it_describes_the_fee_stats() {
+        let description = describe(&stats());
+        assert!(description.contains("over the ledgers up to 1000"));
+        assert!(description.contains("  Base fee: 100\n"));
+        assert!(description.contains("  p90           250        100\n"));
+    }
+
+    #[test]
+    fn it_suggests_a_fee_for_the_operations() {
+        assert_eq!(suggest(&stats(), 3), (250, 750));
+        let quiet = FeeStats::new(1000, 100).with_fee_charged(FeeDistribution::flat(50));
+        assert_eq!(suggest(&quiet, 2), (100, 200));
+        assert!(parse_operations("0").is_err());
+        assert!(parse_operations("101").is_err());
+        assert_eq!(parse_operations("100").unwrap(), 100);
+    }
+}
@@ -2,7 +2,9 @@ use std::io::{stdout, Stdout, Write};
 use std::marker;
 
 mod simple;
+pub mod table;
 pub use self::simple::Simple;
+pub use self::table::Table;
 
 /// The render trait can be used by the formatter to handle the calls to form
 /// output strings. The formatter will call the preamble first when it's created,
@@ -9,14 +9,24 @@ impl Render<Effect> for Simple {
         append!(buf, "id:   {}", effect.id());
         append!(buf, "kind: {}", effect.kind_name());
         append!(buf, "details:");
+        buf.push_str(&self.effect_details(effect));
+        Some(buf)
+    }
+}
 
-        Some(match *effect.kind() {
+impl Simple {
+    /// Renders the details of the effect, without its id and kind.
+    pub fn effect_details(&self, effect: &Effect) -> String {
+        let buf = String::new();
+        match *effect.kind() {
             Kind::Account(ref kind) => account::render(buf, kind),
             Kind::Signer(ref kind) => signer::render(buf, kind),
             Kind::Trustline(ref kind) => trustline::render(buf, kind),
             Kind::Trade(ref kind) => trade::render(buf, kind),
             Kind::Data(ref kind) => data::render(buf, kind),
-        })
+            Kind::ClaimableBalance(ref kind) => claimable_balance::render(buf, kind),
+            Kind::Unknown { .. } => buf,
+        }
     }
 }
 
@@ -61,6 +71,13 @@ mod account {
                 append!(buf, "  account:     {}", effect.account());
                 append!(buf, "  home domain: {}", effect.home_domain());
             }
+            Kind::InflationDestinationUpdated(ref effect) => {
+                append!(buf, "  account: {}", effect.account());
+            }
+            Kind::SequenceBumped(ref effect) => {
+                append!(buf, "  account: {}", effect.account());
+                append!(buf, "  new seq: {}", effect.new_seq());
+            }
             Kind::FlagsUpdated(ref effect) => {
                 append!(buf, "  account:     {}", effect.account());
                 append!(buf, "  flags:");
@@ -115,6 +132,25 @@ mod trustline {
                 append!(buf, "  account: {}", effect.account());
                 append!(buf, "  asset:   {}", render_asset(effect.asset()));
             }
+            Kind::AuthorizedToMaintainLiabilities(ref effect) => {
+                append!(buf, "  account: {}", effect.account());
+                append!(buf, "  asset:   {}", render_asset(effect.asset()));
+            }
+            Kind::FlagsUpdated(ref effect) => {
+                append!(buf, "  account: {}", effect.account());
+                append!(buf, "  trustor: {}", effect.trustor());
+                append!(buf, "  asset:   {}", render_asset(effect.asset()));
+                append!(buf, "  flags:");
+                if let Some(flag) = effect.authorized() {
+                    append!(buf, "    authorized: {}", flag);
+                }
+                if let Some(flag) = effect.authorized_to_maintain_liabilities() {
+                    append!(buf, "    authorized to maintain liabilities: {}", flag);
+                }
+                if let Some(flag) = effect.clawback_enabled() {
+                    append!(buf, "    clawback enabled: {}", flag);
+                }
+            }
             Kind::Removed(ref effect) => {
                 append!(buf, "  account: {}", effect.account());
                 append!(buf, "  limit:   {}", effect.limit());
@@ -177,3 +213,17 @@ mod data {
         buf
     }
 }
+
+mod claimable_balance {
+    use stellar_client::resources::effect::claimable_balance::Kind;
+
+    pub fn render(mut buf: String, kind: &Kind) -> String {
+        match *kind {
+            Kind::ClawedBack(ref effect) => {
+                append!(buf, "  account:    {}", effect.account());
+                append!(buf, "  balance id: {}", effect.balance_id());
+            }
+        }
+        buf
+    }
+}
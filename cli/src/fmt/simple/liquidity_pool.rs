@@ -0,0 +1,34 @@
+use super::Simple;
+use fmt::Render;
+use stellar_client::resources::{Amount, LiquidityPool};
+
+impl Render<LiquidityPool> for Simple {
+    fn render(&self, pool: &LiquidityPool) -> Option<String> {
+        let mut buf = String::new();
+        append!(buf, "ID:           {}", pool.id());
+        append!(buf, "Fee:          {} bp", pool.fee_bp());
+        append!(buf, "Shares:       {}", pool.total_shares());
+        append!(buf, "Trust lines:  {}", pool.total_trustlines());
+        append!(buf, "Reserves:");
+        for reserve in pool.reserves() {
+            indent!(
+                buf,
+                self,
+                "{} {}",
+                reserve.amount(),
+                self.render(reserve.asset()).unwrap()
+            );
+        }
+        append!(buf, "Share price:");
+        for reserve in pool.shares_value(Amount::new(10_000_000)) {
+            indent!(
+                buf,
+                self,
+                "{} {}",
+                reserve.amount(),
+                self.render(reserve.asset()).unwrap()
+            );
+        }
+        Some(buf)
+    }
+}
@@ -70,6 +70,7 @@ mod asset;
 mod datum;
 mod effect;
 mod ledger;
+mod liquidity_pool;
 mod offer;
 mod operation;
 mod orderbook;
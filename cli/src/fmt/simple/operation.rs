@@ -7,10 +7,21 @@ impl Render<Operation> for Simple {
         let mut buf = String::new();
         append!(buf, "ID:   {}", op.id());
         append!(buf, "Kind: {}", op.kind_name());
-        let kind_details = match op.kind() {
+        if let Some(value) = self.operation_details(op) {
+            append!(buf, "{}", value);
+        }
+        Some(buf)
+    }
+}
+
+impl Simple {
+    /// Renders what the operation did, without its id and kind.
+    pub fn operation_details(&self, op: &Operation) -> Option<String> {
+        match op.kind() {
             Kind::CreateAccount(kind) => self.render(kind),
             Kind::Payment(kind) => self.render(kind),
             Kind::PathPayment(kind) => self.render(kind),
+            Kind::PathPaymentStrictSend(kind) => self.render(kind),
             Kind::ManageOffer(kind) => self.render(kind),
             Kind::CreatePassiveOffer(kind) => self.render(kind),
             Kind::SetOptions(kind) => self.render(kind),
@@ -19,11 +30,14 @@ impl Render<Operation> for Simple {
             Kind::AccountMerge(kind) => self.render(kind),
             Kind::Inflation => None,
             Kind::ManageData(kind) => self.render(kind),
-        };
-        if let Some(value) = kind_details {
-            append!(buf, "{}", value);
+            Kind::BumpSequence(kind) => self.render(kind),
+            Kind::Clawback(kind) => self.render(kind),
+            Kind::ClawbackClaimableBalance(kind) => self.render(kind),
+            Kind::SetTrustLineFlags(kind) => self.render(kind),
+            Kind::LiquidityPoolDeposit(kind) => self.render(kind),
+            Kind::LiquidityPoolWithdraw(kind) => self.render(kind),
+            Kind::Unknown { .. } => None,
         }
-        Some(buf)
     }
 }
 
@@ -68,6 +82,27 @@ impl Render<PathPayment> for Simple {
     }
 }
 
+impl Render<PathPaymentStrictSend> for Simple {
+    fn render(&self, op: &PathPaymentStrictSend) -> Option<String> {
+        let mut buf = String::new();
+        append!(buf, "To Account:         {}", op.to());
+        append!(buf, "From Account:       {}", op.from());
+        append!(
+            buf,
+            "Source Asset:       {}",
+            self.render(op.source_asset()).unwrap()
+        );
+        append!(buf, "Source Amount:      {}", op.source_amount());
+        append!(
+            buf,
+            "Destination Asset:  {}",
+            self.render(op.destination_asset()).unwrap()
+        );
+        append!(buf, "Destination Amount: {}", op.destination_amount());
+        Some(buf)
+    }
+}
+
 impl Render<ManageOffer> for Simple {
     fn render(&self, op: &ManageOffer) -> Option<String> {
         let mut buf = String::new();
@@ -167,3 +202,112 @@ impl Render<ManageData> for Simple {
         Some(buf)
     }
 }
+
+impl Render<BumpSequence> for Simple {
+    fn render(&self, op: &BumpSequence) -> Option<String> {
+        let mut buf = String::new();
+        append!(buf, "Bump To: {}", op.bump_to());
+        Some(buf)
+    }
+}
+
+impl Render<Clawback> for Simple {
+    fn render(&self, op: &Clawback) -> Option<String> {
+        let mut buf = String::new();
+        append!(buf, "From Account: {}", op.from());
+        append!(buf, "Asset:        {}", self.render(op.asset()).unwrap());
+        append!(buf, "Amount:       {}", op.amount());
+        Some(buf)
+    }
+}
+
+impl Render<ClawbackClaimableBalance> for Simple {
+    fn render(&self, op: &ClawbackClaimableBalance) -> Option<String> {
+        let mut buf = String::new();
+        append!(buf, "Balance ID: {}", op.balance_id());
+        Some(buf)
+    }
+}
+
+impl Render<SetTrustLineFlags> for Simple {
+    fn render(&self, op: &SetTrustLineFlags) -> Option<String> {
+        let mut buf = String::new();
+        append!(buf, "Trustor: {}", op.trustor());
+        append!(buf, "Asset:   {}", self.render(op.asset()).unwrap());
+        append!(buf, "Set Flags:");
+        if let Some(flags) = op.set_flags() {
+            nest!(buf, self, &flags);
+        } else {
+            indent!(buf, self, "None");
+        }
+        append!(buf, "Cleared Flags:");
+        if let Some(flags) = op.clear_flags() {
+            nest!(buf, self, &flags);
+        } else {
+            indent!(buf, self, "None");
+        }
+        Some(buf)
+    }
+}
+
+impl Render<TrustLineFlags> for Simple {
+    fn render(&self, flags: &TrustLineFlags) -> Option<String> {
+        let mut buf = String::new();
+        if flags.is_authorized() {
+            append!(buf, "authorized");
+        }
+        if flags.is_authorized_to_maintain_liabilities() {
+            append!(buf, "authorized to maintain liabilities");
+        }
+        if flags.is_clawback_enabled() {
+            append!(buf, "clawback enabled");
+        }
+        Some(buf)
+    }
+}
+
+impl Render<LiquidityPoolDeposit> for Simple {
+    fn render(&self, op: &LiquidityPoolDeposit) -> Option<String> {
+        let mut buf = String::new();
+        append!(buf, "Liquidity Pool:  {}", op.liquidity_pool_id());
+        append!(buf, "Min Price:       {}", op.min_price());
+        append!(buf, "Max Price:       {}", op.max_price());
+        append!(buf, "Shares Received: {}", op.shares_received());
+        append!(buf, "Max Reserves:");
+        for reserve in op.reserves_max() {
+            nest!(buf, self, reserve);
+        }
+        append!(buf, "Deposited:");
+        for reserve in op.reserves_deposited() {
+            nest!(buf, self, reserve);
+        }
+        Some(buf)
+    }
+}
+
+impl Render<LiquidityPoolWithdraw> for Simple {
+    fn render(&self, op: &LiquidityPoolWithdraw) -> Option<String> {
+        let mut buf = String::new();
+        append!(buf, "Liquidity Pool: {}", op.liquidity_pool_id());
+        append!(buf, "Shares:         {}", op.shares());
+        append!(buf, "Min Reserves:");
+        for reserve in op.reserves_min() {
+            nest!(buf, self, reserve);
+        }
+        append!(buf, "Received:");
+        for reserve in op.reserves_received() {
+            nest!(buf, self, reserve);
+        }
+        Some(buf)
+    }
+}
+
+impl Render<Reserve> for Simple {
+    fn render(&self, reserve: &Reserve) -> Option<String> {
+        Some(format!(
+            "{} {}",
+            reserve.amount(),
+            self.render(reserve.asset()).unwrap()
+        ))
+    }
+}
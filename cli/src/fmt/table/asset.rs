@@ -0,0 +1,23 @@
+use super::{asset, asset_column, Column, Columns};
+use stellar_client::resources::Asset;
+
+impl Columns for Asset {
+    fn columns() -> Vec<Column<Asset>> {
+        vec![
+            asset_column("asset", |a: &Asset| asset(a.identifier())),
+            Column::new("amount", 24, |a: &Asset| a.amount().to_string()).right(),
+            Column::new("accounts", 8, |a: &Asset| a.num_accounts().to_string()).right(),
+            Column::new("flags", 5, |a: &Asset| {
+                let mut flags = Vec::new();
+                if a.is_auth_required() {
+                    flags.push("auth_required");
+                }
+                if a.is_auth_revocable() {
+                    flags.push("auth_revocable");
+                }
+                flags.join(",")
+            })
+            .hidden(),
+        ]
+    }
+}
@@ -0,0 +1,15 @@
+use super::{one_line, Column, Columns};
+use fmt::Simple;
+use stellar_client::resources::effect::Effect;
+
+impl Columns for Effect {
+    fn columns() -> Vec<Column<Effect>> {
+        vec![
+            Column::new("id", 21, |effect: &Effect| effect.id().to_string()),
+            Column::new("type", 24, |effect: &Effect| effect.kind_name().to_string()),
+            Column::new("details", 0, |effect: &Effect| {
+                one_line(&Simple::new().effect_details(effect))
+            }),
+        ]
+    }
+}
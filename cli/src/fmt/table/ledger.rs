@@ -0,0 +1,43 @@
+use super::{hash_column, time, Column, Columns};
+use stellar_client::resources::Ledger;
+
+impl Columns for Ledger {
+    fn columns() -> Vec<Column<Ledger>> {
+        vec![
+            Column::new("sequence", 8, |ledger: &Ledger| {
+                ledger.sequence().to_string()
+            })
+            .right(),
+            Column::new("closed_at", 19, |ledger: &Ledger| time(ledger.closed_at())),
+            Column::new("transactions", 12, |ledger: &Ledger| {
+                ledger.transaction_count().to_string()
+            })
+            .right(),
+            Column::new("operations", 10, |ledger: &Ledger| {
+                ledger.operation_count().to_string()
+            })
+            .right(),
+            hash_column("hash", |ledger: &Ledger| ledger.hash().to_string()),
+            Column::new("base_fee", 9, |ledger: &Ledger| {
+                ledger.base_fee_as_amount().to_string()
+            })
+            .right()
+            .hidden(),
+            Column::new("base_reserve", 12, |ledger: &Ledger| {
+                ledger.base_reserve_as_amount().to_string()
+            })
+            .right()
+            .hidden(),
+            Column::new("total_coins", 24, |ledger: &Ledger| {
+                ledger.total_coins().to_string()
+            })
+            .right()
+            .hidden(),
+            Column::new("protocol", 8, |ledger: &Ledger| {
+                ledger.protocol_version().to_string()
+            })
+            .right()
+            .hidden(),
+        ]
+    }
+}
@@ -0,0 +1,47 @@
+use super::{asset, asset_column, hash_column, Column, Columns};
+use stellar_client::resources::LiquidityPool;
+
+fn reserve_asset(pool: &LiquidityPool, index: usize) -> String {
+    pool.reserves()
+        .get(index)
+        .map(|reserve| asset(reserve.asset()))
+        .unwrap_or_default()
+}
+
+fn reserve_amount(pool: &LiquidityPool, index: usize) -> String {
+    pool.reserves()
+        .get(index)
+        .map(|reserve| reserve.amount().to_string())
+        .unwrap_or_default()
+}
+
+impl Columns for LiquidityPool {
+    fn columns() -> Vec<Column<LiquidityPool>> {
+        vec![
+            hash_column("id", |pool: &LiquidityPool| pool.id().to_string()),
+            asset_column("asset_a", |pool: &LiquidityPool| reserve_asset(pool, 0)),
+            Column::new("reserve_a", 18, |pool: &LiquidityPool| {
+                reserve_amount(pool, 0)
+            })
+            .right(),
+            asset_column("asset_b", |pool: &LiquidityPool| reserve_asset(pool, 1)),
+            Column::new("reserve_b", 18, |pool: &LiquidityPool| {
+                reserve_amount(pool, 1)
+            })
+            .right(),
+            Column::new("shares", 18, |pool: &LiquidityPool| {
+                pool.total_shares().to_string()
+            })
+            .right(),
+            Column::new("fee_bp", 6, |pool: &LiquidityPool| {
+                pool.fee_bp().to_string()
+            })
+            .right(),
+            Column::new("trustlines", 10, |pool: &LiquidityPool| {
+                pool.total_trustlines().to_string()
+            })
+            .right()
+            .hidden(),
+        ]
+    }
+}
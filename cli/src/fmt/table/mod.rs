@@ -0,0 +1,327 @@
+//! Renders records as rows of an aligned table, one record per line, which
+//! is easier to scan than the multi-line `Simple` output when listing many
+//! records. Ids and hashes are shortened to fit unless `--full-ids` is given.
+use super::Render;
+use chrono::{DateTime, Utc};
+use clap::{App, Arg, ArgMatches};
+use error::InvalidInputError;
+use stellar_client::resources::AssetIdentifier;
+
+mod asset;
+mod effect;
+mod ledger;
+mod liquidity_pool;
+mod offer;
+mod operation;
+mod trade;
+mod trade_aggregation;
+mod transaction;
+
+const COLUMNS_ARG: &str = "columns";
+const FULL_IDS_ARG: &str = "full-ids";
+const SEPARATOR: &str = "  ";
+const ELLIPSIS: char = '…';
+
+/// A column of a table, naming a value of the records in it.
+pub struct Column<T> {
+    name: &'static str,
+    width: usize,
+    full_width: Option<usize>,
+    right: bool,
+    default: bool,
+    value: fn(&T) -> String,
+}
+
+impl<T> Column<T> {
+    /// A column whose values are padded to the width. Values that are
+    /// longer than the width are shown in full.
+    pub fn new(name: &'static str, width: usize, value: fn(&T) -> String) -> Column<T> {
+        Column {
+            name,
+            width: width.max(name.len()),
+            full_width: None,
+            right: false,
+            default: true,
+            value,
+        }
+    }
+
+    /// A column of ids or hashes, which are shortened to the width by
+    /// eliding their middle unless the table shows full ids.
+    pub fn id(
+        name: &'static str,
+        width: usize,
+        full_width: usize,
+        value: fn(&T) -> String,
+    ) -> Column<T> {
+        Column {
+            full_width: Some(full_width),
+            ..Column::new(name, width, value)
+        }
+    }
+
+    /// Aligns values to the right, such as for amounts.
+    pub fn right(mut self) -> Column<T> {
+        self.right = true;
+        self
+    }
+
+    /// Leaves the column out unless it's asked for with `--columns`.
+    pub fn hidden(mut self) -> Column<T> {
+        self.default = false;
+        self
+    }
+
+    fn width(&self, full_ids: bool) -> usize {
+        match self.full_width {
+            Some(full_width) if full_ids => full_width.max(self.name.len()),
+            _ => self.width,
+        }
+    }
+
+    fn header(&self, full_ids: bool) -> String {
+        self.align(self.name.to_uppercase(), self.width(full_ids))
+    }
+
+    fn cell(&self, item: &T, full_ids: bool) -> String {
+        let value = (self.value)(item);
+        let width = self.width(full_ids);
+        let value = match self.full_width {
+            Some(_) if !full_ids => shorten(&value, width),
+            _ => value,
+        };
+        self.align(value, width)
+    }
+
+    fn align(&self, value: String, width: usize) -> String {
+        if self.right {
+            format!("{:>width$}", value, width = width)
+        } else {
+            format!("{:<width$}", value, width = width)
+        }
+    }
+}
+
+/// The columns a record can be shown with in a table.
+pub trait Columns: Sized {
+    /// Every column, in the order they're shown by default.
+    fn columns() -> Vec<Column<Self>>;
+}
+
+/// Renders each record as a row of the chosen columns, after a header row.
+pub struct Table<T> {
+    columns: Vec<Column<T>>,
+    full_ids: bool,
+}
+
+/// Appends the column selection args to the app and returns a newly owned
+/// app.
+pub fn add<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
+    app.arg(
+        Arg::with_name(COLUMNS_ARG)
+            .long("columns")
+            .takes_value(true)
+            .help(
+                "The columns to show, separated by commas. An unknown column lists those available",
+            ),
+    )
+    .arg(
+        Arg::with_name(FULL_IDS_ARG)
+            .long("full-ids")
+            .help("Show ids and hashes in full instead of shortening them"),
+    )
+}
+
+impl<T> Table<T>
+where
+    T: Columns,
+{
+    /// Creates a table with the columns chosen on the command line, or the
+    /// default columns of the record.
+    pub fn from_arg(matches: &ArgMatches) -> Result<Table<T>, InvalidInputError> {
+        let full_ids = matches.is_present(FULL_IDS_ARG);
+        match matches.value_of(COLUMNS_ARG) {
+            Some(names) => Table::with_columns(names, full_ids),
+            None => Ok(Table {
+                columns: T::columns().into_iter().filter(|c| c.default).collect(),
+                full_ids,
+            }),
+        }
+    }
+
+    fn with_columns(names: &str, full_ids: bool) -> Result<Table<T>, InvalidInputError> {
+        let mut available = T::columns();
+        let mut columns = Vec::new();
+        for name in names
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            let name = name.to_lowercase();
+            match available.iter().position(|column| column.name == name) {
+                Some(index) => columns.push(available.remove(index)),
+                None if columns.iter().any(|column: &Column<T>| column.name == name) => {}
+                None => {
+                    let names: Vec<_> = T::columns().iter().map(|column| column.name).collect();
+                    return Err(InvalidInputError::from(format!(
+                        "Unknown column {:?}, choose from: {}",
+                        name,
+                        names.join(", ")
+                    )));
+                }
+            }
+        }
+        if columns.is_empty() {
+            return Err(InvalidInputError::from_str("No columns were given"));
+        }
+        Ok(Table { columns, full_ids })
+    }
+}
+
+impl<T> Table<T> {
+    fn row<F>(&self, cell: F) -> String
+    where
+        F: Fn(&Column<T>) -> String,
+    {
+        let cells: Vec<_> = self.columns.iter().map(cell).collect();
+        cells.join(SEPARATOR).trim_right().to_string()
+    }
+}
+
+impl<T> Render<T> for Table<T> {
+    fn preamble(&self) -> Option<String> {
+        Some(self.row(|column| column.header(self.full_ids)))
+    }
+
+    fn render(&self, item: &T) -> Option<String> {
+        Some(self.row(|column| column.cell(item, self.full_ids)))
+    }
+}
+
+/// Shortens a value to the width by replacing its middle with an ellipsis,
+/// keeping both ends since either may be what tells ids apart.
+fn shorten(value: &str, width: usize) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() <= width || width < 3 {
+        return value.to_string();
+    }
+    let tail = (width - 1) / 2;
+    let head = width - 1 - tail;
+    let mut shortened: String = chars[..head].iter().collect();
+    shortened.push(ELLIPSIS);
+    shortened.extend(&chars[chars.len() - tail..]);
+    shortened
+}
+
+/// Account ids are 56 characters long, and shortened to this many.
+const ACCOUNT_WIDTH: usize = 13;
+
+/// Transaction and ledger hashes are 64 characters long, and shortened to
+/// this many.
+const HASH_WIDTH: usize = 13;
+
+fn account_column<T>(name: &'static str, value: fn(&T) -> String) -> Column<T> {
+    Column::id(name, ACCOUNT_WIDTH, 56, value)
+}
+
+fn hash_column<T>(name: &'static str, value: fn(&T) -> String) -> Column<T> {
+    Column::id(name, HASH_WIDTH, 64, value)
+}
+
+/// Assets are shown as their code and issuer, which is shortened like an
+/// account id.
+fn asset_column<T>(name: &'static str, value: fn(&T) -> String) -> Column<T> {
+    Column::id(name, 18, 69, value)
+}
+
+fn asset(id: &AssetIdentifier) -> String {
+    if id.is_native() {
+        id.code().to_string()
+    } else {
+        format!("{}-{}", id.code(), id.issuer())
+    }
+}
+
+fn time(time: DateTime<Utc>) -> String {
+    time.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Joins multi-line details into a single cell.
+fn one_line(details: &str) -> String {
+    let parts: Vec<_> = details
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect();
+    parts.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fmt::Formatter;
+    use std::io::Cursor;
+
+    struct Record {
+        id: &'static str,
+        amount: u32,
+    }
+
+    impl Columns for Record {
+        fn columns() -> Vec<Column<Record>> {
+            vec![
+                Column::id("id", 9, 16, |r: &Record| r.id.to_string()),
+                Column::new("amount", 6, |r: &Record| r.amount.to_string()).right(),
+                Column::new("note", 4, |_: &Record| "hi".to_string()).hidden(),
+            ]
+        }
+    }
+
+    fn render(table: Table<Record>) -> String {
+        let mut fmt = Formatter::start(Cursor::new(Vec::new()), table);
+        fmt.render(&Record {
+            id: "ABCDEFGHIJKLMNOP",
+            amount: 42,
+        });
+        String::from_utf8(fmt.stop().into_inner()).unwrap()
+    }
+
+    fn table(args: Vec<&str>) -> Result<Table<Record>, InvalidInputError> {
+        let matches = add(App::new("test")).get_matches_from(args);
+        Table::from_arg(&matches)
+    }
+
+    #[test]
+    fn it_aligns_and_shortens_the_default_columns() {
+        assert_eq!(
+            render(table(vec!["test"]).unwrap()),
+            "ID         AMOUNT\nABCD…MNOP      42\n"
+        );
+    }
+
+    #[test]
+    fn it_shows_full_ids() {
+        assert_eq!(
+            render(table(vec!["test", "--full-ids"]).unwrap()),
+            "ID                AMOUNT\nABCDEFGHIJKLMNOP      42\n"
+        );
+    }
+
+    #[test]
+    fn it_selects_columns_in_the_order_given() {
+        assert_eq!(
+            render(table(vec!["test", "--columns", "note,AMOUNT"]).unwrap()),
+            "NOTE  AMOUNT\nhi        42\n"
+        );
+        assert!(table(vec!["test", "--columns", "nope"]).is_err());
+        assert!(table(vec!["test", "--columns", ","]).is_err());
+    }
+
+    #[test]
+    fn it_joins_details_into_one_line() {
+        assert_eq!(
+            one_line("To Account:   GABC\n  Amount:       1.5\n\n"),
+            "To Account: GABC, Amount: 1.5"
+        );
+    }
+}
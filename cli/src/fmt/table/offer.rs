@@ -0,0 +1,20 @@
+use super::{account_column, asset, asset_column, Column, Columns};
+use stellar_client::resources::Offer;
+
+impl Columns for Offer {
+    fn columns() -> Vec<Column<Offer>> {
+        vec![
+            Column::new("id", 10, |offer: &Offer| offer.id().to_string()).right(),
+            account_column("seller", |offer: &Offer| offer.seller().to_string()),
+            asset_column("selling", |offer: &Offer| asset(offer.selling())),
+            asset_column("buying", |offer: &Offer| asset(offer.buying())),
+            Column::new("amount", 18, |offer: &Offer| offer.amount().to_string()).right(),
+            Column::new("price", 12, |offer: &Offer| offer.price().to_string()).right(),
+            Column::new("price_ratio", 11, |offer: &Offer| {
+                let (numerator, denominator) = offer.price_ratio();
+                format!("{}/{}", numerator, denominator)
+            })
+            .hidden(),
+        ]
+    }
+}
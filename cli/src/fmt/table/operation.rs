@@ -0,0 +1,19 @@
+use super::{hash_column, one_line, Column, Columns};
+use fmt::Simple;
+use stellar_client::resources::Operation;
+
+impl Columns for Operation {
+    fn columns() -> Vec<Column<Operation>> {
+        vec![
+            Column::new("id", 19, |op: &Operation| op.id().to_string()).right(),
+            Column::new("type", 20, |op: &Operation| op.kind_name().to_string()),
+            hash_column("transaction", |op: &Operation| op.transaction().to_string()).hidden(),
+            Column::new("details", 0, |op: &Operation| {
+                Simple::new()
+                    .operation_details(op)
+                    .map(|details| one_line(&details))
+                    .unwrap_or_default()
+            }),
+        ]
+    }
+}
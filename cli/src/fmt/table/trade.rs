@@ -0,0 +1,40 @@
+use super::{account_column, asset, asset_column, time, Column, Columns};
+use stellar_client::resources::Trade;
+
+impl Columns for Trade {
+    fn columns() -> Vec<Column<Trade>> {
+        vec![
+            Column::new("id", 21, |trade: &Trade| trade.id().to_string()),
+            Column::new("closed_at", 19, |trade: &Trade| time(trade.closed_at())),
+            asset_column("base", |trade: &Trade| asset(trade.base_asset())),
+            Column::new("base_amount", 18, |trade: &Trade| {
+                trade.base_amount().to_string()
+            })
+            .right(),
+            asset_column("counter", |trade: &Trade| asset(trade.counter_asset())),
+            Column::new("counter_amount", 18, |trade: &Trade| {
+                trade.counter_amount().to_string()
+            })
+            .right(),
+            Column::new("price", 11, |trade: &Trade| {
+                let price = trade.price();
+                format!("{}/{}", price.numerator(), price.denominator())
+            }),
+            Column::new("offer_id", 10, |trade: &Trade| trade.offer_id().to_string())
+                .right()
+                .hidden(),
+            account_column("base_account", |trade: &Trade| {
+                trade.base_account().to_string()
+            })
+            .hidden(),
+            account_column("counter_account", |trade: &Trade| {
+                trade.counter_account().to_string()
+            })
+            .hidden(),
+            account_column("seller", |trade: &Trade| {
+                trade.selling_account().to_string()
+            })
+            .hidden(),
+        ]
+    }
+}
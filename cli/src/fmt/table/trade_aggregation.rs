@@ -0,0 +1,25 @@
+use super::{time, Column, Columns};
+use stellar_client::resources::TradeAggregation;
+
+impl Columns for TradeAggregation {
+    fn columns() -> Vec<Column<TradeAggregation>> {
+        type Aggregation = TradeAggregation;
+        vec![
+            Column::new("started_at", 19, |a: &Aggregation| time(a.started_at())),
+            Column::new("trades", 6, |a: &Aggregation| a.count().to_string()).right(),
+            Column::new("base_volume", 18, |a: &Aggregation| {
+                a.base_volume().to_string()
+            })
+            .right(),
+            Column::new("counter_volume", 18, |a: &Aggregation| {
+                a.counter_volume().to_string()
+            })
+            .right(),
+            Column::new("average", 12, |a: &Aggregation| a.average().to_string()).right(),
+            Column::new("open", 12, |a: &Aggregation| a.open().to_string()).right(),
+            Column::new("high", 12, |a: &Aggregation| a.high().to_string()).right(),
+            Column::new("low", 12, |a: &Aggregation| a.low().to_string()).right(),
+            Column::new("close", 12, |a: &Aggregation| a.close().to_string()).right(),
+        ]
+    }
+}
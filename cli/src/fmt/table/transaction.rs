@@ -0,0 +1,38 @@
+use super::{account_column, hash_column, time, Column, Columns};
+use stellar_client::resources::{Memo, Transaction};
+
+impl Columns for Transaction {
+    fn columns() -> Vec<Column<Transaction>> {
+        vec![
+            hash_column("hash", |txn: &Transaction| txn.hash().to_string()),
+            Column::new("ledger", 8, |txn: &Transaction| txn.ledger().to_string()).right(),
+            Column::new("created_at", 19, |txn: &Transaction| time(txn.created_at())),
+            account_column("source", |txn: &Transaction| {
+                txn.source_account().to_string()
+            }),
+            Column::new("sequence", 16, |txn: &Transaction| {
+                txn.source_account_sequence().to_string()
+            })
+            .right()
+            .hidden(),
+            Column::new("operations", 10, |txn: &Transaction| {
+                txn.operation_count().to_string()
+            })
+            .right(),
+            Column::new("fee", 9, |txn: &Transaction| {
+                txn.fee_as_amount().to_string()
+            })
+            .right(),
+            Column::new("memo", 4, |txn: &Transaction| memo(txn.memo())).hidden(),
+        ]
+    }
+}
+
+fn memo(memo: &Memo) -> String {
+    match *memo {
+        Memo::Text(ref text) => text.clone(),
+        Memo::Id(id) => id.to_string(),
+        Memo::Hash(ref hash) | Memo::Return(ref hash) => hash.clone(),
+        Memo::None => String::new(),
+    }
+}
@@ -0,0 +1,94 @@
+//! Named secret seeds kept in an encrypted keystore, so commands can take a
+//! `--key NAME` instead of a seed on the command line.
+use clap::ArgMatches;
+use config;
+use error::{InvalidInputError, Result};
+use passphrase;
+use std::env;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use stellar_client::keypair::Keypair;
+use stellar_client::keystore::{KeyFile, Keystore};
+
+/// Overrides where the keystore lives, which is `~/.stellar/keys` otherwise.
+const KEYSTORE_VAR: &str = "STELLAR_KEYSTORE";
+
+/// Opens the keystore, creating its directory on first use.
+pub fn keystore() -> Result<Keystore> {
+    let dir = match env::var_os(KEYSTORE_VAR) {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            let dir = config::stellar_dir().ok_or_else(|| {
+                InvalidInputError::from_str(
+                    "Can't find a home directory for the keystore, set STELLAR_KEYSTORE",
+                )
+            })?;
+            dir.join("keys")
+        }
+    };
+    Ok(Keystore::open(dir)?)
+}
+
+/// Prompts for the passphrase of the named key and decrypts it.
+pub fn unlock(name: &str) -> Result<Keypair> {
+    let file = keystore()?.get(name)?;
+    let passphrase = passphrase::prompt(&format!("Passphrase for {}: ", name))?;
+    Ok(file.decrypt(&passphrase)?)
+}
+
+pub fn add(matches: &ArgMatches) -> Result<()> {
+    let name = matches.value_of("NAME").expect("NAME is required");
+    let keystore = keystore()?;
+    if keystore.contains(name) {
+        return Err(format!("A key is already stored under {:?}", name).into());
+    }
+    let seed = passphrase::read_hidden("Secret seed: ")?;
+    let keypair = Keypair::from_secret_seed(seed.trim())
+        .map_err(|_| InvalidInputError::from_str("That isn't a valid secret seed"))?;
+    let passphrase = passphrase::prompt_new()?;
+    keystore.insert(name, &KeyFile::encrypt(&keypair, &passphrase)?)?;
+    println!("{}: {}", name, keypair.account_id());
+    Ok(())
+}
+
+pub fn list() -> Result<()> {
+    let keystore = keystore()?;
+    for name in keystore.names()? {
+        println!("{}: {}", name, keystore.get(&name)?.account_id());
+    }
+    Ok(())
+}
+
+pub fn show(matches: &ArgMatches) -> Result<()> {
+    let name = matches.value_of("NAME").expect("NAME is required");
+    println!("{}", keystore()?.get(name)?.account_id());
+    Ok(())
+}
+
+pub fn check(matches: &ArgMatches) -> Result<()> {
+    let name = matches.value_of("NAME").expect("NAME is required");
+    let keypair = unlock(name)?;
+    println!("The passphrase unlocks {}", keypair.account_id());
+    Ok(())
+}
+
+pub fn remove(matches: &ArgMatches) -> Result<()> {
+    let name = matches.value_of("NAME").expect("NAME is required");
+    let keystore = keystore()?;
+    let account_id = keystore.get(name)?.account_id().to_string();
+    if !matches.is_present("yes") {
+        eprint!(
+            "Removing {} ({}) can't be undone. Type its name to confirm: ",
+            name, account_id
+        );
+        io::stderr().flush()?;
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        if answer.trim() != name {
+            return Err(InvalidInputError::from_str("The key was not removed").into());
+        }
+    }
+    keystore.remove(name)?;
+    println!("Removed {}", name);
+    Ok(())
+}
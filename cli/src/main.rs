@@ -5,31 +5,45 @@ extern crate clap;
 extern crate stellar_client;
 
 use clap::{App, AppSettings, Arg, SubCommand};
+use command::Command;
+use config::Config;
 use error::CliError;
 use pager::Pager;
+use std::env;
 use stellar_client::{error::Error, sync::Client};
 
 mod account;
 mod asset_identifier;
 mod assets;
+mod command;
+mod completions;
+mod config;
 mod cursor;
 mod effects;
 mod error;
+mod fees;
 mod fmt;
+mod keys;
 mod ledgers;
+mod multisig;
+mod network;
+mod offers;
 mod operations;
 mod orderbook;
 mod ordering;
 mod pager;
+mod passphrase;
 mod payments;
+mod pool;
 mod resolution;
 mod trades;
 mod transactions;
+mod xdr;
 
 fn build_app<'a, 'b>() -> App<'a, 'b> {
     macro_rules! listable {
         ($e:expr) => {
-            Pager::add(ordering::add(cursor::add($e)))
+            fmt::table::add(Pager::add(ordering::add(cursor::add($e))))
         };
     }
 
@@ -57,10 +71,167 @@ fn build_app<'a, 'b>() -> App<'a, 'b> {
                 .conflicts_with_all(&["host", "test-net"])
                 .help("Connects to the public net."),
         )
+        .arg(
+            Arg::with_name("network-passphrase")
+                .long("network-passphrase")
+                .takes_value(true)
+                .global(true)
+                .help("The passphrase of the network to sign transactions for. Only needed with --host"),
+        )
+        .arg(
+            Arg::with_name("dry-run")
+                .long("dry-run")
+                .global(true)
+                .help("Print the envelope of any transaction instead of submitting it"),
+        )
+        .arg(
+            Arg::with_name("unsigned")
+                .long("unsigned")
+                .global(true)
+                .requires("dry-run")
+                .help("Leave the envelope printed by --dry-run unsigned"),
+        )
         .subcommand(
             SubCommand::with_name("account")
                 .about("Access information about accounts or related to them")
                 .setting(AppSettings::SubcommandRequired)
+                .subcommand(
+                    SubCommand::with_name("create")
+                        .about("Create a new account, funding it with friendbot on the test net")
+                        .arg(
+                            Arg::with_name("trust")
+                                .long("trust")
+                                .takes_value(true)
+                                .multiple(true)
+                                .number_of_values(1)
+                                .help("An asset to trust once funded, as <asset_code>-<asset_issuer>. May be repeated."),
+                        )
+                        .arg(
+                            Arg::with_name("key")
+                                .long("key")
+                                .takes_value(true)
+                                .conflicts_with("keystore")
+                                .help("Store the secret seed in the keystore under this name"),
+                        )
+                        .arg(
+                            Arg::with_name("keystore")
+                                .long("keystore")
+                                .takes_value(true)
+                                .help("Where to write the encrypted secret seed. Defaults to <account_id>.json"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("merge")
+                        .about("Merge an account into another, sending it all of its lumens")
+                        .arg(
+                            Arg::with_name("from")
+                                .long("from")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The name of the stored key of the account to merge away"),
+                        )
+                        .arg(
+                            Arg::with_name("into")
+                                .long("into")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The account that receives the lumens"),
+                        )
+                        .arg(
+                            Arg::with_name("yes")
+                                .long("yes")
+                                .help("Don't ask for confirmation"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("export")
+                        .about("Export an account's payments, trades and fees with running balances")
+                        .arg(
+                            Arg::with_name("ID")
+                                .required(true)
+                                .help("The identifier of the account to export"),
+                        )
+                        .arg(
+                            Arg::with_name("from")
+                                .long("from")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The first day to export, as 2023-01-01, or a time in ISO 8601 format"),
+                        )
+                        .arg(
+                            Arg::with_name("to")
+                                .long("to")
+                                .takes_value(true)
+                                .help("The last day to export, as 2023-12-31, or a time in ISO 8601 format to stop before. Defaults to now"),
+                        )
+                        .arg(
+                            Arg::with_name("output")
+                                .long("output")
+                                .short("o")
+                                .takes_value(true)
+                                .possible_values(&["csv", "json"])
+                                .default_value("csv")
+                                .help("The format to write the export in"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("set")
+                        .about("Set an account's home domain, flags, master weight or thresholds")
+                        .arg(
+                            Arg::with_name("key")
+                                .long("key")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The name of the stored key of the account to change"),
+                        )
+                        .arg(
+                            Arg::with_name("home-domain")
+                                .long("home-domain")
+                                .takes_value(true)
+                                .help("The domain hosting the account's stellar.toml. An empty value removes it"),
+                        )
+                        .arg(
+                            Arg::with_name("set-flag")
+                                .long("set-flag")
+                                .takes_value(true)
+                                .multiple(true)
+                                .number_of_values(1)
+                                .possible_values(&["auth_required", "auth_revocable", "auth_immutable", "auth_clawback_enabled"])
+                                .help("A flag to set. May be repeated"),
+                        )
+                        .arg(
+                            Arg::with_name("clear-flag")
+                                .long("clear-flag")
+                                .takes_value(true)
+                                .multiple(true)
+                                .number_of_values(1)
+                                .possible_values(&["auth_required", "auth_revocable", "auth_immutable", "auth_clawback_enabled"])
+                                .help("A flag to clear. May be repeated"),
+                        )
+                        .arg(
+                            Arg::with_name("master-weight")
+                                .long("master-weight")
+                                .takes_value(true)
+                                .help("The weight of the account's own key. 0 disables it"),
+                        )
+                        .arg(
+                            Arg::with_name("thresholds")
+                                .long("thresholds")
+                                .takes_value(true)
+                                .help("The low, medium and high thresholds, as <low>/<medium>/<high>"),
+                        )
+                        .arg(
+                            Arg::with_name("inflation-dest")
+                                .long("inflation-dest")
+                                .takes_value(true)
+                                .help("The account to receive the account's inflation votes"),
+                        )
+                        .arg(
+                            Arg::with_name("yes")
+                                .long("yes")
+                                .help("Don't ask for confirmation"),
+                        ),
+                )
                 .subcommand(
                     SubCommand::with_name("data")
                         .about("Fetch and account's metadata for a particular key")
@@ -84,6 +255,31 @@ fn build_app<'a, 'b>() -> App<'a, 'b> {
                                 .help("The identifier of the account to look up"),
                         ),
                 )
+                .subcommand(
+                    SubCommand::with_name("balance")
+                        .about("List an account's balances, with what can be spent and their value")
+                        .arg(
+                            Arg::with_name("ID")
+                                .required(true)
+                                .help("The identifier of the account to look up"),
+                        )
+                        .arg(
+                            Arg::with_name("quote")
+                                .long("quote")
+                                .takes_value(true)
+                                .value_name("ASSET")
+                                .help("Value each balance in this asset, such as USDC-GA5Z..., by what selling it would fetch"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("signers")
+                        .about("List an account's signers and thresholds")
+                        .arg(
+                            Arg::with_name("ID")
+                                .required(true)
+                                .help("The identifier of the account to look up"),
+                        ),
+                )
                 .subcommand(
                     listable!(
                         SubCommand::with_name("transactions")
@@ -145,6 +341,28 @@ fn build_app<'a, 'b>() -> App<'a, 'b> {
 
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("completions")
+                .about("Print a completion script for a shell")
+                .after_help("For example, add `source <(stellar completions bash)` to ~/.bashrc")
+                .arg(
+                    Arg::with_name("SHELL")
+                        .required(true)
+                        .possible_values(completions::SHELLS)
+                        .help("The shell to complete commands in"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("fees")
+                .about("Print the fees the network is charging per operation")
+                .arg(
+                    Arg::with_name("suggest")
+                        .long("suggest")
+                        .takes_value(true)
+                        .value_name("OPERATIONS")
+                        .help("Also print a max fee to offer for a transaction with this many operations"),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("effects")
                 .about("Access lists of effects")
@@ -221,7 +439,17 @@ fn build_app<'a, 'b>() -> App<'a, 'b> {
         .subcommand(
             SubCommand::with_name("transactions")
                 .about("Access lists of transactions")
+                .alias("tx")
                 .setting(AppSettings::SubcommandRequired)
+                .subcommand(
+                    SubCommand::with_name("decode")
+                        .about("Print what a base64 transaction envelope does, such as before signing it")
+                        .arg(
+                            Arg::with_name("XDR")
+                                .required(true)
+                                .help("The base64 encoded transaction envelope"),
+                        ),
+                )
                 .subcommand(
                     listable!(
                         SubCommand::with_name("all")
@@ -248,6 +476,35 @@ fn build_app<'a, 'b>() -> App<'a, 'b> {
                                 .help("The identifier of the transaction to look up"),
                         ),
                 )
+                .subcommand(
+                    SubCommand::with_name("status")
+                        .about("Print whether a transaction succeeded, with its result codes, fee and operations")
+                        .arg(
+                            Arg::with_name("HASH")
+                                .required(true)
+                                .help("The hash of the transaction to look up"),
+                        )
+                        .arg(
+                            Arg::with_name("watch")
+                                .long("watch")
+                                .short("w")
+                                .help("Keep polling until the transaction appears, such as after submitting it"),
+                        )
+                        .arg(
+                            Arg::with_name("interval")
+                                .long("interval")
+                                .takes_value(true)
+                                .requires("watch")
+                                .help("Seconds between polls while watching, defaults to 2"),
+                        )
+                        .arg(
+                            Arg::with_name("timeout")
+                                .long("timeout")
+                                .takes_value(true)
+                                .requires("watch")
+                                .help("Give up watching after this many seconds"),
+                        ),
+                )
                 .subcommand(
                     listable!(
                         SubCommand::with_name("operations")
@@ -296,6 +553,360 @@ fn build_app<'a, 'b>() -> App<'a, 'b> {
                     ),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("key")
+                .about("Manage secret seeds kept in the encrypted keystore")
+                .setting(AppSettings::SubcommandRequired)
+                .subcommand(
+                    SubCommand::with_name("add")
+                        .about("Store a secret seed under a name, prompting for the seed and a passphrase")
+                        .arg(
+                            Arg::with_name("NAME")
+                                .required(true)
+                                .help("The name to store the key under"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("list")
+                        .about("List the stored keys and their accounts"),
+                )
+                .subcommand(
+                    SubCommand::with_name("show")
+                        .about("Print the account id of a stored key")
+                        .arg(
+                            Arg::with_name("NAME")
+                                .required(true)
+                                .help("The name of the key"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("check")
+                        .about("Check that a passphrase unlocks a stored key")
+                        .arg(
+                            Arg::with_name("NAME")
+                                .required(true)
+                                .help("The name of the key"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("remove")
+                        .about("Delete a stored key")
+                        .arg(
+                            Arg::with_name("NAME")
+                                .required(true)
+                                .help("The name of the key"),
+                        )
+                        .arg(
+                            Arg::with_name("yes")
+                                .long("yes")
+                                .help("Don't ask for confirmation"),
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("multisig")
+                .about("Set up accounts that need signatures from several keys")
+                .setting(AppSettings::SubcommandRequired)
+                .subcommand(
+                    SubCommand::with_name("setup")
+                        .about("Add signers and set thresholds, printing the transaction for co-signers")
+                        .arg(
+                            Arg::with_name("account")
+                                .long("account")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The name of the stored key of the account to set up"),
+                        )
+                        .arg(
+                            Arg::with_name("add-signer")
+                                .long("add-signer")
+                                .takes_value(true)
+                                .multiple(true)
+                                .number_of_values(1)
+                                .help("A signer to add, as <account_id>:<weight>. May be repeated. A weight of 0 removes the signer"),
+                        )
+                        .arg(
+                            Arg::with_name("thresholds")
+                                .long("thresholds")
+                                .takes_value(true)
+                                .help("The low, medium and high thresholds, as <low>/<medium>/<high>"),
+                        )
+                        .arg(
+                            Arg::with_name("master-weight")
+                                .long("master-weight")
+                                .takes_value(true)
+                                .help("The weight of the account's own key. 0 disables it"),
+                        )
+                        .arg(
+                            Arg::with_name("signed")
+                                .long("signed")
+                                .takes_value(true)
+                                .multiple(true)
+                                .number_of_values(1)
+                                .help("An envelope signed by a co-signer, whose signatures are merged before signing and submitting. May be repeated"),
+                        )
+                        .arg(
+                            Arg::with_name("force")
+                                .long("force")
+                                .help("Skip checking that the new signers can meet the thresholds"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("sign")
+                        .about("Sign an envelope printed by setup and print it with the signature added")
+                        .arg(
+                            Arg::with_name("XDR")
+                                .required(true)
+                                .help("The base64 encoded envelope to sign"),
+                        )
+                        .arg(
+                            Arg::with_name("key")
+                                .long("key")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The name of the stored key to sign with"),
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("network")
+                .about("Check on the network and the horizon server")
+                .setting(AppSettings::SubcommandRequired)
+                .subcommand(
+                    SubCommand::with_name("status")
+                        .about("Print the versions, latest ledger and current fees of the network"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("offer")
+                .about("Manage offers on the decentralized exchange")
+                .setting(AppSettings::SubcommandRequired)
+                .subcommand(
+                    SubCommand::with_name("create")
+                        .about("Create an offer to trade one asset for another")
+                        .arg(
+                            Arg::with_name("key")
+                                .long("key")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The name of the stored key to sign with"),
+                        )
+                        .arg(
+                            Arg::with_name("selling")
+                                .long("selling")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The asset to sell. format:  <asset_code>-<asset_issuer>, or xlm if lumens"),
+                        )
+                        .arg(
+                            Arg::with_name("buying")
+                                .long("buying")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The asset to buy. format:  <asset_code>-<asset_issuer>, or xlm if lumens"),
+                        )
+                        .arg(
+                            Arg::with_name("amount")
+                                .long("amount")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The amount to sell, or to buy with --buy"),
+                        )
+                        .arg(
+                            Arg::with_name("price")
+                                .long("price")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The price of one unit sold in the buying asset, or of one unit bought in the selling asset with --buy. As a ratio like 3/2 or a decimal"),
+                        )
+                        .arg(
+                            Arg::with_name("buy")
+                                .long("buy")
+                                .help("Make a buy offer for an amount of the buying asset instead of a sell offer"),
+                        )
+                )
+                .subcommand(
+                    SubCommand::with_name("update")
+                        .about("Change the amount and price of an open offer")
+                        .arg(
+                            Arg::with_name("OFFER_ID")
+                                .required(true)
+                                .help("The id of the offer"),
+                        )
+                        .arg(
+                            Arg::with_name("key")
+                                .long("key")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The name of the stored key to sign with"),
+                        )
+                        .arg(
+                            Arg::with_name("amount")
+                                .long("amount")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The amount to sell, or to buy with --buy"),
+                        )
+                        .arg(
+                            Arg::with_name("price")
+                                .long("price")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The price of one unit sold in the buying asset, or of one unit bought in the selling asset with --buy. As a ratio like 3/2 or a decimal"),
+                        )
+                        .arg(
+                            Arg::with_name("buy")
+                                .long("buy")
+                                .help("Make a buy offer for an amount of the buying asset instead of a sell offer"),
+                        )
+                )
+                .subcommand(
+                    SubCommand::with_name("cancel")
+                        .about("Remove an open offer")
+                        .arg(
+                            Arg::with_name("OFFER_ID")
+                                .required(true)
+                                .help("The id of the offer"),
+                        )
+                        .arg(
+                            Arg::with_name("key")
+                                .long("key")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The name of the stored key to sign with"),
+                        )
+                )
+                .subcommand(
+                    listable!(
+                        SubCommand::with_name("list")
+                            .about("Fetch the open offers of an account")
+                            .arg(
+                                Arg::with_name("ACCOUNT")
+                                    .required_unless("key")
+                                    .help("The identifier of the account to look up"),
+                            )
+                            .arg(
+                                Arg::with_name("key")
+                                    .long("key")
+                                    .takes_value(true)
+                                    .conflicts_with("ACCOUNT")
+                                    .help("List the offers of a stored key instead"),
+                            )
+                    ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("pool")
+                .about("Trade liquidity in the pools of the decentralized exchange")
+                .setting(AppSettings::SubcommandRequired)
+                .subcommand(
+                    listable!(
+                        SubCommand::with_name("list")
+                            .about("Fetch liquidity pools")
+                            .arg(
+                                Arg::with_name("reserve")
+                                    .long("reserve")
+                                    .takes_value(true)
+                                    .multiple(true)
+                                    .number_of_values(1)
+                                    .help("Only pools holding the asset. format:  <asset_code>-<asset_issuer>, or xlm if lumens. May be given twice"),
+                            )
+                            .arg(
+                                Arg::with_name("account")
+                                    .long("account")
+                                    .takes_value(true)
+                                    .help("Only pools the account holds shares of"),
+                            )
+                    ),
+                )
+                .subcommand(
+                    SubCommand::with_name("show")
+                        .about("Fetch a pool's reserves and share price, and optionally an account's shares")
+                        .arg(
+                            Arg::with_name("ID")
+                                .required(true)
+                                .help("The id of the liquidity pool"),
+                        )
+                        .arg(
+                            Arg::with_name("account")
+                                .long("account")
+                                .takes_value(true)
+                                .conflicts_with("key")
+                                .help("Also show the shares the account holds"),
+                        )
+                        .arg(
+                            Arg::with_name("key")
+                                .long("key")
+                                .takes_value(true)
+                                .help("Also show the shares a stored key holds"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("deposit")
+                        .about("Deposit assets into a pool for pool shares, trusting the shares first if needed")
+                        .arg(
+                            Arg::with_name("ID")
+                                .required(true)
+                                .help("The id of the liquidity pool"),
+                        )                        .arg(
+                            Arg::with_name("key")
+                                .long("key")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The name of the stored key to sign with"),
+                        )
+                        .arg(
+                            Arg::with_name("max-a")
+                                .long("max-a")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The most of the pool's first asset to deposit"),
+                        )
+                        .arg(
+                            Arg::with_name("max-b")
+                                .long("max-b")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The most of the pool's second asset to deposit"),
+                        )
+                        .arg(
+                            Arg::with_name("slippage")
+                                .long("slippage")
+                                .takes_value(true)
+                                .default_value("100")
+                                .help("How far the pool's price may move before the transaction lands, in basis points. 100 is 1%"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("withdraw")
+                        .about("Redeem pool shares for the pool's assets")
+                        .arg(
+                            Arg::with_name("ID")
+                                .required(true)
+                                .help("The id of the liquidity pool"),
+                        )                        .arg(
+                            Arg::with_name("key")
+                                .long("key")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The name of the stored key to sign with"),
+                        )
+                        .arg(
+                            Arg::with_name("shares")
+                                .long("shares")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The number of shares to redeem"),
+                        )
+                        .arg(
+                            Arg::with_name("slippage")
+                                .long("slippage")
+                                .takes_value(true)
+                                .default_value("100")
+                                .help("How far the pool's price may move before the transaction lands, in basis points. 100 is 1%"),
+                        ),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("ledgers")
                 .about("Access lists of ledgers")
@@ -391,7 +1002,7 @@ fn build_app<'a, 'b>() -> App<'a, 'b> {
                     ),
                 )
                 .subcommand(
-                    SubCommand::with_name("aggregations")
+                    fmt::table::add(SubCommand::with_name("aggregations")
                         .about("Fetch aggregate statistics over a specified time range.")
                         .arg(
                             Arg::with_name("start_time")
@@ -428,12 +1039,42 @@ fn build_app<'a, 'b>() -> App<'a, 'b> {
                                 .required(true)
                                 .help("Filters trades with a given counter_asset. format:  <asset_code>-<asset_issuer>, or xlm if lumens"),
                         )
+                    )
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("xdr")
+                .about("Inspect XDR blobs offline")
+                .setting(AppSettings::SubcommandRequired)
+                .subcommand(
+                    SubCommand::with_name("decode")
+                        .about("Decode a base64 XDR blob and print it as a tree")
+                        .arg(
+                            Arg::with_name("XDR")
+                                .required(true)
+                                .help("The base64 encoded XDR to decode"),
+                        )
+                        .arg(
+                            Arg::with_name("type")
+                                .long("type")
+                                .short("t")
+                                .takes_value(true)
+                                .possible_values(&["envelope", "result", "entry"])
+                                .help("The XDR type to decode as. If not specified the type is detected"),
+                        ),
                 ),
         )
 }
 
 fn main() {
-    let matches = build_app().get_matches();
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{}", err);
+            ::std::process::exit(1);
+        }
+    };
+    let matches = build_app().get_matches_from(config.expand(env::args_os().collect()));
 
     let client = if let Some(host) = matches.value_of("host") {
         Client::new(&host).expect("Failed to initialize client")
@@ -443,62 +1084,9 @@ fn main() {
         Client::horizon_test().unwrap()
     };
 
-    // Master match block. All subcommands need to be captured here.
-    let result = match matches.subcommand() {
-        ("account", Some(sub_m)) => match sub_m.subcommand() {
-            ("data", Some(sub_m)) => account::data(&client, sub_m),
-            ("details", Some(sub_m)) => account::details(&client, sub_m),
-            ("transactions", Some(sub_m)) => account::transactions(&client, sub_m),
-            ("effects", Some(sub_m)) => account::effects(&client, sub_m),
-            ("offers", Some(sub_m)) => account::offers(&client, sub_m),
-            ("operations", Some(sub_m)) => account::operations(&client, sub_m),
-            ("payments", Some(sub_m)) => account::payments(&client, sub_m),
-            _ => return print_help_and_exit(),
-        },
-        ("assets", Some(sub_m)) => match sub_m.subcommand() {
-            ("all", Some(sub_m)) => assets::all(&client, sub_m),
-            _ => return print_help_and_exit(),
-        },
-        ("effects", Some(sub_m)) => match sub_m.subcommand() {
-            ("all", Some(sub_m)) => effects::all(&client, sub_m),
-            _ => return print_help_and_exit(),
-        },
-        ("ledgers", Some(sub_m)) => match sub_m.subcommand() {
-            ("all", Some(sub_m)) => ledgers::all(&client, sub_m),
-            ("details", Some(sub_m)) => ledgers::details(&client, sub_m),
-            ("effects", Some(sub_m)) => ledgers::effects(&client, sub_m),
-            ("payments", Some(sub_m)) => ledgers::payments(&client, sub_m),
-            ("operations", Some(sub_m)) => ledgers::operations(&client, sub_m),
-            ("transactions", Some(sub_m)) => ledgers::transactions(&client, sub_m),
-            _ => return print_help_and_exit(),
-        },
-        ("operations", Some(sub_m)) => match sub_m.subcommand() {
-            ("all", Some(sub_m)) => operations::all(&client, sub_m),
-            ("effects", Some(sub_m)) => operations::effects(&client, sub_m),
-            _ => return print_help_and_exit(),
-        },
-        ("orderbook", Some(sub_m)) => match sub_m.subcommand() {
-            ("details", Some(sub_m)) => orderbook::details(&client, sub_m),
-            _ => return print_help_and_exit(),
-        },
-        ("payments", Some(sub_m)) => match sub_m.subcommand() {
-            ("all", Some(sub_m)) => payments::all(&client, sub_m),
-            _ => return print_help_and_exit(),
-        },
-        ("trades", Some(sub_m)) => match sub_m.subcommand() {
-            ("aggregations", Some(sub_m)) => trades::aggregations(&client, sub_m),
-            ("all", Some(sub_m)) => trades::all(&client, sub_m),
-            _ => return print_help_and_exit(),
-        },
-        ("transactions", Some(sub_m)) => match sub_m.subcommand() {
-            ("all", Some(sub_m)) => transactions::all(&client, sub_m),
-            ("details", Some(sub_m)) => transactions::details(&client, sub_m),
-            ("operations", Some(sub_m)) => transactions::operations(&client, sub_m),
-            ("payments", Some(sub_m)) => transactions::payments(&client, sub_m),
-            ("effects", Some(sub_m)) => transactions::effects(&client, sub_m),
-            _ => return print_help_and_exit(),
-        },
-        _ => return print_help_and_exit(),
+    let result = match Command::from_matches(&matches) {
+        Some((command, sub_m)) => command.run(&client, sub_m),
+        None => return print_help_and_exit(),
     };
 
     match result {
@@ -507,6 +1095,34 @@ fn main() {
             eprintln!("{}", err);
             ::std::process::exit(1);
         }
+        Err(CliError::ClientError(Error::Xdr(err))) => {
+            eprintln!("{}", err);
+            ::std::process::exit(1);
+        }
+        Err(CliError::ClientError(Error::Deserialize(err))) => {
+            eprintln!("{}", err);
+            ::std::process::exit(1);
+        }
+        Err(CliError::OperatorError(err)) => {
+            eprintln!("{}", err);
+            ::std::process::exit(1);
+        }
+        Err(CliError::KeystoreError(err)) => {
+            eprintln!("{}", err);
+            ::std::process::exit(1);
+        }
+        Err(CliError::TransactionError(err)) => {
+            eprintln!("{}", err);
+            ::std::process::exit(1);
+        }
+        Err(CliError::SignerError(err)) => {
+            eprintln!("{}", err);
+            ::std::process::exit(1);
+        }
+        Err(CliError::IoError(err)) => {
+            eprintln!("{}", err);
+            ::std::process::exit(1);
+        }
         err => {
             eprintln!("An unknown error occurred: {:?}", err);
             ::std::process::exit(1);
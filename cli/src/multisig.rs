@@ -0,0 +1,197 @@
+//! Setting up an account to need several signatures, and collecting those
+//! signatures from co-signers.
+//!
+//! `setup` prints the unsigned transaction so it can be handed to co-signers,
+//! who sign it with `sign`. Running `setup` again with the same arguments and
+//! a `--signed` envelope from each co-signer rebuilds the same transaction,
+//! merges in their signatures, signs it with the account's key and submits it.
+use super::{keys, network, transactions};
+use clap::ArgMatches;
+use error::{InvalidInputError, Result};
+use stellar_client::endpoint::account;
+use stellar_client::resources::Account;
+use stellar_client::sync::Client;
+use stellar_client::transaction::{Operation, SetOptions, TransactionBuilder, TransactionEnvelope};
+
+pub fn setup(client: &Client, matches: &ArgMatches) -> Result<()> {
+    let network_passphrase = network::passphrase(client, matches)?;
+    let name = matches.value_of("account").expect("Account is required");
+    let signers = match matches.values_of("add-signer") {
+        Some(values) => values.map(parse_signer).collect::<Result<Vec<_>>>()?,
+        None => Vec::new(),
+    };
+    let thresholds = match matches.value_of("thresholds") {
+        Some(value) => Some(parse_thresholds(value)?),
+        None => None,
+    };
+    let master_weight = match matches.value_of("master-weight") {
+        Some(value) => Some(parse_weight(value)?),
+        None => None,
+    };
+    if signers.is_empty() && thresholds.is_none() && master_weight.is_none() {
+        return Err(InvalidInputError::from_str(
+            "Nothing to set up, give --add-signer, --thresholds or --master-weight",
+        )
+        .into());
+    }
+    if !matches.is_present("force") {
+        check_reachable(&signers, thresholds, master_weight)?;
+    }
+
+    let account_id = keys::keystore()?.get(name)?.account_id().to_string();
+    let account = client.request(account::Details::new(&account_id))?;
+    let mut envelope = build(
+        &account,
+        network_passphrase,
+        &signers,
+        thresholds,
+        master_weight,
+    )?;
+
+    let signed = match matches.values_of("signed") {
+        Some(values) => values.collect::<Vec<_>>(),
+        None => {
+            eprintln!("Have each co-signer run `stellar multisig sign` on this envelope, then");
+            eprintln!("run setup again with a --signed envelope from each of them:");
+            println!("{}", envelope.to_base64()?);
+            return Ok(());
+        }
+    };
+    for xdr in signed {
+        let other = TransactionEnvelope::from_base64(xdr, network_passphrase)?;
+        envelope.merge_signatures(&other).map_err(|_| {
+            InvalidInputError::from_str(
+                "A signed envelope is for a different transaction. Has the account's sequence \
+                 changed since it was printed?",
+            )
+        })?;
+    }
+    let keypair = keys::unlock(name)?;
+    if let Some(submitted) = transactions::submit(client, matches, envelope, &keypair)? {
+        println!("Submitted in ledger {}", submitted.ledger());
+        println!("Hash: {}", submitted.hash());
+    }
+    Ok(())
+}
+
+pub fn sign(client: &Client, matches: &ArgMatches) -> Result<()> {
+    let network_passphrase = network::passphrase(client, matches)?;
+    let xdr = matches.value_of("XDR").expect("XDR is required");
+    let mut envelope = TransactionEnvelope::from_base64(xdr, network_passphrase)?;
+    envelope.sign(&keys::unlock(
+        matches.value_of("key").expect("Key is required"),
+    )?)?;
+    println!("{}", envelope.to_base64()?);
+    Ok(())
+}
+
+/// Signers are added first, so the thresholds and master weight are only
+/// changed once there is someone to meet them.
+fn build(
+    account: &Account,
+    network_passphrase: &str,
+    signers: &[(String, u32)],
+    thresholds: Option<(u32, u32, u32)>,
+    master_weight: Option<u32>,
+) -> Result<TransactionEnvelope> {
+    let mut builder = TransactionBuilder::from_account(account, network_passphrase);
+    for &(ref key, weight) in signers {
+        builder = builder.with_operation(Operation::set_options(
+            SetOptions::default().with_signer(key, weight),
+        ));
+    }
+    if thresholds.is_some() || master_weight.is_some() {
+        let mut options = SetOptions::default();
+        if let Some((low, med, high)) = thresholds {
+            options = options.with_thresholds(low, med, high);
+        }
+        if let Some(weight) = master_weight {
+            options = options.with_master_weight(weight);
+        }
+        builder = builder.with_operation(Operation::set_options(options));
+    }
+    Ok(builder.build()?)
+}
+
+/// Refuses thresholds that the master key and the new signers can't meet
+/// together, which would leave the account unable to sign anything. Signers
+/// the account already has aren't known here, so `--force` skips the check.
+fn check_reachable(
+    signers: &[(String, u32)],
+    thresholds: Option<(u32, u32, u32)>,
+    master_weight: Option<u32>,
+) -> Result<()> {
+    let high = match thresholds {
+        Some((_, _, high)) => high,
+        None if master_weight == Some(0) => 1,
+        None => return Ok(()),
+    };
+    let total = master_weight.unwrap_or(1) + signers.iter().map(|&(_, weight)| weight).sum::<u32>();
+    if high > total {
+        return Err(format!(
+            "The signers only reach a weight of {}, short of the high threshold of {}. This \
+             would lock the account, use --force if it already has other signers",
+            total, high
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn parse_signer(value: &str) -> Result<(String, u32)> {
+    match value.rfind(':') {
+        Some(colon) if colon > 0 => Ok((
+            value[..colon].to_string(),
+            parse_weight(&value[colon + 1..])?,
+        )),
+        _ => Err(InvalidInputError::from_str("Signers are given as <account_id>:<weight>").into()),
+    }
+}
+
+pub fn parse_thresholds(value: &str) -> Result<(u32, u32, u32)> {
+    let weights = value
+        .split('/')
+        .map(parse_weight)
+        .collect::<Result<Vec<_>>>()?;
+    match weights[..] {
+        [low, med, high] => Ok((low, med, high)),
+        _ => {
+            Err(InvalidInputError::from_str("Thresholds are given as <low>/<medium>/<high>").into())
+        }
+    }
+}
+
+pub fn parse_weight(value: &str) -> Result<u32> {
+    match value.parse::<u32>() {
+        Ok(weight) if weight <= 255 => Ok(weight),
+        _ => Err(format!("Weights are between 0 and 255, not {:?}", value).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIGNER: &str = "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3";
+
+    #[test]
+    fn it_parses_signers_and_thresholds() {
+        assert_eq!(
+            parse_signer(&format!("{}:2", SIGNER)).unwrap(),
+            (SIGNER.to_string(), 2)
+        );
+        assert!(parse_signer(SIGNER).is_err());
+        assert!(parse_signer(&format!("{}:256", SIGNER)).is_err());
+        assert_eq!(parse_thresholds("1/2/3").unwrap(), (1, 2, 3));
+        assert!(parse_thresholds("1/2").is_err());
+    }
+
+    #[test]
+    fn it_refuses_thresholds_that_lock_the_account() {
+        let signers = vec![(SIGNER.to_string(), 1)];
+        assert!(check_reachable(&signers, Some((1, 2, 2)), None).is_ok());
+        assert!(check_reachable(&signers, Some((1, 2, 3)), None).is_err());
+        assert!(check_reachable(&[], None, Some(0)).is_err());
+        assert!(check_reachable(&signers, None, Some(0)).is_ok());
+    }
+}
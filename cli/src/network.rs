@@ -0,0 +1,112 @@
+//! Network passphrases, which transactions are signed for so that they can't
+//! be replayed on another network, and the status of the network.
+use chrono::Utc;
+use clap::ArgMatches;
+use error::{InvalidInputError, Result};
+use stellar_client::endpoint::{fee, ledger, root};
+use stellar_client::network::Network;
+use stellar_client::resources::Percentile;
+use stellar_client::sync::Client;
+
+/// The passphrase to sign transactions with. It's known for the public and
+/// test networks, but has to be given with `--network-passphrase` when
+/// connecting to another host.
+pub fn passphrase<'a>(client: &Client, matches: &'a ArgMatches) -> Result<&'a str> {
+    if let Some(passphrase) = matches.value_of("network-passphrase") {
+        Ok(passphrase)
+    } else if client.is_horizon() {
+        Ok(Network::PUBLIC)
+    } else if client.is_horizon_test() {
+        Ok(Network::TESTNET)
+    } else {
+        Err(InvalidInputError::from_str(
+            "The network passphrase is needed to sign for a custom host, set --network-passphrase",
+        )
+        .into())
+    }
+}
+
+/// Prints what horizon says about itself, the latest ledger and the fees
+/// transactions are paying, to see at a glance whether the network and the
+/// server are healthy.
+pub fn status(client: &Client) -> Result<()> {
+    let root = client.request(root::Details)?;
+    let ledgers = client.request(ledger::All::latest())?;
+    let stats = client.request(fee::Stats)?;
+
+    println!("Network:          {}", root.network_passphrase());
+    println!("Horizon version:  {}", root.horizon_version());
+    println!("Core version:     {}", root.core_version());
+    println!(
+        "Protocol version: {} (core supports {})",
+        root.current_protocol_version(),
+        root.core_supported_protocol_version()
+    );
+    if let Some(latest) = ledgers.records().first() {
+        let age = Utc::now().signed_duration_since(latest.closed_at());
+        println!(
+            "Latest ledger:    {}, closed {} ago",
+            latest.sequence(),
+            describe_age(age.num_seconds())
+        );
+    }
+    println!(
+        "Horizon lag:      {} ledgers behind core ({} ingested)",
+        root.ledger_lag(),
+        root.history_latest_ledger()
+    );
+    println!(
+        "History:          ledgers {} to {}",
+        root.history_elder_ledger(),
+        root.history_latest_ledger()
+    );
+    println!();
+    let charged = stats.fee_charged();
+    println!(
+        "Fees charged in stroops per operation, over the ledgers up to {}:",
+        stats.last_ledger()
+    );
+    println!("  Base fee: {}", stats.last_ledger_base_fee());
+    println!("  Mode:     {}", charged.mode());
+    for &(name, percentile) in &[
+        ("p10", Percentile::P10),
+        ("p50", Percentile::P50),
+        ("p90", Percentile::P90),
+        ("p99", Percentile::P99),
+    ] {
+        println!("  {}:      {}", name, charged.percentile(percentile));
+    }
+    println!(
+        "  Capacity: {:.0}% of recent ledgers used",
+        stats.ledger_capacity_usage() * 100.0
+    );
+    Ok(())
+}
+
+/// A number of seconds as the largest two units, such as `1h 5m`.
+fn describe_age(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    if seconds < 60 {
+        format!("{}s", seconds)
+    } else if seconds < 3_600 {
+        format!("{}m {}s", seconds / 60, seconds % 60)
+    } else if seconds < 86_400 {
+        format!("{}h {}m", seconds / 3_600, seconds % 3_600 / 60)
+    } else {
+        format!("{}d {}h", seconds / 86_400, seconds % 86_400 / 3_600)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_describes_ages() {
+        assert_eq!(describe_age(-2), "0s");
+        assert_eq!(describe_age(5), "5s");
+        assert_eq!(describe_age(125), "2m 5s");
+        assert_eq!(describe_age(3_900), "1h 5m");
+        assert_eq!(describe_age(90_000), "1d 1h");
+    }
+}
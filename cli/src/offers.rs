@@ -0,0 +1,176 @@
+//! Managing an account's offers on the decentralized exchange, signing with a
+//! key from the keystore.
+use super::{asset_identifier, cursor, keys, network, ordering, pager::Pager, transactions};
+use clap::ArgMatches;
+use error::{InvalidInputError, Result};
+use fmt::{Formatter, Table};
+use stellar_client::endpoint::account;
+use stellar_client::keypair::Keypair;
+use stellar_client::resources::{Amount, AssetIdentifier, Offer, PriceRatio};
+use stellar_client::sync::{self, Client};
+use stellar_client::transaction::{Operation, TransactionBuilder};
+
+pub fn list(client: &Client, matches: &ArgMatches) -> Result<()> {
+    let id = match matches.value_of("key") {
+        Some(name) => keys::keystore()?.get(name)?.account_id().to_string(),
+        None => matches
+            .value_of("ACCOUNT")
+            .expect("ACCOUNT or --key is required")
+            .to_string(),
+    };
+    let pager = Pager::from_arg(&matches);
+    let endpoint = account::Offers::new(&id);
+    let endpoint = pager.assign(endpoint);
+    let endpoint = cursor::assign_from_arg(matches, endpoint);
+    let endpoint = ordering::assign_from_arg(matches, endpoint);
+    let iter = sync::Iter::new(&client, endpoint);
+
+    let mut res = Ok(());
+    let mut fmt = Formatter::start_stdout(Table::from_arg(matches)?);
+    pager.paginate(iter, |result| match result {
+        Ok(offer) => fmt.render(&offer),
+        Err(err) => res = Err(err.into()),
+    });
+    let _ = fmt.stop();
+    res
+}
+
+pub fn create(client: &Client, matches: &ArgMatches) -> Result<()> {
+    let selling = asset(matches, "selling")?;
+    let buying = asset(matches, "buying")?;
+    if selling == buying {
+        return Err(
+            InvalidInputError::from_str("An offer can't sell and buy the same asset").into(),
+        );
+    }
+    let operation = manage_offer(matches, selling, buying)?;
+    let network_passphrase = network::passphrase(client, matches)?;
+    let keypair = unlock(matches)?;
+    submit(client, matches, network_passphrase, &keypair, operation)
+}
+
+pub fn update(client: &Client, matches: &ArgMatches) -> Result<()> {
+    let network_passphrase = network::passphrase(client, matches)?;
+    let keypair = unlock(matches)?;
+    let offer = find_offer(client, matches, &keypair)?;
+    let operation = manage_offer(matches, offer.selling().clone(), offer.buying().clone())?;
+    submit(
+        client,
+        matches,
+        network_passphrase,
+        &keypair,
+        operation.with_offer_id(offer.id()),
+    )
+}
+
+pub fn cancel(client: &Client, matches: &ArgMatches) -> Result<()> {
+    let network_passphrase = network::passphrase(client, matches)?;
+    let keypair = unlock(matches)?;
+    let offer = find_offer(client, matches, &keypair)?;
+    // An offer is removed by updating it to sell nothing. The price still
+    // has to be valid, so the offer's own price is reused.
+    let (numerator, denominator) = offer.price_ratio();
+    let operation = Operation::manage_sell_offer(
+        offer.selling().clone(),
+        offer.buying().clone(),
+        Amount::new(0),
+        PriceRatio::new(numerator, denominator),
+    );
+    submit(
+        client,
+        matches,
+        network_passphrase,
+        &keypair,
+        operation.with_offer_id(offer.id()),
+    )
+}
+
+/// Builds a manage sell offer, or a manage buy offer with `--buy`, from the
+/// amount and price arguments.
+fn manage_offer(
+    matches: &ArgMatches,
+    selling: AssetIdentifier,
+    buying: AssetIdentifier,
+) -> Result<Operation> {
+    let amount = matches.value_of("amount").expect("Amount is required");
+    let amount: Amount = amount
+        .parse()
+        .map_err(|err| InvalidInputError::from(format!("Invalid amount {:?}: {}", amount, err)))?;
+    if amount == Amount::new(0) {
+        return Err(InvalidInputError::from_str(
+            "The amount must be above zero, use cancel to remove an offer",
+        )
+        .into());
+    }
+    let price = parse_price(matches.value_of("price").expect("Price is required"))?;
+    if matches.is_present("buy") {
+        Ok(Operation::manage_buy_offer(selling, buying, amount, price))
+    } else {
+        Ok(Operation::manage_sell_offer(selling, buying, amount, price))
+    }
+}
+
+fn parse_price(price: &str) -> Result<PriceRatio> {
+    Ok(price
+        .parse()
+        .map_err(|err| InvalidInputError::from(format!("Invalid price {:?}: {}", price, err)))?)
+}
+
+fn asset(matches: &ArgMatches, name: &str) -> Result<AssetIdentifier> {
+    let value = matches
+        .value_of(name)
+        .expect("Selling and buying assets are required");
+    Ok(asset_identifier::from_str(value)?)
+}
+
+fn unlock(matches: &ArgMatches) -> Result<Keypair> {
+    keys::unlock(matches.value_of("key").expect("Key is required"))
+}
+
+/// Looks through the account's open offers for the one being changed, which
+/// also checks that the key owns it.
+fn find_offer(client: &Client, matches: &ArgMatches, keypair: &Keypair) -> Result<Offer> {
+    let offer_id: u64 = matches
+        .value_of("OFFER_ID")
+        .expect("OFFER_ID is required")
+        .parse()?;
+    let account_id = keypair.account_id();
+    for offer in sync::Iter::new(&client, account::Offers::new(&account_id)) {
+        let offer = offer?;
+        if offer.id() == offer_id {
+            return Ok(offer);
+        }
+    }
+    Err(format!("{} has no open offer {}", account_id, offer_id).into())
+}
+
+fn submit(
+    client: &Client,
+    matches: &ArgMatches,
+    network_passphrase: &str,
+    keypair: &Keypair,
+    operation: Operation,
+) -> Result<()> {
+    let account = client.request(account::Details::new(keypair.account_id()))?;
+    let envelope = TransactionBuilder::from_account(&account, network_passphrase)
+        .with_operation(operation)
+        .build()?;
+    if let Some(submitted) = transactions::submit(client, matches, envelope, keypair)? {
+        println!("Submitted in ledger {}", submitted.ledger());
+        println!("Hash: {}", submitted.hash());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_prices_as_ratios_or_decimals() {
+        assert_eq!(parse_price("3/2").unwrap(), PriceRatio::new(3, 2));
+        assert_eq!(parse_price("0.25").unwrap(), PriceRatio::new(1, 4));
+        assert!(parse_price("0").is_err());
+        assert!(parse_price("one").is_err());
+    }
+}
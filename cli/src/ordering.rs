@@ -1,9 +1,7 @@
 use clap::{App, Arg, ArgMatches};
-use stellar_client::endpoint::{Direction::{Asc, Desc}, Order};
+use stellar_client::endpoint::{Direction, Direction::{Asc, Desc}, Order};
 
 static ARG_NAME: &'static str = "order";
-static ASC: &'static str = "asc";
-static DESC: &'static str = "desc";
 
 /// Appends the order arg to the app and returns a newly owned app.
 pub fn add<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
@@ -12,8 +10,8 @@ pub fn add<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
             .long("order")
             .short("o")
             .takes_value(true)
-            .default_value(DESC)
-            .possible_values(&[ASC, DESC])
+            .default_value(Desc.as_str())
+            .possible_values(&[Asc.as_str(), Desc.as_str()])
             .help("Specify the order to return the results"),
     )
 }
@@ -23,17 +21,16 @@ pub fn assign_from_arg<T>(arg: &ArgMatches, order: T) -> T
 where
     T: Order,
 {
-    order.with_order(match arg.value_of(ARG_NAME) {
-        Some(s) if s == ASC => Asc,
-        Some(s) if s == DESC => Desc,
-        _ => Desc,
-    })
+    order.with_order(
+        arg.value_of(ARG_NAME)
+            .and_then(|s| s.parse::<Direction>().ok())
+            .unwrap_or(Desc),
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use stellar_client::endpoint::Direction;
 
     struct Foo {
         order: Option<Direction>,
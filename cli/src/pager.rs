@@ -1,6 +1,6 @@
 use clap::{App, Arg, ArgMatches};
 use std::str::FromStr;
-use stellar_client::endpoint::Limit;
+use stellar_client::endpoint::{Limit, MAX_LIMIT};
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct Pager {
@@ -77,7 +77,7 @@ impl Pager {
     }
 
     fn horizon_page_limit(&self) -> u32 {
-        const MAX: usize = 200;
+        const MAX: usize = MAX_LIMIT as usize;
         match self.size {
             PageSize::All => MAX as u32,
             PageSize::Size(size) => {
@@ -0,0 +1,78 @@
+//! Reading passphrases and other secrets from the terminal without echoing
+//! them, so they never end up in shell history.
+use error::{InvalidInputError, Result};
+use std::env;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+/// When set, its value is used instead of prompting, such as when scripting.
+const PASSPHRASE_VAR: &str = "STELLAR_PASSPHRASE";
+
+/// Asks for the passphrase that unlocks an existing key file.
+pub fn prompt(message: &str) -> Result<String> {
+    if let Ok(passphrase) = env::var(PASSPHRASE_VAR) {
+        return Ok(passphrase);
+    }
+    read_hidden(message)
+}
+
+/// Asks for a passphrase for a new key file, twice to catch typos.
+pub fn prompt_new() -> Result<String> {
+    if let Ok(passphrase) = env::var(PASSPHRASE_VAR) {
+        return confirm(passphrase.clone(), passphrase);
+    }
+    let passphrase = read_hidden("New passphrase: ")?;
+    let repeated = read_hidden("Repeat passphrase: ")?;
+    confirm(passphrase, repeated)
+}
+
+fn confirm(passphrase: String, repeated: String) -> Result<String> {
+    if passphrase.is_empty() {
+        return Err(InvalidInputError::from_str("The passphrase can't be empty").into());
+    }
+    if passphrase != repeated {
+        return Err(InvalidInputError::from_str("The passphrases don't match").into());
+    }
+    Ok(passphrase)
+}
+
+/// Reads a line from the terminal without echoing it, such as a secret seed.
+pub fn read_hidden(message: &str) -> Result<String> {
+    eprint!("{}", message);
+    io::stderr().flush()?;
+    let hidden = set_echo(false);
+    let mut line = String::new();
+    let read = io::stdin().read_line(&mut line);
+    if hidden {
+        set_echo(true);
+        eprintln!();
+    }
+    read?;
+    Ok(line.trim_right_matches(|c| c == '\n' || c == '\r').to_string())
+}
+
+/// Turns terminal echo on or off with `stty`. Returns false when stdin isn't
+/// a terminal, in which case there is nothing to hide.
+fn set_echo(on: bool) -> bool {
+    Command::new("stty")
+        .arg(if on { "echo" } else { "-echo" })
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_requires_matching_passphrases() {
+        assert_eq!(
+            confirm("hunter2".to_string(), "hunter2".to_string()).unwrap(),
+            "hunter2"
+        );
+        assert!(confirm("hunter2".to_string(), "hunter3".to_string()).is_err());
+        assert!(confirm(String::new(), String::new()).is_err());
+    }
+}
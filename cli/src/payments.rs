@@ -1,7 +1,7 @@
 use super::{cursor, ordering, pager::Pager};
 use clap::ArgMatches;
 use error::Result;
-use fmt::{Formatter, Simple};
+use fmt::{Formatter, Table};
 use stellar_client::{endpoint::payment, sync::{self, Client}};
 
 pub fn all(client: &Client, matches: &ArgMatches) -> Result<()> {
@@ -15,7 +15,7 @@ pub fn all(client: &Client, matches: &ArgMatches) -> Result<()> {
     let iter = sync::Iter::new(&client, endpoint);
 
     let mut res = Ok(());
-    let mut fmt = Formatter::start_stdout(Simple::new());
+    let mut fmt = Formatter::start_stdout(Table::from_arg(matches)?);
     pager.paginate(iter, |result| match result {
         Ok(operation) => fmt.render(&operation),
         Err(err) => res = Err(err.into()),
@@ -0,0 +1,233 @@
+//! Liquidity pools: listing them, showing a pool's reserves and share price,
+//! and depositing into or withdrawing from a pool with a key from the
+//! keystore.
+use super::{asset_identifier, cursor, keys, network, ordering, pager::Pager, transactions};
+use clap::ArgMatches;
+use error::{InvalidInputError, Result};
+use fmt::{Formatter, Render, Simple, Table};
+use stellar_client::endpoint::{account, liquidity_pool};
+use stellar_client::keypair::Keypair;
+use stellar_client::resources::{Account, Amount, LiquidityPool};
+use stellar_client::sync::{self, Client};
+use stellar_client::transaction::{Operation, TransactionBuilder};
+
+pub fn list(client: &Client, matches: &ArgMatches) -> Result<()> {
+    let pager = Pager::from_arg(matches);
+    let mut endpoint = liquidity_pool::All::default();
+    for reserve in matches.values_of("reserve").into_iter().flatten() {
+        endpoint = endpoint.with_reserve(asset_identifier::from_str(reserve)?);
+    }
+    if let Some(account_id) = matches.value_of("account") {
+        endpoint = endpoint.with_account(account_id);
+    }
+    let endpoint = pager.assign(endpoint);
+    let endpoint = cursor::assign_from_arg(matches, endpoint);
+    let endpoint = ordering::assign_from_arg(matches, endpoint);
+    let iter = sync::Iter::new(client, endpoint);
+
+    let mut res = Ok(());
+    let mut fmt = Formatter::start_stdout(Table::from_arg(matches)?);
+    pager.paginate(iter, |result| match result {
+        Ok(pool) => fmt.render(&pool),
+        Err(err) => res = Err(err.into()),
+    });
+    let _ = fmt.stop();
+    res
+}
+
+pub fn show(client: &Client, matches: &ArgMatches) -> Result<()> {
+    let pool = fetch_pool(client, matches)?;
+    Formatter::start_stdout(Simple::new()).render(&pool);
+
+    let account_id = match matches.value_of("key") {
+        Some(name) => Some(keys::keystore()?.get(name)?.account_id().to_string()),
+        None => matches.value_of("account").map(str::to_string),
+    };
+    if let Some(account_id) = account_id {
+        let account = client.request(account::Details::new(&account_id))?;
+        match shares(&account, &pool) {
+            Some(shares) => {
+                println!("Shares of {}: {}", account_id, shares);
+                let simple = Simple::new();
+                for reserve in pool.shares_value(shares) {
+                    println!(
+                        "    {} {}",
+                        reserve.amount(),
+                        simple.render(reserve.asset()).unwrap()
+                    );
+                }
+            }
+            None => println!("{} doesn't trust the pool's shares", account_id),
+        }
+    }
+    Ok(())
+}
+
+pub fn deposit(client: &Client, matches: &ArgMatches) -> Result<()> {
+    let network_passphrase = network::passphrase(client, matches)?;
+    let max_a = amount(matches, "max-a")?;
+    let max_b = amount(matches, "max-b")?;
+    let slippage = slippage(matches)?;
+    let pool = fetch_pool(client, matches)?;
+    let (min_price, max_price) = pool.deposit_price_bounds(slippage).ok_or_else(|| {
+        InvalidInputError::from(format!(
+            "{} has no reserves yet, so there's no price to deposit at",
+            pool.id()
+        ))
+    })?;
+
+    let keypair = unlock(matches)?;
+    let account = client.request(account::Details::new(keypair.account_id()))?;
+    let mut operations = Vec::new();
+    // Shares can only be received on a trust line to them, which is added
+    // first if the account doesn't have one yet.
+    if shares(&account, &pool).is_none() {
+        let reserves = pool.reserves();
+        operations.push(Operation::change_trust_pool_share(
+            reserves[0].asset().clone(),
+            reserves[1].asset().clone(),
+            pool.fee_bp(),
+        ));
+    }
+    operations.push(Operation::liquidity_pool_deposit(
+        pool.id(),
+        max_a,
+        max_b,
+        min_price,
+        max_price,
+    ));
+    submit(
+        client,
+        matches,
+        network_passphrase,
+        &keypair,
+        &account,
+        operations,
+    )
+}
+
+pub fn withdraw(client: &Client, matches: &ArgMatches) -> Result<()> {
+    let network_passphrase = network::passphrase(client, matches)?;
+    let amount = amount(matches, "shares")?;
+    let slippage = slippage(matches)?;
+    let pool = fetch_pool(client, matches)?;
+
+    let keypair = unlock(matches)?;
+    let account = client.request(account::Details::new(keypair.account_id()))?;
+    match shares(&account, &pool) {
+        Some(held) if held >= amount => {}
+        Some(held) => {
+            return Err(format!("{} only holds {} shares", keypair.account_id(), held).into())
+        }
+        None => {
+            return Err(format!("{} holds no shares of {}", keypair.account_id(), pool.id()).into())
+        }
+    }
+    let minimums = pool.withdrawal_minimums(amount, slippage);
+    let operation = Operation::liquidity_pool_withdraw(
+        pool.id(),
+        amount,
+        minimums[0].amount(),
+        minimums[1].amount(),
+    );
+    submit(
+        client,
+        matches,
+        network_passphrase,
+        &keypair,
+        &account,
+        vec![operation],
+    )
+}
+
+fn fetch_pool(client: &Client, matches: &ArgMatches) -> Result<LiquidityPool> {
+    let id = matches.value_of("ID").expect("ID is required");
+    let pool = client.request(liquidity_pool::Details::new(id))?;
+    if pool.reserves().len() != 2 {
+        return Err(format!("{} isn't a pool of two assets", id).into());
+    }
+    Ok(pool)
+}
+
+/// The account's balance of the pool's shares, if it trusts them.
+fn shares(account: &Account, pool: &LiquidityPool) -> Option<Amount> {
+    account
+        .balances()
+        .iter()
+        .find(|balance| balance.liquidity_pool_id() == Some(pool.id()))
+        .map(|balance| balance.balance())
+}
+
+fn amount(matches: &ArgMatches, name: &str) -> Result<Amount> {
+    let amount = matches.value_of(name).expect("Amounts are required");
+    let amount: Amount = amount
+        .parse()
+        .map_err(|err| InvalidInputError::from(format!("Invalid amount {:?}: {}", amount, err)))?;
+    if amount == Amount::new(0) {
+        return Err(InvalidInputError::from(format!("--{} must be above zero", name)).into());
+    }
+    Ok(amount)
+}
+
+/// How far the pool's price may move before the transaction lands, in basis
+/// points.
+fn slippage(matches: &ArgMatches) -> Result<u32> {
+    let value = matches.value_of("slippage").unwrap_or("100");
+    match value.parse::<u32>() {
+        Ok(slippage) if slippage < 10_000 => Ok(slippage),
+        _ => Err(InvalidInputError::from(format!(
+            "Invalid slippage {:?}, give it in basis points below 10000, such as 100 for 1%",
+            value
+        ))
+        .into()),
+    }
+}
+
+fn unlock(matches: &ArgMatches) -> Result<Keypair> {
+    keys::unlock(matches.value_of("key").expect("Key is required"))
+}
+
+fn submit(
+    client: &Client,
+    matches: &ArgMatches,
+    network_passphrase: &str,
+    keypair: &Keypair,
+    account: &Account,
+    operations: Vec<Operation>,
+) -> Result<()> {
+    let builder = operations.into_iter().fold(
+        TransactionBuilder::from_account(account, network_passphrase),
+        |builder, operation| builder.with_operation(operation),
+    );
+    let envelope = builder.build()?;
+    if let Some(submitted) = transactions::submit(client, matches, envelope, keypair)? {
+        println!("Submitted in ledger {}", submitted.ledger());
+        println!("Hash: {}", submitted.hash());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stellar_client::resources::operation::Reserve;
+    use stellar_client::resources::{AssetIdentifier, Balance};
+
+    const ACCOUNT: &str = "GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ6";
+
+    #[test]
+    fn it_finds_the_accounts_shares() {
+        let pool = LiquidityPool::new(
+            "abcdef",
+            Reserve::new(AssetIdentifier::native(), Amount::new(10)),
+            Reserve::new(AssetIdentifier::alphanum4("USD", ACCOUNT), Amount::new(40)),
+            Amount::new(20),
+        );
+        let account = Account::new(ACCOUNT, 1)
+            .with_balance(Balance::new(AssetIdentifier::native(), Amount::new(100)));
+        assert_eq!(shares(&account, &pool), None);
+        let account =
+            account.with_balance(Balance::liquidity_pool_shares("abcdef", Amount::new(5)));
+        assert_eq!(shares(&account, &pool), Some(Amount::new(5)));
+    }
+}
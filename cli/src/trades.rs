@@ -3,7 +3,7 @@ use asset_identifier;
 use chrono::{DateTime, Utc};
 use clap::ArgMatches;
 use error::Result;
-use fmt::{Formatter, Simple};
+use fmt::{Formatter, Table};
 use resolution::Resolution;
 use stellar_client::{endpoint::trade, sync::{self, Client}};
 
@@ -15,8 +15,8 @@ pub fn all(client: &Client, matches: &ArgMatches) -> Result<()> {
 
         if let Some(offer_id) = matches.value_of("offer_id") {
             let offer_id = offer_id
-                .parse::<u32>()
-                .map_err(|_| String::from("Offer Id should be a valid u32 integer"))?;
+                .parse::<u64>()
+                .map_err(|_| String::from("Offer Id should be a valid u64 integer"))?;
             endpoint = endpoint.with_offer_id(offer_id);
         };
         if let (Some(base_str), Some(counter_str)) =
@@ -34,26 +34,12 @@ pub fn all(client: &Client, matches: &ArgMatches) -> Result<()> {
     let iter = sync::Iter::new(&client, endpoint);
 
     let mut res = Ok(());
+    let mut fmt = Formatter::start_stdout(Table::from_arg(matches)?);
     pager.paginate(iter, |result| match result {
-        Ok(trade) => {
-            println!("id:                   {}", trade.id());
-            println!("offer_id:             {}", trade.offer_id());
-            println!("paging_token:         {}", trade.paging_token());
-            println!("ledger_close_time:    {}", trade.closed_at());
-            println!("base_account:         {}", trade.base_account());
-            println!("base_amount:          {}", trade.base_amount());
-            println!("base_asset_code:      {}", trade.base_asset().code());
-            println!("base_asset_issuer:    {}", trade.base_asset().issuer());
-            println!("counter_amount:       {}", trade.counter_amount());
-            println!("counter_account:      {}", trade.counter_account());
-            println!("counter_asset_code:   {}", trade.counter_asset().code());
-            println!("counter_asset_issuer: {}", trade.counter_asset().issuer());
-            println!("price:                {}", trade.price());
-            println!("seller:               {}", trade.selling_account());
-            println!();
-        }
+        Ok(trade) => fmt.render(&trade),
         Err(err) => res = Err(err.into()),
     });
+    let _ = fmt.stop();
     res
 }
 
@@ -101,7 +87,7 @@ pub fn aggregations(client: &Client, matches: &ArgMatches) -> Result<()> {
     let iter = sync::Iter::new(&client, endpoint);
 
     let mut res = Ok(());
-    let mut fmt = Formatter::start_stdout(Simple::new());
+    let mut fmt = Formatter::start_stdout(Table::from_arg(matches)?);
     pager.paginate(iter, |result| match result {
         Ok(aggregation) => fmt.render(&aggregation),
         Err(err) => res = Err(err.into()),
@@ -1,8 +1,20 @@
-use super::{cursor, ordering, pager::Pager};
+use super::{cursor, network, ordering, pager::Pager};
+use chrono::{TimeZone, Utc};
 use clap::ArgMatches;
 use error::Result;
-use fmt::{Formatter, Simple};
-use stellar_client::{endpoint::transaction, sync::{self, Client}};
+use fmt::{Formatter, Simple, Table};
+use std::fmt::Write;
+use std::thread;
+use std::time::{Duration, Instant};
+use stellar_client::error::Error;
+use stellar_client::keypair::{Keypair, PublicKey};
+use stellar_client::resources::{Submission, Transaction};
+use stellar_client::transaction::TransactionEnvelope;
+use stellar_client::xdr::{self, Kind, Node};
+use stellar_client::{
+    endpoint::transaction,
+    sync::{self, Client},
+};
 
 pub fn all(client: &Client, matches: &ArgMatches) -> Result<()> {
     let pager = Pager::from_arg(&matches);
@@ -15,7 +27,7 @@ pub fn all(client: &Client, matches: &ArgMatches) -> Result<()> {
     let iter = sync::Iter::new(&client, endpoint);
 
     let mut res = Ok(());
-    let mut fmt = Formatter::start_stdout(Simple::new());
+    let mut fmt = Formatter::start_stdout(Table::from_arg(matches)?);
     pager.paginate(iter, |result| match result {
         Ok(txn) => fmt.render(&txn),
         Err(err) => res = Err(err.into()),
@@ -38,7 +50,7 @@ pub fn payments(client: &Client, matches: &ArgMatches) -> Result<()> {
     let iter = sync::Iter::new(&client, endpoint);
 
     let mut res = Ok(());
-    let mut fmt = Formatter::start_stdout(Simple::new());
+    let mut fmt = Formatter::start_stdout(Table::from_arg(matches)?);
     pager.paginate(iter, |result| match result {
         Ok(operation) => fmt.render(&operation),
         Err(err) => res = Err(err.into()),
@@ -50,13 +62,138 @@ pub fn details(client: &Client, matches: &ArgMatches) -> Result<()> {
     let hash = matches
         .value_of("HASH")
         .expect("Transaction identifier hash is required");
-    let endpoint = transaction::Details::new(&hash);
+    let endpoint = transaction::Details::new(hash);
     let transaction = client.request(endpoint)?;
     let mut fmt = Formatter::start_stdout(Simple::new());
     fmt.render(&transaction);
     Ok(())
 }
 
+/// Prints whether a transaction succeeded, its result codes, the fee it was
+/// charged and its operations. With `--watch` horizon is polled until the
+/// transaction appears, such as just after an asynchronous submission.
+pub fn status(client: &Client, matches: &ArgMatches) -> Result<()> {
+    let hash = matches
+        .value_of("HASH")
+        .expect("Transaction identifier hash is required");
+    let watch = matches.is_present("watch");
+    let interval = seconds_arg(matches, "interval")?.unwrap_or(2);
+    let timeout = seconds_arg(matches, "timeout")?;
+
+    let started = Instant::now();
+    let mut waiting = false;
+    let transaction = loop {
+        match client.request(transaction::Details::new(hash)) {
+            Ok(transaction) => break transaction,
+            Err(Error::BadResponse(ref err)) if watch && err.is_not_found() => {}
+            Err(err) => return Err(err.into()),
+        }
+        if let Some(timeout) = timeout {
+            if started.elapsed() >= Duration::from_secs(timeout) {
+                return Err(format!(
+                    "Transaction {} did not appear within {} seconds",
+                    hash, timeout
+                ).into());
+            }
+        }
+        if !waiting {
+            eprintln!("Waiting for transaction {}...", hash);
+            waiting = true;
+        }
+        thread::sleep(Duration::from_secs(interval));
+    };
+    print!("{}", describe_status(&transaction));
+    Ok(())
+}
+
+fn seconds_arg(matches: &ArgMatches, name: &str) -> Result<Option<u64>> {
+    match matches.value_of(name) {
+        Some(value) => match value.parse::<u64>() {
+            Ok(seconds) => Ok(Some(seconds)),
+            Err(_) => Err(format!("--{} is a number of seconds, not {:?}", name, value).into()),
+        },
+        None => Ok(None),
+    }
+}
+
+fn describe_status(transaction: &Transaction) -> String {
+    let mut out = String::new();
+    let result = xdr::decode_as(Kind::TransactionResult, transaction.result_xdr()).ok();
+    let outcome = result.as_ref().and_then(|result| result.root().get("result"));
+    // A fee bump reports the outcome of its inner transaction one level down.
+    let inner = outcome
+        .and_then(|outcome| outcome.get("result"))
+        .and_then(|inner| inner.get("result"));
+    let code = outcome.and_then(Node::arm);
+    let status = match code {
+        Some("txSUCCESS") | Some("txFEE_BUMP_INNER_SUCCESS") => "success",
+        Some(_) => "failed",
+        None => "unknown",
+    };
+
+    let _ = writeln!(out, "Hash:        {}", transaction.hash());
+    let _ = writeln!(out, "Status:      {}", status);
+    let _ = match (code, inner.and_then(Node::arm)) {
+        (Some(code), Some(inner)) => writeln!(out, "Result:      {} ({})", code, inner),
+        (Some(code), None) => writeln!(out, "Result:      {}", code),
+        (None, _) => writeln!(out, "Result:      -"),
+    };
+    let _ = writeln!(
+        out,
+        "Ledger:      {} at {}",
+        transaction.ledger(),
+        transaction.created_at().to_rfc3339()
+    );
+    let _ = match transaction.max_fee() {
+        Some(max_fee) => writeln!(
+            out,
+            "Fee charged: {} stroops of {} offered",
+            transaction.fee_charged(),
+            max_fee
+        ),
+        None => writeln!(out, "Fee charged: {} stroops", transaction.fee_charged()),
+    };
+
+    let envelope = xdr::decode_as(Kind::TransactionEnvelope, transaction.envelope_xdr()).ok();
+    let mut tx = envelope.as_ref().map(|envelope| envelope.root());
+    if tx.and_then(Node::arm) == Some("ENVELOPE_TYPE_TX_FEE_BUMP") {
+        tx = tx.and_then(|tx| tx.get("tx")).and_then(|tx| tx.get("innerTx"));
+    }
+    let operations = tx
+        .and_then(|tx| tx.get("tx"))
+        .and_then(|tx| tx.get("operations"))
+        .and_then(Node::elements)
+        .unwrap_or(&[]);
+    let results = match inner.or(outcome) {
+        Some(&Node::Union(_, ref results)) => results.elements().unwrap_or(&[]),
+        _ => &[],
+    };
+    let _ = writeln!(out, "Operations:  {}", transaction.operation_count());
+    for (index, operation) in operations.iter().enumerate() {
+        let name = operation
+            .get("body")
+            .and_then(Node::arm)
+            .unwrap_or("UNKNOWN");
+        let _ = match results.get(index).and_then(innermost_arm) {
+            Some(code) => writeln!(out, "  {}. {} {}", index + 1, name, code),
+            None => writeln!(out, "  {}. {}", index + 1, name),
+        };
+    }
+    out
+}
+
+/// The most specific code of an operation result, such as `PAYMENT_SUCCESS`
+/// rather than `opINNER`.
+fn innermost_arm(mut node: &Node) -> Option<&str> {
+    while let Node::Union(_, ref inner) = *node {
+        if inner.arm().is_none() {
+            break;
+        }
+        node = inner;
+    }
+    node.arm()
+}
+
 pub fn operations(client: &Client, matches: &ArgMatches) -> Result<()> {
     let pager = Pager::from_arg(&matches);
     let hash = matches
@@ -71,7 +208,7 @@ pub fn operations(client: &Client, matches: &ArgMatches) -> Result<()> {
     let iter = sync::Iter::new(&client, endpoint);
 
     let mut res = Ok(());
-    let mut fmt = Formatter::start_stdout(Simple::new());
+    let mut fmt = Formatter::start_stdout(Table::from_arg(matches)?);
     pager.paginate(iter, |result| match result {
         Ok(operation) => fmt.render(&operation),
         Err(err) => res = Err(err.into()),
@@ -93,7 +230,7 @@ pub fn effects(client: &Client, matches: &ArgMatches) -> Result<()> {
     let iter = sync::Iter::new(&client, endpoint);
 
     let mut res = Ok(());
-    let mut fmt = Formatter::start_stdout(Simple::new());
+    let mut fmt = Formatter::start_stdout(Table::from_arg(matches)?);
     pager.paginate(iter, |result| match result {
         Ok(txn) => fmt.render(&txn),
         Err(err) => res = Err(err.into()),
@@ -101,3 +238,299 @@ pub fn effects(client: &Client, matches: &ArgMatches) -> Result<()> {
     let _ = fmt.stop();
     res
 }
+
+/// Prints what a transaction envelope does, so it can be checked before it
+/// is signed. Nothing is fetched from the network.
+pub fn decode(client: &Client, matches: &ArgMatches) -> Result<()> {
+    let blob = matches.value_of("XDR").expect("XDR is required");
+    let decoded = xdr::decode_as(Kind::TransactionEnvelope, blob)?;
+    // The hash depends on the network, so signatures can only be checked
+    // when the network passphrase is known. Fee bump envelopes aren't hashed.
+    let hash = network::passphrase(client, matches)
+        .ok()
+        .and_then(|passphrase| TransactionEnvelope::from_base64(blob, passphrase).ok())
+        .map(|envelope| envelope.hash());
+    print!("{}", summarize(decoded.root(), hash));
+    Ok(())
+}
+
+/// Signs an envelope built by a command and submits it. With `--dry-run` it
+/// is printed instead, along with what it does, and is left unsigned if
+/// `--unsigned` is also given. Returns `None` for a dry run.
+pub fn submit(
+    client: &Client,
+    matches: &ArgMatches,
+    mut envelope: TransactionEnvelope,
+    keypair: &Keypair,
+) -> Result<Option<Submission>> {
+    let dry_run = matches.is_present("dry-run");
+    if !(dry_run && matches.is_present("unsigned")) {
+        envelope.sign(keypair)?;
+    }
+    if !dry_run {
+        return Ok(Some(
+            client.request(transaction::Submit::from_envelope(&envelope)?)?,
+        ));
+    }
+    // The summary goes to stderr so the envelope alone can be piped on.
+    let blob = envelope.to_base64()?;
+    let decoded = xdr::decode_as(Kind::TransactionEnvelope, &blob)?;
+    eprint!("{}", summarize(decoded.root(), Some(envelope.hash())));
+    println!("{}", blob);
+    Ok(None)
+}
+
+fn summarize(envelope: &Node, hash: Option<[u8; 32]>) -> String {
+    let mut out = String::new();
+    let mut accounts = Vec::new();
+    collect_accounts(envelope, &mut accounts);
+
+    if let Some(hash) = hash {
+        let _ = writeln!(out, "Hash:        {}", xdr::hex(&hash));
+    }
+    let mut envelope = envelope;
+    if envelope.arm() == Some("ENVELOPE_TYPE_TX_FEE_BUMP") {
+        let tx = envelope.get("tx");
+        let _ = writeln!(out, "Fee bump by: {}", field(tx, "feeSource"));
+        let _ = writeln!(out, "Max fee:     {} stroops", field(tx, "fee"));
+        write_signatures(&mut out, envelope, None, &accounts);
+        let _ = writeln!(out);
+        let _ = writeln!(out, "Inner transaction:");
+        match tx.and_then(|tx| tx.get("innerTx")) {
+            Some(inner) => envelope = inner,
+            None => return out,
+        }
+    }
+    let tx = envelope.get("tx");
+    let source = tx.and_then(|tx| {
+        tx.get("sourceAccount")
+            .or_else(|| tx.get("sourceAccountEd25519"))
+    });
+    let _ = writeln!(out, "Source:      {}", display(source));
+    let _ = writeln!(out, "Fee:         {} stroops", field(tx, "fee"));
+    let _ = writeln!(out, "Sequence:    {}", field(tx, "seqNum"));
+    let time_bounds = tx.and_then(|tx| {
+        tx.get("timeBounds").or_else(|| {
+            tx.get("cond")
+                .and_then(|cond| cond.get("timeBounds").or(Some(cond)))
+        })
+    });
+    let _ = writeln!(out, "Valid:       {}", validity(time_bounds));
+    let _ = writeln!(out, "Memo:        {}", field(tx, "memo"));
+
+    let operations = tx
+        .and_then(|tx| tx.get("operations"))
+        .and_then(Node::elements)
+        .unwrap_or(&[]);
+    let _ = writeln!(out, "Operations:  {}", operations.len());
+    for (index, operation) in operations.iter().enumerate() {
+        let body = operation.get("body");
+        let _ = writeln!(
+            out,
+            "  {}. {}",
+            index + 1,
+            body.and_then(Node::arm).unwrap_or("UNKNOWN")
+        );
+        if let Some(&Node::Optional(Some(ref source))) = operation.get("sourceAccount") {
+            let _ = writeln!(out, "     source: {}", source);
+        }
+        if let Some(&Node::Union(_, ref inner)) = body {
+            for line in inner.to_string().lines() {
+                let _ = writeln!(out, "     {}", line);
+            }
+        }
+    }
+    write_signatures(&mut out, envelope, hash, &accounts);
+    out
+}
+
+/// Lists the signatures, naming the signer when the hint matches an account
+/// mentioned in the transaction, and checking the signature when possible.
+fn write_signatures(
+    out: &mut String,
+    envelope: &Node,
+    hash: Option<[u8; 32]>,
+    accounts: &[String],
+) {
+    let signatures = envelope
+        .get("signatures")
+        .and_then(Node::elements)
+        .unwrap_or(&[]);
+    let _ = writeln!(out, "Signatures:  {}", signatures.len());
+    for signature in signatures {
+        let (hint, bytes) = match (signature.get("hint"), signature.get("signature")) {
+            (Some(&Node::Bytes(ref hint)), Some(&Node::Bytes(ref bytes))) => (hint, bytes),
+            _ => continue,
+        };
+        let signer = accounts.iter().find_map(|account| {
+            PublicKey::from_account_id(account)
+                .ok()
+                .filter(|key| key.signature_hint()[..] == hint[..])
+        });
+        let _ = match signer {
+            Some(key) => {
+                let status = match hash {
+                    Some(ref hash) if key.verify(hash, bytes) => "valid",
+                    Some(_) => "INVALID",
+                    None => "unchecked",
+                };
+                writeln!(
+                    out,
+                    "  {} {} ({})",
+                    xdr::hex(hint),
+                    key.account_id(),
+                    status
+                )
+            }
+            None => writeln!(out, "  {} unknown signer", xdr::hex(hint)),
+        };
+    }
+}
+
+fn validity(time_bounds: Option<&Node>) -> String {
+    let bound = |name| {
+        time_bounds
+            .and_then(|bounds| bounds.get(name))
+            .and_then(Node::as_i64)
+            .unwrap_or(0)
+    };
+    let (min, max) = (bound("minTime"), bound("maxTime"));
+    // The bounds come from whatever envelope was given, so times chrono
+    // can't represent are shown as they are.
+    let time = |seconds| match Utc.timestamp_opt(seconds, 0).single() {
+        Some(time) => time.to_rfc3339(),
+        None => seconds.to_string(),
+    };
+    match (min, max) {
+        (0, 0) => "any time".to_string(),
+        (min, 0) => format!("from {}", time(min)),
+        (0, max) => format!("until {}", time(max)),
+        (min, max) => format!("from {} until {}", time(min), time(max)),
+    }
+}
+
+fn collect_accounts(node: &Node, accounts: &mut Vec<String>) {
+    match *node {
+        Node::Text(ref text) if text.starts_with('G') && !accounts.contains(text) => {
+            accounts.push(text.clone());
+        }
+        Node::Struct(ref fields) => {
+            for &(_, ref node) in fields {
+                collect_accounts(node, accounts);
+            }
+        }
+        Node::Array(ref nodes) => {
+            for node in nodes {
+                collect_accounts(node, accounts);
+            }
+        }
+        Node::Union(_, ref inner) | Node::Optional(Some(ref inner)) => {
+            collect_accounts(inner, accounts)
+        }
+        _ => {}
+    }
+}
+
+fn field(node: Option<&Node>, name: &str) -> String {
+    display(node.and_then(|node| node.get(name)))
+}
+
+fn display(node: Option<&Node>) -> String {
+    node.map(Node::to_string).unwrap_or_else(|| "-".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stellar_client::keypair::Keypair;
+    use stellar_client::network::Network;
+    use stellar_client::resources::{Amount, AssetIdentifier, Memo, Transaction};
+    use stellar_client::transaction::{Operation, TransactionBuilder};
+
+    const SEED: &str = "SBGWSG6BTNCKCOB3DIFBGCVMUPQFYPA2G4O34RMTB343OYPXU5DJDVMN";
+    const DESTINATION: &str = "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3";
+
+    // A payment of 100 XLM, as horizon returns it in `envelope_xdr`.
+    const PAYMENT_ENVELOPE: &str = "AAAAAH2Hmt1JWMfqAdUlDeyUtO9V8zPqJ0aLG8KrZyXv78QGAAAAZAAIgb4AAtRiAAAAAAAAAAEAAAAAAAAAAQAAAAAAAAABAAAAAJZgy/0KAk+3JQwG8hPGBNTZVGew2Joi1TwkVBdwPn9QAAAAAAAAAAA7mUNgAAAAAAAAAAHv78QGAAAAQITCXzWfgHgAjF3djx1VK9JK08UypfpftzFoyNXv7A0Agau/ur/3/+ZZtQb8xSsao8yVAsTiV4ttiT/HqfvvlAk=";
+
+    fn transaction(result_xdr: &str) -> Transaction {
+        Transaction::new("abc123", DESTINATION, 42)
+            .with_ledger(7, Utc.ymd(2018, 1, 2).and_hms(3, 4, 5))
+            .with_fees(100, 200)
+            .with_operation_count(1)
+            .with_xdr(PAYMENT_ENVELOPE, result_xdr, "", "")
+    }
+
+    #[test]
+    fn it_describes_a_successful_transaction() {
+        let status = describe_status(&transaction("AAAAAAAAAGQAAAAAAAAAAQAAAAAAAAABAAAAAAAAAAA="));
+        assert_eq!(
+            status,
+            "Hash:        abc123
+Status:      success
+Result:      txSUCCESS
+Ledger:      7 at 2018-01-02T03:04:05+00:00
+Fee charged: 100 stroops of 200 offered
+Operations:  1
+  1. PAYMENT PAYMENT_SUCCESS
+"
+        );
+    }
+
+    #[test]
+    fn it_describes_a_failed_transaction() {
+        let status = describe_status(&transaction("AAAAAAAAAGT/////AAAAAQAAAAAAAAAB/////gAAAAA="));
+        assert!(status.contains("Status:      failed"));
+        assert!(status.contains("Result:      txFAILED"));
+        assert!(status.contains("  1. PAYMENT PAYMENT_UNDERFUNDED"));
+
+        // Transactions rejected as a whole have no operation results.
+        let status = describe_status(&transaction("AAAAAAAAAGT////7AAAAAA=="));
+        assert!(status.contains("Result:      txBAD_SEQ"));
+        assert!(status.contains("  1. PAYMENT\n"));
+    }
+
+    #[test]
+    fn it_summarizes_an_envelope() {
+        let keypair = Keypair::from_secret_seed(SEED).unwrap();
+        let mut envelope =
+            TransactionBuilder::new(&keypair.account_id(), 41, Network::TESTNET)
+                .with_memo(Memo::Text("rent".to_string()))
+                .with_time_bounds(0, 1_500_000_000)
+                .with_operation(Operation::payment(
+                    DESTINATION,
+                    AssetIdentifier::native(),
+                    Amount::new(10_000_000),
+                ))
+                .build()
+                .unwrap();
+        envelope.sign(&keypair).unwrap();
+        envelope.sign(&Keypair::random()).unwrap();
+
+        let decoded =
+            xdr::decode_as(Kind::TransactionEnvelope, &envelope.to_base64().unwrap()).unwrap();
+        let summary = summarize(decoded.root(), Some(envelope.hash()));
+        assert!(summary.contains(&format!("Source:      {}", keypair.account_id())));
+        assert!(summary.contains("Fee:         100 stroops"));
+        assert!(summary.contains("Sequence:    42"));
+        assert!(summary.contains("Valid:       until 2017-07-14T02:40:00+00:00"));
+        assert!(summary.contains("Memo:        [MEMO_TEXT] rent"));
+        assert!(summary.contains("  1. PAYMENT"));
+        assert!(summary.contains(&format!("destination: {}", DESTINATION)));
+        assert!(summary.contains(&format!("{} (valid)", keypair.account_id())));
+        assert!(summary.contains("unknown signer"));
+    }
+
+    #[test]
+    fn it_shows_time_bounds_out_of_range_as_numbers() {
+        let bounds = Node::Struct(vec![
+            ("minTime", Node::UInt(1_500_000_000)),
+            ("maxTime", Node::UInt(i64::MAX as u64)),
+        ]);
+        assert_eq!(
+            validity(Some(&bounds)),
+            format!("from 2017-07-14T02:40:00+00:00 until {}", i64::MAX)
+        );
+        assert_eq!(validity(None), "any time");
+    }
+}
@@ -0,0 +1,18 @@
+use clap::ArgMatches;
+use error::Result;
+use stellar_client::xdr::{self, Kind};
+
+pub fn decode(matches: &ArgMatches) -> Result<()> {
+    let blob = matches.value_of("XDR").expect("XDR is required");
+    let decoded = match matches.value_of("type") {
+        Some(kind) => {
+            let kind: Kind = kind.parse()?;
+            xdr::decode_as(kind, blob)?
+        }
+        None => xdr::decode(blob)?,
+    };
+
+    print!("{}", decoded);
+
+    Ok(())
+}
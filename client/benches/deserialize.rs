@@ -0,0 +1,121 @@
+//! Benchmarks for deserializing the pages of records ingestion spends most of
+//! its time on. Pages are built from the fixtures, repeated out to the 200
+//! records horizon returns at most, so they parse the same as live pages.
+//!
+//! Run them with `cargo bench -p stellar-client`.
+#[macro_use]
+extern crate criterion;
+extern crate serde_json;
+extern crate stellar_client;
+
+use criterion::{black_box, Criterion, Throughput};
+use std::fs;
+use std::path::Path;
+use stellar_client::endpoint::Records;
+use stellar_client::resources::{
+    Amount, Effect, EffectRef, Operation, OperationRef, Trade, TradeRef,
+};
+
+const PAGE_SIZE: usize = 200;
+
+/// A page of records cycling through the fixtures in the directory, or
+/// repeating the single fixture file.
+fn page(fixture: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("fixtures")
+        .join(fixture);
+    let samples: Vec<String> = if path.is_dir() {
+        let mut files: Vec<_> = fs::read_dir(&path)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        files.sort();
+        files
+            .iter()
+            .map(|file| fs::read_to_string(file).unwrap())
+            .collect()
+    } else {
+        vec![fs::read_to_string(&path).unwrap()]
+    };
+    let records: Vec<&str> = samples
+        .iter()
+        .map(String::as_str)
+        .cycle()
+        .take(PAGE_SIZE)
+        .collect();
+    format!(
+        r#"{{
+            "_links": {{
+                "self": {{ "href": "https://horizon-testnet.stellar.org/{0}?cursor=&limit=200&order=asc" }},
+                "next": {{ "href": "https://horizon-testnet.stellar.org/{0}?cursor=1234&limit=200&order=asc" }},
+                "prev": {{ "href": "https://horizon-testnet.stellar.org/{0}?cursor=1000&limit=200&order=desc" }}
+            }},
+            "_embedded": {{ "records": [{1}] }}
+        }}"#,
+        fixture.trim_end_matches(".json"),
+        records.join(",")
+    )
+}
+
+fn pages(c: &mut Criterion) {
+    let operations = page("operations");
+    let effects = page("effects");
+    let trades = page("trade.json");
+
+    let mut group = c.benchmark_group("page");
+    group.throughput(Throughput::Elements(PAGE_SIZE as u64));
+    group.bench_function("operations", |b| {
+        b.iter(|| serde_json::from_str::<Records<Operation>>(black_box(&operations)).unwrap())
+    });
+    group.bench_function("effects", |b| {
+        b.iter(|| serde_json::from_str::<Records<Effect>>(black_box(&effects)).unwrap())
+    });
+    group.bench_function("trades", |b| {
+        b.iter(|| serde_json::from_str::<Records<Trade>>(black_box(&trades)).unwrap())
+    });
+    group.bench_function("operation refs", |b| {
+        b.iter(|| serde_json::from_str::<Records<OperationRef>>(black_box(&operations)).unwrap())
+    });
+    group.bench_function("effect refs", |b| {
+        b.iter(|| serde_json::from_str::<Records<EffectRef>>(black_box(&effects)).unwrap())
+    });
+    group.bench_function("trade refs", |b| {
+        b.iter(|| serde_json::from_str::<Records<TradeRef>>(black_box(&trades)).unwrap())
+    });
+    group.finish();
+}
+
+fn amounts(c: &mut Criterion) {
+    let amounts = [
+        "0.0000001",
+        "1.0000000",
+        "100.5",
+        "48211.4981000",
+        "922337203685.4775807",
+    ];
+
+    let mut group = c.benchmark_group("amount");
+    group.throughput(Throughput::Elements(amounts.len() as u64));
+    group.bench_function("parse", |b| {
+        b.iter(|| {
+            for amount in &amounts {
+                black_box(amount.parse::<Amount>().unwrap());
+            }
+        })
+    });
+    let json: Vec<String> = amounts
+        .iter()
+        .map(|amount| format!("\"{}\"", amount))
+        .collect();
+    group.bench_function("deserialize", |b| {
+        b.iter(|| {
+            for amount in &json {
+                black_box(serde_json::from_str::<Amount>(amount).unwrap());
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, pages, amounts);
+criterion_main!(benches);
@@ -0,0 +1,530 @@
+//! Folds the effects of an account into how much each of its balances
+//! changed, which is what reconciliation jobs check a ledger of their own
+//! against.
+//!
+//! A `BalanceReducer` is fed an account's effects in the order they
+//! happened, and the account's transactions for the fees it paid. It keeps
+//! a `BalanceDelta` for each asset, splitting the change into what was
+//! credited, debited, bought, sold and paid in fees, and the running
+//! balance of each asset after every change. `verify` checks the
+//! reconstruction against the balances the account actually holds.
+//!
+//! ```no_run
+//! use stellar_client::balance_reducer::BalanceReducer;
+//! use stellar_client::endpoint::account;
+//! use stellar_client::sync::{Client, Iter};
+//!
+//! let client = Client::horizon().unwrap();
+//! let account_id = "GATLAI2D7SSH6PE3HXTDPTRM4RE5VRK6HGA63K5EWP75PSANCZRFDNB5";
+//! let mut reducer = BalanceReducer::new(account_id);
+//! for transaction in Iter::new(&client, account::Transactions::new(account_id)) {
+//!     reducer.apply_transaction(&transaction.unwrap());
+//! }
+//! for effect in Iter::new(&client, account::Effects::new(account_id)) {
+//!     reducer.apply_effect(&effect.unwrap());
+//! }
+//!
+//! let account = client.request(account::Details::new(account_id)).unwrap();
+//! let closing = account
+//!     .balances()
+//!     .iter()
+//!     .filter_map(|balance| Some((balance.asset()?.clone(), balance.balance().into())))
+//!     .collect();
+//! if let Err(discrepancies) = reducer.verify(&closing) {
+//!     for discrepancy in discrepancies {
+//!         eprintln!("{}", discrepancy);
+//!     }
+//! }
+//! ```
+use history_export::{movements, negate, EntryKind};
+use resources::{Amount, AssetIdentifier, Effect, SignedAmount, Transaction};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// How much the balance of one asset changed, split by what changed it.
+/// Every part is a magnitude, so debits, sales and fees count up like
+/// credits do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BalanceDelta {
+    credited: Amount,
+    debited: Amount,
+    bought: Amount,
+    sold: Amount,
+    fees: Amount,
+}
+
+impl Default for BalanceDelta {
+    fn default() -> BalanceDelta {
+        BalanceDelta {
+            credited: Amount::new(0),
+            debited: Amount::new(0),
+            bought: Amount::new(0),
+            sold: Amount::new(0),
+            fees: Amount::new(0),
+        }
+    }
+}
+
+impl BalanceDelta {
+    /// What came in from payments, the account being created and other
+    /// credits.
+    pub fn credited(&self) -> Amount {
+        self.credited
+    }
+
+    /// What went out in payments, merges and other debits.
+    pub fn debited(&self) -> Amount {
+        self.debited
+    }
+
+    /// What the account's trades bought.
+    pub fn bought(&self) -> Amount {
+        self.bought
+    }
+
+    /// What the account's trades sold.
+    pub fn sold(&self) -> Amount {
+        self.sold
+    }
+
+    /// The fees paid for the account's transactions, which are only ever in
+    /// lumens.
+    pub fn fees(&self) -> Amount {
+        self.fees
+    }
+
+    /// How much the balance changed by overall, negative when it went down.
+    pub fn net(&self) -> SignedAmount {
+        let credited = SignedAmount::from(self.credited);
+        let bought = SignedAmount::from(self.bought);
+        let gained = &credited + &bought;
+        let lost = &(&self.debited + &self.sold) + &self.fees;
+        &gained - &lost
+    }
+
+    fn add(&mut self, kind: EntryKind, amount: SignedAmount) {
+        let magnitude = if amount.is_negative() {
+            negate(amount)
+        } else {
+            amount
+        };
+        let magnitude = magnitude
+            .to_amount()
+            .expect("A magnitude is never negative");
+        let part = match kind {
+            EntryKind::Credit => &mut self.credited,
+            EntryKind::Debit => &mut self.debited,
+            EntryKind::Fee => &mut self.fees,
+            EntryKind::Trade if amount.is_negative() => &mut self.sold,
+            EntryKind::Trade => &mut self.bought,
+        };
+        *part = &*part + &magnitude;
+    }
+}
+
+/// One change to the balance of an asset and the balance after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    id: String,
+    kind: EntryKind,
+    asset: AssetIdentifier,
+    amount: SignedAmount,
+    balance: SignedAmount,
+}
+
+impl Step {
+    /// The paging token of the effect or transaction behind the change.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// What changed the balance.
+    pub fn kind(&self) -> EntryKind {
+        self.kind
+    }
+
+    /// The asset whose balance changed.
+    pub fn asset(&self) -> &AssetIdentifier {
+        &self.asset
+    }
+
+    /// How much the balance changed by, negative when it went down.
+    pub fn amount(&self) -> SignedAmount {
+        self.amount
+    }
+
+    /// The balance of the asset after the change, counting from the
+    /// opening balances.
+    pub fn balance(&self) -> SignedAmount {
+        self.balance
+    }
+}
+
+/// A way the reconstructed balances disagree with the ledger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Discrepancy {
+    /// The reconstructed balance of an asset isn't the balance the account
+    /// closed with, so effects are missing or were applied twice.
+    Closing {
+        /// The asset that disagrees.
+        asset: AssetIdentifier,
+        /// The balance the account closed with.
+        expected: SignedAmount,
+        /// The balance the effects add up to.
+        reconstructed: SignedAmount,
+    },
+    /// A balance went below zero, which the ledger never allows, so changes
+    /// before this one are missing or the opening balance is wrong.
+    Negative {
+        /// The paging token of the change that took the balance below zero.
+        id: String,
+        /// The asset whose balance went below zero.
+        asset: AssetIdentifier,
+        /// The balance after the change.
+        balance: SignedAmount,
+    },
+}
+
+impl fmt::Display for Discrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Discrepancy::Closing {
+                ref asset,
+                expected,
+                reconstructed,
+            } => write!(
+                f,
+                "{} closed at {} but the effects add up to {}",
+                asset_name(asset),
+                expected,
+                reconstructed
+            ),
+            Discrepancy::Negative {
+                ref id,
+                ref asset,
+                balance,
+            } => write!(
+                f,
+                "{} went below zero to {} at {}",
+                asset_name(asset),
+                balance,
+                id
+            ),
+        }
+    }
+}
+
+/// Folds an account's effects and transactions into per asset balance
+/// deltas and a running balance for each asset.
+///
+/// Effects and transactions are expected in the order they happened, with
+/// each transaction before the effects of its operations, since that's the
+/// order its fee is charged in. Anything applied twice, such as when pages
+/// of a stream overlap, is only counted once.
+#[derive(Debug, Clone)]
+pub struct BalanceReducer {
+    account_id: String,
+    balances: HashMap<AssetIdentifier, SignedAmount>,
+    deltas: HashMap<AssetIdentifier, BalanceDelta>,
+    steps: Vec<Step>,
+    applied: HashSet<String>,
+}
+
+impl BalanceReducer {
+    /// Starts a reducer for the given account with every balance at zero,
+    /// which is right when the first effect is the account being created.
+    pub fn new(account_id: &str) -> BalanceReducer {
+        BalanceReducer {
+            account_id: account_id.to_string(),
+            balances: HashMap::new(),
+            deltas: HashMap::new(),
+            steps: Vec::new(),
+            applied: HashSet::new(),
+        }
+    }
+
+    /// Sets the balances the account held before the first effect, for
+    /// reducing a stream that starts part way through the account's
+    /// history. Assets that aren't given open at zero.
+    pub fn with_opening_balances(
+        mut self,
+        balances: HashMap<AssetIdentifier, SignedAmount>,
+    ) -> BalanceReducer {
+        self.balances = balances;
+        self
+    }
+
+    /// The account being reduced.
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    /// Applies the balance changes of an effect, returning whether it
+    /// changed any. Effects of other accounts, effects that don't move
+    /// funds and effects already applied change nothing.
+    pub fn apply_effect(&mut self, effect: &Effect) -> bool {
+        let movements = movements(&self.account_id, effect);
+        if movements.is_empty() || !self.applied.insert(effect.paging_token().clone()) {
+            return false;
+        }
+        for (kind, asset, amount) in movements {
+            self.push(effect.paging_token(), kind, asset, amount);
+        }
+        true
+    }
+
+    /// Charges the fee of a transaction, if the account paid for it,
+    /// returning whether it did. The operations of the transaction are
+    /// applied from its effects.
+    pub fn apply_transaction(&mut self, transaction: &Transaction) -> bool {
+        if *transaction.source_account() != self.account_id
+            || !self.applied.insert(transaction.paging_token().clone())
+        {
+            return false;
+        }
+        self.push(
+            transaction.paging_token(),
+            EntryKind::Fee,
+            AssetIdentifier::Native,
+            SignedAmount::new(-transaction.fee_paid()),
+        );
+        true
+    }
+
+    /// How much the balance of each asset changed.
+    pub fn deltas(&self) -> &HashMap<AssetIdentifier, BalanceDelta> {
+        &self.deltas
+    }
+
+    /// How much the balance of an asset changed, which is nothing for an
+    /// asset no effect touched.
+    pub fn delta(&self, asset: &AssetIdentifier) -> BalanceDelta {
+        self.deltas.get(asset).cloned().unwrap_or_default()
+    }
+
+    /// The balances after everything applied so far.
+    pub fn balances(&self) -> &HashMap<AssetIdentifier, SignedAmount> {
+        &self.balances
+    }
+
+    /// Every change applied, with the balance after it, in the order they
+    /// were applied.
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Checks the reconstruction against the balances the account closed
+    /// with, such as its balances right now when every effect up to now has
+    /// been applied. Assets missing from `closing` are expected to be at
+    /// zero. Every discrepancy is returned, balances going below zero first
+    /// in the order they happened, then closing balances in asset order.
+    pub fn verify(
+        &self,
+        closing: &HashMap<AssetIdentifier, SignedAmount>,
+    ) -> Result<(), Vec<Discrepancy>> {
+        let mut discrepancies: Vec<Discrepancy> = self
+            .steps
+            .iter()
+            .filter(|step| step.balance.is_negative())
+            .map(|step| Discrepancy::Negative {
+                id: step.id.clone(),
+                asset: step.asset.clone(),
+                balance: step.balance,
+            })
+            .collect();
+
+        let zero = SignedAmount::new(0);
+        let mut assets: Vec<&AssetIdentifier> =
+            self.balances.keys().chain(closing.keys()).collect();
+        assets.sort_by_key(|asset| asset_name(asset));
+        assets.dedup();
+        for asset in assets {
+            let expected = *closing.get(asset).unwrap_or(&zero);
+            let reconstructed = *self.balances.get(asset).unwrap_or(&zero);
+            if expected != reconstructed {
+                discrepancies.push(Discrepancy::Closing {
+                    asset: asset.clone(),
+                    expected,
+                    reconstructed,
+                });
+            }
+        }
+
+        if discrepancies.is_empty() {
+            Ok(())
+        } else {
+            Err(discrepancies)
+        }
+    }
+
+    fn push(&mut self, id: &str, kind: EntryKind, asset: AssetIdentifier, amount: SignedAmount) {
+        self.deltas
+            .entry(asset.clone())
+            .or_default()
+            .add(kind, amount);
+        let balance = self
+            .balances
+            .entry(asset.clone())
+            .or_insert_with(|| SignedAmount::new(0));
+        *balance = &*balance + &amount;
+        self.steps.push(Step {
+            id: id.to_string(),
+            kind,
+            asset,
+            amount,
+            balance: *balance,
+        });
+    }
+}
+
+fn asset_name(asset: &AssetIdentifier) -> String {
+    match *asset {
+        AssetIdentifier::Native => "native".to_string(),
+        _ => format!("{}:{}", asset.code(), asset.issuer()),
+    }
+}
+
+#[cfg(test)]
+mod balance_reducer_tests {
+    use super::*;
+    use serde_json;
+
+    const ACCOUNT: &str = "GA6U5X6WOPNKKDKQULBR7IDHDBAQKOWPHYEC7WSXHZBFEYFD3XVZAKOO";
+
+    fn credited(paging_token: &str) -> Effect {
+        let json = include_str!("../fixtures/effects/account_credited.json")
+            .replace(
+                "GDLGTRIBFH24364GPWPUS45GUFC2GU4ARPGWTXVCPLGTUHX3IOS3ON47",
+                ACCOUNT,
+            )
+            .replace("13563506724865-1", paging_token);
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn trade() -> Effect {
+        serde_json::from_str(include_str!("../fixtures/effects/trade.json")).unwrap()
+    }
+
+    fn eur() -> AssetIdentifier {
+        AssetIdentifier::alphanum4(
+            "EUR",
+            "GCWVFBJ24754I5GXG4JOEB72GJCL3MKWC7VAEYWKGQHPVH3ENPNBSKWS",
+        )
+    }
+
+    #[test]
+    fn it_splits_deltas_by_what_changed_them() {
+        let mut reducer = BalanceReducer::new(ACCOUNT);
+        assert!(reducer.apply_transaction(
+            &Transaction::new("abc", ACCOUNT, 1)
+                .with_paging_token("8589938688")
+                .with_fee_paid(100)
+        ));
+        assert!(reducer.apply_effect(&credited("8589938689-1")));
+        assert!(reducer.apply_effect(&credited("8589938690-1")));
+
+        let native = reducer.delta(&AssetIdentifier::Native);
+        assert_eq!(native.credited(), Amount::new(20_000_000_000));
+        assert_eq!(native.fees(), Amount::new(100));
+        assert_eq!(native.debited(), Amount::new(0));
+        assert_eq!(native.net(), SignedAmount::new(19_999_999_900));
+
+        let mut trading = BalanceReducer::new(ACCOUNT);
+        assert!(trading.apply_effect(&trade()));
+        assert_eq!(trading.delta(&eur()).sold(), Amount::new(10_000_000_000));
+        assert_eq!(
+            trading.delta(&eur()).net(),
+            SignedAmount::new(-10_000_000_000)
+        );
+        let bought: Vec<Amount> = trading
+            .deltas()
+            .values()
+            .map(BalanceDelta::bought)
+            .filter(|bought| *bought != Amount::new(0))
+            .collect();
+        assert_eq!(bought, vec![Amount::new(600_000_000)]);
+    }
+
+    #[test]
+    fn it_skips_what_doesnt_change_the_account() {
+        let mut reducer = BalanceReducer::new(ACCOUNT);
+        assert!(reducer.apply_effect(&credited("8589938689-1")));
+        assert!(!reducer.apply_effect(&credited("8589938689-1")));
+        let signer: Effect =
+            serde_json::from_str(include_str!("../fixtures/effects/signer_created.json")).unwrap();
+        assert!(!reducer.apply_effect(&signer));
+        let received = Transaction::new(
+            "def",
+            "GDLGTRIBFH24364GPWPUS45GUFC2GU4ARPGWTXVCPLGTUHX3IOS3ON47",
+            1,
+        )
+        .with_paging_token("8589942784");
+        assert!(!reducer.apply_transaction(&received));
+        assert_eq!(reducer.steps().len(), 1);
+        assert_eq!(
+            reducer.balances()[&AssetIdentifier::Native],
+            SignedAmount::new(10_000_000_000)
+        );
+    }
+
+    #[test]
+    fn it_verifies_the_reconstruction() {
+        let mut opening = HashMap::new();
+        opening.insert(AssetIdentifier::Native, SignedAmount::new(5_000_000_000));
+        let mut reducer = BalanceReducer::new(ACCOUNT).with_opening_balances(opening);
+        reducer.apply_transaction(
+            &Transaction::new("abc", ACCOUNT, 1)
+                .with_paging_token("8589938688")
+                .with_fee_paid(100),
+        );
+        reducer.apply_effect(&credited("8589938689-1"));
+        let balances: Vec<SignedAmount> = reducer.steps().iter().map(Step::balance).collect();
+        assert_eq!(
+            balances,
+            vec![
+                SignedAmount::new(4_999_999_900),
+                SignedAmount::new(14_999_999_900)
+            ]
+        );
+
+        let mut closing = HashMap::new();
+        closing.insert(AssetIdentifier::Native, SignedAmount::new(14_999_999_900));
+        assert_eq!(reducer.verify(&closing), Ok(()));
+
+        closing.insert(eur(), SignedAmount::new(1));
+        assert_eq!(
+            reducer.verify(&closing),
+            Err(vec![Discrepancy::Closing {
+                asset: eur(),
+                expected: SignedAmount::new(1),
+                reconstructed: SignedAmount::new(0),
+            }])
+        );
+    }
+
+    #[test]
+    fn it_flags_balances_that_go_below_zero() {
+        let mut reducer = BalanceReducer::new(ACCOUNT);
+        reducer.apply_transaction(
+            &Transaction::new("abc", ACCOUNT, 1)
+                .with_paging_token("8589938688")
+                .with_fee_paid(100),
+        );
+        reducer.apply_effect(&credited("8589938689-1"));
+        let mut closing = HashMap::new();
+        closing.insert(AssetIdentifier::Native, SignedAmount::new(9_999_999_900));
+        let discrepancies = reducer.verify(&closing).unwrap_err();
+        assert_eq!(
+            discrepancies,
+            vec![Discrepancy::Negative {
+                id: "8589938688".to_string(),
+                asset: AssetIdentifier::Native,
+                balance: SignedAmount::new(-100),
+            }]
+        );
+        assert_eq!(
+            discrepancies[0].to_string(),
+            "native went below zero to -0.0000100 at 8589938688"
+        );
+    }
+}
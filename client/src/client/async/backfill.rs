@@ -0,0 +1,318 @@
+use super::{Client, ResponseFuture};
+use endpoint::{ledger_cursor, Cursor, Direction, IntoRequest, Limit, Order, Pageable, Records};
+use error::Error;
+use futures::{future, stream, Future, Stream};
+use resources::Resource;
+use std::ops::Range;
+
+/// A backfill of the records of a range of ledgers, such as the operations
+/// or transactions of a year of history.
+///
+/// Paging through history one page at a time is bound by the round trip to
+/// horizon, so scans of millions of records take hours. A backfill splits
+/// the range into shards of consecutive ledgers, pages through several
+/// shards at once starting each from the cursor of its first ledger, and
+/// returns the records of each shard in ledger order, so the result is the
+/// same as paging through the whole range in ascending order.
+///
+/// Any listing endpoint whose paging tokens begin with the sequence of their
+/// ledger can be backfilled: ledgers, transactions, operations, payments and
+/// effects. The endpoint's filters are kept, and its cursor and order are
+/// replaced for each shard.
+///
+/// ## Examples
+///
+/// ```no_run
+/// # extern crate tokio_core;
+/// # extern crate stellar_client;
+/// # fn main() {
+/// use tokio_core::reactor::Core;
+/// use stellar_client::async::{Backfill, Client};
+/// use stellar_client::endpoint::operation;
+///
+/// let mut core = Core::new().unwrap();
+/// let client = Client::horizon(&core.handle()).unwrap();
+/// let backfill = Backfill::new(client, operation::All::default(), 40_000_000..40_100_000)
+///     .with_shard_size(5_000)
+///     .with_concurrency(16);
+/// assert_eq!(backfill.shards().len(), 20);
+/// let operations = core.run(backfill.run()).unwrap();
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Backfill<E> {
+    client: Client,
+    endpoint: E,
+    ledgers: Range<u32>,
+    shard_size: u32,
+    concurrency: usize,
+    limit: u32,
+}
+
+impl<E, T> Backfill<E>
+where
+    E: IntoRequest<Response = Records<T>> + Pageable + Clone + 'static,
+    T: Resource + 'static,
+{
+    /// Plans a backfill of the endpoint's records in the ledgers of the
+    /// range, from its start up to but not including its end.
+    pub fn new(client: Client, endpoint: E, ledgers: Range<u32>) -> Backfill<E> {
+        Backfill {
+            client,
+            endpoint,
+            ledgers,
+            shard_size: 1_000,
+            concurrency: 8,
+            limit: 200,
+        }
+    }
+
+    /// Sets how many ledgers each shard covers. Defaults to 1000.
+    pub fn with_shard_size(mut self, shard_size: u32) -> Self {
+        self.shard_size = shard_size.max(1);
+        self
+    }
+
+    /// Sets how many shards are paged through at once. Defaults to 8.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Sets the number of records requested in each page. Defaults to 200,
+    /// the most horizon returns.
+    pub fn with_limit(mut self, limit: u32) -> Self {
+        self.limit = limit.max(1);
+        self
+    }
+
+    /// The ledgers being backfilled.
+    pub fn ledgers(&self) -> &Range<u32> {
+        &self.ledgers
+    }
+
+    /// The ledger ranges the backfill is split into, in order.
+    pub fn shards(&self) -> Vec<Range<u32>> {
+        let mut shards = Vec::new();
+        let mut start = self.ledgers.start;
+        while start < self.ledgers.end {
+            let end = start.saturating_add(self.shard_size).min(self.ledgers.end);
+            shards.push(start..end);
+            start = end;
+        }
+        shards
+    }
+
+    /// Pages through the shards, yielding each shard with its records once
+    /// it and every shard before it are done. Shards later in the range
+    /// that finish early are held until then, so the records come out in
+    /// order, and a backfill that stops partway can be resumed from the end
+    /// of the last shard yielded.
+    pub fn stream(&self) -> Box<dyn Stream<Item = (Range<u32>, Vec<T>), Error = Error>> {
+        let backfill = self.clone();
+        let shards = stream::iter_ok(self.shards())
+            .map(move |shard| {
+                backfill
+                    .fetch_shard(shard.clone())
+                    .map(move |records| (shard, records))
+            })
+            .buffered(self.concurrency);
+        Box::new(shards)
+    }
+
+    /// Backfills the whole range, returning its records in order.
+    pub fn run(&self) -> ResponseFuture<Vec<T>> {
+        let records = self.stream().fold(Vec::new(), |mut all, (_, records)| {
+            all.extend(records);
+            Ok::<_, Error>(all)
+        });
+        Box::new(records)
+    }
+
+    /// Pages through a shard from the first record of its first ledger
+    /// until a record past its last ledger or the last page.
+    fn fetch_shard(&self, shard: Range<u32>) -> ResponseFuture<Vec<T>> {
+        let client = self.client.clone();
+        let endpoint = self.endpoint.clone();
+        let limit = self.limit;
+        let records = future::loop_fn(
+            (ledger_cursor(shard.start), Vec::new()),
+            move |(cursor, mut records): (String, Vec<T>)| {
+                let end = shard.end;
+                let page = endpoint
+                    .clone()
+                    .with_cursor(&cursor)
+                    .with_order(Direction::Asc)
+                    .with_limit(limit);
+                client.request(page).map(move |page| {
+                    let page = page.into_records();
+                    let full = page.len() >= limit as usize;
+                    let mut next = None;
+                    for record in page {
+                        let in_shard = ledger(&record.paging_token()).map(|ledger| ledger < end);
+                        if in_shard != Some(true) {
+                            return future::Loop::Break(records);
+                        }
+                        next = Some(record.paging_token().into_owned());
+                        records.push(record);
+                    }
+                    match next {
+                        Some(cursor) if full => future::Loop::Continue((cursor, records)),
+                        _ => future::Loop::Break(records),
+                    }
+                })
+            },
+        );
+        Box::new(records)
+    }
+}
+
+/// The ledger a record was created in, from the total order id its paging
+/// token begins with.
+fn ledger(paging_token: &str) -> Option<u32> {
+    let toid: u64 = paging_token.split('-').next()?.parse().ok()?;
+    Some((toid >> 32) as u32)
+}
+
+#[cfg(test)]
+mod backfill_tests {
+    use super::*;
+    use client::sync::test_server::TestServer;
+    use endpoint::{Body, PageParams};
+    use error::Result;
+    use http::{Request, Uri};
+    use std::borrow::Cow;
+    use std::str::FromStr;
+    use tokio_core::reactor::Core;
+
+    #[derive(Debug, Clone, Default)]
+    struct AllRecords(PageParams);
+
+    impl Pageable for AllRecords {
+        fn page_params(&self) -> &PageParams {
+            &self.0
+        }
+
+        fn page_params_mut(&mut self) -> &mut PageParams {
+            &mut self.0
+        }
+    }
+
+    impl IntoRequest for AllRecords {
+        type Response = Records<Record>;
+
+        fn into_request(self, host: &str) -> Result<Request<Body>> {
+            let mut uri = format!("{}/records", host);
+            self.0.push_to(&mut uri)?;
+            Ok(Request::get(Uri::from_str(&uri)?).body(Body::None)?)
+        }
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Record {
+        paging_token: String,
+    }
+
+    impl Resource for Record {
+        fn id(&self) -> Cow<str> {
+            Cow::Borrowed(&self.paging_token)
+        }
+
+        fn paging_token(&self) -> Cow<str> {
+            Cow::Borrowed(&self.paging_token)
+        }
+    }
+
+    fn backfill(client: Client, ledgers: Range<u32>) -> Backfill<AllRecords> {
+        Backfill::new(client, AllRecords::default(), ledgers)
+    }
+
+    #[test]
+    fn it_splits_the_range_into_shards() {
+        let core = Core::new().unwrap();
+        let client = Client::horizon_test(&core.handle()).unwrap();
+        let backfill = backfill(client, 10..35).with_shard_size(10);
+        assert_eq!(backfill.shards(), vec![10..20, 20..30, 30..35]);
+        assert_eq!(backfill.clone().with_shard_size(0).shards().len(), 25);
+        assert_eq!(backfill.with_shard_size(100).shards(), vec![10..35]);
+
+        let client = Client::horizon_test(&core.handle()).unwrap();
+        assert!(self::backfill(client, 10..10).shards().is_empty());
+    }
+
+    #[test]
+    fn it_reads_the_ledger_of_a_paging_token() {
+        assert_eq!(ledger("71946212651044865"), Some(16751283));
+        assert_eq!(ledger("71946212651044865-1"), Some(16751283));
+        assert_eq!(ledger("now"), None);
+    }
+
+    #[test]
+    fn it_pages_through_each_shard_in_order() {
+        // Ledgers 10 and 11 make up the first shard, 12 and 13 the second.
+        let server = TestServer::sequence(vec![
+            (
+                "200 OK",
+                r#"{"_embedded":{"records":[
+                    {"paging_token":"42949672961"},
+                    {"paging_token":"47244640257"}
+                ]}}"#,
+            ),
+            (
+                "200 OK",
+                r#"{"_embedded":{"records":[
+                    {"paging_token":"47244640258"},
+                    {"paging_token":"51539607553"}
+                ]}}"#,
+            ),
+            (
+                "200 OK",
+                r#"{"_embedded":{"records":[
+                    {"paging_token":"51539607553"},
+                    {"paging_token":"55834574849"}
+                ]}}"#,
+            ),
+            ("200 OK", r#"{"_embedded":{"records":[]}}"#),
+        ]);
+        let mut core = Core::new().unwrap();
+        let client = Client::new(server.uri(), &core.handle()).unwrap();
+        let backfill = backfill(client, 10..14)
+            .with_shard_size(2)
+            .with_limit(2)
+            .with_concurrency(1);
+
+        let records = core.run(backfill.run()).unwrap();
+        let tokens: Vec<&str> = records.iter().map(|r| &*r.paging_token).collect();
+        assert_eq!(
+            tokens,
+            vec![
+                "42949672961",
+                "47244640257",
+                "47244640258",
+                "51539607553",
+                "55834574849",
+            ]
+        );
+        assert_eq!(server.hits(), 4);
+    }
+
+    #[test]
+    fn it_starts_each_shard_with_its_first_ledger() {
+        // A page of ledgers 10 and 11, whose paging tokens are their
+        // sequences shifted into the high 32 bits.
+        let server = TestServer::start(
+            "200 OK",
+            r#"{"_embedded":{"records":[
+                {"paging_token":"42949672960"},
+                {"paging_token":"47244640256"}
+            ]}}"#,
+        );
+        let mut core = Core::new().unwrap();
+        let client = Client::new(server.uri(), &core.handle()).unwrap();
+        let backfill = backfill(client, 10..12).with_limit(3);
+
+        let records = core.run(backfill.run()).unwrap();
+        assert_eq!(records.len(), 2);
+        assert!(server.paths()[0].contains("cursor=42949672959&"));
+    }
+}
@@ -0,0 +1,325 @@
+use super::{Client, ResponseFuture};
+use endpoint::IntoRequest;
+use error::Error;
+use futures::{future, Future};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio_core::reactor::Timeout;
+
+/// When a `HedgedClient` sends a second request.
+///
+/// The second request goes out once the first has taken longer than a
+/// percentile of the primary host's recent response times, so only the
+/// slowest requests are hedged. Until enough responses have been timed, the
+/// initial delay is used instead.
+///
+/// ## Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use stellar_client::async::HedgePolicy;
+///
+/// let policy = HedgePolicy::default()
+///     .with_percentile(0.99)
+///     .with_initial_delay(Duration::from_millis(250));
+/// assert_eq!(policy.percentile(), 0.99);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HedgePolicy {
+    percentile: f64,
+    initial_delay: Duration,
+    min_samples: usize,
+    window: usize,
+}
+
+impl Default for HedgePolicy {
+    fn default() -> HedgePolicy {
+        HedgePolicy {
+            percentile: 0.95,
+            initial_delay: Duration::from_millis(100),
+            min_samples: 20,
+            window: 100,
+        }
+    }
+}
+
+impl HedgePolicy {
+    /// Sets the percentile of response times, between 0 and 1, after which
+    /// a request is hedged. Defaults to 0.95, hedging one request in twenty.
+    pub fn with_percentile(mut self, percentile: f64) -> Self {
+        self.percentile = percentile.max(0.0).min(1.0);
+        self
+    }
+
+    /// Sets the delay used until enough responses have been timed. Defaults
+    /// to 100 milliseconds.
+    pub fn with_initial_delay(mut self, delay: Duration) -> Self {
+        self.initial_delay = delay;
+        self
+    }
+
+    /// Sets how many of the most recent response times the percentile is
+    /// taken over, and how many must be timed before it is used. Defaults to
+    /// the last 100, once 20 have been timed.
+    pub fn with_window(mut self, window: usize, min_samples: usize) -> Self {
+        self.window = window.max(1);
+        self.min_samples = min_samples.max(1).min(self.window);
+        self
+    }
+
+    /// The percentile of response times after which a request is hedged.
+    pub fn percentile(&self) -> f64 {
+        self.percentile
+    }
+
+    /// The delay used until enough responses have been timed.
+    pub fn initial_delay(&self) -> Duration {
+        self.initial_delay
+    }
+
+    /// The delay before hedging given the recent response times.
+    fn delay(&self, samples: &VecDeque<Duration>) -> Duration {
+        if samples.len() < self.min_samples {
+            return self.initial_delay;
+        }
+        let mut sorted: Vec<Duration> = samples.iter().cloned().collect();
+        sorted.sort();
+        let index = ((sorted.len() - 1) as f64 * self.percentile).round() as usize;
+        sorted[index]
+    }
+}
+
+/// An async client for latency sensitive reads, such as trading systems
+/// where the slowest responses matter. Each request goes to the primary
+/// host, and if it hasn't answered within the hedge delay the same request
+/// goes to the secondary host as well. Whichever answers first wins, and
+/// the other request is dropped.
+///
+/// Horizon rejecting a request isn't retried, but if one host can't be
+/// reached the answer from the other is used.
+///
+/// Hedging sends the same request twice, so it's meant for reads. Don't
+/// hedge transaction submissions.
+///
+/// ## Examples
+///
+/// ```no_run
+/// # extern crate tokio_core;
+/// # extern crate stellar_client;
+/// # fn main() {
+/// use tokio_core::reactor::Core;
+/// use stellar_client::async::{Client, HedgePolicy, HedgedClient};
+/// use stellar_client::endpoint::orderbook;
+/// use stellar_client::resources::AssetIdentifier;
+///
+/// let mut core = Core::new().unwrap();
+/// let handle = core.handle();
+/// let client = HedgedClient::new(
+///     Client::new("https://horizon-one.example.com", &handle).unwrap(),
+///     Client::new("https://horizon-two.example.com", &handle).unwrap(),
+/// )
+/// .with_policy(HedgePolicy::default().with_percentile(0.9));
+/// let usd = AssetIdentifier::alphanum4(
+///     "USD",
+///     "GDUKMGUGDZQK6YHYA5Z6AY2G4XDSZPSZ3SW5UN3ARVMO6QSRDWP5YLEX",
+/// );
+/// let endpoint = orderbook::Details::for_asset_pair(AssetIdentifier::native(), usd);
+/// let book = core.run(client.request(endpoint)).unwrap();
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct HedgedClient {
+    primary: Client,
+    secondary: Client,
+    policy: HedgePolicy,
+    latencies: Arc<Mutex<VecDeque<Duration>>>,
+}
+
+impl HedgedClient {
+    /// Hedges requests to the primary host with requests to the secondary.
+    /// Both clients should run on the same event loop.
+    pub fn new(primary: Client, secondary: Client) -> HedgedClient {
+        HedgedClient {
+            primary,
+            secondary,
+            policy: HedgePolicy::default(),
+            latencies: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+
+    /// Sets when requests are hedged.
+    pub fn with_policy(mut self, policy: HedgePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// When requests are hedged.
+    pub fn policy(&self) -> &HedgePolicy {
+        &self.policy
+    }
+
+    /// How long a request to the primary host may take before it is hedged,
+    /// given the response times so far.
+    pub fn delay(&self) -> Duration {
+        let latencies = self.latencies.lock().expect("Latencies lock poisoned");
+        self.policy.delay(&latencies)
+    }
+
+    /// Requests the endpoint from the primary host, and from the secondary
+    /// host too if the primary is slow to answer.
+    pub fn request<E>(&self, endpoint: E) -> ResponseFuture<E::Response>
+    where
+        E: IntoRequest + Clone + 'static,
+        E::Response: 'static,
+    {
+        let started = Instant::now();
+        let latencies = Arc::clone(&self.latencies);
+        let window = self.policy.window;
+        let primary = self.primary.request(endpoint.clone()).map(move |response| {
+            let mut latencies = latencies.lock().expect("Latencies lock poisoned");
+            if latencies.len() >= window {
+                latencies.pop_front();
+            }
+            latencies.push_back(started.elapsed());
+            response
+        });
+
+        let secondary = self.secondary.clone();
+        let hedge = match Timeout::new(self.delay(), self.primary.handle()) {
+            Ok(timeout) => timeout
+                .map_err(Error::Io)
+                .and_then(move |_| secondary.request(endpoint)),
+            Err(err) => return Box::new(future::err(Error::Io(err))),
+        };
+
+        let response = primary.select(hedge).then(|result| match result {
+            Ok((response, _)) => future::Either::A(future::ok(response)),
+            // Horizon would reject the request from the other host too.
+            Err((Error::BadResponse(err), _)) => {
+                future::Either::A(future::err(Error::BadResponse(err)))
+            }
+            // Wait on the other host when one can't answer.
+            Err((_, other)) => future::Either::B(other),
+        });
+        Box::new(response)
+    }
+}
+
+#[cfg(test)]
+mod hedged_client_tests {
+    use super::*;
+    use client::sync::test_server::TestServer;
+    use endpoint::ledger;
+    use tokio_core::reactor::Core;
+
+    const LEDGER: &str = include_str!("../../../fixtures/ledger.json");
+
+    fn millis(samples: &[u64]) -> VecDeque<Duration> {
+        samples
+            .iter()
+            .map(|ms| Duration::from_millis(*ms))
+            .collect()
+    }
+
+    #[test]
+    fn it_waits_for_the_percentile_of_response_times() {
+        let policy = HedgePolicy::default()
+            .with_initial_delay(Duration::from_millis(75))
+            .with_percentile(0.9)
+            .with_window(10, 5);
+        assert_eq!(policy.delay(&millis(&[10, 20])), Duration::from_millis(75));
+        assert_eq!(
+            policy.delay(&millis(&[50, 10, 40, 20, 30])),
+            Duration::from_millis(50)
+        );
+        assert_eq!(
+            policy.delay(&millis(&[90, 10, 20, 30, 40, 50, 60, 70, 80, 100])),
+            Duration::from_millis(90)
+        );
+        assert_eq!(
+            policy
+                .with_percentile(0.5)
+                .delay(&millis(&[50, 10, 40, 20, 30])),
+            Duration::from_millis(30)
+        );
+    }
+
+    #[test]
+    fn it_only_asks_the_primary_when_it_is_fast() {
+        let mut core = Core::new().unwrap();
+        let primary = TestServer::start("200 OK", LEDGER);
+        let secondary = TestServer::start("200 OK", LEDGER);
+        let client = HedgedClient::new(
+            Client::new(primary.uri(), &core.handle()).unwrap(),
+            Client::new(secondary.uri(), &core.handle()).unwrap(),
+        )
+        .with_policy(HedgePolicy::default().with_initial_delay(Duration::from_secs(5)));
+
+        let ledger = core
+            .run(client.request(ledger::Details::new(69859)))
+            .unwrap();
+        assert_eq!(ledger.sequence(), 69859);
+        assert_eq!(primary.hits(), 1);
+        assert_eq!(secondary.hits(), 0);
+        assert_eq!(client.latencies.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn it_hedges_slow_requests() {
+        let mut core = Core::new().unwrap();
+        let primary = TestServer::delayed(Duration::from_secs(5), "200 OK", LEDGER);
+        let secondary = TestServer::start("200 OK", LEDGER);
+        let client = HedgedClient::new(
+            Client::new(primary.uri(), &core.handle()).unwrap(),
+            Client::new(secondary.uri(), &core.handle()).unwrap(),
+        )
+        .with_policy(HedgePolicy::default().with_initial_delay(Duration::from_millis(50)));
+
+        let started = Instant::now();
+        let ledger = core
+            .run(client.request(ledger::Details::new(69859)))
+            .unwrap();
+        assert_eq!(ledger.sequence(), 69859);
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(secondary.hits(), 1);
+    }
+
+    #[test]
+    fn it_uses_the_secondary_when_the_primary_is_down() {
+        let mut core = Core::new().unwrap();
+        let secondary = TestServer::start("200 OK", LEDGER);
+        let client = HedgedClient::new(
+            Client::new(&TestServer::down(), &core.handle()).unwrap(),
+            Client::new(secondary.uri(), &core.handle()).unwrap(),
+        )
+        .with_policy(HedgePolicy::default().with_initial_delay(Duration::from_millis(10)));
+
+        let ledger = core
+            .run(client.request(ledger::Details::new(69859)))
+            .unwrap();
+        assert_eq!(ledger.sequence(), 69859);
+        assert_eq!(secondary.hits(), 1);
+    }
+
+    #[test]
+    fn it_returns_rejections_without_hedging() {
+        let mut core = Core::new().unwrap();
+        let primary = TestServer::start(
+            "404 Not Found",
+            include_str!("../../../fixtures/before_history_error.json"),
+        );
+        let secondary = TestServer::start("200 OK", LEDGER);
+        let client = HedgedClient::new(
+            Client::new(primary.uri(), &core.handle()).unwrap(),
+            Client::new(secondary.uri(), &core.handle()).unwrap(),
+        )
+        .with_policy(HedgePolicy::default().with_initial_delay(Duration::from_millis(200)));
+
+        match core.run(client.request(ledger::Details::new(69859))) {
+            Err(Error::BadResponse(_)) => {}
+            result => panic!("Expected a bad response, got {:?}", result.is_ok()),
+        }
+        assert_eq!(secondary.hits(), 0);
+    }
+}
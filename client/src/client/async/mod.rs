@@ -0,0 +1,551 @@
+//! This module contains the client for asynchronous communcation.
+
+use super::{default_headers, Host, HORIZON_TEST_URI, HORIZON_URI};
+use endpoint::{Body, IntoRequest};
+use error::{DeserializeError, Error, Result};
+use futures::{future, Future, Stream};
+use http;
+use hyper;
+use hyper_tls::HttpsConnector;
+use resources::deserialize;
+use serde_json;
+use std::io;
+use std::time::Duration;
+use tokio_core::reactor::Handle;
+
+mod backfill;
+mod hedge;
+
+pub use self::backfill::Backfill;
+pub use self::hedge::{HedgePolicy, HedgedClient};
+
+/// A response from horizon that has yet to arrive.
+pub type ResponseFuture<T> = Box<dyn Future<Item = T, Error = Error>>;
+
+/// Settings for the pool of connections an async client keeps to horizon.
+/// Reusing connections saves a TCP and TLS handshake on every request,
+/// which matters for clients that poll frequently. Requests are sent over
+/// HTTP/1.1; HTTP/2 isn't supported.
+///
+/// ## Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use stellar_client::async::PoolConfig;
+///
+/// let pool = PoolConfig::default().with_idle_timeout(Some(Duration::from_secs(30)));
+/// assert!(pool.keep_alive());
+/// assert_eq!(pool.idle_timeout(), Some(Duration::from_secs(30)));
+///
+/// // A resolver needs at least one thread.
+/// assert_eq!(pool.with_dns_threads(0).dns_threads(), 1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolConfig {
+    keep_alive: bool,
+    idle_timeout: Option<Duration>,
+    max_idle_per_host: usize,
+    dns_threads: usize,
+}
+
+impl Default for PoolConfig {
+    fn default() -> PoolConfig {
+        PoolConfig {
+            keep_alive: true,
+            idle_timeout: Some(Duration::from_secs(90)),
+            max_idle_per_host: usize::MAX,
+            dns_threads: 4,
+        }
+    }
+}
+
+impl PoolConfig {
+    /// Sets whether connections are kept open and reused after a request
+    /// completes. Enabled by default.
+    pub fn with_keep_alive(mut self, keep_alive: bool) -> Self {
+        self.keep_alive = keep_alive;
+        self
+    }
+
+    /// Sets how long an idle connection is kept before it is closed, or
+    /// `None` to keep idle connections until horizon closes them. Defaults
+    /// to 90 seconds.
+    pub fn with_idle_timeout(mut self, idle_timeout: Option<Duration>) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Sets the most idle connections kept open to horizon at once. Extra
+    /// connections are closed once their request completes. Unlimited by
+    /// default.
+    pub fn with_max_idle_per_host(mut self, max_idle_per_host: usize) -> Self {
+        self.max_idle_per_host = max_idle_per_host;
+        self
+    }
+
+    /// Sets the number of threads used to resolve horizon's address when
+    /// opening new connections. Defaults to 4, and is never less than 1.
+    pub fn with_dns_threads(mut self, dns_threads: usize) -> Self {
+        self.dns_threads = dns_threads.max(1);
+        self
+    }
+
+    /// Returns true if connections are reused.
+    pub fn keep_alive(&self) -> bool {
+        self.keep_alive
+    }
+
+    /// How long an idle connection is kept open.
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.idle_timeout
+    }
+
+    /// The most idle connections kept open at once.
+    pub fn max_idle_per_host(&self) -> usize {
+        self.max_idle_per_host
+    }
+
+    /// The number of threads used to resolve addresses.
+    pub fn dns_threads(&self) -> usize {
+        self.dns_threads
+    }
+}
+
+/// A client that can issue requests to a horizon api.
+#[derive(Debug, Clone)]
+pub struct Client {
+    inner: hyper::Client<HttpsConnector<hyper::client::HttpConnector>>,
+    handle: Handle,
+    host: Host,
+    pool: PoolConfig,
+    allow_unknown_variants: bool,
+}
+
+impl Client {
+    /// Constructs a new stellar client.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # extern crate tokio_core;
+    /// # extern crate stellar_client;
+    /// # fn main() {
+    /// use tokio_core::reactor::Core;
+    /// use stellar_client::async::Client;
+    /// let core = Core::new().unwrap();
+    /// let client = Client::new("https://horizon-testnet.stellar.org", &core.handle()).unwrap();
+    /// # }
+    /// ```
+    pub fn new(uri: &str, handle: &Handle) -> Result<Self> {
+        // Ensure that the uri passed in can parse.
+        let _: http::Uri = uri.parse()?;
+        Self::build(Host::Other(uri.to_string()), &handle)
+    }
+
+    fn build(host: Host, handle: &Handle) -> Result<Self> {
+        Self::build_with_pool(host, handle, PoolConfig::default())
+    }
+
+    fn build_with_pool(host: Host, handle: &Handle, pool: PoolConfig) -> Result<Self> {
+        let connector = HttpsConnector::new(pool.dns_threads).map_err(|_| Error::BadSSL)?;
+        let inner = hyper::Client::builder()
+            .keep_alive(pool.keep_alive)
+            .keep_alive_timeout(pool.idle_timeout)
+            .max_idle_per_host(pool.max_idle_per_host)
+            .build(connector);
+        Ok(Client {
+            host,
+            inner,
+            handle: handle.clone(),
+            pool,
+            allow_unknown_variants: false,
+        })
+    }
+
+    /// Rebuilds the client with the given connection pool settings. Any
+    /// connections already open are dropped.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # extern crate tokio_core;
+    /// # extern crate stellar_client;
+    /// # fn main() {
+    /// use std::time::Duration;
+    /// use tokio_core::reactor::Core;
+    /// use stellar_client::async::{Client, PoolConfig};
+    /// let core = Core::new().unwrap();
+    /// let pool = PoolConfig::default().with_idle_timeout(Some(Duration::from_secs(30)));
+    /// let client = Client::horizon_test(&core.handle())
+    ///     .unwrap()
+    ///     .with_pool(pool)
+    ///     .unwrap();
+    /// assert_eq!(client.pool(), &pool);
+    /// # }
+    /// ```
+    pub fn with_pool(self, pool: PoolConfig) -> Result<Self> {
+        let client = Self::build_with_pool(self.host, &self.handle, pool)?;
+        Ok(client.with_unknown_variants(self.allow_unknown_variants))
+    }
+
+    /// The connection pool settings in use.
+    pub fn pool(&self) -> &PoolConfig {
+        &self.pool
+    }
+
+    /// Allows operations and effects of types this crate doesn't recognize
+    /// to be returned as their `Unknown` kind rather than failing the whole
+    /// response, as `sync::Client::with_unknown_variants` does.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # extern crate tokio_core;
+    /// # extern crate stellar_client;
+    /// # fn main() {
+    /// use tokio_core::reactor::Core;
+    /// use stellar_client::async::Client;
+    /// let core = Core::new().unwrap();
+    /// let client = Client::horizon_test(&core.handle())
+    ///     .unwrap()
+    ///     .with_unknown_variants(true);
+    /// assert!(client.allows_unknown_variants());
+    /// # }
+    /// ```
+    pub fn with_unknown_variants(mut self, allow: bool) -> Self {
+        self.allow_unknown_variants = allow;
+        self
+    }
+
+    /// Returns true if unknown operation and effect types are tolerated.
+    pub fn allows_unknown_variants(&self) -> bool {
+        self.allow_unknown_variants
+    }
+
+    /// Constructs a new stellar client connected to the horizon test network.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # extern crate tokio_core;
+    /// # extern crate stellar_client;
+    /// # fn main() {
+    /// use tokio_core::reactor::Core;
+    /// use stellar_client::async::Client;
+    /// let core = Core::new().unwrap();
+    /// let client = Client::horizon_test(&core.handle()).unwrap();
+    /// # }
+    /// ```
+    pub fn horizon_test(handle: &Handle) -> Result<Self> {
+        Self::build(Host::HorizonTest, &handle)
+    }
+
+    /// Returns true if this is a test client.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # extern crate tokio_core;
+    /// # extern crate stellar_client;
+    /// # fn main() {
+    /// # use tokio_core::reactor::Core;
+    /// # use stellar_client::async::Client;
+    /// # let core = Core::new().unwrap();
+    /// let client = Client::horizon_test(&core.handle()).unwrap();
+    /// assert!(!client.is_horizon());
+    /// assert!(client.is_horizon_test());
+    /// # }
+    /// ```
+    pub fn is_horizon_test(&self) -> bool {
+        self.host == Host::HorizonTest
+    }
+
+    /// Constructs a new stellar client connected to the horizon test network.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # extern crate tokio_core;
+    /// # extern crate stellar_client;
+    /// # fn main() {
+    /// use tokio_core::reactor::Core;
+    /// use stellar_client::async::Client;
+    /// let core = Core::new().unwrap();
+    /// let client = Client::horizon(&core.handle()).unwrap();
+    /// # }
+    /// ```
+    pub fn horizon(handle: &Handle) -> Result<Self> {
+        Self::build(Host::HorizonProd, &handle)
+    }
+
+    /// Returns true if this is a horizon@stellar client.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # extern crate tokio_core;
+    /// # extern crate stellar_client;
+    /// # fn main() {
+    /// # use tokio_core::reactor::Core;
+    /// # use stellar_client::async::Client;
+    /// # let core = Core::new().unwrap();
+    /// let client = Client::horizon(&core.handle()).unwrap();
+    /// assert!(client.is_horizon());
+    /// assert!(!client.is_horizon_test());
+    /// # }
+    /// ```
+    pub fn is_horizon(&self) -> bool {
+        self.host == Host::HorizonProd
+    }
+
+    /// Requests the endpoint from horizon, returning a future for the
+    /// response that runs on the client's event loop.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// # extern crate tokio_core;
+    /// # extern crate stellar_client;
+    /// # fn main() {
+    /// use tokio_core::reactor::Core;
+    /// use stellar_client::async::Client;
+    /// use stellar_client::endpoint::ledger;
+    /// let mut core = Core::new().unwrap();
+    /// let client = Client::horizon_test(&core.handle()).unwrap();
+    /// let ledgers = core.run(client.request(ledger::All::default())).unwrap();
+    /// # }
+    /// ```
+    pub fn request<E>(&self, endpoint: E) -> ResponseFuture<E::Response>
+    where
+        E: IntoRequest,
+        E::Response: 'static,
+    {
+        let request = endpoint.into_request(self.uri());
+        let path = match request {
+            Ok(ref request) => request.uri().path().to_string(),
+            Err(_) => String::new(),
+        };
+        let request = match request
+            .and_then(|request| http_to_hyper(&request))
+        {
+            Ok(request) => request,
+            Err(err) => return Box::new(future::err(err)),
+        };
+        let allow = self.allow_unknown_variants;
+        let response = self
+            .inner
+            .request(request)
+            .and_then(|response| {
+                let status = response.status();
+                response
+                    .into_body()
+                    .concat2()
+                    .map(move |body| (status, body))
+            })
+            .map_err(transport_error)
+            .and_then(move |(status, body)| {
+                let failed = |err| DeserializeError::new(status.as_u16(), &path, &body, err);
+                if status.is_success() {
+                    Ok(deserialize::from_slice(&body, allow).map_err(failed)?)
+                } else if status.is_client_error() {
+                    Err(Error::BadResponse(
+                        serde_json::from_slice(&body).map_err(failed)?,
+                    ))
+                } else {
+                    Err(Error::ServerError)
+                }
+            });
+        Box::new(response)
+    }
+
+    fn handle(&self) -> &Handle {
+        &self.handle
+    }
+
+    fn uri(&self) -> &str {
+        match self.host {
+            Host::HorizonTest => HORIZON_TEST_URI,
+            Host::HorizonProd => HORIZON_URI,
+            Host::Other(ref uri) => uri,
+        }
+    }
+}
+
+/// Converts a request from an endpoint into one hyper can send, with the
+/// content type of its body unless the endpoint set one.
+fn http_to_hyper(request: &http::Request<Body>) -> Result<hyper::Request<hyper::Body>> {
+    let mut req = hyper::Request::builder();
+    req.method(request.method().clone())
+        .uri(request.uri().clone());
+    let defaults = default_headers(request);
+    for (name, value) in request.headers().iter().chain(defaults.iter()) {
+        req.header(name, value.clone());
+    }
+    let body = match request.body().as_str() {
+        Some(body) => hyper::Body::from(body.to_string()),
+        None => hyper::Body::empty(),
+    };
+    Ok(req.body(body)?)
+}
+
+/// Reports errors sending a request or reading the response as io errors,
+/// since hyper's errors don't mean the uri was bad once the request is made.
+fn transport_error(err: hyper::Error) -> Error {
+    Error::Io(io::Error::other(err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use client::sync::test_server::TestServer;
+    use endpoint::ledger;
+    use tokio_core::reactor::Core;
+
+    #[test]
+    fn it_constructs_a_test_client() {
+        let core = Core::new().unwrap();
+        let client = Client::horizon_test(&core.handle()).unwrap();
+        assert_eq!(client.host, Host::HorizonTest);
+        assert_eq!(client.uri(), "https://horizon-testnet.stellar.org");
+    }
+
+    #[test]
+    fn it_constructs_a_horizon_client() {
+        let core = Core::new().unwrap();
+        let client = Client::horizon(&core.handle()).unwrap();
+        assert_eq!(client.host, Host::HorizonProd);
+        assert_eq!(client.uri(), "https://horizon.stellar.org");
+    }
+
+    #[test]
+    fn it_constructs_a_client_to_other() {
+        let core = Core::new().unwrap();
+        let client = Client::new("https://www.google.com", &core.handle()).unwrap();
+        assert_eq!(
+            client.host,
+            Host::Other("https://www.google.com".to_string())
+        );
+        assert_eq!(client.uri(), "https://www.google.com");
+    }
+
+    #[test]
+    fn it_constructs_a_client_with_a_pool() {
+        let core = Core::new().unwrap();
+        let pool = PoolConfig::default()
+            .with_keep_alive(false)
+            .with_max_idle_per_host(2)
+            .with_dns_threads(1);
+        let client = Client::horizon(&core.handle())
+            .unwrap()
+            .with_pool(pool)
+            .unwrap();
+        assert_eq!(client.host, Host::HorizonProd);
+        assert_eq!(client.pool(), &pool);
+        assert!(!client.pool().keep_alive());
+        assert_eq!(client.pool().max_idle_per_host(), 2);
+    }
+
+    #[test]
+    fn it_converts_requests_with_their_content_type() {
+        let request = http::Request::post("https://horizon.example.com/transactions")
+            .body(Body::Json(r#"{"tx":"AAAA"}"#.to_string()))
+            .unwrap();
+        let converted = http_to_hyper(&request).unwrap();
+        assert_eq!(converted.method(), &hyper::Method::POST);
+        assert_eq!(converted.uri().path(), "/transactions");
+        assert_eq!(
+            converted.headers().get("content-type").unwrap(),
+            "application/json"
+        );
+
+        let request = http::Request::post("https://horizon.example.com/transactions")
+            .header(http::header::CONTENT_TYPE, "text/plain")
+            .body(Body::Form("tx=AAAA".to_string()))
+            .unwrap();
+        let converted = http_to_hyper(&request).unwrap();
+        assert_eq!(
+            converted.headers().get("content-type").unwrap(),
+            "text/plain"
+        );
+
+        let request = http::Request::get("https://horizon.example.com/ledgers")
+            .body(Body::None)
+            .unwrap();
+        let converted = http_to_hyper(&request).unwrap();
+        assert!(converted.headers().get("content-type").is_none());
+    }
+
+    #[test]
+    fn it_converts_requests_of_any_method() {
+        let request = http::Request::delete("https://horizon.example.com/accounts/data")
+            .body(Body::None)
+            .unwrap();
+        let converted = http_to_hyper(&request).unwrap();
+        assert_eq!(converted.method(), &hyper::Method::DELETE);
+
+        let request = http::Request::builder()
+            .method("PURGE")
+            .uri("https://horizon.example.com/ledgers")
+            .body(Body::None)
+            .unwrap();
+        let converted = http_to_hyper(&request).unwrap();
+        assert_eq!(converted.method().as_str(), "PURGE");
+    }
+
+    #[test]
+    fn it_requests_endpoints() {
+        let server = TestServer::start("200 OK", include_str!("../../../fixtures/ledger.json"));
+        let mut core = Core::new().unwrap();
+        let client = Client::new(server.uri(), &core.handle()).unwrap();
+        let ledger = core.run(client.request(ledger::Details::new(69859))).unwrap();
+        assert_eq!(ledger.sequence(), 69859);
+        assert_eq!(server.hits(), 1);
+    }
+
+    #[test]
+    fn it_requests_endpoints_with_a_pool() {
+        let server = TestServer::start("200 OK", include_str!("../../../fixtures/ledger.json"));
+        let mut core = Core::new().unwrap();
+        let pool = PoolConfig::default()
+            .with_max_idle_per_host(0)
+            .with_dns_threads(0);
+        let client = Client::new(server.uri(), &core.handle())
+            .unwrap()
+            .with_pool(pool)
+            .unwrap();
+        for _ in 0..2 {
+            let ledger = core
+                .run(client.request(ledger::Details::new(69859)))
+                .unwrap();
+            assert_eq!(ledger.sequence(), 69859);
+        }
+        assert_eq!(server.hits(), 2);
+    }
+
+    #[test]
+    fn it_fails_requests_horizon_rejects_or_cannot_answer() {
+        let mut core = Core::new().unwrap();
+        let server = TestServer::start(
+            "404 Not Found",
+            include_str!("../../../fixtures/before_history_error.json"),
+        );
+        let client = Client::new(server.uri(), &core.handle()).unwrap();
+        match core.run(client.request(ledger::Details::new(1))) {
+            Err(Error::BadResponse(_)) => {}
+            result => panic!("Expected a bad response, got {:?}", result.is_ok()),
+        }
+
+        let client = Client::new(&TestServer::down(), &core.handle()).unwrap();
+        match core.run(client.request(ledger::Details::new(1))) {
+            Err(Error::Io(_)) => {}
+            result => panic!("Expected an io error, got {:?}", result.is_ok()),
+        }
+    }
+
+    #[test]
+    fn it_errs_if_a_bad_uri_is_provided() {
+        let core = Core::new().unwrap();
+        let result = Client::new("htps:/www", &core.handle());
+        assert!(result.is_err());
+    }
+}
@@ -5,6 +5,9 @@
 //!
 //! In contrast, the async client will return a future for execution on the
 //! event loop and will yield the returned resource as a result of a future.
+use endpoint::Body;
+use http::{self, header};
+
 #[derive(Debug, Clone, PartialEq)]
 enum Host {
     HorizonTest,
@@ -16,4 +19,20 @@ static HORIZON_TEST_URI: &'static str = "https://horizon-testnet.stellar.org";
 static HORIZON_URI: &'static str = "https://horizon.stellar.org";
 
 pub mod async;
+mod sse;
 pub mod sync;
+
+/// The headers a request is sent with unless the endpoint set them itself,
+/// which is the content type of its body.
+fn default_headers(request: &http::Request<Body>) -> header::HeaderMap {
+    let mut headers = header::HeaderMap::new();
+    if let Some(content_type) = request.body().content_type() {
+        if !request.headers().contains_key(header::CONTENT_TYPE) {
+            headers.insert(
+                header::CONTENT_TYPE,
+                header::HeaderValue::from_static(content_type),
+            );
+        }
+    }
+    headers
+}
@@ -0,0 +1,108 @@
+//! A minimal reader for the server-sent events horizon uses to stream
+//! records. Only the fields horizon sends are kept.
+//!
+//! <https://html.spec.whatwg.org/multipage/server-sent-events.html#event-stream-interpretation>
+use std::io::{self, BufRead};
+
+/// A single dispatched event.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Event {
+    pub id: Option<String>,
+    pub event: Option<String>,
+    pub data: String,
+}
+
+/// Reads events off of a buffered reader, yielding one per blank line
+/// terminated block. An incomplete block at the end of the input is dropped.
+#[derive(Debug)]
+pub(crate) struct EventReader<R> {
+    reader: R,
+}
+
+impl<R: BufRead> EventReader<R> {
+    pub fn new(reader: R) -> EventReader<R> {
+        EventReader { reader }
+    }
+}
+
+impl<R: BufRead> Iterator for EventReader<R> {
+    type Item = io::Result<Event>;
+
+    fn next(&mut self) -> Option<io::Result<Event>> {
+        let mut event = Event::default();
+        let mut has_data = false;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            match self.reader.read_line(&mut line) {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(err) => return Some(Err(err)),
+            }
+            let line = line.trim_right_matches(|c| c == '\n' || c == '\r');
+            if line.is_empty() {
+                if has_data || event.event.is_some() {
+                    return Some(Ok(event));
+                }
+                continue;
+            }
+            if line.starts_with(':') {
+                continue;
+            }
+            let (field, value) = match line.find(':') {
+                Some(index) => {
+                    let value = &line[index + 1..];
+                    (&line[..index], value.trim_left_matches(' '))
+                }
+                None => (line, ""),
+            };
+            match field {
+                "id" => event.id = Some(value.to_string()),
+                "event" => event.event = Some(value.to_string()),
+                "data" => {
+                    if has_data {
+                        event.data.push('\n');
+                    }
+                    event.data.push_str(value);
+                    has_data = true;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod event_reader_tests {
+    use super::*;
+
+    fn events(input: &str) -> Vec<Event> {
+        EventReader::new(input.as_bytes())
+            .map(Result::unwrap)
+            .collect()
+    }
+
+    #[test]
+    fn it_reads_horizon_events() {
+        let input = "retry: 1000\nevent: open\ndata: \"hello\"\n\n\
+                     id: 121\ndata: {\"id\": 121}\n\n";
+        let events = events(input);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event, Some("open".to_string()));
+        assert_eq!(events[0].data, "\"hello\"");
+        assert_eq!(events[1].id, Some("121".to_string()));
+        assert_eq!(events[1].data, "{\"id\": 121}");
+    }
+
+    #[test]
+    fn it_joins_data_lines_and_skips_comments() {
+        let events = events(": keepalive\r\n\r\ndata: a\r\ndata:b\r\n\r\n");
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].data, "a\nb");
+    }
+
+    #[test]
+    fn it_drops_an_unterminated_event() {
+        assert!(events("data: partial\n").is_empty());
+    }
+}
@@ -0,0 +1,300 @@
+use super::{is_unavailable, rebase, CircuitBreaker, CircuitState, Client, Stream};
+use endpoint::{IntoRequest, Records};
+use error::{Error, Result};
+use http::Method;
+use serde::de::DeserializeOwned;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How a `BalancedClient` picks the replica for each read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Balance {
+    /// Each read goes to the next replica in turn.
+    RoundRobin,
+    /// Each read goes to the replica that has been answering fastest.
+    /// Replicas that haven't answered yet are tried first, so that every
+    /// replica gets measured.
+    LeastLatency,
+}
+
+/// A synchronous client that spreads reads across several horizon read
+/// replicas. `GET` requests are balanced between the replicas, while
+/// submissions and streams are pinned to the first replica that is up, so
+/// that a stream or a transaction and its follow up don't hop between
+/// servers that may be a ledger apart.
+///
+/// Each replica has its own `CircuitBreaker`. A replica whose circuit is
+/// open is left out until its cooldown has passed, and once every circuit is
+/// open requests fail with `Error::CircuitOpen`. Like the `FailoverClient`,
+/// a request that a replica can't answer is retried on the next one.
+///
+/// ## Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use stellar_client::sync::{Balance, BalancedClient};
+///
+/// let client = BalancedClient::new(
+///     &[
+///         "https://horizon-replica-1.example.com",
+///         "https://horizon-replica-2.example.com",
+///     ],
+///     Balance::LeastLatency,
+/// ).unwrap()
+///     .with_failure_threshold(5)
+///     .with_cooldown(Duration::from_secs(60));
+/// assert_eq!(client.balance(), Balance::LeastLatency);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BalancedClient {
+    clients: Vec<Client>,
+    breakers: Vec<CircuitBreaker>,
+    balance: Balance,
+    next: Arc<AtomicUsize>,
+    latencies: Arc<Mutex<Vec<Option<Duration>>>>,
+}
+
+impl BalancedClient {
+    /// Constructs a client for the replicas at the given uris. Fails if
+    /// there are none or any doesn't parse. The first replica that is up is
+    /// the one submissions and streams are pinned to.
+    pub fn new(uris: &[&str], balance: Balance) -> Result<Self> {
+        let clients = uris
+            .iter()
+            .map(|uri| Client::new(uri))
+            .collect::<Result<Vec<_>>>()?;
+        Self::from_clients(clients, balance)
+    }
+
+    /// Constructs a client from clients configured separately, such as with
+    /// a `ClientBuilder`. Requests get the first client's paging defaults.
+    pub fn from_clients(clients: Vec<Client>, balance: Balance) -> Result<Self> {
+        if clients.is_empty() {
+            return Err(Error::BadUri);
+        }
+        let breakers = clients
+            .iter()
+            .map(|_| CircuitBreaker::new().with_failure_threshold(3))
+            .collect();
+        let latencies = vec![None; clients.len()];
+        Ok(BalancedClient {
+            clients,
+            breakers,
+            balance,
+            next: Arc::new(AtomicUsize::new(0)),
+            latencies: Arc::new(Mutex::new(latencies)),
+        })
+    }
+
+    /// Sets how many consecutive failures open a replica's circuit. Defaults
+    /// to 3.
+    pub fn with_failure_threshold(mut self, threshold: u32) -> Self {
+        self.breakers = self
+            .breakers
+            .into_iter()
+            .map(|breaker| breaker.with_failure_threshold(threshold))
+            .collect();
+        self
+    }
+
+    /// Sets how long a replica's circuit stays open. Defaults to 30 seconds.
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.breakers = self
+            .breakers
+            .into_iter()
+            .map(|breaker| breaker.with_cooldown(cooldown))
+            .collect();
+        self
+    }
+
+    /// How reads are spread across the replicas.
+    pub fn balance(&self) -> Balance {
+        self.balance
+    }
+
+    /// The state of the circuit of the replica at the given uri, if there
+    /// is one.
+    pub fn circuit_state(&self, uri: &str) -> Option<CircuitState> {
+        self.clients
+            .iter()
+            .position(|client| client.uri() == uri)
+            .map(|index| self.breakers[index].state())
+    }
+
+    /// Issues a request. Reads are balanced across the replicas and other
+    /// requests go to the pinned replica, moving on to the next replica when
+    /// one can't answer.
+    pub fn request<E>(&self, endpoint: E) -> Result<E::Response>
+    where
+        E: IntoRequest,
+    {
+        let first = &self.clients[0];
+        let request = first.prepare(endpoint)?;
+        let order = if *request.method() == Method::GET {
+            self.order(Some(self.balance))
+        } else {
+            self.order(None)
+        };
+        self.attempt(order, |client| {
+            client.send(&rebase(&request, first.uri(), client.uri())?)
+        })
+    }
+
+    /// Opens a stream on the pinned replica. The stream stays on that
+    /// replica, see `Client::stream`.
+    pub fn stream<E, T>(&self, endpoint: E) -> Result<Stream<T>>
+    where
+        E: IntoRequest<Response = Records<T>>,
+        T: DeserializeOwned,
+    {
+        let first = &self.clients[0];
+        let request = endpoint.into_request(first.uri())?;
+        self.attempt(self.order(None), |client| {
+            client.open(rebase(&request, first.uri(), client.uri())?)
+        })
+    }
+
+    /// The replicas to try, in order, leaving out those whose circuit is
+    /// open. Without a balance, the replicas are tried in the order they
+    /// were given, which pins requests to the first one that is up.
+    fn order(&self, balance: Option<Balance>) -> Vec<usize> {
+        let count = self.clients.len();
+        let mut order: Vec<usize> = (0..count).collect();
+        match balance {
+            None => {}
+            Some(Balance::RoundRobin) => {
+                let start = self.next.fetch_add(1, Ordering::SeqCst) % count;
+                order.rotate_left(start);
+            }
+            Some(Balance::LeastLatency) => {
+                let latencies = self.latencies.lock().expect("Latencies were poisoned");
+                order.sort_by_key(|&index| latencies[index]);
+            }
+        }
+        order.retain(|&index| self.breakers[index].state() != CircuitState::Open);
+        order
+    }
+
+    fn attempt<T, F>(&self, order: Vec<usize>, mut attempt: F) -> Result<T>
+    where
+        F: FnMut(&Client) -> Result<T>,
+    {
+        let mut last = None;
+        for index in order {
+            let started = Instant::now();
+            match self.breakers[index].call(|| attempt(&self.clients[index])) {
+                Err(err) => {
+                    if !is_unavailable(&err) {
+                        return Err(err);
+                    }
+                    last = Some(err);
+                }
+                Ok(value) => {
+                    self.measured(index, started.elapsed());
+                    return Ok(value);
+                }
+            }
+        }
+        Err(last.unwrap_or(Error::CircuitOpen))
+    }
+
+    /// Folds a response time into the replica's moving average, so that one
+    /// slow answer doesn't move all the traffic.
+    fn measured(&self, index: usize, elapsed: Duration) {
+        let mut latencies = self.latencies.lock().expect("Latencies were poisoned");
+        latencies[index] = Some(match latencies[index] {
+            Some(latency) => (latency * 4 + elapsed) / 5,
+            None => elapsed,
+        });
+    }
+}
+
+#[cfg(test)]
+mod balanced_client_tests {
+    use super::super::test_server::{TestServer, EMPTY_RECORDS};
+    use super::*;
+    use endpoint::ledger;
+
+    #[test]
+    fn it_needs_a_replica() {
+        assert!(BalancedClient::new(&[], Balance::RoundRobin).is_err());
+    }
+
+    #[test]
+    fn it_takes_turns_between_replicas() {
+        let one = TestServer::start("200 OK", EMPTY_RECORDS);
+        let two = TestServer::start("200 OK", EMPTY_RECORDS);
+        let client = BalancedClient::new(&[one.uri(), two.uri()], Balance::RoundRobin).unwrap();
+        for _ in 0..4 {
+            client.request(ledger::All::latest()).unwrap();
+        }
+        assert_eq!(one.hits(), 2);
+        assert_eq!(two.hits(), 2);
+    }
+
+    #[test]
+    fn it_prefers_the_fastest_replica() {
+        let client = BalancedClient::new(
+            &[
+                "https://one.example.com",
+                "https://two.example.com",
+                "https://three.example.com",
+            ],
+            Balance::LeastLatency,
+        )
+        .unwrap();
+        client.measured(0, Duration::from_millis(300));
+        client.measured(1, Duration::from_millis(100));
+        assert_eq!(client.order(Some(Balance::LeastLatency)), vec![2, 1, 0]);
+        client.measured(2, Duration::from_millis(200));
+        assert_eq!(client.order(Some(Balance::LeastLatency)), vec![1, 2, 0]);
+        assert_eq!(client.order(None), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn it_averages_latency() {
+        let client =
+            BalancedClient::new(&["https://one.example.com"], Balance::LeastLatency).unwrap();
+        client.measured(0, Duration::from_millis(100));
+        client.measured(0, Duration::from_millis(600));
+        assert_eq!(
+            client.latencies.lock().unwrap()[0],
+            Some(Duration::from_millis(200))
+        );
+    }
+
+    #[test]
+    fn it_leaves_out_failing_replicas() {
+        let down = TestServer::down();
+        let server = TestServer::start("200 OK", EMPTY_RECORDS);
+        let client = BalancedClient::new(&[&down, server.uri()], Balance::RoundRobin)
+            .unwrap()
+            .with_failure_threshold(1);
+
+        client.request(ledger::All::latest()).unwrap();
+        assert_eq!(client.circuit_state(&down), Some(CircuitState::Open));
+        assert_eq!(
+            client.circuit_state(server.uri()),
+            Some(CircuitState::Closed)
+        );
+        assert_eq!(client.order(None), vec![1]);
+        assert_eq!(client.order(Some(Balance::RoundRobin)), vec![1]);
+    }
+
+    #[test]
+    fn it_fails_fast_when_every_circuit_is_open() {
+        let down = TestServer::down();
+        let client = BalancedClient::new(&[&down], Balance::RoundRobin)
+            .unwrap()
+            .with_failure_threshold(1);
+        match client.request(ledger::All::latest()) {
+            Err(Error::Reqwest(_)) => {}
+            result => panic!("Expected a connection error, got {:?}", result),
+        }
+        match client.request(ledger::All::latest()) {
+            Err(Error::CircuitOpen) => {}
+            result => panic!("Expected the circuit to be open, got {:?}", result),
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use super::super::Host;
+use super::{CircuitBreaker, Client, RateLimiter, RequestSigner, StaleGuard};
+use endpoint::Direction;
+use error::Result;
+use http::Uri;
+use std::sync::Arc;
+
+/// Configures a synchronous client before it is built. Besides the horizon
+/// server to connect to, the builder holds defaults applied to every request
+/// the client makes.
+///
+/// ## Examples
+///
+/// ```
+/// use stellar_client::endpoint::Direction;
+/// use stellar_client::sync::ClientBuilder;
+///
+/// let client = ClientBuilder::horizon_test()
+///     .default_limit(200)
+///     .default_order(Direction::Desc)
+///     .build()
+///     .unwrap();
+/// assert_eq!(client.default_limit(), Some(200));
+/// assert_eq!(client.default_order(), Some(Direction::Desc));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClientBuilder {
+    host: Host,
+    allow_unknown_variants: bool,
+    capture_raw: bool,
+    check_submitted: bool,
+    default_limit: Option<u32>,
+    default_order: Option<Direction>,
+    circuit_breaker: Option<CircuitBreaker>,
+    rate_limiter: Option<RateLimiter>,
+    request_signer: Option<RequestSigner>,
+    stale_guard: Option<StaleGuard>,
+}
+
+impl ClientBuilder {
+    /// Starts a client connected to the horizon server at the given uri.
+    pub fn new(uri: &str) -> Self {
+        Self::with_host(Host::Other(uri.to_string()))
+    }
+
+    /// Starts a client connected to the horizon test network.
+    pub fn horizon_test() -> Self {
+        Self::with_host(Host::HorizonTest)
+    }
+
+    /// Starts a client connected to the horizon public network.
+    pub fn horizon() -> Self {
+        Self::with_host(Host::HorizonProd)
+    }
+
+    fn with_host(host: Host) -> Self {
+        ClientBuilder {
+            host,
+            allow_unknown_variants: false,
+            capture_raw: false,
+            check_submitted: false,
+            default_limit: None,
+            default_order: None,
+            circuit_breaker: None,
+            rate_limiter: None,
+            request_signer: None,
+            stale_guard: None,
+        }
+    }
+
+    /// Sets the page size used by listing endpoints that weren't given a
+    /// limit. Horizon allows at most 200.
+    pub fn default_limit(mut self, limit: u32) -> Self {
+        self.default_limit = Some(limit);
+        self
+    }
+
+    /// Sets the order used by listing endpoints that weren't given one.
+    pub fn default_order(mut self, order: Direction) -> Self {
+        self.default_order = Some(order);
+        self
+    }
+
+    /// Allows operations and effects of unknown types. See
+    /// `Client::with_unknown_variants`.
+    pub fn unknown_variants(mut self, allow: bool) -> Self {
+        self.allow_unknown_variants = allow;
+        self
+    }
+
+    /// Keeps the body of each response. See `Client::with_raw_capture`.
+    pub fn capture_raw(mut self, capture: bool) -> Self {
+        self.capture_raw = capture;
+        self
+    }
+
+    /// Looks a transaction up before submitting it. See
+    /// `Client::with_submission_check`.
+    pub fn submission_check(mut self, check: bool) -> Self {
+        self.check_submitted = check;
+        self
+    }
+
+    /// Sends requests through a circuit breaker. See
+    /// `Client::with_circuit_breaker`.
+    pub fn circuit_breaker(mut self, breaker: CircuitBreaker) -> Self {
+        self.circuit_breaker = Some(breaker);
+        self
+    }
+
+    /// Waits for a token from the rate limiter before each request. See
+    /// `Client::with_rate_limiter`.
+    pub fn rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Signs every request. See `Client::with_request_signer`.
+    pub fn request_signer(mut self, signer: RequestSigner) -> Self {
+        self.request_signer = Some(signer);
+        self
+    }
+
+    /// Rejects responses from a horizon server that has fallen behind. See
+    /// `Client::with_stale_guard`.
+    pub fn stale_guard(mut self, guard: StaleGuard) -> Self {
+        self.stale_guard = Some(guard);
+        self
+    }
+
+    /// Builds the client, failing if the horizon uri doesn't parse.
+    pub fn build(self) -> Result<Client> {
+        if let Host::Other(ref uri) = self.host {
+            let _: Uri = uri.parse()?;
+        }
+        let mut client = Client::build(self.host)?
+            .with_unknown_variants(self.allow_unknown_variants)
+            .with_raw_capture(self.capture_raw)
+            .with_submission_check(self.check_submitted);
+        {
+            let config = Arc::make_mut(&mut client.config);
+            config.default_limit = self.default_limit;
+            config.default_order = self.default_order;
+            config.circuit_breaker = self.circuit_breaker;
+            config.rate_limiter = self.rate_limiter;
+            config.request_signer = self.request_signer;
+            config.stale_guard = self.stale_guard;
+        }
+        Ok(client)
+    }
+}
+
+#[cfg(test)]
+mod client_builder_tests {
+    use super::*;
+
+    #[test]
+    fn it_builds_a_client_without_defaults() {
+        let client = ClientBuilder::new("https://www.google.com").build().unwrap();
+        assert_eq!(client.uri(), "https://www.google.com");
+        assert_eq!(client.default_limit(), None);
+        assert_eq!(client.default_order(), None);
+        assert!(!client.allows_unknown_variants());
+        assert!(!client.captures_raw());
+    }
+
+    #[test]
+    fn it_only_fills_in_missing_paging_params() {
+        use endpoint::{transaction, IntoRequest, Limit, Order};
+
+        let endpoint = transaction::All::default()
+            .with_limit(10)
+            .with_paging_defaults(Some(200), Some(Direction::Desc));
+        assert_eq!(endpoint.limit(), Some(10));
+        assert_eq!(endpoint.order(), Some(Direction::Desc));
+    }
+
+    #[test]
+    fn it_errs_if_a_bad_uri_is_provided() {
+        assert!(ClientBuilder::new("htps:/www").build().is_err());
+    }
+}
@@ -0,0 +1,215 @@
+use super::is_unavailable;
+use error::{Error, Result};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// The states of a `CircuitBreaker`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests go through as normal.
+    Closed,
+    /// Horizon failed too many times in a row, so requests fail straight
+    /// away with `Error::CircuitOpen` until the cooldown has passed.
+    Open,
+    /// The cooldown has passed. The next request is let through as a trial,
+    /// closing the circuit if it succeeds and opening it again if not.
+    HalfOpen,
+}
+
+/// Stops requests to a horizon server that keeps failing, so that callers
+/// fail fast with `Error::CircuitOpen` rather than each waiting on their own
+/// timeout. Only failures to answer count, such as connection errors and
+/// server errors. An error response to a bad request shows the server is up.
+///
+/// Clones share their state, so a breaker can be handed to several clients
+/// talking to the same server.
+///
+/// ## Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use stellar_client::sync::{CircuitBreaker, CircuitState, ClientBuilder};
+///
+/// let breaker = CircuitBreaker::new()
+///     .with_failure_threshold(3)
+///     .with_cooldown(Duration::from_secs(10));
+/// let client = ClientBuilder::horizon_test()
+///     .circuit_breaker(breaker.clone())
+///     .build()
+///     .unwrap();
+/// assert_eq!(breaker.state(), CircuitState::Closed);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    failures: u32,
+    opened_at: Option<Instant>,
+    trial: bool,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CircuitBreaker {
+    /// Constructs a closed breaker that opens after 5 consecutive failures
+    /// and stays open for 30 seconds.
+    pub fn new() -> Self {
+        CircuitBreaker {
+            failure_threshold: 5,
+            cooldown: Duration::from_secs(30),
+            inner: Arc::new(Mutex::new(Inner::default())),
+        }
+    }
+
+    /// Sets how many consecutive failures open the circuit.
+    pub fn with_failure_threshold(mut self, threshold: u32) -> Self {
+        self.failure_threshold = threshold.max(1);
+        self
+    }
+
+    /// Sets how long the circuit stays open before a trial request.
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// The current state of the circuit.
+    pub fn state(&self) -> CircuitState {
+        self.state_at(&self.lock(), Instant::now())
+    }
+
+    /// Runs a request through the breaker, failing with `Error::CircuitOpen`
+    /// without running it while the circuit is open.
+    pub fn call<T, F>(&self, request: F) -> Result<T>
+    where
+        F: FnOnce() -> Result<T>,
+    {
+        self.acquire(Instant::now())?;
+        let result = request();
+        let answered = match result {
+            Err(ref err) => !is_unavailable(err),
+            Ok(_) => true,
+        };
+        self.record(answered, Instant::now());
+        result
+    }
+
+    fn lock<'a>(&'a self) -> MutexGuard<'a, Inner> {
+        self.inner
+            .lock()
+            .expect("Circuit breaker state was poisoned")
+    }
+
+    fn state_at(&self, inner: &Inner, now: Instant) -> CircuitState {
+        match inner.opened_at {
+            None => CircuitState::Closed,
+            Some(opened_at) if now < opened_at + self.cooldown => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    fn acquire(&self, now: Instant) -> Result<()> {
+        let mut inner = self.lock();
+        match self.state_at(&inner, now) {
+            CircuitState::Closed => Ok(()),
+            CircuitState::Open => Err(Error::CircuitOpen),
+            // Only one trial at a time, the rest wait for its outcome.
+            CircuitState::HalfOpen if inner.trial => Err(Error::CircuitOpen),
+            CircuitState::HalfOpen => {
+                inner.trial = true;
+                Ok(())
+            }
+        }
+    }
+
+    fn record(&self, answered: bool, now: Instant) {
+        let mut inner = self.lock();
+        inner.trial = false;
+        if answered {
+            inner.failures = 0;
+            inner.opened_at = None;
+        } else {
+            inner.failures = inner.failures.saturating_add(1);
+            if inner.opened_at.is_some() || inner.failures >= self.failure_threshold {
+                inner.opened_at = Some(now);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod circuit_breaker_tests {
+    use super::*;
+
+    fn failed() -> Result<()> {
+        Err(Error::ServerError)
+    }
+
+    #[test]
+    fn it_opens_after_consecutive_failures() {
+        let breaker = CircuitBreaker::new().with_failure_threshold(2);
+        let _ = breaker.call(failed);
+        let _ = breaker.call(|| Ok(()));
+        let _ = breaker.call(failed);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        let _ = breaker.call(failed);
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        let mut ran = false;
+        match breaker.call(|| {
+            ran = true;
+            Ok(())
+        }) {
+            Err(Error::CircuitOpen) => {}
+            result => panic!("Expected the circuit to be open, got {:?}", result),
+        }
+        assert!(!ran);
+    }
+
+    #[test]
+    fn it_does_not_count_error_responses() {
+        let breaker = CircuitBreaker::new().with_failure_threshold(1);
+        let _ = breaker.call::<(), _>(|| Err(Error::BadUri));
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn it_lets_one_trial_through_after_the_cooldown() {
+        let breaker = CircuitBreaker::new().with_failure_threshold(1);
+        let now = Instant::now();
+        breaker.record(false, now);
+        let later = now + Duration::from_secs(31);
+        assert_eq!(
+            breaker.state_at(&breaker.lock(), later),
+            CircuitState::HalfOpen
+        );
+        assert!(breaker.acquire(later).is_ok());
+        assert!(breaker.acquire(later).is_err());
+
+        // A failed trial opens the circuit for another cooldown.
+        breaker.record(false, later);
+        assert_eq!(breaker.state_at(&breaker.lock(), later), CircuitState::Open);
+
+        let after = later + Duration::from_secs(31);
+        assert!(breaker.acquire(after).is_ok());
+        breaker.record(true, after);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn it_shares_state_between_clones() {
+        let breaker = CircuitBreaker::new().with_failure_threshold(1);
+        let shared = breaker.clone();
+        let _ = breaker.call(failed);
+        assert_eq!(shared.state(), CircuitState::Open);
+    }
+}
@@ -0,0 +1,239 @@
+use super::time::search;
+use super::Client;
+use chrono::{DateTime, Utc};
+use endpoint::{ledger, Direction};
+use error::Result;
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// A ledger sequence and when that ledger closed.
+type Closing = (u32, DateTime<Utc>);
+
+/// Maps ledger sequences to the times they closed and back, for time window
+/// queries on data keyed by ledger sequence, such as operations or effects.
+///
+/// Close times are kept in a small cache, so repeated lookups don't go back
+/// to horizon, and times are found with a binary search over `/ledgers` that
+/// starts from the closest ledgers already in the cache. Once the cache is
+/// full the lowest sequences are dropped first. Clones share the cache.
+///
+/// ## Examples
+///
+/// ```
+/// # extern crate chrono;
+/// # extern crate stellar_client;
+/// use chrono::{Duration, Utc};
+/// use stellar_client::sync::{Client, LedgerClock};
+///
+/// # fn main() {
+/// let clock = LedgerClock::new(Client::horizon_test().unwrap()).with_capacity(256);
+/// let now = Utc::now();
+/// let last_hour = clock.sequences_between(now - Duration::hours(1), now).unwrap();
+/// for sequence in last_hour.take(3) {
+///     println!("{} closed at {}", sequence, clock.close_time(sequence).unwrap());
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct LedgerClock {
+    client: Client,
+    capacity: usize,
+    cache: Arc<Mutex<BTreeMap<u32, DateTime<Utc>>>>,
+}
+
+impl LedgerClock {
+    /// Constructs a clock that asks the client's horizon server about
+    /// ledgers, caching the close times of up to 1024 ledgers.
+    pub fn new(client: Client) -> Self {
+        LedgerClock {
+            client,
+            capacity: 1024,
+            cache: Arc::new(Mutex::new(BTreeMap::new())),
+        }
+    }
+
+    /// Sets how many close times are cached.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// The number of close times in the cache.
+    pub fn cached(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// When the ledger with the given sequence closed.
+    pub fn close_time(&self, sequence: u32) -> Result<DateTime<Utc>> {
+        if let Some(closed_at) = self.lock().get(&sequence) {
+            return Ok(*closed_at);
+        }
+        let ledger = self.client.request(ledger::Details::new(sequence))?;
+        self.remember(sequence, ledger.closed_at());
+        Ok(ledger.closed_at())
+    }
+
+    /// The sequence of the first ledger that closed at or after the given
+    /// time. Times before horizon's history begins give the oldest ledger,
+    /// and times after the latest ledger closed give `None`.
+    pub fn sequence_at(&self, time: DateTime<Utc>) -> Result<Option<u32>> {
+        let (before, after) = self.bounds(time);
+        let before = match before {
+            Some(before) => before,
+            None => match self.edge(Direction::Asc)? {
+                Some(oldest) if oldest.1 >= time => return Ok(Some(oldest.0)),
+                Some(oldest) => oldest,
+                None => return Ok(None),
+            },
+        };
+        let after = match after {
+            Some(after) => after,
+            None => match self.edge(Direction::Desc)? {
+                Some(latest) if latest.1 < time => return Ok(None),
+                Some(latest) => latest,
+                None => return Ok(None),
+            },
+        };
+        let found = search(before, after, time, |sequence| {
+            Ok((sequence, self.close_time(sequence)?))
+        })?;
+        Ok(Some(found.0))
+    }
+
+    /// The sequences of the ledgers that closed at or after `start` and
+    /// before `end`. The range is empty when no ledger closed in between.
+    pub fn sequences_between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Range<u32>> {
+        if end <= start {
+            return Ok(0..0);
+        }
+        let first = match self.sequence_at(start)? {
+            Some(first) => first,
+            None => return Ok(0..0),
+        };
+        let end = match self.sequence_at(end)? {
+            Some(end) => end,
+            None => match self.edge(Direction::Desc)? {
+                Some(latest) => latest.0 + 1,
+                None => first,
+            },
+        };
+        Ok(first..end.max(first))
+    }
+
+    /// The closest cached ledgers that closed before the time and at or
+    /// after it. Close times only go up with the sequence.
+    fn bounds(&self, time: DateTime<Utc>) -> (Option<Closing>, Option<Closing>) {
+        let cache = self.lock();
+        let before = cache
+            .iter()
+            .take_while(|&(_, closed_at)| *closed_at < time)
+            .last()
+            .map(|(&sequence, &closed_at)| (sequence, closed_at));
+        let after = cache
+            .iter()
+            .find(|&(_, closed_at)| *closed_at >= time)
+            .map(|(&sequence, &closed_at)| (sequence, closed_at));
+        (before, after)
+    }
+
+    /// The oldest or the latest ledger, which is cached like any other.
+    fn edge(&self, order: Direction) -> Result<Option<Closing>> {
+        Ok(self.client.edge_ledger(order)?.map(|ledger| {
+            self.remember(ledger.sequence(), ledger.closed_at());
+            (ledger.sequence(), ledger.closed_at())
+        }))
+    }
+
+    fn remember(&self, sequence: u32, closed_at: DateTime<Utc>) {
+        let mut cache = self.lock();
+        cache.insert(sequence, closed_at);
+        while cache.len() > self.capacity {
+            let lowest = *cache.keys().next().expect("The cache is not empty");
+            cache.remove(&lowest);
+        }
+    }
+
+    fn lock<'a>(&'a self) -> MutexGuard<'a, BTreeMap<u32, DateTime<Utc>>> {
+        self.cache.lock().expect("Ledger clock cache was poisoned")
+    }
+}
+
+#[cfg(test)]
+mod ledger_clock_tests {
+    use super::super::test_server::TestServer;
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn closed_at(sequence: u32) -> DateTime<Utc> {
+        Utc.ymd(2018, 1, 1).and_hms(0, 0, 0) + Duration::seconds(5 * i64::from(sequence))
+    }
+
+    fn offline() -> LedgerClock {
+        LedgerClock::new(Client::new(&TestServer::down()).unwrap())
+    }
+
+    #[test]
+    fn it_caches_close_times() {
+        let server = TestServer::start("200 OK", include_str!("../../../fixtures/ledger.json"));
+        let clock = LedgerClock::new(Client::new(server.uri()).unwrap());
+        let expected = Utc.ymd(2017, 3, 23).and_hms(20, 13, 23);
+        assert_eq!(clock.close_time(69859).unwrap(), expected);
+        assert_eq!(clock.close_time(69859).unwrap(), expected);
+        assert_eq!(server.hits(), 1);
+        assert_eq!(clock.cached(), 1);
+    }
+
+    #[test]
+    fn it_resolves_times_from_the_cache() {
+        let clock = offline();
+        for sequence in 10..20 {
+            clock.remember(sequence, closed_at(sequence));
+        }
+        let time = closed_at(14) - Duration::seconds(1);
+        assert_eq!(clock.sequence_at(time).unwrap(), Some(14));
+        assert_eq!(clock.sequence_at(closed_at(15)).unwrap(), Some(15));
+        assert_eq!(
+            clock
+                .sequences_between(closed_at(12), closed_at(16))
+                .unwrap(),
+            12..16
+        );
+    }
+
+    #[test]
+    fn it_narrows_the_search_to_the_closest_cached_ledgers() {
+        let clock = offline();
+        clock.remember(100, closed_at(100));
+        clock.remember(200, closed_at(200));
+        clock.remember(300, closed_at(300));
+        let (before, after) = clock.bounds(closed_at(250));
+        assert_eq!(before, Some((200, closed_at(200))));
+        assert_eq!(after, Some((300, closed_at(300))));
+        assert_eq!(clock.bounds(closed_at(50)).0, None);
+        assert_eq!(clock.bounds(closed_at(350)).1, None);
+    }
+
+    #[test]
+    fn it_drops_the_lowest_sequences_when_full() {
+        let clock = offline().with_capacity(2);
+        clock.remember(3, closed_at(3));
+        clock.remember(1, closed_at(1));
+        clock.remember(2, closed_at(2));
+        assert_eq!(clock.cached(), 2);
+        assert_eq!(clock.bounds(closed_at(1)).1, Some((2, closed_at(2))));
+    }
+
+    #[test]
+    fn it_has_no_sequences_in_an_empty_window() {
+        let clock = offline();
+        assert_eq!(
+            clock.sequences_between(closed_at(2), closed_at(1)).unwrap(),
+            0..0
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use super::{is_unavailable, rebase, Client, Stream};
+use endpoint::{ledger, IntoRequest, Records};
+use error::{Error, Result};
+use serde::de::DeserializeOwned;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// A synchronous client for services that run redundant horizon servers.
+/// Requests go to one server at a time, and move on to the next server in
+/// order when one can't be reached or fails with a server error. Whichever
+/// server answered last is tried first from then on, and clones of the
+/// client share it.
+///
+/// Errors horizon returns for the request itself, such as a missing
+/// resource, are returned straight away since another server would give the
+/// same answer.
+///
+/// ## Examples
+///
+/// ```
+/// use stellar_client::sync::FailoverClient;
+///
+/// let client = FailoverClient::new(&[
+///     "https://horizon-testnet.stellar.org",
+///     "https://horizon-testnet.example.com",
+/// ]).unwrap();
+/// assert_eq!(client.active_uri(), "https://horizon-testnet.stellar.org");
+/// ```
+#[derive(Debug, Clone)]
+pub struct FailoverClient {
+    clients: Vec<Client>,
+    active: Arc<AtomicUsize>,
+}
+
+impl FailoverClient {
+    /// Constructs a client for the horizon servers at the given uris, in the
+    /// order they should be tried. Fails if there are none or any doesn't
+    /// parse.
+    pub fn new(uris: &[&str]) -> Result<Self> {
+        let clients = uris
+            .iter()
+            .map(|uri| Client::new(uri))
+            .collect::<Result<Vec<_>>>()?;
+        Self::from_clients(clients)
+    }
+
+    /// Constructs a client from clients configured separately, such as with
+    /// a `ClientBuilder`. Requests get the first client's paging defaults.
+    pub fn from_clients(clients: Vec<Client>) -> Result<Self> {
+        if clients.is_empty() {
+            return Err(Error::BadUri);
+        }
+        Ok(FailoverClient {
+            clients,
+            active: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    /// The uri of the server the next request is sent to first.
+    pub fn active_uri(&self) -> &str {
+        self.clients[self.active.load(Ordering::SeqCst)].uri()
+    }
+
+    /// Checks which servers are up by fetching the latest ledger from each,
+    /// and makes the first healthy one active. Returns whether each server
+    /// is healthy, in the order they were given.
+    pub fn health_check(&self) -> Vec<bool> {
+        let health: Vec<bool> = self
+            .clients
+            .iter()
+            .map(|client| client.request(ledger::All::latest()).is_ok())
+            .collect();
+        if let Some(index) = health.iter().position(|healthy| *healthy) {
+            self.active.store(index, Ordering::SeqCst);
+        }
+        health
+    }
+
+    /// Issues a request, failing over to the other servers in turn. If none
+    /// of them can answer, the last server's error is returned.
+    pub fn request<E>(&self, endpoint: E) -> Result<E::Response>
+    where
+        E: IntoRequest,
+    {
+        let first = &self.clients[0];
+        let request = first.prepare(endpoint)?;
+        self.failover(|client| client.send(&rebase(&request, first.uri(), client.uri())?))
+    }
+
+    /// Opens a stream on the first server that accepts it. The stream stays
+    /// on that server, see `Client::stream`.
+    pub fn stream<E, T>(&self, endpoint: E) -> Result<Stream<T>>
+    where
+        E: IntoRequest<Response = Records<T>>,
+        T: DeserializeOwned,
+    {
+        let first = &self.clients[0];
+        let request = endpoint.into_request(first.uri())?;
+        self.failover(|client| client.open(rebase(&request, first.uri(), client.uri())?))
+    }
+
+    fn failover<T, F>(&self, mut attempt: F) -> Result<T>
+    where
+        F: FnMut(&Client) -> Result<T>,
+    {
+        let start = self.active.load(Ordering::SeqCst);
+        let mut last = None;
+        for offset in 0..self.clients.len() {
+            let index = (start + offset) % self.clients.len();
+            match attempt(&self.clients[index]) {
+                Ok(value) => {
+                    self.active.store(index, Ordering::SeqCst);
+                    return Ok(value);
+                }
+                Err(err) => {
+                    if !is_unavailable(&err) {
+                        return Err(err);
+                    }
+                    last = Some(err);
+                }
+            }
+        }
+        Err(last.expect("There is always at least one client"))
+    }
+}
+
+#[cfg(test)]
+mod failover_client_tests {
+    use super::super::test_server::{TestServer, EMPTY_RECORDS};
+    use super::*;
+
+    #[test]
+    fn it_needs_a_server() {
+        assert!(FailoverClient::new(&[]).is_err());
+        assert!(FailoverClient::new(&["https://horizon.stellar.org", "htps:/www"]).is_err());
+    }
+
+    #[test]
+    fn it_fails_over_to_the_next_server() {
+        let failing = TestServer::start("500 Internal Server Error", "{}");
+        let server = TestServer::start("200 OK", EMPTY_RECORDS);
+        let down = TestServer::down();
+        let client = FailoverClient::new(&[&down, failing.uri(), server.uri()]).unwrap();
+
+        let ledgers = client.request(ledger::All::latest()).unwrap();
+        assert!(ledgers.records().is_empty());
+        assert_eq!(client.active_uri(), server.uri());
+        assert_eq!(failing.hits(), 1);
+
+        client.request(ledger::All::latest()).unwrap();
+        assert_eq!(failing.hits(), 1);
+        assert_eq!(server.hits(), 2);
+    }
+
+    #[test]
+    fn it_returns_request_errors_without_failing_over() {
+        let missing = TestServer::start(
+            "404 Not Found",
+            include_str!("../../../fixtures/before_history_error.json"),
+        );
+        let server = TestServer::start("200 OK", EMPTY_RECORDS);
+        let client = FailoverClient::new(&[missing.uri(), server.uri()]).unwrap();
+        match client.request(ledger::All::latest()) {
+            Err(Error::BadResponse(_)) => {}
+            result => panic!("Expected a bad response, got {:?}", result),
+        }
+        assert_eq!(server.hits(), 0);
+        assert_eq!(client.active_uri(), missing.uri());
+    }
+
+    #[test]
+    fn it_returns_the_last_error_when_all_are_down() {
+        let client = FailoverClient::new(&[&TestServer::down(), &TestServer::down()]).unwrap();
+        match client.request(ledger::All::latest()) {
+            Err(Error::Reqwest(_)) => {}
+            result => panic!("Expected a connection error, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn it_activates_the_first_healthy_server() {
+        let server = TestServer::start("200 OK", EMPTY_RECORDS);
+        let down = TestServer::down();
+        let client = FailoverClient::new(&[&down, server.uri()]).unwrap();
+        let shared = client.clone();
+        assert_eq!(client.health_check(), vec![false, true]);
+        assert_eq!(shared.active_uri(), server.uri());
+    }
+}
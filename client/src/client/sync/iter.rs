@@ -1,4 +1,4 @@
-use super::Client;
+use super::{Client, StopHandle};
 use endpoint::{IntoRequest, Records};
 use error::Result;
 use serde::de::DeserializeOwned;
@@ -29,6 +29,7 @@ where
     endpoint: E,
     records: Option<Records<T>>,
     state: State,
+    stop: Option<StopHandle>,
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -51,9 +52,17 @@ where
             endpoint,
             records: None,
             state: State::Fetching,
+            stop: None,
         }
     }
 
+    /// Ends the iterator when the handle is stopped. A page that is being
+    /// fetched is waited for, but no more records are returned from it.
+    pub fn with_stop(mut self, stop: StopHandle) -> Self {
+        self.stop = Some(stop);
+        self
+    }
+
     fn fetch(&mut self) -> Result<()> {
         // We already have records meaning we've made a request already
         if let Some(ref records) = self.records {
@@ -103,6 +112,9 @@ where
 
     fn next(&mut self) -> Option<Self::Item> {
         loop {
+            if self.stop.as_ref().map_or(false, StopHandle::is_stopped) {
+                self.state = State::EOF;
+            }
             match self.state {
                 State::EOF | State::Error => {
                     return None;
@@ -149,6 +161,24 @@ mod iterator_tests {
         assert_eq!(iter.count(), 0);
     }
 
+    #[test]
+    fn it_stops_partway_through_a_page() {
+        use super::super::test_server::TestServer;
+        use endpoint::ledger;
+
+        let ledger = include_str!("../../../fixtures/ledger.json");
+        let page = format!(r#"{{"_embedded":{{"records":[{},{}]}}}}"#, ledger, ledger);
+        let server = TestServer::start("200 OK", page);
+        let client = Client::new(server.uri()).unwrap();
+        let stop = StopHandle::new();
+        let mut iter = Iter::new(&client, ledger::All::default()).with_stop(stop.clone());
+
+        assert_eq!(iter.next().unwrap().unwrap().sequence(), 69859);
+        stop.stop();
+        assert!(iter.next().is_none());
+        assert_eq!(server.hits(), 1);
+    }
+
     #[test]
     fn it_returns_one_with_error_if_request_fails() {
         let client = Client::horizon_test().unwrap();
@@ -13,26 +13,110 @@
 //! let client = Client::new("https://horizon-testnet.stellar.org").unwrap();
 //! ```
 
-use super::{Host, HORIZON_TEST_URI, HORIZON_URI};
+use super::{default_headers, Host, HORIZON_TEST_URI, HORIZON_URI};
 use StellarError;
-use endpoint::IntoRequest;
-use error::{Error, Result};
-use http::{self, Uri};
+use chrono::Utc;
+use endpoint::transaction::{Details, Submit};
+use endpoint::{Body, Direction, IntoRequest, Records};
+use error::{DeserializeError, Error, Result};
+use http::{self, header, Uri};
 use reqwest;
+use resources::{deserialize, Submission};
+use serde::de::DeserializeOwned;
 use serde_json;
+use std::io::{BufReader, Read};
+use std::sync::Arc;
+use std::time::Instant;
+use transaction::TransactionEnvelope;
 
+mod balanced;
+mod builder;
+mod circuit;
+mod clock;
+mod failover;
 mod iter;
+mod multiplex;
+mod rate_limit;
+mod reconnect;
+mod response;
+mod resubmit;
+mod signing;
+mod stale;
+mod stop;
+mod stream;
+mod submitter;
+mod time;
+#[cfg(test)]
+pub(crate) mod test_server;
 
+pub use self::balanced::{Balance, BalancedClient};
+pub use self::builder::ClientBuilder;
+pub use self::circuit::{CircuitBreaker, CircuitState};
+pub use self::clock::LedgerClock;
+pub use self::failover::FailoverClient;
 pub use self::iter::Iter;
+pub use self::multiplex::{EffectMultiplexer, Subscription};
+pub use self::rate_limit::RateLimiter;
+pub use self::reconnect::{ConnectionState, ReconnectingStream, StreamPolicy};
+pub use self::response::Response;
+pub use self::resubmit::{ResubmitError, ResubmitErrorKind, ResubmitPolicy};
+pub use self::signing::{Credentials, CredentialsProvider, RequestSigner, SigningKey};
+pub use self::stale::{StaleGuard, Staleness};
+pub use self::stop::StopHandle;
+pub use self::stream::Stream;
+pub use self::submitter::{SubmitStatus, Submitter};
 
 /// A client that can issue requests to a horizon api in a synchronous
 /// fashion, meaning that the functions will block until the response
 /// has been formed. The overall performance of this is slightly slower
 /// than using async but will generally be simpler to implement.
+///
+/// Clients are `Send` and `Sync`, and their settings and connection pool
+/// are shared behind an `Arc`, so cloning one is cheap. A server can
+/// configure one client and hand a clone to each thread, or share it by
+/// reference, without a mutex. Clones share the connection pool and
+/// circuit breaker, and changing the settings of a clone leaves the others
+/// as they were.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use std::thread;
+/// use stellar_client::sync::Client;
+/// use stellar_client::endpoint::ledger;
+///
+/// let client = Arc::new(Client::horizon_test().unwrap());
+/// let workers: Vec<_> = (1..5)
+///     .map(|sequence| {
+///         let client = Arc::clone(&client);
+///         thread::spawn(move || client.request(ledger::Details::new(sequence)))
+///     })
+///     .collect();
+/// for worker in workers {
+///     println!("{:?}", worker.join().unwrap().unwrap().sequence());
+/// }
+/// ```
 #[derive(Debug, Clone)]
 pub struct Client {
+    config: Arc<Config>,
+}
+
+/// The settings of a client, shared between its clones until one of them
+/// changes them.
+#[derive(Debug, Clone)]
+struct Config {
     inner: reqwest::Client,
     host: Host,
+    allow_unknown_variants: bool,
+    capture_raw: bool,
+    check_submitted: bool,
+    default_limit: Option<u32>,
+    default_order: Option<Direction>,
+    circuit_breaker: Option<CircuitBreaker>,
+    rate_limiter: Option<RateLimiter>,
+    request_signer: Option<RequestSigner>,
+    stale_guard: Option<StaleGuard>,
 }
 
 impl Client {
@@ -52,7 +136,178 @@ impl Client {
 
     fn build(host: Host) -> Result<Self> {
         let inner = reqwest::Client::new();
-        Ok(Client { host, inner })
+        Ok(Client {
+            config: Arc::new(Config {
+                host,
+                inner,
+                allow_unknown_variants: false,
+                capture_raw: false,
+                check_submitted: false,
+                default_limit: None,
+                default_order: None,
+                circuit_breaker: None,
+                rate_limiter: None,
+                request_signer: None,
+                stale_guard: None,
+            }),
+        })
+    }
+
+    /// Allows operations and effects of types this crate doesn't recognize
+    /// to be returned as their `Unknown` kind rather than failing the whole
+    /// response. This keeps pages of records usable when horizon adds new
+    /// types before the crate is updated.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use stellar_client::sync::Client;
+    /// let client = Client::horizon_test().unwrap().with_unknown_variants(true);
+    /// assert!(client.allows_unknown_variants());
+    /// ```
+    pub fn with_unknown_variants(mut self, allow: bool) -> Self {
+        Arc::make_mut(&mut self.config).allow_unknown_variants = allow;
+        self
+    }
+
+    /// Returns true if unknown operation and effect types are tolerated.
+    pub fn allows_unknown_variants(&self) -> bool {
+        self.config.allow_unknown_variants
+    }
+
+    /// Keeps the body of each successful response, so that
+    /// `request_with_raw` returns it alongside the typed value. Capturing
+    /// reads the whole body into memory before deserializing it.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use stellar_client::sync::Client;
+    /// let client = Client::horizon_test().unwrap().with_raw_capture(true);
+    /// assert!(client.captures_raw());
+    /// ```
+    pub fn with_raw_capture(mut self, capture: bool) -> Self {
+        Arc::make_mut(&mut self.config).capture_raw = capture;
+        self
+    }
+
+    /// Returns true if response bodies are kept.
+    pub fn captures_raw(&self) -> bool {
+        self.config.capture_raw
+    }
+
+    /// Makes `submit` look the transaction up by its hash first, and return
+    /// the result horizon already has rather than submit it again. When a
+    /// submission times out or the connection drops, the transaction may or
+    /// may not have made it into a ledger. Submitting it again once it has
+    /// fails with `tx_bad_seq`, which hides that it succeeded.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use stellar_client::sync::Client;
+    /// let client = Client::horizon_test().unwrap().with_submission_check(true);
+    /// assert!(client.checks_submissions());
+    /// ```
+    pub fn with_submission_check(mut self, check: bool) -> Self {
+        Arc::make_mut(&mut self.config).check_submitted = check;
+        self
+    }
+
+    /// Returns true if transactions are looked up before they're submitted.
+    pub fn checks_submissions(&self) -> bool {
+        self.config.check_submitted
+    }
+
+    /// Sends requests through a circuit breaker, so that they fail fast with
+    /// `Error::CircuitOpen` while horizon keeps failing.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use stellar_client::sync::{CircuitBreaker, Client};
+    /// let client = Client::horizon_test()
+    ///     .unwrap()
+    ///     .with_circuit_breaker(CircuitBreaker::new());
+    /// assert!(client.circuit_breaker().is_some());
+    /// ```
+    pub fn with_circuit_breaker(mut self, breaker: CircuitBreaker) -> Self {
+        Arc::make_mut(&mut self.config).circuit_breaker = Some(breaker);
+        self
+    }
+
+    /// The circuit breaker requests are sent through, if any.
+    pub fn circuit_breaker(&self) -> Option<&CircuitBreaker> {
+        self.config.circuit_breaker.as_ref()
+    }
+
+    /// Waits for a token from the rate limiter before each request, so that
+    /// the clients sharing it stay under its budget together. See
+    /// `RateLimiter`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use stellar_client::sync::{Client, RateLimiter};
+    /// let client = Client::horizon_test()
+    ///     .unwrap()
+    ///     .with_rate_limiter(RateLimiter::per_second(1));
+    /// assert!(client.rate_limiter().is_some());
+    /// ```
+    pub fn with_rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        Arc::make_mut(&mut self.config).rate_limiter = Some(limiter);
+        self
+    }
+
+    /// The rate limiter requests wait on, if any.
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.config.rate_limiter.as_ref()
+    }
+
+    /// Signs every request, for horizon servers behind a proxy that only
+    /// lets signed requests through. See `RequestSigner`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use stellar_client::sync::{Client, Credentials, RequestSigner};
+    /// let client = Client::new("https://horizon.example.com")
+    ///     .unwrap()
+    ///     .with_request_signer(RequestSigner::new(Credentials::hmac("team", b"secret")));
+    /// assert!(client.request_signer().is_some());
+    /// ```
+    pub fn with_request_signer(mut self, signer: RequestSigner) -> Self {
+        Arc::make_mut(&mut self.config).request_signer = Some(signer);
+        self
+    }
+
+    /// The signer requests are signed with, if any.
+    pub fn request_signer(&self) -> Option<&RequestSigner> {
+        self.config.request_signer.as_ref()
+    }
+
+    /// Rejects responses from a horizon server that has fallen too far
+    /// behind a reference server. See `StaleGuard`.
+    pub fn with_stale_guard(mut self, guard: StaleGuard) -> Self {
+        Arc::make_mut(&mut self.config).stale_guard = Some(guard);
+        self
+    }
+
+    /// The guard responses are checked against, if any.
+    pub fn stale_guard(&self) -> Option<&StaleGuard> {
+        self.config.stale_guard.as_ref()
+    }
+
+    /// The page limit applied to listing endpoints that weren't given one.
+    /// Set with `ClientBuilder::default_limit`.
+    pub fn default_limit(&self) -> Option<u32> {
+        self.config.default_limit
+    }
+
+    /// The order applied to listing endpoints that weren't given one. Set
+    /// with `ClientBuilder::default_order`.
+    pub fn default_order(&self) -> Option<Direction> {
+        self.config.default_order
     }
 
     /// Constructs a new stellar client connected to the horizon test network.
@@ -78,7 +333,7 @@ impl Client {
     /// assert!(client.is_horizon_test());
     /// ```
     pub fn is_horizon_test(&self) -> bool {
-        self.host == Host::HorizonTest
+        self.config.host == Host::HorizonTest
     }
 
     /// Constructs a new stellar client connected to the horizon test network.
@@ -104,12 +359,11 @@ impl Client {
     /// assert!(!client.is_horizon_test());
     /// ```
     pub fn is_horizon(&self) -> bool {
-        self.host == Host::HorizonProd
+        self.config.host == Host::HorizonProd
     }
 
-    #[allow(dead_code)]
     fn uri(&self) -> &str {
-        match self.host {
+        match self.config.host {
             Host::HorizonTest => HORIZON_TEST_URI,
             Host::HorizonProd => HORIZON_URI,
             Host::Other(ref uri) => uri,
@@ -133,33 +387,270 @@ impl Client {
     where
         E: IntoRequest,
     {
-        let request = endpoint.into_request(&self.uri())?;
-        let request = Self::http_to_reqwest(&request);
-        let response = self.inner.execute(request)?;
-        if response.status().is_success() {
-            let resp: E::Response = serde_json::from_reader(response)?;
-            Ok(resp)
-        } else if response.status().is_client_error() {
-            let e: StellarError = serde_json::from_reader(response)?;
+        let request = self.prepare(endpoint)?;
+        self.send(&request)
+    }
+
+    /// Issues a request like `request`, also returning the latest ledger of
+    /// the horizon server, how long the request took, and the body horizon
+    /// sent when the client captures raw responses.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use stellar_client::sync::Client;
+    /// use stellar_client::endpoint::account;
+    /// let client = Client::horizon_test().unwrap().with_raw_capture(true);
+    /// let endpoint =
+    ///     account::Details::new("GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ");
+    /// let response = client.request_with_raw(endpoint).unwrap();
+    /// println!("{}", response.raw_str().unwrap());
+    /// ```
+    pub fn request_with_raw<E>(&self, endpoint: E) -> Result<Response<E::Response>>
+    where
+        E: IntoRequest,
+    {
+        let request = self.prepare(endpoint)?;
+        self.guard(|| self.send_unguarded(&request))
+    }
+
+    /// Submits a signed transaction, or returns the result horizon already
+    /// has for it if the client checks submissions and it has been applied.
+    /// See `with_submission_check`.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use stellar_client::keypair::Keypair;
+    /// use stellar_client::network::Network;
+    /// use stellar_client::sync::Client;
+    /// use stellar_client::transaction::{Operation, TransactionBuilder};
+    ///
+    /// let client = Client::horizon_test().unwrap().with_submission_check(true);
+    /// let keypair = Keypair::random();
+    /// let mut envelope = TransactionBuilder::new(&keypair.account_id(), 1, Network::TESTNET)
+    ///     .with_operation(Operation::bump_sequence(2))
+    ///     .build()
+    ///     .unwrap();
+    /// envelope.sign(&keypair).unwrap();
+    ///
+    /// // Safe to call again if the first attempt timed out.
+    /// let submission = client.submit(&envelope).unwrap();
+    /// println!("applied in ledger {}", submission.ledger());
+    /// ```
+    pub fn submit(&self, envelope: &TransactionEnvelope) -> Result<Submission> {
+        if self.config.check_submitted {
+            match self.request(Details::new(envelope.hash_hex())) {
+                Ok(transaction) => return Submission::from_transaction(transaction),
+                Err(Error::BadResponse(ref err)) if err.is_not_found() => {}
+                Err(err) => return Err(err),
+            }
+        }
+        self.request(Submit::from_envelope(envelope)?)
+    }
+
+    /// Turns an endpoint into a request against this client's host, with
+    /// the client's paging defaults filled in.
+    fn prepare<E>(&self, endpoint: E) -> Result<http::Request<Body>>
+    where
+        E: IntoRequest,
+    {
+        endpoint
+            .with_paging_defaults(self.config.default_limit, self.config.default_order)
+            .into_request(self.uri())
+    }
+
+    /// Sends a prepared request and deserializes the response.
+    fn send<T>(&self, request: &http::Request<Body>) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        self.guard(|| self.send_unguarded(request))
+            .map(Response::into_value)
+    }
+
+    fn send_unguarded<T>(&self, request: &http::Request<Body>) -> Result<Response<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let path = request.uri().path().to_string();
+        let request = Self::http_to_reqwest(request, &self.signature_headers(request)?)?;
+        let started = Instant::now();
+        let mut response = self.config.inner.execute(request)?;
+        let status = response.status();
+        if !status.is_success() && !status.is_client_error() {
+            return Err(Error::ServerError);
+        }
+        let latest_ledger = response
+            .headers()
+            .get("Latest-Ledger")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok());
+        if let (true, Some(guard), Some(latest_ledger)) =
+            (status.is_success(), self.stale_guard(), latest_ledger)
+        {
+            guard.check(latest_ledger)?;
+        }
+        // The body is read whole so that it can be shown if it doesn't
+        // deserialize.
+        let mut raw = Vec::new();
+        response.read_to_end(&mut raw)?;
+        let elapsed = started.elapsed();
+        let allow = self.config.allow_unknown_variants;
+        let failed = |err| DeserializeError::new(status.as_u16(), &path, &raw, err);
+        if status.is_success() {
+            let resp: T = deserialize::from_slice(&raw, allow).map_err(failed)?;
+            let raw = if self.config.capture_raw {
+                Some(raw)
+            } else {
+                None
+            };
+            Ok(Response::new(resp, raw, latest_ledger, elapsed))
+        } else {
+            let e: StellarError = serde_json::from_slice(&raw).map_err(failed)?;
+            Err(Error::BadResponse(e))
+        }
+    }
+
+    /// Opens a stream of the records returned by an endpoint. Horizon keeps
+    /// the connection open and pushes each new record as it happens, starting
+    /// after the endpoint's cursor. Use a cursor of `now` to only see new
+    /// records.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use stellar_client::sync::Client;
+    /// use stellar_client::endpoint::{ledger, Cursor, Position};
+    /// let client = Client::horizon_test().unwrap();
+    /// let ledgers = client.stream(ledger::All::default().with_cursor(Position::Now)).unwrap();
+    /// for ledger in ledgers.take(2) {
+    ///     println!("closed {}", ledger.unwrap().sequence());
+    /// }
+    /// ```
+    pub fn stream<E, T>(&self, endpoint: E) -> Result<Stream<T>>
+    where
+        E: IntoRequest<Response = Records<T>>,
+        T: DeserializeOwned,
+    {
+        let request = endpoint.into_request(self.uri())?;
+        self.open(request)
+    }
+
+    /// Opens an event stream for a prepared request.
+    fn open<T>(&self, request: http::Request<Body>) -> Result<Stream<T>>
+    where
+        T: DeserializeOwned,
+    {
+        self.guard(|| self.open_unguarded(request))
+    }
+
+    fn open_unguarded<T>(&self, mut request: http::Request<Body>) -> Result<Stream<T>>
+    where
+        T: DeserializeOwned,
+    {
+        request.headers_mut().insert(
+            header::ACCEPT,
+            header::HeaderValue::from_static("text/event-stream"),
+        );
+        let path = request.uri().path().to_string();
+        let request = Self::http_to_reqwest(&request, &self.signature_headers(&request)?)?;
+        let mut response = self.config.inner.execute(request)?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(Stream::new(
+                Box::new(BufReader::new(response)),
+                self.config.allow_unknown_variants,
+            ))
+        } else if status.is_client_error() {
+            let mut raw = Vec::new();
+            response.read_to_end(&mut raw)?;
+            let e: StellarError = serde_json::from_slice(&raw)
+                .map_err(|err| DeserializeError::new(status.as_u16(), &path, &raw, err))?;
             Err(Error::BadResponse(e))
         } else {
             Err(Error::ServerError)
         }
     }
 
-    fn http_to_reqwest<T>(request: &http::Request<T>) -> reqwest::Request {
-        use http::method::Method;
-        let method = match *request.method() {
-            Method::GET => reqwest::Method::Get,
-            _ => unimplemented!(),
+    /// Runs a request through the circuit breaker, if there is one, waiting
+    /// for the rate limiter first if there is one of those. Requests the
+    /// breaker turns away don't use up a token.
+    fn guard<T, F>(&self, request: F) -> Result<T>
+    where
+        F: FnOnce() -> Result<T>,
+    {
+        let request = || {
+            if let Some(ref limiter) = self.config.rate_limiter {
+                limiter.acquire();
+            }
+            request()
         };
-        // infalliable because it's already passed the more strenuous http crate
-        // url parsing.
-        let url: reqwest::Url = format!("{}", request.uri()).parse().unwrap();
-        reqwest::Request::new(method, url)
+        match self.config.circuit_breaker {
+            Some(ref breaker) => breaker.call(request),
+            None => request(),
+        }
+    }
+
+    /// The headers that sign the request, if the client signs requests.
+    fn signature_headers(&self, request: &http::Request<Body>) -> Result<header::HeaderMap> {
+        match self.config.request_signer {
+            Some(ref signer) => signer.headers(request, Utc::now()),
+            None => Ok(header::HeaderMap::new()),
+        }
+    }
+
+    fn http_to_reqwest(
+        request: &http::Request<Body>,
+        extra: &header::HeaderMap,
+    ) -> Result<reqwest::Request> {
+        let method: reqwest::Method = request
+            .method()
+            .as_str()
+            .parse()
+            .map_err(|_| Error::BadUri)?;
+        let url: reqwest::Url = request.uri().to_string().parse()?;
+        let mut req = reqwest::Request::new(method, url);
+        let defaults = default_headers(request);
+        for (name, value) in request.headers().iter().chain(defaults.iter()).chain(extra.iter()) {
+            req.headers_mut()
+                .set_raw(name.as_str().to_string(), value.as_bytes().to_vec());
+        }
+        if let Some(body) = request.body().as_str() {
+            *req.body_mut() = Some(body.to_string().into());
+        }
+        Ok(req)
+    }
+}
+
+/// Whether an error means the server couldn't answer, or wasn't asked
+/// because its circuit breaker is open, rather than that it answered with an
+/// error.
+fn is_unavailable(err: &Error) -> bool {
+    match *err {
+        Error::Reqwest(_)
+        | Error::Io(_)
+        | Error::ServerError
+        | Error::CircuitOpen
+        | Error::Stale(_) => true,
+        _ => false,
     }
 }
 
+/// Points a request prepared for one host at another host.
+fn rebase(request: &http::Request<Body>, from: &str, to: &str) -> Result<http::Request<Body>> {
+    let uri = request.uri().to_string();
+    if !uri.starts_with(from) {
+        return Err(Error::BadUri);
+    }
+    let uri: Uri = format!("{}{}", to, &uri[from.len()..]).parse()?;
+    let mut rebased = http::Request::new(request.body().clone());
+    *rebased.method_mut() = request.method().clone();
+    *rebased.uri_mut() = uri;
+    *rebased.headers_mut() = request.headers().clone();
+    Ok(rebased)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -168,14 +659,14 @@ mod tests {
     #[test]
     fn it_constructs_a_test_client() {
         let client = Client::horizon_test().unwrap();
-        assert_eq!(client.host, Host::HorizonTest);
+        assert_eq!(client.config.host, Host::HorizonTest);
         assert_eq!(client.uri(), "https://horizon-testnet.stellar.org");
     }
 
     #[test]
     fn it_constructs_a_horizon_client() {
         let client = Client::horizon().unwrap();
-        assert_eq!(client.host, Host::HorizonProd);
+        assert_eq!(client.config.host, Host::HorizonProd);
         assert_eq!(client.uri(), "https://horizon.stellar.org");
     }
 
@@ -183,7 +674,7 @@ mod tests {
     fn it_constructs_a_client_to_other() {
         let client = Client::new("https://www.google.com").unwrap();
         assert_eq!(
-            client.host,
+            client.config.host,
             Host::Other("https://www.google.com".to_string())
         );
         assert_eq!(client.uri(), "https://www.google.com");
@@ -195,6 +686,24 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn it_converts_requests_of_any_method() {
+        let request = http::Request::delete("https://horizon.example.com/accounts/data")
+            .body(Body::None)
+            .unwrap();
+        let converted = Client::http_to_reqwest(&request, &header::HeaderMap::new()).unwrap();
+        assert_eq!(converted.method().to_string(), "DELETE");
+
+        let request = http::Request::builder()
+            .method("PURGE")
+            .uri("https://horizon.example.com/ledgers")
+            .body(Body::None)
+            .unwrap();
+        let converted = Client::http_to_reqwest(&request, &header::HeaderMap::new()).unwrap();
+        assert_eq!(converted.method().to_string(), "PURGE");
+        assert_eq!(converted.url().path(), "/ledgers");
+    }
+
     #[test]
     fn it_can_make_a_request() {
         use endpoint::account::Details;
@@ -217,4 +726,221 @@ mod tests {
             error => panic!("Client did not return a bad response {:?}", error),
         }
     }
+
+    #[test]
+    fn it_captures_raw_responses() {
+        use super::test_server::TestServer;
+        use endpoint::ledger;
+
+        let body = include_str!("../../../fixtures/ledger.json");
+        let server = TestServer::start("200 OK", body);
+        let client = Client::new(server.uri()).unwrap();
+        let response = client.request_with_raw(ledger::Details::new(69859)).unwrap();
+        assert_eq!(response.value().sequence(), 69859);
+        assert_eq!(response.raw(), None);
+
+        let client = client.with_raw_capture(true);
+        let response = client.request_with_raw(ledger::Details::new(69859)).unwrap();
+        assert_eq!(response.raw_str(), Some(body));
+        let (ledger, raw) = response.into_parts();
+        assert_eq!(ledger.sequence(), 69859);
+        assert_eq!(raw, Some(body.as_bytes().to_vec()));
+    }
+
+    #[test]
+    fn it_reads_the_latest_ledger_header() {
+        use super::test_server::TestServer;
+        use endpoint::ledger;
+
+        let body = include_str!("../../../fixtures/ledger.json");
+        let server = TestServer::with_headers("Latest-Ledger: 70012\r\n", vec![("200 OK", body)]);
+        let client = Client::new(server.uri()).unwrap();
+        let response = client.request_with_raw(ledger::Details::new(69859)).unwrap();
+        assert_eq!(response.latest_ledger(), Some(70012));
+
+        let server = TestServer::start("200 OK", body);
+        let client = Client::new(server.uri()).unwrap();
+        let response = client.request_with_raw(ledger::Details::new(69859)).unwrap();
+        assert_eq!(response.latest_ledger(), None);
+    }
+
+    #[test]
+    fn it_reports_where_deserializing_failed() {
+        use super::test_server::TestServer;
+        use endpoint::ledger;
+
+        let server = TestServer::start("200 OK", r#"{"sequence": "soon"}"#);
+        let client = Client::new(server.uri()).unwrap();
+        match client.request(ledger::Details::new(69859)) {
+            Err(Error::Deserialize(err)) => {
+                assert_eq!(err.status(), 200);
+                assert_eq!(err.path(), "/ledgers/69859");
+                assert_eq!(err.body(), r#"{"sequence": "soon"}"#);
+            }
+            other => panic!("Expected a deserialize error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_waits_for_the_rate_limiter() {
+        use super::test_server::TestServer;
+        use endpoint::ledger;
+
+        let server = TestServer::start("200 OK", include_str!("../../../fixtures/ledger.json"));
+        let limiter = RateLimiter::per_second(1);
+        let client = Client::new(server.uri())
+            .unwrap()
+            .with_rate_limiter(limiter.lane("backfill"));
+        client.request(ledger::Details::new(69859)).unwrap();
+        assert_eq!(server.hits(), 1);
+        assert!(!limiter.try_acquire());
+    }
+
+    #[test]
+    fn it_is_shared_between_threads() {
+        use super::test_server::TestServer;
+        use endpoint::ledger;
+        use std::thread;
+
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Client>();
+
+        let server = TestServer::start("200 OK", include_str!("../../../fixtures/ledger.json"));
+        let client = Client::new(server.uri()).unwrap();
+        let workers: Vec<_> = (0..8)
+            .map(|_| {
+                let client = client.clone();
+                thread::spawn(move || client.request(ledger::Details::new(69859)))
+            })
+            .collect();
+        for worker in workers {
+            assert_eq!(worker.join().unwrap().unwrap().sequence(), 69859);
+        }
+        assert_eq!(server.hits(), 8);
+    }
+
+    #[test]
+    fn it_shares_settings_until_a_clone_changes_them() {
+        let client = Client::horizon_test().unwrap();
+        let clone = client.clone();
+        assert!(Arc::ptr_eq(&client.config, &clone.config));
+
+        let changed = clone.with_unknown_variants(true);
+        assert!(!Arc::ptr_eq(&client.config, &changed.config));
+        assert!(!client.allows_unknown_variants());
+        assert!(changed.allows_unknown_variants());
+    }
+
+    fn envelope() -> TransactionEnvelope {
+        use network::Network;
+        use transaction::{Operation, TransactionBuilder};
+        TransactionBuilder::new(
+            "GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ6",
+            1,
+            Network::TESTNET,
+        ).with_operation(Operation::bump_sequence(2))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn it_returns_a_transaction_already_submitted() {
+        use super::test_server::TestServer;
+
+        let transaction = include_str!("../../../fixtures/transactions/transaction_memo_text.json");
+        let server = TestServer::start("200 OK", transaction);
+        let client = Client::new(server.uri()).unwrap().with_submission_check(true);
+        let submission = client.submit(&envelope()).unwrap();
+        assert_eq!(
+            submission.hash(),
+            "648da0d47aa3b3b20afd4499a68f89b6d10ead8b1f38858e99b1d94b6fef6e69"
+        );
+        assert_eq!(server.hits(), 1);
+    }
+
+    #[test]
+    fn it_fails_for_a_transaction_that_already_failed() {
+        use super::test_server::TestServer;
+
+        let transaction = include_str!("../../../fixtures/transactions/transaction_failed.json");
+        let server = TestServer::start("200 OK", transaction);
+        let client = Client::new(server.uri()).unwrap().with_submission_check(true);
+        match client.submit(&envelope()) {
+            Err(Error::BadResponse(err)) => {
+                assert!(err.is_transaction_failed());
+                assert_eq!(err.transaction_result_code(), Some("tx_failed"));
+                assert_eq!(
+                    err.result_codes().unwrap().operations(),
+                    &["op_underfunded".to_string()]
+                );
+            }
+            other => panic!("Expected the transaction to have failed, got {:?}", other),
+        }
+        assert_eq!(server.hits(), 1);
+    }
+
+    #[test]
+    fn it_submits_a_transaction_horizon_does_not_have() {
+        use super::test_server::TestServer;
+
+        let server = TestServer::sequence(vec![
+            (
+                "404 Not Found",
+                include_str!("../../../fixtures/not_found_error.json"),
+            ),
+            ("200 OK", include_str!("../../../fixtures/submission.json")),
+        ]);
+        let client = ClientBuilder::new(server.uri())
+            .submission_check(true)
+            .build()
+            .unwrap();
+        assert!(client.checks_submissions());
+        let submission = client.submit(&envelope()).unwrap();
+        assert_eq!(submission.ledger(), 16751283);
+        assert_eq!(server.hits(), 2);
+    }
+
+    #[test]
+    fn it_submits_without_checking_unless_asked() {
+        use super::test_server::TestServer;
+
+        let server = TestServer::start(
+            "200 OK",
+            include_str!("../../../fixtures/submission.json"),
+        );
+        let client = Client::new(server.uri()).unwrap();
+        assert!(!client.checks_submissions());
+        client.submit(&envelope()).unwrap();
+        assert_eq!(server.hits(), 1);
+    }
+
+    #[test]
+    fn it_rebases_requests() {
+        let request = http::Request::post("https://one.example.com/transactions?limit=1")
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body(Body::Form("tx=AAAA".to_string()))
+            .unwrap();
+        let rebased = rebase(
+            &request,
+            "https://one.example.com",
+            "http://two.example.com:8000",
+        )
+        .unwrap();
+        assert_eq!(
+            rebased.uri().to_string(),
+            "http://two.example.com:8000/transactions?limit=1"
+        );
+        assert_eq!(rebased.method(), &http::Method::POST);
+        assert_eq!(rebased.headers().len(), 1);
+        match *rebased.body() {
+            Body::Form(ref form) => assert_eq!(form, "tx=AAAA"),
+            ref body => panic!("Unexpected body {:?}", body),
+        }
+        assert!(rebase(
+            &request,
+            "https://three.example.com",
+            "https://one.example.com"
+        )
+        .is_err());
+    }
 }
@@ -0,0 +1,241 @@
+use super::{Client, StopHandle, StreamPolicy};
+use endpoint::{effect, Cursor};
+use error::Result;
+use resources::Effect;
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+/// Routes effects to subscribers by account from a single stream of every
+/// effect on the network. Horizon rate limits streaming connections, so a
+/// stream for each of hundreds of accounts soon runs into the limit, while
+/// the global feed is one connection however many accounts are watched.
+///
+/// Accounts can be subscribed to before or while `run` streams the effects,
+/// which it does until stopped. Effects of accounts nobody subscribed to are
+/// dropped. Each subscription receives its effects on a channel of its own,
+/// so one that is slow to read doesn't hold up the others, but its effects
+/// are kept in memory until it reads them.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use std::thread;
+/// use stellar_client::sync::{Client, EffectMultiplexer, StreamPolicy};
+///
+/// let client = Client::horizon_test().unwrap();
+/// let multiplexer = EffectMultiplexer::new();
+/// let alice = multiplexer.subscribe("GATLAI2D7SSH6PE3HXTDPTRM4RE5VRK6HGA63K5EWP75PSANCZRFDNB5");
+/// let bob = multiplexer.subscribe("GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3");
+///
+/// let runner = multiplexer.clone();
+/// thread::spawn(move || runner.run(&client, "now", StreamPolicy::new()));
+///
+/// thread::spawn(move || {
+///     for effect in bob {
+///         println!("bob: {}", effect.kind_name());
+///     }
+/// });
+/// for effect in alice {
+///     println!("alice: {}", effect.kind_name());
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EffectMultiplexer {
+    routes: Arc<Mutex<Routes>>,
+    stop: Option<StopHandle>,
+}
+
+#[derive(Debug, Default)]
+struct Routes {
+    subscribers: HashMap<String, Vec<Sender<Effect>>>,
+    cursor: Option<String>,
+}
+
+impl EffectMultiplexer {
+    /// A multiplexer without any subscriptions.
+    pub fn new() -> EffectMultiplexer {
+        EffectMultiplexer::default()
+    }
+
+    /// Ends `run` when the handle is stopped, whether it's waiting on
+    /// horizon or waiting to reconnect.
+    pub fn with_stop(mut self, stop: StopHandle) -> Self {
+        self.stop = Some(stop);
+        self
+    }
+
+    /// Subscribes to the effects of an account. An account can have any
+    /// number of subscriptions, and each receives every effect of the
+    /// account from the time it subscribed. Dropping the subscription
+    /// unsubscribes it.
+    pub fn subscribe(&self, account_id: &str) -> Subscription {
+        let (sender, receiver) = mpsc::channel();
+        self.lock()
+            .subscribers
+            .entry(account_id.to_string())
+            .or_default()
+            .push(sender);
+        Subscription {
+            account_id: account_id.to_string(),
+            receiver,
+        }
+    }
+
+    /// The accounts with subscriptions, in order. Subscriptions that were
+    /// dropped are only noticed once an effect for their account arrives.
+    pub fn accounts(&self) -> Vec<String> {
+        let mut accounts: Vec<String> = self.lock().subscribers.keys().cloned().collect();
+        accounts.sort();
+        accounts
+    }
+
+    /// The paging token of the last effect routed. Running again from it
+    /// picks up where the multiplexer left off.
+    pub fn cursor(&self) -> Option<String> {
+        self.lock().cursor.clone()
+    }
+
+    /// Sends an effect to the subscriptions of its account, and returns how
+    /// many received it. `run` calls this for each effect it streams, and
+    /// it can be called directly to route effects fetched some other way.
+    pub fn route(&self, effect: Effect) -> usize {
+        let mut routes = self.lock();
+        routes.cursor = Some(effect.paging_token().clone());
+        let account = match effect.account() {
+            Some(account) => account.to_string(),
+            None => return 0,
+        };
+        let delivered = match routes.subscribers.get_mut(&account) {
+            Some(senders) => {
+                senders.retain(|sender| sender.send(effect.clone()).is_ok());
+                senders.len()
+            }
+            None => return 0,
+        };
+        if delivered == 0 {
+            routes.subscribers.remove(&account);
+        }
+        delivered
+    }
+
+    /// Streams every effect from the cursor, a paging token or `"now"`, and
+    /// routes each to its subscribers. The stream reconnects according to
+    /// the policy, and this returns once it is stopped or gives up, or with
+    /// the first error. Call it again with `cursor` to carry on after one.
+    pub fn run(&self, client: &Client, cursor: &str, policy: StreamPolicy) -> Result<()> {
+        let endpoint = effect::All::default().with_cursor(cursor);
+        let mut stream = client.stream_reconnecting(endpoint, policy)?;
+        if let Some(ref stop) = self.stop {
+            stream = stream.with_stop(stop.clone());
+        }
+        for effect in stream {
+            self.route(effect?);
+        }
+        Ok(())
+    }
+
+    fn lock(&self) -> MutexGuard<Routes> {
+        self.routes.lock().expect("Multiplexer lock poisoned")
+    }
+}
+
+/// The effects of one account, from `EffectMultiplexer::subscribe`. Each
+/// call to `next` waits for the account's next effect, and the iterator ends
+/// once the multiplexer and all of its clones are dropped.
+#[derive(Debug)]
+pub struct Subscription {
+    account_id: String,
+    receiver: Receiver<Effect>,
+}
+
+impl Subscription {
+    /// The account subscribed to.
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    /// The next effect if one has already arrived, without waiting.
+    pub fn try_next(&mut self) -> Option<Effect> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Waits up to the timeout for the next effect.
+    pub fn next_timeout(&mut self, timeout: Duration) -> Option<Effect> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(effect) => Some(effect),
+            Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => None,
+        }
+    }
+}
+
+impl Iterator for Subscription {
+    type Item = Effect;
+
+    fn next(&mut self) -> Option<Effect> {
+        self.receiver.recv().ok()
+    }
+}
+
+#[cfg(test)]
+mod multiplexer_tests {
+    use super::super::test_server::TestServer;
+    use super::*;
+    use serde_json;
+
+    const CREDITED: &str = "GDLGTRIBFH24364GPWPUS45GUFC2GU4ARPGWTXVCPLGTUHX3IOS3ON47";
+    const TRADER: &str = "GA6U5X6WOPNKKDKQULBR7IDHDBAQKOWPHYEC7WSXHZBFEYFD3XVZAKOO";
+
+    fn credited() -> &'static str {
+        include_str!("../../../fixtures/effects/account_credited.json")
+    }
+
+    fn trade() -> &'static str {
+        include_str!("../../../fixtures/effects/trade.json")
+    }
+
+    #[test]
+    fn it_routes_effects_to_the_subscribers_of_their_account() {
+        let multiplexer = EffectMultiplexer::new();
+        let mut first = multiplexer.subscribe(CREDITED);
+        let mut second = multiplexer.subscribe(CREDITED);
+        let mut trader = multiplexer.subscribe(TRADER);
+        assert_eq!(multiplexer.accounts(), vec![TRADER, CREDITED]);
+
+        assert_eq!(
+            multiplexer.route(serde_json::from_str(credited()).unwrap()),
+            2
+        );
+        assert_eq!(multiplexer.cursor(), Some("13563506724865-1".to_string()));
+        assert!(first.try_next().unwrap().is_account_credited());
+        assert!(second.try_next().unwrap().is_account_credited());
+        assert!(trader.try_next().is_none());
+
+        drop(trader);
+        assert_eq!(multiplexer.route(serde_json::from_str(trade()).unwrap()), 0);
+        assert_eq!(multiplexer.accounts(), vec![CREDITED]);
+        assert_eq!(multiplexer.cursor(), Some("33788507721730-2".to_string()));
+    }
+
+    #[test]
+    fn it_streams_the_global_feed_once() {
+        let body = format!(
+            "retry: 10\n\nid: 1\ndata: {}\n\nid: 2\ndata: {}\n\n",
+            trade().replace('\n', ""),
+            credited().replace('\n', "")
+        );
+        let server = TestServer::start("200 OK", body);
+        let client = Client::new(server.uri()).unwrap();
+        let multiplexer = EffectMultiplexer::new();
+        let mut subscription = multiplexer.subscribe(CREDITED);
+
+        let policy = StreamPolicy::new().with_max_reconnects(0);
+        multiplexer.run(&client, "now", policy).unwrap();
+        assert_eq!(server.hits(), 1);
+        assert_eq!(multiplexer.cursor(), Some("13563506724865-1".to_string()));
+        let effect = subscription.next_timeout(Duration::from_secs(1)).unwrap();
+        assert_eq!(effect.account(), Some(CREDITED));
+        assert!(subscription.try_next().is_none());
+    }
+}
@@ -0,0 +1,331 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+const DEFAULT_LANE: &str = "default";
+
+/// Keeps the requests of every client it's given under a budget, so that
+/// concurrent callers stay under horizon's rate limit together rather than
+/// each finding it with a `rate_limit_exceeded` error.
+///
+/// The limiter is a token bucket. A token is added at a steady rate up to
+/// the burst, and each request waits for one before it's sent. Clones share
+/// their bucket, so hand a clone to each client sharing the budget.
+///
+/// Callers waiting for tokens are served by lane. Each lane gets a token in
+/// turn, and within a lane the callers are served in the order they asked.
+/// Giving a backfill job a lane of its own keeps it from starving
+/// interactive requests, however many requests it has waiting.
+///
+/// ## Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use stellar_client::sync::{ClientBuilder, RateLimiter};
+///
+/// // Horizon allows 3600 requests an hour by default.
+/// let limiter = RateLimiter::new(3600, Duration::from_secs(3600)).with_burst(10);
+/// let backfill = ClientBuilder::horizon_test()
+///     .rate_limiter(limiter.lane("backfill"))
+///     .build()
+///     .unwrap();
+/// let interactive = ClientBuilder::horizon_test()
+///     .rate_limiter(limiter.lane("interactive"))
+///     .build()
+///     .unwrap();
+/// assert_eq!(backfill.rate_limiter().unwrap().lane_name(), "backfill");
+/// assert!(limiter.try_acquire());
+/// ```
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    lane: String,
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    bucket: Mutex<Bucket>,
+    condvar: Condvar,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    interval: u64,
+    burst: u32,
+    tokens: u32,
+    refilled: Instant,
+    next_ticket: u64,
+    lanes: VecDeque<Lane>,
+}
+
+/// The callers of a lane waiting for a token, by ticket.
+#[derive(Debug)]
+struct Lane {
+    name: String,
+    tickets: VecDeque<u64>,
+}
+
+/// Where a waiting caller stands.
+#[derive(Debug, PartialEq)]
+enum Turn {
+    /// The caller took a token and can send its request.
+    Granted,
+    /// The caller is next, and the next token is due after the duration.
+    Refill(Duration),
+    /// Callers ahead of this one are still waiting.
+    Queued,
+}
+
+impl RateLimiter {
+    /// A limiter allowing the number of requests over the period, spaced
+    /// evenly. It starts with a burst of one, so requests are never closer
+    /// together than the spacing.
+    pub fn new(requests: u32, per: Duration) -> Self {
+        let interval = (nanos(per) / u64::from(requests.max(1))).max(1);
+        RateLimiter {
+            lane: DEFAULT_LANE.to_string(),
+            shared: Arc::new(Shared {
+                bucket: Mutex::new(Bucket {
+                    interval,
+                    burst: 1,
+                    tokens: 1,
+                    refilled: Instant::now(),
+                    next_ticket: 0,
+                    lanes: VecDeque::new(),
+                }),
+                condvar: Condvar::new(),
+            }),
+        }
+    }
+
+    /// A limiter allowing the number of requests each second.
+    pub fn per_second(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(1))
+    }
+
+    /// Sets how many tokens the bucket holds, which is how many requests can
+    /// be sent at once after a quiet spell. The bucket starts full. Clones
+    /// share the setting.
+    pub fn with_burst(self, burst: u32) -> Self {
+        {
+            let mut bucket = self.lock();
+            bucket.burst = burst.max(1);
+            bucket.tokens = bucket.burst;
+        }
+        self
+    }
+
+    /// A clone that waits for tokens in the named lane. Lanes with callers
+    /// waiting take turns at the tokens, so each gets an equal share of the
+    /// budget while they're all busy. Clones made with the same name share
+    /// a lane.
+    pub fn lane(&self, name: &str) -> Self {
+        RateLimiter {
+            lane: name.to_string(),
+            shared: self.shared.clone(),
+        }
+    }
+
+    /// The name of the lane this limiter waits in, `"default"` unless it was
+    /// made with `lane`.
+    pub fn lane_name(&self) -> &str {
+        &self.lane
+    }
+
+    /// Waits for a token, taking it for a request. The client calls this
+    /// before each request it sends.
+    pub fn acquire(&self) {
+        let mut bucket = self.lock();
+        let ticket = bucket.enqueue(&self.lane);
+        loop {
+            bucket = match bucket.take(ticket, Instant::now()) {
+                Turn::Granted => break,
+                Turn::Refill(wait) => {
+                    self.shared
+                        .condvar
+                        .wait_timeout(bucket, wait)
+                        .expect("Rate limiter state was poisoned")
+                        .0
+                }
+                Turn::Queued => self
+                    .shared
+                    .condvar
+                    .wait(bucket)
+                    .expect("Rate limiter state was poisoned"),
+            };
+        }
+        // Wake the callers behind, one of which is now next.
+        self.shared.condvar.notify_all();
+    }
+
+    /// Takes a token if one is free and nobody is waiting for it, without
+    /// waiting. Returns whether a token was taken.
+    pub fn try_acquire(&self) -> bool {
+        self.lock().try_take(Instant::now())
+    }
+
+    fn lock<'a>(&'a self) -> MutexGuard<'a, Bucket> {
+        self.shared
+            .bucket
+            .lock()
+            .expect("Rate limiter state was poisoned")
+    }
+}
+
+impl Bucket {
+    /// Adds the tokens earned since the last refill, up to the burst.
+    fn refill(&mut self, now: Instant) {
+        if now <= self.refilled {
+            return;
+        }
+        let earned = nanos(now - self.refilled) / self.interval;
+        let tokens = u64::from(self.tokens) + earned;
+        if tokens >= u64::from(self.burst) {
+            self.tokens = self.burst;
+            self.refilled = now;
+        } else {
+            self.tokens = tokens as u32;
+            self.refilled += duration(earned * self.interval);
+        }
+    }
+
+    /// How long until the next token is added.
+    fn until_refill(&self, now: Instant) -> Duration {
+        let elapsed = if now > self.refilled {
+            nanos(now - self.refilled)
+        } else {
+            0
+        };
+        duration(self.interval - elapsed % self.interval)
+    }
+
+    /// Queues a caller in its lane, returning its ticket.
+    fn enqueue(&mut self, lane: &str) -> u64 {
+        let ticket = self.next_ticket;
+        self.next_ticket += 1;
+        match self.lanes.iter_mut().find(|waiting| waiting.name == lane) {
+            Some(waiting) => waiting.tickets.push_back(ticket),
+            None => self.lanes.push_back(Lane {
+                name: lane.to_string(),
+                tickets: vec![ticket].into_iter().collect(),
+            }),
+        }
+        ticket
+    }
+
+    /// Gives the caller a token if it's next and one is free. Its lane then
+    /// goes to the back of the queue, behind the other lanes waiting.
+    fn take(&mut self, ticket: u64, now: Instant) -> Turn {
+        let next = self
+            .lanes
+            .front()
+            .and_then(|lane| lane.tickets.front())
+            .cloned();
+        if next != Some(ticket) {
+            return Turn::Queued;
+        }
+        self.refill(now);
+        if self.tokens == 0 {
+            return Turn::Refill(self.until_refill(now));
+        }
+        self.tokens -= 1;
+        if let Some(mut lane) = self.lanes.pop_front() {
+            lane.tickets.pop_front();
+            if !lane.tickets.is_empty() {
+                self.lanes.push_back(lane);
+            }
+        }
+        Turn::Granted
+    }
+
+    fn try_take(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        if !self.lanes.is_empty() || self.tokens == 0 {
+            return false;
+        }
+        self.tokens -= 1;
+        true
+    }
+}
+
+fn nanos(duration: Duration) -> u64 {
+    duration.as_secs() * 1_000_000_000 + u64::from(duration.subsec_nanos())
+}
+
+fn duration(nanos: u64) -> Duration {
+    Duration::new(nanos / 1_000_000_000, (nanos % 1_000_000_000) as u32)
+}
+
+#[cfg(test)]
+mod rate_limiter_tests {
+    use super::*;
+
+    fn second() -> Duration {
+        Duration::from_secs(1)
+    }
+
+    #[test]
+    fn it_refills_tokens_up_to_the_burst() {
+        let limiter = RateLimiter::per_second(2).with_burst(3);
+        let mut bucket = limiter.lock();
+        let start = bucket.refilled;
+        assert!(bucket.try_take(start));
+        assert!(bucket.try_take(start));
+        assert!(bucket.try_take(start));
+        assert!(!bucket.try_take(start));
+        assert_eq!(bucket.until_refill(start), Duration::from_millis(500));
+
+        assert!(bucket.try_take(start + Duration::from_millis(750)));
+        assert!(!bucket.try_take(start + Duration::from_millis(750)));
+        assert_eq!(
+            bucket.until_refill(start + Duration::from_millis(750)),
+            Duration::from_millis(250)
+        );
+
+        let later = start + Duration::from_secs(60);
+        bucket.refill(later);
+        assert_eq!(bucket.tokens, 3);
+    }
+
+    #[test]
+    fn it_takes_turns_between_lanes() {
+        let limiter = RateLimiter::new(1, second());
+        let mut bucket = limiter.lock();
+        let start = bucket.refilled;
+        let backfill: Vec<_> = (0..3).map(|_| bucket.enqueue("backfill")).collect();
+        let interactive = bucket.enqueue("interactive");
+
+        assert_eq!(bucket.take(backfill[0], start), Turn::Granted);
+        assert_eq!(bucket.take(backfill[1], start), Turn::Queued);
+        assert_eq!(bucket.take(interactive, start), Turn::Refill(second()));
+        // Nobody jumps the queue while callers are waiting.
+        assert!(!bucket.try_take(start + second()));
+        assert_eq!(bucket.take(interactive, start + second()), Turn::Granted);
+        assert_eq!(
+            bucket.take(backfill[1], start + second()),
+            Turn::Refill(second())
+        );
+        assert_eq!(bucket.take(backfill[2], start + second() * 2), Turn::Queued);
+        assert_eq!(
+            bucket.take(backfill[1], start + second() * 2),
+            Turn::Granted
+        );
+        assert_eq!(
+            bucket.take(backfill[2], start + second() * 3),
+            Turn::Granted
+        );
+        assert!(bucket.lanes.is_empty());
+    }
+
+    #[test]
+    fn it_waits_for_tokens_across_clones() {
+        let limiter = RateLimiter::per_second(50);
+        let other = limiter.lane("other");
+        let started = Instant::now();
+        limiter.acquire();
+        other.acquire();
+        limiter.acquire();
+        assert!(started.elapsed() >= Duration::from_millis(40));
+        assert!(!other.try_acquire());
+    }
+}
@@ -0,0 +1,510 @@
+use super::{Client, StopHandle, Stream};
+use endpoint::{Cursor, IntoRequest, Records};
+use error::{Error, Result};
+use reqwest;
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// The state of the connection behind a `ReconnectingStream`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// The stream is connecting for the first time.
+    Connecting,
+    /// The stream is connected and waiting for records.
+    Connected,
+    /// Nothing, not even a heartbeat, arrived within the heartbeat timeout,
+    /// so the connection was dropped.
+    Stalled,
+    /// Horizon closed the connection or it broke.
+    Disconnected,
+    /// The stream is connecting again, picking up after the last record.
+    /// The attempt counts the reconnects since a record last arrived.
+    Reconnecting {
+        /// How many times the stream has tried to reconnect in a row.
+        attempt: u32,
+    },
+    /// The stream ran out of reconnect attempts and ended.
+    GaveUp,
+    /// The stream's stop handle was stopped.
+    Stopped,
+}
+
+/// How a `ReconnectingStream` watches for stalls and reconnects.
+///
+/// ## Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use stellar_client::sync::{ConnectionState, StreamPolicy};
+///
+/// let policy = StreamPolicy::new()
+///     .with_heartbeat_timeout(Duration::from_secs(20))
+///     .with_max_reconnects(10)
+///     .on_state_change(|state| {
+///         if state == ConnectionState::Stalled {
+///             eprintln!("horizon stream stalled, reconnecting");
+///         }
+///     });
+/// assert_eq!(policy.heartbeat_timeout(), Duration::from_secs(20));
+/// ```
+#[derive(Clone)]
+pub struct StreamPolicy {
+    heartbeat_timeout: Duration,
+    reconnect_delay: Duration,
+    max_reconnect_delay: Duration,
+    max_reconnects: Option<u32>,
+    on_state_change: Option<Arc<dyn Fn(ConnectionState) + Send + Sync>>,
+}
+
+impl Default for StreamPolicy {
+    fn default() -> Self {
+        StreamPolicy {
+            heartbeat_timeout: Duration::from_secs(30),
+            reconnect_delay: Duration::from_secs(1),
+            max_reconnect_delay: Duration::from_secs(30),
+            max_reconnects: None,
+            on_state_change: None,
+        }
+    }
+}
+
+impl StreamPolicy {
+    /// A policy that gives up on connections silent for 30 seconds and
+    /// reconnects without limit, waiting a second before the first attempt
+    /// and doubling the wait up to 30 seconds.
+    pub fn new() -> Self {
+        StreamPolicy::default()
+    }
+
+    /// Sets how long the stream may go without receiving anything before it
+    /// is considered stalled. Horizon sends heartbeats well within the
+    /// default, so quiet streams aren't mistaken for stalled ones.
+    pub fn with_heartbeat_timeout(mut self, timeout: Duration) -> Self {
+        self.heartbeat_timeout = timeout;
+        self
+    }
+
+    /// Sets how long to wait before the first reconnect attempt, and the
+    /// most to wait between attempts as the wait doubles.
+    pub fn with_reconnect_delay(mut self, delay: Duration, max_delay: Duration) -> Self {
+        self.reconnect_delay = delay;
+        self.max_reconnect_delay = max_delay.max(delay);
+        self
+    }
+
+    /// Sets how many times in a row the stream tries to reconnect before it
+    /// gives up. A record arriving resets the count.
+    pub fn with_max_reconnects(mut self, max_reconnects: u32) -> Self {
+        self.max_reconnects = Some(max_reconnects);
+        self
+    }
+
+    /// Calls the function whenever the connection changes state, such as to
+    /// log stalls or export them as metrics.
+    pub fn on_state_change<F>(mut self, f: F) -> Self
+    where
+        F: Fn(ConnectionState) + Send + Sync + 'static,
+    {
+        self.on_state_change = Some(Arc::new(f));
+        self
+    }
+
+    /// How long the stream may go without receiving anything.
+    pub fn heartbeat_timeout(&self) -> Duration {
+        self.heartbeat_timeout
+    }
+
+    /// How many times in a row the stream tries to reconnect, if limited.
+    pub fn max_reconnects(&self) -> Option<u32> {
+        self.max_reconnects
+    }
+
+    /// The wait before the given reconnect attempt, counting from one.
+    fn delay(&self, attempt: u32) -> Duration {
+        let mut delay = self.reconnect_delay;
+        for _ in 1..attempt {
+            delay *= 2;
+            if delay >= self.max_reconnect_delay {
+                return self.max_reconnect_delay;
+            }
+        }
+        delay
+    }
+}
+
+impl fmt::Debug for StreamPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("StreamPolicy")
+            .field("heartbeat_timeout", &self.heartbeat_timeout)
+            .field("reconnect_delay", &self.reconnect_delay)
+            .field("max_reconnect_delay", &self.max_reconnect_delay)
+            .field("max_reconnects", &self.max_reconnects)
+            .field("on_state_change", &self.on_state_change.is_some())
+            .finish()
+    }
+}
+
+/// A stream of records that survives stalls and dropped connections. When
+/// nothing arrives within the heartbeat timeout, or horizon hangs up, the
+/// stream connects again with the paging token of the last record as the
+/// cursor, so records are neither missed nor repeated.
+///
+/// Streams are opened with `Client::stream_reconnecting`. Errors connecting
+/// are retried according to the policy, and the stream only ends with an
+/// error once it gives up. Records that don't parse are yielded as errors
+/// without dropping the connection.
+pub struct ReconnectingStream<E, T> {
+    client: Client,
+    endpoint: E,
+    policy: StreamPolicy,
+    stream: Option<Stream<T>>,
+    state: ConnectionState,
+    attempt: u32,
+    cursor: Option<String>,
+    stop: Option<StopHandle>,
+    done: bool,
+}
+
+impl Client {
+    /// Opens a stream of the records returned by an endpoint that watches
+    /// for stalls and reconnects according to the policy. The connection is
+    /// made when the first record is asked for.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use stellar_client::endpoint::{payment, Cursor, Position};
+    /// use stellar_client::sync::{Client, StreamPolicy};
+    ///
+    /// let client = Client::horizon_test().unwrap();
+    /// let endpoint = payment::All::default().with_cursor(Position::Now);
+    /// let payments = client
+    ///     .stream_reconnecting(endpoint, StreamPolicy::new())
+    ///     .unwrap();
+    /// for payment in payments {
+    ///     println!("{:?}", payment.unwrap());
+    /// }
+    /// ```
+    pub fn stream_reconnecting<E, T>(
+        &self,
+        endpoint: E,
+        policy: StreamPolicy,
+    ) -> Result<ReconnectingStream<E, T>>
+    where
+        E: IntoRequest<Response = Records<T>> + Cursor + Clone,
+        T: DeserializeOwned,
+    {
+        let inner = reqwest::Client::builder()
+            .timeout(policy.heartbeat_timeout)
+            .build()?;
+        let mut client = self.clone();
+        Arc::make_mut(&mut client.config).inner = inner;
+        let cursor = endpoint.cursor().map(str::to_string);
+        Ok(ReconnectingStream {
+            client,
+            endpoint,
+            policy,
+            stream: None,
+            state: ConnectionState::Connecting,
+            attempt: 0,
+            cursor,
+            stop: None,
+            done: false,
+        })
+    }
+}
+
+impl<E, T> ReconnectingStream<E, T> {
+    /// Ends the stream when the handle is stopped, whether it's waiting on
+    /// horizon or waiting to reconnect.
+    pub fn with_stop(mut self, stop: StopHandle) -> Self {
+        self.stop = Some(stop);
+        self
+    }
+
+    /// The current state of the connection.
+    pub fn state(&self) -> ConnectionState {
+        self.state
+    }
+
+    /// The cursor the stream would reconnect from, which is the paging
+    /// token of the last record received, or the endpoint's own cursor
+    /// before any record arrives.
+    pub fn cursor(&self) -> Option<&str> {
+        self.cursor.as_ref().map(String::as_str)
+    }
+
+    fn set_state(&mut self, state: ConnectionState) {
+        self.state = state;
+        if let Some(ref f) = self.policy.on_state_change {
+            f(state);
+        }
+    }
+
+    /// Drops the connection after it stalled or broke.
+    fn drop_connection(&mut self, err: Option<&io::Error>) {
+        self.stream = None;
+        match err.map(io::Error::kind) {
+            Some(io::ErrorKind::TimedOut) | Some(io::ErrorKind::WouldBlock) => {
+                self.set_state(ConnectionState::Stalled)
+            }
+            _ => self.set_state(ConnectionState::Disconnected),
+        }
+    }
+
+    fn stopped(&self) -> bool {
+        self.stop.as_ref().map_or(false, StopHandle::is_stopped)
+    }
+
+    /// Whether the stream has used up its reconnect attempts.
+    fn exhausted(&self) -> bool {
+        self.policy
+            .max_reconnects
+            .map_or(false, |max| self.attempt >= max)
+    }
+
+    fn give_up(&mut self) {
+        self.done = true;
+        self.set_state(ConnectionState::GaveUp);
+    }
+}
+
+impl<E, T> fmt::Debug for ReconnectingStream<E, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ReconnectingStream")
+            .field("policy", &self.policy)
+            .field("state", &self.state)
+            .field("cursor", &self.cursor)
+            .finish()
+    }
+}
+
+impl<E, T> Iterator for ReconnectingStream<E, T>
+where
+    E: IntoRequest<Response = Records<T>> + Cursor + Clone,
+    T: DeserializeOwned,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        loop {
+            if self.done {
+                return None;
+            }
+            if self.stopped() {
+                self.done = true;
+                self.stream = None;
+                self.set_state(ConnectionState::Stopped);
+                return None;
+            }
+            if self.stream.is_none() {
+                if self.state != ConnectionState::Connecting {
+                    if self.exhausted() {
+                        self.give_up();
+                        return None;
+                    }
+                    self.attempt += 1;
+                    let attempt = self.attempt;
+                    self.set_state(ConnectionState::Reconnecting { attempt });
+                    let delay = self.policy.delay(attempt);
+                    match self.stop {
+                        Some(ref stop) => {
+                            stop.sleep(delay);
+                        }
+                        None => thread::sleep(delay),
+                    }
+                    if self.stopped() {
+                        continue;
+                    }
+                }
+                let endpoint = match self.cursor {
+                    Some(ref cursor) => self.endpoint.clone().with_cursor(cursor),
+                    None => self.endpoint.clone(),
+                };
+                match self.client.stream(endpoint) {
+                    Ok(stream) => {
+                        self.stream = Some(match self.stop {
+                            Some(ref stop) => stream.with_stop(stop.clone()),
+                            None => stream,
+                        });
+                        self.set_state(ConnectionState::Connected);
+                    }
+                    // Horizon won't accept a request it rejected once.
+                    Err(Error::BadResponse(err)) => {
+                        self.done = true;
+                        return Some(Err(Error::BadResponse(err)));
+                    }
+                    Err(err) => {
+                        if self.exhausted() {
+                            self.give_up();
+                            return Some(Err(err));
+                        }
+                        if self.state == ConnectionState::Connecting {
+                            self.set_state(ConnectionState::Disconnected);
+                        }
+                    }
+                }
+                continue;
+            }
+
+            let next = self.stream.as_mut().and_then(Iterator::next);
+            match next {
+                Some(Ok(record)) => {
+                    self.attempt = 0;
+                    let last_id = self.stream.as_ref().and_then(Stream::last_id);
+                    if let Some(id) = last_id {
+                        self.cursor = Some(id.to_string());
+                    }
+                    return Some(Ok(record));
+                }
+                Some(Err(Error::Io(err))) => self.drop_connection(Some(&err)),
+                Some(Err(err)) => return Some(Err(err)),
+                None if self.stopped() => {}
+                None => self.drop_connection(None),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod reconnecting_stream_tests {
+    use super::super::test_server::TestServer;
+    use super::*;
+    use endpoint::ledger;
+    use std::sync::Mutex;
+
+    fn event(id: u64) -> String {
+        let ledger = include_str!("../../../fixtures/ledger.json")
+            .replace('\n', "")
+            .replace("\"sequence\": 69859", &format!("\"sequence\": {}", id));
+        format!("id: {}\ndata: {}\n\n", id, ledger)
+    }
+
+    fn fast() -> StreamPolicy {
+        StreamPolicy::new().with_reconnect_delay(Duration::from_millis(1), Duration::from_millis(1))
+    }
+
+    #[test]
+    fn it_backs_off_between_attempts() {
+        let policy = StreamPolicy::new()
+            .with_reconnect_delay(Duration::from_millis(100), Duration::from_millis(350));
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(200));
+        assert_eq!(policy.delay(3), Duration::from_millis(350));
+        assert_eq!(policy.delay(30), Duration::from_millis(350));
+    }
+
+    #[test]
+    fn it_reconnects_from_the_last_record_when_horizon_hangs_up() {
+        let server = TestServer::sequence(vec![
+            ("200 OK", format!("retry: 10\n\n{}", event(1))),
+            ("200 OK", event(2)),
+        ]);
+        let states = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&states);
+        let policy = fast().on_state_change(move |state| seen.lock().unwrap().push(state));
+        let client = Client::new(server.uri()).unwrap();
+        let mut stream = client
+            .stream_reconnecting(ledger::All::default(), policy)
+            .unwrap();
+
+        assert_eq!(stream.next().unwrap().unwrap().sequence(), 1);
+        assert_eq!(stream.cursor(), Some("1"));
+        assert_eq!(stream.next().unwrap().unwrap().sequence(), 2);
+        assert_eq!(stream.cursor(), Some("2"));
+        assert_eq!(server.hits(), 2);
+        assert_eq!(
+            *states.lock().unwrap(),
+            vec![
+                ConnectionState::Connected,
+                ConnectionState::Disconnected,
+                ConnectionState::Reconnecting { attempt: 1 },
+                ConnectionState::Connected,
+            ]
+        );
+    }
+
+    #[test]
+    fn it_gives_up_after_the_last_attempt() {
+        let client = Client::new(&TestServer::down()).unwrap();
+        let mut stream = client
+            .stream_reconnecting(ledger::All::default(), fast().with_max_reconnects(2))
+            .unwrap();
+        assert!(stream.next().unwrap().is_err());
+        assert_eq!(stream.state(), ConnectionState::GaveUp);
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn it_does_not_retry_rejected_requests() {
+        let server = TestServer::start(
+            "404 Not Found",
+            include_str!("../../../fixtures/before_history_error.json"),
+        );
+        let client = Client::new(server.uri()).unwrap();
+        let mut stream = client
+            .stream_reconnecting(ledger::All::default(), fast())
+            .unwrap();
+        match stream.next() {
+            Some(Err(Error::BadResponse(_))) => {}
+            other => panic!(
+                "Expected a bad response, got {:?}",
+                other.map(|r| r.is_ok())
+            ),
+        }
+        assert_eq!(server.hits(), 1);
+    }
+
+    #[test]
+    fn it_stops_waiting_to_reconnect() {
+        let stop = StopHandle::new();
+        let states = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&states);
+        let stopper = stop.clone();
+        let policy = StreamPolicy::new()
+            .with_reconnect_delay(Duration::from_secs(30), Duration::from_secs(30))
+            .on_state_change(move |state| {
+                seen.lock().unwrap().push(state);
+                if let ConnectionState::Reconnecting { .. } = state {
+                    stopper.stop();
+                }
+            });
+        let client = Client::new(&TestServer::down()).unwrap();
+        let mut stream = client
+            .stream_reconnecting(ledger::All::default(), policy)
+            .unwrap()
+            .with_stop(stop);
+        assert!(stream.next().is_none());
+        assert_eq!(stream.state(), ConnectionState::Stopped);
+        assert_eq!(
+            *states.lock().unwrap(),
+            vec![
+                ConnectionState::Disconnected,
+                ConnectionState::Reconnecting { attempt: 1 },
+                ConnectionState::Stopped,
+            ]
+        );
+    }
+
+    #[test]
+    fn it_detects_stalled_streams() {
+        let server = TestServer::stalling(event(7));
+        let states = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&states);
+        let policy = fast()
+            .with_heartbeat_timeout(Duration::from_millis(200))
+            .with_max_reconnects(1)
+            .on_state_change(move |state| seen.lock().unwrap().push(state));
+        let client = Client::new(server.uri()).unwrap();
+        let mut stream = client
+            .stream_reconnecting(ledger::All::default(), policy)
+            .unwrap();
+        assert_eq!(stream.next().unwrap().unwrap().sequence(), 7);
+        assert_eq!(stream.next().unwrap().unwrap().sequence(), 7);
+        assert_eq!(states.lock().unwrap()[1], ConnectionState::Stalled);
+    }
+}
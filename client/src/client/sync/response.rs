@@ -0,0 +1,69 @@
+use std::str;
+use std::time::Duration;
+
+/// A typed response along with what else is known about it: the body horizon
+/// sent, for applications that keep the exact payload for auditing or
+/// debugging, the latest ledger the horizon server had ingested, and how long
+/// the request took. The body is only kept when the client captures raw
+/// responses, see `Client::with_raw_capture`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response<T> {
+    value: T,
+    raw: Option<Vec<u8>>,
+    latest_ledger: Option<u32>,
+    elapsed: Duration,
+}
+
+impl<T> Response<T> {
+    pub(super) fn new(
+        value: T,
+        raw: Option<Vec<u8>>,
+        latest_ledger: Option<u32>,
+        elapsed: Duration,
+    ) -> Self {
+        Response {
+            value,
+            raw,
+            latest_ledger,
+            elapsed,
+        }
+    }
+
+    /// The deserialized response.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Takes the deserialized response, dropping the raw body.
+    pub fn into_value(self) -> T {
+        self.value
+    }
+
+    /// The body exactly as horizon sent it, if the client captured it.
+    pub fn raw(&self) -> Option<&[u8]> {
+        self.raw.as_ref().map(Vec::as_slice)
+    }
+
+    /// The body as text, if the client captured it and it is utf-8, which
+    /// horizon's json always is.
+    pub fn raw_str(&self) -> Option<&str> {
+        self.raw().and_then(|raw| str::from_utf8(raw).ok())
+    }
+
+    /// The latest ledger the horizon server had ingested when it answered,
+    /// from its `Latest-Ledger` header. A server whose latest ledger is
+    /// behind others' is a lagging replica. `None` if the header was missing.
+    pub fn latest_ledger(&self) -> Option<u32> {
+        self.latest_ledger
+    }
+
+    /// How long the request took, from sending it to reading the whole body.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Splits the response into the deserialized value and the raw body.
+    pub fn into_parts(self) -> (T, Option<Vec<u8>>) {
+        (self.value, self.raw)
+    }
+}
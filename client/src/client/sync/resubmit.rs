@@ -0,0 +1,352 @@
+use super::Client;
+use error::Error;
+use resources::Submission;
+use signer::{self, Signer};
+use std::error::Error as StdError;
+use std::fmt;
+use std::sync::Arc;
+use transaction::{self, TransactionBuilder};
+use StellarError;
+
+/// Resubmits a transaction with a higher fee when horizon rejects it with
+/// `tx_insufficient_fee`, which happens when surge pricing pushes the fee
+/// needed to get into a ledger above what the transaction offered.
+///
+/// Each time the transaction is rejected, its base fee is multiplied and it
+/// is rebuilt and signed again. A rejected transaction doesn't use up its
+/// sequence number, so the rebuilt one can take its place. The spend is
+/// bounded by the number of resubmissions, a maximum total fee and an
+/// approval hook that sees every fee before it is offered. Only the
+/// transaction that makes it into a ledger is charged.
+///
+/// ## Examples
+///
+/// ```
+/// use stellar_client::sync::ResubmitPolicy;
+///
+/// let policy = ResubmitPolicy::new(3)
+///     .with_fee_multiplier(1.5)
+///     .with_max_fee(10_000)
+///     .with_approval(|fee| {
+///         println!("offering a fee of {} stroops", fee);
+///         true
+///     });
+/// assert_eq!(policy.max_resubmits(), 3);
+/// ```
+#[derive(Clone)]
+pub struct ResubmitPolicy {
+    max_resubmits: u32,
+    fee_multiplier: f64,
+    max_fee: Option<u32>,
+    approval: Option<Arc<dyn Fn(u32) -> bool + Send + Sync>>,
+}
+
+impl fmt::Debug for ResubmitPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ResubmitPolicy")
+            .field("max_resubmits", &self.max_resubmits)
+            .field("fee_multiplier", &self.fee_multiplier)
+            .field("max_fee", &self.max_fee)
+            .field("approval", &self.approval.is_some())
+            .finish()
+    }
+}
+
+impl ResubmitPolicy {
+    /// Constructs a policy that resubmits at most `max_resubmits` times,
+    /// doubling the fee each time.
+    pub fn new(max_resubmits: u32) -> Self {
+        ResubmitPolicy {
+            max_resubmits,
+            fee_multiplier: 2.0,
+            max_fee: None,
+            approval: None,
+        }
+    }
+
+    /// Sets what the base fee is multiplied by on each resubmission. The fee
+    /// always goes up by at least a stroop.
+    pub fn with_fee_multiplier(mut self, multiplier: f64) -> Self {
+        self.fee_multiplier = multiplier;
+        self
+    }
+
+    /// Caps the total fee of the transaction, in stroops. Rather than offer
+    /// more, submitting fails with `ResubmitErrorKind::FeeLimit`.
+    pub fn with_max_fee(mut self, max_fee: u32) -> Self {
+        self.max_fee = Some(max_fee);
+        self
+    }
+
+    /// Sets a hook that is given the total fee of each resubmission before
+    /// it is offered, in stroops. Returning false stops resubmitting with
+    /// `ResubmitErrorKind::FeeLimit`, such as when a budget has run out.
+    pub fn with_approval<F>(mut self, approval: F) -> Self
+    where
+        F: Fn(u32) -> bool + Send + Sync + 'static,
+    {
+        self.approval = Some(Arc::new(approval));
+        self
+    }
+
+    /// The most times a transaction is resubmitted.
+    pub fn max_resubmits(&self) -> u32 {
+        self.max_resubmits
+    }
+
+    /// Builds, signs and submits the transaction, resubmitting it with a
+    /// higher fee while horizon rejects the fee as too low.
+    pub fn submit(
+        &self,
+        client: &Client,
+        builder: &TransactionBuilder,
+        signers: &[&dyn Signer],
+    ) -> Result<Submission, ResubmitError> {
+        let mut builder = builder.clone();
+        let mut resubmits = 0;
+        loop {
+            let mut envelope = builder.build()?;
+            for signer in signers {
+                envelope.sign(*signer)?;
+            }
+            match client.submit(&envelope) {
+                Err(Error::BadResponse(ref err))
+                    if is_insufficient_fee(err) && resubmits < self.max_resubmits => {}
+                result => return Ok(result?),
+            }
+            resubmits += 1;
+
+            let base_fee = self.next_base_fee(builder.base_fee());
+            builder = builder.with_base_fee(base_fee);
+            let fee = builder.fee().ok_or_else(transaction::Error::fee_overflow)?;
+            if !self.allows(fee) {
+                return Err(ResubmitError::fee_limit(fee));
+            }
+        }
+    }
+
+    fn next_base_fee(&self, base_fee: u32) -> u32 {
+        let next = (f64::from(base_fee) * self.fee_multiplier).ceil();
+        if next >= f64::from(u32::MAX) {
+            u32::MAX
+        } else {
+            (next as u32).max(base_fee.saturating_add(1))
+        }
+    }
+
+    fn allows(&self, fee: u32) -> bool {
+        self.max_fee.map_or(true, |max_fee| fee <= max_fee)
+            && self
+                .approval
+                .as_ref()
+                .map_or(true, |approval| approval(fee))
+    }
+}
+
+pub(super) fn is_insufficient_fee(err: &StellarError) -> bool {
+    err.is_transaction_failed() && err.transaction_result_code() == Some("tx_insufficient_fee")
+}
+
+/// An error that occurs while submitting with a `ResubmitPolicy`.
+#[derive(Debug)]
+pub struct ResubmitError {
+    kind: ResubmitErrorKind,
+}
+
+impl ResubmitError {
+    /// Constructs an error for a fee that the policy won't offer.
+    pub fn fee_limit(fee: u32) -> ResubmitError {
+        ResubmitError {
+            kind: ResubmitErrorKind::FeeLimit(fee),
+        }
+    }
+
+    /// The underlying kind of error.
+    pub fn kind(&self) -> &ResubmitErrorKind {
+        &self.kind
+    }
+}
+
+/// The reasons that submitting with a `ResubmitPolicy` can fail.
+#[derive(Debug)]
+pub enum ResubmitErrorKind {
+    /// The request failed, or horizon rejected the transaction. A transaction
+    /// still rejected with `tx_insufficient_fee` after the last resubmission
+    /// ends up here.
+    Request(Error),
+    /// The transaction could not be rebuilt.
+    Transaction(transaction::Error),
+    /// The transaction could not be signed.
+    Signer(signer::Error),
+    /// Resubmitting would have offered a total fee, in stroops, above the
+    /// maximum or that the approval hook declined.
+    FeeLimit(u32),
+}
+
+impl From<Error> for ResubmitError {
+    fn from(inner: Error) -> ResubmitError {
+        ResubmitError {
+            kind: ResubmitErrorKind::Request(inner),
+        }
+    }
+}
+
+impl From<transaction::Error> for ResubmitError {
+    fn from(inner: transaction::Error) -> ResubmitError {
+        ResubmitError {
+            kind: ResubmitErrorKind::Transaction(inner),
+        }
+    }
+}
+
+impl From<signer::Error> for ResubmitError {
+    fn from(inner: signer::Error) -> ResubmitError {
+        ResubmitError {
+            kind: ResubmitErrorKind::Signer(inner),
+        }
+    }
+}
+
+impl StdError for ResubmitError {
+    fn description(&self) -> &str {
+        match self.kind {
+            ResubmitErrorKind::Request(ref inner) => inner.description(),
+            ResubmitErrorKind::Transaction(ref inner) => inner.description(),
+            ResubmitErrorKind::Signer(ref inner) => inner.description(),
+            ResubmitErrorKind::FeeLimit(_) => "The fee needed is more than allowed",
+        }
+    }
+}
+
+impl fmt::Display for ResubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ResubmitErrorKind::Request(ref inner) => write!(f, "{}", inner),
+            ResubmitErrorKind::Transaction(ref inner) => write!(f, "{}", inner),
+            ResubmitErrorKind::Signer(ref inner) => write!(f, "{}", inner),
+            ResubmitErrorKind::FeeLimit(fee) => {
+                write!(f, "A fee of {} stroops is more than allowed", fee)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod resubmit_policy_tests {
+    use super::super::test_server::TestServer;
+    use super::*;
+    use keypair::Keypair;
+    use network::Network;
+    use resources::{Amount, AssetIdentifier};
+    use std::sync::Mutex;
+    use transaction::Operation;
+
+    const SEED: &str = "SBGWSG6BTNCKCOB3DIFBGCVMUPQFYPA2G4O34RMTB343OYPXU5DJDVMN";
+    const INSUFFICIENT_FEE: &str = include_str!("../../../fixtures/transaction_failed_error.json");
+    const SUBMISSION: &str = include_str!("../../../fixtures/submission.json");
+
+    fn builder(keypair: &Keypair) -> TransactionBuilder {
+        TransactionBuilder::new(
+            &keypair.account_id(),
+            41,
+            Network::TESTNET,
+        )
+        .with_operation(Operation::payment(
+            "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3",
+            AssetIdentifier::native(),
+            Amount::new(10_000_000),
+        ))
+        .with_operation(Operation::payment(
+            "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3",
+            AssetIdentifier::native(),
+            Amount::new(20_000_000),
+        ))
+    }
+
+    fn insufficient_fee_then_applied(rejections: usize) -> TestServer {
+        let mut responses = vec![("400 Bad Request", INSUFFICIENT_FEE); rejections];
+        responses.push(("200 OK", SUBMISSION));
+        TestServer::sequence(responses)
+    }
+
+    #[test]
+    fn it_raises_the_fee_until_the_transaction_is_applied() {
+        let server = insufficient_fee_then_applied(2);
+        let client = Client::new(server.uri()).unwrap();
+        let keypair = Keypair::from_secret_seed(SEED).unwrap();
+        let offered = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&offered);
+        let policy = ResubmitPolicy::new(3).with_approval(move |fee| {
+            recorded.lock().unwrap().push(fee);
+            true
+        });
+
+        let submission = policy
+            .submit(&client, &builder(&keypair), &[&keypair])
+            .unwrap();
+        assert_eq!(submission.ledger(), 16751283);
+        assert_eq!(server.hits(), 3);
+        assert_eq!(*offered.lock().unwrap(), vec![400, 800]);
+    }
+
+    #[test]
+    fn it_gives_up_after_the_last_resubmission() {
+        let server = insufficient_fee_then_applied(3);
+        let client = Client::new(server.uri()).unwrap();
+        let keypair = Keypair::from_secret_seed(SEED).unwrap();
+
+        let err = ResubmitPolicy::new(1)
+            .submit(&client, &builder(&keypair), &[&keypair])
+            .unwrap_err();
+        match *err.kind() {
+            ResubmitErrorKind::Request(Error::BadResponse(ref err)) => {
+                assert!(is_insufficient_fee(err))
+            }
+            ref kind => panic!("Expected an insufficient fee, got {:?}", kind),
+        }
+        assert_eq!(server.hits(), 2);
+    }
+
+    #[test]
+    fn it_stops_at_the_maximum_fee() {
+        let server = insufficient_fee_then_applied(1);
+        let client = Client::new(server.uri()).unwrap();
+        let keypair = Keypair::from_secret_seed(SEED).unwrap();
+
+        let err = ResubmitPolicy::new(3)
+            .with_fee_multiplier(1.5)
+            .with_max_fee(250)
+            .submit(&client, &builder(&keypair), &[&keypair])
+            .unwrap_err();
+        match *err.kind() {
+            ResubmitErrorKind::FeeLimit(300) => {}
+            ref kind => panic!("Expected the fee limit, got {:?}", kind),
+        }
+        assert_eq!(server.hits(), 1);
+    }
+
+    #[test]
+    fn it_stops_when_the_fee_is_not_approved() {
+        let server = insufficient_fee_then_applied(1);
+        let client = Client::new(server.uri()).unwrap();
+        let keypair = Keypair::from_secret_seed(SEED).unwrap();
+
+        let err = ResubmitPolicy::new(3)
+            .with_approval(|_| false)
+            .submit(&client, &builder(&keypair), &[&keypair])
+            .unwrap_err();
+        match *err.kind() {
+            ResubmitErrorKind::FeeLimit(400) => {}
+            ref kind => panic!("Expected the fee limit, got {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn it_raises_the_fee_by_at_least_a_stroop() {
+        let policy = ResubmitPolicy::new(1).with_fee_multiplier(1.0);
+        assert_eq!(policy.next_base_fee(100), 101);
+        let policy = ResubmitPolicy::new(1).with_fee_multiplier(1.25);
+        assert_eq!(policy.next_base_fee(100), 125);
+        assert_eq!(policy.next_base_fee(u32::MAX), u32::MAX);
+    }
+}
@@ -0,0 +1,319 @@
+use base64;
+use chrono::{DateTime, Utc};
+use endpoint::Body;
+use error::Result;
+use http::{self, header};
+use openssl::error::ErrorStack;
+use openssl::hash::MessageDigest;
+use openssl::pkey::PKey;
+use openssl::sign;
+use sha2::{Digest, Sha256};
+use signer::{self, Signer};
+use std::fmt;
+use std::sync::Arc;
+use zeroize::Zeroizing;
+
+/// The key a request is signed with.
+#[derive(Clone)]
+pub enum SigningKey {
+    /// A secret shared with the proxy, signing with HMAC-SHA256. The secret
+    /// is zeroed when dropped.
+    Hmac(Zeroizing<Vec<u8>>),
+    /// An ed25519 signer, such as a `Keypair`, which signs the sha256 hash
+    /// of the signing string.
+    Ed25519(Arc<dyn Signer + Send + Sync>),
+}
+
+impl SigningKey {
+    /// The name of the algorithm in the `Authorization` header.
+    pub fn algorithm(&self) -> &'static str {
+        match *self {
+            SigningKey::Hmac(_) => "hmac-sha256",
+            SigningKey::Ed25519(_) => "ed25519-sha256",
+        }
+    }
+
+    fn sign(&self, message: &[u8]) -> ::std::result::Result<Vec<u8>, signer::Error> {
+        match *self {
+            SigningKey::Hmac(ref secret) => {
+                hmac_sha256(secret, message).map_err(|err| signer::Error::device(&err.to_string()))
+            }
+            SigningKey::Ed25519(ref signer) => {
+                let mut hash = [0; 32];
+                hash.copy_from_slice(&Sha256::digest(message));
+                signer.sign_hash(&hash)
+            }
+        }
+    }
+}
+
+impl fmt::Debug for SigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SigningKey::Hmac(_) => f.write_str("Hmac(..)"),
+            SigningKey::Ed25519(ref signer) => {
+                write!(f, "Ed25519({})", signer.public_key().account_id())
+            }
+        }
+    }
+}
+
+/// A key and the id the proxy knows it by.
+#[derive(Debug, Clone)]
+pub struct Credentials {
+    key_id: String,
+    key: SigningKey,
+}
+
+impl Credentials {
+    /// Credentials for a secret shared with the proxy.
+    pub fn hmac(key_id: &str, secret: &[u8]) -> Credentials {
+        Credentials {
+            key_id: key_id.to_string(),
+            key: SigningKey::Hmac(Zeroizing::new(secret.to_vec())),
+        }
+    }
+
+    /// Credentials for an ed25519 key, whose public key the proxy holds.
+    pub fn ed25519<S>(key_id: &str, signer: S) -> Credentials
+    where
+        S: Signer + Send + Sync + 'static,
+    {
+        Credentials {
+            key_id: key_id.to_string(),
+            key: SigningKey::Ed25519(Arc::new(signer)),
+        }
+    }
+
+    /// The id of the key, sent as the `keyId` of the signature.
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    /// The key requests are signed with.
+    pub fn key(&self) -> &SigningKey {
+        &self.key
+    }
+}
+
+/// Supplies the credentials to sign each request with, so that keys can be
+/// rotated or fetched from a secret store while the client is running.
+/// Fixed credentials provide themselves.
+pub trait CredentialsProvider: Send + Sync {
+    /// The credentials to sign the next request with.
+    fn credentials(&self) -> ::std::result::Result<Credentials, signer::Error>;
+}
+
+impl CredentialsProvider for Credentials {
+    fn credentials(&self) -> ::std::result::Result<Credentials, signer::Error> {
+        Ok(self.clone())
+    }
+}
+
+/// Signs outgoing requests for horizon servers behind an access controlled
+/// proxy. Each request gets a `Date` header and an `Authorization` header
+/// carrying a signature over the method, path and date:
+///
+/// ```text
+/// Authorization: Signature keyId="KEY_ID",algorithm="hmac-sha256",
+///     headers="(request-target) date",signature="BASE64"
+/// ```
+///
+/// The signed string follows HTTP message signatures, with a line for the
+/// request target and one for the date:
+///
+/// ```text
+/// (request-target): get /accounts/GABC?limit=10
+/// date: Tue, 07 Jun 2022 20:51:35 GMT
+/// ```
+///
+/// The host isn't signed, so signatures stay valid for any of the servers a
+/// `FailoverClient` or `BalancedClient` tries.
+///
+/// ## Examples
+///
+/// ```
+/// use stellar_client::sync::{ClientBuilder, Credentials, RequestSigner};
+///
+/// let signer = RequestSigner::new(Credentials::hmac("my-team", b"shared secret"));
+/// let client = ClientBuilder::new("https://horizon.example.com")
+///     .request_signer(signer)
+///     .build()
+///     .unwrap();
+/// assert!(client.request_signer().is_some());
+/// ```
+#[derive(Clone)]
+pub struct RequestSigner {
+    provider: Arc<dyn CredentialsProvider>,
+}
+
+impl RequestSigner {
+    /// Constructs a signer that takes its credentials from the provider.
+    pub fn new<P>(provider: P) -> RequestSigner
+    where
+        P: CredentialsProvider + 'static,
+    {
+        RequestSigner {
+            provider: Arc::new(provider),
+        }
+    }
+
+    /// The headers that sign the request at the given time.
+    pub fn headers(
+        &self,
+        request: &http::Request<Body>,
+        now: DateTime<Utc>,
+    ) -> Result<header::HeaderMap> {
+        let credentials = self.provider.credentials()?;
+        let date = now.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        let signature = credentials
+            .key()
+            .sign(signing_string(request, &date).as_bytes())?;
+        let authorization = format!(
+            "Signature keyId=\"{}\",algorithm=\"{}\",headers=\"(request-target) date\",signature=\"{}\"",
+            credentials.key_id(),
+            credentials.key().algorithm(),
+            base64::encode(&signature)
+        );
+
+        let mut headers = header::HeaderMap::new();
+        headers.insert(header::DATE, header::HeaderValue::from_str(&date)?);
+        headers.insert(
+            header::AUTHORIZATION,
+            header::HeaderValue::from_str(&authorization)?,
+        );
+        Ok(headers)
+    }
+}
+
+impl fmt::Debug for RequestSigner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RequestSigner").finish()
+    }
+}
+
+/// The string that is signed, naming the method, the path and query, and
+/// the date.
+fn signing_string(request: &http::Request<Body>, date: &str) -> String {
+    let target = request
+        .uri()
+        .path_and_query()
+        .map(|path| path.as_str())
+        .unwrap_or("/");
+    format!(
+        "(request-target): {} {}\ndate: {}",
+        request.method().as_str().to_lowercase(),
+        target,
+        date
+    )
+}
+
+/// HMAC-SHA256 as described in RFC 2104, computed by openssl.
+fn hmac_sha256(secret: &[u8], message: &[u8]) -> ::std::result::Result<Vec<u8>, ErrorStack> {
+    let key = PKey::hmac(secret)?;
+    let mut signer = sign::Signer::new(MessageDigest::sha256(), &key)?;
+    signer.update(message)?;
+    signer.sign_to_vec()
+}
+
+#[cfg(test)]
+mod request_signer_tests {
+    use super::*;
+    use chrono::TimeZone;
+    use keypair::{Keypair, PublicKey};
+
+    fn request() -> http::Request<Body> {
+        http::Request::get("https://horizon.example.com/accounts/GABC?limit=10")
+            .body(Body::None)
+            .unwrap()
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.ymd(2022, 6, 7).and_hms(20, 51, 35)
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    struct Failing;
+
+    impl CredentialsProvider for Failing {
+        fn credentials(&self) -> ::std::result::Result<Credentials, signer::Error> {
+            Err(signer::Error::device("The secret store is sealed"))
+        }
+    }
+
+    #[test]
+    fn it_computes_hmac_sha256() {
+        // RFC 4231 test cases 2 and 6.
+        assert_eq!(
+            hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?").unwrap()),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(
+            hex(&hmac_sha256(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            )
+            .unwrap()),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn it_signs_the_method_path_and_date() {
+        assert_eq!(
+            signing_string(&request(), "Tue, 07 Jun 2022 20:51:35 GMT"),
+            "(request-target): get /accounts/GABC?limit=10\n\
+             date: Tue, 07 Jun 2022 20:51:35 GMT"
+        );
+    }
+
+    #[test]
+    fn it_adds_hmac_headers() {
+        let signer = RequestSigner::new(Credentials::hmac("team", b"secret"));
+        let headers = signer.headers(&request(), now()).unwrap();
+        assert_eq!(headers[header::DATE], "Tue, 07 Jun 2022 20:51:35 GMT");
+
+        let expected = base64::encode(
+            &hmac_sha256(
+                b"secret",
+                signing_string(&request(), "Tue, 07 Jun 2022 20:51:35 GMT").as_bytes(),
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            headers[header::AUTHORIZATION].to_str().unwrap(),
+            format!(
+                "Signature keyId=\"team\",algorithm=\"hmac-sha256\",\
+                 headers=\"(request-target) date\",signature=\"{}\"",
+                expected
+            )
+        );
+    }
+
+    #[test]
+    fn it_adds_ed25519_headers_that_verify() {
+        let keypair =
+            Keypair::from_secret_seed("SBGWSG6BTNCKCOB3DIFBGCVMUPQFYPA2G4O34RMTB343OYPXU5DJDVMN")
+                .unwrap();
+        let public_key = PublicKey::from_account_id(&keypair.account_id()).unwrap();
+        let signer = RequestSigner::new(Credentials::ed25519("ops", keypair));
+        let headers = signer.headers(&request(), now()).unwrap();
+        let authorization = headers[header::AUTHORIZATION].to_str().unwrap();
+        assert!(authorization.contains("algorithm=\"ed25519-sha256\""));
+
+        let signature = authorization.split("signature=\"").nth(1).unwrap();
+        let signature = base64::decode(signature.trim_end_matches('"')).unwrap();
+        let hash =
+            Sha256::digest(signing_string(&request(), "Tue, 07 Jun 2022 20:51:35 GMT").as_bytes());
+        assert!(public_key.verify(&hash, &signature));
+    }
+
+    #[test]
+    fn it_fails_when_the_provider_does() {
+        let signer = RequestSigner::new(Failing);
+        assert!(signer.headers(&request(), now()).is_err());
+    }
+}
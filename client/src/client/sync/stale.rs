@@ -0,0 +1,298 @@
+use super::Client;
+use endpoint::root;
+use error::{Error, Result};
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// How far a horizon server was behind the reference server when it
+/// answered. Returned in `Error::Stale`, and passed to the callback of a
+/// `StaleGuard` that warns instead of rejecting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Staleness {
+    latest_ledger: u32,
+    reference_ledger: u32,
+}
+
+impl Staleness {
+    /// The latest ledger of the server that answered.
+    pub fn latest_ledger(&self) -> u32 {
+        self.latest_ledger
+    }
+
+    /// The latest ledger of the reference server.
+    pub fn reference_ledger(&self) -> u32 {
+        self.reference_ledger
+    }
+
+    /// How many ledgers the server that answered is behind.
+    pub fn lag(&self) -> u32 {
+        self.reference_ledger.saturating_sub(self.latest_ledger)
+    }
+}
+
+impl fmt::Display for Staleness {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Horizon answered at ledger {}, {} behind the reference server at {}",
+            self.latest_ledger,
+            self.lag(),
+            self.reference_ledger
+        )
+    }
+}
+
+/// Called with how far behind a stale response was.
+type OnStale = Arc<dyn Fn(&Staleness) + Send + Sync>;
+
+/// Rejects responses from a horizon server that has fallen behind, judged
+/// by the `Latest-Ledger` header of each response against the latest ledger
+/// of a reference server. Trading systems reading from replicas use this to
+/// avoid acting on an order book or balance that is several ledgers old.
+///
+/// Stale responses fail with `Error::Stale`, which counts as the server
+/// being unavailable, so a `FailoverClient` or `BalancedClient` moves on to
+/// another host. With `on_stale` the response is returned anyway and the
+/// callback is told instead.
+///
+/// The reference server's latest ledger is fetched from its root and kept
+/// for the refresh interval, five seconds by default, which is about one
+/// ledger. Responses are let through when the reference can't be reached or
+/// the header is missing, since there is nothing to judge them by. A failure
+/// to reach the reference is kept for the refresh interval too, and while
+/// one request fetches the reference others are judged by the last ledger
+/// fetched rather than waiting on it.
+///
+/// ## Examples
+///
+/// ```
+/// use stellar_client::sync::{Client, StaleGuard};
+///
+/// let reference = Client::horizon().unwrap();
+/// let replica = Client::new("https://horizon-replica.example.com")
+///     .unwrap()
+///     .with_stale_guard(StaleGuard::new(reference, 2).on_stale(|staleness| {
+///         eprintln!("{}", staleness);
+///     }));
+/// assert_eq!(replica.stale_guard().unwrap().max_lag(), 2);
+/// ```
+#[derive(Clone)]
+pub struct StaleGuard {
+    reference: Client,
+    max_lag: u32,
+    refresh: Duration,
+    on_stale: Option<OnStale>,
+    reference_ledger: Arc<Mutex<Reference>>,
+}
+
+/// What is known of the reference server's latest ledger.
+#[derive(Debug, Default)]
+struct Reference {
+    /// The last ledger fetched, kept through failures to fetch it again.
+    ledger: Option<u32>,
+    /// When the reference was last asked, and whether it answered.
+    checked: Option<(Instant, bool)>,
+    fetching: bool,
+}
+
+impl StaleGuard {
+    /// Rejects responses more than `max_lag` ledgers behind the reference
+    /// server.
+    pub fn new(reference: Client, max_lag: u32) -> Self {
+        StaleGuard {
+            reference,
+            max_lag,
+            refresh: Duration::from_secs(5),
+            on_stale: None,
+            reference_ledger: Arc::default(),
+        }
+    }
+
+    /// Sets how long the reference server's latest ledger is kept before
+    /// it's fetched again.
+    pub fn with_refresh(mut self, refresh: Duration) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    /// Returns stale responses rather than rejecting them, calling `f` with
+    /// how far behind each one was.
+    pub fn on_stale<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&Staleness) + Send + Sync + 'static,
+    {
+        self.on_stale = Some(Arc::new(f));
+        self
+    }
+
+    /// How many ledgers behind the reference a response may be.
+    pub fn max_lag(&self) -> u32 {
+        self.max_lag
+    }
+
+    /// The latest ledger of the reference server, fetching it if the one
+    /// kept is older than the refresh interval. `None` if the reference
+    /// couldn't be reached within the refresh interval. While another
+    /// request is fetching it, the last ledger fetched is returned instead.
+    pub fn reference_ledger(&self) -> Option<u32> {
+        let now = Instant::now();
+        {
+            let mut kept = self.kept();
+            match kept.checked {
+                Some((checked_at, answered)) if now < checked_at + self.refresh => {
+                    return if answered { kept.ledger } else { None };
+                }
+                _ if kept.fetching => return kept.ledger,
+                _ => kept.fetching = true,
+            }
+        }
+        let ledger = self
+            .reference
+            .request(root::Details)
+            .ok()
+            .map(|root| root.history_latest_ledger());
+        let mut kept = self.kept();
+        kept.fetching = false;
+        kept.checked = Some((now, ledger.is_some()));
+        if ledger.is_some() {
+            kept.ledger = ledger;
+        }
+        ledger
+    }
+
+    fn kept(&self) -> MutexGuard<Reference> {
+        self.reference_ledger
+            .lock()
+            .expect("Stale guard state was poisoned")
+    }
+
+    /// Checks the latest ledger a server answered with against the
+    /// reference, failing with `Error::Stale` if it's too far behind.
+    pub fn check(&self, latest_ledger: u32) -> Result<()> {
+        let reference_ledger = match self.reference_ledger() {
+            Some(ledger) => ledger,
+            None => return Ok(()),
+        };
+        let staleness = Staleness {
+            latest_ledger,
+            reference_ledger,
+        };
+        if staleness.lag() <= self.max_lag {
+            return Ok(());
+        }
+        match self.on_stale {
+            Some(ref on_stale) => {
+                on_stale(&staleness);
+                Ok(())
+            }
+            None => Err(Error::Stale(staleness)),
+        }
+    }
+}
+
+impl fmt::Debug for StaleGuard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("StaleGuard")
+            .field("reference", &self.reference)
+            .field("max_lag", &self.max_lag)
+            .field("refresh", &self.refresh)
+            .field("on_stale", &self.on_stale.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod stale_guard_tests {
+    use super::super::test_server::TestServer;
+    use super::*;
+    use endpoint::ledger;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    const ROOT: &str = include_str!("../../../fixtures/root.json");
+    const LEDGER: &str = include_str!("../../../fixtures/ledger.json");
+
+    fn replica(latest_ledger: &'static str, guard: StaleGuard) -> (TestServer, Client) {
+        let server = TestServer::with_headers(latest_ledger, vec![("200 OK", LEDGER)]);
+        let client = Client::new(server.uri()).unwrap().with_stale_guard(guard);
+        (server, client)
+    }
+
+    #[test]
+    fn it_rejects_responses_too_far_behind() {
+        // The reference's root says its latest ledger is 1234567.
+        let reference = TestServer::start("200 OK", ROOT);
+        let guard = StaleGuard::new(Client::new(reference.uri()).unwrap(), 2);
+
+        let (_server, client) = replica("Latest-Ledger: 1234565\r\n", guard.clone());
+        assert!(client.request(ledger::Details::new(69859)).is_ok());
+
+        let (_server, client) = replica("Latest-Ledger: 1234564\r\n", guard);
+        match client.request(ledger::Details::new(69859)) {
+            Err(Error::Stale(staleness)) => {
+                assert_eq!(staleness.latest_ledger(), 1234564);
+                assert_eq!(staleness.lag(), 3);
+            }
+            other => panic!("Expected a stale error, got {:?}", other),
+        }
+        // The reference's ledger was kept between the two checks.
+        assert_eq!(reference.hits(), 1);
+    }
+
+    #[test]
+    fn it_warns_instead_when_given_a_callback() {
+        let reference = TestServer::start("200 OK", ROOT);
+        let warnings = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&warnings);
+        let guard =
+            StaleGuard::new(Client::new(reference.uri()).unwrap(), 2).on_stale(move |staleness| {
+                assert_eq!(staleness.lag(), 67);
+                counter.fetch_add(1, Ordering::SeqCst);
+            });
+        let (_server, client) = replica("Latest-Ledger: 1234500\r\n", guard);
+        assert!(client.request(ledger::Details::new(69859)).is_ok());
+        assert_eq!(warnings.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn it_lets_responses_through_without_a_reference() {
+        let guard = StaleGuard::new(Client::new(&TestServer::down()).unwrap(), 2);
+        let (_server, client) = replica("Latest-Ledger: 1\r\n", guard.clone());
+        assert!(client.request(ledger::Details::new(69859)).is_ok());
+
+        let reference = TestServer::start("200 OK", ROOT);
+        let guard = StaleGuard::new(Client::new(reference.uri()).unwrap(), 2);
+        let (_server, client) = replica("", guard);
+        assert!(client.request(ledger::Details::new(69859)).is_ok());
+        assert_eq!(reference.hits(), 0);
+    }
+
+    #[test]
+    fn it_keeps_failures_to_reach_the_reference() {
+        let reference = TestServer::start("500 Internal Server Error", "{}");
+        let guard = StaleGuard::new(Client::new(reference.uri()).unwrap(), 2);
+        let (_server, client) = replica("Latest-Ledger: 1\r\n", guard);
+        assert!(client.request(ledger::Details::new(69859)).is_ok());
+        assert!(client.request(ledger::Details::new(69859)).is_ok());
+        assert_eq!(reference.hits(), 1);
+    }
+
+    #[test]
+    fn it_does_not_wait_on_another_request_fetching_the_reference() {
+        let reference = TestServer::delayed(Duration::from_millis(500), "200 OK", ROOT);
+        let guard = StaleGuard::new(Client::new(reference.uri()).unwrap(), 2);
+        let fetching = guard.clone();
+        let fetch = thread::spawn(move || fetching.reference_ledger());
+        while reference.hits() == 0 {
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let started = Instant::now();
+        assert_eq!(guard.reference_ledger(), None);
+        assert!(started.elapsed() < Duration::from_millis(250));
+        assert_eq!(fetch.join().unwrap(), Some(1234567));
+        assert_eq!(guard.reference_ledger(), Some(1234567));
+        assert_eq!(reference.hits(), 1);
+    }
+}
@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// A handle for shutting down long running iterators and streams from
+/// another thread, such as when a server is asked to exit.
+///
+/// Give a clone of the handle to an `Iter`, `Stream` or `ReconnectingStream`
+/// with their `with_stop` methods, and call `stop` from anywhere. Iterators
+/// end before fetching their next page or returning their next record.
+/// Streams end right away, even while waiting on horizon, and reconnecting
+/// streams stop waiting to reconnect. A handle can't be restarted, and
+/// stopping it again does nothing.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use std::thread;
+/// use stellar_client::endpoint::{ledger, Cursor, Position};
+/// use stellar_client::sync::{Client, StopHandle};
+///
+/// let client = Client::horizon_test().unwrap();
+/// let stop = StopHandle::new();
+/// let ledgers = client
+///     .stream(ledger::All::default().with_cursor(Position::Now))
+///     .unwrap()
+///     .with_stop(stop.clone());
+/// let consumer = thread::spawn(move || {
+///     for ledger in ledgers {
+///         println!("closed {}", ledger.unwrap().sequence());
+///     }
+/// });
+///
+/// // Later, when shutting down
+/// stop.stop();
+/// consumer.join().unwrap();
+/// ```
+#[derive(Clone, Default)]
+pub struct StopHandle {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    state: Mutex<State>,
+    condvar: Condvar,
+}
+
+#[derive(Default)]
+struct State {
+    stopped: bool,
+    next_listener: usize,
+    listeners: HashMap<usize, Box<dyn FnOnce() + Send>>,
+}
+
+impl StopHandle {
+    /// A handle that hasn't been stopped.
+    pub fn new() -> StopHandle {
+        StopHandle::default()
+    }
+
+    /// Stops everything the handle was given to.
+    pub fn stop(&self) {
+        let listeners: Vec<_> = {
+            let mut state = self.lock();
+            if state.stopped {
+                return;
+            }
+            state.stopped = true;
+            state
+                .listeners
+                .drain()
+                .map(|(_, listener)| listener)
+                .collect()
+        };
+        self.inner.condvar.notify_all();
+        for listener in listeners {
+            listener();
+        }
+    }
+
+    /// Returns true once the handle has been stopped.
+    pub fn is_stopped(&self) -> bool {
+        self.lock().stopped
+    }
+
+    /// Sleeps for the duration, waking early if the handle is stopped.
+    /// Returns true if it was stopped.
+    pub(crate) fn sleep(&self, duration: Duration) -> bool {
+        let deadline = Instant::now() + duration;
+        let mut state = self.lock();
+        while !state.stopped {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            state = self
+                .inner
+                .condvar
+                .wait_timeout(state, deadline - now)
+                .expect("Stop handle lock poisoned")
+                .0;
+        }
+        state.stopped
+    }
+
+    /// Calls the function when the handle is stopped, or right away if it
+    /// already has been. Returns an id for removing the function with
+    /// `remove_listener` once it's no longer needed.
+    pub(crate) fn on_stop<F>(&self, listener: F) -> Option<usize>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let mut state = self.lock();
+        if state.stopped {
+            drop(state);
+            listener();
+            return None;
+        }
+        let id = state.next_listener;
+        state.next_listener += 1;
+        state.listeners.insert(id, Box::new(listener));
+        Some(id)
+    }
+
+    pub(crate) fn remove_listener(&self, id: usize) {
+        self.lock().listeners.remove(&id);
+    }
+
+    fn lock(&self) -> ::std::sync::MutexGuard<State> {
+        self.inner.state.lock().expect("Stop handle lock poisoned")
+    }
+}
+
+impl fmt::Debug for StopHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("StopHandle")
+            .field("stopped", &self.is_stopped())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod stop_handle_tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    #[test]
+    fn it_stops_once() {
+        let stop = StopHandle::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        stop.on_stop(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        assert!(!stop.is_stopped());
+
+        stop.clone().stop();
+        stop.stop();
+        assert!(stop.is_stopped());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let counter = Arc::clone(&calls);
+        assert_eq!(
+            stop.on_stop(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }),
+            None
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn it_forgets_removed_listeners() {
+        let stop = StopHandle::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let id = stop
+            .on_stop(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+            })
+            .unwrap();
+        stop.remove_listener(id);
+        stop.stop();
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn it_wakes_sleepers() {
+        let stop = StopHandle::new();
+        assert!(!stop.sleep(Duration::from_millis(1)));
+
+        let stopper = stop.clone();
+        let started = Instant::now();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            stopper.stop();
+        });
+        assert!(stop.sleep(Duration::from_secs(30)));
+        assert!(started.elapsed() < Duration::from_secs(30));
+    }
+}
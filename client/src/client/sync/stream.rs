@@ -0,0 +1,264 @@
+use super::super::sse::{Event, EventReader};
+use super::StopHandle;
+use error::{Error, Result};
+use resources::deserialize;
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::io::{self, BufRead};
+use std::marker::PhantomData;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+/// How many events a stream that can be stopped reads ahead of the records
+/// taken from it, so a slow consumer holds horizon back rather than
+/// buffering without bound.
+const READ_AHEAD: usize = 64;
+
+/// A blocking iterator over the records horizon pushes to a streaming
+/// endpoint. Each call to `next` waits until horizon sends another record,
+/// and the iterator ends when horizon closes the connection.
+///
+/// Streams are opened with `Client::stream`, which takes the same endpoints
+/// used for fetching pages of records.
+///
+/// # Examples
+///
+/// ```no_run
+/// use stellar_client::sync::Client;
+/// use stellar_client::endpoint::{account, Cursor, Position};
+///
+/// let client = Client::horizon_test().unwrap();
+/// let endpoint = account::Offers::new("GATLAI2D7SSH6PE3HXTDPTRM4RE5VRK6HGA63K5EWP75PSANCZRFDNB5")
+///     .with_cursor(Position::Now);
+/// for offer in client.stream(endpoint).unwrap() {
+///     println!("offer {} changed", offer.unwrap().id());
+/// }
+/// ```
+pub struct Stream<T> {
+    events: Events,
+    allow_unknown_variants: bool,
+    last_id: Option<String>,
+    stop: Option<(StopHandle, Option<usize>)>,
+    marker: PhantomData<T>,
+}
+
+/// Where a stream reads its events from. Streams that can be stopped read
+/// on a thread of their own, so that stopping doesn't wait on horizon.
+enum Events {
+    Reader(EventReader<Box<dyn BufRead + Send>>),
+    /// Events from the reading thread, ending with `None` when the
+    /// connection closes or the stream is stopped.
+    Channel(Receiver<Option<io::Result<Event>>>),
+    Done,
+}
+
+impl<T> Stream<T> {
+    pub(super) fn new(reader: Box<dyn BufRead + Send>, allow_unknown_variants: bool) -> Self {
+        Stream {
+            events: Events::Reader(EventReader::new(reader)),
+            allow_unknown_variants,
+            last_id: None,
+            stop: None,
+            marker: PhantomData,
+        }
+    }
+
+    /// The paging token of the last record received. Streaming again with
+    /// this as the cursor picks up where this stream left off.
+    pub fn last_id(&self) -> Option<&str> {
+        self.last_id.as_ref().map(String::as_str)
+    }
+
+    /// Ends the stream when the handle is stopped, even while it is waiting
+    /// on horizon. The connection is read on a thread of its own, at most a
+    /// few dozen events ahead, which exits once horizon next sends something
+    /// or closes the connection.
+    pub fn with_stop(mut self, stop: StopHandle) -> Self {
+        let mut reader = match ::std::mem::replace(&mut self.events, Events::Done) {
+            Events::Reader(reader) => reader,
+            events => {
+                self.events = events;
+                return self;
+            }
+        };
+        let (sender, receiver) = mpsc::sync_channel(READ_AHEAD);
+        let stopper = sender.clone();
+        // A full channel means the stream isn't waiting on it, and it checks
+        // the handle before taking the next event anyway.
+        let listener = stop.on_stop(move || {
+            let _ = stopper.try_send(None);
+        });
+        thread::spawn(move || {
+            for event in &mut reader {
+                if sender.send(Some(event)).is_err() {
+                    return;
+                }
+            }
+            let _ = sender.send(None);
+        });
+        self.events = Events::Channel(receiver);
+        self.stop = Some((stop, listener));
+        self
+    }
+
+    fn next_event(&mut self) -> Option<io::Result<Event>> {
+        let event = match self.events {
+            Events::Reader(ref mut reader) => reader.next(),
+            Events::Channel(_) if self.is_stopped() => None,
+            Events::Channel(ref receiver) => receiver.recv().ok().and_then(|event| event),
+            Events::Done => None,
+        };
+        if event.is_none() {
+            self.events = Events::Done;
+        }
+        event
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.stop
+            .as_ref()
+            .is_some_and(|&(ref stop, _)| stop.is_stopped())
+    }
+}
+
+impl<T> Drop for Stream<T> {
+    fn drop(&mut self) {
+        if let Some((ref stop, Some(listener))) = self.stop {
+            stop.remove_listener(listener);
+        }
+    }
+}
+
+impl<T> fmt::Debug for Stream<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Stream")
+            .field("allow_unknown_variants", &self.allow_unknown_variants)
+            .field("last_id", &self.last_id)
+            .field("stop", &self.stop.as_ref().map(|stop| &stop.0))
+            .finish()
+    }
+}
+
+impl<T> Iterator for Stream<T>
+where
+    T: DeserializeOwned,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        loop {
+            let event = match self.next_event()? {
+                Ok(event) => event,
+                Err(err) => return Some(Err(Error::Io(err))),
+            };
+            // Horizon greets each connection with an `open` event and says
+            // goodbye with a `close` event before hanging up.
+            match event.event.as_ref().map(String::as_str) {
+                Some("open") => continue,
+                Some("close") => {
+                    self.events = Events::Done;
+                    return None;
+                }
+                _ => {}
+            }
+            if event.data.is_empty() {
+                continue;
+            }
+            let record = deserialize::from_slice(event.data.as_bytes(), self.allow_unknown_variants);
+            if event.id.is_some() {
+                self.last_id = event.id;
+            }
+            return Some(record.map_err(Error::from));
+        }
+    }
+}
+
+#[cfg(test)]
+mod stream_tests {
+    use super::*;
+    use resources::Offer;
+    use std::io::Cursor;
+
+    fn stream(input: &'static str) -> Stream<Offer> {
+        Stream::new(Box::new(Cursor::new(input)), false)
+    }
+
+    #[test]
+    fn it_yields_records_and_tracks_the_last_id() {
+        let offer = include_str!("../../../fixtures/offer.json").replace('\n', "");
+        let input = format!(
+            "retry: 1000\nevent: open\ndata: \"hello\"\n\nid: 121\ndata: {}\n\n",
+            offer
+        );
+        let mut stream: Stream<Offer> =
+            Stream::new(Box::new(Cursor::new(input.into_bytes())), false);
+        assert_eq!(stream.next().unwrap().unwrap().id(), 121);
+        assert_eq!(stream.last_id(), Some("121"));
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn it_stops_when_horizon_says_goodbye() {
+        let mut stream = stream("event: close\ndata: \"byebye\"\n\ndata: {}\n\n");
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn it_stops_while_waiting_on_horizon() {
+        use super::super::test_server::TestServer;
+        use super::super::Client;
+        use endpoint::ledger;
+        use std::time::{Duration, Instant};
+
+        let server = TestServer::stalling("retry: 1000\n\n");
+        let stop = StopHandle::new();
+        let mut stream = Client::new(server.uri())
+            .unwrap()
+            .stream(ledger::All::default())
+            .unwrap()
+            .with_stop(stop.clone());
+
+        let stopper = stop.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            stopper.stop();
+        });
+        let started = Instant::now();
+        assert!(stream.next().is_none());
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn it_reads_to_the_end_when_it_can_be_stopped() {
+        let offer = include_str!("../../../fixtures/offer.json").replace('\n', "");
+        let input = format!("id: 121\ndata: {}\n\n", offer);
+        let stop = StopHandle::new();
+        let mut stream: Stream<Offer> =
+            Stream::new(Box::new(Cursor::new(input.into_bytes())), false)
+                .with_stop(stop.clone());
+        assert_eq!(stream.next().unwrap().unwrap().id(), 121);
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn it_stops_with_events_read_ahead() {
+        let offer = include_str!("../../../fixtures/offer.json").replace('\n', "");
+        let input: String = (0..READ_AHEAD * 2)
+            .map(|id| format!("id: {}\ndata: {}\n\n", id, offer))
+            .collect();
+        let stop = StopHandle::new();
+        let mut stream: Stream<Offer> =
+            Stream::new(Box::new(Cursor::new(input.into_bytes())), false)
+                .with_stop(stop.clone());
+        assert!(stream.next().unwrap().is_ok());
+        stop.stop();
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn it_surfaces_records_that_dont_parse() {
+        let mut stream = stream("id: 1\ndata: {\"id\": \"nope\"}\n\n");
+        assert!(stream.next().unwrap().is_err());
+    }
+}
@@ -0,0 +1,435 @@
+use super::resubmit::{is_insufficient_fee, ResubmitError, ResubmitErrorKind};
+use super::{is_unavailable, Client};
+use endpoint::fee;
+use error::Error;
+use resources::Submission;
+use signer::Signer;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use transaction::{self, FeeStrategy, TransactionBuilder, TransactionEnvelope};
+
+/// What finally became of a transaction given to a `Submitter`.
+#[derive(Debug)]
+pub enum SubmitStatus {
+    /// The transaction made it into a ledger.
+    Applied(Submission),
+    /// Horizon rejected the transaction for a reason that submitting it
+    /// again won't fix, such as a bad sequence number, or it couldn't be
+    /// built or signed.
+    Failed(ResubmitError),
+    /// The transaction was still failing in a way worth retrying, such as
+    /// horizon being down or the fee being too low, after the last retry.
+    Exhausted(ResubmitError),
+}
+
+/// A queue of transactions to submit, for bots that submit more than one
+/// transaction at a time.
+///
+/// Transactions are submitted highest priority first, and in the order
+/// they were queued within a priority. Each call to `run_ledger` submits at
+/// most the per ledger limit, so that a burst doesn't flood horizon or
+/// compete with itself for space in the next ledger. Transactions that fail
+/// in a way worth retrying go back in the queue for a later ledger, and
+/// once a transaction is applied or has failed for good its status is sent
+/// on the channel returned when it was queued.
+///
+/// A transaction rejected with `tx_insufficient_fee` is retried with the
+/// fee the submitter's fee strategy picks from the latest fee stats, if it
+/// was queued as a builder the submitter can rebuild and sign. Envelopes
+/// are already signed, so they're retried as they are, in case the surge
+/// has passed.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use stellar_client::endpoint::{ledger, Cursor, Position};
+/// use stellar_client::keypair::Keypair;
+/// use stellar_client::network::Network;
+/// use stellar_client::resources::Percentile;
+/// use stellar_client::sync::{Client, SubmitStatus, Submitter};
+/// use stellar_client::transaction::{CappedFee, PercentileFee, TransactionBuilder};
+///
+/// let client = Client::horizon_test().unwrap();
+/// let keypair = Keypair::random();
+/// let mut submitter = Submitter::new(client.clone())
+///     .with_per_ledger(10)
+///     .with_fee_strategy(CappedFee::new(PercentileFee::new(Percentile::P90), 10_000))
+///     .with_signer(keypair.clone());
+///
+/// let builder = TransactionBuilder::new(&keypair.account_id(), 1, Network::TESTNET);
+/// let status = submitter.enqueue_builder(builder, 10);
+///
+/// for ledger in client.stream(ledger::All::default().with_cursor(Position::Now)).unwrap() {
+///     ledger.unwrap();
+///     submitter.run_ledger();
+///     if let Ok(SubmitStatus::Applied(submission)) = status.try_recv() {
+///         println!("applied in {}", submission.ledger());
+///         break;
+///     }
+/// }
+/// ```
+pub struct Submitter {
+    client: Client,
+    queue: BinaryHeap<Queued>,
+    queued: u64,
+    per_ledger: usize,
+    max_retries: u32,
+    fee_strategy: Option<Box<dyn FeeStrategy>>,
+    signers: Vec<Box<dyn Signer>>,
+}
+
+impl fmt::Debug for Submitter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Submitter")
+            .field("client", &self.client)
+            .field("queued", &self.queue.len())
+            .field("per_ledger", &self.per_ledger)
+            .field("max_retries", &self.max_retries)
+            .field("fee_strategy", &self.fee_strategy.is_some())
+            .field("signers", &self.signers.len())
+            .finish()
+    }
+}
+
+impl Submitter {
+    /// Constructs a submitter that sends up to 10 transactions a ledger and
+    /// retries each at most 3 times.
+    pub fn new(client: Client) -> Submitter {
+        Submitter {
+            client,
+            queue: BinaryHeap::new(),
+            queued: 0,
+            per_ledger: 10,
+            max_retries: 3,
+            fee_strategy: None,
+            signers: Vec::new(),
+        }
+    }
+
+    /// Sets the most transactions submitted in each call to `run_ledger`.
+    pub fn with_per_ledger(mut self, per_ledger: usize) -> Self {
+        self.per_ledger = per_ledger;
+        self
+    }
+
+    /// Sets how many times a transaction is retried before it's given up
+    /// on with `SubmitStatus::Exhausted`.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the strategy that picks the fee of builders rejected with
+    /// `tx_insufficient_fee`. Without one they're resubmitted with their
+    /// base fee doubled. Cap the strategy with `CappedFee` to bound what a
+    /// surge can cost.
+    pub fn with_fee_strategy<S>(mut self, strategy: S) -> Self
+    where
+        S: FeeStrategy + 'static,
+    {
+        self.fee_strategy = Some(Box::new(strategy));
+        self
+    }
+
+    /// Adds a signer that signs the transactions of queued builders.
+    pub fn with_signer<S>(mut self, signer: S) -> Self
+    where
+        S: Signer + 'static,
+    {
+        self.signers.push(Box::new(signer));
+        self
+    }
+
+    /// The number of transactions waiting to be submitted.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns true if no transactions are waiting to be submitted.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Queues a signed envelope, returning a channel that receives its
+    /// status once it's applied or given up on.
+    pub fn enqueue(
+        &mut self,
+        envelope: TransactionEnvelope,
+        priority: u32,
+    ) -> Receiver<SubmitStatus> {
+        self.push(Pending::Envelope(envelope), priority)
+    }
+
+    /// Queues a transaction to be built and signed by the submitter's
+    /// signers when it's submitted, so that its fee can be raised.
+    pub fn enqueue_builder(
+        &mut self,
+        builder: TransactionBuilder,
+        priority: u32,
+    ) -> Receiver<SubmitStatus> {
+        self.push(Pending::Builder(builder), priority)
+    }
+
+    fn push(&mut self, pending: Pending, priority: u32) -> Receiver<SubmitStatus> {
+        let (sender, receiver) = channel();
+        self.queued += 1;
+        self.queue.push(Queued {
+            priority,
+            order: self.queued,
+            retries: 0,
+            pending,
+            sender,
+        });
+        receiver
+    }
+
+    /// Submits the highest priority transactions, up to the per ledger
+    /// limit, returning how many were submitted. Call it once for each
+    /// ledger that closes.
+    pub fn run_ledger(&mut self) -> usize {
+        let mut retry = Vec::new();
+        let mut submitted = 0;
+        while submitted < self.per_ledger {
+            let mut queued = match self.queue.pop() {
+                Some(queued) => queued,
+                None => break,
+            };
+            submitted += 1;
+            let status = match self.submit(&queued.pending) {
+                Ok(submission) => SubmitStatus::Applied(submission),
+                Err(err) => {
+                    if !is_retryable(&err) {
+                        SubmitStatus::Failed(err)
+                    } else if queued.retries >= self.max_retries {
+                        SubmitStatus::Exhausted(err)
+                    } else {
+                        match self.reprice(&mut queued.pending, &err) {
+                            Ok(()) => {
+                                queued.retries += 1;
+                                retry.push(queued);
+                                continue;
+                            }
+                            Err(err) => SubmitStatus::Failed(err),
+                        }
+                    }
+                }
+            };
+            // Nobody may be waiting on the status any more.
+            let _ = queued.sender.send(status);
+        }
+        // Retries wait for the next ledger, in their original place.
+        self.queue.extend(retry);
+        submitted
+    }
+
+    fn submit(&self, pending: &Pending) -> Result<Submission, ResubmitError> {
+        let submission = match *pending {
+            Pending::Envelope(ref envelope) => self.client.submit(envelope),
+            Pending::Builder(ref builder) => {
+                let mut envelope = builder.build()?;
+                for signer in &self.signers {
+                    envelope.sign(&**signer)?;
+                }
+                self.client.submit(&envelope)
+            }
+        };
+        Ok(submission?)
+    }
+
+    /// Raises the fee of a builder rejected for offering too little.
+    fn reprice(&self, pending: &mut Pending, err: &ResubmitError) -> Result<(), ResubmitError> {
+        let builder = match (pending, err.kind()) {
+            (
+                &mut Pending::Builder(ref mut builder),
+                &ResubmitErrorKind::Request(Error::BadResponse(ref err)),
+            ) if is_insufficient_fee(err) => builder,
+            _ => return Ok(()),
+        };
+        let base_fee = match self.fee_strategy {
+            Some(ref strategy) => strategy.base_fee(&self.client.request(fee::Stats)?),
+            None => builder.base_fee().saturating_mul(2),
+        };
+        if base_fee <= builder.base_fee() {
+            let fee = builder.fee().ok_or_else(transaction::Error::fee_overflow)?;
+            return Err(ResubmitError::fee_limit(fee));
+        }
+        let repriced = builder.clone().with_base_fee(base_fee);
+        repriced
+            .fee()
+            .ok_or_else(transaction::Error::fee_overflow)?;
+        *builder = repriced;
+        Ok(())
+    }
+}
+
+/// Whether submitting again might get a different answer: horizon couldn't
+/// be reached or was overloaded, or the fee was too low for a busy ledger.
+fn is_retryable(err: &ResubmitError) -> bool {
+    match *err.kind() {
+        ResubmitErrorKind::Request(Error::BadResponse(ref err)) => {
+            err.is_rate_limit_exceeded() || is_insufficient_fee(err)
+        }
+        ResubmitErrorKind::Request(ref err) => is_unavailable(err),
+        _ => false,
+    }
+}
+
+enum Pending {
+    Envelope(TransactionEnvelope),
+    Builder(TransactionBuilder),
+}
+
+struct Queued {
+    priority: u32,
+    order: u64,
+    retries: u32,
+    pending: Pending,
+    sender: Sender<SubmitStatus>,
+}
+
+impl Ord for Queued {
+    /// The highest priority is greatest, and within a priority the first
+    /// queued, so it's what the heap pops first.
+    fn cmp(&self, other: &Queued) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.order.cmp(&self.order))
+    }
+}
+
+impl PartialOrd for Queued {
+    fn partial_cmp(&self, other: &Queued) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Queued {
+    fn eq(&self, other: &Queued) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Queued {}
+
+#[cfg(test)]
+mod submitter_tests {
+    use super::super::test_server::TestServer;
+    use super::*;
+    use keypair::Keypair;
+    use network::Network;
+    use resources::{Amount, AssetIdentifier};
+    use std::sync::mpsc::TryRecvError;
+    use transaction::{FixedFee, Operation};
+
+    const SEED: &str = "SBGWSG6BTNCKCOB3DIFBGCVMUPQFYPA2G4O34RMTB343OYPXU5DJDVMN";
+    const INSUFFICIENT_FEE: &str = include_str!("../../../fixtures/transaction_failed_error.json");
+    const SUBMISSION: &str = include_str!("../../../fixtures/submission.json");
+    const FEE_STATS: &str = include_str!("../../../fixtures/fee_stats.json");
+
+    fn builder(sequence: i64) -> TransactionBuilder {
+        let keypair = Keypair::from_secret_seed(SEED).unwrap();
+        TransactionBuilder::new(&keypair.account_id(), sequence, Network::TESTNET).with_operation(
+            Operation::payment(
+                "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3",
+                AssetIdentifier::native(),
+                Amount::new(10_000_000),
+            ),
+        )
+    }
+
+    fn submitter(server: &TestServer) -> Submitter {
+        Submitter::new(Client::new(server.uri()).unwrap())
+            .with_signer(Keypair::from_secret_seed(SEED).unwrap())
+    }
+
+    fn applied(status: &Receiver<SubmitStatus>) -> bool {
+        match status.try_recv() {
+            Ok(SubmitStatus::Applied(_)) => true,
+            Err(TryRecvError::Empty) => false,
+            result => panic!("Expected the transaction to be applied, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn it_submits_the_highest_priority_first() {
+        let server = TestServer::start("200 OK", SUBMISSION);
+        let mut submitter = submitter(&server).with_per_ledger(2);
+        let low = submitter.enqueue_builder(builder(1), 1);
+        let high = submitter.enqueue(builder(2).build().unwrap(), 5);
+        let first = submitter.enqueue_builder(builder(3), 3);
+        let second = submitter.enqueue_builder(builder(4), 3);
+
+        assert_eq!(submitter.run_ledger(), 2);
+        assert!(applied(&high));
+        assert!(applied(&first));
+        assert!(!applied(&second));
+        assert!(!applied(&low));
+        assert_eq!(submitter.len(), 2);
+
+        assert_eq!(submitter.run_ledger(), 2);
+        assert!(applied(&second));
+        assert!(applied(&low));
+        assert!(submitter.is_empty());
+        assert_eq!(submitter.run_ledger(), 0);
+        assert_eq!(server.hits(), 4);
+    }
+
+    #[test]
+    fn it_retries_in_a_later_ledger_with_the_strategys_fee() {
+        let server = TestServer::sequence(vec![
+            ("400 Bad Request", INSUFFICIENT_FEE),
+            ("200 OK", FEE_STATS),
+            ("200 OK", SUBMISSION),
+        ]);
+        let mut submitter = submitter(&server).with_fee_strategy(FixedFee::new(1000));
+        let status = submitter.enqueue_builder(builder(1), 1);
+
+        assert_eq!(submitter.run_ledger(), 1);
+        assert!(!applied(&status));
+        match submitter.queue.peek().unwrap().pending {
+            Pending::Builder(ref builder) => assert_eq!(builder.base_fee(), 1000),
+            Pending::Envelope(_) => panic!("Expected a builder"),
+        }
+        assert_eq!(submitter.run_ledger(), 1);
+        assert!(applied(&status));
+        assert_eq!(server.hits(), 3);
+    }
+
+    #[test]
+    fn it_gives_up_after_the_last_retry() {
+        let server = TestServer::start("400 Bad Request", INSUFFICIENT_FEE);
+        let mut submitter = submitter(&server).with_max_retries(1);
+        let status = submitter.enqueue(builder(1).build().unwrap(), 1);
+
+        submitter.run_ledger();
+        submitter.run_ledger();
+        match status.try_recv() {
+            Ok(SubmitStatus::Exhausted(ref err)) => match *err.kind() {
+                ResubmitErrorKind::Request(Error::BadResponse(_)) => {}
+                ref kind => panic!("Expected a rejection, got {:?}", kind),
+            },
+            result => panic!("Expected to give up, got {:?}", result),
+        }
+        assert!(submitter.is_empty());
+        assert_eq!(server.hits(), 2);
+    }
+
+    #[test]
+    fn it_fails_transactions_retrying_wont_help() {
+        let server = TestServer::start(
+            "404 Not Found",
+            include_str!("../../../fixtures/before_history_error.json"),
+        );
+        let mut submitter = submitter(&server);
+        let status = submitter.enqueue_builder(builder(1), 1);
+
+        submitter.run_ledger();
+        match status.try_recv() {
+            Ok(SubmitStatus::Failed(_)) => {}
+            result => panic!("Expected a failure, got {:?}", result),
+        }
+        assert!(submitter.is_empty());
+    }
+}
@@ -0,0 +1,186 @@
+//! A local http server for testing how clients react to hosts that are up,
+//! down or failing, without going out to the network.
+use std::borrow::Cow;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// An empty page of records, which any listing endpoint accepts.
+pub const EMPTY_RECORDS: &str = r#"{"_embedded":{"records":[]}}"#;
+
+/// A server answering requests with canned responses.
+pub struct TestServer {
+    uri: String,
+    hits: Arc<AtomicUsize>,
+    paths: Arc<Mutex<Vec<String>>>,
+}
+
+impl TestServer {
+    /// Starts a server on a free local port that answers with the given
+    /// status line, such as `200 OK`, and json body.
+    pub fn start<B: Into<Cow<'static, str>>>(status: &'static str, body: B) -> TestServer {
+        Self::sequence(vec![(status, body)])
+    }
+
+    /// Starts a server that answers with each of the responses in turn,
+    /// repeating the last one once it runs out.
+    pub fn sequence<B: Into<Cow<'static, str>>>(responses: Vec<(&'static str, B)>) -> TestServer {
+        Self::with_headers("", responses)
+    }
+
+    /// Starts a server like `sequence` that also sends the headers, given
+    /// as lines such as `Latest-Ledger: 100\r\n`, with each response.
+    pub fn with_headers<B: Into<Cow<'static, str>>>(
+        headers: &'static str,
+        responses: Vec<(&'static str, B)>,
+    ) -> TestServer {
+        let responses: Vec<(&str, Cow<str>)> = responses
+            .into_iter()
+            .map(|(status, body)| (status, body.into()))
+            .collect();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let uri = format!("http://{}", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicUsize::new(0));
+        let paths = Arc::new(Mutex::new(Vec::new()));
+        let counter = Arc::clone(&hits);
+        let requested = Arc::clone(&paths);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let hit = counter.fetch_add(1, Ordering::SeqCst);
+                let (status, ref body) = responses[hit.min(responses.len() - 1)];
+                respond(stream, status, headers, body, Some(&requested));
+            }
+        });
+        TestServer { uri, hits, paths }
+    }
+
+    /// Starts a server that waits before answering each request, answering
+    /// requests that arrive meanwhile on their own threads.
+    pub fn delayed<B: Into<Cow<'static, str>>>(
+        delay: Duration,
+        status: &'static str,
+        body: B,
+    ) -> TestServer {
+        let body = body.into();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let uri = format!("http://{}", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&hits);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                counter.fetch_add(1, Ordering::SeqCst);
+                let body = body.clone();
+                thread::spawn(move || {
+                    thread::sleep(delay);
+                    respond(stream, status, "", &body, None);
+                });
+            }
+        });
+        TestServer {
+            uri,
+            hits,
+            paths: Arc::default(),
+        }
+    }
+
+    /// Starts a server that sends the body as an event stream and then goes
+    /// quiet, holding each connection open for a few seconds without
+    /// sending anything more.
+    pub fn stalling<B: Into<Cow<'static, str>>>(body: B) -> TestServer {
+        let body = body.into();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let uri = format!("http://{}", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&hits);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                counter.fetch_add(1, Ordering::SeqCst);
+                let body = body.clone();
+                thread::spawn(move || {
+                    let mut reader = BufReader::new(stream);
+                    let mut line = String::new();
+                    while reader.read_line(&mut line).map(|read| read > 2).unwrap_or(false) {
+                        line.clear();
+                    }
+                    let _ = write!(
+                        reader.get_mut(),
+                        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nConnection: close\r\n\r\n{}",
+                        body
+                    );
+                    let _ = reader.get_mut().flush();
+                    thread::sleep(Duration::from_secs(5));
+                });
+            }
+        });
+        TestServer {
+            uri,
+            hits,
+            paths: Arc::default(),
+        }
+    }
+
+    /// A uri on which nothing is listening, so connections are refused.
+    pub fn down() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("http://{}", listener.local_addr().unwrap())
+    }
+
+    /// The uri to connect to the server with.
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// How many requests the server has answered.
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::SeqCst)
+    }
+
+    /// The paths and queries of the requests answered so far, in order.
+    /// Only recorded by servers that answer in turn, such as `sequence`.
+    pub fn paths(&self) -> Vec<String> {
+        self.paths.lock().unwrap().clone()
+    }
+}
+
+/// Answers a request, first noting its path in `paths` if given.
+fn respond(
+    stream: TcpStream,
+    status: &str,
+    headers: &str,
+    body: &str,
+    paths: Option<&Mutex<Vec<String>>>,
+) {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    let mut length = 0;
+    let _ = reader.read_line(&mut line);
+    if let Some(paths) = paths {
+        let path = line.split_whitespace().nth(1).unwrap_or("");
+        paths.lock().unwrap().push(path.to_string());
+    }
+    line.clear();
+    while reader
+        .read_line(&mut line)
+        .map(|read| read > 2)
+        .unwrap_or(false)
+    {
+        let header = line.to_lowercase();
+        if let Some(value) = header.strip_prefix("content-length:") {
+            length = value.trim().parse().unwrap_or(0);
+        }
+        line.clear();
+    }
+    // Read the whole request so that closing the connection doesn't reset it.
+    let _ = reader.by_ref().take(length).read_to_end(&mut Vec::new());
+    let _ = write!(
+        reader.get_mut(),
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        headers,
+        body.len(),
+        body
+    );
+}
@@ -0,0 +1,183 @@
+use super::Client;
+use chrono::{DateTime, Utc};
+use endpoint::{ledger, ledger_cursor, Direction, Limit, Order, Position};
+use error::Result;
+use resources::Ledger;
+
+impl Client {
+    /// Finds the first ledger that closed at or after the given time with a
+    /// binary search over the ledgers horizon has, which takes a request for
+    /// each halving of the range. Times before horizon's history begins give
+    /// the oldest ledger, and times after the latest ledger closed give
+    /// `None`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # extern crate chrono;
+    /// # extern crate stellar_client;
+    /// use chrono::{Duration, Utc};
+    /// use stellar_client::sync::Client;
+    ///
+    /// # fn main() {
+    /// let client = Client::horizon_test().unwrap();
+    /// let yesterday = Utc::now() - Duration::days(1);
+    /// let ledger = client.ledger_at(yesterday).unwrap().unwrap();
+    /// assert!(ledger.closed_at() >= yesterday);
+    /// # }
+    /// ```
+    pub fn ledger_at(&self, time: DateTime<Utc>) -> Result<Option<Ledger>> {
+        let oldest = match self.edge_ledger(Direction::Asc)? {
+            Some(oldest) => oldest,
+            None => return Ok(None),
+        };
+        if oldest.closed_at() >= time {
+            return Ok(Some(oldest));
+        }
+        let latest = match self.edge_ledger(Direction::Desc)? {
+            Some(ref latest) if latest.closed_at() < time => return Ok(None),
+            Some(latest) => latest,
+            None => return Ok(None),
+        };
+        search(oldest, latest, time, |sequence| {
+            self.request(ledger::Details::new(sequence))
+        })
+        .map(Some)
+    }
+
+    /// The cursor to page from for the records since the given time, such as
+    /// all payments since yesterday. The cursor is only as precise as a
+    /// ledger, starting with the first ledger that closed at or after the
+    /// time. When no ledger has closed since, the cursor is `now`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # extern crate chrono;
+    /// # extern crate stellar_client;
+    /// use chrono::{Duration, Utc};
+    /// use stellar_client::endpoint::{payment, Cursor};
+    /// use stellar_client::sync::Client;
+    ///
+    /// # fn main() {
+    /// let client = Client::horizon_test().unwrap();
+    /// let cursor = client.cursor_at(Utc::now() - Duration::days(1)).unwrap();
+    /// let payments = client
+    ///     .request(payment::All::default().with_cursor(&cursor))
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn cursor_at(&self, time: DateTime<Utc>) -> Result<String> {
+        Ok(match self.ledger_at(time)? {
+            Some(ledger) => ledger_cursor(ledger.sequence()),
+            None => Position::Now.to_string(),
+        })
+    }
+
+    /// The oldest or the latest ledger horizon has.
+    pub(super) fn edge_ledger(&self, order: Direction) -> Result<Option<Ledger>> {
+        let endpoint = ledger::All::default().with_order(order).with_limit(1);
+        Ok(self.request(endpoint)?.records().first().cloned())
+    }
+}
+
+/// Something that closed with a ledger, such as the ledger itself.
+pub(super) trait Closed {
+    /// The sequence of the ledger.
+    fn sequence(&self) -> u32;
+
+    /// When the ledger closed.
+    fn closed_at(&self) -> DateTime<Utc>;
+}
+
+impl Closed for Ledger {
+    fn sequence(&self) -> u32 {
+        Ledger::sequence(self)
+    }
+
+    fn closed_at(&self) -> DateTime<Utc> {
+        Ledger::closed_at(self)
+    }
+}
+
+impl Closed for (u32, DateTime<Utc>) {
+    fn sequence(&self) -> u32 {
+        self.0
+    }
+
+    fn closed_at(&self) -> DateTime<Utc> {
+        self.1
+    }
+}
+
+/// Narrows down to the first ledger that closed at or after the time, given
+/// a ledger that closed before it and one that closed at or after it.
+pub(super) fn search<T, F>(
+    mut before: T,
+    mut after: T,
+    time: DateTime<Utc>,
+    mut fetch: F,
+) -> Result<T>
+where
+    T: Closed,
+    F: FnMut(u32) -> Result<T>,
+{
+    while after.sequence() - before.sequence() > 1 {
+        let middle = before.sequence() + (after.sequence() - before.sequence()) / 2;
+        let ledger = fetch(middle)?;
+        if ledger.closed_at() < time {
+            before = ledger;
+        } else {
+            after = ledger;
+        }
+    }
+    Ok(after)
+}
+
+#[cfg(test)]
+mod ledger_time_tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    // Ledgers close every five seconds.
+    fn ledger(sequence: u32) -> Ledger {
+        let genesis = Utc.ymd(2018, 1, 1).and_hms(0, 0, 0);
+        Ledger::new(
+            sequence,
+            genesis + Duration::seconds(5 * i64::from(sequence)),
+        )
+    }
+
+    #[test]
+    fn it_searches_for_the_first_ledger_closed_since() {
+        let mut fetched = 0;
+        let time = ledger(437).closed_at() - Duration::seconds(2);
+        let found = search(ledger(1), ledger(1000), time, |sequence| {
+            fetched += 1;
+            Ok(ledger(sequence))
+        })
+        .unwrap();
+        assert_eq!(found.sequence(), 437);
+        assert!(fetched <= 10);
+    }
+
+    #[test]
+    fn it_finds_a_ledger_closed_exactly_at_the_time() {
+        let time = ledger(600).closed_at();
+        let found = search(ledger(1), ledger(1000), time, |sequence| {
+            Ok(ledger(sequence))
+        })
+        .unwrap();
+        assert_eq!(found.sequence(), 600);
+    }
+
+    #[test]
+    fn it_returns_the_later_ledger_when_they_are_adjacent() {
+        let time = ledger(2).closed_at();
+        let found = search(ledger(1), ledger(2), time, |_| {
+            panic!("Adjacent ledgers need no requests")
+        })
+        .unwrap();
+        assert_eq!(found.sequence(), 2);
+    }
+}
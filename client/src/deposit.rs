@@ -0,0 +1,421 @@
+//! Watches an exchange's hot wallet for incoming payments, which is how an
+//! exchange learns of customer deposits.
+//!
+//! Customers share the hot wallet and tell their deposits apart with the
+//! memo of the transaction, or by paying a multiplexed `M...` address made
+//! from the hot wallet. A `DepositScanner` follows the payments to the
+//! account, looks up the transaction of each one it accepts, and yields a
+//! `Deposit` carrying the memo or muxed id along with a key for crediting
+//! the deposit exactly once.
+//!
+//! ```no_run
+//! use stellar_client::deposit::DepositScanner;
+//! use stellar_client::resources::AssetIdentifier;
+//! use stellar_client::sync::Client;
+//!
+//! let client = Client::horizon_test().unwrap();
+//! let scanner = DepositScanner::new("GATLAI2D7SSH6PE3HXTDPTRM4RE5VRK6HGA63K5EWP75PSANCZRFDNB5")
+//!     .with_asset(AssetIdentifier::native());
+//!
+//! // Resume from the paging token of the last deposit credited
+//! for deposit in scanner.scan(&client, "now").unwrap() {
+//!     let deposit = deposit.unwrap();
+//!     println!(
+//!         "{} {} for {:?} ({})",
+//!         deposit.amount(),
+//!         deposit.asset().code(),
+//!         deposit.customer_id(),
+//!         deposit.idempotency_key()
+//!     );
+//! }
+//! ```
+use client::sync::{Client, Stream};
+use endpoint::{account, transaction, Cursor};
+use error::Result;
+use resources::{Amount, AssetIdentifier, Memo, Operation, OperationKind, Transaction};
+
+/// A payment into the watched account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deposit {
+    operation_id: i64,
+    paging_token: String,
+    transaction_hash: String,
+    from: String,
+    asset: AssetIdentifier,
+    amount: Amount,
+    memo: Memo,
+    muxed_id: Option<u64>,
+}
+
+impl Deposit {
+    /// A key that is the same every time the deposit is seen, for crediting
+    /// it exactly once. It's the id of the payment operation, which is
+    /// unique across the network.
+    pub fn idempotency_key(&self) -> String {
+        self.operation_id.to_string()
+    }
+
+    /// The id of the payment operation.
+    pub fn operation_id(&self) -> i64 {
+        self.operation_id
+    }
+
+    /// The cursor to resume scanning from after this deposit.
+    pub fn paging_token(&self) -> &str {
+        &self.paging_token
+    }
+
+    /// The hash of the transaction that made the payment.
+    pub fn transaction_hash(&self) -> &str {
+        &self.transaction_hash
+    }
+
+    /// The account that sent the payment.
+    pub fn from(&self) -> &str {
+        &self.from
+    }
+
+    /// The asset received.
+    pub fn asset(&self) -> &AssetIdentifier {
+        &self.asset
+    }
+
+    /// The amount received.
+    pub fn amount(&self) -> Amount {
+        self.amount
+    }
+
+    /// The memo of the transaction.
+    pub fn memo(&self) -> &Memo {
+        &self.memo
+    }
+
+    /// The id of the multiplexed address the payment was sent to, if any.
+    pub fn muxed_id(&self) -> Option<u64> {
+        self.muxed_id
+    }
+
+    /// Who the deposit is for, as the sender gave it: the muxed id when the
+    /// payment went to a multiplexed address, otherwise an id or text memo.
+    /// `None` when the sender left it out, which usually means the deposit
+    /// needs a person to look at it.
+    pub fn customer_id(&self) -> Option<String> {
+        if let Some(id) = self.muxed_id {
+            return Some(id.to_string());
+        }
+        match self.memo {
+            Memo::Id(id) => Some(id.to_string()),
+            Memo::Text(ref text) => Some(text.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Picks the deposits out of the payments of an account.
+#[derive(Debug, Clone)]
+pub struct DepositScanner {
+    account_id: String,
+    assets: Vec<AssetIdentifier>,
+}
+
+impl DepositScanner {
+    /// Scans for payments of any asset into the account.
+    pub fn new(account_id: &str) -> DepositScanner {
+        DepositScanner {
+            account_id: account_id.to_string(),
+            assets: Vec::new(),
+        }
+    }
+
+    /// Only accepts payments of the asset. Can be given more than once to
+    /// accept several assets.
+    pub fn with_asset(mut self, asset: AssetIdentifier) -> DepositScanner {
+        self.assets.push(asset);
+        self
+    }
+
+    /// The account being watched.
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    /// The payment's sender, asset, amount and muxed id, if the operation
+    /// is a payment into the account of an accepted asset. Payments the
+    /// account makes to itself aren't deposits.
+    fn payment<'a>(
+        &self,
+        operation: &'a Operation,
+    ) -> Option<(&'a str, &'a AssetIdentifier, Amount, Option<u64>)> {
+        let payment = match *operation.kind() {
+            OperationKind::Payment(ref op) => (
+                op.from(),
+                op.to(),
+                op.asset(),
+                op.amount(),
+                op.to_muxed_id(),
+            ),
+            OperationKind::PathPayment(ref op) => (
+                op.from(),
+                op.to(),
+                op.destination_asset(),
+                op.destination_amount(),
+                op.to_muxed_id(),
+            ),
+            OperationKind::PathPaymentStrictSend(ref op) => (
+                op.from(),
+                op.to(),
+                op.destination_asset(),
+                op.destination_amount(),
+                op.to_muxed_id(),
+            ),
+            _ => return None,
+        };
+        let (from, to, asset, amount, muxed_id) = payment;
+        let accepted = self.assets.is_empty() || self.assets.contains(asset);
+        if to == self.account_id && from != self.account_id && accepted {
+            Some((from, asset, amount, muxed_id))
+        } else {
+            None
+        }
+    }
+
+    /// Whether the operation is a deposit, without looking at its
+    /// transaction.
+    pub fn accepts(&self, operation: &Operation) -> bool {
+        self.payment(operation).is_some()
+    }
+
+    /// The deposit made by the operation, given the transaction it's part
+    /// of, or `None` if the operation isn't a deposit.
+    pub fn deposit(&self, operation: &Operation, transaction: &Transaction) -> Option<Deposit> {
+        let (from, asset, amount, muxed_id) = self.payment(operation)?;
+        Some(Deposit {
+            operation_id: operation.id(),
+            paging_token: operation.paging_token().to_string(),
+            transaction_hash: operation.transaction().to_string(),
+            from: from.to_string(),
+            asset: asset.clone(),
+            amount,
+            memo: transaction.memo().clone(),
+            muxed_id,
+        })
+    }
+
+    /// Streams the deposits into the account made after the cursor, which
+    /// is `now` for new deposits only or the paging token of the last
+    /// deposit handled.
+    pub fn scan<'a>(
+        &self,
+        client: &'a Client,
+        cursor: &str,
+    ) -> Result<Deposits<'a, Stream<Operation>>> {
+        let payments =
+            client.stream(account::Payments::new(&self.account_id).with_cursor(cursor))?;
+        Ok(self.deposits(client, payments))
+    }
+
+    /// The deposits among payments from anywhere, such as a page of
+    /// `account::Payments` fetched to catch up before streaming. Payments
+    /// that aren't deposits are skipped, and transactions are fetched from
+    /// the client.
+    pub fn deposits<'a, I>(&self, client: &'a Client, payments: I) -> Deposits<'a, I>
+    where
+        I: Iterator<Item = Result<Operation>>,
+    {
+        Deposits {
+            scanner: self.clone(),
+            client,
+            payments,
+            transaction: None,
+        }
+    }
+}
+
+/// The deposits found by a `DepositScanner`.
+#[derive(Debug)]
+pub struct Deposits<'a, I> {
+    scanner: DepositScanner,
+    client: &'a Client,
+    payments: I,
+    transaction: Option<(String, Transaction)>,
+}
+
+impl<'a, I> Deposits<'a, I> {
+    /// The transaction with the hash, which is kept since a transaction
+    /// may pay into the account more than once.
+    fn transaction(&mut self, hash: &str) -> Result<&Transaction> {
+        let cached = match self.transaction {
+            Some((ref cached, _)) => cached == hash,
+            None => false,
+        };
+        if !cached {
+            let transaction = self.client.request(transaction::Details::new(hash))?;
+            self.transaction = Some((hash.to_string(), transaction));
+        }
+        Ok(self
+            .transaction
+            .as_ref()
+            .map(|cached| &cached.1)
+            .expect("The transaction was just fetched"))
+    }
+}
+
+impl<'a, I> Iterator for Deposits<'a, I>
+where
+    I: Iterator<Item = Result<Operation>>,
+{
+    type Item = Result<Deposit>;
+
+    fn next(&mut self) -> Option<Result<Deposit>> {
+        loop {
+            let operation = match self.payments.next()? {
+                Ok(operation) => operation,
+                Err(err) => return Some(Err(err)),
+            };
+            if !self.scanner.accepts(&operation) {
+                continue;
+            }
+            let scanner = self.scanner.clone();
+            return Some(
+                self.transaction(operation.transaction())
+                    .map(|transaction| {
+                        scanner
+                            .deposit(&operation, transaction)
+                            .expect("The operation was accepted")
+                    }),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod deposit_tests {
+    use super::*;
+    use client::sync::test_server::TestServer;
+    use serde_json;
+
+    const HOT_WALLET: &str = "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ";
+
+    fn payment(muxed_id: Option<u64>) -> Operation {
+        let json = include_str!("../fixtures/operations/payment.json");
+        let json = match muxed_id {
+            Some(id) => json.replace(
+                r#""type_i": 1,"#,
+                &format!(r#""type_i": 1, "to_muxed_id": "{}","#, id),
+            ),
+            None => json.to_string(),
+        };
+        serde_json::from_str(&json).unwrap()
+    }
+
+    /// A path payment of the given type into the hot wallet.
+    fn path_payment(type_name: &str) -> Operation {
+        let json = if type_name == "path_payment_strict_send" {
+            include_str!("../fixtures/operations/path_payment_strict_send.json").to_string()
+        } else {
+            include_str!("../fixtures/operations/path_payment.json").replace(
+                r#""type": "path_payment""#,
+                &format!(r#""type": "{}""#, type_name),
+            )
+        };
+        let json = json.replace(
+            "GA5WBPYA5Y4WAEHXWR2UKO2UO4BUGHUQ74EUPKON2QHV4WRHOIRNKKH2",
+            HOT_WALLET,
+        );
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn transaction() -> Transaction {
+        serde_json::from_str(include_str!(
+            "../fixtures/transactions/transaction_memo_id.json"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn it_accepts_payments_into_the_account() {
+        let scanner = DepositScanner::new(HOT_WALLET);
+        assert!(scanner.accepts(&payment(None)));
+        assert!(
+            !DepositScanner::new("GAKLBGHNHFQ3BMUYG5KU4BEWO6EYQHZHAXEWC33W34PH2RBHZDSQBD75")
+                .accepts(&payment(None))
+        );
+        let usd = AssetIdentifier::alphanum4("USD", HOT_WALLET);
+        assert!(!scanner
+            .clone()
+            .with_asset(usd.clone())
+            .accepts(&payment(None)));
+        assert!(scanner
+            .with_asset(usd)
+            .with_asset(AssetIdentifier::native())
+            .accepts(&payment(None)));
+    }
+
+    #[test]
+    fn it_accepts_path_payments_into_the_account() {
+        let scanner = DepositScanner::new(HOT_WALLET);
+        let eur = AssetIdentifier::alphanum4(
+            "EUR",
+            "GCQPYGH4K57XBDENKKX55KDTWOTK5WDWRQOH2LHEDX3EKVIQRLMESGBG",
+        );
+        for &type_name in &[
+            "path_payment",
+            "path_payment_strict_receive",
+            "path_payment_strict_send",
+        ] {
+            let operation = path_payment(type_name);
+            assert!(scanner.accepts(&operation), "{}", type_name);
+            let deposit = scanner.deposit(&operation, &transaction()).unwrap();
+            assert_eq!(deposit.asset(), &eur);
+        }
+        let usd_only = scanner.with_asset(AssetIdentifier::alphanum4(
+            "USD",
+            "GC23QF2HUE52AMXUFUH3AYJAXXGXXV2VHXYYR6EYXETPKDXZSAW67XO4",
+        ));
+        assert!(!usd_only.accepts(&path_payment("path_payment_strict_send")));
+    }
+
+    #[test]
+    fn it_reads_the_memo_of_the_transaction() {
+        let deposit = DepositScanner::new(HOT_WALLET)
+            .deposit(&payment(None), &transaction())
+            .unwrap();
+        assert_eq!(deposit.idempotency_key(), "58402965295104");
+        assert_eq!(deposit.amount(), Amount::new(2_000_000_000));
+        assert_eq!(deposit.memo(), &Memo::Id(19292920));
+        assert_eq!(deposit.customer_id(), Some("19292920".to_string()));
+    }
+
+    #[test]
+    fn it_prefers_the_muxed_id() {
+        let deposit = DepositScanner::new(HOT_WALLET)
+            .deposit(&payment(Some(7)), &transaction())
+            .unwrap();
+        assert_eq!(deposit.muxed_id(), Some(7));
+        assert_eq!(deposit.customer_id(), Some("7".to_string()));
+    }
+
+    #[test]
+    fn it_fetches_each_transaction_once() {
+        let server = TestServer::start(
+            "200 OK",
+            include_str!("../fixtures/transactions/transaction_memo_id.json"),
+        );
+        let client = Client::new(server.uri()).unwrap();
+        let elsewhere = DepositScanner::new(HOT_WALLET)
+            .with_asset(AssetIdentifier::alphanum4("USD", HOT_WALLET));
+        let payments = vec![Ok(payment(None)), Ok(payment(Some(1))), Ok(payment(None))];
+        let deposits: Vec<Deposit> = DepositScanner::new(HOT_WALLET)
+            .deposits(&client, payments.into_iter())
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(deposits.len(), 3);
+        assert_eq!(deposits[1].customer_id(), Some("1".to_string()));
+
+        let skipped = elsewhere
+            .deposits(&client, vec![Ok(payment(None))].into_iter())
+            .count();
+        assert_eq!(skipped, 0);
+        assert_eq!(server.hits(), 1);
+    }
+}
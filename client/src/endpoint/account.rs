@@ -1,8 +1,9 @@
 //! Contains endpoints for accessing accounts and related information.
-use super::{Body, Cursor, Direction, IntoRequest, Limit, Order, Records};
+use super::{join_from_wrap, push_join, Body, Direction, IntoRequest, Join, PageParams, Pageable,
+            Records};
 use error::Result;
 use http::{Request, Uri};
-use resources::{Account, Datum, Effect, Offer, Operation, Transaction};
+use resources::{Account, AssetIdentifier, Datum, Effect, Offer, Operation, Trade, Transaction};
 use std::str::FromStr;
 use uri::{self, TryFromUri, UriWrap};
 
@@ -30,7 +31,7 @@ use uri::{self, TryFromUri, UriWrap};
 ///
 /// assert_eq!(details.id(), account_id);
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Details {
     account_id: String,
 }
@@ -44,9 +45,9 @@ impl Details {
     ///
     /// let details = account::Details::new("abc123");
     /// ```
-    pub fn new(account_id: &str) -> Self {
+    pub fn new(account_id: impl Into<String>) -> Self {
         Self {
-            account_id: account_id.to_string(),
+            account_id: account_id.into(),
         }
     }
 }
@@ -75,9 +76,9 @@ impl IntoRequest for Details {
 /// let endpoint    = account::Data::new("GATLAI2D7SSH6PE3HXTDPTRM4RE5VRK6HGA63K5EWP75PSANCZRFDNB5", "Food");
 /// let record      = client.request(endpoint).unwrap();
 /// #
-/// # assert_eq!(record.value(), "Pizza");
+/// # assert_eq!(record.value_utf8(), Ok("Pizza"));
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Data {
     account_id: String,
     key: String,
@@ -92,10 +93,10 @@ impl Data {
     ///
     /// let data = account::Data::new("abc123", "Food");
     /// ```
-    pub fn new(account_id: &str, key: &str) -> Self {
+    pub fn new(account_id: impl Into<String>, key: impl Into<String>) -> Self {
         Self {
-            account_id: account_id.to_string(),
-            key: key.to_string(),
+            account_id: account_id.into(),
+            key: key.into(),
         }
     }
 }
@@ -135,6 +136,20 @@ mod account_tests {
         assert_eq!(request.uri().host().unwrap(), "horizon-testnet.stellar.org");
         assert_eq!(request.uri().path(), "/accounts/abc123/data/key");
     }
+
+    #[test]
+    fn it_takes_owned_or_borrowed_ids() {
+        let owned = String::from("abc123");
+        let request = Details::new(owned)
+            .into_request("https://horizon-testnet.stellar.org")
+            .unwrap();
+        assert_eq!(request.uri().path(), "/accounts/abc123");
+        let key = String::from("key");
+        let request = Data::new("abc123", &key)
+            .into_request("https://horizon-testnet.stellar.org")
+            .unwrap();
+        assert_eq!(request.uri().path(), "/accounts/abc123/data/key");
+    }
 }
 
 /// Represents the transaction for account endpoint on the stellar horizon server.
@@ -161,17 +176,13 @@ mod account_tests {
 ///
 /// assert!(acct_txns.records().len() > 0);
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Transactions {
     account_id: String,
-    cursor: Option<String>,
-    order: Option<Direction>,
-    limit: Option<u32>,
+    page: PageParams,
 }
 
-impl_cursor!(Transactions);
-impl_limit!(Transactions);
-impl_order!(Transactions);
+impl_pageable!(Transactions);
 
 impl Transactions {
     /// Creates a new account::Transactions endpoint struct. Hand this to the client in order to
@@ -182,40 +193,22 @@ impl Transactions {
     ///
     /// let txns = account::Transactions::new("abc123");
     /// ```
-    pub fn new(account_id: &str) -> Self {
+    pub fn new(account_id: impl Into<String>) -> Self {
         Self {
-            account_id: account_id.to_string(),
-            cursor: None,
-            order: None,
-            limit: None,
+            account_id: account_id.into(),
+            page: PageParams::default(),
         }
     }
-
-    fn has_query(&self) -> bool {
-        self.order.is_some() || self.cursor.is_some() || self.limit.is_some()
-    }
 }
 
 impl IntoRequest for Transactions {
     type Response = Records<Transaction>;
 
+    impl_paging_defaults!();
+
     fn into_request(self, host: &str) -> Result<Request<Body>> {
         let mut uri = format!("{}/accounts/{}/transactions", host, self.account_id);
-        if self.has_query() {
-            uri.push_str("?");
-
-            if let Some(cursor) = self.cursor {
-                uri.push_str(&format!("cursor={}&", cursor));
-            }
-
-            if let Some(order) = self.order {
-                uri.push_str(&format!("order={}&", order.to_string()));
-            }
-
-            if let Some(limit) = self.limit {
-                uri.push_str(&format!("limit={}", limit));
-            }
-        }
+        self.page.push_to(&mut uri)?;
 
         let uri = Uri::from_str(&uri)?;
         let request = Request::get(uri).body(Body::None)?;
@@ -227,12 +220,9 @@ impl TryFromUri for Transactions {
     fn try_from_wrap(wrap: &UriWrap) -> ::std::result::Result<Self, uri::Error> {
         match wrap.path() {
             ["accounts", account_id, "transactions"] => {
-                let params = wrap.params();
                 Ok(Self {
                     account_id: account_id.to_string(),
-                    cursor: params.get_parse("cursor").ok(),
-                    order: params.get_parse("order").ok(),
-                    limit: params.get_parse("limit").ok(),
+                    page: PageParams::from_wrap(wrap),
                 })
             }
             _ => Err(uri::Error::invalid_path()),
@@ -243,6 +233,7 @@ impl TryFromUri for Transactions {
 #[cfg(test)]
 mod transactions_tests {
     use super::*;
+    use endpoint::{Cursor, Limit, Order};
 
     #[test]
     fn it_leaves_off_the_params_if_not_specified() {
@@ -274,7 +265,7 @@ mod transactions_tests {
         assert_eq!(req.uri().path(), "/accounts/abc123/transactions");
         assert_eq!(
             req.uri().query(),
-            Some("cursor=CURSOR&order=desc&limit=123")
+            Some("order=desc&cursor=CURSOR&limit=123")
         );
     }
 
@@ -285,9 +276,9 @@ mod transactions_tests {
             .unwrap();
         let ep = Transactions::try_from(&uri).unwrap();
         assert_eq!(ep.account_id, "abc123");
-        assert_eq!(ep.limit, Some(123));
-        assert_eq!(ep.cursor, Some("CURSOR".to_string()));
-        assert_eq!(ep.order, Some(Direction::Desc));
+        assert_eq!(ep.limit(), Some(123));
+        assert_eq!(ep.cursor(), Some("CURSOR"));
+        assert_eq!(ep.order(), Some(Direction::Desc));
     }
 }
 
@@ -315,17 +306,13 @@ mod transactions_tests {
 ///
 /// assert!(effects.records().len() > 0);
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Effects {
     account_id: String,
-    cursor: Option<String>,
-    order: Option<Direction>,
-    limit: Option<u32>,
+    page: PageParams,
 }
 
-impl_cursor!(Effects);
-impl_limit!(Effects);
-impl_order!(Effects);
+impl_pageable!(Effects);
 
 impl Effects {
     /// Creates a new account::Effects endpoint struct. Hand this to the client in order to
@@ -336,40 +323,22 @@ impl Effects {
     ///
     /// let effects = account::Effects::new("abc123");
     /// ```
-    pub fn new(account_id: &str) -> Self {
+    pub fn new(account_id: impl Into<String>) -> Self {
         Self {
-            account_id: account_id.to_string(),
-            cursor: None,
-            order: None,
-            limit: None,
+            account_id: account_id.into(),
+            page: PageParams::default(),
         }
     }
-
-    fn has_query(&self) -> bool {
-        self.order.is_some() || self.cursor.is_some() || self.limit.is_some()
-    }
 }
 
 impl IntoRequest for Effects {
     type Response = Records<Effect>;
 
+    impl_paging_defaults!();
+
     fn into_request(self, host: &str) -> Result<Request<Body>> {
         let mut uri = format!("{}/accounts/{}/effects", host, self.account_id);
-        if self.has_query() {
-            uri.push_str("?");
-
-            if let Some(cursor) = self.cursor {
-                uri.push_str(&format!("cursor={}&", cursor));
-            }
-
-            if let Some(order) = self.order {
-                uri.push_str(&format!("order={}&", order.to_string()));
-            }
-
-            if let Some(limit) = self.limit {
-                uri.push_str(&format!("limit={}", limit));
-            }
-        }
+        self.page.push_to(&mut uri)?;
 
         let uri = Uri::from_str(&uri)?;
         let request = Request::get(uri).body(Body::None)?;
@@ -381,12 +350,9 @@ impl TryFromUri for Effects {
     fn try_from_wrap(wrap: &UriWrap) -> ::std::result::Result<Self, uri::Error> {
         match wrap.path() {
             ["accounts", account_id, "effects"] => {
-                let params = wrap.params();
                 Ok(Self {
                     account_id: account_id.to_string(),
-                    cursor: params.get_parse("cursor").ok(),
-                    order: params.get_parse("order").ok(),
-                    limit: params.get_parse("limit").ok(),
+                    page: PageParams::from_wrap(wrap),
                 })
             }
             _ => Err(uri::Error::invalid_path()),
@@ -397,6 +363,7 @@ impl TryFromUri for Effects {
 #[cfg(test)]
 mod effects_tests {
     use super::*;
+    use endpoint::{Cursor, Limit, Order};
 
     #[test]
     fn it_leaves_off_the_params_if_not_specified() {
@@ -427,7 +394,7 @@ mod effects_tests {
         let req = ep.into_request("https://horizon-testnet.stellar.org")
             .unwrap();
         assert_eq!(req.uri().path(), "/accounts/abc123/effects");
-        assert_eq!(req.uri().query(), Some("cursor=CURSOR&order=asc&limit=123"));
+        assert_eq!(req.uri().query(), Some("order=asc&cursor=CURSOR&limit=123"));
     }
 
     #[test]
@@ -437,9 +404,9 @@ mod effects_tests {
             .unwrap();
         let ep = Effects::try_from(&uri).unwrap();
         assert_eq!(ep.account_id, "abc123");
-        assert_eq!(ep.limit, Some(123));
-        assert_eq!(ep.cursor, Some("CURSOR".to_string()));
-        assert_eq!(ep.order, Some(Direction::Desc));
+        assert_eq!(ep.limit(), Some(123));
+        assert_eq!(ep.cursor(), Some("CURSOR"));
+        assert_eq!(ep.order(), Some(Direction::Desc));
     }
 }
 
@@ -469,17 +436,13 @@ mod effects_tests {
 ///
 /// assert!(account_operations.records().len() > 0);
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Operations {
     account_id: String,
-    cursor: Option<String>,
-    order: Option<Direction>,
-    limit: Option<u32>,
+    page: PageParams,
 }
 
-impl_cursor!(Operations);
-impl_limit!(Operations);
-impl_order!(Operations);
+impl_pageable!(Operations);
 
 impl Operations {
     /// Creates a new account::Operations endpoint struct.
@@ -489,41 +452,23 @@ impl Operations {
     ///
     /// let txns = account::Operations::new("abc123");
     /// ```
-    pub fn new(account_id: &str) -> Operations {
+    pub fn new(account_id: impl Into<String>) -> Operations {
         Operations {
-            account_id: account_id.to_string(),
-            cursor: None,
-            order: None,
-            limit: None,
+            account_id: account_id.into(),
+            page: PageParams::default(),
         }
     }
-
-    fn has_query(&self) -> bool {
-        self.order.is_some() || self.cursor.is_some() || self.limit.is_some()
-    }
 }
 
 impl IntoRequest for Operations {
     type Response = Records<Operation>;
 
+    impl_paging_defaults!();
+
     fn into_request(self, host: &str) -> Result<Request<Body>> {
         let mut uri = format!("{}/accounts/{}/operations", host, self.account_id);
 
-        if self.has_query() {
-            uri.push_str("?");
-
-            if let Some(order) = self.order {
-                uri.push_str(&format!("order={}&", order.to_string()));
-            }
-
-            if let Some(cursor) = self.cursor {
-                uri.push_str(&format!("cursor={}&", cursor));
-            }
-
-            if let Some(limit) = self.limit {
-                uri.push_str(&format!("limit={}", limit));
-            }
-        }
+        self.page.push_to(&mut uri)?;
 
         let uri = Uri::from_str(&uri)?;
         let request = Request::get(uri).body(Body::None)?;
@@ -535,12 +480,9 @@ impl TryFromUri for Operations {
     fn try_from_wrap(wrap: &UriWrap) -> ::std::result::Result<Self, uri::Error> {
         match wrap.path() {
             ["accounts", account_id, "operations"] => {
-                let params = wrap.params();
                 Ok(Self {
                     account_id: account_id.to_string(),
-                    cursor: params.get_parse("cursor").ok(),
-                    order: params.get_parse("order").ok(),
-                    limit: params.get_parse("limit").ok(),
+                    page: PageParams::from_wrap(wrap),
                 })
             }
             _ => Err(uri::Error::invalid_path()),
@@ -551,6 +493,7 @@ impl TryFromUri for Operations {
 #[cfg(test)]
 mod ledger_operations_tests {
     use super::*;
+    use endpoint::{Cursor, Limit, Order};
 
     #[test]
     fn it_leaves_off_the_params_if_not_specified() {
@@ -581,9 +524,9 @@ mod ledger_operations_tests {
             .unwrap();
         let ep = Operations::try_from(&uri).unwrap();
         assert_eq!(ep.account_id, "abc123");
-        assert_eq!(ep.limit, Some(123));
-        assert_eq!(ep.cursor, Some("CURSOR".to_string()));
-        assert_eq!(ep.order, Some(Direction::Desc));
+        assert_eq!(ep.limit(), Some(123));
+        assert_eq!(ep.cursor(), Some("CURSOR"));
+        assert_eq!(ep.order(), Some(Direction::Desc));
     }
 }
 
@@ -616,17 +559,15 @@ mod ledger_operations_tests {
 ///
 /// assert!(acct_payments.records().len() > 0);
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Payments {
     account_id: String,
-    cursor: Option<String>,
-    order: Option<Direction>,
-    limit: Option<u32>,
+    page: PageParams,
+    #[serde(default)]
+    join: bool,
 }
 
-impl_cursor!(Payments);
-impl_limit!(Payments);
-impl_order!(Payments);
+impl_pageable!(Payments);
 
 impl Payments {
     /// Creates a new account::Payments endpoint struct. Hand this to the client in order to
@@ -637,40 +578,24 @@ impl Payments {
     ///
     /// let payments = account::Payments::new("abc123");
     /// ```
-    pub fn new(account_id: &str) -> Self {
+    pub fn new(account_id: impl Into<String>) -> Self {
         Self {
-            account_id: account_id.to_string(),
-            cursor: None,
-            order: None,
-            limit: None,
+            account_id: account_id.into(),
+            page: PageParams::default(),
+            join: false,
         }
     }
-
-    fn has_query(&self) -> bool {
-        self.order.is_some() || self.cursor.is_some() || self.limit.is_some()
-    }
 }
 
 impl IntoRequest for Payments {
     type Response = Records<Operation>;
 
+    impl_paging_defaults!();
+
     fn into_request(self, host: &str) -> Result<Request<Body>> {
         let mut uri = format!("{}/accounts/{}/payments", host, self.account_id);
-        if self.has_query() {
-            uri.push_str("?");
-
-            if let Some(cursor) = self.cursor {
-                uri.push_str(&format!("cursor={}&", cursor));
-            }
-
-            if let Some(order) = self.order {
-                uri.push_str(&format!("order={}&", order.to_string()));
-            }
-
-            if let Some(limit) = self.limit {
-                uri.push_str(&format!("limit={}", limit));
-            }
-        }
+        self.page.push_to(&mut uri)?;
+        push_join(&mut uri, self.join);
 
         let uri = Uri::from_str(&uri)?;
         let request = Request::get(uri).body(Body::None)?;
@@ -682,12 +607,10 @@ impl TryFromUri for Payments {
     fn try_from_wrap(wrap: &UriWrap) -> ::std::result::Result<Self, uri::Error> {
         match wrap.path() {
             ["accounts", account_id, "payments"] => {
-                let params = wrap.params();
                 Ok(Self {
                     account_id: account_id.to_string(),
-                    cursor: params.get_parse("cursor").ok(),
-                    order: params.get_parse("order").ok(),
-                    limit: params.get_parse("limit").ok(),
+                    page: PageParams::from_wrap(wrap),
+                    join: join_from_wrap(wrap),
                 })
             }
             _ => Err(uri::Error::invalid_path()),
@@ -695,9 +618,21 @@ impl TryFromUri for Payments {
     }
 }
 
+impl Join for Payments {
+    fn with_join_transactions(mut self) -> Payments {
+        self.join = true;
+        self
+    }
+
+    fn joins_transactions(&self) -> bool {
+        self.join
+    }
+}
+
 #[cfg(test)]
 mod payments_tests {
     use super::*;
+    use endpoint::{Cursor, Limit, Order};
 
     #[test]
     fn it_can_make_a_payments_uri() {
@@ -710,6 +645,18 @@ mod payments_tests {
         assert_eq!(request.uri().query(), None);
     }
 
+    #[test]
+    fn it_asks_for_joined_transactions() {
+        let ep = Payments::new("abc123").with_join_transactions().with_limit(10);
+        assert!(ep.joins_transactions());
+        let req = ep.into_request("https://www.google.com").unwrap();
+        assert_eq!(req.uri().path(), "/accounts/abc123/payments");
+        assert_eq!(req.uri().query(), Some("limit=10&join=transactions"));
+
+        let uri: Uri = "/accounts/abc123/payments?join=transactions".parse().unwrap();
+        assert!(Payments::try_from(&uri).unwrap().joins_transactions());
+    }
+
     #[test]
     fn it_puts_the_query_params_on_the_uri() {
         let ep = Payments::new("abc123")
@@ -720,7 +667,7 @@ mod payments_tests {
         assert_eq!(req.uri().path(), "/accounts/abc123/payments");
         assert_eq!(
             req.uri().query(),
-            Some("cursor=CURSOR&order=desc&limit=123")
+            Some("order=desc&cursor=CURSOR&limit=123")
         );
     }
 
@@ -731,9 +678,9 @@ mod payments_tests {
             .unwrap();
         let ep = Payments::try_from(&uri).unwrap();
         assert_eq!(ep.account_id, "abc123");
-        assert_eq!(ep.limit, Some(123));
-        assert_eq!(ep.cursor, Some("CURSOR".to_string()));
-        assert_eq!(ep.order, Some(Direction::Desc));
+        assert_eq!(ep.limit(), Some(123));
+        assert_eq!(ep.cursor(), Some("CURSOR"));
+        assert_eq!(ep.order(), Some(Direction::Desc));
     }
 }
 
@@ -760,17 +707,13 @@ mod payments_tests {
 ///
 /// assert!(offers.records().len() > 0);
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Offers {
     account_id: String,
-    cursor: Option<String>,
-    order: Option<Direction>,
-    limit: Option<u32>,
+    page: PageParams,
 }
 
-impl_cursor!(Offers);
-impl_limit!(Offers);
-impl_order!(Offers);
+impl_pageable!(Offers);
 
 impl Offers {
     /// Creates a new account::Offers endpoint struct. Hand this to the client in order to
@@ -781,40 +724,22 @@ impl Offers {
     ///
     /// let offers = account::Offers::new("abc123");
     /// ```
-    pub fn new(account_id: &str) -> Self {
+    pub fn new(account_id: impl Into<String>) -> Self {
         Self {
-            account_id: account_id.to_string(),
-            cursor: None,
-            order: None,
-            limit: None,
+            account_id: account_id.into(),
+            page: PageParams::default(),
         }
     }
-
-    fn has_query(&self) -> bool {
-        self.order.is_some() || self.cursor.is_some() || self.limit.is_some()
-    }
 }
 
 impl IntoRequest for Offers {
     type Response = Records<Offer>;
 
+    impl_paging_defaults!();
+
     fn into_request(self, host: &str) -> Result<Request<Body>> {
         let mut uri = format!("{}/accounts/{}/offers", host, self.account_id);
-        if self.has_query() {
-            uri.push_str("?");
-
-            if let Some(cursor) = self.cursor {
-                uri.push_str(&format!("cursor={}&", cursor));
-            }
-
-            if let Some(order) = self.order {
-                uri.push_str(&format!("order={}&", order.to_string()));
-            }
-
-            if let Some(limit) = self.limit {
-                uri.push_str(&format!("limit={}", limit));
-            }
-        }
+        self.page.push_to(&mut uri)?;
 
         let uri = Uri::from_str(&uri)?;
         let request = Request::get(uri).body(Body::None)?;
@@ -826,12 +751,9 @@ impl TryFromUri for Offers {
     fn try_from_wrap(wrap: &UriWrap) -> ::std::result::Result<Self, uri::Error> {
         match wrap.path() {
             ["accounts", account_id, "offers"] => {
-                let params = wrap.params();
                 Ok(Self {
                     account_id: account_id.to_string(),
-                    cursor: params.get_parse("cursor").ok(),
-                    order: params.get_parse("order").ok(),
-                    limit: params.get_parse("limit").ok(),
+                    page: PageParams::from_wrap(wrap),
                 })
             }
             _ => Err(uri::Error::invalid_path()),
@@ -842,6 +764,7 @@ impl TryFromUri for Offers {
 #[cfg(test)]
 mod offers_tests {
     use super::*;
+    use endpoint::{Cursor, Limit, Order};
 
     #[test]
     fn it_can_make_an_offers_uri() {
@@ -864,7 +787,7 @@ mod offers_tests {
         assert_eq!(req.uri().path(), "/accounts/abc123/offers");
         assert_eq!(
             req.uri().query(),
-            Some("cursor=CURSOR&order=desc&limit=123")
+            Some("order=desc&cursor=CURSOR&limit=123")
         );
     }
 
@@ -875,8 +798,354 @@ mod offers_tests {
             .unwrap();
         let ep = Offers::try_from(&uri).unwrap();
         assert_eq!(ep.account_id, "abc123");
-        assert_eq!(ep.limit, Some(123));
-        assert_eq!(ep.cursor, Some("CURSOR".to_string()));
-        assert_eq!(ep.order, Some(Direction::Desc));
+        assert_eq!(ep.limit(), Some(123));
+        assert_eq!(ep.cursor(), Some("CURSOR"));
+        assert_eq!(ep.order(), Some(Direction::Desc));
+    }
+}
+
+/// Represents the trades for account endpoint on the stellar horizon server.
+/// The endpoint will return all the trades a specific account took part in,
+/// on either side.
+///
+/// <https://developers.stellar.org/api/resources/accounts/trades/>
+///
+/// ## Example
+/// ```
+/// use stellar_client::sync::Client;
+/// use stellar_client::endpoint::{account, trade, Limit};
+///
+/// let client = Client::horizon_test().unwrap();
+///
+/// // Grab a trade and its base account to ensure an account with trades
+/// let trades      = client.request(trade::All::default().with_limit(1)).unwrap();
+/// let trade       = &trades.records()[0];
+/// let account_id  = trade.base_account();
+///
+/// // Now we issue a request for that account's trades
+/// let endpoint = account::Trades::new(account_id);
+/// let trades   = client.request(endpoint).unwrap();
+///
+/// assert!(trades.records().len() > 0);
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Trades {
+    account_id: String,
+    page: PageParams,
+}
+
+impl_pageable!(Trades);
+
+impl Trades {
+    /// Creates a new account::Trades endpoint struct. Hand this to the client in order to
+    /// request the trades of a specific account.
+    ///
+    /// ```
+    /// use stellar_client::endpoint::account;
+    ///
+    /// let trades = account::Trades::new("abc123");
+    /// ```
+    pub fn new(account_id: impl Into<String>) -> Self {
+        Self {
+            account_id: account_id.into(),
+            page: PageParams::default(),
+        }
+    }
+}
+
+impl IntoRequest for Trades {
+    type Response = Records<Trade>;
+
+    impl_paging_defaults!();
+
+    fn into_request(self, host: &str) -> Result<Request<Body>> {
+        let mut uri = format!("{}/accounts/{}/trades", host, self.account_id);
+        self.page.push_to(&mut uri)?;
+
+        let uri = Uri::from_str(&uri)?;
+        let request = Request::get(uri).body(Body::None)?;
+        Ok(request)
+    }
+}
+
+impl TryFromUri for Trades {
+    fn try_from_wrap(wrap: &UriWrap) -> ::std::result::Result<Self, uri::Error> {
+        match wrap.path() {
+            ["accounts", account_id, "trades"] => Ok(Self {
+                account_id: account_id.to_string(),
+                page: PageParams::from_wrap(wrap),
+            }),
+            _ => Err(uri::Error::invalid_path()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod trades_tests {
+    use super::*;
+    use endpoint::{Cursor, Limit, Order};
+
+    #[test]
+    fn it_puts_the_query_params_on_the_uri() {
+        let ep = Trades::new("abc123")
+            .with_cursor("CURSOR")
+            .with_order(Direction::Desc)
+            .with_limit(123);
+        let req = ep.into_request("https://www.google.com").unwrap();
+        assert_eq!(req.uri().path(), "/accounts/abc123/trades");
+        assert_eq!(
+            req.uri().query(),
+            Some("order=desc&cursor=CURSOR&limit=123")
+        );
+    }
+
+    #[test]
+    fn it_parses_from_a_uri() {
+        let uri: Uri = "/accounts/abc123/trades?cursor=CURSOR&order=desc&limit=123"
+            .parse()
+            .unwrap();
+        let ep = Trades::try_from(&uri).unwrap();
+        assert_eq!(ep.account_id, "abc123");
+        assert_eq!(ep.limit(), Some(123));
+        assert_eq!(ep.cursor(), Some("CURSOR"));
+        assert_eq!(ep.order(), Some(Direction::Desc));
+    }
+}
+
+/// The filter applied to the all accounts endpoint. Horizon requires
+/// exactly one.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+enum Filter {
+    Signer(String),
+    Asset(AssetIdentifier),
+    Sponsor(String),
+    LiquidityPool(String),
+}
+
+impl Filter {
+    fn to_param(&self) -> String {
+        match *self {
+            Filter::Signer(ref signer) => format!("signer={}", signer),
+            Filter::Asset(ref asset) if asset.is_native() => "asset=native".to_string(),
+            Filter::Asset(ref asset) => format!("asset={}:{}", asset.code(), asset.issuer()),
+            Filter::Sponsor(ref sponsor) => format!("sponsor={}", sponsor),
+            Filter::LiquidityPool(ref id) => format!("liquidity_pool={}", id),
+        }
+    }
+}
+
+impl TryFromUri for Filter {
+    fn try_from_wrap(wrap: &UriWrap) -> ::std::result::Result<Filter, uri::Error> {
+        let params = wrap.params();
+        let mut filters = Vec::new();
+        if let Some(signer) = params.get("signer") {
+            filters.push(Filter::Signer(signer.to_string()));
+        }
+        if let Some(asset) = params.get("asset") {
+            filters.push(Filter::Asset(AssetIdentifier::from_canonical(asset)?));
+        }
+        if let Some(sponsor) = params.get("sponsor") {
+            filters.push(Filter::Sponsor(sponsor.to_string()));
+        }
+        if let Some(id) = params.get("liquidity_pool") {
+            filters.push(Filter::LiquidityPool(id.to_string()));
+        }
+        match filters.len() {
+            0 => Err(uri::Error::missing_query_param(
+                "signer, asset, sponsor or liquidity_pool",
+            )),
+            1 => Ok(filters.remove(0)),
+            _ => Err("Only one of signer, asset, sponsor or liquidity_pool may be given".into()),
+        }
+    }
+}
+
+/// Represents the all accounts endpoint on the stellar horizon server. The
+/// endpoint returns the accounts matching exactly one filter: accounts with
+/// a given signer, accounts trusting an asset, accounts sponsored by an
+/// account, or accounts participating in a liquidity pool. Since the filters
+/// are mutually exclusive, each has its own constructor.
+///
+/// <https://developers.stellar.org/api/resources/accounts/list/>
+///
+/// ## Example
+/// ```
+/// use stellar_client::sync::Client;
+/// use stellar_client::endpoint::{account, Limit};
+///
+/// let client   = Client::horizon_test().unwrap();
+/// let endpoint = account::All::for_signer("GATLAI2D7SSH6PE3HXTDPTRM4RE5VRK6HGA63K5EWP75PSANCZRFDNB5")
+///     .with_limit(5);
+/// let accounts = client.request(endpoint).unwrap();
+/// #
+/// # assert!(accounts.records().len() > 0);
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct All {
+    filter: Filter,
+    page: PageParams,
+}
+
+impl_pageable!(All);
+
+impl All {
+    fn new(filter: Filter) -> Self {
+        Self {
+            filter,
+            page: PageParams::default(),
+        }
+    }
+
+    /// Fetches the accounts that have the given account or key as a signer.
+    ///
+    /// ```
+    /// use stellar_client::endpoint::account;
+    ///
+    /// let accounts = account::All::for_signer("abc123");
+    /// ```
+    pub fn for_signer(signer: impl Into<String>) -> Self {
+        Self::new(Filter::Signer(signer.into()))
+    }
+
+    /// Fetches the accounts holding a trustline to the given asset.
+    ///
+    /// ```
+    /// use stellar_client::endpoint::account;
+    /// use stellar_client::resources::AssetIdentifier;
+    ///
+    /// let accounts = account::All::for_asset(AssetIdentifier::alphanum4("USD", "abc123"));
+    /// ```
+    pub fn for_asset(asset: AssetIdentifier) -> Self {
+        Self::new(Filter::Asset(asset))
+    }
+
+    /// Fetches the accounts whose reserves, subentries or signers are
+    /// sponsored by the given account.
+    ///
+    /// ```
+    /// use stellar_client::endpoint::account;
+    ///
+    /// let accounts = account::All::for_sponsor("abc123");
+    /// ```
+    pub fn for_sponsor(sponsor: impl Into<String>) -> Self {
+        Self::new(Filter::Sponsor(sponsor.into()))
+    }
+
+    /// Fetches the accounts holding shares in the given liquidity pool.
+    ///
+    /// ```
+    /// use stellar_client::endpoint::account;
+    ///
+    /// let accounts = account::All::for_liquidity_pool("abc123");
+    /// ```
+    pub fn for_liquidity_pool(liquidity_pool_id: impl Into<String>) -> Self {
+        Self::new(Filter::LiquidityPool(liquidity_pool_id.into()))
+    }
+}
+
+impl IntoRequest for All {
+    type Response = Records<Account>;
+
+    impl_paging_defaults!();
+
+    fn into_request(self, host: &str) -> Result<Request<Body>> {
+        let mut uri = format!("{}/accounts?{}", host, self.filter.to_param());
+
+        self.page.push_to(&mut uri)?;
+
+        let uri = Uri::from_str(&uri)?;
+        let request = Request::get(uri).body(Body::None)?;
+        Ok(request)
+    }
+}
+
+impl TryFromUri for All {
+    fn try_from_wrap(wrap: &UriWrap) -> ::std::result::Result<Self, uri::Error> {
+        match wrap.path() {
+            ["accounts"] => {
+                Ok(Self {
+                    filter: Filter::try_from_wrap(wrap)?,
+                    page: PageParams::from_wrap(wrap),
+                })
+            }
+            _ => Err(uri::Error::invalid_path()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod all_accounts_tests {
+    use super::*;
+    use endpoint::{Cursor, Limit, Order};
+    use serde_json;
+
+    const ISSUER: &str = "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3";
+
+    #[test]
+    fn it_puts_the_filter_on_the_uri() {
+        let ep = All::for_signer("abc123");
+        let req = ep.into_request("https://www.google.com").unwrap();
+        assert_eq!(req.uri().path(), "/accounts");
+        assert_eq!(req.uri().query(), Some("signer=abc123"));
+    }
+
+    #[test]
+    fn it_puts_the_asset_in_canonical_form() {
+        let ep = All::for_asset(AssetIdentifier::alphanum4("USD", ISSUER))
+            .with_cursor("CURSOR")
+            .with_order(Direction::Desc)
+            .with_limit(123);
+        let req = ep.into_request("https://www.google.com").unwrap();
+        assert_eq!(
+            req.uri().query(),
+            Some(&*format!(
+                "asset=USD:{}&order=desc&cursor=CURSOR&limit=123",
+                ISSUER
+            ))
+        );
+    }
+
+    #[test]
+    fn it_parses_from_a_uri() {
+        let uri: Uri = format!("/accounts?asset=USD:{}&cursor=CURSOR&order=desc&limit=123", ISSUER)
+            .parse()
+            .unwrap();
+        let ep = All::try_from(&uri).unwrap();
+        assert_eq!(
+            ep.filter,
+            Filter::Asset(AssetIdentifier::alphanum4("USD", ISSUER))
+        );
+        assert_eq!(ep.limit(), Some(123));
+        assert_eq!(ep.cursor(), Some("CURSOR"));
+        assert_eq!(ep.order(), Some(Direction::Desc));
+
+        let uri: Uri = "/accounts?liquidity_pool=abc123".parse().unwrap();
+        let ep = All::try_from(&uri).unwrap();
+        assert_eq!(ep.filter, Filter::LiquidityPool("abc123".to_string()));
+    }
+
+    #[test]
+    fn it_restores_a_persisted_endpoint() {
+        let ep = All::for_asset(AssetIdentifier::alphanum4("USD", ISSUER))
+            .with_cursor("CURSOR")
+            .with_order(Direction::Desc)
+            .with_limit(123);
+        let json = serde_json::to_string(&ep).unwrap();
+        let restored: All = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.filter, ep.filter);
+        assert_eq!(restored.cursor(), Some("CURSOR"));
+        assert_eq!(
+            restored.into_request("https://www.google.com").unwrap().uri(),
+            ep.into_request("https://www.google.com").unwrap().uri()
+        );
+    }
+
+    #[test]
+    fn it_requires_exactly_one_filter() {
+        let uri: Uri = "/accounts?limit=10".parse().unwrap();
+        assert!(All::try_from(&uri).is_err());
+
+        let uri: Uri = "/accounts?signer=abc123&sponsor=abc123".parse().unwrap();
+        assert!(All::try_from(&uri).is_err());
     }
 }
@@ -1,5 +1,5 @@
 //! Contains endpoints for assets and related information to specific assets.
-use super::{Body, Cursor, Direction, IntoRequest, Limit, Order, Records};
+use super::{push_param, Body, Direction, IntoRequest, PageParams, Pageable, Records};
 use error::Result;
 use http::{Request, Uri};
 use resources::Asset;
@@ -23,18 +23,14 @@ use uri::{self, TryFromUri, UriWrap};
 /// #
 /// # assert!(records.records().len() > 0);
 /// ```
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct All {
     code: Option<String>,
     issuer: Option<String>,
-    cursor: Option<String>,
-    order: Option<Direction>,
-    limit: Option<u32>,
+    page: PageParams,
 }
 
-impl_cursor!(All);
-impl_limit!(All);
-impl_order!(All);
+impl_pageable!(All);
 
 impl All {
     /// Fetches all records for a given asset code.
@@ -52,8 +48,8 @@ impl All {
     /// # assert!(records.records().len() > 0);
     /// # assert_eq!(records.records()[0].code(), "USD");
     /// ```
-    pub fn with_asset_code(mut self, code: &str) -> Self {
-        self.code = Some(code.to_string());
+    pub fn with_asset_code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
         self
     }
 
@@ -75,45 +71,27 @@ impl All {
     /// # assert!(records.records().len() > 0);
     /// # assert_eq!(records.records()[0].issuer(), issuer);
     /// ```
-    pub fn with_asset_issuer(mut self, issuer: &str) -> Self {
-        self.issuer = Some(issuer.to_string());
+    pub fn with_asset_issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.issuer = Some(issuer.into());
         self
     }
-
-    fn has_query(&self) -> bool {
-        self.code.is_some() || self.issuer.is_some() || self.order.is_some()
-            || self.cursor.is_some() || self.limit.is_some()
-    }
 }
 
 impl IntoRequest for All {
     type Response = Records<Asset>;
 
+    impl_paging_defaults!();
+
     fn into_request(self, host: &str) -> Result<Request<Body>> {
         let mut uri = format!("{}/assets", host);
 
-        if self.has_query() {
-            uri.push_str("?");
-            if let Some(code) = self.code {
-                uri.push_str(&format!("asset_code={}&", code));
-            }
-
-            if let Some(issuer) = self.issuer {
-                uri.push_str(&format!("asset_issuer={}&", issuer));
-            }
-
-            if let Some(order) = self.order {
-                uri.push_str(&format!("order={}&", order.to_string()));
-            }
-
-            if let Some(cursor) = self.cursor {
-                uri.push_str(&format!("cursor={}&", cursor));
-            }
-
-            if let Some(limit) = self.limit {
-                uri.push_str(&format!("limit={}", limit));
-            }
+        if let Some(ref code) = self.code {
+            push_param(&mut uri, "asset_code", code);
+        }
+        if let Some(ref issuer) = self.issuer {
+            push_param(&mut uri, "asset_issuer", issuer);
         }
+        self.page.push_to(&mut uri)?;
 
         let uri = Uri::from_str(&uri)?;
         let request = Request::get(uri).body(Body::None)?;
@@ -127,9 +105,7 @@ impl TryFromUri for All {
         Ok(All {
             code: params.get_parse("asset_code").ok(),
             issuer: params.get_parse("asset_issuer").ok(),
-            cursor: params.get_parse("cursor").ok(),
-            order: params.get_parse("order").ok(),
-            limit: params.get_parse("limit").ok(),
+            page: PageParams::from_wrap(wrap),
         })
     }
 }
@@ -137,6 +113,7 @@ impl TryFromUri for All {
 #[cfg(test)]
 mod all_assets_tests {
     use super::*;
+    use endpoint::{Cursor, Limit, Order};
 
     #[test]
     fn it_leaves_off_the_params_if_not_specified() {
@@ -171,8 +148,8 @@ mod all_assets_tests {
         let ep = All::try_from(&uri).unwrap();
         assert_eq!(ep.code, Some("CODE".to_string()));
         assert_eq!(ep.issuer, Some("ISSUER".to_string()));
-        assert_eq!(ep.limit, Some(123));
-        assert_eq!(ep.cursor, Some("CURSOR".to_string()));
-        assert_eq!(ep.order, Some(Direction::Desc));
+        assert_eq!(ep.limit(), Some(123));
+        assert_eq!(ep.cursor(), Some("CURSOR"));
+        assert_eq!(ep.order(), Some(Direction::Desc));
     }
 }
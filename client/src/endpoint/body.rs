@@ -0,0 +1,120 @@
+use error::Result;
+use serde::Serialize;
+use serde_json;
+use uri;
+
+/// Represents the body of a request to an IntoRequest.
+///
+/// Clients send the body with the content type it calls for, unless the
+/// endpoint already set a `Content-Type` header.
+///
+/// ## Example
+///
+/// ```
+/// use stellar_client::endpoint::Body;
+///
+/// let form = Body::form(vec![("tx", "AAAA+b/c==")]);
+/// assert_eq!(form.as_str(), Some("tx=AAAA%2Bb%2Fc%3D%3D"));
+/// assert_eq!(form.content_type(), Some("application/x-www-form-urlencoded"));
+///
+/// let json = Body::json(&vec!["GABC"]).unwrap();
+/// assert_eq!(json.as_str(), Some(r#"["GABC"]"#));
+/// assert_eq!(json.content_type(), Some("application/json"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Body {
+    /// Declares that the endpoint does not have a body.
+    None,
+    /// A form encoded body, such as the transaction envelope posted to
+    /// `/transactions`.
+    Form(String),
+    /// A json body, as posted to the services of anchors.
+    Json(String),
+}
+
+impl Body {
+    /// A form encoded body from names and values, which are percent encoded.
+    pub fn form<'a, I>(pairs: I) -> Body
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        let encoded: Vec<String> = pairs
+            .into_iter()
+            .map(|(name, value)| {
+                format!(
+                    "{}={}",
+                    uri::percent_encode(name),
+                    uri::percent_encode(value)
+                )
+            })
+            .collect();
+        Body::Form(encoded.join("&"))
+    }
+
+    /// A json body serialized from the value.
+    pub fn json<T>(value: &T) -> Result<Body>
+    where
+        T: Serialize,
+    {
+        Ok(Body::Json(serde_json::to_string(value)?))
+    }
+
+    /// The content type the body is sent with, or `None` without a body.
+    pub fn content_type(&self) -> Option<&'static str> {
+        match *self {
+            Body::None => None,
+            Body::Form(_) => Some("application/x-www-form-urlencoded"),
+            Body::Json(_) => Some("application/json"),
+        }
+    }
+
+    /// The encoded body, or `None` without a body.
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            Body::None => None,
+            Body::Form(ref body) | Body::Json(ref body) => Some(body),
+        }
+    }
+}
+
+#[cfg(test)]
+mod body_tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn it_encodes_forms() {
+        let body = Body::form(vec![("tx", "AAAA+b/c=="), ("memo type", "text")]);
+        assert_eq!(
+            body,
+            Body::Form("tx=AAAA%2Bb%2Fc%3D%3D&memo%20type=text".to_string())
+        );
+        assert_eq!(Body::form(vec![]), Body::Form(String::new()));
+    }
+
+    #[test]
+    fn it_encodes_json() {
+        let mut fields = BTreeMap::new();
+        fields.insert("account", "GABC");
+        fields.insert("asset_code", "USD");
+        let body = Body::json(&fields).unwrap();
+        assert_eq!(
+            body.as_str(),
+            Some(r#"{"account":"GABC","asset_code":"USD"}"#)
+        );
+    }
+
+    #[test]
+    fn it_knows_its_content_type() {
+        assert_eq!(Body::None.content_type(), None);
+        assert_eq!(Body::None.as_str(), None);
+        assert_eq!(
+            Body::Form("a=b".to_string()).content_type(),
+            Some("application/x-www-form-urlencoded")
+        );
+        assert_eq!(
+            Body::Json("{}".to_string()).content_type(),
+            Some("application/json")
+        );
+    }
+}
@@ -1,9 +1,15 @@
+//! Where a page starts. Horizon calls the query param `cursor`, so the trait
+//! for endpoints that take it is `Cursor`, and the value it takes, a paging
+//! token or one of horizon's sentinels, is a `Position`.
+use super::Pageable;
+use std::fmt;
+
 /// Declares that this endpoint has a cursor and can have it set.
 ///
 /// ## Example
 ///
 /// ```
-/// use stellar_client::endpoint::{Cursor, transaction};
+/// use stellar_client::endpoint::{Cursor, Position, transaction};
 ///
 /// let txns = transaction::All::default();
 /// assert_eq!(txns.cursor(), None);
@@ -11,46 +17,161 @@
 /// let txns = txns.with_cursor("CURSOR");
 /// assert_eq!(txns.cursor(), Some("CURSOR"));
 ///
+/// let txns = txns.with_cursor(Position::Now);
+/// assert_eq!(txns.cursor(), Some("now"));
+/// assert_eq!(txns.position(), Some(Position::Now));
 /// ```
 pub trait Cursor {
-    /// Sets a cursor on the struct and returns an owned version.
-    fn with_cursor(self, cursor: &str) -> Self;
+    /// Sets a cursor on the struct and returns an owned version. Takes a
+    /// `Position` or a paging token.
+    fn with_cursor<P: Into<Position>>(self, cursor: P) -> Self;
 
     /// Returns the cursor that has been set, if it has been set.
     fn cursor(&self) -> Option<&str>;
+
+    /// Returns the cursor that has been set as a `Position`, if it has been
+    /// set.
+    fn position(&self) -> Option<Position> {
+        self.cursor().map(Position::from)
+    }
 }
 
-#[allow(unused_macros)]
-macro_rules! impl_cursor {
-    ($name:path) => {
-        impl Cursor for $name {
-            fn with_cursor(mut self, cursor: &str) -> $name {
-                self.cursor = Some(cursor.to_string());
-                self
-            }
-
-            fn cursor(&self) -> Option<&str> {
-                // Take the cursor as a ref, then map the string to a slice. Needs
-                // two derefs to deref to String then to str then return reference
-                self.cursor.as_ref().map(|s| &**s)
-            }
+impl<T: Pageable> Cursor for T {
+    fn with_cursor<P: Into<Position>>(mut self, cursor: P) -> T {
+        self.page_params_mut().set_cursor(cursor.into().as_str());
+        self
+    }
+
+    fn cursor(&self) -> Option<&str> {
+        self.page_params().cursor()
+    }
+}
+
+/// The position in a list of records that a page starts after.
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Hash)]
+pub enum Position {
+    /// The present. Streams from here only see records made after they
+    /// start.
+    Now,
+    /// The start of history. Ascending pages from here start with the
+    /// oldest record.
+    First,
+    /// The paging token of a record, which pages start after.
+    Token(String),
+}
+
+impl Position {
+    /// The value of the `cursor` query param for the position.
+    pub fn as_str(&self) -> &str {
+        match *self {
+            Position::Now => "now",
+            Position::First => "0",
+            Position::Token(ref token) => token,
         }
-    };
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<'a> From<&'a str> for Position {
+    fn from(cursor: &'a str) -> Position {
+        match cursor {
+            "now" => Position::Now,
+            "0" => Position::First,
+            token => Position::Token(token.to_string()),
+        }
+    }
+}
+
+impl<'a> From<&'a String> for Position {
+    fn from(cursor: &'a String) -> Position {
+        Position::from(cursor.as_str())
+    }
+}
+
+impl From<String> for Position {
+    fn from(cursor: String) -> Position {
+        match cursor.as_str() {
+            "now" => Position::Now,
+            "0" => Position::First,
+            _ => Position::Token(cursor),
+        }
+    }
+}
+
+/// The cursor that starts a page with the first record of a ledger. Ledgers,
+/// transactions, operations, payments and effects have paging tokens that
+/// begin with the sequence of their ledger, so ascending pages from this
+/// cursor hold the records of that ledger and every ledger since.
+///
+/// Cursors are exclusive and a ledger's own paging token is its sequence
+/// shifted into the high 32 bits, so the cursor is the token just before it.
+///
+/// ## Example
+///
+/// ```
+/// use stellar_client::endpoint::{ledger_cursor, payment, Cursor};
+///
+/// let payments = payment::All::default().with_cursor(&ledger_cursor(3));
+/// assert_eq!(payments.cursor(), Some("12884901887"));
+/// ```
+pub fn ledger_cursor(sequence: u32) -> String {
+    (u64::from(sequence) << 32).saturating_sub(1).to_string()
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use endpoint::PageParams;
 
-    #[test]
-    fn it_can_be_derived() {
-        impl_cursor!(Foo);
-        struct Foo {
-            cursor: Option<String>,
+    struct Foo(PageParams);
+
+    impl Pageable for Foo {
+        fn page_params(&self) -> &PageParams {
+            &self.0
+        }
+        fn page_params_mut(&mut self) -> &mut PageParams {
+            &mut self.0
         }
+    }
 
-        let foo = Foo { cursor: None }.with_cursor("CURSOR");
-        assert_eq!(foo.cursor, Some("CURSOR".to_string()));
+    #[test]
+    fn it_is_implemented_for_pageable_endpoints() {
+        let foo = Foo(PageParams::default()).with_cursor("CURSOR");
         assert_eq!(foo.cursor(), Some("CURSOR"));
     }
+
+    #[test]
+    fn it_reads_sentinels_as_positions() {
+        assert_eq!(Position::from("now"), Position::Now);
+        assert_eq!(Position::from("0"), Position::First);
+        assert_eq!(
+            Position::from("12884901888".to_string()),
+            Position::Token("12884901888".to_string())
+        );
+        assert_eq!(Position::First.to_string(), "0");
+    }
+
+    #[test]
+    fn it_sets_a_position_as_the_cursor() {
+        let foo = Foo(PageParams::default()).with_cursor(Position::First);
+        assert_eq!(foo.cursor(), Some("0"));
+        assert_eq!(foo.position(), Some(Position::First));
+
+        let foo = foo.with_cursor(Position::Token("CURSOR".to_string()));
+        assert_eq!(foo.position(), Some(Position::Token("CURSOR".to_string())));
+    }
+
+    #[test]
+    fn it_makes_a_cursor_from_a_ledger_sequence() {
+        assert_eq!(ledger_cursor(0), "0");
+        // The paging token of ledger 16751283 is 71946212651040768, and of
+        // the first operation in it 71946212651044865.
+        assert_eq!(ledger_cursor(16751283), "71946212651040767");
+        assert_eq!(ledger_cursor(u32::MAX), "18446744069414584319");
+    }
 }
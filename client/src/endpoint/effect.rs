@@ -1,5 +1,5 @@
 //! Contains the endpoint for all effects.
-use super::{Body, Cursor, Direction, IntoRequest, Limit, Order, Records};
+use super::{Body, Direction, IntoRequest, PageParams, Pageable, Records};
 use error::Result;
 use http::{Request, Uri};
 use resources::Effect;
@@ -27,44 +27,22 @@ pub use super::transaction::Effects as ForTransaction;
 /// #
 /// # assert!(records.records().len() > 0);
 /// ```
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct All {
-    cursor: Option<String>,
-    order: Option<Direction>,
-    limit: Option<u32>,
+    page: PageParams,
 }
 
-impl_cursor!(All);
-impl_limit!(All);
-impl_order!(All);
-
-impl All {
-    fn has_query(&self) -> bool {
-        self.order.is_some() || self.cursor.is_some() || self.limit.is_some()
-    }
-}
+impl_pageable!(All);
 
 impl IntoRequest for All {
     type Response = Records<Effect>;
 
+    impl_paging_defaults!();
+
     fn into_request(self, host: &str) -> Result<Request<Body>> {
         let mut uri = format!("{}/effects", host);
 
-        if self.has_query() {
-            uri.push_str("?");
-
-            if let Some(order) = self.order {
-                uri.push_str(&format!("order={}&", order.to_string()));
-            }
-
-            if let Some(cursor) = self.cursor {
-                uri.push_str(&format!("cursor={}&", cursor));
-            }
-
-            if let Some(limit) = self.limit {
-                uri.push_str(&format!("limit={}", limit));
-            }
-        }
+        self.page.push_to(&mut uri)?;
 
         let uri = Uri::from_str(&uri)?;
         let request = Request::get(uri).body(Body::None)?;
@@ -74,11 +52,8 @@ impl IntoRequest for All {
 
 impl TryFromUri for All {
     fn try_from_wrap(wrap: &UriWrap) -> ::std::result::Result<Self, uri::Error> {
-        let params = wrap.params();
         Ok(Self {
-            cursor: params.get_parse("cursor").ok(),
-            order: params.get_parse("order").ok(),
-            limit: params.get_parse("limit").ok(),
+            page: PageParams::from_wrap(wrap),
         })
     }
 }
@@ -86,6 +61,7 @@ impl TryFromUri for All {
 #[cfg(test)]
 mod all_effects_tests {
     use super::*;
+    use endpoint::{Cursor, Limit, Order};
 
     #[test]
     fn it_leaves_off_the_params_if_not_specified() {
@@ -115,8 +91,8 @@ mod all_effects_tests {
             .parse()
             .unwrap();
         let all = All::try_from(&uri).unwrap();
-        assert_eq!(all.order, Some(Direction::Desc));
-        assert_eq!(all.cursor, Some("CURSOR".to_string()));
-        assert_eq!(all.limit, Some(123));
+        assert_eq!(all.order(), Some(Direction::Desc));
+        assert_eq!(all.cursor(), Some("CURSOR"));
+        assert_eq!(all.limit(), Some(123));
     }
 }
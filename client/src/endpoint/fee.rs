@@ -0,0 +1,49 @@
+//! Contains the endpoint for the network's recent fee statistics.
+use super::{Body, IntoRequest};
+use error::Result;
+use http::{Request, Uri};
+use resources::FeeStats;
+use std::str::FromStr;
+
+/// Fetches statistics about the fees charged in the last few ledgers, for
+/// picking a fee with a `transaction::FeeStrategy`.
+///
+/// <https://www.stellar.org/developers/horizon/reference/endpoints/fee-stats.html>
+///
+/// ## Example
+///
+/// ```no_run
+/// use stellar_client::sync::Client;
+/// use stellar_client::endpoint::fee;
+/// use stellar_client::resources::Percentile;
+///
+/// let client = Client::horizon_test().unwrap();
+/// let stats = client.request(fee::Stats).unwrap();
+/// println!("median fee {}", stats.fee_charged().percentile(Percentile::P50));
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+pub struct Stats;
+
+impl IntoRequest for Stats {
+    type Response = FeeStats;
+
+    fn into_request(self, host: &str) -> Result<Request<Body>> {
+        let uri = Uri::from_str(&format!("{}/fee_stats", host))?;
+        let request = Request::get(uri).body(Body::None)?;
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod fee_stats_tests {
+    use super::*;
+
+    #[test]
+    fn it_can_make_a_fee_stats_uri() {
+        let request = Stats
+            .into_request("https://horizon-testnet.stellar.org")
+            .unwrap();
+        assert_eq!(request.uri().host().unwrap(), "horizon-testnet.stellar.org");
+        assert_eq!(request.uri().path(), "/fee_stats");
+    }
+}
@@ -0,0 +1,67 @@
+//! Contains the endpoint for funding accounts on the test network.
+use super::{push_param, Body, IntoRequest};
+use error::Result;
+use http::{Request, Uri};
+use resources::Submission;
+use std::str::FromStr;
+
+/// Asks friendbot to create and fund an account with test lumens. Only the
+/// test network runs friendbot, so the public network answers with a not
+/// found error.
+///
+/// <https://www.stellar.org/developers/guides/get-started/create-account.html>
+///
+/// ## Example
+///
+/// ```no_run
+/// use stellar_client::sync::Client;
+/// use stellar_client::endpoint::friendbot;
+/// use stellar_client::keypair::Keypair;
+///
+/// let client = Client::horizon_test().unwrap();
+/// let keypair = Keypair::random();
+/// let submission = client.request(friendbot::Fund::new(keypair.account_id())).unwrap();
+/// println!("funded in ledger {}", submission.ledger());
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Fund {
+    account_id: String,
+}
+
+impl Fund {
+    /// Returns a new end point for funding the given account.
+    pub fn new(account_id: impl Into<String>) -> Self {
+        Self {
+            account_id: account_id.into(),
+        }
+    }
+}
+
+impl IntoRequest for Fund {
+    type Response = Submission;
+
+    fn into_request(self, host: &str) -> Result<Request<Body>> {
+        let mut uri = format!("{}/friendbot", host);
+        push_param(&mut uri, "addr", &self.account_id);
+        let uri = Uri::from_str(&uri)?;
+        let request = Request::get(uri).body(Body::None)?;
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod fund_tests {
+    use super::*;
+
+    #[test]
+    fn it_can_make_a_friendbot_uri() {
+        let request = Fund::new("GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ6")
+            .into_request("https://horizon-testnet.stellar.org")
+            .unwrap();
+        assert_eq!(request.uri().path(), "/friendbot");
+        assert_eq!(
+            request.uri().query(),
+            Some("addr=GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ6")
+        );
+    }
+}
@@ -0,0 +1,137 @@
+//! Contains a wrapper for sending extra headers with any endpoint.
+use super::{Body, Direction, IntoRequest, PageParams, Pageable};
+use error::Result;
+use http::header::{HeaderMap, HeaderName, HeaderValue};
+use http::Request;
+
+/// Requests the same thing as the endpoint it wraps, with extra headers
+/// added to the request. Use it for an `Authorization` header a gated
+/// horizon needs, or an `X-Request-Id` to trace a request through logs,
+/// when only some requests need them.
+///
+/// The headers replace any of the same name the endpoint sets. Build one
+/// with `IntoRequest::with_headers`.
+///
+/// ## Example
+///
+/// ```
+/// # extern crate http;
+/// # extern crate stellar_client;
+/// # fn main() {
+/// use http::header::{HeaderValue, AUTHORIZATION};
+/// use stellar_client::endpoint::{ledger, IntoRequest};
+///
+/// let endpoint = ledger::All::default()
+///     .with_header(AUTHORIZATION, HeaderValue::from_static("Bearer abc123"));
+/// let request = endpoint.into_request("https://horizon.example.com").unwrap();
+/// assert_eq!(request.headers()[AUTHORIZATION], "Bearer abc123");
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WithHeaders<E> {
+    endpoint: E,
+    headers: HeaderMap,
+}
+
+impl<E> WithHeaders<E> {
+    /// Wraps an endpoint so its request is sent with the headers.
+    pub fn new(endpoint: E, headers: HeaderMap) -> WithHeaders<E> {
+        WithHeaders { endpoint, headers }
+    }
+
+    /// Adds another header, replacing one of the same name.
+    pub fn with_header(mut self, name: HeaderName, value: HeaderValue) -> WithHeaders<E> {
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// The headers added to the request.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// The endpoint being wrapped.
+    pub fn endpoint(&self) -> &E {
+        &self.endpoint
+    }
+
+    /// Unwraps the endpoint, dropping the headers.
+    pub fn into_inner(self) -> E {
+        self.endpoint
+    }
+}
+
+impl<E> IntoRequest for WithHeaders<E>
+where
+    E: IntoRequest,
+{
+    type Response = E::Response;
+
+    fn into_request(self, host: &str) -> Result<Request<Body>> {
+        let mut request = self.endpoint.into_request(host)?;
+        for (name, value) in self.headers.iter() {
+            request.headers_mut().insert(name.clone(), value.clone());
+        }
+        Ok(request)
+    }
+
+    fn with_paging_defaults(self, limit: Option<u32>, order: Option<Direction>) -> Self {
+        WithHeaders {
+            endpoint: self.endpoint.with_paging_defaults(limit, order),
+            headers: self.headers,
+        }
+    }
+}
+
+impl<E> Pageable for WithHeaders<E>
+where
+    E: Pageable,
+{
+    fn page_params(&self) -> &PageParams {
+        self.endpoint.page_params()
+    }
+
+    fn page_params_mut(&mut self) -> &mut PageParams {
+        self.endpoint.page_params_mut()
+    }
+}
+
+#[cfg(test)]
+mod with_headers_tests {
+    use super::*;
+    use endpoint::{account, transaction, Cursor, Limit};
+    use http::header::{AUTHORIZATION, CONTENT_TYPE};
+
+    #[test]
+    fn it_adds_the_headers_to_the_request() {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer abc123"));
+        let endpoint = account::Payments::new("abc123")
+            .with_headers(headers)
+            .with_header(
+                HeaderName::from_static("x-request-id"),
+                HeaderValue::from_static("42"),
+            )
+            .with_limit(10)
+            .with_cursor("now");
+        assert_eq!(endpoint.headers().len(), 2);
+
+        let request = endpoint
+            .into_request("https://horizon.example.com")
+            .unwrap();
+        assert_eq!(request.uri().query(), Some("cursor=now&limit=10"));
+        assert_eq!(request.headers()[AUTHORIZATION], "Bearer abc123");
+        assert_eq!(request.headers()["x-request-id"], "42");
+    }
+
+    #[test]
+    fn it_replaces_headers_the_endpoint_sets() {
+        let endpoint = transaction::Submit::new("AAAA")
+            .with_header(CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+        let request = endpoint
+            .into_request("https://horizon.example.com")
+            .unwrap();
+        assert_eq!(request.headers().get_all(CONTENT_TYPE).iter().count(), 1);
+        assert_eq!(request.headers()[CONTENT_TYPE], "text/plain");
+    }
+}
@@ -0,0 +1,35 @@
+use super::push_param;
+use uri::UriWrap;
+
+/// Declares that horizon can embed the transaction of each record this
+/// endpoint returns, by requesting it with `join=transactions`. The
+/// transaction is then read with `Operation::embedded_transaction`, which
+/// saves a request per record for its memo or envelope.
+///
+/// ## Example
+///
+/// ```
+/// use stellar_client::endpoint::{payment, Join};
+///
+/// let payments = payment::All::default().with_join_transactions();
+/// assert!(payments.joins_transactions());
+/// ```
+pub trait Join {
+    /// Asks horizon to embed the transaction of each record.
+    fn with_join_transactions(self) -> Self;
+
+    /// Whether horizon is asked to embed the transaction of each record.
+    fn joins_transactions(&self) -> bool;
+}
+
+/// Appends `join=transactions` to the query of a uri if it is asked for.
+pub(crate) fn push_join(uri: &mut String, join: bool) {
+    if join {
+        push_param(uri, "join", "transactions");
+    }
+}
+
+/// Whether a uri asks for transactions to be embedded.
+pub(crate) fn join_from_wrap(wrap: &UriWrap) -> bool {
+    wrap.params().get("join") == Some("transactions")
+}
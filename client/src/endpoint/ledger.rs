@@ -1,5 +1,6 @@
 //! Contains the endpoint for all ledgers.
-use super::{Body, Cursor, Direction, IntoRequest, Limit, Order, Records};
+use super::{join_from_wrap, push_join, Body, Direction, IntoRequest, Join, Limit, Order,
+            PageParams, Pageable, Records};
 use error::Result;
 use http::{Request, Uri};
 use resources::{Effect, Ledger, Operation, Transaction};
@@ -22,44 +23,38 @@ use uri::{self, TryFromUri, UriWrap};
 /// #
 /// # assert!(records.records().len() > 0);
 /// ```
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct All {
-    cursor: Option<String>,
-    order: Option<Direction>,
-    limit: Option<u32>,
+    page: PageParams,
 }
 
-impl_cursor!(All);
-impl_limit!(All);
-impl_order!(All);
+impl_pageable!(All);
 
 impl All {
-    fn has_query(&self) -> bool {
-        self.order.is_some() || self.cursor.is_some() || self.limit.is_some()
+    /// Fetches only the most recently closed ledger, which carries the
+    /// network's current base fee and base reserve.
+    ///
+    /// ```
+    /// use stellar_client::endpoint::{ledger, Direction, Limit, Order};
+    ///
+    /// let endpoint = ledger::All::latest();
+    /// assert_eq!(endpoint.order(), Some(Direction::Desc));
+    /// assert_eq!(endpoint.limit(), Some(1));
+    /// ```
+    pub fn latest() -> All {
+        All::default().with_order(Direction::Desc).with_limit(1)
     }
 }
 
 impl IntoRequest for All {
     type Response = Records<Ledger>;
 
+    impl_paging_defaults!();
+
     fn into_request(self, host: &str) -> Result<Request<Body>> {
         let mut uri = format!("{}/ledgers", host);
 
-        if self.has_query() {
-            uri.push_str("?");
-
-            if let Some(order) = self.order {
-                uri.push_str(&format!("order={}&", order.to_string()));
-            }
-
-            if let Some(cursor) = self.cursor {
-                uri.push_str(&format!("cursor={}&", cursor));
-            }
-
-            if let Some(limit) = self.limit {
-                uri.push_str(&format!("limit={}", limit));
-            }
-        }
+        self.page.push_to(&mut uri)?;
 
         let uri = Uri::from_str(&uri)?;
         let request = Request::get(uri).body(Body::None)?;
@@ -69,11 +64,8 @@ impl IntoRequest for All {
 
 impl TryFromUri for All {
     fn try_from_wrap(wrap: &UriWrap) -> ::std::result::Result<All, uri::Error> {
-        let params = wrap.params();
         Ok(All {
-            cursor: params.get_parse("cursor").ok(),
-            order: params.get_parse("order").ok(),
-            limit: params.get_parse("limit").ok(),
+            page: PageParams::from_wrap(wrap),
         })
     }
 }
@@ -81,6 +73,7 @@ impl TryFromUri for All {
 #[cfg(test)]
 mod all_ledgers_tests {
     use super::*;
+    use endpoint::{Cursor, Limit, Order};
 
     #[test]
     fn it_leaves_off_the_params_if_not_specified() {
@@ -110,9 +103,9 @@ mod all_ledgers_tests {
             .parse()
             .unwrap();
         let all = All::try_from(&uri).unwrap();
-        assert_eq!(all.order, Some(Direction::Desc));
-        assert_eq!(all.cursor, Some("CURSOR".to_string()));
-        assert_eq!(all.limit, Some(123));
+        assert_eq!(all.order(), Some(Direction::Desc));
+        assert_eq!(all.cursor(), Some("CURSOR"));
+        assert_eq!(all.limit(), Some(123));
     }
 }
 
@@ -132,7 +125,7 @@ mod all_ledgers_tests {
 /// #
 /// # assert_eq!(record.sequence(), 12345);
 /// ```
-#[derive(Debug, Default)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct Details {
     sequence: u32,
 }
@@ -204,17 +197,15 @@ mod ledger_details_tests {
 ///
 /// assert!(payments.records().len() > 0);
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Payments {
     sequence: u32,
-    cursor: Option<String>,
-    order: Option<Direction>,
-    limit: Option<u32>,
+    page: PageParams,
+    #[serde(default)]
+    join: bool,
 }
 
-impl_cursor!(Payments);
-impl_limit!(Payments);
-impl_order!(Payments);
+impl_pageable!(Payments);
 
 impl Payments {
     /// Creates a new payments endpoint struct.
@@ -227,38 +218,22 @@ impl Payments {
     pub fn new(sequence: u32) -> Payments {
         Payments {
             sequence,
-            cursor: None,
-            order: None,
-            limit: None,
+            page: PageParams::default(),
+            join: false,
         }
     }
-
-    fn has_query(&self) -> bool {
-        self.order.is_some() || self.cursor.is_some() || self.limit.is_some()
-    }
 }
 
 impl IntoRequest for Payments {
     type Response = Records<Operation>;
 
+    impl_paging_defaults!();
+
     fn into_request(self, host: &str) -> Result<Request<Body>> {
         let mut uri = format!("{}/ledgers/{}/payments", host, self.sequence);
 
-        if self.has_query() {
-            uri.push_str("?");
-
-            if let Some(order) = self.order {
-                uri.push_str(&format!("order={}&", order.to_string()));
-            }
-
-            if let Some(cursor) = self.cursor {
-                uri.push_str(&format!("cursor={}&", cursor));
-            }
-
-            if let Some(limit) = self.limit {
-                uri.push_str(&format!("limit={}", limit));
-            }
-        }
+        self.page.push_to(&mut uri)?;
+        push_join(&mut uri, self.join);
 
         let uri = Uri::from_str(&uri)?;
         let request = Request::get(uri).body(Body::None)?;
@@ -270,12 +245,10 @@ impl TryFromUri for Payments {
     fn try_from_wrap(wrap: &UriWrap) -> ::std::result::Result<Self, uri::Error> {
         match wrap.path() {
             ["ledgers", sequence, "payments"] => {
-                let params = wrap.params();
                 Ok(Self {
                     sequence: sequence.parse()?,
-                    cursor: params.get_parse("cursor").ok(),
-                    order: params.get_parse("order").ok(),
-                    limit: params.get_parse("limit").ok(),
+                    page: PageParams::from_wrap(wrap),
+                    join: join_from_wrap(wrap),
                 })
             }
             _ => Err(uri::Error::invalid_path()),
@@ -283,9 +256,21 @@ impl TryFromUri for Payments {
     }
 }
 
+impl Join for Payments {
+    fn with_join_transactions(mut self) -> Payments {
+        self.join = true;
+        self
+    }
+
+    fn joins_transactions(&self) -> bool {
+        self.join
+    }
+}
+
 #[cfg(test)]
 mod ledger_payments_tests {
     use super::*;
+    use endpoint::{Cursor, Limit, Order};
 
     #[test]
     fn it_leaves_off_the_params_if_not_specified() {
@@ -295,6 +280,18 @@ mod ledger_payments_tests {
         assert_eq!(req.uri().query(), None);
     }
 
+    #[test]
+    fn it_asks_for_joined_transactions() {
+        let ep = Payments::new(123).with_join_transactions().with_limit(10);
+        assert!(ep.joins_transactions());
+        let req = ep.into_request("https://www.google.com").unwrap();
+        assert_eq!(req.uri().path(), "/ledgers/123/payments");
+        assert_eq!(req.uri().query(), Some("limit=10&join=transactions"));
+
+        let uri: Uri = "/ledgers/123/payments?join=transactions".parse().unwrap();
+        assert!(Payments::try_from(&uri).unwrap().joins_transactions());
+    }
+
     #[test]
     fn it_puts_the_query_params_on_the_uri() {
         let ep = Payments::new(123)
@@ -316,9 +313,9 @@ mod ledger_payments_tests {
             .unwrap();
         let ep = Payments::try_from(&uri).unwrap();
         assert_eq!(ep.sequence, 123);
-        assert_eq!(ep.limit, Some(123));
-        assert_eq!(ep.cursor, Some("CURSOR".to_string()));
-        assert_eq!(ep.order, Some(Direction::Desc));
+        assert_eq!(ep.limit(), Some(123));
+        assert_eq!(ep.cursor(), Some("CURSOR"));
+        assert_eq!(ep.order(), Some(Direction::Desc));
     }
 }
 
@@ -345,17 +342,13 @@ mod ledger_payments_tests {
 ///
 /// assert!(ledger_txns.records().len() > 0);
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Transactions {
     sequence: u32,
-    cursor: Option<String>,
-    order: Option<Direction>,
-    limit: Option<u32>,
+    page: PageParams,
 }
 
-impl_cursor!(Transactions);
-impl_limit!(Transactions);
-impl_order!(Transactions);
+impl_pageable!(Transactions);
 
 impl Transactions {
     /// Creates a new ledger::Transactions endpoint struct.
@@ -368,38 +361,20 @@ impl Transactions {
     pub fn new(sequence: u32) -> Transactions {
         Transactions {
             sequence,
-            cursor: None,
-            order: None,
-            limit: None,
+            page: PageParams::default(),
         }
     }
-
-    fn has_query(&self) -> bool {
-        self.order.is_some() || self.cursor.is_some() || self.limit.is_some()
-    }
 }
 
 impl IntoRequest for Transactions {
     type Response = Records<Transaction>;
 
+    impl_paging_defaults!();
+
     fn into_request(self, host: &str) -> Result<Request<Body>> {
         let mut uri = format!("{}/ledgers/{}/transactions", host, self.sequence);
 
-        if self.has_query() {
-            uri.push_str("?");
-
-            if let Some(order) = self.order {
-                uri.push_str(&format!("order={}&", order.to_string()));
-            }
-
-            if let Some(cursor) = self.cursor {
-                uri.push_str(&format!("cursor={}&", cursor));
-            }
-
-            if let Some(limit) = self.limit {
-                uri.push_str(&format!("limit={}", limit));
-            }
-        }
+        self.page.push_to(&mut uri)?;
 
         let uri = Uri::from_str(&uri)?;
         let request = Request::get(uri).body(Body::None)?;
@@ -411,12 +386,9 @@ impl TryFromUri for Transactions {
     fn try_from_wrap(wrap: &UriWrap) -> ::std::result::Result<Self, uri::Error> {
         match wrap.path() {
             ["ledgers", sequence, "transactions"] => {
-                let params = wrap.params();
                 Ok(Self {
                     sequence: sequence.parse()?,
-                    cursor: params.get_parse("cursor").ok(),
-                    order: params.get_parse("order").ok(),
-                    limit: params.get_parse("limit").ok(),
+                    page: PageParams::from_wrap(wrap),
                 })
             }
             _ => Err(uri::Error::invalid_path()),
@@ -427,6 +399,7 @@ impl TryFromUri for Transactions {
 #[cfg(test)]
 mod ledger_transactions_tests {
     use super::*;
+    use endpoint::{Cursor, Limit, Order};
 
     #[test]
     fn it_leaves_off_the_params_if_not_specified() {
@@ -457,9 +430,9 @@ mod ledger_transactions_tests {
             .unwrap();
         let ep = Transactions::try_from(&uri).unwrap();
         assert_eq!(ep.sequence, 123);
-        assert_eq!(ep.limit, Some(123));
-        assert_eq!(ep.cursor, Some("CURSOR".to_string()));
-        assert_eq!(ep.order, Some(Direction::Desc));
+        assert_eq!(ep.limit(), Some(123));
+        assert_eq!(ep.cursor(), Some("CURSOR"));
+        assert_eq!(ep.order(), Some(Direction::Desc));
     }
 }
 
@@ -488,17 +461,13 @@ mod ledger_transactions_tests {
 ///
 /// assert!(ledger_effects.records().len() > 0);
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Effects {
     sequence: u32,
-    cursor: Option<String>,
-    order: Option<Direction>,
-    limit: Option<u32>,
+    page: PageParams,
 }
 
-impl_cursor!(Effects);
-impl_limit!(Effects);
-impl_order!(Effects);
+impl_pageable!(Effects);
 
 impl Effects {
     /// Creates a new ledger::Effects endpoint struct.
@@ -511,38 +480,20 @@ impl Effects {
     pub fn new(sequence: u32) -> Effects {
         Effects {
             sequence,
-            cursor: None,
-            order: None,
-            limit: None,
+            page: PageParams::default(),
         }
     }
-
-    fn has_query(&self) -> bool {
-        self.order.is_some() || self.cursor.is_some() || self.limit.is_some()
-    }
 }
 
 impl IntoRequest for Effects {
     type Response = Records<Effect>;
 
+    impl_paging_defaults!();
+
     fn into_request(self, host: &str) -> Result<Request<Body>> {
         let mut uri = format!("{}/ledgers/{}/effects", host, self.sequence);
 
-        if self.has_query() {
-            uri.push_str("?");
-
-            if let Some(order) = self.order {
-                uri.push_str(&format!("order={}&", order.to_string()));
-            }
-
-            if let Some(cursor) = self.cursor {
-                uri.push_str(&format!("cursor={}&", cursor));
-            }
-
-            if let Some(limit) = self.limit {
-                uri.push_str(&format!("limit={}", limit));
-            }
-        }
+        self.page.push_to(&mut uri)?;
 
         let uri = Uri::from_str(&uri)?;
         let request = Request::get(uri).body(Body::None)?;
@@ -554,12 +505,9 @@ impl TryFromUri for Effects {
     fn try_from_wrap(wrap: &UriWrap) -> ::std::result::Result<Self, uri::Error> {
         match wrap.path() {
             ["ledgers", sequence, "effects"] => {
-                let params = wrap.params();
                 Ok(Self {
                     sequence: sequence.parse()?,
-                    cursor: params.get_parse("cursor").ok(),
-                    order: params.get_parse("order").ok(),
-                    limit: params.get_parse("limit").ok(),
+                    page: PageParams::from_wrap(wrap),
                 })
             }
             _ => Err(uri::Error::invalid_path()),
@@ -570,6 +518,7 @@ impl TryFromUri for Effects {
 #[cfg(test)]
 mod ledger_effects_tests {
     use super::*;
+    use endpoint::{Cursor, Limit, Order};
 
     #[test]
     fn it_leaves_off_the_params_if_not_specified() {
@@ -600,9 +549,9 @@ mod ledger_effects_tests {
             .unwrap();
         let ep = Effects::try_from(&uri).unwrap();
         assert_eq!(ep.sequence, 123);
-        assert_eq!(ep.limit, Some(123));
-        assert_eq!(ep.cursor, Some("CURSOR".to_string()));
-        assert_eq!(ep.order, Some(Direction::Desc));
+        assert_eq!(ep.limit(), Some(123));
+        assert_eq!(ep.cursor(), Some("CURSOR"));
+        assert_eq!(ep.order(), Some(Direction::Desc));
     }
 }
 
@@ -631,17 +580,13 @@ mod ledger_effects_tests {
 ///
 /// assert!(ledger_operations.records().len() > 0);
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Operations {
     sequence: u32,
-    cursor: Option<String>,
-    order: Option<Direction>,
-    limit: Option<u32>,
+    page: PageParams,
 }
 
-impl_cursor!(Operations);
-impl_limit!(Operations);
-impl_order!(Operations);
+impl_pageable!(Operations);
 
 impl Operations {
     /// Creates a new ledger::Operations endpoint struct.
@@ -654,38 +599,20 @@ impl Operations {
     pub fn new(sequence: u32) -> Operations {
         Operations {
             sequence,
-            cursor: None,
-            order: None,
-            limit: None,
+            page: PageParams::default(),
         }
     }
-
-    fn has_query(&self) -> bool {
-        self.order.is_some() || self.cursor.is_some() || self.limit.is_some()
-    }
 }
 
 impl IntoRequest for Operations {
     type Response = Records<Operation>;
 
+    impl_paging_defaults!();
+
     fn into_request(self, host: &str) -> Result<Request<Body>> {
         let mut uri = format!("{}/ledgers/{}/operations", host, self.sequence);
 
-        if self.has_query() {
-            uri.push_str("?");
-
-            if let Some(order) = self.order {
-                uri.push_str(&format!("order={}&", order.to_string()));
-            }
-
-            if let Some(cursor) = self.cursor {
-                uri.push_str(&format!("cursor={}&", cursor));
-            }
-
-            if let Some(limit) = self.limit {
-                uri.push_str(&format!("limit={}", limit));
-            }
-        }
+        self.page.push_to(&mut uri)?;
 
         let uri = Uri::from_str(&uri)?;
         let request = Request::get(uri).body(Body::None)?;
@@ -697,12 +624,9 @@ impl TryFromUri for Operations {
     fn try_from_wrap(wrap: &UriWrap) -> ::std::result::Result<Self, uri::Error> {
         match wrap.path() {
             ["ledgers", sequence, "operations"] => {
-                let params = wrap.params();
                 Ok(Self {
                     sequence: sequence.parse()?,
-                    cursor: params.get_parse("cursor").ok(),
-                    order: params.get_parse("order").ok(),
-                    limit: params.get_parse("limit").ok(),
+                    page: PageParams::from_wrap(wrap),
                 })
             }
             _ => Err(uri::Error::invalid_path()),
@@ -713,6 +637,7 @@ impl TryFromUri for Operations {
 #[cfg(test)]
 mod ledger_operations_tests {
     use super::*;
+    use endpoint::{Cursor, Limit, Order};
 
     #[test]
     fn it_leaves_off_the_params_if_not_specified() {
@@ -743,8 +668,8 @@ mod ledger_operations_tests {
             .unwrap();
         let ep = Operations::try_from(&uri).unwrap();
         assert_eq!(ep.sequence, 123);
-        assert_eq!(ep.limit, Some(123));
-        assert_eq!(ep.cursor, Some("CURSOR".to_string()));
-        assert_eq!(ep.order, Some(Direction::Desc));
+        assert_eq!(ep.limit(), Some(123));
+        assert_eq!(ep.cursor(), Some("CURSOR"));
+        assert_eq!(ep.order(), Some(Direction::Desc));
     }
 }
@@ -1,5 +1,11 @@
+use super::Pageable;
+
 /// Declares that this endpoint has a limit and can have it set.
 ///
+/// Horizon returns between `MIN_LIMIT` and `MAX_LIMIT` records in a page.
+/// Building the request for an endpoint with a limit outside that range
+/// fails with `Error::InvalidLimit`.
+///
 /// ## Example
 ///
 /// ```
@@ -15,34 +21,35 @@ pub trait Limit {
     fn limit(&self) -> Option<u32>;
 }
 
-macro_rules! impl_limit {
-    ($name:path) => {
-        impl Limit for $name {
-            fn with_limit(mut self, limit: u32) -> $name {
-                self.limit = Some(limit);
-                self
-            }
+impl<T: Pageable> Limit for T {
+    fn with_limit(mut self, limit: u32) -> T {
+        self.page_params_mut().set_limit(limit);
+        self
+    }
 
-            fn limit(&self) -> Option<u32> {
-                self.limit
-            }
-        }
-    };
+    fn limit(&self) -> Option<u32> {
+        self.page_params().limit()
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use endpoint::PageParams;
 
     #[test]
-    fn it_can_be_derived() {
-        struct Foo {
-            limit: Option<u32>,
+    fn it_is_implemented_for_pageable_endpoints() {
+        struct Foo(PageParams);
+        impl Pageable for Foo {
+            fn page_params(&self) -> &PageParams {
+                &self.0
+            }
+            fn page_params_mut(&mut self) -> &mut PageParams {
+                &mut self.0
+            }
         }
-        impl_limit!(Foo);
 
-        let foo = Foo { limit: None };
-        let foo = foo.with_limit(7);
+        let foo = Foo(PageParams::default()).with_limit(7);
         assert_eq!(foo.limit(), Some(7));
     }
 }
@@ -0,0 +1,193 @@
+//! Contains endpoints for liquidity pools.
+use super::{push_param, Body, Direction, IntoRequest, PageParams, Pageable, Records};
+use error::Result;
+use http::{Request, Uri};
+use resources::{AssetIdentifier, LiquidityPool};
+use std::str::FromStr;
+use uri::{self, TryFromUri, UriWrap};
+
+/// Represents the all liquidity pools endpoint for the stellar horizon
+/// server, optionally only the pools holding some assets or that an
+/// account has shares in.
+///
+/// <https://developers.stellar.org/api/horizon/resources/list-liquidity-pools>
+///
+/// ## Example
+///
+/// ```no_run
+/// use stellar_client::sync::Client;
+/// use stellar_client::endpoint::liquidity_pool;
+/// use stellar_client::resources::AssetIdentifier;
+///
+/// let client = Client::horizon_test().unwrap();
+/// let endpoint = liquidity_pool::All::default().with_reserve(AssetIdentifier::native());
+/// let records = client.request(endpoint).unwrap();
+/// ```
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct All {
+    reserves: Vec<AssetIdentifier>,
+    account: Option<String>,
+    page: PageParams,
+}
+
+impl_pageable!(All);
+
+impl All {
+    /// Fetches only the pools holding the asset. Can be given twice to
+    /// find the pool of a pair of assets.
+    pub fn with_reserve(mut self, asset: AssetIdentifier) -> Self {
+        self.reserves.push(asset);
+        self
+    }
+
+    /// Fetches only the pools the account has shares in.
+    pub fn with_account(mut self, account_id: impl Into<String>) -> Self {
+        self.account = Some(account_id.into());
+        self
+    }
+}
+
+impl IntoRequest for All {
+    type Response = Records<LiquidityPool>;
+
+    impl_paging_defaults!();
+
+    fn into_request(self, host: &str) -> Result<Request<Body>> {
+        let mut uri = format!("{}/liquidity_pools", host);
+
+        if !self.reserves.is_empty() {
+            let reserves: Vec<String> = self
+                .reserves
+                .iter()
+                .map(AssetIdentifier::to_canonical)
+                .collect();
+            push_param(&mut uri, "reserves", &reserves.join(","));
+        }
+        if let Some(ref account) = self.account {
+            push_param(&mut uri, "account", account);
+        }
+        self.page.push_to(&mut uri)?;
+
+        let uri = Uri::from_str(&uri)?;
+        let request = Request::get(uri).body(Body::None)?;
+        Ok(request)
+    }
+}
+
+impl TryFromUri for All {
+    fn try_from_wrap(wrap: &UriWrap) -> ::std::result::Result<All, uri::Error> {
+        let params = wrap.params();
+        let reserves = match params.get("reserves") {
+            Some(reserves) => reserves
+                .split(',')
+                .map(AssetIdentifier::from_canonical)
+                .collect::<::std::result::Result<Vec<_>, String>>()?,
+            None => Vec::new(),
+        };
+        Ok(All {
+            reserves,
+            account: params.get_parse("account").ok(),
+            page: PageParams::from_wrap(wrap),
+        })
+    }
+}
+
+/// Represents the liquidity pool details endpoint for the stellar horizon
+/// server, returning a single pool by its id.
+///
+/// <https://developers.stellar.org/api/horizon/resources/retrieve-a-liquidity-pool>
+///
+/// ## Example
+///
+/// ```no_run
+/// use stellar_client::sync::Client;
+/// use stellar_client::endpoint::liquidity_pool;
+///
+/// let client = Client::horizon_test().unwrap();
+/// let endpoint = liquidity_pool::Details::new(
+///     "dd7b1ab831c273310ddbec6f97870aa83c2fbd78ce22aded37ecbf4f3380fac7",
+/// );
+/// let pool = client.request(endpoint).unwrap();
+/// println!("{} shares", pool.total_shares());
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Details {
+    id: String,
+}
+
+impl Details {
+    /// Returns a new endpoint for the pool with the id.
+    pub fn new(id: impl Into<String>) -> Self {
+        Self { id: id.into() }
+    }
+}
+
+impl IntoRequest for Details {
+    type Response = LiquidityPool;
+
+    fn into_request(self, host: &str) -> Result<Request<Body>> {
+        let uri = Uri::from_str(&format!("{}/liquidity_pools/{}", host, self.id))?;
+        let request = Request::get(uri).body(Body::None)?;
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod liquidity_pool_tests {
+    use super::*;
+    use endpoint::{Cursor, Limit, Order};
+
+    const ISSUER: &str = "GBBD47IF6LWK7P7MDEVSCWR7DPUWV3NY3DTQEVFL4NAT4AQH3ZLLFLA5";
+
+    #[test]
+    fn it_leaves_off_the_params_if_not_specified() {
+        let req = All::default()
+            .into_request("https://www.google.com")
+            .unwrap();
+        assert_eq!(req.uri().path(), "/liquidity_pools");
+        assert_eq!(req.uri().query(), None);
+    }
+
+    #[test]
+    fn it_puts_the_query_params_on_the_uri() {
+        let ep = All::default()
+            .with_reserve(AssetIdentifier::native())
+            .with_reserve(AssetIdentifier::alphanum4("USD", ISSUER))
+            .with_account("ACCOUNT")
+            .with_cursor("CURSOR")
+            .with_limit(10)
+            .with_order(Direction::Desc);
+        let req = ep.into_request("https://www.google.com").unwrap();
+        assert_eq!(
+            req.uri().query(),
+            Some(
+                "reserves=native,USD:GBBD47IF6LWK7P7MDEVSCWR7DPUWV3NY3DTQEVFL4NAT4AQH3ZLLFLA5\
+                 &account=ACCOUNT&order=desc&cursor=CURSOR&limit=10"
+            )
+        );
+    }
+
+    #[test]
+    fn it_parses_the_query_params_on_the_uri() {
+        let uri: Uri = format!("/liquidity_pools?reserves=native,USD:{}&limit=5", ISSUER)
+            .parse()
+            .unwrap();
+        let ep = All::try_from(&uri).unwrap();
+        assert_eq!(
+            ep.reserves,
+            vec![
+                AssetIdentifier::native(),
+                AssetIdentifier::alphanum4("USD", ISSUER)
+            ]
+        );
+        assert_eq!(ep.limit(), Some(5));
+    }
+
+    #[test]
+    fn it_can_make_a_details_uri() {
+        let req = Details::new("abcdef")
+            .into_request("https://horizon-testnet.stellar.org")
+            .unwrap();
+        assert_eq!(req.uri().path(), "/liquidity_pools/abcdef");
+    }
+}
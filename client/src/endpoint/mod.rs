@@ -21,36 +21,44 @@ use error::Result;
 use http;
 use serde::de::DeserializeOwned;
 
-#[macro_use]
+mod body;
 mod cursor;
-#[macro_use]
+mod headers;
+mod join;
 mod limit;
-#[macro_use]
 mod order;
-
+#[macro_use]
+mod page;
+mod raw;
 mod records;
+mod time_range;
 
 pub mod account;
 pub mod asset;
 pub mod effect;
+pub mod fee;
+pub mod friendbot;
 pub mod ledger;
+pub mod liquidity_pool;
 pub mod operation;
 pub mod orderbook;
 pub mod payment;
+pub mod root;
 pub mod trade;
 pub mod transaction;
 
-pub use self::cursor::Cursor;
+pub use self::body::Body;
+pub use self::cursor::{ledger_cursor, Cursor, Position};
+pub use self::headers::WithHeaders;
+pub use self::join::Join;
+pub(crate) use self::join::{join_from_wrap, push_join};
 pub use self::limit::Limit;
 pub use self::order::{Direction, Order, ParseDirectionError};
+pub use self::page::{PageParams, Pageable, MAX_LIMIT, MIN_LIMIT};
+pub(crate) use self::page::{check_limit, push_param};
+pub use self::raw::Raw;
 pub use self::records::Records;
-
-/// Represents the body of a request to an IntoRequest.
-#[derive(Debug)]
-pub enum Body {
-    /// Declares that the endpoint does not have a body.
-    None,
-}
+pub use self::time_range::TimeRange;
 
 /// Declares the definition of a stellar endpoint and the return type.
 pub trait IntoRequest {
@@ -60,4 +68,39 @@ pub trait IntoRequest {
 
     /// Converts the implementing struct into an http request.
     fn into_request(self, host: &str) -> Result<http::Request<Body>>;
+
+    /// Fills in the page limit and order if the endpoint wasn't given them.
+    /// A client built with defaults calls this before each request. Endpoints
+    /// that don't return pages of records ignore them.
+    fn with_paging_defaults(self, _limit: Option<u32>, _order: Option<Direction>) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+
+    /// Sends the request with the extra headers, replacing any of the same
+    /// name the endpoint sets. See `WithHeaders`.
+    fn with_headers(self, headers: http::HeaderMap) -> WithHeaders<Self>
+    where
+        Self: Sized,
+    {
+        WithHeaders::new(self, headers)
+    }
+
+    /// Sends the request with an extra header, replacing one of the same
+    /// name the endpoint sets. See `WithHeaders`.
+    fn with_header(
+        self,
+        name: http::header::HeaderName,
+        value: http::HeaderValue,
+    ) -> WithHeaders<Self>
+    where
+        Self: Sized,
+    {
+        WithHeaders::new(self, http::HeaderMap::new()).with_header(name, value)
+    }
 }
+
+#[cfg(test)]
+mod round_trip_tests;
@@ -1,5 +1,5 @@
 //! Contains the endpoint for all operations.
-use super::{Body, Cursor, Direction, IntoRequest, Limit, Order, Records};
+use super::{Body, Direction, IntoRequest, PageParams, Pageable, Records};
 use error::Result;
 use http::{Request, Uri};
 use resources::{Effect, Operation};
@@ -26,44 +26,22 @@ pub use super::transaction::Operations as ForTransaction;
 /// #
 /// # assert!(records.records().len() > 0);
 /// ```
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct All {
-    cursor: Option<String>,
-    order: Option<Direction>,
-    limit: Option<u32>,
+    page: PageParams,
 }
 
-impl_cursor!(All);
-impl_limit!(All);
-impl_order!(All);
-
-impl All {
-    fn has_query(&self) -> bool {
-        self.order.is_some() || self.cursor.is_some() || self.limit.is_some()
-    }
-}
+impl_pageable!(All);
 
 impl IntoRequest for All {
     type Response = Records<Operation>;
 
+    impl_paging_defaults!();
+
     fn into_request(self, host: &str) -> Result<Request<Body>> {
         let mut uri = format!("{}/operations", host);
 
-        if self.has_query() {
-            uri.push_str("?");
-
-            if let Some(order) = self.order {
-                uri.push_str(&format!("order={}&", order.to_string()));
-            }
-
-            if let Some(cursor) = self.cursor {
-                uri.push_str(&format!("cursor={}&", cursor));
-            }
-
-            if let Some(limit) = self.limit {
-                uri.push_str(&format!("limit={}", limit));
-            }
-        }
+        self.page.push_to(&mut uri)?;
 
         let uri = Uri::from_str(&uri)?;
         let request = Request::get(uri).body(Body::None)?;
@@ -73,11 +51,8 @@ impl IntoRequest for All {
 
 impl TryFromUri for All {
     fn try_from_wrap(wrap: &UriWrap) -> ::std::result::Result<Self, uri::Error> {
-        let params = wrap.params();
         Ok(Self {
-            cursor: params.get_parse("cursor").ok(),
-            order: params.get_parse("order").ok(),
-            limit: params.get_parse("limit").ok(),
+            page: PageParams::from_wrap(wrap),
         })
     }
 }
@@ -85,6 +60,7 @@ impl TryFromUri for All {
 #[cfg(test)]
 mod all_operations_tests {
     use super::*;
+    use endpoint::{Cursor, Limit, Order};
 
     #[test]
     fn it_leaves_off_the_params_if_not_specified() {
@@ -114,9 +90,9 @@ mod all_operations_tests {
             .parse()
             .unwrap();
         let all = All::try_from(&uri).unwrap();
-        assert_eq!(all.order, Some(Direction::Desc));
-        assert_eq!(all.cursor, Some("CURSOR".to_string()));
-        assert_eq!(all.limit, Some(123));
+        assert_eq!(all.order(), Some(Direction::Desc));
+        assert_eq!(all.cursor(), Some("CURSOR"));
+        assert_eq!(all.limit(), Some(123));
     }
 }
 
@@ -145,7 +121,7 @@ mod all_operations_tests {
 ///
 /// assert_eq!(operation.id(), operation_id);
 /// ```
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
 pub struct Details {
     id: i64,
 }
@@ -214,17 +190,13 @@ mod operation_details_tests {
 ///
 /// # assert!(effects.records().len() > 0);
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Effects {
     id: i64,
-    cursor: Option<String>,
-    order: Option<Direction>,
-    limit: Option<u32>,
+    page: PageParams,
 }
 
-impl_cursor!(Effects);
-impl_limit!(Effects);
-impl_order!(Effects);
+impl_pageable!(Effects);
 
 impl Effects {
     /// Creates a new endpoint struct for use in requesting the effects for
@@ -239,38 +211,20 @@ impl Effects {
     pub fn new(id: i64) -> Effects {
         Effects {
             id,
-            cursor: None,
-            order: None,
-            limit: None,
+            page: PageParams::default(),
         }
     }
-
-    fn has_query(&self) -> bool {
-        self.order.is_some() || self.cursor.is_some() || self.limit.is_some()
-    }
 }
 
 impl IntoRequest for Effects {
     type Response = Records<Effect>;
 
+    impl_paging_defaults!();
+
     fn into_request(self, host: &str) -> Result<Request<Body>> {
         let mut uri = format!("{}/operations/{}/effects", host, self.id);
 
-        if self.has_query() {
-            uri.push_str("?");
-
-            if let Some(order) = self.order {
-                uri.push_str(&format!("order={}&", order.to_string()));
-            }
-
-            if let Some(cursor) = self.cursor {
-                uri.push_str(&format!("cursor={}&", cursor));
-            }
-
-            if let Some(limit) = self.limit {
-                uri.push_str(&format!("limit={}", limit));
-            }
-        }
+        self.page.push_to(&mut uri)?;
 
         let uri = Uri::from_str(&uri)?;
         let request = Request::get(uri).body(Body::None)?;
@@ -282,12 +236,9 @@ impl TryFromUri for Effects {
     fn try_from_wrap(wrap: &UriWrap) -> ::std::result::Result<Self, uri::Error> {
         match wrap.path() {
             ["operations", id, "effects"] => {
-                let params = wrap.params();
                 Ok(Self {
                     id: id.parse()?,
-                    cursor: params.get_parse("cursor").ok(),
-                    order: params.get_parse("order").ok(),
-                    limit: params.get_parse("limit").ok(),
+                    page: PageParams::from_wrap(wrap),
                 })
             }
             _ => Err(uri::Error::invalid_path()),
@@ -298,6 +249,7 @@ impl TryFromUri for Effects {
 #[cfg(test)]
 mod all_effects_tests {
     use super::*;
+    use endpoint::{Cursor, Limit, Order};
 
     #[test]
     fn it_leaves_off_the_params_if_not_specified() {
@@ -329,8 +281,8 @@ mod all_effects_tests {
 
         let effects = Effects::try_from(&uri).unwrap();
         assert_eq!(effects.id, 123);
-        assert_eq!(effects.order, Some(Direction::Desc));
-        assert_eq!(effects.cursor, Some("CURSOR".to_string()));
-        assert_eq!(effects.limit, Some(123));
+        assert_eq!(effects.order(), Some(Direction::Desc));
+        assert_eq!(effects.cursor(), Some("CURSOR"));
+        assert_eq!(effects.limit(), Some(123));
     }
 }
@@ -1,4 +1,9 @@
-use std::{fmt, error::Error, str::FromStr, string::ToString};
+//! Ordering of records. Horizon calls the query param `order`, so the trait
+//! for endpoints that take it is `Order`, and the value it takes, ascending
+//! or descending, is a `Direction`. Everything that sorts records, such as
+//! the client's default order, uses `Direction` for the value.
+use super::Pageable;
+use std::{fmt, error::Error, str::FromStr};
 /// Declares that this endpoint has an order field and can have it set.
 ///
 /// ## Example
@@ -21,23 +26,20 @@ pub trait Order {
     fn order(&self) -> Option<Direction>;
 }
 
-macro_rules! impl_order {
-    ($name:path) => {
-        impl Order for $name {
-            fn with_order(mut self, order: Direction) -> $name {
-                self.order = Some(order);
-                self
-            }
+impl<T: Pageable> Order for T {
+    fn with_order(mut self, order: Direction) -> T {
+        self.page_params_mut().set_order(order);
+        self
+    }
 
-            fn order(&self) -> Option<Direction> {
-                self.order
-            }
-        }
-    };
+    fn order(&self) -> Option<Direction> {
+        self.page_params().order()
+    }
 }
 
 /// The order to return results in.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
 pub enum Direction {
     /// Order the results ascending
     Asc,
@@ -45,15 +47,30 @@ pub enum Direction {
     Desc,
 }
 
-impl ToString for Direction {
-    fn to_string(&self) -> String {
+impl Direction {
+    /// The value of the `order` query param for the direction.
+    pub fn as_str(&self) -> &'static str {
         match *self {
-            Direction::Asc => "asc".to_string(),
-            Direction::Desc => "desc".to_string(),
+            Direction::Asc => "asc",
+            Direction::Desc => "desc",
+        }
+    }
+
+    /// The opposite direction.
+    pub fn reverse(&self) -> Direction {
+        match *self {
+            Direction::Asc => Direction::Desc,
+            Direction::Desc => Direction::Asc,
         }
     }
 }
 
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// When a bad token or string is provided to parsing into a direction
 /// you get an error.
 #[derive(Debug)]
@@ -105,14 +122,26 @@ mod tests {
     }
 
     #[test]
-    fn it_can_be_derived() {
-        struct Foo {
-            order: Option<Direction>,
+    fn it_can_be_reversed() {
+        assert_eq!(Direction::Asc.reverse(), Direction::Desc);
+        assert_eq!(Direction::Desc.reverse(), Direction::Asc);
+    }
+
+    #[test]
+    fn it_is_implemented_for_pageable_endpoints() {
+        use endpoint::PageParams;
+
+        struct Foo(PageParams);
+        impl Pageable for Foo {
+            fn page_params(&self) -> &PageParams {
+                &self.0
+            }
+            fn page_params_mut(&mut self) -> &mut PageParams {
+                &mut self.0
+            }
         }
-        impl_order!(Foo);
 
-        let foo = Foo { order: None }.with_order(Direction::Asc);
-        assert_eq!(foo.order, Some(Direction::Asc));
+        let foo = Foo(PageParams::default()).with_order(Direction::Asc);
         assert_eq!(foo.order(), Some(Direction::Asc));
     }
 
@@ -1,5 +1,5 @@
 //! Contains the endpoint for fetching the orderbook for a given asset pair
-use super::{Body, IntoRequest, Limit};
+use super::{check_limit, Body, IntoRequest, Limit};
 use error::Result;
 use http::{Request, Uri};
 use resources::{AssetIdentifier, Orderbook};
@@ -28,14 +28,23 @@ use std::str::FromStr;
 ///
 /// assert_eq!(orderbook.base(), trade.base_asset());
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Details {
     base_asset: AssetIdentifier,
     counter_asset: AssetIdentifier,
     limit: Option<u32>,
 }
 
-impl_limit!(Details);
+impl Limit for Details {
+    fn with_limit(mut self, limit: u32) -> Details {
+        self.limit = Some(limit);
+        self
+    }
+
+    fn limit(&self) -> Option<u32> {
+        self.limit
+    }
+}
 
 impl Details {
     /// Creates a new orderbook::Details endpoint struct. Hand this to the client in order to request
@@ -95,6 +104,7 @@ impl IntoRequest for Details {
             ));
         }
 
+        check_limit(self.limit)?;
         if let Some(limit) = self.limit {
             uri_str.push_str(&format!("&limit={}", limit));
         }
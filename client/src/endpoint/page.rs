@@ -0,0 +1,247 @@
+use super::Direction;
+use error::{Error, Result};
+use uri::UriWrap;
+
+/// The fewest records horizon will return in a page.
+pub const MIN_LIMIT: u32 = 1;
+
+/// The most records horizon will return in a page.
+pub const MAX_LIMIT: u32 = 200;
+
+/// The paging params shared by every endpoint that returns pages of records.
+/// Endpoints embed one and implement `Pageable`, which gives them the
+/// `Cursor`, `Limit` and `Order` traits.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Eq)]
+pub struct PageParams {
+    cursor: Option<String>,
+    order: Option<Direction>,
+    limit: Option<u32>,
+}
+
+impl PageParams {
+    /// The cursor to start the page after.
+    pub fn cursor(&self) -> Option<&str> {
+        self.cursor.as_ref().map(String::as_str)
+    }
+
+    /// The order of the records.
+    pub fn order(&self) -> Option<Direction> {
+        self.order
+    }
+
+    /// The most records to return in a page.
+    pub fn limit(&self) -> Option<u32> {
+        self.limit
+    }
+
+    pub(super) fn set_cursor(&mut self, cursor: &str) {
+        self.cursor = Some(cursor.to_string());
+    }
+
+    pub(super) fn set_order(&mut self, order: Direction) {
+        self.order = Some(order);
+    }
+
+    pub(super) fn set_limit(&mut self, limit: u32) {
+        self.limit = Some(limit);
+    }
+
+    /// Sets the limit and order to the given defaults where they haven't
+    /// been set already.
+    pub fn fill_defaults(&mut self, limit: Option<u32>, order: Option<Direction>) {
+        if self.limit.is_none() {
+            self.limit = limit;
+        }
+        if self.order.is_none() {
+            self.order = order;
+        }
+    }
+
+    /// Reads the params from the query of a uri, such as a `next` link.
+    pub(crate) fn from_wrap(wrap: &UriWrap) -> PageParams {
+        let params = wrap.params();
+        PageParams {
+            cursor: params.get_parse("cursor").ok(),
+            order: params.get_parse("order").ok(),
+            limit: params.get_parse("limit").ok(),
+        }
+    }
+
+    /// Appends the params that are set to the query of a uri, starting the
+    /// query if the uri doesn't have one yet. Fails with
+    /// `Error::InvalidLimit` if the limit is one horizon would reject.
+    pub(crate) fn push_to(&self, uri: &mut String) -> Result<()> {
+        check_limit(self.limit)?;
+        if let Some(order) = self.order {
+            push_param(uri, "order", order.as_str());
+        }
+        if let Some(ref cursor) = self.cursor {
+            push_param(uri, "cursor", cursor);
+        }
+        if let Some(limit) = self.limit {
+            push_param(uri, "limit", &limit.to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Checks that a limit, if there is one, is within the range horizon
+/// accepts.
+pub(crate) fn check_limit(limit: Option<u32>) -> Result<()> {
+    match limit {
+        Some(limit) if !(MIN_LIMIT..=MAX_LIMIT).contains(&limit) => Err(Error::InvalidLimit(limit)),
+        _ => Ok(()),
+    }
+}
+
+/// Appends a single `key=value` param to a uri.
+pub(crate) fn push_param(uri: &mut String, key: &str, value: &str) {
+    if uri.contains('?') {
+        uri.push('&');
+    } else {
+        uri.push('?');
+    }
+    uri.push_str(key);
+    uri.push('=');
+    uri.push_str(value);
+}
+
+/// Declares that an endpoint returns pages of records. Implementing it is
+/// enough to get `Cursor`, `Limit` and `Order` for the endpoint.
+///
+/// ## Example
+///
+/// ```
+/// use stellar_client::endpoint::{Direction, Limit, Order, PageParams, Pageable};
+///
+/// #[derive(Default)]
+/// struct Widgets {
+///     page: PageParams,
+/// }
+///
+/// impl Pageable for Widgets {
+///     fn page_params(&self) -> &PageParams {
+///         &self.page
+///     }
+///
+///     fn page_params_mut(&mut self) -> &mut PageParams {
+///         &mut self.page
+///     }
+/// }
+///
+/// let widgets = Widgets::default().with_limit(10).with_order(Direction::Desc);
+/// assert_eq!(widgets.limit(), Some(10));
+/// assert_eq!(widgets.order(), Some(Direction::Desc));
+/// ```
+pub trait Pageable {
+    /// The paging params of the endpoint.
+    fn page_params(&self) -> &PageParams;
+
+    /// The paging params of the endpoint, for changing them.
+    fn page_params_mut(&mut self) -> &mut PageParams;
+}
+
+/// Implements `Pageable` for an endpoint that keeps its paging params in a
+/// `page` field.
+macro_rules! impl_pageable {
+    ($name:ty) => {
+        impl Pageable for $name {
+            fn page_params(&self) -> &PageParams {
+                &self.page
+            }
+
+            fn page_params_mut(&mut self) -> &mut PageParams {
+                &mut self.page
+            }
+        }
+    };
+}
+
+/// Implements `IntoRequest::with_paging_defaults` for a `Pageable` endpoint.
+/// Used inside the endpoint's `IntoRequest` impl.
+macro_rules! impl_paging_defaults {
+    () => {
+        fn with_paging_defaults(mut self, limit: Option<u32>, order: Option<Direction>) -> Self {
+            self.page_params_mut().fill_defaults(limit, order);
+            self
+        }
+    };
+}
+
+#[cfg(test)]
+mod page_params_tests {
+    use super::*;
+    use http::Uri;
+    use uri::TryFromUri;
+
+    #[test]
+    fn it_starts_or_extends_the_query() {
+        let mut page = PageParams::default();
+        page.set_cursor("CURSOR");
+        page.set_order(Direction::Desc);
+        page.set_limit(10);
+
+        let mut uri = "/ledgers".to_string();
+        page.push_to(&mut uri).unwrap();
+        assert_eq!(uri, "/ledgers?order=desc&cursor=CURSOR&limit=10");
+
+        let mut uri = "/assets?asset_code=USD".to_string();
+        page.push_to(&mut uri).unwrap();
+        assert_eq!(
+            uri,
+            "/assets?asset_code=USD&order=desc&cursor=CURSOR&limit=10"
+        );
+    }
+
+    #[test]
+    fn it_leaves_the_uri_alone_without_params() {
+        let mut uri = "/ledgers".to_string();
+        PageParams::default().push_to(&mut uri).unwrap();
+        assert_eq!(uri, "/ledgers");
+    }
+
+    #[test]
+    fn it_rejects_limits_horizon_would_reject() {
+        for &limit in &[0, 201] {
+            let mut page = PageParams::default();
+            page.set_limit(limit);
+            let mut uri = "/ledgers".to_string();
+            match page.push_to(&mut uri) {
+                Err(Error::InvalidLimit(l)) => assert_eq!(l, limit),
+                other => panic!("Expected an invalid limit, got {:?}", other),
+            }
+        }
+
+        for &limit in &[MIN_LIMIT, MAX_LIMIT] {
+            let mut page = PageParams::default();
+            page.set_limit(limit);
+            assert!(page.push_to(&mut "/ledgers".to_string()).is_ok());
+        }
+    }
+
+    #[test]
+    fn it_only_fills_in_missing_defaults() {
+        let mut page = PageParams::default();
+        page.set_limit(10);
+        page.fill_defaults(Some(200), Some(Direction::Asc));
+        assert_eq!(page.limit(), Some(10));
+        assert_eq!(page.order(), Some(Direction::Asc));
+    }
+
+    struct Page(PageParams);
+
+    impl TryFromUri for Page {
+        fn try_from_wrap(wrap: &UriWrap) -> ::std::result::Result<Page, ::uri::Error> {
+            Ok(Page(PageParams::from_wrap(wrap)))
+        }
+    }
+
+    #[test]
+    fn it_parses_from_a_uri() {
+        let uri: Uri = "/ledgers?order=asc&limit=5&cursor=123".parse().unwrap();
+        let Page(page) = Page::try_from(&uri).unwrap();
+        assert_eq!(page.cursor(), Some("123"));
+        assert_eq!(page.order(), Some(Direction::Asc));
+        assert_eq!(page.limit(), Some(5));
+    }
+}
@@ -1,5 +1,6 @@
 //! Contains the endpoint for all payment operations.
-use super::{Body, Cursor, Direction, IntoRequest, Limit, Order, Records};
+use super::{join_from_wrap, push_join, Body, Direction, IntoRequest, Join, PageParams, Pageable,
+            Records};
 use error::Result;
 use http::{Request, Uri};
 use resources::{Amount, AssetIdentifier, Operation, PaymentPath};
@@ -26,44 +27,25 @@ pub use super::transaction::Payments as ForTransaction;
 /// #
 /// # assert!(records.records().len() > 0);
 /// ```
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct All {
-    cursor: Option<String>,
-    order: Option<Direction>,
-    limit: Option<u32>,
+    page: PageParams,
+    #[serde(default)]
+    join: bool,
 }
 
-impl_cursor!(All);
-impl_limit!(All);
-impl_order!(All);
-
-impl All {
-    fn has_query(&self) -> bool {
-        self.order.is_some() || self.cursor.is_some() || self.limit.is_some()
-    }
-}
+impl_pageable!(All);
 
 impl IntoRequest for All {
     type Response = Records<Operation>;
 
+    impl_paging_defaults!();
+
     fn into_request(self, host: &str) -> Result<Request<Body>> {
         let mut uri = format!("{}/payments", host);
 
-        if self.has_query() {
-            uri.push_str("?");
-
-            if let Some(order) = self.order {
-                uri.push_str(&format!("order={}&", order.to_string()));
-            }
-
-            if let Some(cursor) = self.cursor {
-                uri.push_str(&format!("cursor={}&", cursor));
-            }
-
-            if let Some(limit) = self.limit {
-                uri.push_str(&format!("limit={}", limit));
-            }
-        }
+        self.page.push_to(&mut uri)?;
+        push_join(&mut uri, self.join);
 
         let uri = Uri::from_str(&uri)?;
         let request = Request::get(uri).body(Body::None)?;
@@ -73,18 +55,28 @@ impl IntoRequest for All {
 
 impl TryFromUri for All {
     fn try_from_wrap(wrap: &UriWrap) -> ::std::result::Result<All, uri::Error> {
-        let params = wrap.params();
         Ok(All {
-            cursor: params.get_parse("cursor").ok(),
-            order: params.get_parse("order").ok(),
-            limit: params.get_parse("limit").ok(),
+            page: PageParams::from_wrap(wrap),
+            join: join_from_wrap(wrap),
         })
     }
 }
 
+impl Join for All {
+    fn with_join_transactions(mut self) -> All {
+        self.join = true;
+        self
+    }
+
+    fn joins_transactions(&self) -> bool {
+        self.join
+    }
+}
+
 #[cfg(test)]
 mod all_payments_tests {
     use super::*;
+    use endpoint::{Cursor, Limit, Order};
 
     #[test]
     fn it_leaves_off_the_params_if_not_specified() {
@@ -94,6 +86,18 @@ mod all_payments_tests {
         assert_eq!(req.uri().query(), None);
     }
 
+    #[test]
+    fn it_asks_for_joined_transactions() {
+        let ep = All::default().with_join_transactions().with_limit(10);
+        assert!(ep.joins_transactions());
+        let req = ep.into_request("https://www.google.com").unwrap();
+        assert_eq!(req.uri().path(), "/payments");
+        assert_eq!(req.uri().query(), Some("limit=10&join=transactions"));
+
+        let uri: Uri = "/payments?join=transactions".parse().unwrap();
+        assert!(All::try_from(&uri).unwrap().joins_transactions());
+    }
+
     #[test]
     fn it_puts_the_query_params_on_the_uri() {
         let ep = All::default()
@@ -114,9 +118,9 @@ mod all_payments_tests {
             .parse()
             .unwrap();
         let all = All::try_from(&uri).unwrap();
-        assert_eq!(all.order, Some(Direction::Desc));
-        assert_eq!(all.cursor, Some("CURSOR".to_string()));
-        assert_eq!(all.limit, Some(123));
+        assert_eq!(all.order(), Some(Direction::Desc));
+        assert_eq!(all.cursor(), Some("CURSOR"));
+        assert_eq!(all.limit(), Some(123));
     }
 }
 
@@ -162,7 +166,7 @@ mod all_payments_tests {
 ///
 /// assert!(records.records().len() > 0);
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct FindPath {
     source_account: String,
     destination_account: String,
@@ -288,3 +292,111 @@ mod find_path_tests {
         );
     }
 }
+
+/// Finds the paths that turn a set amount of one asset into as much as
+/// possible of other assets, along with how much of each they deliver. This
+/// is what selling a balance into the market would fetch.
+///
+/// <https://www.stellar.org/developers/horizon/reference/endpoints/path-finding-strict-send.html>
+///
+/// ## Example
+/// ```
+/// use stellar_client::endpoint::payment;
+/// use stellar_client::resources::{Amount, AssetIdentifier};
+///
+/// let paths = payment::StrictSend::new(
+///     AssetIdentifier::alphanum4("USD", "GDSBCQO34HWPGUGQSP3QBFEXVTSR2PW46UIGTHVWGWJGQKH3AFNHXHXN"),
+///     Amount::new(100_000_000),
+///     vec![AssetIdentifier::native()],
+/// );
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StrictSend {
+    source_asset: AssetIdentifier,
+    source_amount: Amount,
+    destination_assets: Vec<AssetIdentifier>,
+}
+
+impl StrictSend {
+    /// Creates an endpoint for the paths that send the given amount of the
+    /// source asset and end in any of the destination assets.
+    pub fn new(
+        source_asset: AssetIdentifier,
+        source_amount: Amount,
+        destination_assets: Vec<AssetIdentifier>,
+    ) -> Self {
+        Self {
+            source_asset,
+            source_amount,
+            destination_assets,
+        }
+    }
+}
+
+impl IntoRequest for StrictSend {
+    type Response = Records<PaymentPath>;
+
+    fn into_request(self, host: &str) -> Result<Request<Body>> {
+        let mut uri = format!(
+            "{}/paths/strict-send?source_asset_type={}",
+            host,
+            self.source_asset.asset_type()
+        );
+        if !self.source_asset.is_native() {
+            uri.push_str(&format!(
+                "&source_asset_code={}&source_asset_issuer={}",
+                self.source_asset.code(),
+                self.source_asset.issuer()
+            ));
+        }
+        let destination_assets: Vec<String> = self
+            .destination_assets
+            .iter()
+            .map(|asset| {
+                if asset.is_native() {
+                    "native".to_string()
+                } else {
+                    format!("{}:{}", asset.code(), asset.issuer())
+                }
+            })
+            .collect();
+        uri.push_str(&format!(
+            "&source_amount={}&destination_assets={}",
+            self.source_amount,
+            destination_assets.join(",")
+        ));
+
+        let uri = Uri::from_str(&uri)?;
+        let request = Request::get(uri).body(Body::None)?;
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod strict_send_tests {
+    use super::*;
+
+    #[test]
+    fn it_can_make_a_strict_send_uri() {
+        let paths = StrictSend::new(
+            AssetIdentifier::alphanum4("USD", "ISSUER"),
+            Amount::new(100_000_000),
+            vec![
+                AssetIdentifier::native(),
+                AssetIdentifier::alphanum4("EUR", "ISSUER"),
+            ],
+        );
+        let request = paths
+            .into_request("https://horizon-testnet.stellar.org")
+            .unwrap();
+        assert_eq!(request.uri().path(), "/paths/strict-send");
+        assert_eq!(
+            request.uri().query(),
+            Some(
+                "source_asset_type=credit_alphanum4&source_asset_code=USD&\
+                 source_asset_issuer=ISSUER&source_amount=10.0000000&\
+                 destination_assets=native,EUR:ISSUER"
+            )
+        );
+    }
+}
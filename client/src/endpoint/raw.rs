@@ -0,0 +1,113 @@
+//! Contains a wrapper for requesting any endpoint without deserializing it
+//! into a typed resource.
+use super::{Body, Direction, IntoRequest, PageParams, Pageable};
+use error::Result;
+use http::Request;
+use serde_json::Value;
+
+/// Requests the same thing as the endpoint it wraps, but returns the json
+/// horizon sent as a `serde_json::Value` instead of a typed resource.
+///
+/// This is a way around a resource that fails to deserialize because
+/// horizon's schema moved on, and saves building the whole resource when
+/// only a field or two is needed. The raw bytes of any response are also
+/// available from `sync::Client::request_with_raw`.
+///
+/// ## Example
+///
+/// ```no_run
+/// use stellar_client::sync::Client;
+/// use stellar_client::endpoint::{account, Raw};
+///
+/// let client = Client::horizon_test().unwrap();
+/// let endpoint =
+///     account::Details::new("GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ");
+/// let account = client.request(Raw::new(endpoint)).unwrap();
+/// println!("{}", account["sequence"]);
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Raw<E> {
+    endpoint: E,
+}
+
+impl<E> Raw<E> {
+    /// Wraps an endpoint so its response is left as json.
+    pub fn new(endpoint: E) -> Raw<E> {
+        Raw { endpoint }
+    }
+
+    /// The endpoint being wrapped.
+    pub fn endpoint(&self) -> &E {
+        &self.endpoint
+    }
+
+    /// Unwraps the endpoint, so it can be requested as a typed resource.
+    pub fn into_inner(self) -> E {
+        self.endpoint
+    }
+}
+
+impl<E> IntoRequest for Raw<E>
+where
+    E: IntoRequest,
+{
+    type Response = Value;
+
+    fn into_request(self, host: &str) -> Result<Request<Body>> {
+        self.endpoint.into_request(host)
+    }
+
+    fn with_paging_defaults(self, limit: Option<u32>, order: Option<Direction>) -> Self {
+        Raw::new(self.endpoint.with_paging_defaults(limit, order))
+    }
+}
+
+impl<E> Pageable for Raw<E>
+where
+    E: Pageable,
+{
+    fn page_params(&self) -> &PageParams {
+        self.endpoint.page_params()
+    }
+
+    fn page_params_mut(&mut self) -> &mut PageParams {
+        self.endpoint.page_params_mut()
+    }
+}
+
+#[cfg(test)]
+mod raw_tests {
+    use super::*;
+    use client::sync::{test_server::TestServer, Client};
+    use endpoint::{ledger, Limit};
+
+    #[test]
+    fn it_requests_what_the_endpoint_requests() {
+        let host = "https://horizon-testnet.stellar.org";
+        let typed = ledger::All::default()
+            .with_limit(3)
+            .into_request(host)
+            .unwrap();
+        let raw = Raw::new(ledger::All::default())
+            .with_limit(3)
+            .into_request(host)
+            .unwrap();
+        assert_eq!(raw.uri(), typed.uri());
+    }
+
+    #[test]
+    fn it_passes_paging_defaults_through() {
+        let raw = Raw::new(ledger::All::default()).with_paging_defaults(Some(7), None);
+        assert_eq!(raw.limit(), Some(7));
+    }
+
+    #[test]
+    fn it_returns_the_json() {
+        let server = TestServer::start("200 OK", include_str!("../../fixtures/ledger.json"));
+        let client = Client::new(server.uri()).unwrap();
+        let ledger = client
+            .request(Raw::new(ledger::Details::new(69859)))
+            .unwrap();
+        assert_eq!(ledger["sequence"], 69859);
+    }
+}
@@ -1,31 +1,37 @@
 use http;
-use serde::de::{Deserialize, DeserializeOwned, Deserializer};
+use serde::de::{self, Deserialize, Deserializer, MapAccess, Visitor};
+use serde_json::{Map, Value};
 use std;
+use std::fmt;
+use std::marker::PhantomData;
 
 /// A struct that represents a set of records returned from the horizon api.
 ///
 /// Use this struct when querying an end point that returns an index route with
 /// embedded resources. There will also be a links object in the returned value
 /// that will provide access to the cursor to paginate.
+///
+/// Records of borrowed resources such as `OperationRef` can be deserialized
+/// straight from a response body with `serde_json::from_str`, and borrow
+/// their strings from it.
 #[derive(Debug)]
-pub struct Records<T>
-where
-    T: DeserializeOwned,
-{
+pub struct Records<T> {
     records: Vec<T>,
     next: Option<http::Uri>,
     prev: Option<http::Uri>,
 }
 
-impl<T> Records<T>
-where
-    T: DeserializeOwned,
-{
+impl<T> Records<T> {
     /// Returns a slice of the embedded records.
     pub fn records(&self) -> &Vec<T> {
         &self.records
     }
 
+    /// Takes the embedded records, leaving the links behind.
+    pub fn into_records(self) -> Vec<T> {
+        self.records
+    }
+
     /// Returns the uri to the next page.
     pub fn next(&self) -> Option<&http::Uri> {
         self.next.as_ref()
@@ -39,68 +45,103 @@ where
 
 impl<'de, T> Deserialize<'de> for Records<T>
 where
-    T: DeserializeOwned,
+    T: Deserialize<'de>,
 {
     fn deserialize<D>(d: D) -> std::result::Result<Records<T>, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let embedded: Embedded<RecordsIntermediate<T>> = Embedded::deserialize(d)?;
-        if let Some(links) = embedded.links {
-            Ok(Records {
-                records: embedded.embedded.records,
-                next: links.next.and_then(|v| v.uri()),
-                prev: links.prev.and_then(|v| v.uri()),
-            })
-        } else {
-            Ok(Records {
-                records: embedded.embedded.records,
-                next: None,
-                prev: None,
-            })
-        }
+        d.deserialize_map(PageVisitor(PhantomData))
     }
 }
 
-/// The HAL response format will embed resources within it. When it does
-/// this provides a wrapper to the `_embedded` key.
+/// Reads a page of records in horizon's HAL response format, which embeds
+/// the records under the `_embedded` key and links to other pages under
+/// `_links`. Endpoints that return a single resource have no `_embedded`
+/// key, and their fields are kept so that the resource can be read as the
+/// only record.
+///
+/// The records are deserialized straight from the response rather than
+/// buffered first, as `#[serde(flatten)]` would, since pages of records are
+/// what ingestion spends most of its time parsing.
 ///
 /// https://www.stellar.org/developers/horizon/reference/responses.html
-#[derive(Deserialize)]
-struct Embedded<T> {
-    #[serde(rename = "_embedded")]
-    embedded: T,
-    #[serde(rename = "_links")]
-    links: Option<Links>,
-}
+struct PageVisitor<T>(PhantomData<T>);
 
-/// If the embedded resource is a set of records, this can provide that data back in
-/// a generic way.
-#[derive(Deserialize)]
-struct RecordsIntermediate<T> {
-    records: Vec<T>,
-}
+impl<'de, T> Visitor<'de> for PageVisitor<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = Records<T>;
 
-#[derive(Deserialize)]
-struct Links {
-    next: Option<Href>,
-    prev: Option<Href>,
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a page of records or a single record")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> std::result::Result<Records<T>, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut embedded: Option<RecordsIntermediate<T>> = None;
+        let mut links: Option<Map<String, Value>> = None;
+        let mut rest = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "_embedded" => embedded = map.next_value()?,
+                "_links" => links = map.next_value()?,
+                _ => {
+                    rest.insert(key, map.next_value()?);
+                }
+            }
+        }
+
+        let records = match embedded {
+            Some(embedded) => embedded.records.unwrap_or_default(),
+            None if rest.is_empty() => Vec::new(),
+            None => {
+                // The links of a single record are its own, not a page's.
+                if let Some(ref links) = links {
+                    rest.insert("_links".to_string(), Value::Object(links.clone()));
+                }
+                let record = T::deserialize(Value::Object(rest)).map_err(|err| {
+                    de::Error::custom(format!(
+                        "expected `_embedded.records` or a single record: {}",
+                        err
+                    ))
+                })?;
+                vec![record]
+            }
+        };
+        let links = links.unwrap_or_default();
+        Ok(Records {
+            records,
+            next: link(&links, "next"),
+            prev: link(&links, "prev"),
+        })
+    }
 }
 
+/// If the embedded resource is a set of records, this can provide that data back in
+/// a generic way. Horizon leaves out or nulls the records of some empty pages.
 #[derive(Deserialize)]
-struct Href {
-    href: String,
+struct RecordsIntermediate<T> {
+    records: Option<Vec<T>>,
 }
 
-impl Href {
-    fn uri(&self) -> Option<http::Uri> {
-        self.href.parse().ok()
-    }
+/// The uri of the link with the given rel. Other rels, links without an
+/// href and hrefs that aren't uris, such as templated ones, are ignored.
+fn link(links: &Map<String, Value>, rel: &str) -> Option<http::Uri> {
+    links
+        .get(rel)
+        .and_then(|link| link.get("href"))
+        .and_then(Value::as_str)
+        .and_then(|href| href.parse().ok())
 }
 
 #[cfg(test)]
 mod records_test {
     use super::*;
+    use resources::Offer;
     use serde_json;
 
     #[derive(Deserialize)]
@@ -177,4 +218,93 @@ mod records_test {
         assert_eq!(records.next(), None);
         assert_eq!(records.prev(), None);
     }
+
+    #[test]
+    fn it_parses_an_empty_page() {
+        let json = r#"
+        {
+            "_links": {
+                "next": { "href": "/assets?order=asc&limit=10&cursor=NEXT_CURSOR" }
+            },
+            "_embedded": { "records": [] }
+        }"#;
+        let records: Records<Foo> = serde_json::from_str(json).unwrap();
+        assert!(records.records().is_empty());
+        assert!(records.next().is_some());
+    }
+
+    #[test]
+    fn it_parses_embedded_without_records() {
+        for json in &[
+            r#"{ "_embedded": {} }"#,
+            r#"{ "_embedded": { "records": null } }"#,
+            r#"{ "_links": {} }"#,
+            r#"{}"#,
+        ] {
+            let records: Records<Foo> = serde_json::from_str(json).unwrap();
+            assert!(records.records().is_empty());
+            assert_eq!(records.next(), None);
+        }
+    }
+
+    #[test]
+    fn it_parses_a_single_record_without_embedded() {
+        let json = r#"
+        {
+            "_links": {
+                "self": { "href": "/foos/1" }
+            },
+            "foo": "bar"
+        }"#;
+        let records: Records<Foo> = serde_json::from_str(json).unwrap();
+        assert_eq!(records.records().len(), 1);
+        assert_eq!(records.records()[0].foo, "bar");
+        assert_eq!(records.next(), None);
+    }
+
+    #[test]
+    fn it_keeps_the_links_of_a_single_record() {
+        let json = include_str!("../../fixtures/offer.json");
+        let records: Records<Offer> = serde_json::from_str(json).unwrap();
+        let links = records.records()[0].links().unwrap();
+        assert!(links.offer_maker().is_some());
+    }
+
+    #[test]
+    fn it_explains_a_response_that_is_neither_a_page_nor_a_record() {
+        let json = r#"{ "_links": {}, "bar": "baz" }"#;
+        let err = serde_json::from_str::<Records<Foo>>(json)
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("expected `_embedded.records` or a single record"));
+        assert!(err.contains("missing field `foo`"));
+    }
+
+    #[test]
+    fn it_ignores_unknown_and_malformed_link_rels() {
+        let json = r#"
+        {
+            "_links": {
+                "self": { "href": "/assets?cursor=&limit=10&order=asc" },
+                "transactions": {
+                    "href": "/transactions{?cursor,limit,order}",
+                    "templated": true
+                },
+                "related": [{ "href": "/foo" }, { "href": "/bar" }],
+                "prev": null,
+                "next": { "href": "/assets?cursor=NEXT_CURSOR", "title": "Next" }
+            },
+            "_embedded": {
+                "records": [
+                    { "foo": "bar" }
+                ]
+            }
+        }"#;
+        let next: http::Uri = "/assets?cursor=NEXT_CURSOR".parse().unwrap();
+        let records: Records<Foo> = serde_json::from_str(json).unwrap();
+        assert_eq!(records.records().len(), 1);
+        assert_eq!(records.next(), Some(&next));
+        assert_eq!(records.prev(), None);
+    }
 }
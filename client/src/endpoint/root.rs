@@ -0,0 +1,49 @@
+//! Contains the endpoint for the root of a horizon server.
+use super::{Body, IntoRequest};
+use error::Result;
+use http::{Request, Uri};
+use resources::Root;
+use std::str::FromStr;
+
+/// Fetches what the horizon server says about itself: its version, the
+/// network's protocol version and passphrase, and the latest ledgers it
+/// knows of.
+///
+/// <https://developers.stellar.org/api/horizon/resources/get-root>
+///
+/// ## Example
+///
+/// ```no_run
+/// use stellar_client::sync::Client;
+/// use stellar_client::endpoint::root;
+///
+/// let client = Client::horizon_test().unwrap();
+/// let root = client.request(root::Details).unwrap();
+/// println!("horizon {}", root.horizon_version());
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+pub struct Details;
+
+impl IntoRequest for Details {
+    type Response = Root;
+
+    fn into_request(self, host: &str) -> Result<Request<Body>> {
+        let uri = Uri::from_str(&format!("{}/", host))?;
+        let request = Request::get(uri).body(Body::None)?;
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod root_tests {
+    use super::*;
+
+    #[test]
+    fn it_can_make_a_root_uri() {
+        let request = Details
+            .into_request("https://horizon-testnet.stellar.org")
+            .unwrap();
+        assert_eq!(request.uri().host().unwrap(), "horizon-testnet.stellar.org");
+        assert_eq!(request.uri().path(), "/");
+    }
+}
@@ -0,0 +1,213 @@
+//! Generates endpoints with random combinations of parameters and checks
+//! that the uri each builds parses back into an endpoint that builds the
+//! same uri. This is what lets the `next` and `prev` links horizon returns
+//! be followed, so any endpoint that implements `TryFromUri` belongs here.
+use super::*;
+use proptest::option;
+use proptest::prelude::*;
+use proptest::test_runner::TestCaseError;
+use resources::AssetIdentifier;
+use uri::TryFromUri;
+
+const HOST: &str = "https://horizon.example.com";
+
+fn account_id() -> impl Strategy<Value = String> {
+    "G[A-Z2-7]{55}"
+}
+
+fn hash() -> impl Strategy<Value = String> {
+    "[0-9a-f]{64}"
+}
+
+fn cursor() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just("now".to_string()),
+        "[0-9]{1,19}",
+        "[0-9]{1,19}-[0-9]{1,10}",
+    ]
+}
+
+fn direction() -> impl Strategy<Value = Direction> {
+    prop_oneof![Just(Direction::Asc), Just(Direction::Desc)]
+}
+
+fn asset() -> impl Strategy<Value = AssetIdentifier> {
+    prop_oneof![
+        Just(AssetIdentifier::native()),
+        ("[A-Za-z0-9]{1,4}", account_id())
+            .prop_map(|(code, issuer)| AssetIdentifier::alphanum4(&code, &issuer)),
+        ("[A-Za-z0-9]{5,12}", account_id())
+            .prop_map(|(code, issuer)| AssetIdentifier::alphanum12(&code, &issuer)),
+    ]
+}
+
+prop_compose! {
+    fn page()(
+        cursor in option::of(cursor()),
+        order in option::of(direction()),
+        limit in option::of(1u32..=200),
+    ) -> PageParams {
+        let mut page = PageParams::default();
+        if let Some(cursor) = cursor {
+            page.set_cursor(&cursor);
+        }
+        if let Some(order) = order {
+            page.set_order(order);
+        }
+        if let Some(limit) = limit {
+            page.set_limit(limit);
+        }
+        page
+    }
+}
+
+fn paged<E: Pageable>(mut endpoint: E, page: PageParams) -> E {
+    *endpoint.page_params_mut() = page;
+    endpoint
+}
+
+fn uri_of<E: IntoRequest>(endpoint: E) -> ::std::result::Result<String, TestCaseError> {
+    endpoint
+        .into_request(HOST)
+        .map(|request| request.uri().to_string())
+        .map_err(|err| TestCaseError::fail(format!("Failed to build a uri: {:?}", err)))
+}
+
+fn assert_round_trips<E>(endpoint: E) -> ::std::result::Result<(), TestCaseError>
+where
+    E: IntoRequest + TryFromUri,
+{
+    let uri = uri_of(endpoint)?;
+    let parsed = E::try_from(&uri.parse().unwrap())
+        .map_err(|err| TestCaseError::fail(format!("Failed to parse {}: {:?}", uri, err)))?;
+    prop_assert_eq!(uri_of(parsed)?, uri);
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn all_accounts_round_trip(
+        filter in prop_oneof![
+            account_id().prop_map(|id| account::All::for_signer(&id)),
+            asset().prop_map(account::All::for_asset),
+            account_id().prop_map(|id| account::All::for_sponsor(&id)),
+            hash().prop_map(|id| account::All::for_liquidity_pool(&id)),
+        ],
+        page in page(),
+    ) {
+        assert_round_trips(paged(filter, page))?;
+    }
+
+    #[test]
+    fn account_records_round_trip(id in account_id(), page in page()) {
+        assert_round_trips(paged(account::Transactions::new(&id), page.clone()))?;
+        assert_round_trips(paged(account::Effects::new(&id), page.clone()))?;
+        assert_round_trips(paged(account::Operations::new(&id), page.clone()))?;
+        assert_round_trips(paged(account::Payments::new(&id), page.clone()))?;
+        assert_round_trips(paged(account::Offers::new(&id), page.clone()))?;
+        assert_round_trips(paged(account::Trades::new(&id), page))?;
+    }
+
+    #[test]
+    fn all_assets_round_trip(
+        code in option::of("[A-Za-z0-9]{1,12}"),
+        issuer in option::of(account_id()),
+        page in page(),
+    ) {
+        let mut endpoint = asset::All::default();
+        if let Some(code) = code {
+            endpoint = endpoint.with_asset_code(&code);
+        }
+        if let Some(issuer) = issuer {
+            endpoint = endpoint.with_asset_issuer(&issuer);
+        }
+        assert_round_trips(paged(endpoint, page))?;
+    }
+
+    #[test]
+    fn unfiltered_records_round_trip(page in page()) {
+        assert_round_trips(paged(effect::All::default(), page.clone()))?;
+        assert_round_trips(paged(ledger::All::default(), page.clone()))?;
+        assert_round_trips(paged(operation::All::default(), page.clone()))?;
+        assert_round_trips(paged(payment::All::default(), page.clone()))?;
+        assert_round_trips(paged(transaction::All::default(), page))?;
+    }
+
+    #[test]
+    fn ledger_records_round_trip(sequence in any::<u32>(), page in page()) {
+        assert_round_trips(paged(ledger::Payments::new(sequence), page.clone()))?;
+        assert_round_trips(paged(ledger::Transactions::new(sequence), page.clone()))?;
+        assert_round_trips(paged(ledger::Effects::new(sequence), page.clone()))?;
+        assert_round_trips(paged(ledger::Operations::new(sequence), page))?;
+    }
+
+    #[test]
+    fn joined_payments_round_trip(
+        id in account_id(),
+        sequence in any::<u32>(),
+        hash in hash(),
+        page in page(),
+    ) {
+        let all = payment::All::default().with_join_transactions();
+        assert_round_trips(paged(all, page.clone()))?;
+        let account = account::Payments::new(&id).with_join_transactions();
+        assert_round_trips(paged(account, page.clone()))?;
+        let ledger = ledger::Payments::new(sequence).with_join_transactions();
+        assert_round_trips(paged(ledger, page.clone()))?;
+        let transaction = transaction::Payments::new(&hash).with_join_transactions();
+        assert_round_trips(paged(transaction, page))?;
+    }
+
+    #[test]
+    fn operation_effects_round_trip(id in 0i64.., page in page()) {
+        assert_round_trips(paged(operation::Effects::new(id), page))?;
+    }
+
+    #[test]
+    fn transaction_records_round_trip(hash in hash(), page in page()) {
+        assert_round_trips(paged(transaction::Effects::new(&hash), page.clone()))?;
+        assert_round_trips(paged(transaction::Payments::new(&hash), page.clone()))?;
+        assert_round_trips(paged(transaction::Operations::new(&hash), page))?;
+    }
+
+    #[test]
+    fn all_trades_round_trip(
+        pair in option::of((asset(), asset())),
+        offer_id in option::of(any::<u64>()),
+        page in page(),
+    ) {
+        let mut endpoint = trade::All::default();
+        if let Some((base, counter)) = pair {
+            endpoint = endpoint.with_asset_pair(base, counter);
+        }
+        if let Some(offer_id) = offer_id {
+            endpoint = endpoint.with_offer_id(offer_id);
+        }
+        assert_round_trips(paged(endpoint, page))?;
+    }
+
+    #[test]
+    fn trade_aggregations_round_trip(
+        base in asset(),
+        counter in asset(),
+        resolution in any::<u64>(),
+        start_time in any::<u64>(),
+        end_time in any::<u64>(),
+        offset in any::<u64>(),
+        order in option::of(direction()),
+        limit in option::of(1u32..=200),
+    ) {
+        let mut endpoint = trade::Aggregations::new(&base, &counter)
+            .with_resolution(resolution)
+            .with_start_time(start_time)
+            .with_end_time(end_time)
+            .with_offset(offset);
+        if let Some(order) = order {
+            endpoint = endpoint.with_order(order);
+        }
+        if let Some(limit) = limit {
+            endpoint = endpoint.with_limit(limit);
+        }
+        assert_round_trips(endpoint)?;
+    }
+}
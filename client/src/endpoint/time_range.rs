@@ -0,0 +1,119 @@
+use chrono::{DateTime, TimeZone, Utc};
+
+/// A window of time for endpoints that filter by time, such as trade
+/// aggregations. Horizon takes the start and end as milliseconds since the
+/// epoch, which the endpoints convert to from the range. Times before the
+/// epoch are taken as the epoch itself, and milliseconds past the latest time
+/// a `DateTime` can hold as that latest time.
+///
+/// ## Example
+///
+/// ```
+/// # extern crate chrono;
+/// # extern crate stellar_client;
+/// use chrono::{Duration, TimeZone, Utc};
+/// use stellar_client::endpoint::TimeRange;
+///
+/// # fn main() {
+/// let start = Utc.timestamp(1_517_522_400, 0);
+/// let range = TimeRange::new(start, start + Duration::hours(1));
+/// assert_eq!(range.start_millis(), 1_517_522_400_000);
+/// assert_eq!(range.end_millis(), 1_517_526_000_000);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimeRange {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+}
+
+impl TimeRange {
+    /// The range from `start` up to `end`.
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>) -> TimeRange {
+        TimeRange { start, end }
+    }
+
+    /// The range from milliseconds since the epoch, as horizon returns them.
+    pub fn from_millis(start: u64, end: u64) -> TimeRange {
+        TimeRange::new(from_millis(start), from_millis(end))
+    }
+
+    /// When the range starts.
+    pub fn start(&self) -> DateTime<Utc> {
+        self.start
+    }
+
+    /// When the range ends.
+    pub fn end(&self) -> DateTime<Utc> {
+        self.end
+    }
+
+    /// Whether the range ends at or before it starts, which horizon rejects.
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    /// The start in milliseconds since the epoch.
+    pub fn start_millis(&self) -> u64 {
+        to_millis(self.start)
+    }
+
+    /// The end in milliseconds since the epoch.
+    pub fn end_millis(&self) -> u64 {
+        to_millis(self.end)
+    }
+}
+
+fn to_millis(time: DateTime<Utc>) -> u64 {
+    time.timestamp_millis().max(0) as u64
+}
+
+fn from_millis(millis: u64) -> DateTime<Utc> {
+    let seconds = (millis / 1000) as i64;
+    Utc.timestamp_opt(seconds, ((millis % 1000) * 1_000_000) as u32)
+        .single()
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
+#[cfg(test)]
+mod time_range_tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn it_converts_to_milliseconds() {
+        let start = Utc.timestamp(1_517_522_400, 250_000_000);
+        let range = TimeRange::new(start, start + Duration::milliseconds(1500));
+        assert_eq!(range.start_millis(), 1_517_522_400_250);
+        assert_eq!(range.end_millis(), 1_517_522_401_750);
+        assert_eq!(
+            TimeRange::from_millis(1_517_522_400_250, 1_517_522_401_750),
+            range
+        );
+    }
+
+    #[test]
+    fn it_clamps_times_before_the_epoch() {
+        let range = TimeRange::new(Utc.timestamp(-10, 0), Utc.timestamp(10, 0));
+        assert_eq!(range.start_millis(), 0);
+        assert_eq!(range.end_millis(), 10_000);
+    }
+
+    #[test]
+    fn it_clamps_milliseconds_past_the_latest_time() {
+        let range = TimeRange::from_millis(0, u64::MAX);
+        assert_eq!(range.end(), DateTime::<Utc>::MAX_UTC);
+        assert_eq!(
+            range.end_millis(),
+            DateTime::<Utc>::MAX_UTC.timestamp_millis() as u64
+        );
+    }
+
+    #[test]
+    fn it_knows_when_it_is_empty() {
+        let now = Utc::now();
+        assert!(TimeRange::new(now, now).is_empty());
+        assert!(TimeRange::new(now, now - Duration::seconds(1)).is_empty());
+        assert!(!TimeRange::new(now, now + Duration::seconds(1)).is_empty());
+    }
+}
@@ -1,5 +1,7 @@
 //! Contains the endpoint for all trades.
-use super::{Body, Cursor, Direction, IntoRequest, Limit, Order, Records};
+use super::{
+    check_limit, push_param, Body, Direction, IntoRequest, Limit, Order, PageParams, Pageable, Records, TimeRange,
+};
 use error::Result;
 use http::{Request, Uri};
 use resources::{AssetIdentifier, Trade, TradeAggregation};
@@ -9,7 +11,7 @@ use uri::{self, TryFromUri, UriWrap};
 /// Private struct used to define a trade pair.
 /// Since the uri must include a base and a counter
 /// asset, it makes sense to group them together.
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
 struct AssetPair {
     base: AssetIdentifier,
     counter: AssetIdentifier,
@@ -138,18 +140,14 @@ mod asset_pair_tests {
 /// #
 /// # assert!(records.records().len() > 0);
 /// ```
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct All {
     asset_pair: Option<AssetPair>,
-    offer_id: Option<u32>,
-    cursor: Option<String>,
-    order: Option<Direction>,
-    limit: Option<u32>,
+    offer_id: Option<u64>,
+    page: PageParams,
 }
 
-impl_cursor!(All);
-impl_limit!(All);
-impl_order!(All);
+impl_pageable!(All);
 
 impl All {
     /// Fetches the record for a specified trade pair.
@@ -196,46 +194,28 @@ impl All {
     /// #
     /// # assert!(records.records().len() > 0);
     /// ```
-    pub fn with_offer_id(mut self, offer_id: u32) -> Self {
+    pub fn with_offer_id(mut self, offer_id: u64) -> Self {
         self.offer_id = Some(offer_id);
         self
     }
-
-    fn has_query(&self) -> bool {
-        self.order.is_some() || self.cursor.is_some() || self.limit.is_some()
-            || self.offer_id.is_some() || self.asset_pair.is_some()
-    }
 }
 
 impl IntoRequest for All {
     type Response = Records<Trade>;
 
+    impl_paging_defaults!();
+
     fn into_request(self, host: &str) -> Result<Request<Body>> {
         let mut uri = format!("{}/trades", host);
 
-        if self.has_query() {
-            uri.push_str("?");
-
-            if let Some(asset_pair) = self.asset_pair {
-                uri.push_str(&asset_pair.to_param());
-            }
-
-            if let Some(offer_id) = self.offer_id {
-                uri.push_str(&format!("&offer_id={}", offer_id));
-            }
-
-            if let Some(order) = self.order {
-                uri.push_str(&format!("&order={}", order.to_string()));
-            }
-
-            if let Some(cursor) = self.cursor {
-                uri.push_str(&format!("&cursor={}", cursor));
-            }
-
-            if let Some(limit) = self.limit {
-                uri.push_str(&format!("&limit={}", limit));
-            }
+        if let Some(ref asset_pair) = self.asset_pair {
+            uri.push('?');
+            uri.push_str(&asset_pair.to_param());
         }
+        if let Some(offer_id) = self.offer_id {
+            push_param(&mut uri, "offer_id", &offer_id.to_string());
+        }
+        self.page.push_to(&mut uri)?;
 
         let uri = Uri::from_str(&uri)?;
         let request = Request::get(uri).body(Body::None)?;
@@ -246,12 +226,15 @@ impl IntoRequest for All {
 impl TryFromUri for All {
     fn try_from_wrap(wrap: &UriWrap) -> ::std::result::Result<All, uri::Error> {
         let params = wrap.params();
+        let asset_pair = if params.get("base_asset_type").is_some() {
+            Some(AssetPair::try_from_wrap(wrap)?)
+        } else {
+            None
+        };
         Ok(All {
-            asset_pair: Some(AssetPair::try_from_wrap(&wrap)?),
+            asset_pair,
             offer_id: params.get_parse("offer_id").ok(),
-            cursor: params.get_parse("cursor").ok(),
-            order: params.get_parse("order").ok(),
-            limit: params.get_parse("limit").ok(),
+            page: PageParams::from_wrap(wrap),
         })
     }
 }
@@ -259,6 +242,7 @@ impl TryFromUri for All {
 #[cfg(test)]
 mod all_trades_tests {
     use super::*;
+    use endpoint::Cursor;
 
     #[test]
     fn it_leaves_off_the_params_if_not_specified() {
@@ -324,9 +308,9 @@ mod all_trades_tests {
         let counter = AssetIdentifier::native();
         assert_eq!(all.asset_pair, Some(AssetPair { base, counter }));
         assert_eq!(all.offer_id, Some(123));
-        assert_eq!(all.order, Some(Direction::Desc));
-        assert_eq!(all.cursor, Some("CURSOR".to_string()));
-        assert_eq!(all.limit, Some(123));
+        assert_eq!(all.order(), Some(Direction::Desc));
+        assert_eq!(all.cursor(), Some("CURSOR"));
+        assert_eq!(all.limit(), Some(123));
     }
 }
 
@@ -337,11 +321,14 @@ mod all_trades_tests {
 /// ## Example
 ///
 /// ```
+/// # extern crate chrono;
+/// # extern crate stellar_client;
+/// use chrono::{Duration, Utc};
 /// use stellar_client::sync::Client;
-/// use stellar_client::endpoint::{Direction, Order, trade};
+/// use stellar_client::endpoint::{Direction, Order, TimeRange, trade};
 /// use stellar_client::resources::AssetIdentifier;
-/// use std::time::{SystemTime, UNIX_EPOCH};
 ///
+/// # fn main() {
 /// let client = Client::horizon_test().unwrap();
 ///
 /// // Grab a trade so that we know aggregations should exist.
@@ -351,33 +338,51 @@ mod all_trades_tests {
 /// let base = trade.base_asset();
 /// let counter = trade.counter_asset();
 ///
-/// // Determine the current end time
-/// let now = SystemTime::now()
-///     .duration_since(UNIX_EPOCH)
-///     .unwrap()
-///     .as_secs() * 1000;
-///
 /// // Place the start time in the past so we capture it.
+/// let now = Utc::now();
 /// let agg = trade::Aggregations::new(base, counter)
-///     .with_start_time(now - 3_000_000_000)
-///     .with_end_time(now)
+///     .with_time_range(TimeRange::new(now - Duration::days(35), now))
 ///     .with_resolution(300_000);
 ///
 /// let records = client.request(agg).unwrap();
 /// # assert!(records.records().len() > 0);
+/// # }
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Aggregations {
     asset_pair: AssetPair,
     resolution: u64,
     start_time: u64,
     end_time: u64,
+    #[serde(default)]
+    offset: u64,
+    #[serde(default)]
+    inverted: bool,
     order: Option<Direction>,
     limit: Option<u32>,
 }
 
-impl_limit!(Aggregations);
-impl_order!(Aggregations);
+impl Limit for Aggregations {
+    fn with_limit(mut self, limit: u32) -> Aggregations {
+        self.limit = Some(limit);
+        self
+    }
+
+    fn limit(&self) -> Option<u32> {
+        self.limit
+    }
+}
+
+impl Order for Aggregations {
+    fn with_order(mut self, order: Direction) -> Aggregations {
+        self.order = Some(order);
+        self
+    }
+
+    fn order(&self) -> Option<Direction> {
+        self.order
+    }
+}
 
 impl Aggregations {
     /// Creates a new aggregations endpoint. There are some defaults but generally
@@ -391,11 +396,64 @@ impl Aggregations {
             resolution: 300_000,
             start_time: 0,
             end_time: 0,
+            offset: 0,
+            inverted: false,
             order: None,
             limit: None,
         }
     }
 
+    /// Creates an aggregations endpoint for a pair of assets in the order
+    /// horizon expects, with native first and then by type, code and issuer,
+    /// so that the same pair is always requested with the same base and
+    /// counter whichever order the assets are given in.
+    ///
+    /// If the assets had to be swapped, `is_inverted` is true and the prices
+    /// of the returned aggregations are of `first` in terms of `second`.
+    /// `TradeAggregation::inverted` turns them back around.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stellar_client::endpoint::trade;
+    /// use stellar_client::resources::AssetIdentifier;
+    ///
+    /// let issuer = "GBSTRUSD7IRX73RQZBL3RQUH6KS3O4NYFY3QCALDLZD77XMZOPWAVTUK";
+    /// let usd = AssetIdentifier::alphanum4("USD", issuer);
+    /// let xlm = AssetIdentifier::native();
+    ///
+    /// let endpoint = trade::Aggregations::canonical(&usd, &xlm);
+    /// assert!(endpoint.is_inverted());
+    /// assert_eq!(endpoint.base(), &xlm);
+    /// assert_eq!(endpoint.counter(), &usd);
+    /// assert!(!trade::Aggregations::canonical(&xlm, &usd).is_inverted());
+    /// ```
+    pub fn canonical(first: &AssetIdentifier, second: &AssetIdentifier) -> Aggregations {
+        if second < first {
+            let mut aggregations = Aggregations::new(second, first);
+            aggregations.inverted = true;
+            aggregations
+        } else {
+            Aggregations::new(first, second)
+        }
+    }
+
+    /// The base asset of the pair.
+    pub fn base(&self) -> &AssetIdentifier {
+        &self.asset_pair.base
+    }
+
+    /// The counter asset of the pair.
+    pub fn counter(&self) -> &AssetIdentifier {
+        &self.asset_pair.counter
+    }
+
+    /// Whether `canonical` swapped the assets it was given, so that the
+    /// prices of the aggregations are the inverse of those asked for.
+    pub fn is_inverted(&self) -> bool {
+        self.inverted
+    }
+
     /// Sets the resolution to bin by. The pagination will increment at this
     /// interval of milliseconds.
     ///
@@ -458,11 +516,80 @@ impl Aggregations {
         self.end_time = s;
         self
     }
+
+    /// Sets the offset of each bucket from the start of its resolution, in
+    /// milliseconds, such as to bin days by a timezone other than UTC. Horizon
+    /// requires it to be a whole number of hours less than the resolution.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stellar_client::endpoint::trade;
+    /// use stellar_client::resources::AssetIdentifier;
+    ///
+    /// let base = AssetIdentifier::native();
+    /// let counter = AssetIdentifier::native();
+    ///
+    /// let endpoint = trade::Aggregations::new(&base, &counter)
+    ///     .with_resolution(86_400_000)
+    ///     .with_offset(3_600_000);
+    /// assert_eq!(endpoint.offset(), 3_600_000);
+    /// ```
+    pub fn with_offset(mut self, offset: u64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// The offset of each bucket, in milliseconds.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Sets the start and end times from a range of times.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate chrono;
+    /// # extern crate stellar_client;
+    /// use chrono::{Duration, Utc};
+    /// use stellar_client::endpoint::{trade, TimeRange};
+    /// use stellar_client::resources::AssetIdentifier;
+    ///
+    /// # fn main() {
+    /// let base = AssetIdentifier::native();
+    /// let counter = AssetIdentifier::native();
+    ///
+    /// let now = Utc::now();
+    /// let range = TimeRange::new(now - Duration::days(1), now);
+    /// let endpoint = trade::Aggregations::new(&base, &counter).with_time_range(range);
+    /// assert_eq!(endpoint.time_range(), TimeRange::from_millis(range.start_millis(), range.end_millis()));
+    /// # }
+    /// ```
+    pub fn with_time_range(self, range: TimeRange) -> Self {
+        self.with_start_time(range.start_millis())
+            .with_end_time(range.end_millis())
+    }
+
+    /// The start and end times as a range of times.
+    pub fn time_range(&self) -> TimeRange {
+        TimeRange::from_millis(self.start_time, self.end_time)
+    }
 }
 
 impl IntoRequest for Aggregations {
     type Response = Records<TradeAggregation>;
 
+    fn with_paging_defaults(mut self, limit: Option<u32>, order: Option<Direction>) -> Self {
+        if self.limit.is_none() {
+            self.limit = limit;
+        }
+        if self.order.is_none() {
+            self.order = order;
+        }
+        self
+    }
+
     fn into_request(self, host: &str) -> Result<Request<Body>> {
         let mut uri = format!("{}/trade_aggregations?", host);
 
@@ -472,10 +599,15 @@ impl IntoRequest for Aggregations {
         uri.push_str(&format!("start_time={}&", self.start_time));
         uri.push_str(&format!("end_time={}", self.end_time));
 
+        if self.offset > 0 {
+            uri.push_str(&format!("&offset={}", self.offset));
+        }
+
         if let Some(order) = self.order {
-            uri.push_str(&format!("&order={}", order.to_string()));
+            uri.push_str(&format!("&order={}", order));
         }
 
+        check_limit(self.limit)?;
         if let Some(limit) = self.limit {
             uri.push_str(&format!("&limit={}", limit));
         }
@@ -494,6 +626,8 @@ impl TryFromUri for Aggregations {
             resolution: params.get_parse("resolution")?,
             start_time: params.get_parse("start_time")?,
             end_time: params.get_parse("end_time")?,
+            offset: params.get_parse("offset").unwrap_or(0),
+            inverted: false,
             order: params.get_parse("order").ok(),
             limit: params.get_parse("limit").ok(),
         })
@@ -515,6 +649,7 @@ mod aggregation_tests {
         assert_eq!(agg.end_time, 100000);
         assert_eq!(agg.order, Some(Direction::Desc));
         assert_eq!(agg.limit, Some(123));
+        assert_eq!(agg.time_range(), TimeRange::from_millis(100, 100000));
     }
 
     #[test]
@@ -534,8 +669,7 @@ mod aggregation_tests {
         let agg = Aggregations::new(&AssetIdentifier::native(), &AssetIdentifier::native())
             .with_limit(123)
             .with_resolution(1)
-            .with_start_time(10)
-            .with_end_time(20)
+            .with_time_range(TimeRange::from_millis(10, 20))
             .with_order(Direction::Desc);
         let req = agg.into_request("https://www.google.com").unwrap();
         assert_eq!(req.uri().path(), "/trade_aggregations");
@@ -552,4 +686,38 @@ mod aggregation_tests {
             )
         );
     }
+
+    #[test]
+    fn it_rejects_an_invalid_limit() {
+        let agg = Aggregations::new(&AssetIdentifier::native(), &AssetIdentifier::native())
+            .with_limit(1_000);
+        match agg.into_request("https://www.google.com") {
+            Err(::error::Error::InvalidLimit(1_000)) => {}
+            other => panic!("Expected an invalid limit, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_requests_an_offset() {
+        let agg = Aggregations::new(&AssetIdentifier::native(), &AssetIdentifier::native())
+            .with_resolution(86_400_000)
+            .with_offset(3_600_000);
+        let req = agg.into_request("https://www.google.com").unwrap();
+        assert!(req.uri().query().unwrap().ends_with("&offset=3600000"));
+        let agg = Aggregations::try_from(req.uri()).unwrap();
+        assert_eq!(agg.offset(), 3_600_000);
+    }
+
+    #[test]
+    fn it_orders_the_pair_canonically() {
+        let usd = AssetIdentifier::alphanum4("USD", "ISSUER");
+        let eur = AssetIdentifier::alphanum4("EUR", "ISSUER");
+        let forward = Aggregations::canonical(&eur, &usd);
+        let backward = Aggregations::canonical(&usd, &eur);
+        assert!(!forward.is_inverted());
+        assert!(backward.is_inverted());
+        assert_eq!(forward.asset_pair, backward.asset_pair);
+        assert_eq!(backward.base(), &eur);
+        assert_eq!(backward.counter(), &usd);
+    }
 }
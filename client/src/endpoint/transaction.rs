@@ -1,9 +1,11 @@
 //! Contains endpoints for transactions and related information.
-use super::{Body, Cursor, Direction, IntoRequest, Limit, Order, Records};
+use super::{join_from_wrap, push_join, Body, Direction, IntoRequest, Join, PageParams, Pageable,
+            Records};
 use error::Result;
-use http::{Request, Uri};
-use resources::{Effect, Operation, Transaction};
+use http::{header, Request, Uri};
+use resources::{Effect, Operation, Submission, Transaction};
 use std::str::FromStr;
+use transaction::TransactionEnvelope;
 use uri::{self, TryFromUri, UriWrap};
 
 pub use super::account::Transactions as ForAccount;
@@ -26,43 +28,21 @@ pub use super::ledger::Transactions as ForLedger;
 /// #
 /// # assert!(records.records().len() > 0);
 /// ```
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct All {
-    cursor: Option<String>,
-    order: Option<Direction>,
-    limit: Option<u32>,
+    page: PageParams,
 }
 
-impl_cursor!(All);
-impl_limit!(All);
-impl_order!(All);
-
-impl All {
-    fn has_query(&self) -> bool {
-        self.order.is_some() || self.cursor.is_some() || self.limit.is_some()
-    }
-}
+impl_pageable!(All);
 
 impl IntoRequest for All {
     type Response = Records<Transaction>;
 
+    impl_paging_defaults!();
+
     fn into_request(self, host: &str) -> Result<Request<Body>> {
         let mut uri = format!("{}/transactions", host);
-        if self.has_query() {
-            uri.push_str("?");
-
-            if let Some(cursor) = self.cursor {
-                uri.push_str(&format!("cursor={}&", cursor));
-            }
-
-            if let Some(order) = self.order {
-                uri.push_str(&format!("order={}&", order.to_string()));
-            }
-
-            if let Some(limit) = self.limit {
-                uri.push_str(&format!("limit={}", limit));
-            }
-        }
+        self.page.push_to(&mut uri)?;
 
         let uri = Uri::from_str(&uri)?;
         let request = Request::get(uri).body(Body::None)?;
@@ -72,11 +52,8 @@ impl IntoRequest for All {
 
 impl TryFromUri for All {
     fn try_from_wrap(wrap: &UriWrap) -> ::std::result::Result<All, uri::Error> {
-        let params = wrap.params();
         Ok(All {
-            cursor: params.get_parse("cursor").ok(),
-            order: params.get_parse("order").ok(),
-            limit: params.get_parse("limit").ok(),
+            page: PageParams::from_wrap(wrap),
         })
     }
 }
@@ -84,6 +61,7 @@ impl TryFromUri for All {
 #[cfg(test)]
 mod all_transactions_test {
     use super::*;
+    use endpoint::{Cursor, Limit, Order};
 
     #[test]
     fn it_leaves_off_the_params_if_not_specified() {
@@ -103,7 +81,7 @@ mod all_transactions_test {
         assert_eq!(req.uri().path(), "/transactions");
         assert_eq!(
             req.uri().query(),
-            Some("cursor=CURSOR&order=desc&limit=123")
+            Some("order=desc&cursor=CURSOR&limit=123")
         );
     }
 
@@ -113,9 +91,9 @@ mod all_transactions_test {
             .parse()
             .unwrap();
         let all = All::try_from(&uri).unwrap();
-        assert_eq!(all.order, Some(Direction::Desc));
-        assert_eq!(all.cursor, Some("CURSOR".to_string()));
-        assert_eq!(all.limit, Some(123));
+        assert_eq!(all.order(), Some(Direction::Desc));
+        assert_eq!(all.cursor(), Some("CURSOR"));
+        assert_eq!(all.limit(), Some(123));
     }
 }
 
@@ -139,7 +117,7 @@ mod all_transactions_test {
 /// #
 /// # assert_eq!(txn.hash(), hash);
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Details {
     hash: String,
 }
@@ -147,9 +125,9 @@ pub struct Details {
 impl Details {
     /// Returns a new end point for transaction details. Hand this to the client in order to request
     /// the details for a specific transaction
-    pub fn new(hash: &str) -> Self {
+    pub fn new(hash: impl Into<String>) -> Self {
         Self {
-            hash: hash.to_string(),
+            hash: hash.into(),
         }
     }
 }
@@ -179,6 +157,86 @@ mod transaction_details_tests {
     }
 }
 
+/// Submits a signed transaction envelope to the network. Horizon holds the
+/// request open until the transaction is included in a ledger or rejected,
+/// in which case the client returns a bad response with the reason.
+///
+/// <https://www.stellar.org/developers/horizon/reference/endpoints/transactions-create.html>
+///
+/// ## Example
+///
+/// ```no_run
+/// use stellar_client::sync::Client;
+/// use stellar_client::endpoint::transaction;
+///
+/// let client = Client::horizon_test().unwrap();
+/// let endpoint = transaction::Submit::new("AAAAAH2Hmt1JWMfqAdUlDeyUtO9V8zPq...");
+/// let submission = client.request(endpoint).unwrap();
+/// println!("applied in ledger {}", submission.ledger());
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Submit {
+    envelope_xdr: String,
+}
+
+impl Submit {
+    /// Returns a new end point for submitting the given base64 encoded
+    /// transaction envelope.
+    pub fn new(envelope_xdr: impl Into<String>) -> Self {
+        Self {
+            envelope_xdr: envelope_xdr.into(),
+        }
+    }
+
+    /// Returns a new end point for submitting a signed envelope.
+    pub fn from_envelope(envelope: &TransactionEnvelope) -> Result<Self> {
+        Ok(Self {
+            envelope_xdr: envelope.to_base64()?,
+        })
+    }
+
+    /// The base64 encoded envelope that will be submitted.
+    pub fn envelope_xdr(&self) -> &str {
+        &self.envelope_xdr
+    }
+}
+
+impl IntoRequest for Submit {
+    type Response = Submission;
+
+    fn into_request(self, host: &str) -> Result<Request<Body>> {
+        let uri = Uri::from_str(&format!("{}/transactions", host))?;
+        let body = format!("tx={}", uri::percent_encode(&self.envelope_xdr));
+        let request = Request::post(uri)
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::Form(body))?;
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod transaction_submit_tests {
+    use super::*;
+    use http::Method;
+
+    #[test]
+    fn it_posts_the_envelope_as_a_form() {
+        let request = Submit::new("AAAA+b/c==")
+            .into_request("https://horizon-testnet.stellar.org")
+            .unwrap();
+        assert_eq!(request.method(), &Method::POST);
+        assert_eq!(request.uri().path(), "/transactions");
+        assert_eq!(
+            request.headers()[header::CONTENT_TYPE],
+            "application/x-www-form-urlencoded"
+        );
+        match *request.body() {
+            Body::Form(ref form) => assert_eq!(form, "tx=AAAA%2Bb%2Fc%3D%3D"),
+            ref body => panic!("Unexpected body {:?}", body),
+        }
+    }
+}
+
 /// ```
 /// use stellar_client::sync::Client;
 /// use stellar_client::endpoint::{transaction, effect, Limit};
@@ -197,55 +255,33 @@ mod transaction_details_tests {
 ///
 /// assert!(effects.records().len() > 0);
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Effects {
     hash: String,
-    cursor: Option<String>,
-    order: Option<Direction>,
-    limit: Option<u32>,
+    page: PageParams,
 }
 
-impl_cursor!(Effects);
-impl_limit!(Effects);
-impl_order!(Effects);
+impl_pageable!(Effects);
 
 impl Effects {
     /// Returns a new endpoint for effects. Hand this to the client in order
     /// to request effects for a specific transaction by hash
-    pub fn new(hash: &str) -> Self {
+    pub fn new(hash: impl Into<String>) -> Self {
         Effects {
-            hash: hash.to_string(),
-            cursor: None,
-            order: None,
-            limit: None,
+            hash: hash.into(),
+            page: PageParams::default(),
         }
     }
-
-    fn has_query(&self) -> bool {
-        self.order.is_some() || self.cursor.is_some() || self.limit.is_some()
-    }
 }
 
 impl IntoRequest for Effects {
     type Response = Records<Effect>;
 
+    impl_paging_defaults!();
+
     fn into_request(self, host: &str) -> Result<Request<Body>> {
         let mut uri = format!("{}/transactions/{}/effects", host, self.hash);
-        if self.has_query() {
-            uri.push_str("?");
-
-            if let Some(cursor) = self.cursor {
-                uri.push_str(&format!("cursor={}&", cursor));
-            }
-
-            if let Some(order) = self.order {
-                uri.push_str(&format!("order={}&", order.to_string()));
-            }
-
-            if let Some(limit) = self.limit {
-                uri.push_str(&format!("limit={}", limit));
-            }
-        }
+        self.page.push_to(&mut uri)?;
 
         let uri = Uri::from_str(&uri)?;
         let request = Request::get(uri).body(Body::None)?;
@@ -257,12 +293,9 @@ impl TryFromUri for Effects {
     fn try_from_wrap(wrap: &UriWrap) -> ::std::result::Result<Self, uri::Error> {
         match wrap.path() {
             ["transactions", hash, "effects"] => {
-                let params = wrap.params();
                 Ok(Self {
                     hash: hash.to_string(),
-                    cursor: params.get_parse("cursor").ok(),
-                    order: params.get_parse("order").ok(),
-                    limit: params.get_parse("limit").ok(),
+                    page: PageParams::from_wrap(wrap),
                 })
             }
             _ => Err(uri::Error::invalid_path()),
@@ -273,6 +306,7 @@ impl TryFromUri for Effects {
 #[cfg(test)]
 mod effects_tests {
     use super::*;
+    use endpoint::{Cursor, Limit, Order};
 
     #[test]
     fn it_leaves_off_the_params_if_not_specified() {
@@ -295,7 +329,7 @@ mod effects_tests {
         assert_eq!(req.uri().path(), "/transactions/abc123/effects");
         assert_eq!(
             req.uri().query(),
-            Some("cursor=CURSOR&order=desc&limit=123")
+            Some("order=desc&cursor=CURSOR&limit=123")
         );
     }
 
@@ -306,9 +340,9 @@ mod effects_tests {
             .unwrap();
         let ep = Effects::try_from(&uri).unwrap();
         assert_eq!(ep.hash, "abc123");
-        assert_eq!(ep.limit, Some(123));
-        assert_eq!(ep.cursor, Some("CURSOR".to_string()));
-        assert_eq!(ep.order, Some(Direction::Desc));
+        assert_eq!(ep.limit(), Some(123));
+        assert_eq!(ep.cursor(), Some("CURSOR"));
+        assert_eq!(ep.order(), Some(Direction::Desc));
     }
 }
 
@@ -336,54 +370,36 @@ mod effects_tests {
 /// assert!(payments.records().len() > 0);
 /// assert_eq!(payments.records()[0].transaction(), hash);
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Payments {
     hash: String,
-    cursor: Option<String>,
-    order: Option<Direction>,
-    limit: Option<u32>,
+    page: PageParams,
+    #[serde(default)]
+    join: bool,
 }
 
-impl_cursor!(Payments);
-impl_limit!(Payments);
-impl_order!(Payments);
+impl_pageable!(Payments);
 
 impl Payments {
     /// Creates a new struct representing a request to the payments endpoint
-    pub fn new(hash: &str) -> Payments {
+    pub fn new(hash: impl Into<String>) -> Payments {
         Payments {
-            hash: hash.to_string(),
-            cursor: None,
-            order: None,
-            limit: None,
+            hash: hash.into(),
+            page: PageParams::default(),
+            join: false,
         }
     }
-
-    fn has_query(&self) -> bool {
-        self.order.is_some() || self.cursor.is_some() || self.limit.is_some()
-    }
 }
 
 impl IntoRequest for Payments {
     type Response = Records<Operation>;
 
+    impl_paging_defaults!();
+
     fn into_request(self, host: &str) -> Result<Request<Body>> {
         let mut uri = format!("{}/transactions/{}/payments", host, self.hash);
-        if self.has_query() {
-            uri.push_str("?");
-
-            if let Some(cursor) = self.cursor {
-                uri.push_str(&format!("cursor={}&", cursor));
-            }
-
-            if let Some(order) = self.order {
-                uri.push_str(&format!("order={}&", order.to_string()));
-            }
-
-            if let Some(limit) = self.limit {
-                uri.push_str(&format!("limit={}", limit));
-            }
-        }
+        self.page.push_to(&mut uri)?;
+        push_join(&mut uri, self.join);
 
         let uri = Uri::from_str(&uri)?;
         let request = Request::get(uri).body(Body::None)?;
@@ -395,12 +411,10 @@ impl TryFromUri for Payments {
     fn try_from_wrap(wrap: &UriWrap) -> ::std::result::Result<Self, uri::Error> {
         match wrap.path() {
             ["transactions", hash, "payments"] => {
-                let params = wrap.params();
                 Ok(Self {
                     hash: hash.to_string(),
-                    cursor: params.get_parse("cursor").ok(),
-                    order: params.get_parse("order").ok(),
-                    limit: params.get_parse("limit").ok(),
+                    page: PageParams::from_wrap(wrap),
+                    join: join_from_wrap(wrap),
                 })
             }
             _ => Err(uri::Error::invalid_path()),
@@ -408,9 +422,21 @@ impl TryFromUri for Payments {
     }
 }
 
+impl Join for Payments {
+    fn with_join_transactions(mut self) -> Payments {
+        self.join = true;
+        self
+    }
+
+    fn joins_transactions(&self) -> bool {
+        self.join
+    }
+}
+
 #[cfg(test)]
 mod transaction_payments_test {
     use super::*;
+    use endpoint::{Cursor, Limit, Order};
 
     #[test]
     fn it_leaves_off_the_params_if_not_specified() {
@@ -420,6 +446,18 @@ mod transaction_payments_test {
         assert_eq!(req.uri().query(), None);
     }
 
+    #[test]
+    fn it_asks_for_joined_transactions() {
+        let ep = Payments::new("abc123").with_join_transactions().with_limit(10);
+        assert!(ep.joins_transactions());
+        let req = ep.into_request("https://www.google.com").unwrap();
+        assert_eq!(req.uri().path(), "/transactions/abc123/payments");
+        assert_eq!(req.uri().query(), Some("limit=10&join=transactions"));
+
+        let uri: Uri = "/transactions/abc123/payments?join=transactions".parse().unwrap();
+        assert!(Payments::try_from(&uri).unwrap().joins_transactions());
+    }
+
     #[test]
     fn it_puts_the_query_params_on_the_uri() {
         let ep = Payments::new("HASH123")
@@ -430,7 +468,7 @@ mod transaction_payments_test {
         assert_eq!(req.uri().path(), "/transactions/HASH123/payments");
         assert_eq!(
             req.uri().query(),
-            Some("cursor=CURSOR&order=desc&limit=123")
+            Some("order=desc&cursor=CURSOR&limit=123")
         );
     }
 
@@ -441,9 +479,9 @@ mod transaction_payments_test {
             .unwrap();
         let ep = Payments::try_from(&uri).unwrap();
         assert_eq!(ep.hash, "abc123");
-        assert_eq!(ep.limit, Some(123));
-        assert_eq!(ep.cursor, Some("CURSOR".to_string()));
-        assert_eq!(ep.order, Some(Direction::Desc));
+        assert_eq!(ep.limit(), Some(123));
+        assert_eq!(ep.cursor(), Some("CURSOR"));
+        assert_eq!(ep.order(), Some(Direction::Desc));
     }
 }
 
@@ -471,54 +509,32 @@ mod transaction_payments_test {
 /// assert!(operations.records().len() > 0);
 /// assert_eq!(operations.records()[0].transaction(), hash);
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Operations {
     hash: String,
-    cursor: Option<String>,
-    order: Option<Direction>,
-    limit: Option<u32>,
+    page: PageParams,
 }
 
-impl_cursor!(Operations);
-impl_limit!(Operations);
-impl_order!(Operations);
+impl_pageable!(Operations);
 
 impl Operations {
     /// Creates a new struct representing a request to the payments endpoint
-    pub fn new(hash: &str) -> Operations {
+    pub fn new(hash: impl Into<String>) -> Operations {
         Operations {
-            hash: hash.to_string(),
-            cursor: None,
-            order: None,
-            limit: None,
+            hash: hash.into(),
+            page: PageParams::default(),
         }
     }
-
-    fn has_query(&self) -> bool {
-        self.order.is_some() || self.cursor.is_some() || self.limit.is_some()
-    }
 }
 
 impl IntoRequest for Operations {
     type Response = Records<Operation>;
 
+    impl_paging_defaults!();
+
     fn into_request(self, host: &str) -> Result<Request<Body>> {
         let mut uri = format!("{}/transactions/{}/operations", host, self.hash);
-        if self.has_query() {
-            uri.push_str("?");
-
-            if let Some(cursor) = self.cursor {
-                uri.push_str(&format!("cursor={}&", cursor));
-            }
-
-            if let Some(order) = self.order {
-                uri.push_str(&format!("order={}&", order.to_string()));
-            }
-
-            if let Some(limit) = self.limit {
-                uri.push_str(&format!("limit={}", limit));
-            }
-        }
+        self.page.push_to(&mut uri)?;
 
         let uri = Uri::from_str(&uri)?;
         let request = Request::get(uri).body(Body::None)?;
@@ -530,12 +546,9 @@ impl TryFromUri for Operations {
     fn try_from_wrap(wrap: &UriWrap) -> ::std::result::Result<Self, uri::Error> {
         match wrap.path() {
             ["transactions", hash, "operations"] => {
-                let params = wrap.params();
                 Ok(Self {
                     hash: hash.to_string(),
-                    cursor: params.get_parse("cursor").ok(),
-                    order: params.get_parse("order").ok(),
-                    limit: params.get_parse("limit").ok(),
+                    page: PageParams::from_wrap(wrap),
                 })
             }
             _ => Err(uri::Error::invalid_path()),
@@ -546,6 +559,7 @@ impl TryFromUri for Operations {
 #[cfg(test)]
 mod transaction_operations_test {
     use super::*;
+    use endpoint::{Cursor, Limit, Order};
 
     #[test]
     fn it_leaves_off_the_params_if_not_specified() {
@@ -565,7 +579,7 @@ mod transaction_operations_test {
         assert_eq!(req.uri().path(), "/transactions/HASH123/operations");
         assert_eq!(
             req.uri().query(),
-            Some("cursor=CURSOR&order=desc&limit=123")
+            Some("order=desc&cursor=CURSOR&limit=123")
         );
     }
 
@@ -576,8 +590,8 @@ mod transaction_operations_test {
             .unwrap();
         let ep = Operations::try_from(&uri).unwrap();
         assert_eq!(ep.hash, "abc123");
-        assert_eq!(ep.limit, Some(123));
-        assert_eq!(ep.cursor, Some("CURSOR".to_string()));
-        assert_eq!(ep.order, Some(Direction::Desc));
+        assert_eq!(ep.limit(), Some(123));
+        assert_eq!(ep.cursor(), Some("CURSOR"));
+        assert_eq!(ep.order(), Some(Direction::Desc));
     }
 }
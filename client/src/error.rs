@@ -1,13 +1,19 @@
 //! Error and result module
 use super::StellarError;
+use endpoint::{MAX_LIMIT, MIN_LIMIT};
 use http;
 use hyper;
-use hyper::error::UriError;
 use reqwest;
 use serde_json;
+use signer;
 use std::error::Error as StdError;
 use std::fmt;
+use std::io;
+use std::str;
+use sync::Staleness;
+use toml;
 use uri;
+use xdr;
 
 /// A set of errors for use in the client
 #[derive(Debug)]
@@ -30,10 +36,33 @@ pub enum Error {
     ///
     /// <https://docs.serde.rs/serde_json/error/struct.Error.html>
     JsonParseError(serde_json::error::Error),
+    /// A response from horizon couldn't be deserialized. Carries the status,
+    /// the path requested and the start of the body, to tell which endpoint
+    /// and field broke.
+    Deserialize(DeserializeError),
+    /// An error occurred while parsing a stellar.toml file.
+    ///
+    /// <https://docs.rs/toml/0.4/toml/de/struct.Error.html>
+    TomlParseError(toml::de::Error),
     /// Catch-all for reqwest error handling
     Reqwest(reqwest::Error),
     /// Errors that occur when converting from uri into something else.
     TryFromUri(uri::Error),
+    /// Errors that occur when decoding XDR.
+    Xdr(xdr::Error),
+    /// An error reading from an open stream.
+    Io(io::Error),
+    /// A circuit breaker stopped the request because horizon has been
+    /// failing. See `sync::CircuitBreaker`.
+    CircuitOpen,
+    /// The horizon server that answered was too far behind the reference
+    /// server. See `sync::StaleGuard`.
+    Stale(Staleness),
+    /// A request couldn't be signed. See `sync::RequestSigner`.
+    Signer(signer::Error),
+    /// A page limit outside the 1 to 200 records horizon accepts. Caught
+    /// when the request is built rather than sent for horizon to reject.
+    InvalidLimit(u32),
     #[doc(hidden)]
     __Nonexhaustive,
 }
@@ -49,9 +78,17 @@ impl StdError for Error {
             Error::Http(ref inner) => inner.description(),
             Error::Reqwest(ref inner) => inner.description(),
             Error::JsonParseError(ref inner) => inner.description(),
+            Error::Deserialize(_) => "The response from horizon could not be deserialized",
+            Error::TomlParseError(ref inner) => inner.description(),
             Error::BadResponse(ref inner) => inner.description(),
             Error::TryFromUri(ref inner) => inner.description(),
+            Error::Xdr(ref inner) => inner.description(),
+            Error::Io(ref inner) => inner.description(),
             Error::ServerError => "An unknown error on the server has occurred",
+            Error::CircuitOpen => "Horizon has been failing, so the request was not sent",
+            Error::Stale(_) => "The horizon server is too far behind the reference server",
+            Error::Signer(ref inner) => inner.description(),
+            Error::InvalidLimit(_) => "The limit is outside the range horizon accepts",
             Error::__Nonexhaustive => unreachable!(),
         }
     }
@@ -59,13 +96,103 @@ impl StdError for Error {
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.write_str(self.description())
+        match *self {
+            Error::Deserialize(ref inner) => inner.fmt(f),
+            Error::Stale(ref inner) => inner.fmt(f),
+            Error::InvalidLimit(limit) => write!(
+                f,
+                "A limit of {} is outside the {} to {} records horizon accepts",
+                limit, MIN_LIMIT, MAX_LIMIT
+            ),
+            _ => f.write_str(self.description()),
+        }
     }
 }
 
-impl From<UriError> for Error {
-    fn from(_: UriError) -> Self {
-        Error::BadUri
+/// The most of a body kept by a `DeserializeError`.
+const MAX_BODY_LEN: usize = 512;
+
+/// A response body that couldn't be deserialized, along with where it came
+/// from.
+#[derive(Debug)]
+pub struct DeserializeError {
+    status: u16,
+    path: String,
+    body: String,
+    truncated: bool,
+    inner: serde_json::error::Error,
+}
+
+impl DeserializeError {
+    pub(crate) fn new(
+        status: u16,
+        path: &str,
+        body: &[u8],
+        inner: serde_json::error::Error,
+    ) -> DeserializeError {
+        let truncated = body.len() > MAX_BODY_LEN;
+        let mut head = &body[..body.len().min(MAX_BODY_LEN)];
+        if truncated {
+            // Drop a character the cut split in two, rather than showing it
+            // as a replacement character.
+            if let Err(err) = str::from_utf8(head) {
+                if err.error_len().is_none() {
+                    head = &head[..err.valid_up_to()];
+                }
+            }
+        }
+        DeserializeError {
+            status,
+            path: path.to_string(),
+            body: String::from_utf8_lossy(head).into_owned(),
+            truncated,
+            inner,
+        }
+    }
+
+    /// The http status of the response.
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    /// The path that was requested, such as `/accounts/GABC...`.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The body of the response, cut off after its first 512 bytes.
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    /// Whether the body was longer than what's kept.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// The error from serde, which gives the line and column that broke.
+    pub fn inner(&self) -> &serde_json::error::Error {
+        &self.inner
+    }
+}
+
+impl fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Couldn't deserialize the {} response from {}: {}. The body was: {}{}",
+            self.status,
+            self.path,
+            self.inner,
+            self.body,
+            if self.truncated { "..." } else { "" }
+        )
+    }
+}
+
+impl From<DeserializeError> for Error {
+    fn from(inner: DeserializeError) -> Self {
+        Error::Deserialize(inner)
     }
 }
 
@@ -81,6 +208,12 @@ impl From<http::Error> for Error {
     }
 }
 
+impl From<http::header::InvalidHeaderValue> for Error {
+    fn from(inner: http::header::InvalidHeaderValue) -> Self {
+        Error::Http(inner.into())
+    }
+}
+
 impl From<http::uri::InvalidUri> for Error {
     fn from(_: http::uri::InvalidUri) -> Self {
         Error::BadUri
@@ -105,12 +238,36 @@ impl From<serde_json::error::Error> for Error {
     }
 }
 
+impl From<toml::de::Error> for Error {
+    fn from(inner: toml::de::Error) -> Self {
+        Error::TomlParseError(inner)
+    }
+}
+
 impl From<uri::Error> for Error {
     fn from(inner: uri::Error) -> Self {
         Error::TryFromUri(inner)
     }
 }
 
+impl From<xdr::Error> for Error {
+    fn from(inner: xdr::Error) -> Self {
+        Error::Xdr(inner)
+    }
+}
+
+impl From<signer::Error> for Error {
+    fn from(inner: signer::Error) -> Self {
+        Error::Signer(inner)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(inner: io::Error) -> Self {
+        Error::Io(inner)
+    }
+}
+
 #[cfg(test)]
 mod error_coversion_tests {
     use super::*;
@@ -126,6 +283,28 @@ mod error_coversion_tests {
         );
     }
 
+    #[test]
+    fn it_describes_where_deserializing_failed() {
+        let body = format!(r#"{{"id": 12, "memo": "{}"}}"#, "é".repeat(400));
+        let inner = serde_json::from_str::<String>(&body).unwrap_err();
+        let error = DeserializeError::new(200, "/accounts/GABC", body.as_bytes(), inner);
+        assert!(error.is_truncated());
+        assert!(error.body().len() <= 512);
+        assert!(error.body().starts_with(r#"{"id": 12"#));
+
+        let message = Error::from(error).to_string();
+        assert!(message.starts_with(
+            "Couldn't deserialize the 200 response from /accounts/GABC: invalid type: map"
+        ));
+        assert!(message.ends_with("é..."));
+
+        let mut body = vec![b'a'; 600];
+        body[550] = 0xff;
+        let inner = serde_json::from_slice::<String>(&body).unwrap_err();
+        let error = DeserializeError::new(200, "/accounts/GABC", &body, inner);
+        assert_eq!(error.body(), "a".repeat(512));
+    }
+
     #[test]
     fn it_coerces_a_reqwest_parse_error() {
         let error = reqwest::Url::from_str("b l a h").unwrap_err();
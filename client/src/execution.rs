@@ -0,0 +1,546 @@
+//! Execution quality for algorithmic traders: how the prices an account
+//! traded at compare to the top of the orderbook when the trades happened.
+//!
+//! Horizon only serves the orderbook as it is now, so the top of the book is
+//! recorded ledger by ledger in a `BookHistory`, usually by capturing it as
+//! each ledger closes. A `SlippageReport` then matches each of the account's
+//! trades with the quote recorded for its ledger, or the latest one before
+//! it, and measures the slippage in basis points: how much worse than the
+//! best ask a buy was, or than the best bid a sell was. A negative slippage
+//! is a price improvement.
+//!
+//! ```no_run
+//! use stellar_client::endpoint::{account, ledger, Cursor, Limit, Position};
+//! use stellar_client::execution::{BookHistory, SlippageReport};
+//! use stellar_client::resources::AssetIdentifier;
+//! use stellar_client::sync::Client;
+//!
+//! let client = Client::horizon().unwrap();
+//! let usd = AssetIdentifier::alphanum4(
+//!     "USD",
+//!     "GDUKMGUGDZQK6YHYA5Z6AY2G4XDSZPSZ3SW5UN3ARVMO6QSRDWP5YLEX",
+//! );
+//! let trader = "GATLAI2D7SSH6PE3HXTDPTRM4RE5VRK6HGA63K5EWP75PSANCZRFDNB5";
+//!
+//! let mut history = BookHistory::new(AssetIdentifier::native(), usd);
+//! let ledgers = client.stream(ledger::All::default().with_cursor(Position::Now)).unwrap();
+//! for ledger in ledgers.take(720) {
+//!     history.capture(&client, ledger.unwrap().sequence()).unwrap();
+//! }
+//!
+//! let trades = client
+//!     .request(account::Trades::new(trader).with_limit(200))
+//!     .unwrap();
+//! let report = SlippageReport::new(trader, trades.records(), &history);
+//! println!(
+//!     "{} trades, mean slippage {:?} bps, worst {:?} bps",
+//!     report.executions().len(),
+//!     report.mean_bps(),
+//!     report.worst_bps()
+//! );
+//! ```
+use client::sync::Client;
+use endpoint::{orderbook, Limit};
+use error::Result;
+use resources::{Amount, AssetIdentifier, Orderbook, Trade};
+use std::collections::BTreeMap;
+
+/// The best bid and ask for a pair of assets as of a ledger, in units of
+/// the counter asset for one of the base asset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quote {
+    ledger: u32,
+    bid: Option<Amount>,
+    ask: Option<Amount>,
+}
+
+impl Quote {
+    /// The ledger the quote was recorded for.
+    pub fn ledger(&self) -> u32 {
+        self.ledger
+    }
+
+    /// The best price anyone was bidding, or `None` when there were no
+    /// bids.
+    pub fn bid(&self) -> Option<Amount> {
+        self.bid
+    }
+
+    /// The best price anyone was asking, or `None` when there were no asks.
+    pub fn ask(&self) -> Option<Amount> {
+        self.ask
+    }
+}
+
+/// The top of the orderbook between a base and a counter asset, recorded
+/// ledger by ledger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookHistory {
+    base: AssetIdentifier,
+    counter: AssetIdentifier,
+    quotes: BTreeMap<u32, Quote>,
+}
+
+impl BookHistory {
+    /// Starts an empty history of the orderbook for a pair of assets.
+    pub fn new(base: AssetIdentifier, counter: AssetIdentifier) -> BookHistory {
+        BookHistory {
+            base,
+            counter,
+            quotes: BTreeMap::new(),
+        }
+    }
+
+    /// The asset being bought and sold.
+    pub fn base(&self) -> &AssetIdentifier {
+        &self.base
+    }
+
+    /// The asset prices are in.
+    pub fn counter(&self) -> &AssetIdentifier {
+        &self.counter
+    }
+
+    /// Records the top of an orderbook as of a ledger, replacing anything
+    /// recorded for that ledger before. Orderbooks for any other pair, or
+    /// for this pair the other way around, are ignored.
+    pub fn record(&mut self, ledger: u32, orderbook: &Orderbook) {
+        if orderbook.base() != &self.base || orderbook.counter() != &self.counter {
+            return;
+        }
+        let quote = Quote {
+            ledger,
+            bid: orderbook.bids().first().map(|offer| offer.price()),
+            ask: orderbook.asks().first().map(|offer| offer.price()),
+        };
+        self.quotes.insert(ledger, quote);
+    }
+
+    /// Fetches the top of the orderbook from horizon and records it as of
+    /// the ledger, which should be the ledger that closed last.
+    pub fn capture(&mut self, client: &Client, ledger: u32) -> Result<()> {
+        let endpoint = orderbook::Details::for_asset_pair(self.base.clone(), self.counter.clone())
+            .with_limit(1);
+        let orderbook = client.request(endpoint)?;
+        self.record(ledger, &orderbook);
+        Ok(())
+    }
+
+    /// The quote as of a ledger: the one recorded for it, or else the
+    /// latest one recorded before it.
+    pub fn quote_at(&self, ledger: u32) -> Option<&Quote> {
+        self.quotes
+            .range(..=ledger)
+            .next_back()
+            .map(|(_, quote)| quote)
+    }
+
+    /// The number of ledgers recorded.
+    pub fn len(&self) -> usize {
+        self.quotes.len()
+    }
+
+    /// Whether nothing has been recorded yet.
+    pub fn is_empty(&self) -> bool {
+        self.quotes.is_empty()
+    }
+}
+
+/// Which way a trade went for the account, in terms of the base asset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// The account bought the base asset, paying the counter asset.
+    Buy,
+    /// The account sold the base asset for the counter asset.
+    Sell,
+}
+
+/// One of the account's trades measured against the top of the book.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Execution {
+    trade_id: String,
+    ledger: u32,
+    side: Side,
+    base_amount: Amount,
+    price: Amount,
+    reference: Amount,
+    slippage_bps: f64,
+}
+
+impl Execution {
+    /// The id of the trade.
+    pub fn trade_id(&self) -> &str {
+        &self.trade_id
+    }
+
+    /// The ledger the trade happened in.
+    pub fn ledger(&self) -> u32 {
+        self.ledger
+    }
+
+    /// Whether the account bought or sold the base asset.
+    pub fn side(&self) -> Side {
+        self.side
+    }
+
+    /// How much of the base asset changed hands.
+    pub fn base_amount(&self) -> Amount {
+        self.base_amount
+    }
+
+    /// The price the trade executed at.
+    pub fn price(&self) -> Amount {
+        self.price
+    }
+
+    /// The price it is measured against: the best ask for a buy or the
+    /// best bid for a sell.
+    pub fn reference(&self) -> Amount {
+        self.reference
+    }
+
+    /// How much worse than the reference the price was, in basis points
+    /// of the reference. Negative when the price was better.
+    pub fn slippage_bps(&self) -> f64 {
+        self.slippage_bps
+    }
+}
+
+/// The slippage of an account's trades in a pair of assets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlippageReport {
+    executions: Vec<Execution>,
+    unquoted: usize,
+}
+
+impl SlippageReport {
+    /// Measures the trades the account made in the history's pair of
+    /// assets, in either direction, against the history. Trades in other
+    /// pairs or between other accounts are left out.
+    pub fn new(account_id: &str, trades: &[Trade], history: &BookHistory) -> SlippageReport {
+        let mut executions = Vec::new();
+        let mut unquoted = 0;
+        for trade in trades {
+            let fill = match Fill::of(account_id, trade, history) {
+                Some(fill) => fill,
+                None => continue,
+            };
+            match fill.measure(trade, history) {
+                Some(execution) => executions.push(execution),
+                None => unquoted += 1,
+            }
+        }
+        SlippageReport {
+            executions,
+            unquoted,
+        }
+    }
+
+    /// The trades that could be measured, in the order they were given.
+    pub fn executions(&self) -> &[Execution] {
+        &self.executions
+    }
+
+    /// The number of the account's trades that couldn't be measured,
+    /// because nothing was recorded as of their ledger or the side of the
+    /// book they traded against was empty.
+    pub fn unquoted(&self) -> usize {
+        self.unquoted
+    }
+
+    /// The average slippage, in basis points.
+    pub fn mean_bps(&self) -> Option<f64> {
+        if self.executions.is_empty() {
+            return None;
+        }
+        let total: f64 = self.executions.iter().map(Execution::slippage_bps).sum();
+        Some(total / self.executions.len() as f64)
+    }
+
+    /// The average slippage weighted by the amount of the base asset each
+    /// trade moved, in basis points.
+    pub fn weighted_mean_bps(&self) -> Option<f64> {
+        let volume: f64 = self
+            .executions
+            .iter()
+            .map(|execution| execution.base_amount.stroops() as f64)
+            .sum();
+        if volume == 0.0 {
+            return None;
+        }
+        let total: f64 = self
+            .executions
+            .iter()
+            .map(|execution| execution.slippage_bps * execution.base_amount.stroops() as f64)
+            .sum();
+        Some(total / volume)
+    }
+
+    /// The median slippage, in basis points.
+    pub fn median_bps(&self) -> Option<f64> {
+        let mut sorted: Vec<f64> = self
+            .executions
+            .iter()
+            .map(Execution::slippage_bps)
+            .collect();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let middle = sorted.len() / 2;
+        match sorted.len() {
+            0 => None,
+            len if len % 2 == 0 => Some((sorted[middle - 1] + sorted[middle]) / 2.0),
+            _ => Some(sorted[middle]),
+        }
+    }
+
+    /// The largest slippage, in basis points.
+    pub fn worst_bps(&self) -> Option<f64> {
+        self.executions
+            .iter()
+            .map(Execution::slippage_bps)
+            .fold(None, |worst, bps| match worst {
+                Some(worst) if worst >= bps => Some(worst),
+                _ => Some(bps),
+            })
+    }
+
+    /// The number of trades that executed at a better price than the top
+    /// of the book.
+    pub fn improved(&self) -> usize {
+        self.executions
+            .iter()
+            .filter(|execution| execution.slippage_bps < 0.0)
+            .count()
+    }
+}
+
+/// A trade seen from the account's side and in the history's orientation.
+struct Fill {
+    side: Side,
+    base_amount: Amount,
+    counter_amount: Amount,
+}
+
+impl Fill {
+    /// The account's side of a trade in the history's pair, or `None` when
+    /// the account or the pair aren't in the trade.
+    fn of(account_id: &str, trade: &Trade, history: &BookHistory) -> Option<Fill> {
+        // The base account of a trade gives up the trade's base asset.
+        let sold_trade_base = if trade.base_account() == account_id {
+            true
+        } else if trade.counter_account() == account_id {
+            false
+        } else {
+            return None;
+        };
+        let same_way =
+            trade.base_asset() == history.base() && trade.counter_asset() == history.counter();
+        let other_way =
+            trade.base_asset() == history.counter() && trade.counter_asset() == history.base();
+        let (sold_base, base_amount, counter_amount) = if same_way {
+            (sold_trade_base, trade.base_amount(), trade.counter_amount())
+        } else if other_way {
+            (
+                !sold_trade_base,
+                trade.counter_amount(),
+                trade.base_amount(),
+            )
+        } else {
+            return None;
+        };
+        Some(Fill {
+            side: if sold_base { Side::Sell } else { Side::Buy },
+            base_amount,
+            counter_amount,
+        })
+    }
+
+    /// Measures the fill against the quote as of the trade's ledger, or
+    /// `None` when there's nothing to measure it against.
+    fn measure(&self, trade: &Trade, history: &BookHistory) -> Option<Execution> {
+        let ledger = trade.trade_id().ok()?.operation().ledger();
+        let quote = history.quote_at(ledger)?;
+        let price = price(self.base_amount, self.counter_amount)?;
+        let (reference, worse) = match self.side {
+            Side::Buy => {
+                let ask = quote.ask()?;
+                (ask, price.stroops() - ask.stroops())
+            }
+            Side::Sell => {
+                let bid = quote.bid()?;
+                (bid, bid.stroops() - price.stroops())
+            }
+        };
+        if reference.stroops() == 0 {
+            return None;
+        }
+        Some(Execution {
+            trade_id: trade.id().to_string(),
+            ledger,
+            side: self.side,
+            base_amount: self.base_amount,
+            price,
+            reference,
+            slippage_bps: worse as f64 / reference.stroops() as f64 * 10_000.0,
+        })
+    }
+}
+
+/// The price of one of the base asset in the counter asset, rounded to the
+/// nearest stroop, or `None` when no base asset changed hands.
+fn price(base: Amount, counter: Amount) -> Option<Amount> {
+    let (base, counter) = (i128::from(base.stroops()), i128::from(counter.stroops()));
+    if base == 0 {
+        return None;
+    }
+    let stroops = (counter * 10_000_000 + base / 2) / base;
+    Some(Amount::new(stroops.min(i128::from(i64::MAX)) as i64))
+}
+
+#[cfg(test)]
+mod execution_tests {
+    use super::*;
+    use serde_json;
+
+    const TRADER: &str = "GBZXCJIUEPDXGHMS64UBJHUVKV6ETWYOVHADLTBXJNJFUC7A7RU5B3GN";
+    const OTHER: &str = "GBHKUQDYXGK5IEYORI7DZMMXANOIEHHOF364LNT4Q7EWPUL7FOO2SP6D";
+
+    fn slt() -> AssetIdentifier {
+        AssetIdentifier::alphanum4(
+            "SLT",
+            "GCKA6K5PCQ6PNF5RQBF7PQDJWRHO6UOGFMRLK3DYHDOI244V47XKQ4GP",
+        )
+    }
+
+    /// A trade of 10 XLM for SLT in ledger 16027 made by the operation, with
+    /// the trader as the base account, so the trader sold XLM.
+    fn trade(operation: u64, counter_amount: &str) -> Trade {
+        let id = format!("{}-0", (16_027u64 << 32) + operation);
+        let json = include_str!("../fixtures/trade.json")
+            .replace("68836918321750017-0", &id)
+            .replace("\"0.1217566\"", "\"10.0000000\"")
+            .replace("\"0.0199601\"", &format!("\"{}\"", counter_amount));
+        serde_json::from_str(&json).unwrap()
+    }
+
+    /// The same trade with the trader as the counter account, so the
+    /// trader bought XLM.
+    fn bought(operation: u64, counter_amount: &str) -> Trade {
+        let json = serde_json::to_string(&trade(operation, counter_amount))
+            .unwrap()
+            .replace(TRADER, "SWAP")
+            .replace(OTHER, TRADER)
+            .replace("SWAP", OTHER);
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn orderbook(bid: &str, ask: &str) -> Orderbook {
+        let json = include_str!("../fixtures/orderbook.json")
+            .replace("\"7.7200005\"", &format!("\"{}\"", bid))
+            .replace("\"7.7600000\"", &format!("\"{}\"", ask))
+            .replace("FOO", "SLT")
+            .replace(
+                "GBAUUA74H4XOQYRSOW2RZUA4QL5PB37U3JS5NE3RTB2ELJVMIF5RLMAG",
+                "GCKA6K5PCQ6PNF5RQBF7PQDJWRHO6UOGFMRLK3DYHDOI244V47XKQ4GP",
+            );
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn history() -> BookHistory {
+        let mut history = BookHistory::new(AssetIdentifier::native(), slt());
+        history.record(16_020, &orderbook("0.2000000", "0.2100000"));
+        history.record(16_030, &orderbook("0.3000000", "0.3100000"));
+        history
+    }
+
+    #[test]
+    fn it_finds_the_quote_as_of_a_ledger() {
+        let history = history();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.quote_at(16_019), None);
+        assert_eq!(history.quote_at(16_027).unwrap().ledger(), 16_020);
+        assert_eq!(
+            history.quote_at(16_027).unwrap().bid(),
+            Some(Amount::new(2_000_000))
+        );
+        assert_eq!(history.quote_at(20_000).unwrap().ledger(), 16_030);
+    }
+
+    #[test]
+    fn it_ignores_orderbooks_for_other_pairs() {
+        let mut history = BookHistory::new(slt(), AssetIdentifier::native());
+        history.record(16_020, &orderbook("0.2000000", "0.2100000"));
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn it_measures_sells_against_the_bid_and_buys_against_the_ask() {
+        let trades = vec![
+            // Sold at 0.19 against a bid of 0.20, 500 bps worse.
+            trade(1, "1.9000000"),
+            // Sold at 0.21 against a bid of 0.20, 500 bps better.
+            trade(2, "2.1000000"),
+            // Bought at 0.2205 against an ask of 0.21, 500 bps worse.
+            bought(3, "2.2050000"),
+        ];
+        let report = SlippageReport::new(TRADER, &trades, &history());
+        let executions = report.executions();
+        assert_eq!(executions.len(), 3);
+        assert_eq!(executions[0].side(), Side::Sell);
+        assert_eq!(executions[0].ledger(), 16_027);
+        assert_eq!(executions[0].price(), Amount::new(1_900_000));
+        assert_eq!(executions[0].reference(), Amount::new(2_000_000));
+        assert_eq!(executions[0].slippage_bps(), 500.0);
+        assert_eq!(executions[1].slippage_bps(), -500.0);
+        assert_eq!(executions[2].side(), Side::Buy);
+        assert_eq!(executions[2].reference(), Amount::new(2_100_000));
+        assert_eq!(executions[2].slippage_bps(), 500.0);
+
+        assert_eq!(report.unquoted(), 0);
+        assert_eq!(report.improved(), 1);
+        assert_eq!(report.worst_bps(), Some(500.0));
+        assert_eq!(report.median_bps(), Some(500.0));
+        assert!((report.mean_bps().unwrap() - 500.0 / 3.0).abs() < 1e-9);
+        assert!((report.weighted_mean_bps().unwrap() - 500.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn it_reads_trades_of_the_pair_the_other_way_around() {
+        let mut history = BookHistory::new(slt(), AssetIdentifier::native());
+        let json = serde_json::to_string(&orderbook("5.0000000", "5.2000000"))
+            .unwrap()
+            .replace("\"base\"", "\"swap\"")
+            .replace("\"counter\"", "\"base\"")
+            .replace("\"swap\"", "\"counter\"");
+        history.record(16_020, &serde_json::from_str(&json).unwrap());
+
+        // Selling 10 XLM for 2 SLT buys SLT at 5 XLM, about 385 bps under
+        // the ask.
+        let report = SlippageReport::new(TRADER, &[trade(1, "2.0000000")], &history);
+        assert_eq!(report.executions()[0].side(), Side::Buy);
+        assert_eq!(
+            report.executions()[0].base_amount(),
+            Amount::new(20_000_000)
+        );
+        assert_eq!(report.executions()[0].price(), Amount::new(50_000_000));
+        assert!((report.executions()[0].slippage_bps() + 384.615_384_6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn it_counts_trades_it_cannot_measure() {
+        let mut history = BookHistory::new(AssetIdentifier::native(), slt());
+        history.record(16_030, &orderbook("0.3000000", "0.3100000"));
+        let report = SlippageReport::new(TRADER, &[trade(1, "1.9000000")], &history);
+        assert!(report.executions().is_empty());
+        assert_eq!(report.unquoted(), 1);
+        assert_eq!(report.mean_bps(), None);
+        assert_eq!(report.median_bps(), None);
+        assert_eq!(report.worst_bps(), None);
+
+        let report = SlippageReport::new(
+            "GATLAI2D7SSH6PE3HXTDPTRM4RE5VRK6HGA63K5EWP75PSANCZRFDNB5",
+            &[trade(1, "1.9000000")],
+            &history,
+        );
+        assert_eq!(report.unquoted(), 0);
+    }
+}
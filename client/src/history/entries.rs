@@ -0,0 +1,372 @@
+use chrono::prelude::*;
+use resources::Amount;
+use xdr::{self, Decoded, Error, Kind, LedgerEntry, LedgerKey, Reader, TryFromXdr};
+
+type Result<T> = ::std::result::Result<T, Error>;
+
+const UNBOUNDED: usize = u32::MAX as usize;
+
+fn hash(r: &mut Reader) -> Result<String> {
+    Ok(xdr::hex(r.read_fixed(32)?))
+}
+
+fn amount(r: &mut Reader) -> Result<Amount> {
+    match r.read_i64()? {
+        value if value < 0 => Err(Error::invalid_value("amount")),
+        value => Ok(Amount::new(value)),
+    }
+}
+
+fn ext_v0(r: &mut Reader) -> Result<()> {
+    match r.read_i32()? {
+        0 => Ok(()),
+        v => Err(Error::unknown_discriminant("ext", v)),
+    }
+}
+
+/// The header of a closed ledger, which commits to the transactions applied
+/// in it and the state of the ledger after they were applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LedgerHeader {
+    ledger_version: u32,
+    previous_ledger_hash: String,
+    tx_set_hash: String,
+    close_time: DateTime<Utc>,
+    tx_set_result_hash: String,
+    bucket_list_hash: String,
+    ledger_seq: u32,
+    total_coins: Amount,
+    fee_pool: Amount,
+    inflation_seq: u32,
+    id_pool: u64,
+    base_fee: u32,
+    base_reserve: u32,
+    max_tx_set_size: u32,
+}
+
+impl LedgerHeader {
+    /// The protocol version the ledger was closed with.
+    pub fn ledger_version(&self) -> u32 {
+        self.ledger_version
+    }
+
+    /// The hex encoded hash of the previous ledger.
+    pub fn previous_ledger_hash(&self) -> &str {
+        &self.previous_ledger_hash
+    }
+
+    /// The hex encoded hash of the transaction set applied in this ledger.
+    pub fn tx_set_hash(&self) -> &str {
+        &self.tx_set_hash
+    }
+
+    /// When the ledger was closed.
+    pub fn close_time(&self) -> DateTime<Utc> {
+        self.close_time
+    }
+
+    /// The hex encoded hash of the results of applying the transaction set.
+    pub fn tx_set_result_hash(&self) -> &str {
+        &self.tx_set_result_hash
+    }
+
+    /// The hex encoded hash of the bucket list after the ledger closed.
+    pub fn bucket_list_hash(&self) -> &str {
+        &self.bucket_list_hash
+    }
+
+    /// The sequence number of the ledger.
+    pub fn ledger_seq(&self) -> u32 {
+        self.ledger_seq
+    }
+
+    /// The total number of lumens in existence.
+    pub fn total_coins(&self) -> Amount {
+        self.total_coins
+    }
+
+    /// The fees collected and not yet redistributed.
+    pub fn fee_pool(&self) -> Amount {
+        self.fee_pool
+    }
+
+    /// The number of inflation runs that have occurred.
+    pub fn inflation_seq(&self) -> u32 {
+        self.inflation_seq
+    }
+
+    /// The last id used to create an offer or claimable balance.
+    pub fn id_pool(&self) -> u64 {
+        self.id_pool
+    }
+
+    /// The base fee per operation in stroops.
+    pub fn base_fee(&self) -> u32 {
+        self.base_fee
+    }
+
+    /// The base reserve in stroops.
+    pub fn base_reserve(&self) -> u32 {
+        self.base_reserve
+    }
+
+    /// The maximum number of operations allowed in a transaction set.
+    pub fn max_tx_set_size(&self) -> u32 {
+        self.max_tx_set_size
+    }
+}
+
+impl TryFromXdr for LedgerHeader {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        let ledger_version = r.read_u32()?;
+        let previous_ledger_hash = hash(r)?;
+        let tx_set_hash = hash(r)?;
+        let close_time = Utc
+            .timestamp_opt(r.read_u64()? as i64, 0)
+            .single()
+            .ok_or_else(|| Error::invalid_value("closeTime"))?;
+        xdr::array(r, 6, |r| r.read_var(128).map(|_| ()))?;
+        match r.read_i32()? {
+            0 => {}
+            1 => {
+                // The signature of the validator that nominated the value.
+                r.read_i32()?;
+                r.read_fixed(32)?;
+                r.read_var(64)?;
+            }
+            v => return Err(Error::unknown_discriminant("StellarValueType", v)),
+        }
+        let header = LedgerHeader {
+            ledger_version,
+            previous_ledger_hash,
+            tx_set_hash,
+            close_time,
+            tx_set_result_hash: hash(r)?,
+            bucket_list_hash: hash(r)?,
+            ledger_seq: r.read_u32()?,
+            total_coins: amount(r)?,
+            fee_pool: amount(r)?,
+            inflation_seq: r.read_u32()?,
+            id_pool: r.read_u64()?,
+            base_fee: r.read_u32()?,
+            base_reserve: r.read_u32()?,
+            max_tx_set_size: r.read_u32()?,
+        };
+        for _ in 0..4 {
+            hash(r)?;
+        }
+        match r.read_i32()? {
+            0 => {}
+            1 => {
+                r.read_u32()?;
+                ext_v0(r)?;
+            }
+            v => return Err(Error::unknown_discriminant("LedgerHeader", v)),
+        }
+        Ok(header)
+    }
+}
+
+/// A ledger header along with its hash, as stored in the `ledger` category
+/// of a checkpoint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LedgerHeaderHistoryEntry {
+    hash: String,
+    header: LedgerHeader,
+}
+
+impl LedgerHeaderHistoryEntry {
+    /// The hex encoded hash of the ledger header.
+    pub fn hash(&self) -> &str {
+        &self.hash
+    }
+
+    /// The ledger header.
+    pub fn header(&self) -> &LedgerHeader {
+        &self.header
+    }
+}
+
+impl TryFromXdr for LedgerHeaderHistoryEntry {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        let entry = LedgerHeaderHistoryEntry {
+            hash: hash(r)?,
+            header: LedgerHeader::try_from_xdr(r)?,
+        };
+        ext_v0(r)?;
+        Ok(entry)
+    }
+}
+
+fn envelopes(r: &mut Reader) -> Result<Vec<Decoded>> {
+    xdr::array(r, UNBOUNDED, |r| Decoded::read(Kind::TransactionEnvelope, r))
+}
+
+/// The transactions applied in a ledger, as stored in the `transactions`
+/// category of a checkpoint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionHistoryEntry {
+    ledger_seq: u32,
+    previous_ledger_hash: String,
+    envelopes: Vec<Decoded>,
+}
+
+impl TransactionHistoryEntry {
+    /// The sequence of the ledger the transactions were applied in.
+    pub fn ledger_seq(&self) -> u32 {
+        self.ledger_seq
+    }
+
+    /// The hex encoded hash of the ledger before this one.
+    pub fn previous_ledger_hash(&self) -> &str {
+        &self.previous_ledger_hash
+    }
+
+    /// The envelopes of the transactions in the set. The set is ordered by
+    /// hash rather than by application order.
+    pub fn envelopes(&self) -> &[Decoded] {
+        &self.envelopes
+    }
+}
+
+impl TryFromXdr for TransactionHistoryEntry {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        let mut entry = TransactionHistoryEntry {
+            ledger_seq: r.read_u32()?,
+            previous_ledger_hash: hash(r)?,
+            envelopes: envelopes(r)?,
+        };
+        match r.read_i32()? {
+            0 => {}
+            1 => {
+                // Generalized transaction sets split the envelopes into
+                // phases and components, each of which may set a base fee.
+                match r.read_i32()? {
+                    1 => {}
+                    v => return Err(Error::unknown_discriminant("GeneralizedTransactionSet", v)),
+                }
+                entry.previous_ledger_hash = hash(r)?;
+                let phases = xdr::array(r, UNBOUNDED, |r| match r.read_i32()? {
+                    0 => xdr::array(r, UNBOUNDED, |r| match r.read_i32()? {
+                        0 => {
+                            if r.read_optional()? {
+                                r.read_i64()?;
+                            }
+                            envelopes(r)
+                        }
+                        v => Err(Error::unknown_discriminant("TxSetComponentType", v)),
+                    }),
+                    v => Err(Error::unknown_discriminant("TransactionPhase", v)),
+                })?;
+                for component in phases.into_iter().flatten() {
+                    entry.envelopes.extend(component);
+                }
+            }
+            v => return Err(Error::unknown_discriminant("TransactionHistoryEntry", v)),
+        }
+        Ok(entry)
+    }
+}
+
+/// The result of a single transaction along with the hash that identifies it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionResultPair {
+    transaction_hash: String,
+    result: Decoded,
+}
+
+impl TransactionResultPair {
+    /// The hex encoded hash of the transaction.
+    pub fn transaction_hash(&self) -> &str {
+        &self.transaction_hash
+    }
+
+    /// The decoded result of the transaction.
+    pub fn result(&self) -> &Decoded {
+        &self.result
+    }
+}
+
+/// The results of the transactions applied in a ledger, as stored in the
+/// `results` category of a checkpoint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionHistoryResultEntry {
+    ledger_seq: u32,
+    results: Vec<TransactionResultPair>,
+}
+
+impl TransactionHistoryResultEntry {
+    /// The sequence of the ledger the transactions were applied in.
+    pub fn ledger_seq(&self) -> u32 {
+        self.ledger_seq
+    }
+
+    /// The results, in the order the transactions were applied.
+    pub fn results(&self) -> &[TransactionResultPair] {
+        &self.results
+    }
+}
+
+impl TryFromXdr for TransactionHistoryResultEntry {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        let entry = TransactionHistoryResultEntry {
+            ledger_seq: r.read_u32()?,
+            results: xdr::array(r, UNBOUNDED, |r| {
+                Ok(TransactionResultPair {
+                    transaction_hash: hash(r)?,
+                    result: Decoded::read(Kind::TransactionResult, r)?,
+                })
+            })?,
+        };
+        ext_v0(r)?;
+        Ok(entry)
+    }
+}
+
+/// An entry in a bucket file. Buckets are snapshots of the ledger that can
+/// be replayed, from the oldest level to the newest, to rebuild its state.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BucketEntry {
+    /// An entry that is live as of the bucket.
+    Live(LedgerEntry),
+    /// An entry that was created within the bucket's range of ledgers.
+    Init(LedgerEntry),
+    /// An entry that was deleted.
+    Dead(LedgerKey),
+    /// Describes the bucket, holding the protocol version it was written with.
+    Meta(u32),
+}
+
+impl BucketEntry {
+    /// The key of the ledger entry this bucket entry applies to, or none for
+    /// the bucket's metadata.
+    pub fn key(&self) -> Option<LedgerKey> {
+        match *self {
+            BucketEntry::Live(ref entry) | BucketEntry::Init(ref entry) => Some(entry.key()),
+            BucketEntry::Dead(ref key) => Some(key.clone()),
+            BucketEntry::Meta(_) => None,
+        }
+    }
+}
+
+impl TryFromXdr for BucketEntry {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        match r.read_i32()? {
+            -1 => {
+                let version = r.read_u32()?;
+                match r.read_i32()? {
+                    0 => {}
+                    1 => {
+                        r.read_i32()?;
+                    }
+                    v => return Err(Error::unknown_discriminant("BucketMetadata", v)),
+                }
+                Ok(BucketEntry::Meta(version))
+            }
+            0 => LedgerEntry::try_from_xdr(r).map(BucketEntry::Live),
+            1 => LedgerKey::try_from_xdr(r).map(BucketEntry::Dead),
+            2 => LedgerEntry::try_from_xdr(r).map(BucketEntry::Init),
+            v => Err(Error::unknown_discriminant("BucketEntryType", v)),
+        }
+    }
+}
@@ -0,0 +1,341 @@
+//! A reader for stellar history archives.
+//!
+//! Every validator publishes the ledger's history to an archive in checkpoints
+//! of 64 ledgers. Each checkpoint is described by a JSON state file and holds
+//! gzipped XDR files for the ledger headers, transaction sets and results of
+//! its ledgers, along with the buckets that snapshot the ledger itself. Reading
+//! these files allows the full chain to be reprocessed without horizon.
+//!
+//! ```
+//! use stellar_client::history::{self, Category};
+//!
+//! let checkpoint = history::checkpoint_containing(1_000_000);
+//! assert_eq!(checkpoint, 1_000_063);
+//! assert_eq!(
+//!     Category::Ledger.path(checkpoint),
+//!     "ledger/00/0f/42/ledger-000f427f.xdr.gz"
+//! );
+//! ```
+use error::{Error, Result};
+use flate2::read::GzDecoder;
+use http::Uri;
+use reqwest;
+use serde_json;
+use std::io::Read;
+use xdr::{self, Reader, TryFromXdr};
+
+mod entries;
+mod state;
+
+pub use self::entries::{BucketEntry, LedgerHeader, LedgerHeaderHistoryEntry,
+                        TransactionHistoryEntry, TransactionHistoryResultEntry,
+                        TransactionResultPair};
+pub use self::state::{BucketLevel, FutureBucket, HistoryArchiveState};
+
+static SDF_TESTNET_ARCHIVE: &str = "https://history.stellar.org/prd/core-testnet/core_testnet_001";
+static SDF_ARCHIVE: &str = "https://history.stellar.org/prd/core-live/core_live_001";
+
+/// The number of ledgers in each checkpoint.
+pub const CHECKPOINT_FREQUENCY: u32 = 64;
+
+/// Returns the checkpoint that a ledger is published in. Checkpoints are
+/// named after the last ledger they contain.
+pub fn checkpoint_containing(ledger: u32) -> u32 {
+    (ledger / CHECKPOINT_FREQUENCY + 1) * CHECKPOINT_FREQUENCY - 1
+}
+
+/// The kinds of files that are published for each checkpoint.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Category {
+    /// The history archive state of the checkpoint.
+    History,
+    /// The headers of the ledgers in the checkpoint.
+    Ledger,
+    /// The transaction sets of the ledgers in the checkpoint.
+    Transactions,
+    /// The transaction results of the ledgers in the checkpoint.
+    Results,
+}
+
+impl Category {
+    fn name(&self) -> &'static str {
+        match *self {
+            Category::History => "history",
+            Category::Ledger => "ledger",
+            Category::Transactions => "transactions",
+            Category::Results => "results",
+        }
+    }
+
+    /// The path of the category's file for a checkpoint, relative to the
+    /// root of the archive.
+    pub fn path(&self, checkpoint: u32) -> String {
+        let hex = format!("{:08x}", checkpoint);
+        let extension = match *self {
+            Category::History => "json",
+            _ => "xdr.gz",
+        };
+        format!(
+            "{name}/{}/{}/{}/{name}-{}.{}",
+            &hex[0..2],
+            &hex[2..4],
+            &hex[4..6],
+            hex,
+            extension,
+            name = self.name()
+        )
+    }
+}
+
+/// The path of a bucket, relative to the root of the archive. Hashes come
+/// from the archive's state files, so anything but 64 hex digits is refused
+/// rather than trusted to build a path.
+fn bucket_path(hash: &str) -> Result<String> {
+    if hash.len() != 64 || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(Error::BadUri);
+    }
+    Ok(format!(
+        "bucket/{}/{}/{}/bucket-{}.xdr.gz",
+        &hash[0..2],
+        &hash[2..4],
+        &hash[4..6],
+        hash
+    ))
+}
+
+/// Decompresses a file fetched in full, so that a failure here is the file's
+/// fault rather than the connection's.
+fn gunzip(compressed: &[u8]) -> ::std::result::Result<Vec<u8>, xdr::Error> {
+    let mut bytes = Vec::new();
+    GzDecoder::new(compressed)
+        .read_to_end(&mut bytes)
+        .map_err(xdr::Error::gzip)?;
+    Ok(bytes)
+}
+
+/// Splits a file into its XDR records. Each record is prefixed with its
+/// length, with the high bit set to mark the last fragment of the record.
+fn records<T: TryFromXdr>(bytes: &[u8]) -> ::std::result::Result<Vec<T>, xdr::Error> {
+    let mut reader = Reader::new(bytes);
+    let mut values = Vec::new();
+    while !reader.is_empty() {
+        let len = (reader.read_u32()? & 0x7fff_ffff) as usize;
+        let mut record = Reader::new(reader.read_fixed(len)?);
+        values.push(T::try_from_xdr(&mut record)?);
+        record.finish()?;
+    }
+    Ok(values)
+}
+
+/// A client for a single history archive. Archives are plain file servers so
+/// the files are fetched with a blocking client, much like the synchronous
+/// horizon client.
+#[derive(Debug, Clone)]
+pub struct HistoryArchive {
+    inner: reqwest::Client,
+    root: String,
+}
+
+impl HistoryArchive {
+    /// Constructs a reader for the archive at the given root url.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use stellar_client::history::HistoryArchive;
+    /// let archive = HistoryArchive::new("https://history.stellar.org/prd/core-live/core_live_002").unwrap();
+    /// ```
+    pub fn new(uri: &str) -> Result<Self> {
+        // Ensure that the uri passed in can parse.
+        let _: Uri = uri.parse()?;
+        Ok(Self::build(uri.trim_right_matches('/')))
+    }
+
+    fn build(root: &str) -> Self {
+        HistoryArchive {
+            inner: reqwest::Client::new(),
+            root: root.to_string(),
+        }
+    }
+
+    /// Constructs a reader for one of SDF's test network archives.
+    pub fn sdf_testnet() -> Self {
+        Self::build(SDF_TESTNET_ARCHIVE)
+    }
+
+    /// Constructs a reader for one of SDF's public network archives.
+    pub fn sdf() -> Self {
+        Self::build(SDF_ARCHIVE)
+    }
+
+    /// The root url of the archive.
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
+    fn fetch(&self, path: &str) -> Result<reqwest::Response> {
+        let response = self.inner.get(&format!("{}/{}", self.root, path)).send()?;
+        if response.status().is_success() {
+            Ok(response)
+        } else {
+            Err(Error::ServerError)
+        }
+    }
+
+    fn fetch_records<T: TryFromXdr>(&self, path: &str) -> Result<Vec<T>> {
+        let mut compressed = Vec::new();
+        self.fetch(path)?.read_to_end(&mut compressed)?;
+        Ok(records(&gunzip(&compressed)?)?)
+    }
+
+    /// Fetches the state of the most recently published checkpoint.
+    pub fn current_state(&self) -> Result<HistoryArchiveState> {
+        let response = self.fetch(".well-known/stellar-history.json")?;
+        Ok(serde_json::from_reader(response)?)
+    }
+
+    /// Fetches the state of a checkpoint.
+    pub fn state(&self, checkpoint: u32) -> Result<HistoryArchiveState> {
+        let response = self.fetch(&Category::History.path(checkpoint))?;
+        Ok(serde_json::from_reader(response)?)
+    }
+
+    /// Fetches the ledger headers of a checkpoint.
+    pub fn ledger_headers(&self, checkpoint: u32) -> Result<Vec<LedgerHeaderHistoryEntry>> {
+        self.fetch_records(&Category::Ledger.path(checkpoint))
+    }
+
+    /// Fetches the transaction sets of a checkpoint. Ledgers without any
+    /// transactions are omitted.
+    pub fn transactions(&self, checkpoint: u32) -> Result<Vec<TransactionHistoryEntry>> {
+        self.fetch_records(&Category::Transactions.path(checkpoint))
+    }
+
+    /// Fetches the transaction results of a checkpoint. Ledgers without any
+    /// transactions are omitted.
+    pub fn results(&self, checkpoint: u32) -> Result<Vec<TransactionHistoryResultEntry>> {
+        self.fetch_records(&Category::Results.path(checkpoint))
+    }
+
+    /// Fetches the entries of a bucket by its hex encoded hash.
+    pub fn bucket(&self, hash: &str) -> Result<Vec<BucketEntry>> {
+        self.fetch_records(&bucket_path(hash)?)
+    }
+}
+
+#[cfg(test)]
+mod history_tests {
+    use super::*;
+    use base64;
+    use xdr::LedgerEntryData;
+
+    // Headers for ledgers 2 and 3 of a made up network, chained by hash.
+    const LEDGER_HEADERS: &str = "gAABaL8FLA0SPtYoVQ6hDwBS/LBAMeS5e0iQCX9y8s9DxYxxAAAACQAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAEREREREREREREREREREREREREREREREREREREREREREAAAAAWWgvCgAAAAAAAAAAIiIiIiIiIiIiIiIiIiIiIiIiIiIiIiIiIiIiIiIiIiIzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMwAAAAIN4Lazp2QAAAAAAAAAAABkAAAAAAAAAAAAAAAAAAAAZAX14QAAAABkAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAIAAAWhmmUdUvFcubj8zvrYHiU0ilKQs3lOQdEuCDTTpBbygvwAAAAm/BSwNEj7WKFUOoQ8AUvywQDHkuXtIkAl/cvLPQ8WMcRERERERERERERERERERERERERERERERERERERERERERAAAAAFloLw8AAAAAAAAAACIiIiIiIiIiIiIiIiIiIiIiIiIiIiIiIiIiIiIiIiIiMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMAAAADDeC2s6dkAAAAAAAAAAAAZAAAAAAAAAAAAAAAAAAAAGQF9eEAAAAAZAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=";
+
+    // A bucket holding its metadata, a live account and a deleted account.
+    const BUCKET: &str = "gAAADP////8AAAAJAAAAAIAAAGAAAAAAAAAABwAAAAAAAAAAfYea3UlYx+oB1SUN7JS071XzM+onRosbwqtnJe/vxAYAAAACVAvkAAAAAAEAAAAAAAAAAAAAAAAAAAAAAAAAAAEAAAAAAAAAAAAAAAAAAACAAAAsAAAAAQAAAAAAAAAAfYea3UlYx+oB1SUN7JS071XzM+onRosbwqtnJe/vxAY=";
+
+    #[test]
+    fn it_finds_the_checkpoint_of_a_ledger() {
+        assert_eq!(checkpoint_containing(0), 63);
+        assert_eq!(checkpoint_containing(63), 63);
+        assert_eq!(checkpoint_containing(64), 127);
+        assert_eq!(checkpoint_containing(1_000_000), 1_000_063);
+    }
+
+    #[test]
+    fn it_builds_checkpoint_paths() {
+        assert_eq!(
+            Category::History.path(63),
+            "history/00/00/00/history-0000003f.json"
+        );
+        assert_eq!(
+            Category::Results.path(0x00ab_cdef),
+            "results/00/ab/cd/results-00abcdef.xdr.gz"
+        );
+        let hash = "a1b2c3d4e5f6a7b8c9d0a1b2c3d4e5f6a7b8c9d0a1b2c3d4e5f6a7b8c9d0a1b2";
+        assert_eq!(
+            bucket_path(hash).unwrap(),
+            format!("bucket/a1/b2/c3/bucket-{}.xdr.gz", hash)
+        );
+    }
+
+    #[test]
+    fn it_refuses_bucket_hashes_that_are_not_64_hex_digits() {
+        assert!(bucket_path("a1b2c3").is_err());
+        assert!(bucket_path(&"g".repeat(64)).is_err());
+        assert!(bucket_path(&"é".repeat(32)).is_err());
+        assert!(HistoryArchive::sdf().bucket("a").is_err());
+    }
+
+    #[test]
+    fn it_reports_corrupt_files_as_decode_errors() {
+        let err = gunzip(b"not gzip").unwrap_err();
+        match *err.kind() {
+            xdr::ErrorKind::Gzip(_) => {}
+            ref kind => panic!("Expected a gzip error, got {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn it_errors_on_close_times_out_of_range() {
+        let mut bytes = base64::decode(LEDGER_HEADERS).unwrap();
+        // The close time follows the record mark, the header hash, the
+        // version and the two hashes.
+        let at = 4 + 32 + 4 + 32 + 32;
+        bytes[at..at + 8].copy_from_slice(&[0xff; 8]);
+        bytes[at] = 0x7f;
+        assert!(records::<LedgerHeaderHistoryEntry>(&bytes).is_err());
+    }
+
+    #[test]
+    fn it_reads_ledger_header_records() {
+        let bytes = base64::decode(LEDGER_HEADERS).unwrap();
+        let headers: Vec<LedgerHeaderHistoryEntry> = records(&bytes).unwrap();
+        assert_eq!(headers.len(), 2);
+
+        let first = &headers[0];
+        assert_eq!(
+            first.hash(),
+            "bf052c0d123ed628550ea10f0052fcb04031e4b97b4890097f72f2cf43c58c71"
+        );
+        assert_eq!(first.header().ledger_seq(), 2);
+        assert_eq!(first.header().ledger_version(), 9);
+        assert_eq!(first.header().base_reserve(), 100_000_000);
+        assert_eq!(first.header().close_time().timestamp(), 1_500_000_010);
+        assert_eq!(headers[1].header().previous_ledger_hash(), first.hash());
+    }
+
+    #[test]
+    fn it_reads_bucket_records() {
+        let bytes = base64::decode(BUCKET).unwrap();
+        let entries: Vec<BucketEntry> = records(&bytes).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0], BucketEntry::Meta(9));
+        match entries[1] {
+            BucketEntry::Live(ref entry) => match *entry.data() {
+                LedgerEntryData::Account(ref account) => {
+                    assert_eq!(account.balance().to_string(), "1000.0000000")
+                }
+                _ => panic!("Expected an account entry"),
+            },
+            _ => panic!("Expected a live entry"),
+        }
+        match entries[2] {
+            BucketEntry::Dead(ref key) => assert_eq!(Some(key.clone()), entries[1].key()),
+            _ => panic!("Expected a dead entry"),
+        }
+    }
+
+    #[test]
+    fn it_errors_on_truncated_records() {
+        let bytes = base64::decode(BUCKET).unwrap();
+        assert!(records::<BucketEntry>(&bytes[..20]).is_err());
+    }
+
+    #[test]
+    fn it_strips_trailing_slashes_from_the_root() {
+        let archive = HistoryArchive::new("https://example.com/archive/").unwrap();
+        assert_eq!(archive.root(), "https://example.com/archive");
+    }
+}
@@ -0,0 +1,140 @@
+/// The history archive state, or HAS, is the JSON file that describes a
+/// checkpoint. It names the last ledger in the checkpoint and the buckets that
+/// make up the bucket list as of that ledger.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryArchiveState {
+    version: u32,
+    server: String,
+    current_ledger: u32,
+    network_passphrase: Option<String>,
+    current_buckets: Vec<BucketLevel>,
+}
+
+impl HistoryArchiveState {
+    /// The version of the HAS format.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// The version string of the stellar-core that published the checkpoint.
+    pub fn server(&self) -> &str {
+        &self.server
+    }
+
+    /// The last ledger contained in the checkpoint.
+    pub fn current_ledger(&self) -> u32 {
+        self.current_ledger
+    }
+
+    /// The passphrase of the network, if the archive publishes it.
+    pub fn network_passphrase(&self) -> Option<&str> {
+        self.network_passphrase.as_ref().map(|s| &**s)
+    }
+
+    /// The levels of the bucket list, from the most to the least recent.
+    pub fn current_buckets(&self) -> &[BucketLevel] {
+        &self.current_buckets
+    }
+
+    /// The hashes of every non-empty bucket referenced by this state. These
+    /// are the buckets that need to be fetched to rebuild the ledger.
+    pub fn bucket_hashes(&self) -> Vec<&str> {
+        let mut hashes = Vec::new();
+        for level in &self.current_buckets {
+            for hash in &[&level.curr, &level.snap] {
+                if !is_empty_bucket(hash) && !hashes.contains(&hash.as_str()) {
+                    hashes.push(hash.as_str());
+                }
+            }
+        }
+        hashes
+    }
+}
+
+/// A single level of the bucket list.
+#[derive(Debug, Deserialize, Clone)]
+pub struct BucketLevel {
+    curr: String,
+    snap: String,
+    next: FutureBucket,
+}
+
+impl BucketLevel {
+    /// The hash of the bucket currently accumulating entries.
+    pub fn curr(&self) -> &str {
+        &self.curr
+    }
+
+    /// The hash of the bucket that was last snapshotted at this level.
+    pub fn snap(&self) -> &str {
+        &self.snap
+    }
+
+    /// The merge in progress for this level.
+    pub fn next(&self) -> &FutureBucket {
+        &self.next
+    }
+}
+
+/// A bucket merge that was in progress when the checkpoint was published.
+#[derive(Debug, Deserialize, Clone)]
+pub struct FutureBucket {
+    state: u32,
+    output: Option<String>,
+}
+
+impl FutureBucket {
+    /// The state of the merge. Zero means there is no merge in progress.
+    pub fn state(&self) -> u32 {
+        self.state
+    }
+
+    /// The hash of the merged bucket if the merge has completed.
+    pub fn output(&self) -> Option<&str> {
+        self.output.as_ref().map(|s| &**s)
+    }
+}
+
+fn is_empty_bucket(hash: &str) -> bool {
+    hash.chars().all(|c| c == '0')
+}
+
+#[cfg(test)]
+mod state_tests {
+    use super::*;
+    use serde_json;
+
+    fn state() -> HistoryArchiveState {
+        let json = include_str!("../../fixtures/history/stellar-history.json");
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn it_parses_a_history_archive_state() {
+        let state = state();
+        assert_eq!(state.version(), 1);
+        assert_eq!(state.current_ledger(), 1_048_575);
+        assert_eq!(
+            state.network_passphrase(),
+            Some("Test SDF Network ; September 2015")
+        );
+        assert_eq!(state.current_buckets().len(), 3);
+        assert_eq!(state.current_buckets()[0].next().state(), 0);
+        assert_eq!(state.current_buckets()[1].next().output(), Some(
+            "3cb0f58c6c1ff5b5d4f3cb6bd5b42b9e2b0c3f1c4c9b3c7c54e5cf2d2a5d2d8e"
+        ));
+    }
+
+    #[test]
+    fn it_lists_the_non_empty_buckets() {
+        assert_eq!(
+            state().bucket_hashes(),
+            vec![
+                "0f5ff0a4b3e4f1d4a0c2b97d5f1f4a5b8f9b4d0e6a1c3b2a5d4e7f8091a2b3c4",
+                "5c2a3d4e1f0b9a8c7d6e5f4a3b2c1d0e9f8a7b6c5d4e3f2a1b0c9d8e7f6a5b4c",
+                "a1b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f90",
+            ]
+        );
+    }
+}
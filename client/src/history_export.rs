@@ -0,0 +1,596 @@
+//! Exports the balance changes of an account over a range of time, which is
+//! what tax and accounting tools need from the network.
+//!
+//! A `HistoryExport` turns an account's effects and transactions into a
+//! flat list of entries, each one a change to the balance of one asset:
+//! credits and debits such as payments, both sides of trades, and the fees
+//! paid for the account's transactions. Entries are kept in the order they
+//! happened and can be written out as CSV or json.
+//!
+//! ```no_run
+//! # extern crate chrono;
+//! # extern crate stellar_client;
+//! use chrono::{TimeZone, Utc};
+//! use stellar_client::history_export::HistoryExport;
+//! use stellar_client::sync::Client;
+//!
+//! # fn main() {
+//! let client = Client::horizon().unwrap();
+//! let export = HistoryExport::fetch(
+//!     &client,
+//!     "GATLAI2D7SSH6PE3HXTDPTRM4RE5VRK6HGA63K5EWP75PSANCZRFDNB5",
+//!     Utc.ymd(2018, 1, 1).and_hms(0, 0, 0),
+//!     Utc.ymd(2019, 1, 1).and_hms(0, 0, 0),
+//! ).unwrap();
+//! export.write_csv(std::io::stdout()).unwrap();
+//! # }
+//! ```
+use chrono::{DateTime, Utc};
+use client::sync::{Client, Iter, LedgerClock};
+use endpoint::{account, ledger_cursor, Cursor, Direction, Order};
+use error::Result;
+use resources::effect::{self, EffectKind};
+use resources::{AssetIdentifier, Effect, SignedAmount, Transaction};
+use serde_json;
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+/// What changed an account's balance.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    /// Funds came in, such as from a payment or the account being created.
+    Credit,
+    /// Funds went out, such as for a payment or an account merge.
+    Debit,
+    /// The fee paid for a transaction the account submitted.
+    Fee,
+    /// One side of a trade, negative for the asset sold and positive for the
+    /// asset bought.
+    Trade,
+}
+
+impl EntryKind {
+    /// The name of the kind, as written to exports.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            EntryKind::Credit => "credit",
+            EntryKind::Debit => "debit",
+            EntryKind::Fee => "fee",
+            EntryKind::Trade => "trade",
+        }
+    }
+}
+
+/// A change to the balance of one asset.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct Entry {
+    id: String,
+    ledger: u32,
+    closed_at: DateTime<Utc>,
+    transaction: Option<String>,
+    kind: EntryKind,
+    asset: AssetIdentifier,
+    amount: SignedAmount,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    balance: Option<SignedAmount>,
+    #[serde(skip)]
+    order: (u64, u32),
+}
+
+impl Entry {
+    /// The paging token of the effect or transaction the entry came from.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The sequence of the ledger the change happened in.
+    pub fn ledger(&self) -> u32 {
+        self.ledger
+    }
+
+    /// When the ledger closed.
+    pub fn closed_at(&self) -> DateTime<Utc> {
+        self.closed_at
+    }
+
+    /// The hash of the transaction behind the change, when it is known.
+    pub fn transaction(&self) -> Option<&str> {
+        self.transaction.as_ref().map(String::as_str)
+    }
+
+    /// What changed the balance.
+    pub fn kind(&self) -> EntryKind {
+        self.kind
+    }
+
+    /// The asset whose balance changed.
+    pub fn asset(&self) -> &AssetIdentifier {
+        &self.asset
+    }
+
+    /// How much the balance changed by, negative when it went down.
+    pub fn amount(&self) -> SignedAmount {
+        self.amount
+    }
+
+    /// The balance of the asset after the change, once the export knows the
+    /// balances it opened with.
+    pub fn balance(&self) -> Option<SignedAmount> {
+        self.balance
+    }
+}
+
+/// The balance changes of an account, in the order they happened.
+#[derive(Debug, Clone)]
+pub struct HistoryExport {
+    account_id: String,
+    entries: Vec<Entry>,
+    opening_balances: Option<HashMap<AssetIdentifier, SignedAmount>>,
+}
+
+impl HistoryExport {
+    /// Starts an empty export for the given account.
+    pub fn new(account_id: &str) -> HistoryExport {
+        HistoryExport {
+            account_id: account_id.to_string(),
+            entries: Vec::new(),
+            opening_balances: None,
+        }
+    }
+
+    /// Sets the balances the account held before the first entry, which
+    /// gives every entry the running balance of its asset. Assets that
+    /// aren't given open at zero.
+    pub fn with_opening_balances(
+        mut self,
+        balances: HashMap<AssetIdentifier, SignedAmount>,
+    ) -> HistoryExport {
+        self.opening_balances = Some(balances);
+        self.fill_balances();
+        self
+    }
+
+    /// The balances the account held before the first entry, given the
+    /// balances it held after the last one, such as the balances of the
+    /// account right now when the export runs up to now.
+    pub fn balances_before(
+        &self,
+        closing: &HashMap<AssetIdentifier, SignedAmount>,
+    ) -> HashMap<AssetIdentifier, SignedAmount> {
+        let mut balances = closing.clone();
+        for entry in &self.entries {
+            let balance = balances
+                .entry(entry.asset.clone())
+                .or_insert_with(|| SignedAmount::new(0));
+            *balance = &*balance - &entry.amount;
+        }
+        balances
+    }
+
+    /// Fetches the balance changes of an account in ledgers that closed at
+    /// or after `start` and before `end`. This walks every transaction and
+    /// effect of the account in that range, so long ranges of busy accounts
+    /// take many requests.
+    pub fn fetch(
+        client: &Client,
+        account_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<HistoryExport> {
+        let mut export = HistoryExport::new(account_id);
+        let clock = LedgerClock::new(client.clone());
+        let ledgers = clock.sequences_between(start, end)?;
+        if ledgers.start >= ledgers.end {
+            return Ok(export);
+        }
+        let cursor = ledger_cursor(ledgers.start);
+
+        // Effects carry no close time or transaction hash, so both are taken
+        // from the account's transactions where they can be.
+        let mut transactions = HashMap::new();
+        let endpoint = account::Transactions::new(account_id)
+            .with_cursor(&cursor)
+            .with_order(Direction::Asc);
+        for transaction in Iter::new(client, endpoint) {
+            let transaction = transaction?;
+            if transaction.ledger() >= ledgers.end {
+                break;
+            }
+            export.add_transaction(&transaction);
+            if let Some(toid) = toid(transaction.paging_token()) {
+                transactions.insert(toid, transaction);
+            }
+        }
+
+        let endpoint = account::Effects::new(account_id)
+            .with_cursor(&cursor)
+            .with_order(Direction::Asc);
+        for effect in Iter::new(client, endpoint) {
+            let effect = effect?;
+            let operation = match toid(effect.paging_token()) {
+                Some(operation) => operation,
+                None => continue,
+            };
+            let ledger = (operation >> 32) as u32;
+            if ledger >= ledgers.end {
+                break;
+            }
+            if movements(&export.account_id, &effect).is_empty() {
+                continue;
+            }
+            match transactions.get(&(operation & !0xfff)) {
+                Some(transaction) => {
+                    export.add_effect(&effect, transaction.created_at(), Some(transaction.hash()))
+                }
+                None => export.add_effect(&effect, clock.close_time(ledger)?, None),
+            }
+        }
+        Ok(export)
+    }
+
+    /// The account the export is for.
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    /// The balance changes, in the order they happened.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Adds the fee of a transaction, if the account paid for it. The
+    /// operations of the transaction are added from its effects.
+    pub fn add_transaction(&mut self, transaction: &Transaction) {
+        if *transaction.source_account() != self.account_id {
+            return;
+        }
+        let id = transaction.paging_token();
+        self.insert(Entry {
+            id: id.clone(),
+            ledger: transaction.ledger(),
+            closed_at: transaction.created_at(),
+            transaction: Some(transaction.hash().clone()),
+            kind: EntryKind::Fee,
+            asset: AssetIdentifier::Native,
+            amount: SignedAmount::new(-transaction.fee_paid()),
+            balance: None,
+            order: (toid(id).unwrap_or(0), 0),
+        });
+    }
+
+    /// Adds the balance changes of an effect that closed at the given time,
+    /// in the transaction with the given hash. Effects that don't change the
+    /// account's balances, such as signer updates, are left out.
+    pub fn add_effect(
+        &mut self,
+        effect: &Effect,
+        closed_at: DateTime<Utc>,
+        transaction: Option<&str>,
+    ) {
+        let id = effect.paging_token();
+        let operation = toid(id).unwrap_or(0);
+        let index = id
+            .split_once('-')
+            .and_then(|(_, index)| index.parse().ok())
+            .unwrap_or(0);
+        for (kind, asset, amount) in movements(&self.account_id, effect) {
+            self.insert(Entry {
+                id: id.clone(),
+                ledger: (operation >> 32) as u32,
+                closed_at,
+                transaction: transaction.map(str::to_string),
+                kind,
+                asset,
+                amount,
+                balance: None,
+                order: (operation, index),
+            });
+        }
+    }
+
+    /// Writes the entries as CSV with a header row. Assets are written as
+    /// `native` or `CODE:ISSUER`. When the opening balances are known, each
+    /// row ends with the running balance of its asset.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write!(writer, "id,ledger,closed_at,transaction,kind,asset,amount")?;
+        if self.opening_balances.is_some() {
+            write!(writer, ",balance")?;
+        }
+        writeln!(writer)?;
+        for entry in &self.entries {
+            write!(
+                writer,
+                "{},{},{},{},{},{},{}",
+                entry.id,
+                entry.ledger,
+                entry.closed_at.to_rfc3339(),
+                entry.transaction().unwrap_or(""),
+                entry.kind.as_str(),
+                asset_name(&entry.asset),
+                entry.amount
+            )?;
+            if let Some(balance) = entry.balance {
+                write!(writer, ",{}", balance)?;
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+
+    /// The entries as a json array.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&self.entries)
+    }
+
+    /// Keeps the entries in the order they happened, whatever order they
+    /// were added in. Entries from the same effect keep the order they were
+    /// added.
+    fn insert(&mut self, entry: Entry) {
+        let index = self
+            .entries
+            .iter()
+            .rposition(|existing| existing.order <= entry.order)
+            .map_or(0, |index| index + 1);
+        self.entries.insert(index, entry);
+        if self.opening_balances.is_some() {
+            self.fill_balances();
+        }
+    }
+
+    /// Works out the running balance of each entry from the opening
+    /// balances.
+    fn fill_balances(&mut self) {
+        let mut balances = match self.opening_balances {
+            Some(ref balances) => balances.clone(),
+            None => return,
+        };
+        for entry in &mut self.entries {
+            let balance = balances
+                .entry(entry.asset.clone())
+                .or_insert_with(|| SignedAmount::new(0));
+            *balance = &*balance + &entry.amount;
+            entry.balance = Some(*balance);
+        }
+    }
+}
+
+/// The balance changes an effect makes to the account.
+pub(crate) fn movements(
+    account_id: &str,
+    effect: &Effect,
+) -> Vec<(EntryKind, AssetIdentifier, SignedAmount)> {
+    match *effect.kind() {
+        EffectKind::Account(effect::account::Kind::Created(ref created))
+            if created.account() == account_id =>
+        {
+            vec![(
+                EntryKind::Credit,
+                AssetIdentifier::Native,
+                created.starting_balance().into(),
+            )]
+        }
+        EffectKind::Account(effect::account::Kind::Credited(ref credited))
+            if credited.account() == account_id =>
+        {
+            vec![(
+                EntryKind::Credit,
+                credited.asset().clone(),
+                credited.amount().into(),
+            )]
+        }
+        EffectKind::Account(effect::account::Kind::Debited(ref debited))
+            if debited.account() == account_id =>
+        {
+            vec![(
+                EntryKind::Debit,
+                debited.asset().clone(),
+                negate(debited.amount().into()),
+            )]
+        }
+        EffectKind::Trade(effect::trade::Kind::Trade(ref trade))
+            if trade.account() == account_id =>
+        {
+            vec![
+                (
+                    EntryKind::Trade,
+                    trade.sold_asset().clone(),
+                    negate(trade.sold_amount().into()),
+                ),
+                (
+                    EntryKind::Trade,
+                    trade.bought_asset().clone(),
+                    trade.bought_amount().into(),
+                ),
+            ]
+        }
+        _ => Vec::new(),
+    }
+}
+
+pub(crate) fn negate(amount: SignedAmount) -> SignedAmount {
+    &SignedAmount::new(0) - &amount
+}
+
+/// The total order id of an operation or transaction, which is the paging
+/// token of a transaction and the first part of an effect's paging token.
+fn toid(paging_token: &str) -> Option<u64> {
+    paging_token.split('-').next()?.parse().ok()
+}
+
+fn asset_name(asset: &AssetIdentifier) -> String {
+    match *asset {
+        AssetIdentifier::Native => "native".to_string(),
+        _ => format!("{}:{}", asset.code(), asset.issuer()),
+    }
+}
+
+#[cfg(test)]
+mod history_export_tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const ACCOUNT: &str = "GA6U5X6WOPNKKDKQULBR7IDHDBAQKOWPHYEC7WSXHZBFEYFD3XVZAKOO";
+
+    fn effect(json: &str, account: &str, paging_token: &str) -> Effect {
+        let json = json
+            .replace(
+                "GDLGTRIBFH24364GPWPUS45GUFC2GU4ARPGWTXVCPLGTUHX3IOS3ON47",
+                account,
+            )
+            .replace("13563506724865-1", paging_token);
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn credited(paging_token: &str) -> Effect {
+        effect(
+            include_str!("../fixtures/effects/account_credited.json"),
+            ACCOUNT,
+            paging_token,
+        )
+    }
+
+    fn trade() -> Effect {
+        serde_json::from_str(include_str!("../fixtures/effects/trade.json")).unwrap()
+    }
+
+    fn closed_at() -> DateTime<Utc> {
+        Utc.ymd(2018, 2, 2).and_hms(0, 20, 10)
+    }
+
+    #[test]
+    fn it_records_both_sides_of_a_trade() {
+        let mut export = HistoryExport::new(ACCOUNT);
+        export.add_effect(&trade(), closed_at(), None);
+        let entries = export.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].kind(), EntryKind::Trade);
+        assert_eq!(entries[0].asset().code(), "EUR");
+        assert_eq!(entries[0].amount(), SignedAmount::new(-10_000_000_000));
+        assert_eq!(entries[1].asset().code(), "TESTTEST");
+        assert_eq!(entries[1].amount(), SignedAmount::new(600_000_000));
+        assert_eq!(entries[1].ledger(), 7867);
+    }
+
+    #[test]
+    fn it_leaves_out_other_accounts_and_other_effects() {
+        let mut export = HistoryExport::new(ACCOUNT);
+        let other = effect(
+            include_str!("../fixtures/effects/account_credited.json"),
+            "GDLGTRIBFH24364GPWPUS45GUFC2GU4ARPGWTXVCPLGTUHX3IOS3ON47",
+            "13563506724865-1",
+        );
+        export.add_effect(&other, closed_at(), None);
+        let signer: Effect =
+            serde_json::from_str(include_str!("../fixtures/effects/signer_created.json")).unwrap();
+        export.add_effect(&signer, closed_at(), None);
+        assert!(export.entries().is_empty());
+    }
+
+    #[test]
+    fn it_records_fees_of_transactions_the_account_sent() {
+        let mut export = HistoryExport::new(ACCOUNT);
+        let sent = Transaction::new("abc", ACCOUNT, 1)
+            .with_paging_token("8589938688")
+            .with_ledger(2, closed_at())
+            .with_fee_paid(200);
+        export.add_transaction(&sent);
+        let received = Transaction::new(
+            "def",
+            "GDLGTRIBFH24364GPWPUS45GUFC2GU4ARPGWTXVCPLGTUHX3IOS3ON47",
+            1,
+        )
+        .with_paging_token("8589942784");
+        export.add_transaction(&received);
+
+        assert_eq!(export.entries().len(), 1);
+        let fee = &export.entries()[0];
+        assert_eq!(fee.kind(), EntryKind::Fee);
+        assert_eq!(fee.asset(), &AssetIdentifier::Native);
+        assert_eq!(fee.amount(), SignedAmount::new(-200));
+        assert_eq!(fee.transaction(), Some("abc"));
+    }
+
+    #[test]
+    fn it_orders_entries_as_they_happened() {
+        let mut export = HistoryExport::new(ACCOUNT);
+        export.add_effect(&credited("8589938689-2"), closed_at(), Some("abc"));
+        export.add_effect(&credited("8589938689-1"), closed_at(), Some("abc"));
+        // The fee comes before the operations of its transaction.
+        export.add_transaction(
+            &Transaction::new("abc", ACCOUNT, 1)
+                .with_paging_token("8589938688")
+                .with_ledger(2, closed_at())
+                .with_fee_paid(100),
+        );
+        let ids: Vec<&str> = export.entries().iter().map(Entry::id).collect();
+        assert_eq!(ids, vec!["8589938688", "8589938689-1", "8589938689-2"]);
+    }
+
+    #[test]
+    fn it_writes_csv() {
+        let mut export = HistoryExport::new(ACCOUNT);
+        export.add_effect(&credited("8589938689-1"), closed_at(), Some("abc"));
+        export.add_effect(&trade(), closed_at(), None);
+        let mut csv = Vec::new();
+        export.write_csv(&mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "id,ledger,closed_at,transaction,kind,asset,amount\n\
+             8589938689-1,2,2018-02-02T00:20:10+00:00,abc,credit,native,1000.0000000\n\
+             33788507721730-2,7867,2018-02-02T00:20:10+00:00,,trade,\
+             EUR:GCWVFBJ24754I5GXG4JOEB72GJCL3MKWC7VAEYWKGQHPVH3ENPNBSKWS,-1000.0000000\n\
+             33788507721730-2,7867,2018-02-02T00:20:10+00:00,,trade,\
+             TESTTEST:GAHXPUDP3AK6F2QQM4FIRBGPNGKLRDDSTQCVKEXXKKRHJZUUQ23D5BU7,60.0000000\n"
+        );
+    }
+
+    #[test]
+    fn it_keeps_running_balances() {
+        let mut export = HistoryExport::new(ACCOUNT);
+        export.add_effect(&credited("8589938689-1"), closed_at(), Some("abc"));
+        export.add_effect(&credited("8589938690-1"), closed_at(), Some("def"));
+        let mut closing = HashMap::new();
+        closing.insert(AssetIdentifier::Native, SignedAmount::new(25_000_000_000));
+        let opening = export.balances_before(&closing);
+        assert_eq!(
+            opening[&AssetIdentifier::Native],
+            SignedAmount::new(5_000_000_000)
+        );
+
+        let mut export = export.with_opening_balances(opening);
+        export.add_transaction(
+            &Transaction::new("abc", ACCOUNT, 1)
+                .with_paging_token("8589938688")
+                .with_ledger(2, closed_at())
+                .with_fee_paid(100),
+        );
+        let balances: Vec<_> = export.entries().iter().map(Entry::balance).collect();
+        assert_eq!(
+            balances,
+            vec![
+                Some(SignedAmount::new(4_999_999_900)),
+                Some(SignedAmount::new(14_999_999_900)),
+                Some(SignedAmount::new(24_999_999_900)),
+            ]
+        );
+
+        let mut csv = Vec::new();
+        export.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert!(csv.starts_with("id,ledger,closed_at,transaction,kind,asset,amount,balance\n"));
+        assert!(csv.ends_with(",credit,native,1000.0000000,2499.9999900\n"));
+    }
+
+    #[test]
+    fn it_writes_json() {
+        let mut export = HistoryExport::new(ACCOUNT);
+        export.add_effect(&credited("8589938689-1"), closed_at(), Some("abc"));
+        let json: serde_json::Value = serde_json::from_str(&export.to_json().unwrap()).unwrap();
+        assert_eq!(json[0]["kind"], "credit");
+        assert_eq!(json[0]["amount"], "1000.0000000");
+        assert_eq!(json[0]["asset"]["asset_type"], "native");
+        assert_eq!(json[0]["transaction"], "abc");
+        assert_eq!(json[0]["ledger"], 2);
+    }
+}
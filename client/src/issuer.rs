@@ -0,0 +1,415 @@
+//! Looks up how issuers describe their assets, so wallets can show an asset
+//! by its name and image rather than its code and issuer alone.
+//!
+//! Issuers point their account's home domain at the website hosting their
+//! `stellar.toml`, which lists the assets they issue under `CURRENCIES`. See
+//! [SEP-1](https://github.com/stellar/stellar-protocol/blob/master/ecosystem/sep-0001.md).
+//!
+//! ```no_run
+//! use stellar_client::issuer;
+//! use stellar_client::resources::AssetIdentifier;
+//! use stellar_client::sync::Client;
+//!
+//! let client = Client::horizon().unwrap();
+//! let usd = AssetIdentifier::alphanum4(
+//!     "USD",
+//!     "GDUKMGUGDZQK6YHYA5Z6AY2G4XDSZPSZ3SW5UN3ARVMO6QSRDWP5YLEX",
+//! );
+//! if let Some(info) = issuer::issuer_info(&client, &usd).unwrap() {
+//!     println!("{} ({})", info.name().unwrap_or(info.asset().code()), info.home_domain());
+//! }
+//! ```
+use client::sync::Client;
+use endpoint::account;
+use error::{Error, Result};
+use reqwest;
+use resources::AssetIdentifier;
+use std::io::Read;
+use toml;
+
+/// The number of decimals to show when an issuer doesn't say, which is every
+/// decimal an amount has.
+const DEFAULT_DECIMALS: u8 = 7;
+
+/// Whether an issuer still backs an asset, as given in its stellar.toml.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetStatus {
+    /// The asset is issued and backed.
+    Live,
+    /// The asset is no longer backed, and holders shouldn't expect to redeem
+    /// it.
+    Dead,
+    /// The asset is for testing only.
+    Test,
+    /// The asset is only for the issuer's own use or its partners.
+    Private,
+}
+
+impl AssetStatus {
+    fn parse(status: &str) -> Option<AssetStatus> {
+        match status {
+            "live" => Some(AssetStatus::Live),
+            "dead" => Some(AssetStatus::Dead),
+            "test" => Some(AssetStatus::Test),
+            "private" => Some(AssetStatus::Private),
+            _ => None,
+        }
+    }
+}
+
+/// How an issuer describes one of its assets, for displaying the asset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetInfo {
+    asset: AssetIdentifier,
+    home_domain: String,
+    name: Option<String>,
+    description: Option<String>,
+    image: Option<String>,
+    decimals: u8,
+    status: Option<AssetStatus>,
+}
+
+impl AssetInfo {
+    /// Reads the description of the asset from the contents of a
+    /// stellar.toml hosted at the home domain. Returns `None` when the
+    /// stellar.toml doesn't list the asset, which is always the case for
+    /// lumens.
+    ///
+    /// ```
+    /// use stellar_client::issuer::AssetInfo;
+    /// use stellar_client::resources::AssetIdentifier;
+    ///
+    /// let toml = r#"
+    /// [[CURRENCIES]]
+    /// code="USD"
+    /// issuer="GDUKMGUGDZQK6YHYA5Z6AY2G4XDSZPSZ3SW5UN3ARVMO6QSRDWP5YLEX"
+    /// name="US Dollar"
+    /// display_decimals=2
+    /// "#;
+    /// let usd = AssetIdentifier::alphanum4(
+    ///     "USD",
+    ///     "GDUKMGUGDZQK6YHYA5Z6AY2G4XDSZPSZ3SW5UN3ARVMO6QSRDWP5YLEX",
+    /// );
+    /// let info = AssetInfo::from_stellar_toml(toml, "example.com", &usd)
+    ///     .unwrap()
+    ///     .unwrap();
+    /// assert_eq!(info.name(), Some("US Dollar"));
+    /// assert_eq!(info.decimals(), 2);
+    /// ```
+    pub fn from_stellar_toml(
+        toml: &str,
+        home_domain: &str,
+        asset: &AssetIdentifier,
+    ) -> Result<Option<AssetInfo>> {
+        if asset.is_native() {
+            return Ok(None);
+        }
+        let stellar_toml: StellarToml = toml::from_str(toml)?;
+        let currency = stellar_toml.currencies.into_iter().find(|currency| {
+            currency.code.as_ref().map(String::as_str) == Some(asset.code())
+                && currency.issuer.as_ref().map(String::as_str) == Some(asset.issuer())
+        });
+        Ok(currency.map(|currency| AssetInfo {
+            asset: asset.clone(),
+            home_domain: home_domain.to_string(),
+            name: currency.name,
+            description: currency.desc,
+            image: currency.image,
+            decimals: currency.display_decimals.unwrap_or(DEFAULT_DECIMALS),
+            status: currency
+                .status
+                .as_ref()
+                .and_then(|status| AssetStatus::parse(status)),
+        }))
+    }
+
+    /// The asset described.
+    pub fn asset(&self) -> &AssetIdentifier {
+        &self.asset
+    }
+
+    /// The issuer's home domain, which hosts the stellar.toml describing the
+    /// asset.
+    pub fn home_domain(&self) -> &str {
+        &self.home_domain
+    }
+
+    /// A short name for the asset, such as `US Dollar`.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_ref().map(String::as_str)
+    }
+
+    /// A description of the asset and what backs it.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_ref().map(String::as_str)
+    }
+
+    /// The url of an image representing the asset.
+    pub fn image(&self) -> Option<&str> {
+        self.image.as_ref().map(String::as_str)
+    }
+
+    /// The number of decimals to show amounts of the asset with. This is
+    /// every decimal, 7, unless the issuer says otherwise.
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    /// Whether the issuer still backs the asset, if it says.
+    pub fn status(&self) -> Option<AssetStatus> {
+        self.status
+    }
+}
+
+/// The part of a stellar.toml describing the assets an issuer issues.
+#[derive(Deserialize)]
+struct StellarToml {
+    #[serde(default, rename = "CURRENCIES")]
+    currencies: Vec<Currency>,
+}
+
+#[derive(Deserialize)]
+struct Currency {
+    code: Option<String>,
+    issuer: Option<String>,
+    name: Option<String>,
+    desc: Option<String>,
+    image: Option<String>,
+    display_decimals: Option<u8>,
+    status: Option<String>,
+}
+
+/// The uri of the stellar.toml hosted at a home domain.
+///
+/// ```
+/// use stellar_client::issuer;
+///
+/// assert_eq!(
+///     issuer::stellar_toml_uri("example.com"),
+///     "https://example.com/.well-known/stellar.toml"
+/// );
+/// ```
+pub fn stellar_toml_uri(home_domain: &str) -> String {
+    format!("https://{}/.well-known/stellar.toml", home_domain)
+}
+
+/// Looks up how the issuer of an asset describes it. This fetches the
+/// issuer's account from horizon for its home domain, then the stellar.toml
+/// hosted there.
+///
+/// Returns `None` for lumens, when the issuer hasn't set a home domain or
+/// doesn't host a stellar.toml, and when its stellar.toml doesn't list the
+/// asset.
+pub fn issuer_info(client: &Client, asset: &AssetIdentifier) -> Result<Option<AssetInfo>> {
+    resolve(client, asset, fetch_stellar_toml)
+}
+
+fn resolve<F>(client: &Client, asset: &AssetIdentifier, fetch: F) -> Result<Option<AssetInfo>>
+where
+    F: FnOnce(&str) -> Result<Option<String>>,
+{
+    if asset.is_native() {
+        return Ok(None);
+    }
+    let issuer = client.request(account::Details::new(asset.issuer()))?;
+    let home_domain = match issuer.home_domain() {
+        Some(home_domain) => home_domain,
+        None => return Ok(None),
+    };
+    match fetch(home_domain)? {
+        Some(toml) => AssetInfo::from_stellar_toml(&toml, home_domain, asset),
+        None => Ok(None),
+    }
+}
+
+/// The most of a stellar.toml that is read, the limit SEP-1 sets on its
+/// size.
+const MAX_STELLAR_TOML: u64 = 100 * 1024;
+
+/// Fetches the stellar.toml hosted at the home domain, or `None` when there
+/// isn't one. Fails with `Error::BadUri` if the home domain isn't a
+/// hostname, since issuers set it to whatever they like.
+fn fetch_stellar_toml(home_domain: &str) -> Result<Option<String>> {
+    if !is_hostname(home_domain) {
+        return Err(Error::BadUri);
+    }
+    let response = reqwest::get(stellar_toml_uri(home_domain).as_str())?;
+    if response.status().as_u16() == 404 {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(Error::ServerError);
+    }
+    let mut toml = String::new();
+    response.take(MAX_STELLAR_TOML).read_to_string(&mut toml)?;
+    Ok(Some(toml))
+}
+
+/// Whether the domain is a plain hostname, dot separated labels of letters,
+/// digits and inner hyphens, with nothing that would change the uri it is
+/// put into, such as a port, path or credentials.
+fn is_hostname(domain: &str) -> bool {
+    let is_label = |label: &str| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+    };
+    domain.len() <= 253 && domain.split('.').all(is_label)
+}
+
+#[cfg(test)]
+mod issuer_tests {
+    use super::*;
+    use client::sync::test_server::TestServer;
+
+    const STELLAR_TOML: &str = r#"
+VERSION="2.0.0"
+NETWORK_PASSPHRASE="Public Global Stellar Network ; September 2015"
+
+[DOCUMENTATION]
+ORG_NAME="Example Anchor"
+
+[[CURRENCIES]]
+code="USD"
+issuer="GDUKMGUGDZQK6YHYA5Z6AY2G4XDSZPSZ3SW5UN3ARVMO6QSRDWP5YLEX"
+name="US Dollar"
+desc="Redeemable one for one for US dollars held by Example Anchor."
+image="https://example.com/usd.png"
+display_decimals=2
+status="live"
+is_asset_anchored=true
+
+[[CURRENCIES]]
+code="EUR"
+issuer="GDUKMGUGDZQK6YHYA5Z6AY2G4XDSZPSZ3SW5UN3ARVMO6QSRDWP5YLEX"
+status="dead"
+
+[[CURRENCIES]]
+code="BTC"
+issuer="GAUTUYY2THLF7SGITDFMXJVYH3LHDSMGEAKSBU267M2K7A3W543CKUEF"
+name="Bitcoin"
+"#;
+
+    fn usd() -> AssetIdentifier {
+        AssetIdentifier::alphanum4(
+            "USD",
+            "GDUKMGUGDZQK6YHYA5Z6AY2G4XDSZPSZ3SW5UN3ARVMO6QSRDWP5YLEX",
+        )
+    }
+
+    #[test]
+    fn it_reads_the_currency_of_the_asset() {
+        let info = AssetInfo::from_stellar_toml(STELLAR_TOML, "example.com", &usd())
+            .unwrap()
+            .unwrap();
+        assert_eq!(info.asset(), &usd());
+        assert_eq!(info.home_domain(), "example.com");
+        assert_eq!(info.name(), Some("US Dollar"));
+        assert_eq!(
+            info.description(),
+            Some("Redeemable one for one for US dollars held by Example Anchor.")
+        );
+        assert_eq!(info.image(), Some("https://example.com/usd.png"));
+        assert_eq!(info.decimals(), 2);
+        assert_eq!(info.status(), Some(AssetStatus::Live));
+    }
+
+    #[test]
+    fn it_defaults_what_the_issuer_leaves_out() {
+        let eur = AssetIdentifier::alphanum4("EUR", usd().issuer());
+        let info = AssetInfo::from_stellar_toml(STELLAR_TOML, "example.com", &eur)
+            .unwrap()
+            .unwrap();
+        assert_eq!(info.name(), None);
+        assert_eq!(info.image(), None);
+        assert_eq!(info.decimals(), 7);
+        assert_eq!(info.status(), Some(AssetStatus::Dead));
+    }
+
+    #[test]
+    fn it_matches_on_both_code_and_issuer() {
+        let btc = AssetIdentifier::alphanum4("BTC", usd().issuer());
+        let info = AssetInfo::from_stellar_toml(STELLAR_TOML, "example.com", &btc).unwrap();
+        assert_eq!(info, None);
+        let native = AssetIdentifier::native();
+        let info = AssetInfo::from_stellar_toml(STELLAR_TOML, "example.com", &native).unwrap();
+        assert_eq!(info, None);
+    }
+
+    #[test]
+    fn it_errors_on_a_malformed_stellar_toml() {
+        match AssetInfo::from_stellar_toml("[[CURRENCIES]\ncode=", "example.com", &usd()) {
+            Err(Error::TomlParseError(_)) => (),
+            other => panic!("expected a toml error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_resolves_through_the_home_domain() {
+        let server = TestServer::start(
+            "200 OK",
+            r#"{
+                "id": "GDUKMGUGDZQK6YHYA5Z6AY2G4XDSZPSZ3SW5UN3ARVMO6QSRDWP5YLEX",
+                "account_id": "GDUKMGUGDZQK6YHYA5Z6AY2G4XDSZPSZ3SW5UN3ARVMO6QSRDWP5YLEX",
+                "sequence": "1",
+                "subentry_count": 0,
+                "home_domain": "example.com",
+                "balances": [],
+                "data": {}
+            }"#,
+        );
+        let client = Client::new(server.uri()).unwrap();
+        let info = resolve(&client, &usd(), |home_domain| {
+            assert_eq!(home_domain, "example.com");
+            Ok(Some(STELLAR_TOML.to_string()))
+        })
+        .unwrap()
+        .unwrap();
+        assert_eq!(info.name(), Some("US Dollar"));
+    }
+
+    #[test]
+    fn it_only_fetches_from_hostnames() {
+        assert!(is_hostname("example.com"));
+        assert!(is_hostname("stellar-anchor.example.co.uk"));
+        for &domain in &[
+            "",
+            "example..com",
+            "-example.com",
+            "example.com:8443",
+            "example.com/evil",
+            "user@example.com",
+            "example.com?x=",
+            "exa mple.com",
+        ] {
+            assert!(!is_hostname(domain), "{}", domain);
+            match fetch_stellar_toml(domain) {
+                Err(Error::BadUri) => {}
+                other => panic!("Expected a bad uri for {:?}, got {:?}", domain, other),
+            }
+        }
+    }
+
+    #[test]
+    fn it_has_nothing_without_a_home_domain() {
+        let server = TestServer::start(
+            "200 OK",
+            r#"{
+                "id": "GDUKMGUGDZQK6YHYA5Z6AY2G4XDSZPSZ3SW5UN3ARVMO6QSRDWP5YLEX",
+                "account_id": "GDUKMGUGDZQK6YHYA5Z6AY2G4XDSZPSZ3SW5UN3ARVMO6QSRDWP5YLEX",
+                "sequence": "1",
+                "subentry_count": 0,
+                "home_domain": "",
+                "balances": [],
+                "data": {}
+            }"#,
+        );
+        let client = Client::new(server.uri()).unwrap();
+        let info = resolve(&client, &usd(), |_| panic!("fetched a stellar.toml")).unwrap();
+        assert_eq!(info, None);
+    }
+}
@@ -0,0 +1,320 @@
+//! Ed25519 keypairs for signing transactions.
+//!
+//! A keypair is made from a secret seed, from which the account's public key
+//! is derived. Keypairs may also be constructed from just a public key, in
+//! which case they can verify signatures but not produce them.
+//!
+//! Secret seeds are held in a `SecretSeed`, which wipes its memory when it is
+//! dropped, never prints its contents in debug output and compares in
+//! constant time.
+//!
+//! ```
+//! use stellar_client::keypair::Keypair;
+//!
+//! let keypair = Keypair::random();
+//! let signature = keypair.sign(b"hello").unwrap();
+//! assert!(keypair.verify(b"hello", &signature));
+//! ```
+use ed25519_dalek::{self, SigningKey, VerifyingKey};
+use rand::rngs::OsRng;
+use std::error::Error as StdError;
+use std::fmt;
+use strkey::{self, DecodeError, Version};
+use subtle::ConstantTimeEq;
+use zeroize::{Zeroize, Zeroizing};
+
+/// The 32 byte seed that a keypair's secret key is derived from. The seed is
+/// zeroed when dropped and its debug output is redacted.
+#[derive(Clone)]
+pub struct SecretSeed([u8; 32]);
+
+impl SecretSeed {
+    /// Constructs a seed from its raw bytes.
+    pub fn from_bytes(bytes: [u8; 32]) -> SecretSeed {
+        SecretSeed(bytes)
+    }
+
+    /// Decodes a seed from its strkey encoding, which starts with an `S`.
+    pub fn from_strkey(seed: &str) -> Result<SecretSeed, Error> {
+        let bytes = Zeroizing::new(strkey::decode_version(Version::Seed, seed)?);
+        if bytes.len() != 32 {
+            return Err(Error::InvalidKey(DecodeError::InvalidLength));
+        }
+        let mut seed = SecretSeed([0; 32]);
+        seed.0.copy_from_slice(&bytes);
+        Ok(seed)
+    }
+
+    /// Encodes the seed as a strkey. The returned string is zeroed when it is
+    /// dropped as well.
+    pub fn to_strkey(&self) -> Zeroizing<String> {
+        Zeroizing::new(strkey::encode(Version::Seed, &self.0))
+    }
+
+    /// The raw bytes of the seed.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    fn signing_key(&self) -> SigningKey {
+        SigningKey::from_bytes(&self.0)
+    }
+}
+
+impl Drop for SecretSeed {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl fmt::Debug for SecretSeed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("SecretSeed(<redacted>)")
+    }
+}
+
+impl ConstantTimeEq for SecretSeed {
+    fn ct_eq(&self, other: &SecretSeed) -> ::subtle::Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl PartialEq for SecretSeed {
+    fn eq(&self, other: &SecretSeed) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for SecretSeed {}
+
+/// An ed25519 public key, which doubles as the id of an account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PublicKey([u8; 32]);
+
+impl PublicKey {
+    /// Constructs a public key from its raw bytes.
+    pub fn from_bytes(bytes: [u8; 32]) -> PublicKey {
+        PublicKey(bytes)
+    }
+
+    /// Decodes a public key from an account id, which starts with a `G`.
+    pub fn from_account_id(account_id: &str) -> Result<PublicKey, Error> {
+        let bytes = strkey::decode_version(Version::AccountId, account_id)?;
+        if bytes.len() != 32 {
+            return Err(Error::InvalidKey(DecodeError::InvalidLength));
+        }
+        let mut raw = [0; 32];
+        raw.copy_from_slice(&bytes);
+        Ok(PublicKey(raw))
+    }
+
+    /// Encodes the public key as an account id.
+    pub fn account_id(&self) -> String {
+        strkey::encode(Version::AccountId, &self.0)
+    }
+
+    /// The raw bytes of the public key.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// The last four bytes of the key, which are sent alongside signatures
+    /// to hint at which signer produced them.
+    pub fn signature_hint(&self) -> [u8; 4] {
+        [self.0[28], self.0[29], self.0[30], self.0[31]]
+    }
+
+    /// Returns true if the signature was produced over the data by the secret
+    /// key matching this public key.
+    pub fn verify(&self, data: &[u8], signature: &[u8]) -> bool {
+        let key = match VerifyingKey::from_bytes(&self.0) {
+            Ok(key) => key,
+            Err(_) => return false,
+        };
+        match ed25519_dalek::Signature::from_slice(signature) {
+            Ok(signature) => key.verify_strict(data, &signature).is_ok(),
+            Err(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.account_id())
+    }
+}
+
+/// A public key along with, optionally, the secret seed that it was derived
+/// from. Only keypairs with a seed are able to sign.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Keypair {
+    public_key: PublicKey,
+    seed: Option<SecretSeed>,
+}
+
+impl Keypair {
+    /// Generates a new keypair from the operating system's random number
+    /// generator.
+    pub fn random() -> Keypair {
+        let key = SigningKey::generate(&mut OsRng);
+        Keypair::from_seed(SecretSeed(key.to_bytes()))
+    }
+
+    /// Constructs a keypair that is able to sign from a secret seed.
+    pub fn from_seed(seed: SecretSeed) -> Keypair {
+        let public_key = PublicKey(seed.signing_key().verifying_key().to_bytes());
+        Keypair {
+            public_key,
+            seed: Some(seed),
+        }
+    }
+
+    /// Constructs a keypair that is able to sign from a strkey encoded secret
+    /// seed.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use stellar_client::keypair::Keypair;
+    ///
+    /// let keypair =
+    ///     Keypair::from_secret_seed("SBGWSG6BTNCKCOB3DIFBGCVMUPQFYPA2G4O34RMTB343OYPXU5DJDVMN")
+    ///         .unwrap();
+    /// assert!(keypair.can_sign());
+    /// ```
+    pub fn from_secret_seed(seed: &str) -> Result<Keypair, Error> {
+        SecretSeed::from_strkey(seed).map(Keypair::from_seed)
+    }
+
+    /// Constructs a keypair from an account id. It will be able to verify
+    /// signatures but not produce them.
+    pub fn from_account_id(account_id: &str) -> Result<Keypair, Error> {
+        Ok(Keypair {
+            public_key: PublicKey::from_account_id(account_id)?,
+            seed: None,
+        })
+    }
+
+    /// The public key of the keypair.
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    /// The account id of the keypair.
+    pub fn account_id(&self) -> String {
+        self.public_key.account_id()
+    }
+
+    /// The secret seed of the keypair, if it has one.
+    pub fn secret_seed(&self) -> Option<&SecretSeed> {
+        self.seed.as_ref()
+    }
+
+    /// Returns true if the keypair holds a secret seed and can sign.
+    pub fn can_sign(&self) -> bool {
+        self.seed.is_some()
+    }
+
+    /// Signs the data, returning the 64 byte ed25519 signature.
+    pub fn sign(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        use ed25519_dalek::Signer;
+
+        let seed = self.seed.as_ref().ok_or(Error::MissingSecret)?;
+        Ok(seed.signing_key().sign(data).to_bytes().to_vec())
+    }
+
+    /// Returns true if the signature was produced over the data by this
+    /// keypair.
+    pub fn verify(&self, data: &[u8], signature: &[u8]) -> bool {
+        self.public_key.verify(data, signature)
+    }
+}
+
+impl fmt::Debug for Keypair {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Keypair")
+            .field("account_id", &self.account_id())
+            .field("can_sign", &self.can_sign())
+            .finish()
+    }
+}
+
+/// The reasons a keypair operation can fail.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Error {
+    /// The key could not be decoded.
+    InvalidKey(DecodeError),
+    /// The keypair has no secret seed so it can't sign.
+    MissingSecret,
+}
+
+impl From<DecodeError> for Error {
+    fn from(inner: DecodeError) -> Error {
+        Error::InvalidKey(inner)
+    }
+}
+
+impl StdError for Error {
+    fn description(&self) -> &str {
+        match *self {
+            Error::InvalidKey(ref inner) => inner.description(),
+            Error::MissingSecret => "The keypair does not have a secret seed to sign with",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+#[cfg(test)]
+mod keypair_tests {
+    use super::*;
+
+    const SEED: &str = "SBGWSG6BTNCKCOB3DIFBGCVMUPQFYPA2G4O34RMTB343OYPXU5DJDVMN";
+    const ACCOUNT_ID: &str = "GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ6";
+
+    #[test]
+    fn it_derives_the_account_id_from_a_seed() {
+        let keypair = Keypair::from_secret_seed(SEED).unwrap();
+        assert_eq!(keypair.account_id(), ACCOUNT_ID);
+        assert_eq!(&*keypair.secret_seed().unwrap().to_strkey(), SEED);
+    }
+
+    #[test]
+    fn it_signs_and_verifies() {
+        let keypair = Keypair::from_secret_seed(SEED).unwrap();
+        let signature = keypair.sign(b"test").unwrap();
+        assert_eq!(signature.len(), 64);
+
+        let public = Keypair::from_account_id(ACCOUNT_ID).unwrap();
+        assert!(public.verify(b"test", &signature));
+        assert!(!public.verify(b"other", &signature));
+        assert_eq!(public.sign(b"test"), Err(Error::MissingSecret));
+    }
+
+    #[test]
+    fn it_does_not_leak_the_seed_when_debugged() {
+        let keypair = Keypair::from_secret_seed(SEED).unwrap();
+        let debug = format!("{:?} {:?}", keypair, keypair.secret_seed().unwrap());
+        assert!(!debug.contains(SEED));
+        assert!(debug.contains("<redacted>"));
+    }
+
+    #[test]
+    fn it_compares_seeds() {
+        let seed = SecretSeed::from_strkey(SEED).unwrap();
+        assert_eq!(seed, seed.clone());
+        assert!(seed != SecretSeed::from_bytes([0; 32]));
+    }
+
+    #[test]
+    fn it_rejects_keys_of_the_wrong_version() {
+        assert_eq!(
+            Keypair::from_secret_seed(ACCOUNT_ID).unwrap_err(),
+            Error::InvalidKey(DecodeError::InvalidVersion)
+        );
+    }
+}
@@ -0,0 +1,605 @@
+//! Encrypted key files for keeping secret seeds on disk.
+//!
+//! A `KeyFile` holds a secret seed encrypted with AES-256-GCM under a key
+//! derived from a passphrase with scrypt. The account id is stored in the
+//! clear, so files can be told apart without the passphrase, but it is
+//! authenticated along with the seed and can't be swapped out.
+//!
+//! A `Keystore` is a directory of key files, each saved under a name, so
+//! that tools can refer to a key by name rather than by its seed.
+//!
+//! ```
+//! use stellar_client::keypair::Keypair;
+//! use stellar_client::keystore::KeyFile;
+//!
+//! let keypair = Keypair::random();
+//! let file = KeyFile::encrypt(&keypair, "correct horse battery staple").unwrap();
+//! assert_eq!(file.account_id(), keypair.account_id());
+//! assert_eq!(file.decrypt("correct horse battery staple").unwrap(), keypair);
+//! assert!(file.decrypt("tr0ub4dor&3").is_err());
+//! ```
+use base64;
+use keypair::{Keypair, SecretSeed};
+use openssl::error::ErrorStack;
+use openssl::pkcs5;
+use openssl::symm::{self, Cipher};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde_json;
+use std::error::Error as StdError;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
+
+const VERSION: u32 = 1;
+const CIPHER: &str = "aes-256-gcm";
+const KDF: &str = "scrypt";
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+const SALT_LEN: usize = 32;
+
+// scrypt with N = 2^15, r = 8 and p = 1 takes 32MiB and a fraction of a
+// second, which is what other wallets settle on for interactive use.
+const LOG_N: u8 = 15;
+const R: u32 = 8;
+const P: u32 = 1;
+// Files asking for more than this are refused rather than letting a crafted
+// file exhaust memory or spin the cpu.
+const MAX_LOG_N: u8 = 20;
+const MAX_R: u32 = 32;
+const MAX_P: u32 = 16;
+const EXTENSION: &str = "json";
+
+/// A secret seed encrypted with a passphrase, in the json shape it is
+/// written to disk in.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct KeyFile {
+    version: u32,
+    account_id: String,
+    cipher: String,
+    nonce: String,
+    ciphertext: String,
+    kdf: KdfParams,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct KdfParams {
+    name: String,
+    log_n: u8,
+    r: u32,
+    p: u32,
+    salt: String,
+}
+
+impl KeyFile {
+    /// Encrypts the keypair's secret seed with the passphrase. Fails if the
+    /// keypair has no seed.
+    pub fn encrypt(keypair: &Keypair, passphrase: &str) -> Result<KeyFile, Error> {
+        let seed = keypair.secret_seed().ok_or_else(Error::missing_secret)?;
+        let account_id = keypair.account_id();
+
+        let mut salt = [0; SALT_LEN];
+        let mut nonce = [0; NONCE_LEN];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce);
+
+        let kdf = KdfParams {
+            name: KDF.to_string(),
+            log_n: LOG_N,
+            r: R,
+            p: P,
+            salt: base64::encode(&salt),
+        };
+        let key = kdf.derive(passphrase, &salt)?;
+        let mut tag = [0; TAG_LEN];
+        let mut ciphertext = symm::encrypt_aead(
+            Cipher::aes_256_gcm(),
+            &*key,
+            Some(&nonce),
+            account_id.as_bytes(),
+            seed.as_bytes(),
+            &mut tag,
+        )?;
+        ciphertext.extend_from_slice(&tag);
+
+        Ok(KeyFile {
+            version: VERSION,
+            account_id,
+            cipher: CIPHER.to_string(),
+            nonce: base64::encode(&nonce),
+            ciphertext: base64::encode(&ciphertext),
+            kdf,
+        })
+    }
+
+    /// Decrypts the secret seed with the passphrase. A wrong passphrase and
+    /// a file that has been tampered with both fail with
+    /// `ErrorKind::WrongPassphrase`.
+    pub fn decrypt(&self, passphrase: &str) -> Result<Keypair, Error> {
+        if self.version != VERSION || self.cipher != CIPHER || !self.kdf.is_supported() {
+            return Err(Error::unsupported());
+        }
+        let salt = base64::decode(&self.kdf.salt).map_err(|_| Error::malformed())?;
+        let nonce = base64::decode(&self.nonce).map_err(|_| Error::malformed())?;
+        let sealed = base64::decode(&self.ciphertext).map_err(|_| Error::malformed())?;
+        if nonce.len() != NONCE_LEN || sealed.len() < TAG_LEN {
+            return Err(Error::malformed());
+        }
+        let (ciphertext, tag) = sealed.split_at(sealed.len() - TAG_LEN);
+
+        let key = self.kdf.derive(passphrase, &salt)?;
+        let plaintext = symm::decrypt_aead(
+            Cipher::aes_256_gcm(),
+            &*key,
+            Some(&nonce),
+            self.account_id.as_bytes(),
+            ciphertext,
+            tag,
+        ).map_err(|_| Error::wrong_passphrase())?;
+        let plaintext = Zeroizing::new(plaintext);
+        if plaintext.len() != 32 {
+            return Err(Error::malformed());
+        }
+        let mut bytes = [0; 32];
+        bytes.copy_from_slice(&plaintext);
+        let keypair = Keypair::from_seed(SecretSeed::from_bytes(bytes));
+        if keypair.account_id() != self.account_id {
+            return Err(Error::malformed());
+        }
+        Ok(keypair)
+    }
+
+    /// The account whose seed is in the file.
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    /// Reads a key file written by `save`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<KeyFile, Error> {
+        let file = File::open(path)?;
+        Ok(serde_json::from_reader(file)?)
+    }
+
+    /// Writes the key file to a new file, failing if one already exists so
+    /// that a seed is never overwritten. On unix the file is only readable
+    /// by its owner.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let file = options.open(path)?;
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(())
+    }
+}
+
+impl KdfParams {
+    fn is_supported(&self) -> bool {
+        self.name == KDF
+            && (1..=MAX_LOG_N).contains(&self.log_n)
+            && (1..=MAX_R).contains(&self.r)
+            && (1..=MAX_P).contains(&self.p)
+    }
+
+    fn derive(&self, passphrase: &str, salt: &[u8]) -> Result<Zeroizing<[u8; 32]>, Error> {
+        let n = 1u64 << self.log_n;
+        let r = u64::from(self.r);
+        let p = u64::from(self.p);
+        // scrypt needs 128 * r * (n + p) bytes, leave room for the rest.
+        let max_mem = n
+            .checked_add(p)
+            .and_then(|blocks| blocks.checked_mul(128 * r))
+            .and_then(|mem| mem.checked_add(1 << 20))
+            .ok_or_else(Error::unsupported)?;
+        let mut key = Zeroizing::new([0; 32]);
+        pkcs5::scrypt(passphrase.as_bytes(), salt, n, r, p, max_mem, &mut *key)?;
+        Ok(key)
+    }
+}
+
+/// A directory of key files, each saved as `<name>.json`. Names are made of
+/// letters, digits, `-`, `_` and `.`, and can't start with a `.`.
+///
+/// ```no_run
+/// use stellar_client::keypair::Keypair;
+/// use stellar_client::keystore::{KeyFile, Keystore};
+///
+/// let keystore = Keystore::open("/home/me/.stellar/keys").unwrap();
+/// let file = KeyFile::encrypt(&Keypair::random(), "hunter2").unwrap();
+/// keystore.insert("market-maker", &file).unwrap();
+/// let keypair = keystore.get("market-maker").unwrap().decrypt("hunter2").unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Keystore {
+    dir: PathBuf,
+}
+
+impl Keystore {
+    /// Opens the keystore in the given directory, creating it if needed. On
+    /// unix a new directory is only accessible by its owner.
+    pub fn open<P: AsRef<Path>>(dir: P) -> Result<Keystore, Error> {
+        let dir = dir.as_ref().to_path_buf();
+        if !dir.is_dir() {
+            let mut builder = fs::DirBuilder::new();
+            builder.recursive(true);
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::DirBuilderExt;
+                builder.mode(0o700);
+            }
+            builder.create(&dir)?;
+        }
+        Ok(Keystore { dir })
+    }
+
+    /// The directory the key files are kept in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The names of the stored keys, in alphabetical order.
+    pub fn names(&self) -> Result<Vec<String>, Error> {
+        let mut names = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(EXTENSION) {
+                continue;
+            }
+            if let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) {
+                if is_valid_name(name) {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    /// Returns true if a key is stored under the name.
+    pub fn contains(&self, name: &str) -> bool {
+        self.path(name).map(|path| path.is_file()).unwrap_or(false)
+    }
+
+    /// Reads the key file stored under the name.
+    pub fn get(&self, name: &str) -> Result<KeyFile, Error> {
+        let path = self.path(name)?;
+        KeyFile::load(path).map_err(|err| match err.kind {
+            ErrorKind::Io(ref inner) if inner.kind() == io::ErrorKind::NotFound => {
+                Error::not_found(name)
+            }
+            _ => err,
+        })
+    }
+
+    /// Stores a key file under the name. An existing key is never replaced;
+    /// remove it first.
+    pub fn insert(&self, name: &str, file: &KeyFile) -> Result<(), Error> {
+        let path = self.path(name)?;
+        file.save(path).map_err(|err| match err.kind {
+            ErrorKind::Io(ref inner) if inner.kind() == io::ErrorKind::AlreadyExists => {
+                Error::already_exists(name)
+            }
+            _ => err,
+        })
+    }
+
+    /// Deletes the key file stored under the name.
+    pub fn remove(&self, name: &str) -> Result<(), Error> {
+        let path = self.path(name)?;
+        fs::remove_file(path).map_err(|err| {
+            if err.kind() == io::ErrorKind::NotFound {
+                Error::not_found(name)
+            } else {
+                err.into()
+            }
+        })
+    }
+
+    fn path(&self, name: &str) -> Result<PathBuf, Error> {
+        if !is_valid_name(name) {
+            return Err(Error::invalid_name(name));
+        }
+        Ok(self.dir.join(format!("{}.{}", name, EXTENSION)))
+    }
+}
+
+// Keeping names to a small set of characters means they can't reach outside
+// of the keystore's directory.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with('.')
+        && name.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+}
+
+/// An error that occurs while encrypting, decrypting, reading or writing a
+/// key file.
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+}
+
+impl Error {
+    /// Constructs an error for encrypting a keypair without a secret seed.
+    pub fn missing_secret() -> Error {
+        Error {
+            kind: ErrorKind::MissingSecret,
+        }
+    }
+
+    /// Constructs an error for a passphrase that doesn't decrypt the file.
+    pub fn wrong_passphrase() -> Error {
+        Error {
+            kind: ErrorKind::WrongPassphrase,
+        }
+    }
+
+    /// Constructs an error for a key file of a version, cipher or key
+    /// derivation this crate doesn't support.
+    pub fn unsupported() -> Error {
+        Error {
+            kind: ErrorKind::Unsupported,
+        }
+    }
+
+    /// Constructs an error for a key file whose contents don't decode.
+    pub fn malformed() -> Error {
+        Error {
+            kind: ErrorKind::Malformed,
+        }
+    }
+
+    /// Constructs an error for a key name that isn't allowed in a keystore.
+    pub fn invalid_name(name: &str) -> Error {
+        Error {
+            kind: ErrorKind::InvalidName(name.to_string()),
+        }
+    }
+
+    /// Constructs an error for a name with no key stored under it.
+    pub fn not_found(name: &str) -> Error {
+        Error {
+            kind: ErrorKind::NotFound(name.to_string()),
+        }
+    }
+
+    /// Constructs an error for a name that already has a key stored under it.
+    pub fn already_exists(name: &str) -> Error {
+        Error {
+            kind: ErrorKind::AlreadyExists(name.to_string()),
+        }
+    }
+
+    /// The underlying kind of error.
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+}
+
+/// The reasons that working with a key file can fail.
+#[derive(Debug)]
+pub enum ErrorKind {
+    /// The keypair has no secret seed to encrypt.
+    MissingSecret,
+    /// The passphrase is wrong or the file has been tampered with.
+    WrongPassphrase,
+    /// The file uses a version, cipher or key derivation that isn't
+    /// supported.
+    Unsupported,
+    /// The file's contents don't decode into a seed for its account.
+    Malformed,
+    /// The key name has characters that aren't allowed.
+    InvalidName(String),
+    /// No key is stored under the name.
+    NotFound(String),
+    /// A key is already stored under the name.
+    AlreadyExists(String),
+    /// The cryptography library failed.
+    Crypto(ErrorStack),
+    /// The file couldn't be read or written.
+    Io(io::Error),
+    /// The file isn't valid json.
+    Json(serde_json::Error),
+}
+
+impl From<ErrorStack> for Error {
+    fn from(inner: ErrorStack) -> Error {
+        Error {
+            kind: ErrorKind::Crypto(inner),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(inner: io::Error) -> Error {
+        Error {
+            kind: ErrorKind::Io(inner),
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(inner: serde_json::Error) -> Error {
+        Error {
+            kind: ErrorKind::Json(inner),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn description(&self) -> &str {
+        match self.kind {
+            ErrorKind::MissingSecret => "The keypair has no secret seed",
+            ErrorKind::WrongPassphrase => "The passphrase is wrong or the key file is corrupt",
+            ErrorKind::Unsupported => "The key file format is not supported",
+            ErrorKind::Malformed => "The key file is malformed",
+            ErrorKind::InvalidName(_) => "The key name is not allowed",
+            ErrorKind::NotFound(_) => "No key is stored under the name",
+            ErrorKind::AlreadyExists(_) => "A key is already stored under the name",
+            ErrorKind::Crypto(ref inner) => inner.description(),
+            ErrorKind::Io(ref inner) => inner.description(),
+            ErrorKind::Json(ref inner) => inner.description(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ErrorKind::Crypto(ref inner) => write!(f, "{}", inner),
+            ErrorKind::Io(ref inner) => write!(f, "{}", inner),
+            ErrorKind::Json(ref inner) => write!(f, "{}", inner),
+            ErrorKind::InvalidName(ref name) => write!(
+                f,
+                "The key name {:?} is not allowed, use letters, digits, '-', '_' and '.'",
+                name
+            ),
+            ErrorKind::NotFound(ref name) => write!(f, "No key is stored under {:?}", name),
+            ErrorKind::AlreadyExists(ref name) => {
+                write!(f, "A key is already stored under {:?}", name)
+            }
+            _ => f.write_str(self.description()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod key_file_tests {
+    use super::*;
+
+    const SEED: &str = "SBGWSG6BTNCKCOB3DIFBGCVMUPQFYPA2G4O34RMTB343OYPXU5DJDVMN";
+    const ACCOUNT_ID: &str = "GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ6";
+
+    fn key_file() -> KeyFile {
+        let keypair = Keypair::from_secret_seed(SEED).unwrap();
+        KeyFile::encrypt(&keypair, "hunter2").unwrap()
+    }
+
+    #[test]
+    fn it_decrypts_with_the_passphrase() {
+        let file = key_file();
+        assert_eq!(file.account_id(), ACCOUNT_ID);
+        let keypair = file.decrypt("hunter2").unwrap();
+        assert_eq!(keypair, Keypair::from_secret_seed(SEED).unwrap());
+        match *file.decrypt("hunter3").unwrap_err().kind() {
+            ErrorKind::WrongPassphrase => {}
+            ref kind => panic!("Unexpected error {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn it_refuses_a_file_for_another_account() {
+        let mut file = key_file();
+        file.account_id = "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3".to_string();
+        assert!(file.decrypt("hunter2").is_err());
+    }
+
+    #[test]
+    fn it_refuses_to_encrypt_without_a_seed() {
+        let keypair = Keypair::from_account_id(ACCOUNT_ID).unwrap();
+        match *KeyFile::encrypt(&keypair, "hunter2").unwrap_err().kind() {
+            ErrorKind::MissingSecret => {}
+            ref kind => panic!("Unexpected error {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn it_refuses_expensive_key_derivation() {
+        let mut file = key_file();
+        file.kdf.log_n = 40;
+        match *file.decrypt("hunter2").unwrap_err().kind() {
+            ErrorKind::Unsupported => {}
+            ref kind => panic!("Unexpected error {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn it_refuses_huge_block_sizes_and_parallelism() {
+        let mut file = key_file();
+        file.kdf.r = u32::MAX;
+        match *file.decrypt("hunter2").unwrap_err().kind() {
+            ErrorKind::Unsupported => {}
+            ref kind => panic!("Unexpected error {:?}", kind),
+        }
+
+        let mut file = key_file();
+        file.kdf.p = u32::MAX;
+        match *file.decrypt("hunter2").unwrap_err().kind() {
+            ErrorKind::Unsupported => {}
+            ref kind => panic!("Unexpected error {:?}", kind),
+        }
+        // The memory bound can't overflow even past the checks above.
+        let mut kdf = key_file().kdf;
+        kdf.log_n = 63;
+        kdf.r = u32::MAX;
+        match *kdf.derive("hunter2", &[0; SALT_LEN]).unwrap_err().kind() {
+            ErrorKind::Unsupported => {}
+            ref kind => panic!("Unexpected error {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn it_round_trips_through_json() {
+        let file = key_file();
+        let json = serde_json::to_string(&file).unwrap();
+        let reparsed: KeyFile = serde_json::from_str(&json).unwrap();
+        assert_eq!(reparsed, file);
+        assert!(reparsed.decrypt("hunter2").is_ok());
+    }
+}
+
+#[cfg(test)]
+mod keystore_tests {
+    use super::*;
+    use std::env;
+
+    fn keystore() -> Keystore {
+        let mut suffix = [0; 8];
+        OsRng.fill_bytes(&mut suffix);
+        let dir = env::temp_dir().join(format!("keystore-{}", ::xdr::hex(&suffix)));
+        Keystore::open(dir).unwrap()
+    }
+
+    fn key_file() -> KeyFile {
+        KeyFile::encrypt(&Keypair::random(), "hunter2").unwrap()
+    }
+
+    #[test]
+    fn it_stores_keys_by_name() {
+        let keystore = keystore();
+        let file = key_file();
+        keystore.insert("alice", &file).unwrap();
+        keystore.insert("bob", &key_file()).unwrap();
+        assert!(keystore.contains("alice"));
+        assert_eq!(keystore.names().unwrap(), vec!["alice", "bob"]);
+        assert_eq!(keystore.get("alice").unwrap(), file);
+
+        match *keystore.insert("alice", &key_file()).unwrap_err().kind() {
+            ErrorKind::AlreadyExists(ref name) => assert_eq!(name, "alice"),
+            ref kind => panic!("Unexpected error {:?}", kind),
+        }
+        keystore.remove("alice").unwrap();
+        match *keystore.get("alice").unwrap_err().kind() {
+            ErrorKind::NotFound(ref name) => assert_eq!(name, "alice"),
+            ref kind => panic!("Unexpected error {:?}", kind),
+        }
+        fs::remove_dir_all(keystore.dir()).unwrap();
+    }
+
+    #[test]
+    fn it_refuses_names_that_leave_the_directory() {
+        let keystore = keystore();
+        for name in &["../alice", "a/b", ".hidden", ""] {
+            match *keystore.insert(name, &key_file()).unwrap_err().kind() {
+                ErrorKind::InvalidName(_) => {}
+                ref kind => panic!("Unexpected error {:?}", kind),
+            }
+        }
+        fs::remove_dir_all(keystore.dir()).unwrap();
+    }
+}
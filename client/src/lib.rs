@@ -38,23 +38,54 @@
 
 extern crate base64;
 extern crate chrono;
+extern crate ed25519_dalek;
+extern crate flate2;
 extern crate futures;
 extern crate http;
 extern crate hyper;
 extern crate hyper_tls;
+extern crate openssl;
+extern crate rand;
+#[cfg(test)]
+extern crate proptest;
 extern crate reqwest;
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
 extern crate serde_json;
+extern crate sha2;
+extern crate subtle;
 extern crate tokio_core;
+extern crate toml;
+extern crate zeroize;
 
+pub mod balance_reducer;
 pub mod client;
+pub mod deposit;
 pub mod endpoint;
 pub mod error;
+pub mod execution;
+pub mod history;
+pub mod history_export;
+pub mod issuer;
+pub mod keypair;
+pub mod keystore;
+pub mod markets;
+pub mod network;
+pub mod portfolio;
 pub mod resources;
+pub mod sep10;
+pub mod sep9;
+pub mod sep38;
+pub mod signer;
 mod stellar_error;
+pub mod strkey;
+pub mod token;
+pub mod tracking;
+pub mod transaction;
+pub mod withdrawal;
 mod uri;
+pub mod xdr;
 
 /// The stellar client is a data structure that wraps the logic and state of the
 /// stellar horizon api. Interaction generally relies on building resources from
@@ -67,4 +98,4 @@ mod uri;
 /// itself.
 pub use client::{async, sync};
 pub use error::{Error, Result};
-pub use stellar_error::StellarError;
+pub use stellar_error::{ResultCodes, StellarError};
@@ -0,0 +1,264 @@
+//! Summaries of DEX markets for exchange UIs, with the best bid and ask,
+//! the last price, and the volume and change in price over the last day.
+//!
+//! Each market takes two requests, one for the top of the orderbook and one
+//! for trade aggregations over the last 24 hours, and every request for
+//! every pair is made at the same time on its own thread.
+//!
+//! ```no_run
+//! use stellar_client::markets;
+//! use stellar_client::resources::AssetIdentifier;
+//! use stellar_client::sync::Client;
+//!
+//! let client = Client::horizon().unwrap();
+//! let usd = AssetIdentifier::alphanum4(
+//!     "USD",
+//!     "GDUKMGUGDZQK6YHYA5Z6AY2G4XDSZPSZ3SW5UN3ARVMO6QSRDWP5YLEX",
+//! );
+//! let pairs = vec![(AssetIdentifier::native(), usd)];
+//! for market in markets::summaries(&client, &pairs).unwrap() {
+//!     println!(
+//!         "{}/{} bid {:?} ask {:?} last {:?} volume {}",
+//!         market.base().code(),
+//!         market.counter().code(),
+//!         market.bid(),
+//!         market.ask(),
+//!         market.last(),
+//!         market.base_volume()
+//!     );
+//! }
+//! ```
+use chrono::Utc;
+use client::sync::Client;
+use endpoint::{orderbook, trade, Direction, Limit, Order};
+use error::Result;
+use resources::{Amount, AssetIdentifier, Orderbook, SignedAmount, TradeAggregation};
+use std::thread;
+
+/// The width of the buckets trades are aggregated into, fifteen minutes.
+const RESOLUTION: u64 = 15 * 60 * 1000;
+
+/// How far back the summary goes, one day.
+const WINDOW: u64 = 24 * 60 * 60 * 1000;
+
+/// The state of the market between a base and a counter asset, with prices
+/// in units of the counter asset for one of the base asset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarketSummary {
+    base: AssetIdentifier,
+    counter: AssetIdentifier,
+    bid: Option<Amount>,
+    ask: Option<Amount>,
+    open: Option<Amount>,
+    last: Option<Amount>,
+    high: Option<Amount>,
+    low: Option<Amount>,
+    base_volume: Amount,
+    counter_volume: Amount,
+    trade_count: u64,
+}
+
+impl MarketSummary {
+    /// Summarizes a market from the top of its orderbook and its trade
+    /// aggregations over a window of time, oldest first.
+    pub fn new(orderbook: &Orderbook, aggregations: &[TradeAggregation]) -> MarketSummary {
+        let zero = Amount::new(0);
+        MarketSummary {
+            base: orderbook.base().clone(),
+            counter: orderbook.counter().clone(),
+            bid: orderbook.bids().first().map(|offer| offer.price()),
+            ask: orderbook.asks().first().map(|offer| offer.price()),
+            open: aggregations.first().map(TradeAggregation::open),
+            last: aggregations.last().map(TradeAggregation::close),
+            high: aggregations.iter().map(TradeAggregation::high).max(),
+            low: aggregations.iter().map(TradeAggregation::low).min(),
+            base_volume: aggregations
+                .iter()
+                .fold(zero, |total, agg| &total + &agg.base_volume()),
+            counter_volume: aggregations
+                .iter()
+                .fold(zero, |total, agg| &total + &agg.counter_volume()),
+            trade_count: aggregations.iter().map(TradeAggregation::count).sum(),
+        }
+    }
+
+    /// The asset being bought and sold.
+    pub fn base(&self) -> &AssetIdentifier {
+        &self.base
+    }
+
+    /// The asset prices are in.
+    pub fn counter(&self) -> &AssetIdentifier {
+        &self.counter
+    }
+
+    /// The best price anyone is bidding, or `None` when there are no bids.
+    pub fn bid(&self) -> Option<Amount> {
+        self.bid
+    }
+
+    /// The best price anyone is asking, or `None` when there are no asks.
+    pub fn ask(&self) -> Option<Amount> {
+        self.ask
+    }
+
+    /// The price of the first trade in the window.
+    pub fn open(&self) -> Option<Amount> {
+        self.open
+    }
+
+    /// The price of the last trade in the window, or `None` when nothing
+    /// traded.
+    pub fn last(&self) -> Option<Amount> {
+        self.last
+    }
+
+    /// The highest price traded at in the window.
+    pub fn high(&self) -> Option<Amount> {
+        self.high
+    }
+
+    /// The lowest price traded at in the window.
+    pub fn low(&self) -> Option<Amount> {
+        self.low
+    }
+
+    /// How much of the base asset traded in the window.
+    pub fn base_volume(&self) -> Amount {
+        self.base_volume
+    }
+
+    /// How much of the counter asset traded in the window.
+    pub fn counter_volume(&self) -> Amount {
+        self.counter_volume
+    }
+
+    /// The number of trades in the window.
+    pub fn trade_count(&self) -> u64 {
+        self.trade_count
+    }
+
+    /// How far the price moved from the first trade in the window to the
+    /// last, or `None` when nothing traded.
+    pub fn change(&self) -> Option<SignedAmount> {
+        match (self.open, self.last) {
+            (Some(open), Some(last)) => Some(&SignedAmount::from(last) - &open),
+            _ => None,
+        }
+    }
+}
+
+/// Summarizes the market for each base and counter pair over the last 24
+/// hours, in the order the pairs are given. The requests are all made at
+/// once, and the first to fail fails the whole call.
+pub fn summaries(
+    client: &Client,
+    pairs: &[(AssetIdentifier, AssetIdentifier)],
+) -> Result<Vec<MarketSummary>> {
+    let (start, end) = window(Utc::now().timestamp_millis() as u64);
+    let requests: Vec<_> = pairs
+        .iter()
+        .map(|pair| {
+            let (base, counter) = (&pair.0, &pair.1);
+            let book =
+                orderbook::Details::for_asset_pair(base.clone(), counter.clone()).with_limit(1);
+            let aggregations = trade::Aggregations::new(base, counter)
+                .with_resolution(RESOLUTION)
+                .with_start_time(start)
+                .with_end_time(end)
+                .with_order(Direction::Asc)
+                .with_limit((WINDOW / RESOLUTION) as u32);
+            let book_client = client.clone();
+            let aggregations_client = client.clone();
+            (
+                thread::spawn(move || book_client.request(book)),
+                thread::spawn(move || aggregations_client.request(aggregations)),
+            )
+        })
+        .collect();
+
+    let mut summaries = Vec::with_capacity(requests.len());
+    for (book, aggregations) in requests {
+        let book = book.join().expect("Orderbook request panicked");
+        let aggregations = aggregations
+            .join()
+            .expect("Trade aggregations request panicked");
+        summaries.push(MarketSummary::new(&book?, aggregations?.records()));
+    }
+    Ok(summaries)
+}
+
+/// The start and end of the last day, in milliseconds, with the end rounded
+/// up to a whole bucket so that horizon accepts it.
+fn window(now: u64) -> (u64, u64) {
+    let end = (now / RESOLUTION + 1) * RESOLUTION;
+    (end.saturating_sub(WINDOW), end)
+}
+
+#[cfg(test)]
+mod markets_tests {
+    use super::*;
+    use client::sync::test_server::TestServer;
+    use serde_json;
+
+    fn orderbook() -> Orderbook {
+        serde_json::from_str(include_str!("../fixtures/orderbook.json")).unwrap()
+    }
+
+    fn aggregation(open: &str, close: &str, base_volume: &str) -> TradeAggregation {
+        let json = include_str!("../fixtures/trade_aggregation.json")
+            .replace("\"0.1724138\"", &format!("\"{}\"", open))
+            .replace("\"0.1506024\"", &format!("\"{}\"", close))
+            .replace("\"27575.0201596\"", &format!("\"{}\"", base_volume));
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn it_summarizes_a_market() {
+        let aggregations = vec![
+            aggregation("0.1724138", "0.1800000", "10"),
+            aggregation("0.1800000", "0.1900000", "5.5"),
+        ];
+        let summary = MarketSummary::new(&orderbook(), &aggregations);
+        assert_eq!(summary.base(), orderbook().base());
+        assert_eq!(summary.bid(), Some("7.7200005".parse().unwrap()));
+        assert_eq!(summary.ask(), Some("7.7600000".parse().unwrap()));
+        assert_eq!(summary.open(), Some(Amount::new(1_724_138)));
+        assert_eq!(summary.last(), Some(Amount::new(1_900_000)));
+        assert_eq!(summary.high(), Some(Amount::new(1_915_709)));
+        assert_eq!(summary.base_volume(), Amount::new(155_000_000));
+        assert_eq!(summary.trade_count(), 52);
+        assert_eq!(summary.change(), Some(SignedAmount::new(175_862)));
+    }
+
+    #[test]
+    fn it_has_no_prices_without_trades() {
+        let summary = MarketSummary::new(&orderbook(), &[]);
+        assert_eq!(summary.last(), None);
+        assert_eq!(summary.change(), None);
+        assert_eq!(summary.base_volume(), Amount::new(0));
+        assert_eq!(summary.trade_count(), 0);
+    }
+
+    #[test]
+    fn it_reports_a_falling_price() {
+        let summary = MarketSummary::new(&orderbook(), &[aggregation("0.2", "0.15", "1")]);
+        assert_eq!(summary.change(), Some(SignedAmount::new(-500_000)));
+    }
+
+    #[test]
+    fn it_ends_the_window_on_a_whole_bucket() {
+        let (start, end) = window(1_517_522_400_001);
+        assert_eq!(end, 1_517_523_300_000);
+        assert_eq!(end % RESOLUTION, 0);
+        assert_eq!(end - start, WINDOW);
+    }
+
+    #[test]
+    fn it_fails_when_horizon_is_down() {
+        let client = Client::new(&TestServer::down()).unwrap();
+        let pairs = vec![(AssetIdentifier::native(), orderbook().counter().clone())];
+        assert!(summaries(&client, &pairs).is_err());
+        assert_eq!(summaries(&client, &[]).unwrap(), vec![]);
+    }
+}
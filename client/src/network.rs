@@ -0,0 +1,94 @@
+//! The stellar networks, told apart by their passphrases. Transactions are
+//! signed for the id of one network, the hash of its passphrase, so that they
+//! can't be replayed on another.
+//!
+//! ```
+//! use stellar_client::keypair::Keypair;
+//! use stellar_client::network::Network;
+//! use stellar_client::transaction::TransactionBuilder;
+//!
+//! let keypair = Keypair::random();
+//! let builder = TransactionBuilder::new(&keypair.account_id(), 1, Network::TESTNET);
+//! ```
+use sha2::{Digest, Sha256};
+
+/// A stellar network, such as the public network or the test network.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Network {
+    passphrase: String,
+}
+
+impl Network {
+    /// The passphrase of the public network.
+    pub const PUBLIC: &'static str = "Public Global Stellar Network ; September 2015";
+
+    /// The passphrase of the test network.
+    pub const TESTNET: &'static str = "Test SDF Network ; September 2015";
+
+    /// A network with the given passphrase, such as that of a private
+    /// network or a standalone core.
+    pub fn new(passphrase: &str) -> Network {
+        Network {
+            passphrase: passphrase.to_string(),
+        }
+    }
+
+    /// The public network.
+    pub fn public() -> Network {
+        Network::new(Network::PUBLIC)
+    }
+
+    /// The test network.
+    pub fn testnet() -> Network {
+        Network::new(Network::TESTNET)
+    }
+
+    /// The passphrase of the network.
+    pub fn passphrase(&self) -> &str {
+        &self.passphrase
+    }
+
+    /// The id of the network, which transaction hashes commit to.
+    pub fn network_id(&self) -> [u8; 32] {
+        network_id(&self.passphrase)
+    }
+}
+
+/// The id of the network with the given passphrase, which is the SHA-256
+/// hash of the passphrase.
+///
+/// ```
+/// use stellar_client::network::{self, Network};
+///
+/// assert_eq!(network::network_id(Network::TESTNET), Network::testnet().network_id());
+/// ```
+pub fn network_id(passphrase: &str) -> [u8; 32] {
+    let mut id = [0; 32];
+    id.copy_from_slice(&Sha256::digest(passphrase.as_bytes()));
+    id
+}
+
+#[cfg(test)]
+mod network_tests {
+    use super::*;
+    use xdr;
+
+    #[test]
+    fn it_hashes_the_passphrase_into_the_network_id() {
+        assert_eq!(
+            xdr::hex(&network_id(Network::PUBLIC)),
+            "7ac33997544e3175d266bd022439b22cdb16508c01163f26e5cb2a3e1045a979"
+        );
+        assert_eq!(
+            xdr::hex(&Network::testnet().network_id()),
+            "cee0302d59844d32bdca915c8203dd44b33fbb7edc19051ea37abedf28ecd472"
+        );
+    }
+
+    #[test]
+    fn it_keeps_custom_passphrases() {
+        let network = Network::new("Standalone Network ; February 2017");
+        assert_eq!(network.passphrase(), "Standalone Network ; February 2017");
+        assert_ne!(network.network_id(), Network::public().network_id());
+    }
+}
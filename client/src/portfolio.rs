@@ -0,0 +1,305 @@
+//! Values an account's balances in a single quote asset, such as a stable
+//! coin, for dashboards that show what a portfolio is worth.
+//!
+//! Each balance is priced either by asking horizon's path finding what
+//! selling the whole balance would fetch, or from the middle of the spread
+//! in the orderbook between the asset and the quote asset. Path finding
+//! accounts for the depth of the market and routes through other assets,
+//! while mid prices only need one orderbook each and don't move with the
+//! size of the balance.
+//!
+//! ```no_run
+//! use stellar_client::endpoint::account;
+//! use stellar_client::portfolio::{Portfolio, Pricing};
+//! use stellar_client::resources::AssetIdentifier;
+//! use stellar_client::sync::Client;
+//!
+//! let client = Client::horizon().unwrap();
+//! let account = client
+//!     .request(account::Details::new(
+//!         "GATLAI2D7SSH6PE3HXTDPTRM4RE5VRK6HGA63K5EWP75PSANCZRFDNB5",
+//!     ))
+//!     .unwrap();
+//! let usd = AssetIdentifier::alphanum4(
+//!     "USD",
+//!     "GDUKMGUGDZQK6YHYA5Z6AY2G4XDSZPSZ3SW5UN3ARVMO6QSRDWP5YLEX",
+//! );
+//! let portfolio = Portfolio::value(&client, account.balances(), &usd, Pricing::Path).unwrap();
+//! for valuation in portfolio.valuations() {
+//!     println!("{} {:?}", valuation.asset().code(), valuation.value());
+//! }
+//! println!("total {}", portfolio.total());
+//! ```
+use client::sync::Client;
+use endpoint::{orderbook, payment};
+use error::Result;
+use resources::{Amount, AssetIdentifier, Balance, Orderbook, PaymentPath};
+
+/// How balances are priced in the quote asset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pricing {
+    /// What selling the balance would fetch, going by the best path horizon
+    /// finds from the asset to the quote asset.
+    Path,
+    /// The balance at the price halfway between the best bid and the best
+    /// ask in the orderbook of the asset against the quote asset.
+    MidPrice,
+}
+
+/// The value of a single balance in the quote asset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Valuation {
+    asset: AssetIdentifier,
+    balance: Amount,
+    value: Option<Amount>,
+}
+
+impl Valuation {
+    /// Constructs the valuation of a balance, which has no value when the
+    /// asset has no market with the quote asset.
+    pub fn new(asset: AssetIdentifier, balance: Amount, value: Option<Amount>) -> Valuation {
+        Valuation {
+            asset,
+            balance,
+            value,
+        }
+    }
+
+    /// The asset held.
+    pub fn asset(&self) -> &AssetIdentifier {
+        &self.asset
+    }
+
+    /// How much of the asset is held.
+    pub fn balance(&self) -> Amount {
+        self.balance
+    }
+
+    /// The value of the balance in the quote asset, or `None` when it
+    /// couldn't be priced.
+    pub fn value(&self) -> Option<Amount> {
+        self.value
+    }
+}
+
+/// The values of an account's balances in a quote asset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Portfolio {
+    quote: AssetIdentifier,
+    valuations: Vec<Valuation>,
+}
+
+impl Portfolio {
+    /// Values each balance in the quote asset, taking a request for each
+    /// balance other than the quote asset itself and empty balances. Liquidity
+    /// pool shares are left out.
+    pub fn value(
+        client: &Client,
+        balances: &[Balance],
+        quote: &AssetIdentifier,
+        pricing: Pricing,
+    ) -> Result<Portfolio> {
+        let mut valuations = Vec::with_capacity(balances.len());
+        for balance in balances {
+            let asset = match balance.asset() {
+                Some(asset) => asset,
+                None => continue,
+            };
+            let value = if asset == quote {
+                Some(balance.balance())
+            } else if balance.balance() == Amount::new(0) {
+                Some(Amount::new(0))
+            } else {
+                match pricing {
+                    Pricing::Path => {
+                        let endpoint = payment::StrictSend::new(
+                            asset.clone(),
+                            balance.balance(),
+                            vec![quote.clone()],
+                        );
+                        best_path_value(client.request(endpoint)?.records(), quote)
+                    }
+                    Pricing::MidPrice => {
+                        let endpoint =
+                            orderbook::Details::for_asset_pair(asset.clone(), quote.clone());
+                        mid_price_value(&client.request(endpoint)?, balance.balance())
+                    }
+                }
+            };
+            valuations.push(Valuation::new(asset.clone(), balance.balance(), value));
+        }
+        Ok(Portfolio::new(quote.clone(), valuations))
+    }
+
+    /// Constructs a portfolio from valuations made separately.
+    pub fn new(quote: AssetIdentifier, valuations: Vec<Valuation>) -> Portfolio {
+        Portfolio { quote, valuations }
+    }
+
+    /// The asset the balances are valued in.
+    pub fn quote(&self) -> &AssetIdentifier {
+        &self.quote
+    }
+
+    /// The value of each balance.
+    pub fn valuations(&self) -> &[Valuation] {
+        &self.valuations
+    }
+
+    /// The total value of the balances that could be priced.
+    pub fn total(&self) -> Amount {
+        self.valuations
+            .iter()
+            .filter_map(Valuation::value)
+            .fold(Amount::new(0), |total, value| &total + &value)
+    }
+
+    /// Whether every balance could be priced, so that the total is the value
+    /// of the whole portfolio.
+    pub fn is_complete(&self) -> bool {
+        self.valuations
+            .iter()
+            .all(|valuation| valuation.value().is_some())
+    }
+}
+
+/// The most of the quote asset any of the paths deliver.
+fn best_path_value(paths: &[PaymentPath], quote: &AssetIdentifier) -> Option<Amount> {
+    paths
+        .iter()
+        .filter(|path| path.destination_asset() == quote)
+        .map(|path| *path.destination_amount())
+        .max()
+}
+
+/// The amount of the base asset at the price between the best bid and ask.
+fn mid_price_value(orderbook: &Orderbook, amount: Amount) -> Option<Amount> {
+    let bid = orderbook.bids().first()?.price_ratio();
+    let ask = orderbook.asks().first()?.price_ratio();
+    let (bid_n, bid_d) = (u128::from(bid.numerator()), u128::from(bid.denominator()));
+    let (ask_n, ask_d) = (u128::from(ask.numerator()), u128::from(ask.denominator()));
+    let numerator = (bid_n * ask_d + ask_n * bid_d).checked_mul(amount.stroops() as u128)?;
+    let denominator = 2 * bid_d * ask_d;
+    if denominator == 0 {
+        return None;
+    }
+    let value = numerator / denominator;
+    if value > i64::MAX as u128 {
+        None
+    } else {
+        Some(Amount::new(value as i64))
+    }
+}
+
+#[cfg(test)]
+mod portfolio_tests {
+    use super::*;
+    use client::sync::test_server::TestServer;
+    use serde_json;
+
+    fn foo() -> AssetIdentifier {
+        AssetIdentifier::alphanum4(
+            "FOO",
+            "GBAUUA74H4XOQYRSOW2RZUA4QL5PB37U3JS5NE3RTB2ELJVMIF5RLMAG",
+        )
+    }
+
+    fn orderbook() -> Orderbook {
+        serde_json::from_str(include_str!("../fixtures/orderbook.json")).unwrap()
+    }
+
+    #[test]
+    fn it_values_at_the_mid_price() {
+        // Halfway between 7.7200005 and 7.76.
+        assert_eq!(
+            mid_price_value(&orderbook(), Amount::new(100_000_000)),
+            Some(Amount::new(774_000_026))
+        );
+    }
+
+    #[test]
+    fn it_needs_both_sides_of_the_book_for_a_mid_price() {
+        let json = include_str!("../fixtures/orderbook.json");
+        let start = json.find("\"asks\"").unwrap();
+        let end = json.find("\"base\"").unwrap();
+        let one_sided = format!("{}\"asks\": [],\n  {}", &json[..start], &json[end..]);
+        let orderbook: Orderbook = serde_json::from_str(&one_sided).unwrap();
+        assert_eq!(mid_price_value(&orderbook, Amount::new(1)), None);
+    }
+
+    #[test]
+    fn it_takes_the_best_path() {
+        let eur = AssetIdentifier::alphanum4(
+            "EUR",
+            "GDSBCQO34HWPGUGQSP3QBFEXVTSR2PW46UIGTHVWGWJGQKH3AFNHXHXN",
+        );
+        let usd = AssetIdentifier::alphanum4(
+            "USD",
+            "GDSBCQO34HWPGUGQSP3QBFEXVTSR2PW46UIGTHVWGWJGQKH3AFNHXHXN",
+        );
+        let path = |amount| {
+            PaymentPath::new(
+                vec![],
+                Amount::new(10),
+                Amount::new(amount),
+                eur.clone(),
+                usd.clone(),
+            )
+            .unwrap()
+        };
+        let paths = vec![path(20), path(35), path(30)];
+        assert_eq!(best_path_value(&paths, &eur), Some(Amount::new(35)));
+        assert_eq!(best_path_value(&paths, &usd), None);
+        assert_eq!(best_path_value(&[], &eur), None);
+    }
+
+    #[test]
+    fn it_values_a_portfolio() {
+        let server = TestServer::start("200 OK", include_str!("../fixtures/orderbook.json"));
+        let client = Client::new(server.uri()).unwrap();
+        let balances = vec![
+            Balance::new(AssetIdentifier::native(), Amount::new(100_000_000)),
+            Balance::new(foo(), Amount::new(5_000_000)),
+            Balance::new(
+                AssetIdentifier::alphanum4(
+                    "BAR",
+                    "GBAUUA74H4XOQYRSOW2RZUA4QL5PB37U3JS5NE3RTB2ELJVMIF5RLMAG",
+                ),
+                Amount::new(0),
+            ),
+            Balance::liquidity_pool_shares("abcdef", Amount::new(1)),
+        ];
+
+        let portfolio = Portfolio::value(&client, &balances, &foo(), Pricing::MidPrice).unwrap();
+        assert_eq!(server.hits(), 1);
+        assert_eq!(portfolio.valuations().len(), 3);
+        assert_eq!(
+            portfolio.valuations()[0].value(),
+            Some(Amount::new(774_000_026))
+        );
+        assert_eq!(
+            portfolio.valuations()[1].value(),
+            Some(Amount::new(5_000_000))
+        );
+        assert_eq!(portfolio.valuations()[2].value(), Some(Amount::new(0)));
+        assert_eq!(portfolio.total(), Amount::new(779_000_026));
+        assert!(portfolio.is_complete());
+    }
+
+    #[test]
+    fn it_totals_what_could_be_priced() {
+        let portfolio = Portfolio::new(
+            foo(),
+            vec![
+                Valuation::new(
+                    AssetIdentifier::native(),
+                    Amount::new(10),
+                    Some(Amount::new(70)),
+                ),
+                Valuation::new(foo(), Amount::new(10), None),
+            ],
+        );
+        assert_eq!(portfolio.total(), Amount::new(70));
+        assert!(!portfolio.is_complete());
+    }
+}
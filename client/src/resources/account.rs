@@ -1,22 +1,149 @@
-use super::deserialize;
+use super::{deserialize, serialize};
+use chrono::prelude::*;
 use resources::base64string::Base64String;
+use resources::links::Links;
+use resources::{AccountLinks, Amount, AssetIdentifier, Balance, Ledger, SignedAmount};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::str::Utf8Error;
 
 /// In the Stellar network, users interact using accounts which can be controlled by a
 /// corresponding keypair that can authorize transactions.
 ///
 /// <https://www.stellar.org/developers/horizon/reference/resources/account.html>
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Account {
     id: String,
     account_id: String,
-    #[serde(deserialize_with = "deserialize::from_str")]
-    sequence: u64,
+    #[serde(serialize_with = "serialize::to_string",
+            deserialize_with = "deserialize::from_number_or_str")]
+    sequence: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sequence_ledger: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none",
+            serialize_with = "serialize::optional_to_string",
+            deserialize_with = "deserialize::optional_from_number_or_str")]
+    sequence_time: Option<i64>,
     subentry_count: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    inflation_destination: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sponsor: Option<String>,
+    #[serde(default)]
+    num_sponsoring: u64,
+    #[serde(default)]
+    num_sponsored: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none",
+            deserialize_with = "deserialize::non_empty_string")]
+    home_domain: Option<String>,
+    #[serde(default)]
+    last_modified_ledger: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_modified_time: Option<DateTime<Utc>>,
+    #[serde(default)]
+    thresholds: AccountThresholds,
+    balances: Vec<Balance>,
+    #[serde(default)]
+    signers: Vec<AccountSigner>,
     data: HashMap<String, Base64String>,
+    #[serde(rename = "_links", default, skip_serializing_if = "Links::is_none")]
+    links: Links<AccountLinks>,
 }
 
 impl Account {
+    /// An account with the given id and sequence number, without any
+    /// subentries, balances, or data. The `with_` methods fill in the rest,
+    /// which is handy for tests and mock servers.
+    ///
+    /// ```
+    /// use stellar_client::resources::{Account, Amount, AssetIdentifier, Balance};
+    ///
+    /// let account = Account::new("GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ6", 1)
+    ///     .with_balance(Balance::new(AssetIdentifier::native(), Amount::new(100_000_000)));
+    /// assert_eq!(account.balances().len(), 1);
+    /// ```
+    pub fn new(account_id: &str, sequence: i64) -> Account {
+        Account {
+            id: account_id.to_string(),
+            account_id: account_id.to_string(),
+            sequence,
+            sequence_ledger: None,
+            sequence_time: None,
+            subentry_count: 0,
+            inflation_destination: None,
+            sponsor: None,
+            num_sponsoring: 0,
+            num_sponsored: 0,
+            home_domain: None,
+            last_modified_ledger: 0,
+            last_modified_time: None,
+            thresholds: AccountThresholds::default(),
+            balances: Vec::new(),
+            signers: Vec::new(),
+            data: HashMap::new(),
+            links: Links::none(),
+        }
+    }
+
+    /// Sets the number of account subentries.
+    pub fn with_subentry_count(mut self, subentry_count: u64) -> Account {
+        self.subentry_count = subentry_count;
+        self
+    }
+
+    /// Sets the ledger and time, in seconds since the epoch, of the account's
+    /// last sequence number bump.
+    pub fn with_sequence_bumped(mut self, ledger: u32, time: i64) -> Account {
+        self.sequence_ledger = Some(ledger);
+        self.sequence_time = Some(time);
+        self
+    }
+
+    /// Sets the account that receives the account's inflation votes.
+    pub fn with_inflation_destination(mut self, account_id: &str) -> Account {
+        self.inflation_destination = Some(account_id.to_string());
+        self
+    }
+
+    /// Sets the account paying the reserve for this account.
+    pub fn with_sponsor(mut self, account_id: &str) -> Account {
+        self.sponsor = Some(account_id.to_string());
+        self
+    }
+
+    /// Sets the ledger, and when it closed, that last changed the account.
+    pub fn with_last_modified(mut self, ledger: u32, time: DateTime<Utc>) -> Account {
+        self.last_modified_ledger = ledger;
+        self.last_modified_time = Some(time);
+        self
+    }
+
+    /// Sets the number of reserves this account sponsors for others.
+    pub fn with_num_sponsoring(mut self, num_sponsoring: u64) -> Account {
+        self.num_sponsoring = num_sponsoring;
+        self
+    }
+
+    /// Sets the number of this account's reserves sponsored by others.
+    pub fn with_num_sponsored(mut self, num_sponsored: u64) -> Account {
+        self.num_sponsored = num_sponsored;
+        self
+    }
+
+    /// Adds a balance to the end of the account's balances.
+    pub fn with_balance(mut self, balance: Balance) -> Account {
+        self.balances.push(balance);
+        self
+    }
+
+    /// Attaches a data entry to the account. The value is the decoded value,
+    /// not its base64 encoding.
+    pub fn with_data(mut self, key: &str, value: &str) -> Account {
+        self.data
+            .insert(key.to_string(), Base64String::new(value.to_string()));
+        self
+    }
+
     /// The canonical id of this account, suitable for use as the :id parameter
     /// for url templates that require an account’s ID. Returns a slice that lives
     /// as long as the account does.
@@ -30,6 +157,24 @@ impl Account {
         &self.account_id
     }
 
+    /// Sets the domain hosting the account's stellar.toml.
+    pub fn with_home_domain(mut self, home_domain: &str) -> Account {
+        self.home_domain = Some(home_domain.to_string());
+        self
+    }
+
+    /// Sets the weights the account's signatures have to add up to.
+    pub fn with_thresholds(mut self, thresholds: AccountThresholds) -> Account {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Adds a signer to the account.
+    pub fn with_signer(mut self, signer: AccountSigner) -> Account {
+        self.signers.push(signer);
+        self
+    }
+
     /// The canonical id of this account, suitable for use as the :id parameter
     /// for url templates that require an account’s ID.
     pub fn id(&self) -> &String {
@@ -43,18 +188,480 @@ impl Account {
 
     /// The current sequence number that can be used when submitting a transaction
     /// from this account.
-    pub fn sequence(&self) -> u64 {
+    pub fn sequence(&self) -> i64 {
         self.sequence
     }
 
+    /// The ledger in which the account's sequence number was last bumped,
+    /// if horizon reports it. Sequence number preconditions use it to hold
+    /// a transaction back until enough ledgers have closed.
+    pub fn sequence_ledger(&self) -> Option<u32> {
+        self.sequence_ledger
+    }
+
+    /// When the account's sequence number was last bumped, if horizon
+    /// reports it and it is a time that can be represented.
+    pub fn sequence_time(&self) -> Option<DateTime<Utc>> {
+        self.sequence_time
+            .and_then(|secs| Utc.timestamp_opt(secs, 0).single())
+    }
+
     /// The number of account subentries.  This number is multiplied by
     /// 0.5 to determine the minimum required balance.
     pub fn subentry_count(&self) -> u64 {
         self.subentry_count
     }
 
+    /// The number of reserves this account is paying for on behalf of
+    /// other accounts.
+    pub fn num_sponsoring(&self) -> u64 {
+        self.num_sponsoring
+    }
+
+    /// The number of this account's reserves that other accounts are
+    /// paying for.
+    pub fn num_sponsored(&self) -> u64 {
+        self.num_sponsored
+    }
+
+    /// The domain hosting the account's stellar.toml, if the account has
+    /// set one. Issuers describe their assets there, see
+    /// `issuer::issuer_info`.
+    pub fn home_domain(&self) -> Option<&str> {
+        self.home_domain.as_ref().map(String::as_str)
+    }
+
+    /// The account that receives the account's inflation votes, if it has
+    /// set one.
+    pub fn inflation_destination(&self) -> Option<&str> {
+        self.inflation_destination.as_ref().map(String::as_str)
+    }
+
+    /// The account paying the base reserve for this account, if it is
+    /// sponsored.
+    pub fn sponsor(&self) -> Option<&str> {
+        self.sponsor.as_ref().map(String::as_str)
+    }
+
+    /// The sequence of the ledger that last changed the account. Zero for an
+    /// account built with `new`.
+    pub fn last_modified_ledger(&self) -> u32 {
+        self.last_modified_ledger
+    }
+
+    /// When the ledger that last changed the account closed, if horizon
+    /// reports it.
+    pub fn last_modified_time(&self) -> Option<DateTime<Utc>> {
+        self.last_modified_time
+    }
+
+    /// The weights the account's signatures have to add up to for low,
+    /// medium and high threshold operations.
+    pub fn thresholds(&self) -> AccountThresholds {
+        self.thresholds
+    }
+
+    /// The keys that can sign for the account, including its master key
+    /// unless that has been given a weight of zero.
+    pub fn signers(&self) -> &[AccountSigner] {
+        &self.signers
+    }
+
+    /// The signer with the given key, if it can sign for the account.
+    pub fn signer(&self, key: &str) -> Option<&AccountSigner> {
+        self.signers.iter().find(|signer| signer.key() == key)
+    }
+
+    /// The balances of lumens, trusted assets, and liquidity pool shares
+    /// held by this account.
+    pub fn balances(&self) -> &[Balance] {
+        &self.balances
+    }
+
+    /// The balance held of the given asset, if the account holds it.
+    pub fn balance(&self, asset: &AssetIdentifier) -> Option<&Balance> {
+        self.balances
+            .iter()
+            .find(|balance| balance.asset() == Some(asset))
+    }
+
     /// A key/value store of data attached to this account.
     pub fn data(&self) -> &HashMap<String, Base64String> {
         &self.data
     }
+
+    /// The decoded bytes of the data entry under the key, if there is one.
+    pub fn data_bytes(&self, key: &str) -> Option<&[u8]> {
+        self.data.get(key).map(Base64String::as_bytes)
+    }
+
+    /// The decoded value of the data entry under the key as utf8. Values
+    /// that aren't valid utf8 are an error, see `data_utf8_lossy`.
+    pub fn data_utf8(&self, key: &str) -> Option<Result<&str, Utf8Error>> {
+        self.data.get(key).map(Base64String::to_utf8)
+    }
+
+    /// The decoded value of the data entry under the key as utf8, with
+    /// invalid sequences replaced by `�`.
+    pub fn data_utf8_lossy(&self, key: &str) -> Option<Cow<str>> {
+        self.data.get(key).map(Base64String::to_utf8_lossy)
+    }
+
+    /// The links horizon sent with the account, to its transactions,
+    /// operations, payments, effects, offers, trades and data. `None` for an
+    /// account built with `new`.
+    pub fn links(&self) -> Option<&AccountLinks> {
+        self.links.get()
+    }
+
+    /// The fewest lumens this account must hold, using the base reserve of
+    /// the given ledger. Fetch the latest ledger with `ledger::All::latest`
+    /// to use the network's current reserve.
+    ///
+    /// The account pays two reserves for itself, one for each subentry and
+    /// one for each reserve it sponsors, less the reserves sponsored for it.
+    pub fn minimum_balance(&self, ledger: &Ledger) -> Amount {
+        self.reserve(ledger.base_reserve_as_amount())
+    }
+
+    /// The lumens this account can spend, given the network's base reserve.
+    /// This is the lumen balance less what open offers are selling and the
+    /// account's minimum balance. It is negative when the account is below
+    /// its reserve.
+    pub fn available_balance(&self, base_reserve: Amount) -> SignedAmount {
+        let native = self.balance(&AssetIdentifier::native());
+        let balance = native.map(Balance::balance).unwrap_or_else(|| Amount::new(0));
+        let selling = native
+            .map(Balance::selling_liabilities)
+            .unwrap_or_else(|| Amount::new(0));
+        &(&SignedAmount::from(balance) - &selling) - &self.reserve(base_reserve)
+    }
+
+    /// The reserve, capped at the most stroops an amount can hold for
+    /// counts too large for any real account.
+    fn reserve(&self, base_reserve: Amount) -> Amount {
+        let entries = self
+            .subentry_count
+            .saturating_add(self.num_sponsoring)
+            .saturating_add(2)
+            .saturating_sub(self.num_sponsored);
+        let entries = entries.min(i64::MAX as u64) as i64;
+        Amount::new(base_reserve.stroops().saturating_mul(entries))
+    }
+}
+
+/// The weights an account's signatures have to add up to for an operation
+/// to be authorized. Each operation needs the low, medium or high threshold
+/// depending on what it does.
+///
+/// <https://www.stellar.org/developers/guides/concepts/multi-sig.html#thresholds>
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AccountThresholds {
+    low_threshold: u8,
+    med_threshold: u8,
+    high_threshold: u8,
+}
+
+impl AccountThresholds {
+    /// Thresholds of the given low, medium and high weights.
+    pub fn new(low: u8, med: u8, high: u8) -> AccountThresholds {
+        AccountThresholds {
+            low_threshold: low,
+            med_threshold: med,
+            high_threshold: high,
+        }
+    }
+
+    /// The weight needed for low threshold operations, such as allowing
+    /// trust.
+    pub fn low(&self) -> u8 {
+        self.low_threshold
+    }
+
+    /// The weight needed for medium threshold operations, such as payments.
+    pub fn med(&self) -> u8 {
+        self.med_threshold
+    }
+
+    /// The weight needed for high threshold operations, such as changing
+    /// signers or merging the account.
+    pub fn high(&self) -> u8 {
+        self.high_threshold
+    }
+}
+
+/// A key that can sign for an account, and the weight its signature adds
+/// towards the account's thresholds.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AccountSigner {
+    weight: u8,
+    key: String,
+    #[serde(rename = "type")]
+    signer_type: String,
+}
+
+impl AccountSigner {
+    /// A signer of the type its strkey encoded key is for: an ed25519 key,
+    /// a pre-authorized transaction, a hash or a signed payload.
+    ///
+    /// ```
+    /// use stellar_client::resources::AccountSigner;
+    ///
+    /// let signer = AccountSigner::new(
+    ///     "TBU2RRGLXH3E5CQHTD3ODLDF2BWDCYUSSBLLZ5GNW7JXHDIYKXZWHXL7",
+    ///     1,
+    /// );
+    /// assert_eq!(signer.signer_type(), "preauth_tx");
+    /// ```
+    pub fn new(key: &str, weight: u8) -> AccountSigner {
+        let signer_type = match key.chars().next() {
+            Some('T') => "preauth_tx",
+            Some('X') => "sha256_hash",
+            Some('P') => "ed25519_signed_payload",
+            _ => "ed25519_public_key",
+        };
+        AccountSigner {
+            weight,
+            key: key.to_string(),
+            signer_type: signer_type.to_string(),
+        }
+    }
+
+    /// An ed25519 public key signer, such as another account's key.
+    pub fn ed25519(key: &str, weight: u8) -> AccountSigner {
+        AccountSigner {
+            weight,
+            key: key.to_string(),
+            signer_type: "ed25519_public_key".to_string(),
+        }
+    }
+
+    /// The strkey encoded key of the signer. Ed25519 keys start with a `G`,
+    /// pre-authorized transactions with a `T` and hashes with an `X`.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// The weight the signer's signature adds.
+    pub fn weight(&self) -> u8 {
+        self.weight
+    }
+
+    /// The type of key, such as `ed25519_public_key`, `preauth_tx` or
+    /// `sha256_hash`.
+    pub fn signer_type(&self) -> &str {
+        &self.signer_type
+    }
+
+    /// Whether the key is an ed25519 public key, which signs with
+    /// signatures rather than being met by a hash.
+    pub fn is_ed25519(&self) -> bool {
+        self.signer_type == "ed25519_public_key"
+    }
+}
+
+#[cfg(test)]
+mod account_tests {
+    use super::*;
+    use serde_json;
+
+    fn account_json() -> String {
+        include_str!("../../fixtures/account.json").to_string()
+    }
+
+    #[test]
+    fn it_parses_balances() {
+        let account: Account = serde_json::from_str(&account_json()).unwrap();
+        assert_eq!(account.sequence(), 2394452857640034);
+        assert_eq!(account.balances().len(), 3);
+
+        let usd = AssetIdentifier::alphanum4(
+            "USD",
+            "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3",
+        );
+        let balance = account.balance(&usd).unwrap();
+        assert_eq!(balance.balance(), Amount::new(1_000_000_000));
+        assert_eq!(balance.limit(), Some(Amount::new(9_223_372_036_854_775_807)));
+        assert_eq!(balance.buying_liabilities(), Amount::new(20_000_000));
+
+        let shares = &account.balances()[1];
+        assert_eq!(shares.asset(), None);
+        assert_eq!(
+            shares.liquidity_pool_id(),
+            Some("dd7b1ab831c273310ddbec6f97870aa83c2fbd78ce22aded37ecbf4f3380fac7")
+        );
+    }
+
+    #[test]
+    fn it_computes_the_available_balance() {
+        let account: Account = serde_json::from_str(&account_json()).unwrap();
+        // 25 XLM less 5 XLM selling liabilities and (2 + 3 + 2 - 1) * 0.5 XLM reserve
+        assert_eq!(
+            account.available_balance(Amount::new(5_000_000)),
+            SignedAmount::new(170_000_000)
+        );
+    }
+
+    #[test]
+    fn it_goes_negative_below_the_reserve() {
+        let account: Account = serde_json::from_str(&account_json()).unwrap();
+        let available = account.available_balance(Amount::new(50_000_000));
+        assert!(available.is_negative());
+        assert_eq!(format!("{}", available), "-10.0000000");
+    }
+
+    #[test]
+    fn it_caps_the_reserve_instead_of_overflowing() {
+        let id = "GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ6";
+        let account = Account::new(id, 1)
+            .with_subentry_count(u64::MAX)
+            .with_num_sponsoring(u64::MAX);
+        let reserve = Amount::new(5_000_000);
+        assert_eq!(account.reserve(reserve), Amount::new(i64::MAX));
+        assert_eq!(
+            account.available_balance(reserve),
+            SignedAmount::new(-i64::MAX)
+        );
+    }
+
+    #[test]
+    fn it_computes_the_minimum_balance_from_the_ledger() {
+        let account: Account = serde_json::from_str(&account_json()).unwrap();
+        let ledger: Ledger =
+            serde_json::from_str(include_str!("../../fixtures/ledger.json")).unwrap();
+        assert_eq!(account.num_sponsoring(), 2);
+        assert_eq!(account.num_sponsored(), 1);
+        assert_eq!(account.minimum_balance(&ledger), Amount::new(600_000_000));
+    }
+
+    #[test]
+    fn it_builds_the_same_account_as_horizon_returns() {
+        let parsed: Account = serde_json::from_str(&account_json()).unwrap();
+        let usd = AssetIdentifier::alphanum4(
+            "USD",
+            "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3",
+        );
+        let max = Amount::new(9_223_372_036_854_775_807);
+        let built = Account::new(
+            "GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ6",
+            2394452857640034,
+        ).with_subentry_count(3)
+            .with_signer(AccountSigner::ed25519(
+                "GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ6",
+                1,
+            ))
+            .with_num_sponsoring(2)
+            .with_num_sponsored(1)
+            .with_sequence_bumped(16751280, 1707931250)
+            .with_inflation_destination("GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3")
+            .with_sponsor("GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3")
+            .with_last_modified(16751283, Utc.ymd(2024, 2, 14).and_hms(17, 21, 5))
+            .with_balance(
+                Balance::new(usd, Amount::new(1_000_000_000))
+                    .with_limit(max)
+                    .with_buying_liabilities(Amount::new(20_000_000))
+                    .with_selling_liabilities(Amount::new(0)),
+            )
+            .with_balance(
+                Balance::liquidity_pool_shares(
+                    "dd7b1ab831c273310ddbec6f97870aa83c2fbd78ce22aded37ecbf4f3380fac7",
+                    Amount::new(125_000_000),
+                ).with_limit(max),
+            )
+            .with_balance(
+                Balance::new(AssetIdentifier::native(), Amount::new(250_000_000))
+                    .with_buying_liabilities(Amount::new(0))
+                    .with_selling_liabilities(Amount::new(50_000_000)),
+            );
+        assert_eq!(built, parsed);
+        assert_ne!(built.with_data("name", "alice"), parsed);
+    }
+
+    #[test]
+    fn it_parses_provenance_and_sponsorship() {
+        let account: Account = serde_json::from_str(&account_json()).unwrap();
+        assert_eq!(account.sequence_ledger(), Some(16751280));
+        assert_eq!(
+            account.sequence_time(),
+            Some(Utc.ymd(2024, 2, 14).and_hms(17, 20, 50))
+        );
+        assert_eq!(account.last_modified_ledger(), 16751283);
+        assert_eq!(
+            account.last_modified_time(),
+            Some(Utc.ymd(2024, 2, 14).and_hms(17, 21, 5))
+        );
+        assert_eq!(
+            account.sponsor(),
+            Some("GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3")
+        );
+        assert_eq!(
+            account.inflation_destination(),
+            Some("GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3")
+        );
+    }
+
+    #[test]
+    fn it_leaves_out_sequence_times_out_of_range() {
+        let json = account_json().replace(
+            "\"sequence_time\": \"1707931250\",",
+            "\"sequence_time\": \"9223372036854775807\",",
+        );
+        let account: Account = serde_json::from_str(&json).unwrap();
+        assert_eq!(account.sequence_time(), None);
+    }
+
+    #[test]
+    fn it_leaves_out_what_older_horizons_do_not_send() {
+        let json = account_json()
+            .replace("\"sequence_ledger\": 16751280,", "")
+            .replace("\"sequence_time\": \"1707931250\",", "")
+            .replace(
+                "\"last_modified_ledger\": 16751283,\n  \"last_modified_time\": \"2024-02-14T17:21:05Z\",",
+                "",
+            );
+        let account: Account = serde_json::from_str(&json).unwrap();
+        assert_eq!(account.sequence_ledger(), None);
+        assert_eq!(account.sequence_time(), None);
+        assert_eq!(account.last_modified_ledger(), 0);
+        assert_eq!(account.last_modified_time(), None);
+        let json = serde_json::to_string(&account).unwrap();
+        assert!(!json.contains("sequence_time"));
+    }
+
+    #[test]
+    fn it_parses_signers_and_thresholds() {
+        let json = account_json().replace("\"med_threshold\": 0", "\"med_threshold\": 2");
+        let account: Account = serde_json::from_str(&json).unwrap();
+        assert_eq!(account.thresholds(), AccountThresholds::new(0, 2, 0));
+        assert_eq!(account.signers().len(), 1);
+        let signer = account
+            .signer("GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ6")
+            .unwrap();
+        assert_eq!(signer.weight(), 1);
+        assert!(signer.is_ed25519());
+        assert_eq!(account.signer(account.signers()[0].key()), Some(signer));
+    }
+
+    #[test]
+    fn it_decodes_data_entries() {
+        let json = account_json().replace(
+            "\"data\": {}",
+            "\"data\": { \"name\": \"YWxpY2U=\", \"raw\": \"/w==\" }",
+        );
+        let account: Account = serde_json::from_str(&json).unwrap();
+        assert_eq!(account.data_bytes("name"), Some(&b"alice"[..]));
+        assert_eq!(account.data_utf8("name"), Some(Ok("alice")));
+        assert!(account.data_utf8("raw").unwrap().is_err());
+        assert_eq!(account.data_utf8_lossy("raw").unwrap(), "\u{fffd}");
+        assert_eq!(account.data_bytes("missing"), None);
+        assert_eq!(account.with_data("name", "bob").data_utf8("name"), Some(Ok("bob")));
+    }
+
+    #[test]
+    fn it_round_trips_through_json() {
+        let account: Account = serde_json::from_str(&account_json()).unwrap();
+        let json = serde_json::to_string(&account).unwrap();
+        let reparsed: Account = serde_json::from_str(&json).unwrap();
+        assert_eq!(reparsed.balances(), account.balances());
+    }
 }
@@ -1,5 +1,6 @@
 use serde::de;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::error::Error as StdError;
 use std::fmt;
 use std::ops::{Add, Sub};
 use std::str::FromStr;
@@ -10,7 +11,7 @@ use std::str::FromStr;
 /// in this module.
 ///
 /// <https://www.stellar.org/developers/guides/concepts/assets.html#amount-precision-and-representation>
-#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash)]
 pub struct Amount(i64);
 
 impl Amount {
@@ -19,6 +20,53 @@ impl Amount {
         assert!(amount >= 0);
         Amount(amount)
     }
+
+    pub(crate) fn stroops(&self) -> i64 {
+        self.0
+    }
+}
+
+/// An amount that may be negative. Horizon never reports one, but values
+/// computed from amounts can be, such as a balance after its liabilities
+/// and reserve are taken out.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash)]
+pub struct SignedAmount(i64);
+
+impl SignedAmount {
+    /// Create a signed amount from a number of stroops
+    pub fn new(amount: i64) -> SignedAmount {
+        SignedAmount(amount)
+    }
+
+    /// Returns true if the amount is below zero
+    pub fn is_negative(&self) -> bool {
+        self.0 < 0
+    }
+
+    /// Adds two signed amounts, or `None` if the sum overflows
+    pub fn checked_add(&self, other: &SignedAmount) -> Option<SignedAmount> {
+        self.0.checked_add(other.0).map(SignedAmount)
+    }
+
+    /// Subtracts a signed amount, or `None` if the difference overflows
+    pub fn checked_sub(&self, other: &SignedAmount) -> Option<SignedAmount> {
+        self.0.checked_sub(other.0).map(SignedAmount)
+    }
+
+    /// Converts back into an amount, or `None` if this is negative
+    pub fn to_amount(&self) -> Option<Amount> {
+        if self.is_negative() {
+            None
+        } else {
+            Some(Amount(self.0))
+        }
+    }
+}
+
+impl From<Amount> for SignedAmount {
+    fn from(amount: Amount) -> SignedAmount {
+        SignedAmount(amount.0)
+    }
 }
 
 #[cfg(test)]
@@ -59,6 +107,37 @@ impl<'a> Sub for &'a Amount {
     }
 }
 
+impl<'a> Add for &'a SignedAmount {
+    type Output = SignedAmount;
+
+    /// Adding two signed amounts returns their sum, saturating at the
+    /// bounds of an `i64`. Use `checked_add` to catch overflow.
+    fn add(self, other: &SignedAmount) -> SignedAmount {
+        SignedAmount(self.0.saturating_add(other.0))
+    }
+}
+
+impl<'a> Sub for &'a SignedAmount {
+    type Output = SignedAmount;
+
+    /// Subtracting two signed amounts returns their difference, which may
+    /// be negative, saturating at the bounds of an `i64`. Use `checked_sub`
+    /// to catch overflow.
+    fn sub(self, other: &SignedAmount) -> SignedAmount {
+        SignedAmount(self.0.saturating_sub(other.0))
+    }
+}
+
+impl<'a> Sub<&'a Amount> for &'a SignedAmount {
+    type Output = SignedAmount;
+
+    /// Subtracting an amount from a signed amount returns their difference,
+    /// which may be negative, saturating at the bounds of an `i64`
+    fn sub(self, other: &Amount) -> SignedAmount {
+        SignedAmount(self.0.saturating_sub(other.0))
+    }
+}
+
 #[cfg(test)]
 mod amount_ops_tests {
     use super::*;
@@ -70,6 +149,26 @@ mod amount_ops_tests {
     fn it_can_subtract_amounts() {
         assert_eq!(&Amount::new(12) - &Amount::new(6), Amount(6));
     }
+    #[test]
+    fn it_can_subtract_below_zero_with_signed_amounts() {
+        let difference = &SignedAmount::from(Amount::new(6)) - &Amount::new(12);
+        assert_eq!(difference, SignedAmount(-6));
+        assert!(difference.is_negative());
+        assert_eq!(difference.to_amount(), None);
+        assert_eq!((&difference + &SignedAmount::new(8)).to_amount(), Some(Amount(2)));
+    }
+    #[test]
+    fn it_catches_or_saturates_signed_overflow() {
+        let max = SignedAmount::new(i64::MAX);
+        let min = SignedAmount::new(i64::MIN);
+        let one = SignedAmount::new(1);
+        assert_eq!(max.checked_add(&one), None);
+        assert_eq!(min.checked_sub(&one), None);
+        assert_eq!(max.checked_sub(&one), Some(SignedAmount(i64::MAX - 1)));
+        assert_eq!(&max + &one, max);
+        assert_eq!(&min - &one, min);
+        assert_eq!(&min - &Amount::new(1), min);
+    }
 }
 
 /// We convert amounts to their stroop values (multiply by 10^7) on serialization.
@@ -77,22 +176,35 @@ mod amount_ops_tests {
 /// in the unit values, so we divide by 10^7
 impl fmt::Display for Amount {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut raw_amount = self.0.to_string();
-        let mut result_string = String::with_capacity(20);
-        let raw_digit_count = raw_amount.len();
-        if raw_digit_count < 8 {
-            result_string.push_str("0.");
-            let decimal_padding = 7 - raw_digit_count;
-            (0..decimal_padding).for_each(|_| result_string.push_str("0"));
-        } else {
-            let period_index = raw_digit_count - 7;
-            raw_amount.insert(period_index, '.');
+        write!(f, "{}", format_stroops(self.0 as u64))
+    }
+}
+
+impl fmt::Display for SignedAmount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_negative() {
+            write!(f, "-")?;
         }
-        result_string.push_str(&raw_amount);
-        write!(f, "{}", result_string)
+        write!(f, "{}", format_stroops(self.0.unsigned_abs()))
     }
 }
 
+fn format_stroops(stroops: u64) -> String {
+    let mut raw_amount = stroops.to_string();
+    let mut result_string = String::with_capacity(20);
+    let raw_digit_count = raw_amount.len();
+    if raw_digit_count < 8 {
+        result_string.push_str("0.");
+        let decimal_padding = 7 - raw_digit_count;
+        (0..decimal_padding).for_each(|_| result_string.push_str("0"));
+    } else {
+        let period_index = raw_digit_count - 7;
+        raw_amount.insert(period_index, '.');
+    }
+    result_string.push_str(&raw_amount);
+    result_string
+}
+
 #[cfg(test)]
 mod display_amount_tests {
     use super::*;
@@ -104,6 +216,11 @@ mod display_amount_tests {
     fn it_displays_large_numbers_as_float() {
         assert_eq!(format!("{}", Amount(123456789)), "12.3456789");
     }
+    #[test]
+    fn it_displays_negative_signed_amounts() {
+        assert_eq!(format!("{}", SignedAmount(-123456789)), "-12.3456789");
+        assert_eq!(format!("{}", SignedAmount(10)), "0.0000010");
+    }
 }
 
 /// Converts internally stored stroop value of asset amount into full unit amount
@@ -118,6 +235,15 @@ impl Serialize for Amount {
     }
 }
 
+impl Serialize for SignedAmount {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
 #[cfg(test)]
 mod serialize_amount_tests {
     use super::*;
@@ -129,42 +255,99 @@ mod serialize_amount_tests {
     }
 }
 
+/// The error returned when parsing a string that isn't an amount, which is
+/// a non-negative decimal with at most 7 digits after the point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseAmountError {
+    kind: ParseAmountErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ParseAmountErrorKind {
+    Invalid,
+    TooPrecise,
+}
+
+impl StdError for ParseAmountError {
+    fn description(&self) -> &str {
+        match self.kind {
+            ParseAmountErrorKind::Invalid => "Failed to parse string field",
+            ParseAmountErrorKind::TooPrecise => "Amount has too many digits of precision.",
+        }
+    }
+}
+
+impl fmt::Display for ParseAmountError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+/// Parses a non-negative decimal such as `12.5`, as typed by a user or sent
+/// by horizon, into stroops. Signs, exponents and more than 7 decimal places
+/// are rejected.
+impl FromStr for Amount {
+    type Err = ParseAmountError;
+
+    fn from_str(s: &str) -> Result<Amount, ParseAmountError> {
+        let invalid = ParseAmountError {
+            kind: ParseAmountErrorKind::Invalid,
+        };
+        let (whole, decimals) = match s.rfind('.') {
+            None => (s, ""),
+            Some(decimal_place) => (&s[..decimal_place], &s[decimal_place + 1..]),
+        };
+        if decimals.len() > 7 {
+            return Err(ParseAmountError {
+                kind: ParseAmountErrorKind::TooPrecise,
+            });
+        }
+        let digits = whole.bytes().chain(decimals.bytes());
+        if whole.is_empty() && decimals.is_empty() || !digits.clone().all(|b| b.is_ascii_digit()) {
+            return Err(invalid);
+        }
+        // Stellar sends a float that is reduced from true value by 10^7, so
+        // padding the decimals out to 7 digits gives the stroops. Amounts are
+        // parsed for every record in a page, so this avoids allocating.
+        let padding = ::std::iter::repeat_n(b'0', 7 - decimals.len());
+        digits
+            .chain(padding)
+            .try_fold(0i64, |stroops, digit| {
+                stroops
+                    .checked_mul(10)
+                    .and_then(|stroops| stroops.checked_add(i64::from(digit - b'0')))
+            })
+            .map(Amount::new)
+            .ok_or(invalid)
+    }
+}
+
 /// The stellar ammount fields are represented as strings in the horizon api,
 /// however, they look like floats. But, in the actual ledger they are signed
 /// 64-bit integers. This function converts what we get from horizon into a
 /// signed integer. The precision of the float is assumed to be out to 7
 /// digits (but if fewer are found it's ok).
-
 impl<'de> Deserialize<'de> for Amount {
     fn deserialize<D>(d: D) -> Result<Amount, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let s = String::deserialize(d)?;
-        match s.rfind('.') {
-            None => {
-                // There is no decimal so just multiply
-                let parsed_amount = i64::from_str(&s)
-                    .map_err(|_| de::Error::custom("Failed to parse string field"))?;
-                Ok(Amount::new(parsed_amount * 10_000_000))
-            }
-            Some(decimal_place) => {
-                let number_decimals = s.len() - (decimal_place + 1);
-                if number_decimals > 7 {
-                    Err(de::Error::custom(
-                        "Amount has too many digits of precision.",
-                    ))
-                } else {
-                    let s = s.replace(".", "");
-                    let parsed_amount = i64::from_str(&s)
-                        .map_err(|_| de::Error::custom("Failed to parse string field"))?;
-                    // Stellar sends a float that is reduced from true value by 10^7 so raise by 10
-                    // minus the amount we gained from removing decimal
-                    let required_power: u32 = (7 - number_decimals) as u32;
-                    Ok(Amount::new(parsed_amount * (10_i64.pow(required_power))))
-                }
-            }
-        }
+        d.deserialize_str(AmountVisitor)
+    }
+}
+
+/// Parses amounts from the string horizon sends without copying it.
+struct AmountVisitor;
+
+impl<'de> de::Visitor<'de> for AmountVisitor {
+    type Value = Amount;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an amount as a string")
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Amount, E> {
+        Amount::from_str(s).map_err(de::Error::custom)
     }
 }
 
@@ -196,4 +379,39 @@ mod deserialize_amount_tests {
         let amount = serde_json::from_str::<Amount>("\"0.212847948\"");
         assert!(amount.is_err());
     }
+
+    #[test]
+    fn it_errors_on_signed_amounts() {
+        assert!(serde_json::from_str::<Amount>("\"-1.5\"").is_err());
+        assert!(serde_json::from_str::<Amount>("\"+1.5\"").is_err());
+    }
+
+    #[test]
+    fn it_parses_amounts_from_strings() {
+        assert_eq!("12.5".parse::<Amount>(), Ok(Amount(125_000_000)));
+        assert_eq!("0.0000001".parse::<Amount>(), Ok(Amount(1)));
+        assert!("-1".parse::<Amount>().is_err());
+        assert!("1.2.3".parse::<Amount>().is_err());
+        assert!("".parse::<Amount>().is_err());
+        assert!("922337203686".parse::<Amount>().is_err());
+    }
+
+    #[test]
+    fn it_parses_amounts_up_to_the_largest_stroop_count() {
+        assert_eq!(
+            "922337203685.4775807".parse::<Amount>(),
+            Ok(Amount(i64::MAX))
+        );
+        assert!("922337203685.4775808".parse::<Amount>().is_err());
+        assert_eq!("00012.5".parse::<Amount>(), Ok(Amount(125_000_000)));
+        assert_eq!(".5".parse::<Amount>(), Ok(Amount(5_000_000)));
+        assert!(".".parse::<Amount>().is_err());
+    }
+
+    #[test]
+    fn it_only_deserializes_strings() {
+        assert!(serde_json::from_str::<Amount>("12").is_err());
+        let amounts: Vec<Amount> = serde_json::from_slice(b"[\"1\", \"2.5\"]").unwrap();
+        assert_eq!(amounts, vec![Amount(10_000_000), Amount(25_000_000)]);
+    }
 }
@@ -0,0 +1,203 @@
+use super::AssetIdentifier;
+use serde::de;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::error::Error as StdError;
+use std::fmt;
+use std::str::FromStr;
+
+/// An asset as anchors name it, either one on the stellar network or a fiat
+/// currency by its ISO 4217 code. The services of anchors, such as SEP-38
+/// quotes, identify assets with strings like `stellar:USDC:G...`,
+/// `stellar:native` and `iso4217:USD`.
+///
+/// <https://github.com/stellar/stellar-protocol/blob/master/ecosystem/sep-0038.md#asset-identification-format>
+///
+/// ## Example
+///
+/// ```
+/// use stellar_client::resources::{AnchorAsset, AssetIdentifier};
+///
+/// let usd: AnchorAsset = "iso4217:USD".parse().unwrap();
+/// assert_eq!(usd, AnchorAsset::iso4217("USD"));
+///
+/// let lumens = AnchorAsset::stellar(AssetIdentifier::native());
+/// assert_eq!(lumens.to_string(), "stellar:native");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AnchorAsset {
+    /// An asset on the stellar network.
+    Stellar(AssetIdentifier),
+    /// A fiat currency by its ISO 4217 code, such as `USD`.
+    Iso4217(String),
+}
+
+impl AnchorAsset {
+    /// An anchor asset for an asset on the stellar network.
+    pub fn stellar(asset: AssetIdentifier) -> AnchorAsset {
+        AnchorAsset::Stellar(asset)
+    }
+
+    /// An anchor asset for a fiat currency by its ISO 4217 code.
+    pub fn iso4217(code: &str) -> AnchorAsset {
+        AnchorAsset::Iso4217(code.to_string())
+    }
+
+    /// The stellar asset, or `None` for a fiat currency.
+    pub fn as_stellar(&self) -> Option<&AssetIdentifier> {
+        match *self {
+            AnchorAsset::Stellar(ref asset) => Some(asset),
+            AnchorAsset::Iso4217(_) => None,
+        }
+    }
+
+    /// The code of the asset, which is the currency code for fiat.
+    pub fn code(&self) -> &str {
+        match *self {
+            AnchorAsset::Stellar(ref asset) => asset.code(),
+            AnchorAsset::Iso4217(ref code) => code,
+        }
+    }
+}
+
+impl From<AssetIdentifier> for AnchorAsset {
+    fn from(asset: AssetIdentifier) -> AnchorAsset {
+        AnchorAsset::Stellar(asset)
+    }
+}
+
+impl fmt::Display for AnchorAsset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AnchorAsset::Stellar(ref asset) => write!(f, "stellar:{}", asset.to_canonical()),
+            AnchorAsset::Iso4217(ref code) => write!(f, "iso4217:{}", code),
+        }
+    }
+}
+
+/// An error for a string that isn't an anchor asset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseAnchorAssetError {
+    kind: ParseAnchorAssetErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ParseAnchorAssetErrorKind {
+    UnknownScheme,
+    InvalidStellarAsset,
+    InvalidCurrencyCode,
+}
+
+impl StdError for ParseAnchorAssetError {
+    fn description(&self) -> &str {
+        match self.kind {
+            ParseAnchorAssetErrorKind::UnknownScheme => {
+                "Anchor assets must start with stellar: or iso4217:"
+            }
+            ParseAnchorAssetErrorKind::InvalidStellarAsset => {
+                "Stellar assets must be native or CODE:ISSUER"
+            }
+            ParseAnchorAssetErrorKind::InvalidCurrencyCode => {
+                "Currency codes must be three upper case letters"
+            }
+        }
+    }
+}
+
+impl fmt::Display for ParseAnchorAssetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+impl FromStr for AnchorAsset {
+    type Err = ParseAnchorAssetError;
+
+    fn from_str(s: &str) -> Result<AnchorAsset, ParseAnchorAssetError> {
+        let error = |kind| ParseAnchorAssetError { kind };
+        if let Some(canonical) = s.strip_prefix("stellar:") {
+            let valid = canonical == "native"
+                || match canonical.find(':') {
+                    Some(index) => index + 1 < canonical.len(),
+                    None => false,
+                };
+            if !valid {
+                return Err(error(ParseAnchorAssetErrorKind::InvalidStellarAsset));
+            }
+            AssetIdentifier::from_canonical(canonical)
+                .map(AnchorAsset::Stellar)
+                .map_err(|_| error(ParseAnchorAssetErrorKind::InvalidStellarAsset))
+        } else if let Some(code) = s.strip_prefix("iso4217:") {
+            if code.len() != 3 || !code.bytes().all(|b| b.is_ascii_uppercase()) {
+                return Err(error(ParseAnchorAssetErrorKind::InvalidCurrencyCode));
+            }
+            Ok(AnchorAsset::iso4217(code))
+        } else {
+            Err(error(ParseAnchorAssetErrorKind::UnknownScheme))
+        }
+    }
+}
+
+impl Serialize for AnchorAsset {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for AnchorAsset {
+    fn deserialize<D>(d: D) -> Result<AnchorAsset, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(d)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod anchor_asset_tests {
+    use super::*;
+    use serde_json;
+
+    const ISSUER: &str = "GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN";
+
+    #[test]
+    fn it_parses_stellar_assets() {
+        let usdc: AnchorAsset = format!("stellar:USDC:{}", ISSUER).parse().unwrap();
+        assert_eq!(
+            usdc,
+            AnchorAsset::stellar(AssetIdentifier::alphanum4("USDC", ISSUER))
+        );
+        assert_eq!(usdc.code(), "USDC");
+        let lumens: AnchorAsset = "stellar:native".parse().unwrap();
+        assert_eq!(lumens.as_stellar(), Some(&AssetIdentifier::native()));
+    }
+
+    #[test]
+    fn it_parses_fiat_currencies() {
+        let usd: AnchorAsset = "iso4217:USD".parse().unwrap();
+        assert_eq!(usd, AnchorAsset::iso4217("USD"));
+        assert_eq!(usd.as_stellar(), None);
+    }
+
+    #[test]
+    fn it_rejects_malformed_assets() {
+        assert!("USD".parse::<AnchorAsset>().is_err());
+        assert!("stellar:USDC".parse::<AnchorAsset>().is_err());
+        assert!("stellar:USDC:".parse::<AnchorAsset>().is_err());
+        assert!("stellar:TOOLONGFORACODE:G".parse::<AnchorAsset>().is_err());
+        assert!("iso4217:usd".parse::<AnchorAsset>().is_err());
+        assert!("iso4217:DOLLARS".parse::<AnchorAsset>().is_err());
+    }
+
+    #[test]
+    fn it_round_trips_through_json() {
+        let asset = AnchorAsset::stellar(AssetIdentifier::alphanum12("LONGCODE", ISSUER));
+        let json = serde_json::to_string(&asset).unwrap();
+        assert_eq!(json, format!("\"stellar:LONGCODE:{}\"", ISSUER));
+        let parsed: AnchorAsset = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, asset);
+    }
+}
@@ -1,5 +1,7 @@
 use resources::Amount;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use strkey::{self, Version};
 
 /// Assets are the units that are traded on the Stellar Network.
 /// An asset consists of an type, code, and issuer.
@@ -8,7 +10,11 @@ use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 /// <https://www.stellar.org/developers/horizon/reference/resources/asset.html>
 
 /// An identifer is the type, code, and issuer.
-#[derive(Debug, PartialEq, Eq, Clone)]
+///
+/// Identifiers are ordered the way the network orders assets: native first,
+/// then 4 character codes and then 12 character codes, each by code and then
+/// by the bytes of the issuer's public key.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
 pub enum AssetIdentifier {
     /// Stellar Lumens!
     Native,
@@ -19,12 +25,36 @@ pub enum AssetIdentifier {
 }
 
 /// Struct containing code and issuer
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Hash)]
 pub struct AssetId {
     code: String,
     issuer: String,
 }
 
+impl AssetId {
+    /// The issuer's public key, which the network orders issuers by. The
+    /// base32 account id sorts digits before letters, so it can't be
+    /// compared directly.
+    fn issuer_key(&self) -> Option<Vec<u8>> {
+        strkey::decode_version(Version::AccountId, &self.issuer).ok()
+    }
+}
+
+impl Ord for AssetId {
+    fn cmp(&self, other: &AssetId) -> Ordering {
+        self.code
+            .cmp(&other.code)
+            .then_with(|| self.issuer_key().cmp(&other.issuer_key()))
+            .then_with(|| self.issuer.cmp(&other.issuer))
+    }
+}
+
+impl PartialOrd for AssetId {
+    fn partial_cmp(&self, other: &AssetId) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 /// A convenience struct used for deserializing AssetIdentifier
 #[derive(Serialize, Deserialize, Debug)]
 pub struct IntermediateAssetIdentifier {
@@ -161,6 +191,31 @@ impl AssetIdentifier {
             issuer: issuer.to_string(),
         })
     }
+
+    /// Parses the canonical form horizon uses in liquidity pool reserves,
+    /// either `native` or `CODE:ISSUER`.
+    pub(crate) fn from_canonical(asset: &str) -> Result<AssetIdentifier, String> {
+        if asset == "native" {
+            return Ok(AssetIdentifier::Native);
+        }
+        match asset.find(':') {
+            Some(index) if index > 0 && index <= 4 => {
+                Ok(AssetIdentifier::alphanum4(&asset[..index], &asset[index + 1..]))
+            }
+            Some(index) if index > 4 && index <= 12 => {
+                Ok(AssetIdentifier::alphanum12(&asset[..index], &asset[index + 1..]))
+            }
+            _ => Err(format!("Invalid canonical asset {}.", asset)),
+        }
+    }
+
+    /// The inverse of `from_canonical`.
+    pub(crate) fn to_canonical(&self) -> String {
+        match *self {
+            AssetIdentifier::Native => "native".to_string(),
+            _ => format!("{}:{}", self.code(), self.issuer()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -243,6 +298,28 @@ mod asset_identifier_tests {
         assert!(!asset.is_native());
     }
 
+    #[test]
+    fn it_parses_canonical_assets() {
+        assert_eq!(
+            AssetIdentifier::from_canonical("native"),
+            Ok(AssetIdentifier::native())
+        );
+        assert_eq!(
+            AssetIdentifier::from_canonical("USD:ISSUER"),
+            Ok(AssetIdentifier::alphanum4("USD", "ISSUER"))
+        );
+        assert_eq!(
+            AssetIdentifier::from_canonical("LONGCODE:ISSUER"),
+            Ok(AssetIdentifier::alphanum12("LONGCODE", "ISSUER"))
+        );
+        assert!(AssetIdentifier::from_canonical("USD").is_err());
+        assert_eq!(
+            AssetIdentifier::alphanum4("USD", "ISSUER").to_canonical(),
+            "USD:ISSUER"
+        );
+        assert_eq!(AssetIdentifier::native().to_canonical(), "native");
+    }
+
     #[test]
     fn it_creates_an_alphanum12_asset() {
         let asset: AssetIdentifier = AssetIdentifier::alphanum12("ABCD", "ISSUER");
@@ -251,11 +328,45 @@ mod asset_identifier_tests {
         assert_eq!(asset.issuer(), "ISSUER");
         assert!(!asset.is_native());
     }
+
+    #[test]
+    fn it_orders_assets_like_the_network() {
+        let mut assets = vec![
+            AssetIdentifier::alphanum12("ABCDE", "ISSUER"),
+            AssetIdentifier::alphanum4("USD", "ISSUERB"),
+            AssetIdentifier::alphanum4("USD", "ISSUERA"),
+            AssetIdentifier::alphanum4("EUR", "ISSUERB"),
+            AssetIdentifier::native(),
+        ];
+        assets.sort();
+        assert_eq!(
+            assets,
+            vec![
+                AssetIdentifier::native(),
+                AssetIdentifier::alphanum4("EUR", "ISSUERB"),
+                AssetIdentifier::alphanum4("USD", "ISSUERA"),
+                AssetIdentifier::alphanum4("USD", "ISSUERB"),
+                AssetIdentifier::alphanum12("ABCDE", "ISSUER"),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_orders_issuers_by_their_public_key() {
+        // The first key is all zeros; the second starts with 0x3e, which
+        // encodes to a digit that sorts before the letter of the first.
+        let zero = "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF";
+        let larger = "GA7AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAB4MV";
+        assert!(larger < zero);
+        assert!(
+            AssetIdentifier::alphanum4("USD", zero) < AssetIdentifier::alphanum4("USD", larger)
+        );
+    }
 }
 
 /// Permissions around who can own an asset and whether or
 /// not the asset issuer can freeze the asset.
-#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct Flags {
     auth_required: bool,
     auth_revocable: bool,
@@ -286,28 +397,131 @@ impl Flags {
     }
 }
 
+/// The number of accounts holding an asset, by whether the issuer has
+/// authorized their trust lines.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct AccountCounts {
+    authorized: u32,
+    authorized_to_maintain_liabilities: u32,
+    unauthorized: u32,
+}
+
+impl AccountCounts {
+    /// Creates a new set of account counts
+    pub fn new(
+        authorized: u32,
+        authorized_to_maintain_liabilities: u32,
+        unauthorized: u32,
+    ) -> AccountCounts {
+        AccountCounts {
+            authorized,
+            authorized_to_maintain_liabilities,
+            unauthorized,
+        }
+    }
+
+    /// The number of accounts fully authorized to hold the asset.
+    pub fn authorized(&self) -> u32 {
+        self.authorized
+    }
+
+    /// The number of accounts that may keep their offers and balances but
+    /// can't send or receive the asset.
+    pub fn authorized_to_maintain_liabilities(&self) -> u32 {
+        self.authorized_to_maintain_liabilities
+    }
+
+    /// The number of accounts that trust the asset but aren't authorized to
+    /// hold it.
+    pub fn unauthorized(&self) -> u32 {
+        self.unauthorized
+    }
+}
+
+/// The total balances of an asset, by whether the issuer has authorized the
+/// trust lines holding them.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct BalanceTotals {
+    authorized: Amount,
+    authorized_to_maintain_liabilities: Amount,
+    unauthorized: Amount,
+}
+
+impl BalanceTotals {
+    /// Creates a new set of balance totals
+    pub fn new(
+        authorized: Amount,
+        authorized_to_maintain_liabilities: Amount,
+        unauthorized: Amount,
+    ) -> BalanceTotals {
+        BalanceTotals {
+            authorized,
+            authorized_to_maintain_liabilities,
+            unauthorized,
+        }
+    }
+
+    /// The amount held in fully authorized trust lines.
+    pub fn authorized(&self) -> Amount {
+        self.authorized
+    }
+
+    /// The amount held in trust lines authorized to maintain liabilities.
+    pub fn authorized_to_maintain_liabilities(&self) -> Amount {
+        self.authorized_to_maintain_liabilities
+    }
+
+    /// The amount held in unauthorized trust lines.
+    pub fn unauthorized(&self) -> Amount {
+        self.unauthorized
+    }
+}
+
 /// Assets are the units that are traded on the Stellar Network.
 /// An asset consists of an type, code, and issuer.
 /// Any asset can be traded for any other asset.
 ///
+/// Newer horizon versions break the holders and balances down by
+/// authorization and count what is held in claimable balances and
+/// liquidity pools. Those statistics are `None` when talking to horizon
+/// versions that don't return them.
+///
 /// <https://www.stellar.org/developers/horizon/reference/resources/asset.html>
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Asset {
     asset_identifier: AssetIdentifier,
     amount: Amount,
     num_accounts: u32,
     flags: Flags,
+    accounts: Option<AccountCounts>,
+    balances: Option<BalanceTotals>,
+    num_claimable_balances: Option<u32>,
+    claimable_balances_amount: Option<Amount>,
+    num_liquidity_pools: Option<u32>,
+    liquidity_pools_amount: Option<Amount>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct IntermediateAsset {
     asset_type: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     asset_code: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     asset_issuer: Option<String>,
-    amount: Amount,
-    num_accounts: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    accounts: Option<AccountCounts>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_claimable_balances: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_liquidity_pools: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    balances: Option<BalanceTotals>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    claimable_balances_amount: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    liquidity_pools_amount: Option<Amount>,
+    amount: Option<Amount>,
+    num_accounts: Option<u32>,
     flags: Flags,
 }
 
@@ -320,15 +534,54 @@ impl<'de> Deserialize<'de> for Asset {
         let asset_identifier: Result<AssetIdentifier, D::Error> =
             AssetIdentifier::new(&rep.asset_type, rep.asset_code, rep.asset_issuer)
                 .map_err(de::Error::custom);
+        // Horizon may drop the aggregates in favour of the breakdowns, which
+        // count the same fully authorized holders.
+        let (accounts, balances) = (rep.accounts, rep.balances);
+        let amount = rep
+            .amount
+            .or_else(|| balances.map(|balances| balances.authorized()))
+            .ok_or_else(|| de::Error::missing_field("amount"))?;
+        let num_accounts = rep
+            .num_accounts
+            .or_else(|| accounts.map(|accounts| accounts.authorized()))
+            .ok_or_else(|| de::Error::missing_field("num_accounts"))?;
         Ok(Asset {
-            asset_identifier: asset_identifier.unwrap(),
-            amount: rep.amount,
-            num_accounts: rep.num_accounts,
+            asset_identifier: asset_identifier?,
+            amount,
+            num_accounts,
             flags: rep.flags,
+            accounts,
+            balances,
+            num_claimable_balances: rep.num_claimable_balances,
+            claimable_balances_amount: rep.claimable_balances_amount,
+            num_liquidity_pools: rep.num_liquidity_pools,
+            liquidity_pools_amount: rep.liquidity_pools_amount,
         })
     }
 }
 
+impl Serialize for Asset {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        IntermediateAsset {
+            asset_type: self.asset_type().to_string(),
+            asset_code: self.asset_identifier.asset_code(),
+            asset_issuer: self.asset_identifier.asset_issuer(),
+            accounts: self.accounts,
+            num_claimable_balances: self.num_claimable_balances,
+            num_liquidity_pools: self.num_liquidity_pools,
+            balances: self.balances,
+            claimable_balances_amount: self.claimable_balances_amount,
+            liquidity_pools_amount: self.liquidity_pools_amount,
+            amount: Some(self.amount),
+            num_accounts: Some(self.num_accounts),
+            flags: self.flags,
+        }.serialize(s)
+    }
+}
+
 impl Asset {
     /// The identifier of this asset.
     pub fn identifier(&self) -> &AssetIdentifier {
@@ -388,11 +641,44 @@ impl Asset {
     pub fn flags(&self) -> Flags {
         self.flags
     }
+
+    /// The number of accounts holding the asset by authorization, if horizon
+    /// returned the breakdown.
+    pub fn accounts(&self) -> Option<AccountCounts> {
+        self.accounts
+    }
+
+    /// The total balances of the asset by authorization, if horizon returned
+    /// the breakdown.
+    pub fn balances(&self) -> Option<BalanceTotals> {
+        self.balances
+    }
+
+    /// The number of claimable balances holding the asset.
+    pub fn num_claimable_balances(&self) -> Option<u32> {
+        self.num_claimable_balances
+    }
+
+    /// The amount of the asset held in claimable balances.
+    pub fn claimable_balances_amount(&self) -> Option<Amount> {
+        self.claimable_balances_amount
+    }
+
+    /// The number of liquidity pools holding the asset.
+    pub fn num_liquidity_pools(&self) -> Option<u32> {
+        self.num_liquidity_pools
+    }
+
+    /// The amount of the asset held in liquidity pools.
+    pub fn liquidity_pools_amount(&self) -> Option<Amount> {
+        self.liquidity_pools_amount
+    }
 }
 
 #[cfg(test)]
 mod asset_tests {
     use super::*;
+    use resources::serialize::assert_round_trips;
     use serde_json;
 
     fn asset_json() -> &'static str {
@@ -422,4 +708,58 @@ mod asset_tests {
             ),
         );
     }
+
+    #[test]
+    fn it_serializes_like_horizon() {
+        assert_round_trips::<Asset>(asset_json());
+        assert_round_trips::<Asset>(asset_stats_json());
+    }
+
+    fn asset_stats_json() -> &'static str {
+        include_str!("../../fixtures/asset_stats.json")
+    }
+
+    #[test]
+    fn it_parses_the_statistics_breakdown() {
+        let asset: Asset = serde_json::from_str(asset_stats_json()).unwrap();
+        assert_eq!(asset.accounts(), Some(AccountCounts::new(1529084, 12, 3)));
+        assert_eq!(
+            asset.balances(),
+            Some(BalanceTotals::new(
+                Amount::new(876_543_211_234_567),
+                Amount::new(1_500_000_000),
+                Amount::new(25_000_000),
+            ))
+        );
+        assert_eq!(asset.num_claimable_balances(), Some(24));
+        assert_eq!(asset.claimable_balances_amount(), Some(Amount::new(12_345_000_000)));
+        assert_eq!(asset.num_liquidity_pools(), Some(95));
+        assert_eq!(
+            asset.liquidity_pools_amount(),
+            Some(Amount::new(45_678_900_000_000))
+        );
+        assert_eq!(asset.amount(), Amount::new(876_543_211_234_567));
+        assert_eq!(asset.num_accounts(), 1529084);
+    }
+
+    #[test]
+    fn it_has_no_breakdown_from_older_horizons() {
+        let asset: Asset = serde_json::from_str(asset_json()).unwrap();
+        assert_eq!(asset.accounts(), None);
+        assert_eq!(asset.balances(), None);
+        assert_eq!(asset.liquidity_pools_amount(), None);
+    }
+
+    #[test]
+    fn it_falls_back_to_the_breakdown_without_aggregates() {
+        let json = asset_stats_json()
+            .replace("\"amount\": \"87654321.1234567\",", "")
+            .replace("\"num_accounts\": 1529084,", "");
+        let asset: Asset = serde_json::from_str(&json).unwrap();
+        assert_eq!(asset.amount(), Amount::new(876_543_211_234_567));
+        assert_eq!(asset.num_accounts(), 1529084);
+
+        let json = asset_json().replace("\"amount\": \"100.0000000\",", "");
+        assert!(serde_json::from_str::<Asset>(&json).is_err());
+    }
 }
@@ -0,0 +1,184 @@
+use resources::{Amount, AssetIdentifier};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// A balance an account holds, either of an asset it trusts, of lumens, or
+/// of shares in a liquidity pool.
+///
+/// <https://www.stellar.org/developers/horizon/reference/resources/account.html#balances>
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Balance {
+    holding: Holding,
+    balance: Amount,
+    limit: Option<Amount>,
+    buying_liabilities: Option<Amount>,
+    selling_liabilities: Option<Amount>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Holding {
+    Asset(AssetIdentifier),
+    LiquidityPoolShares(String),
+}
+
+impl Balance {
+    /// A balance of the given asset, without a limit or liabilities. Use
+    /// `with_limit` to set the trust line limit of a non-native asset.
+    pub fn new(asset: AssetIdentifier, balance: Amount) -> Balance {
+        Balance {
+            holding: Holding::Asset(asset),
+            balance,
+            limit: None,
+            buying_liabilities: None,
+            selling_liabilities: None,
+        }
+    }
+
+    /// A balance of shares in the given liquidity pool.
+    pub fn liquidity_pool_shares(liquidity_pool_id: &str, balance: Amount) -> Balance {
+        Balance {
+            holding: Holding::LiquidityPoolShares(liquidity_pool_id.to_string()),
+            balance,
+            limit: None,
+            buying_liabilities: None,
+            selling_liabilities: None,
+        }
+    }
+
+    /// Sets the most the account is willing to hold.
+    pub fn with_limit(mut self, limit: Amount) -> Balance {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Sets the amount the account's open offers are committed to buy.
+    pub fn with_buying_liabilities(mut self, amount: Amount) -> Balance {
+        self.buying_liabilities = Some(amount);
+        self
+    }
+
+    /// Sets the amount the account's open offers are committed to sell.
+    pub fn with_selling_liabilities(mut self, amount: Amount) -> Balance {
+        self.selling_liabilities = Some(amount);
+        self
+    }
+
+    /// The asset held, or `None` if this is a balance of liquidity pool
+    /// shares.
+    pub fn asset(&self) -> Option<&AssetIdentifier> {
+        match self.holding {
+            Holding::Asset(ref asset) => Some(asset),
+            Holding::LiquidityPoolShares(_) => None,
+        }
+    }
+
+    /// The liquidity pool whose shares are held, if this is a pool share
+    /// balance.
+    pub fn liquidity_pool_id(&self) -> Option<&str> {
+        match self.holding {
+            Holding::Asset(_) => None,
+            Holding::LiquidityPoolShares(ref id) => Some(id),
+        }
+    }
+
+    /// Returns true if this is the account's lumen balance.
+    pub fn is_native(&self) -> bool {
+        self.asset().map(AssetIdentifier::is_native).unwrap_or(false)
+    }
+
+    /// The amount held.
+    pub fn balance(&self) -> Amount {
+        self.balance
+    }
+
+    /// The most the account is willing to hold. Lumen balances have no
+    /// limit.
+    pub fn limit(&self) -> Option<Amount> {
+        self.limit
+    }
+
+    /// The amount the account's open offers are committed to buy.
+    pub fn buying_liabilities(&self) -> Amount {
+        self.buying_liabilities.unwrap_or_else(|| Amount::new(0))
+    }
+
+    /// The amount the account's open offers are committed to sell. This is
+    /// locked up and can't be spent.
+    pub fn selling_liabilities(&self) -> Amount {
+        self.selling_liabilities.unwrap_or_else(|| Amount::new(0))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Intermediate {
+    asset_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    asset_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    asset_issuer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    liquidity_pool_id: Option<String>,
+    balance: Amount,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    buying_liabilities: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    selling_liabilities: Option<Amount>,
+}
+
+impl<'de> Deserialize<'de> for Balance {
+    fn deserialize<D>(d: D) -> Result<Balance, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let rep = Intermediate::deserialize(d)?;
+        let holding = if rep.asset_type == "liquidity_pool_shares" {
+            let id = rep.liquidity_pool_id
+                .ok_or_else(|| de::Error::missing_field("liquidity_pool_id"))?;
+            Holding::LiquidityPoolShares(id)
+        } else {
+            let asset = AssetIdentifier::new(&rep.asset_type, rep.asset_code, rep.asset_issuer)
+                .map_err(de::Error::custom)?;
+            Holding::Asset(asset)
+        };
+        Ok(Balance {
+            holding,
+            balance: rep.balance,
+            limit: rep.limit,
+            buying_liabilities: rep.buying_liabilities,
+            selling_liabilities: rep.selling_liabilities,
+        })
+    }
+}
+
+impl Serialize for Balance {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let (asset_type, asset_code, asset_issuer, liquidity_pool_id) = match self.holding {
+            Holding::Asset(ref asset) => (
+                asset.asset_type().to_string(),
+                asset.asset_code(),
+                asset.asset_issuer(),
+                None,
+            ),
+            Holding::LiquidityPoolShares(ref id) => (
+                "liquidity_pool_shares".to_string(),
+                None,
+                None,
+                Some(id.clone()),
+            ),
+        };
+        Intermediate {
+            asset_type,
+            asset_code,
+            asset_issuer,
+            liquidity_pool_id,
+            balance: self.balance,
+            limit: self.limit,
+            buying_liabilities: self.buying_liabilities,
+            selling_liabilities: self.selling_liabilities,
+        }.serialize(s)
+    }
+}
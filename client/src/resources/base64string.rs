@@ -1,21 +1,57 @@
 use base64::{decode, encode};
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Cow;
 use std::fmt;
+use std::str::{self, Utf8Error};
 
 /// Base64 encoded Strings are used in several resources in the stellar ecosystem. There
 /// are encoding and decoding conversions that must take place to display data to users
 /// in a way that makes sense to both users and the horizon API. That logic is contained
 /// in this module.
 ///
+/// The value is kept both as horizon sent it and decoded into bytes. Values
+/// are arbitrary bytes, so they are only read as utf8 on request.
+///
 /// An example where this is used:
 /// <https://www.stellar.org/developers/horizon/reference/resources/data.html#attributes>
-#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone)]
-pub struct Base64String(pub String);
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Hash)]
+pub struct Base64String {
+    encoded: String,
+    decoded: Vec<u8>,
+}
 
 impl Base64String {
-    /// Create a base64string newtype
+    /// Create a base64string newtype from the decoded value
     pub fn new(thing: String) -> Base64String {
-        Base64String(thing)
+        Base64String::from_bytes(thing.into_bytes())
+    }
+
+    /// Create a base64string from decoded bytes
+    pub fn from_bytes(bytes: Vec<u8>) -> Base64String {
+        Base64String {
+            encoded: encode(&bytes),
+            decoded: bytes,
+        }
+    }
+
+    /// The value base64 encoded, as horizon represents it.
+    pub fn as_base64(&self) -> &str {
+        &self.encoded
+    }
+
+    /// The decoded bytes of the value.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.decoded
+    }
+
+    /// The decoded value as utf8, failing for values that aren't valid utf8.
+    pub fn to_utf8(&self) -> Result<&str, Utf8Error> {
+        str::from_utf8(&self.decoded)
+    }
+
+    /// The decoded value as utf8, with invalid sequences replaced by `�`.
+    pub fn to_utf8_lossy(&self) -> Cow<str> {
+        String::from_utf8_lossy(&self.decoded)
     }
 }
 
@@ -24,18 +60,26 @@ mod base64string_tests {
     use super::*;
     #[test]
     fn it_creates_a_new_base64string() {
-        assert_eq!(
-            Base64String::new(String::from("abc123")),
-            Base64String(String::from("abc123"))
-        );
+        let base64string = Base64String::new(String::from("abc123"));
+        assert_eq!(base64string.as_base64(), "YWJjMTIz");
+        assert_eq!(base64string.as_bytes(), b"abc123");
+        assert_eq!(base64string.to_utf8(), Ok("abc123"));
+    }
+
+    #[test]
+    fn it_reads_values_that_are_not_utf8() {
+        let base64string = Base64String::from_bytes(vec![0xff, 0xfe]);
+        assert_eq!(base64string.as_base64(), "//4=");
+        assert!(base64string.to_utf8().is_err());
+        assert_eq!(base64string.to_utf8_lossy(), "\u{fffd}\u{fffd}");
     }
 }
 
-/// Base64Strings are stored as decoded Strings so we can simply
-/// return the raw string to display to the user.
+/// Base64Strings display their decoded value so we can simply show it to
+/// the user. Values that aren't valid utf8 are displayed lossily.
 impl fmt::Display for Base64String {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.0.to_string())
+        write!(f, "{}", self.to_utf8_lossy())
     }
 }
 
@@ -45,22 +89,19 @@ mod display_amount_tests {
     #[test]
     fn it_displays_as_a_string() {
         assert_eq!(
-            format!("{}", Base64String(String::from("abc123"))),
+            format!("{}", Base64String::new(String::from("abc123"))),
             "abc123"
         );
     }
 }
 
-/// Converts internally stored data value of a string into a base64 encoded value
-/// and returns a serialized string of the result.
+/// Serializes the value base64 encoded, as horizon represents it.
 impl Serialize for Base64String {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let formatted_string = format!("{}", self);
-        let encoded_string = encode(&formatted_string);
-        serializer.serialize_str(&encoded_string)
+        serializer.serialize_str(&self.encoded)
     }
 }
 
@@ -70,7 +111,7 @@ mod serialize_amount_tests {
     use serde_json;
     #[test]
     fn it_displays_data_as_base64_encoded() {
-        let base64string = Base64String(String::from("Pizza"));
+        let base64string = Base64String::new(String::from("Pizza"));
         assert_eq!(
             serde_json::to_string(&base64string).unwrap(),
             "\"UGl6emE=\""
@@ -79,18 +120,17 @@ mod serialize_amount_tests {
 }
 
 /// The stellar value fields of account key/value pairs are represented as
-/// base64 encoded strings in the horizon api. This function converts the
-/// base64 encoded string into regular utf8.
+/// base64 encoded strings in the horizon api. This function decodes the
+/// base64 encoded string into its bytes.
 impl<'de> Deserialize<'de> for Base64String {
     fn deserialize<D>(d: D) -> Result<Base64String, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let s = String::deserialize(d)?;
-        let decoded_vec = decode(&s).unwrap();
-        let decoded_string = String::from_utf8(decoded_vec).unwrap();
+        let encoded = String::deserialize(d)?;
+        let decoded = decode(&encoded).map_err(de::Error::custom)?;
 
-        Ok(Base64String::new(decoded_string))
+        Ok(Base64String { encoded, decoded })
     }
 }
 
@@ -102,6 +142,17 @@ mod deserialize_base64string_tests {
     #[test]
     fn it_decodes_base64() {
         let data: Base64String = serde_json::from_str("\"UGl6emE=\"").unwrap();
-        assert_eq!(data, Base64String(String::from("Pizza")));
+        assert_eq!(data, Base64String::new(String::from("Pizza")));
+    }
+
+    #[test]
+    fn it_decodes_binary_values() {
+        let data: Base64String = serde_json::from_str("\"3q2+7w==\"").unwrap();
+        assert_eq!(data.as_bytes(), &[0xde, 0xad, 0xbe, 0xef]);
+    }
+
+    #[test]
+    fn it_rejects_values_that_are_not_base64() {
+        assert!(serde_json::from_str::<Base64String>("\"not base64!\"").is_err());
     }
 }
@@ -1,18 +1,62 @@
 use resources::base64string::Base64String;
+use std::borrow::Cow;
+use std::str::Utf8Error;
 
 /// In the Stellar network, key/value pairs can be attached to accounts.
 /// These key/value pairs can be useful for associating data with an account
 /// for various reasons. Datum represents the value of a single key/value pair.
 ///
+/// Values are arbitrary bytes of up to 64 bytes, which horizon base64
+/// encodes. They are decoded when the datum is parsed.
+///
 /// <https://www.stellar.org/developers/horizon/reference/resources/data.html>
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Datum {
     value: Base64String,
 }
 
 impl Datum {
-    /// The value of a single key/value pair tied to a single account.
+    /// The value of a single key/value pair tied to a single account, base64
+    /// encoded as horizon returns it.
     pub fn value(&self) -> &str {
-        &self.value.0
+        self.value.as_base64()
+    }
+
+    /// The decoded bytes of the value.
+    pub fn value_bytes(&self) -> &[u8] {
+        self.value.as_bytes()
+    }
+
+    /// The decoded value as utf8, failing for values that aren't valid utf8.
+    pub fn value_utf8(&self) -> Result<&str, Utf8Error> {
+        self.value.to_utf8()
+    }
+
+    /// The decoded value as utf8, with invalid sequences replaced by `�`.
+    pub fn value_utf8_lossy(&self) -> Cow<str> {
+        self.value.to_utf8_lossy()
+    }
+}
+
+#[cfg(test)]
+mod datum_tests {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn it_decodes_the_value() {
+        let datum: Datum = serde_json::from_str(r#"{ "value": "UGl6emE=" }"#).unwrap();
+        assert_eq!(datum.value(), "UGl6emE=");
+        assert_eq!(datum.value_bytes(), b"Pizza");
+        assert_eq!(datum.value_utf8(), Ok("Pizza"));
+        assert_eq!(datum.value_utf8_lossy(), "Pizza");
+    }
+
+    #[test]
+    fn it_decodes_values_that_are_not_utf8() {
+        let datum: Datum = serde_json::from_str(r#"{ "value": "/w==" }"#).unwrap();
+        assert_eq!(datum.value_bytes(), &[0xff]);
+        assert!(datum.value_utf8().is_err());
+        assert_eq!(datum.value_utf8_lossy(), "\u{fffd}");
     }
 }
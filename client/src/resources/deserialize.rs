@@ -1,7 +1,319 @@
-use serde::de;
+use serde::de::{self, DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor};
 use serde::{Deserialize, Deserializer};
+use serde_json;
+use std::borrow::Cow;
+use std::fmt;
 use std::str::FromStr;
 
+/// The name of the newtype struct that operations and effects deserialize
+/// themselves through. `UnknownVariants` answers it with `visit_some` to
+/// tell them that unknown types are tolerated, while other deserializers
+/// answer with `visit_newtype_struct`.
+pub(crate) const UNKNOWN_VARIANTS: &str = "$stellar_client::UnknownVariants";
+
+/// Deserializes json with unknown operation and effect types either
+/// tolerated or rejected. When tolerated they deserialize into an `Unknown`
+/// kind that keeps the raw json, rather than failing the whole response.
+pub(crate) fn from_slice<'a, T>(v: &'a [u8], unknown_variants: bool) -> serde_json::Result<T>
+where
+    T: Deserialize<'a>,
+{
+    if !unknown_variants {
+        return serde_json::from_slice(v);
+    }
+    let mut d = serde_json::Deserializer::from_slice(v);
+    let value = T::deserialize(UnknownVariants(&mut d))?;
+    d.end()?;
+    Ok(value)
+}
+
+/// Wraps a deserializer, and the visitors, seeds and accessors it hands out,
+/// so that operations and effects anywhere in the value tolerate unknown
+/// types.
+pub(crate) struct UnknownVariants<T>(pub(crate) T);
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $ty:ty),*),)*) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.0.$method($($arg,)* UnknownVariants(visitor))
+            }
+        )*
+    };
+}
+
+impl<'de, D> Deserializer<'de> for UnknownVariants<D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    forward_deserialize! {
+        deserialize_any(),
+        deserialize_bool(),
+        deserialize_i8(),
+        deserialize_i16(),
+        deserialize_i32(),
+        deserialize_i64(),
+        deserialize_i128(),
+        deserialize_u8(),
+        deserialize_u16(),
+        deserialize_u32(),
+        deserialize_u64(),
+        deserialize_u128(),
+        deserialize_f32(),
+        deserialize_f64(),
+        deserialize_char(),
+        deserialize_str(),
+        deserialize_string(),
+        deserialize_bytes(),
+        deserialize_byte_buf(),
+        deserialize_option(),
+        deserialize_unit(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_seq(),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_identifier(),
+        deserialize_ignored_any(),
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, D::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if name == UNKNOWN_VARIANTS {
+            return visitor.visit_some(self);
+        }
+        self.0
+            .deserialize_newtype_struct(name, UnknownVariants(visitor))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.0.is_human_readable()
+    }
+}
+
+macro_rules! forward_visit {
+    ($($method:ident($ty:ty),)*) => {
+        $(
+            fn $method<E: de::Error>(self, v: $ty) -> Result<V::Value, E> {
+                self.0.$method(v)
+            }
+        )*
+    };
+}
+
+impl<'de, V> Visitor<'de> for UnknownVariants<V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.expecting(f)
+    }
+
+    forward_visit! {
+        visit_bool(bool),
+        visit_i8(i8),
+        visit_i16(i16),
+        visit_i32(i32),
+        visit_i64(i64),
+        visit_i128(i128),
+        visit_u8(u8),
+        visit_u16(u16),
+        visit_u32(u32),
+        visit_u64(u64),
+        visit_u128(u128),
+        visit_f32(f32),
+        visit_f64(f64),
+        visit_char(char),
+        visit_str(&str),
+        visit_borrowed_str(&'de str),
+        visit_string(String),
+        visit_bytes(&[u8]),
+        visit_borrowed_bytes(&'de [u8]),
+        visit_byte_buf(Vec<u8>),
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<V::Value, E> {
+        self.0.visit_none()
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<V::Value, E> {
+        self.0.visit_unit()
+    }
+
+    fn visit_some<D>(self, d: D) -> Result<V::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.0.visit_some(UnknownVariants(d))
+    }
+
+    fn visit_newtype_struct<D>(self, d: D) -> Result<V::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.0.visit_newtype_struct(UnknownVariants(d))
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<V::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        self.0.visit_seq(UnknownVariants(seq))
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<V::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        self.0.visit_map(UnknownVariants(map))
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<V::Value, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        self.0.visit_enum(UnknownVariants(data))
+    }
+}
+
+impl<'de, T> DeserializeSeed<'de> for UnknownVariants<T>
+where
+    T: DeserializeSeed<'de>,
+{
+    type Value = T::Value;
+
+    fn deserialize<D>(self, d: D) -> Result<T::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.0.deserialize(UnknownVariants(d))
+    }
+}
+
+impl<'de, A> SeqAccess<'de> for UnknownVariants<A>
+where
+    A: SeqAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, A::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.0.next_element_seed(UnknownVariants(seed))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+impl<'de, A> MapAccess<'de> for UnknownVariants<A>
+where
+    A: MapAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, A::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        self.0.next_key_seed(UnknownVariants(seed))
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, A::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        self.0.next_value_seed(UnknownVariants(seed))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+impl<'de, A> EnumAccess<'de> for UnknownVariants<A>
+where
+    A: EnumAccess<'de>,
+{
+    type Error = A::Error;
+    type Variant = UnknownVariants<A::Variant>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), A::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let (value, variant) = self.0.variant_seed(UnknownVariants(seed))?;
+        Ok((value, UnknownVariants(variant)))
+    }
+}
+
+impl<'de, A> VariantAccess<'de> for UnknownVariants<A>
+where
+    A: VariantAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), A::Error> {
+        self.0.unit_variant()
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, A::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.0.newtype_variant_seed(UnknownVariants(seed))
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, A::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.0.tuple_variant(len, UnknownVariants(visitor))
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, A::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.0.struct_variant(fields, UnknownVariants(visitor))
+    }
+}
+
+/// Borrows an optional string from the json instead of allocating it, unless
+/// it has escapes that have to be undone. Serde only borrows `Cow` fields that
+/// aren't wrapped in an `Option`, so fields using this need
+/// `#[serde(borrow, default)]` as well.
+pub(crate) fn optional_cow_str<'de: 'a, 'a, D>(d: D) -> Result<Option<Cow<'a, str>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Borrowed<'a>(#[serde(borrow)] Cow<'a, str>);
+
+    Ok(Option::<Borrowed>::deserialize(d)?.map(|borrowed| borrowed.0))
+}
+
 /// Some fields in the json are represented as "strings" but are actually
 /// another type. If that type implements the `FromStr` trait, then this
 /// function parses it into that type.
@@ -16,6 +328,178 @@ where
     T::from_str(&s).map_err(|_| de::Error::custom("Failed to parse string field"))
 }
 
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NumberOrString<T> {
+    Number(T),
+    String(String),
+}
+
+impl<T: FromStr> NumberOrString<T> {
+    fn parse<E: de::Error>(self) -> Result<T, E> {
+        match self {
+            NumberOrString::Number(value) => Ok(value),
+            NumberOrString::String(s) => {
+                T::from_str(&s).map_err(|_| de::Error::custom("Failed to parse string field"))
+            }
+        }
+    }
+}
+
+/// Horizon represents large integers such as sequence numbers, offer ids and
+/// fees as strings, though older versions and some fields use plain numbers.
+/// This accepts either and parses it into the integer type.
+pub(crate) fn from_number_or_str<'de, D, T>(d: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Deserialize<'de>,
+{
+    NumberOrString::deserialize(d)?.parse()
+}
+
+/// The same as `from_number_or_str` for fields that may be missing or null.
+/// Fields using this need `#[serde(default)]` as well.
+pub(crate) fn optional_from_number_or_str<'de, D, T>(d: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Deserialize<'de>,
+{
+    match Option::<NumberOrString<T>>::deserialize(d)? {
+        Some(value) => value.parse().map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Horizon sends some optional strings, such as an account's home domain,
+/// as empty strings when they are unset. This reads those as `None`. Fields
+/// using this need `#[serde(default)]` as well.
+pub(crate) fn non_empty_string<'de, D>(d: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<String>::deserialize(d)?.filter(|s| !s.is_empty()))
+}
+
+#[cfg(test)]
+mod from_number_or_str_tests {
+    use serde_json::value::Value;
+
+    #[test]
+    fn it_parses_numbers_and_strings() {
+        let value = Value::String("9223372036854775807".to_string());
+        assert_eq!(
+            super::from_number_or_str::<Value, i64>(value).unwrap(),
+            i64::MAX
+        );
+        let value = Value::from(12);
+        assert_eq!(super::from_number_or_str::<Value, u64>(value).unwrap(), 12);
+    }
+
+    #[test]
+    fn it_parses_optional_values() {
+        assert_eq!(
+            super::optional_from_number_or_str::<Value, u64>(Value::String("12".to_string())).unwrap(),
+            Some(12)
+        );
+        assert_eq!(
+            super::optional_from_number_or_str::<Value, u64>(Value::Null).unwrap(),
+            None
+        );
+        assert!(super::optional_from_number_or_str::<Value, u64>(Value::String("-1".to_string())).is_err());
+    }
+}
+
+#[cfg(test)]
+mod unknown_variants_tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// Reads `Some` if it was deserialized through `UnknownVariants`.
+    #[derive(Debug, PartialEq)]
+    struct Tolerant(Option<u32>);
+
+    impl<'de> Deserialize<'de> for Tolerant {
+        fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Tolerant, D::Error> {
+            struct TolerantVisitor;
+
+            impl<'de> Visitor<'de> for TolerantVisitor {
+                type Value = Tolerant;
+
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    write!(f, "a number")
+                }
+
+                fn visit_some<D: Deserializer<'de>>(self, d: D) -> Result<Tolerant, D::Error> {
+                    u32::deserialize(d).map(|n| Tolerant(Some(n)))
+                }
+
+                fn visit_newtype_struct<D>(self, d: D) -> Result<Tolerant, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    u32::deserialize(d).map(|_| Tolerant(None))
+                }
+            }
+
+            d.deserialize_newtype_struct(UNKNOWN_VARIANTS, TolerantVisitor)
+        }
+    }
+
+    #[test]
+    fn it_reaches_values_nested_in_maps_and_seqs() {
+        let json = br#"{"a": [1, 2], "b": [3]}"#;
+        let tolerant: HashMap<String, Vec<Tolerant>> = from_slice(json, true).unwrap();
+        assert_eq!(tolerant["a"], vec![Tolerant(Some(1)), Tolerant(Some(2))]);
+        assert_eq!(tolerant["b"], vec![Tolerant(Some(3))]);
+
+        let strict: HashMap<String, Vec<Tolerant>> = from_slice(json, false).unwrap();
+        assert_eq!(strict["a"], vec![Tolerant(None), Tolerant(None)]);
+    }
+
+    #[test]
+    fn it_rejects_trailing_json() {
+        assert!(from_slice::<Vec<Tolerant>>(b"[1] [2]", true).is_err());
+    }
+}
+
+#[cfg(test)]
+mod optional_cow_str_tests {
+    use super::*;
+    use serde_json;
+
+    #[derive(Deserialize)]
+    struct Sample<'a> {
+        #[serde(borrow, default, deserialize_with = "optional_cow_str")]
+        field: Option<Cow<'a, str>>,
+    }
+
+    #[test]
+    fn it_borrows_strings_without_escapes() {
+        let sample: Sample = serde_json::from_str(r#"{ "field": "GABC" }"#).unwrap();
+        match sample.field {
+            Some(Cow::Borrowed(field)) => assert_eq!(field, "GABC"),
+            other => panic!("Expected a borrowed string, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_owns_strings_with_escapes() {
+        let sample: Sample = serde_json::from_str(r#"{ "field": "a\\nb" }"#).unwrap();
+        match sample.field {
+            Some(Cow::Owned(field)) => assert_eq!(field, "a\\nb"),
+            other => panic!("Expected an owned string, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_defaults_missing_and_null_strings() {
+        let sample: Sample = serde_json::from_str("{}").unwrap();
+        assert_eq!(sample.field, None);
+        let sample: Sample = serde_json::from_str(r#"{ "field": null }"#).unwrap();
+        assert_eq!(sample.field, None);
+    }
+}
+
 #[cfg(test)]
 mod from_str_tests {
     use serde_json::value::Value;
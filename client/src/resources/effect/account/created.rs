@@ -1,7 +1,7 @@
 use resources::Amount;
 /// This effect is the result of a create account operation and represents
 /// the fact that an account was created
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
 pub struct Created {
     account: String,
     starting_balance: Amount,
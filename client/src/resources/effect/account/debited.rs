@@ -2,7 +2,7 @@ use resources::{Amount, AssetIdentifier};
 /// This effect can be the result of a create_account, payment, path_payment
 /// or merge_account operation.  It represents the fact that assets were
 /// removed to an account
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
 pub struct Debited {
     account: String,
     amount: Amount,
@@ -1,7 +1,7 @@
 use resources::asset::Flags;
 /// This effect can be the result of a set options operation and represents
 /// the fact that an account's flags have been updated
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
 pub struct FlagsUpdated {
     account: String,
     flags: Flags,
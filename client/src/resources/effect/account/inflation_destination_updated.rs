@@ -0,0 +1,20 @@
+/// This effect is the result of a set options operation and represents the
+/// fact that an account's inflation destination has changed. Inflation has
+/// since been retired, but the effect remains in the history of old ledgers.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct InflationDestinationUpdated {
+    account: String,
+}
+
+impl InflationDestinationUpdated {
+    /// Creates a new InflationDestinationUpdated effect
+    pub fn new(account: String) -> InflationDestinationUpdated {
+        InflationDestinationUpdated { account }
+    }
+
+    /// The public address of the account whose inflation destination was
+    /// updated
+    pub fn account(&self) -> &String {
+        &self.account
+    }
+}
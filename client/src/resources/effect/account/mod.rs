@@ -5,7 +5,9 @@ mod credited;
 mod debited;
 mod flags_updated;
 mod home_domain_updated;
+mod inflation_destination_updated;
 mod removed;
+mod sequence_bumped;
 mod thresholds_updated;
 
 pub use self::created::Created;
@@ -13,12 +15,14 @@ pub use self::credited::Credited;
 pub use self::debited::Debited;
 pub use self::flags_updated::FlagsUpdated;
 pub use self::home_domain_updated::HomeDomainUpdated;
+pub use self::inflation_destination_updated::InflationDestinationUpdated;
 pub use self::removed::Removed;
+pub use self::sequence_bumped::SequenceBumped;
 pub use self::thresholds_updated::ThresholdsUpdated;
 
 /// Enum representing all the different kinds of effects that represent
 /// changes made to an account.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
 pub enum Kind {
     /// An effect representing the fact that an account was created
     Created(Created),
@@ -34,4 +38,9 @@ pub enum Kind {
     HomeDomainUpdated(HomeDomainUpdated),
     /// An effect representing the change of an account's flags as a result of an operation
     FlagsUpdated(FlagsUpdated),
+    /// An effect representing the change of an account's inflation destination as a result of an
+    /// operation
+    InflationDestinationUpdated(InflationDestinationUpdated),
+    /// An effect representing the bump of an account's sequence number as a result of an operation
+    SequenceBumped(SequenceBumped),
 }
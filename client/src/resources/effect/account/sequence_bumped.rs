@@ -0,0 +1,24 @@
+/// This effect is the result of a bump sequence operation and represents
+/// the fact that an account's sequence number has been raised.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct SequenceBumped {
+    account: String,
+    new_seq: i64,
+}
+
+impl SequenceBumped {
+    /// Creates a new SequenceBumped effect
+    pub fn new(account: String, new_seq: i64) -> SequenceBumped {
+        SequenceBumped { account, new_seq }
+    }
+
+    /// The public address of the account whose sequence number was bumped
+    pub fn account(&self) -> &String {
+        &self.account
+    }
+
+    /// The account's new sequence number
+    pub fn new_seq(&self) -> i64 {
+        self.new_seq
+    }
+}
@@ -0,0 +1,35 @@
+//! Contains effects related to claimable balances.
+
+/// The type of change that was performed
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub enum Kind {
+    /// A claimable balance was clawed back by the issuer of its asset.
+    ClawedBack(ClawedBack),
+}
+
+/// Contains details about the claimable balance that was clawed back
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct ClawedBack {
+    account: String,
+    balance_id: String,
+}
+
+impl ClawedBack {
+    /// Creates a new claimable balance clawed back effect
+    pub fn new(account: String, balance_id: String) -> Self {
+        Self {
+            account,
+            balance_id,
+        }
+    }
+
+    /// The public address of the issuer that clawed back the balance.
+    pub fn account(&self) -> &str {
+        &self.account
+    }
+
+    /// The id of the claimable balance that was clawed back.
+    pub fn balance_id(&self) -> &str {
+        &self.balance_id
+    }
+}
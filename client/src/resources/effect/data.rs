@@ -1,7 +1,7 @@
 //! Contains effects related to the management of data.
 
 /// The type of change that was performed
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
 pub enum Kind {
     /// Data was added to an account.
     Created(Effect),
@@ -12,7 +12,7 @@ pub enum Kind {
 }
 
 /// Contains details about the data that was changed
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
 pub struct Effect {
     account: String,
 }
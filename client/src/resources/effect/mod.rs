@@ -1,7 +1,14 @@
-use resources::{Amount, AssetIdentifier, asset::Flags};
-use serde::{de, Deserialize, Deserializer};
+use chrono::{DateTime, Utc};
+use resources::{Amount, AssetIdentifier, EffectLinks, asset::Flags};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{self, Value};
+use std::borrow::Cow;
+use std::fmt;
+use super::{deserialize, serialize};
+use super::links::Links;
 
 pub mod account;
+pub mod claimable_balance;
 pub mod data;
 pub mod signer;
 pub mod trade;
@@ -13,16 +20,18 @@ mod test;
 /// A successful operation will yield zero or more effects. These effects represent specific
 /// changes that occur in the ledger, but are not necessarily directly reflected in the ledger or
 /// history, as transactions and operations are.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Effect {
     id: String,
     paging_token: String,
+    created_at: Option<DateTime<Utc>>,
     kind: Kind,
+    links: Links<EffectLinks>,
 }
 
 /// Each effect type is representing by a kind and captures data specific to that
 /// type within it's newtype.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
 pub enum EffectKind {
     /// A collection of effects that represent updates to an account
     Account(account::Kind),
@@ -34,6 +43,16 @@ pub enum EffectKind {
     Trade(trade::Kind),
     /// An effect representing data being managed.
     Data(data::Kind),
+    /// A collection of effects that represent changes to claimable balances
+    ClaimableBalance(claimable_balance::Kind),
+    /// An effect type this crate doesn't recognize yet. These are only
+    /// produced when the client is configured to allow unknown variants.
+    Unknown {
+        /// The type of the effect as horizon named it.
+        type_name: String,
+        /// The effect as it was returned by horizon.
+        raw: Value,
+    },
     // The stellar api docs list other operations for offers, but as of this writing those
     // endpoints do not yet exist in horizon https://github.com/stellar/go/issues/166
 }
@@ -51,6 +70,17 @@ impl Effect {
         &self.paging_token
     }
 
+    /// When the ledger the effect happened in closed, if horizon said.
+    pub fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    /// The links horizon sent with the effect, to the operation behind it
+    /// and the effects either side of it.
+    pub fn links(&self) -> Option<&EffectLinks> {
+        self.links.get()
+    }
+
     /// Specifies the type of effect, See “Types” section below for reference.
     pub fn type_i(&self) -> u32 {
         match self.kind {
@@ -62,6 +92,8 @@ impl Effect {
                 account::Kind::ThresholdsUpdated(_) => 4,
                 account::Kind::HomeDomainUpdated(_) => 5,
                 account::Kind::FlagsUpdated(_) => 6,
+                account::Kind::InflationDestinationUpdated(_) => 7,
+                account::Kind::SequenceBumped(_) => 43,
             },
             Kind::Signer(ref signer_kind) => match *signer_kind {
                 signer::Kind::Created(_) => 10,
@@ -74,6 +106,8 @@ impl Effect {
                 trustline::Kind::Updated(_) => 22,
                 trustline::Kind::Authorized(_) => 23,
                 trustline::Kind::Deauthorized(_) => 24,
+                trustline::Kind::AuthorizedToMaintainLiabilities(_) => 25,
+                trustline::Kind::FlagsUpdated(_) => 26,
             },
             Kind::Data(ref kind) => match *kind {
                 data::Kind::Created(_) => 40,
@@ -81,6 +115,13 @@ impl Effect {
                 data::Kind::Updated(_) => 42,
             },
             Kind::Trade(_) => 33,
+            Kind::ClaimableBalance(ref kind) => match *kind {
+                claimable_balance::Kind::ClawedBack(_) => 80,
+            },
+            Kind::Unknown { ref raw, .. } => raw.get("type_i")
+                .and_then(Value::as_u64)
+                .map(|type_i| type_i as u32)
+                .unwrap_or(u32::MAX),
         }
     }
 
@@ -100,6 +141,10 @@ impl Effect {
                 account::Kind::ThresholdsUpdated(_) => "Account tresholds updated",
                 account::Kind::HomeDomainUpdated(_) => "Account home domain updated",
                 account::Kind::FlagsUpdated(_) => "Flags updated",
+                account::Kind::InflationDestinationUpdated(_) => {
+                    "Account inflation destination updated"
+                }
+                account::Kind::SequenceBumped(_) => "Sequence bumped",
             },
             Kind::Signer(ref signer_kind) => match *signer_kind {
                 signer::Kind::Created(_) => "Signer created",
@@ -112,6 +157,10 @@ impl Effect {
                 trustline::Kind::Updated(_) => "Trustline updated",
                 trustline::Kind::Authorized(_) => "Trustline authorized",
                 trustline::Kind::Deauthorized(_) => "Trustline deauthorized",
+                trustline::Kind::AuthorizedToMaintainLiabilities(_) => {
+                    "Trustline authorized to maintain liabilities"
+                }
+                trustline::Kind::FlagsUpdated(_) => "Trustline flags updated",
             },
             Kind::Trade(_) => "Trade",
             Kind::Data(ref kind) => match *kind {
@@ -119,9 +168,56 @@ impl Effect {
                 data::Kind::Removed(_) => "Data removed",
                 data::Kind::Updated(_) => "Data updated",
             },
+            Kind::ClaimableBalance(ref kind) => match *kind {
+                claimable_balance::Kind::ClawedBack(_) => "Claimable balance clawed back",
+            },
+            Kind::Unknown { ref type_name, .. } => type_name,
         }
     }
 
+    /// The account the effect applies to. Every effect horizon knows has
+    /// one, so this is only `None` for an unknown effect without one.
+    pub fn account(&self) -> Option<&str> {
+        let account: &str = match self.kind {
+            Kind::Account(ref account_kind) => match *account_kind {
+                account::Kind::Created(ref effect) => effect.account(),
+                account::Kind::Removed(ref effect) => effect.account(),
+                account::Kind::Credited(ref effect) => effect.account(),
+                account::Kind::Debited(ref effect) => effect.account(),
+                account::Kind::ThresholdsUpdated(ref effect) => effect.account(),
+                account::Kind::HomeDomainUpdated(ref effect) => effect.account(),
+                account::Kind::FlagsUpdated(ref effect) => effect.account(),
+                account::Kind::InflationDestinationUpdated(ref effect) => effect.account(),
+                account::Kind::SequenceBumped(ref effect) => effect.account(),
+            },
+            Kind::Signer(ref signer_kind) => match *signer_kind {
+                signer::Kind::Created(ref effect) => effect.account(),
+                signer::Kind::Removed(ref effect) => effect.account(),
+                signer::Kind::Updated(ref effect) => effect.account(),
+            },
+            Kind::Trustline(ref trustline_kind) => match *trustline_kind {
+                trustline::Kind::Created(ref effect) => effect.account(),
+                trustline::Kind::Removed(ref effect) => effect.account(),
+                trustline::Kind::Updated(ref effect) => effect.account(),
+                trustline::Kind::Authorized(ref effect) => effect.account(),
+                trustline::Kind::Deauthorized(ref effect) => effect.account(),
+                trustline::Kind::AuthorizedToMaintainLiabilities(ref effect) => effect.account(),
+                trustline::Kind::FlagsUpdated(ref effect) => effect.account(),
+            },
+            Kind::Trade(trade::Kind::Trade(ref effect)) => effect.account(),
+            Kind::Data(ref kind) => match *kind {
+                data::Kind::Created(ref effect)
+                | data::Kind::Removed(ref effect)
+                | data::Kind::Updated(ref effect) => effect.account(),
+            },
+            Kind::ClaimableBalance(claimable_balance::Kind::ClawedBack(ref effect)) => {
+                effect.account()
+            }
+            Kind::Unknown { ref raw, .. } => return raw.get("account").and_then(Value::as_str),
+        };
+        Some(account)
+    }
+
     /// Returns true if the effect is an account_created effect
     pub fn is_account_created(&self) -> bool {
         match self.kind {
@@ -178,6 +274,22 @@ impl Effect {
         }
     }
 
+    /// Returns true if the effect is an account_inflation_destination_updated effect
+    pub fn is_account_inflation_destination_updated(&self) -> bool {
+        match self.kind {
+            Kind::Account(account::Kind::InflationDestinationUpdated(_)) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the effect is a sequence_bumped effect
+    pub fn is_sequence_bumped(&self) -> bool {
+        match self.kind {
+            Kind::Account(account::Kind::SequenceBumped(_)) => true,
+            _ => false,
+        }
+    }
+
     /// Returns true if the effect is a data_created effect
     pub fn is_data_created(&self) -> bool {
         match self.kind {
@@ -266,6 +378,38 @@ impl Effect {
         }
     }
 
+    /// Returns true if the effect is a trustline authorized to maintain liabilities effect
+    pub fn is_trustline_authorized_to_maintain_liabilities(&self) -> bool {
+        match self.kind {
+            Kind::Trustline(trustline::Kind::AuthorizedToMaintainLiabilities(_)) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the effect is a trustline flags updated effect
+    pub fn is_trustline_flags_updated(&self) -> bool {
+        match self.kind {
+            Kind::Trustline(trustline::Kind::FlagsUpdated(_)) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the effect is a claimable balance clawed back effect
+    pub fn is_claimable_balance_clawed_back(&self) -> bool {
+        match self.kind {
+            Kind::ClaimableBalance(claimable_balance::Kind::ClawedBack(_)) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the effect is of a type this crate doesn't recognize
+    pub fn is_unknown(&self) -> bool {
+        match self.kind {
+            Kind::Unknown { .. } => true,
+            _ => false,
+        }
+    }
+
     /// Returns true if the effect is a trade effect
     pub fn is_trade(&self) -> bool {
         match self.kind {
@@ -275,40 +419,185 @@ impl Effect {
     }
 }
 
+/// An effect that borrows its strings from the json it was deserialized
+/// from, for reading pages of effects without allocating a string for every
+/// field of every record. It exposes the fields ingestion reads most, and
+/// `into_owned` finishes parsing it into an `Effect`.
+///
+/// Strings are only borrowed when deserializing from a borrowed source such
+/// as `serde_json::from_str` or `from_slice`, and only when they have no
+/// escapes to undo.
+#[derive(Debug, Clone)]
+pub struct EffectRef<'a> {
+    rep: Intermediate<'a>,
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for EffectRef<'a> {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Intermediate::deserialize(d).map(|rep| EffectRef { rep })
+    }
+}
+
+impl<'a> EffectRef<'a> {
+    /// The unique identifier of the effect.
+    pub fn id(&self) -> &str {
+        &self.rep.id
+    }
+
+    /// A paging token suitable for use as a cursor parameter.
+    pub fn paging_token(&self) -> &str {
+        &self.rep.paging_token
+    }
+
+    /// The type of the effect as horizon names it, such as
+    /// `account_credited`. Types this crate doesn't recognize are read like
+    /// any other.
+    pub fn type_name(&self) -> &str {
+        &self.rep.kind
+    }
+
+    /// The numeric type of the effect, when horizon includes it.
+    pub fn type_i(&self) -> Option<u32> {
+        self.rep.type_i
+    }
+
+    /// The account the effect applies to.
+    pub fn account(&self) -> Option<&str> {
+        borrowed(&self.rep.account)
+    }
+
+    /// The amount credited or debited.
+    pub fn amount(&self) -> Option<Amount> {
+        self.rep.amount
+    }
+
+    /// The type of the asset credited, debited or trusted.
+    pub fn asset_type(&self) -> Option<&str> {
+        borrowed(&self.rep.asset_type)
+    }
+
+    /// The code of the asset credited, debited or trusted, unless native.
+    pub fn asset_code(&self) -> Option<&str> {
+        borrowed(&self.rep.asset_code)
+    }
+
+    /// The issuer of the asset credited, debited or trusted, unless native.
+    pub fn asset_issuer(&self) -> Option<&str> {
+        borrowed(&self.rep.asset_issuer)
+    }
+
+    /// Parses the effect into its kind, allocating the strings it borrowed.
+    /// This fails for effects missing the fields of their kind and for types
+    /// this crate doesn't recognize.
+    pub fn into_owned(self) -> Result<Effect, serde_json::Error> {
+        self.rep.into_effect(None)
+    }
+}
+
+fn borrowed<'b>(field: &'b Option<Cow<str>>) -> Option<&'b str> {
+    field.as_ref().map(|field| field.as_ref())
+}
+
 /// Represents the actual structure of the json api. This allows us to parse
 /// directly from the captured json into our own types.
-#[derive(Debug, Deserialize, Clone)]
-struct Intermediate {
-    id: String,
-    paging_token: String,
-    #[serde(rename = "type")]
-    kind: String,
-    account: Option<String>,
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+struct Intermediate<'a> {
+    #[serde(borrow)]
+    id: Cow<'a, str>,
+    #[serde(borrow)]
+    paging_token: Cow<'a, str>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created_at: Option<DateTime<Utc>>,
+    #[serde(borrow, rename = "type")]
+    kind: Cow<'a, str>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    type_i: Option<u32>,
+    #[serde(rename = "_links", default, skip_serializing_if = "Links::is_none")]
+    links: Links<EffectLinks>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    account: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     starting_balance: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     amount: Option<Amount>,
-    asset_type: Option<String>,
-    asset_code: Option<String>,
-    asset_issuer: Option<String>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    asset_type: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    asset_code: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    asset_issuer: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     low_threshold: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     med_threshold: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     high_threshold: Option<u32>,
-    home_domain: Option<String>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    home_domain: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     auth_required_flag: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     auth_revokable_flag: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     weight: Option<u8>,
-    public_key: Option<String>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    public_key: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     limit: Option<Amount>,
-    trustor: Option<String>,
-    offer_id: Option<i64>,
-    seller: Option<String>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    trustor: Option<Cow<'a, str>>,
+    #[serde(default, skip_serializing_if = "Option::is_none",
+            deserialize_with = "deserialize::optional_from_number_or_str")]
+    offer_id: Option<u64>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    seller: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     bought_amount: Option<Amount>,
-    bought_asset_type: Option<String>,
-    bought_asset_code: Option<String>,
-    bought_asset_issuer: Option<String>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    bought_asset_type: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    bought_asset_code: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    bought_asset_issuer: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     sold_amount: Option<Amount>,
-    sold_asset_type: Option<String>,
-    sold_asset_code: Option<String>,
-    sold_asset_issuer: Option<String>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    sold_asset_type: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    sold_asset_code: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    sold_asset_issuer: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    authorized_flag: Option<bool>,
+    // Horizon misspells this field.
+    #[serde(rename = "authorized_to_maintain_liabilites_flag",
+            skip_serializing_if = "Option::is_none")]
+    authorized_to_maintain_liabilities_flag: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    clawback_enabled_flag: Option<bool>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    balance_id: Option<Cow<'a, str>>,
+    #[serde(default, deserialize_with = "deserialize::optional_from_number_or_str",
+            serialize_with = "serialize::optional_to_string", skip_serializing_if = "Option::is_none")]
+    new_seq: Option<i64>,
 }
 
 impl<'de> Deserialize<'de> for Effect {
@@ -316,16 +605,54 @@ impl<'de> Deserialize<'de> for Effect {
     where
         D: Deserializer<'de>,
     {
-        let rep = Intermediate::deserialize(d)?;
+        d.deserialize_newtype_struct(deserialize::UNKNOWN_VARIANTS, EffectVisitor)
+    }
+}
+
+/// Deserializes an effect, rejecting unknown types when visited as a newtype
+/// struct and keeping them when `deserialize::UnknownVariants` visits it as
+/// `Some`.
+struct EffectVisitor;
+
+impl<'de> de::Visitor<'de> for EffectVisitor {
+    type Value = Effect;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an effect")
+    }
+
+    fn visit_newtype_struct<D>(self, d: D) -> Result<Effect, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Intermediate::deserialize(d)?.into_effect(None)
+    }
 
-        let kind: Kind = match rep.kind.as_str() {
+    fn visit_some<D>(self, d: D) -> Result<Effect, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = Value::deserialize(d)?;
+        Intermediate::deserialize(&raw)
+            .map_err(de::Error::custom)?
+            .into_effect(Some(&raw))
+    }
+}
+
+impl<'a> Intermediate<'a> {
+    /// Builds the effect from the fields of its kind, allocating the strings
+    /// it borrowed. Unknown kinds keep the raw json when it's given and fail
+    /// otherwise.
+    fn into_effect<E: de::Error>(self, raw: Option<&Value>) -> Result<Effect, E> {
+        let rep = self;
+        let kind: Kind = match rep.kind.as_ref() {
             "account_created" => match rep {
                 Intermediate {
                     account: Some(account),
                     starting_balance: Some(starting_balance),
                     ..
                 } => Kind::Account(account::Kind::Created(account::Created::new(
-                    account,
+                    account.into_owned(),
                     starting_balance,
                 ))),
                 _ => {
@@ -338,7 +665,7 @@ impl<'de> Deserialize<'de> for Effect {
                 Intermediate {
                     account: Some(account),
                     ..
-                } => Kind::Account(account::Kind::Removed(account::Removed::new(account))),
+                } => Kind::Account(account::Kind::Removed(account::Removed::new(account.into_owned()))),
                 _ => {
                     return Err(de::Error::custom(
                         "Missing fields for account_removed effect.",
@@ -355,10 +682,10 @@ impl<'de> Deserialize<'de> for Effect {
                     ..
                 } => {
                     let asset_identifier =
-                        AssetIdentifier::new(&asset_type, asset_code, asset_issuer)
+                        owned_asset(&asset_type, asset_code, asset_issuer)
                             .map_err(de::Error::custom)?;
                     Kind::Account(account::Kind::Credited(account::Credited::new(
-                        account,
+                        account.into_owned(),
                         amount,
                         asset_identifier,
                     )))
@@ -379,10 +706,10 @@ impl<'de> Deserialize<'de> for Effect {
                     ..
                 } => {
                     let asset_identifier =
-                        AssetIdentifier::new(&asset_type, asset_code, asset_issuer)
+                        owned_asset(&asset_type, asset_code, asset_issuer)
                             .map_err(de::Error::custom)?;
                     Kind::Account(account::Kind::Debited(account::Debited::new(
-                        account,
+                        account.into_owned(),
                         amount,
                         asset_identifier,
                     )))
@@ -402,7 +729,7 @@ impl<'de> Deserialize<'de> for Effect {
                     ..
                 } => Kind::Account(account::Kind::ThresholdsUpdated(
                     account::ThresholdsUpdated::new(
-                        account,
+                        account.into_owned(),
                         low_threshold,
                         med_threshold,
                         high_threshold,
@@ -420,7 +747,7 @@ impl<'de> Deserialize<'de> for Effect {
                     home_domain: Some(home_domain),
                     ..
                 } => Kind::Account(account::Kind::HomeDomainUpdated(
-                    account::HomeDomainUpdated::new(account, home_domain),
+                    account::HomeDomainUpdated::new(account.into_owned(), home_domain.into_owned()),
                 )),
                 _ => {
                     return Err(de::Error::custom(
@@ -437,7 +764,7 @@ impl<'de> Deserialize<'de> for Effect {
                 } => {
                     let flags = Flags::new(auth_required_flag, auth_revokable_flag);
                     Kind::Account(account::Kind::FlagsUpdated(account::FlagsUpdated::new(
-                        account,
+                        account.into_owned(),
                         flags,
                     )))
                 }
@@ -447,25 +774,53 @@ impl<'de> Deserialize<'de> for Effect {
                     ))
                 }
             },
+            "account_inflation_destination_updated" => match rep {
+                Intermediate {
+                    account: Some(account),
+                    ..
+                } => Kind::Account(account::Kind::InflationDestinationUpdated(
+                    account::InflationDestinationUpdated::new(account.into_owned()),
+                )),
+                _ => {
+                    return Err(de::Error::custom(
+                        "Missing fields for account_inflation_destination_updated effect.",
+                    ))
+                }
+            },
+            "sequence_bumped" => match rep {
+                Intermediate {
+                    account: Some(account),
+                    new_seq: Some(new_seq),
+                    ..
+                } => Kind::Account(account::Kind::SequenceBumped(account::SequenceBumped::new(
+                    account.into_owned(),
+                    new_seq,
+                ))),
+                _ => {
+                    return Err(de::Error::custom(
+                        "Missing fields for sequence_bumped effect.",
+                    ))
+                }
+            },
             "data_created" => match rep {
                 Intermediate {
                     account: Some(account),
                     ..
-                } => Kind::Data(data::Kind::Created(data::Effect::new(account))),
+                } => Kind::Data(data::Kind::Created(data::Effect::new(account.into_owned()))),
                 _ => return Err(de::Error::custom("Missing fields for data_created effect.")),
             },
             "data_removed" => match rep {
                 Intermediate {
                     account: Some(account),
                     ..
-                } => Kind::Data(data::Kind::Removed(data::Effect::new(account))),
+                } => Kind::Data(data::Kind::Removed(data::Effect::new(account.into_owned()))),
                 _ => return Err(de::Error::custom("Missing fields for data_removed effect.")),
             },
             "data_updated" => match rep {
                 Intermediate {
                     account: Some(account),
                     ..
-                } => Kind::Data(data::Kind::Updated(data::Effect::new(account))),
+                } => Kind::Data(data::Kind::Updated(data::Effect::new(account.into_owned()))),
                 _ => return Err(de::Error::custom("Missing fields for data_updated effect.")),
             },
             "signer_created" => match rep {
@@ -475,8 +830,8 @@ impl<'de> Deserialize<'de> for Effect {
                     weight: Some(weight),
                     ..
                 } => Kind::Signer(signer::Kind::Created(signer::Created::new(
-                    account,
-                    public_key,
+                    account.into_owned(),
+                    public_key.into_owned(),
                     weight,
                 ))),
                 _ => {
@@ -492,8 +847,8 @@ impl<'de> Deserialize<'de> for Effect {
                     weight: Some(weight),
                     ..
                 } => Kind::Signer(signer::Kind::Removed(signer::Removed::new(
-                    account,
-                    public_key,
+                    account.into_owned(),
+                    public_key.into_owned(),
                     weight,
                 ))),
                 _ => {
@@ -509,8 +864,8 @@ impl<'de> Deserialize<'de> for Effect {
                     weight: Some(weight),
                     ..
                 } => Kind::Signer(signer::Kind::Updated(signer::Updated::new(
-                    account,
-                    public_key,
+                    account.into_owned(),
+                    public_key.into_owned(),
                     weight,
                 ))),
                 _ => {
@@ -529,10 +884,10 @@ impl<'de> Deserialize<'de> for Effect {
                     ..
                 } => {
                     let asset_identifier =
-                        AssetIdentifier::new(&asset_type, asset_code, asset_issuer)
+                        owned_asset(&asset_type, asset_code, asset_issuer)
                             .map_err(de::Error::custom)?;
                     Kind::Trustline(trustline::Kind::Created(trustline::Created::new(
-                        account,
+                        account.into_owned(),
                         limit,
                         asset_identifier,
                     )))
@@ -553,10 +908,10 @@ impl<'de> Deserialize<'de> for Effect {
                     ..
                 } => {
                     let asset_identifier =
-                        AssetIdentifier::new(&asset_type, asset_code, asset_issuer)
+                        owned_asset(&asset_type, asset_code, asset_issuer)
                             .map_err(de::Error::custom)?;
                     Kind::Trustline(trustline::Kind::Removed(trustline::Removed::new(
-                        account,
+                        account.into_owned(),
                         limit,
                         asset_identifier,
                     )))
@@ -577,10 +932,10 @@ impl<'de> Deserialize<'de> for Effect {
                     ..
                 } => {
                     let asset_identifier =
-                        AssetIdentifier::new(&asset_type, asset_code, asset_issuer)
+                        owned_asset(&asset_type, asset_code, asset_issuer)
                             .map_err(de::Error::custom)?;
                     Kind::Trustline(trustline::Kind::Updated(trustline::Updated::new(
-                        account,
+                        account.into_owned(),
                         limit,
                         asset_identifier,
                     )))
@@ -599,10 +954,10 @@ impl<'de> Deserialize<'de> for Effect {
                     trustor,
                     ..
                 } => {
-                    let asset_identifier = AssetIdentifier::new(&asset_type, asset_code, trustor)
+                    let asset_identifier = owned_asset(&asset_type, asset_code, trustor)
                         .map_err(de::Error::custom)?;
                     Kind::Trustline(trustline::Kind::Authorized(trustline::Authorized::new(
-                        account,
+                        account.into_owned(),
                         asset_identifier,
                     )))
                 }
@@ -620,10 +975,10 @@ impl<'de> Deserialize<'de> for Effect {
                     trustor,
                     ..
                 } => {
-                    let asset_identifier = AssetIdentifier::new(&asset_type, asset_code, trustor)
+                    let asset_identifier = owned_asset(&asset_type, asset_code, trustor)
                         .map_err(de::Error::custom)?;
                     Kind::Trustline(trustline::Kind::Deauthorized(trustline::Deauthorized::new(
-                        account,
+                        account.into_owned(),
                         asset_identifier,
                     )))
                 }
@@ -633,6 +988,71 @@ impl<'de> Deserialize<'de> for Effect {
                     ))
                 }
             },
+            "trustline_authorized_to_maintain_liabilities" => match rep {
+                Intermediate {
+                    account: Some(account),
+                    asset_type: Some(asset_type),
+                    asset_code,
+                    ..
+                } => {
+                    // The account is the issuer of the asset.
+                    let asset_identifier =
+                        owned_asset(&asset_type, asset_code, Some(account.clone()))
+                            .map_err(de::Error::custom)?;
+                    Kind::Trustline(trustline::Kind::AuthorizedToMaintainLiabilities(
+                        trustline::AuthorizedToMaintainLiabilities::new(account.into_owned(), asset_identifier),
+                    ))
+                }
+                _ => {
+                    return Err(de::Error::custom(
+                        "Missing fields for trustline_authorized_to_maintain_liabilities effect.",
+                    ))
+                }
+            },
+            "trustline_flags_updated" => match rep {
+                Intermediate {
+                    account: Some(account),
+                    trustor: Some(trustor),
+                    asset_type: Some(asset_type),
+                    asset_code,
+                    asset_issuer,
+                    authorized_flag,
+                    authorized_to_maintain_liabilities_flag,
+                    clawback_enabled_flag,
+                    ..
+                } => {
+                    let asset_identifier =
+                        owned_asset(&asset_type, asset_code, asset_issuer)
+                            .map_err(de::Error::custom)?;
+                    Kind::Trustline(trustline::Kind::FlagsUpdated(trustline::FlagsUpdated::new(
+                        account.into_owned(),
+                        trustor.into_owned(),
+                        asset_identifier,
+                        authorized_flag,
+                        authorized_to_maintain_liabilities_flag,
+                        clawback_enabled_flag,
+                    )))
+                }
+                _ => {
+                    return Err(de::Error::custom(
+                        "Missing fields for trustline_flags_updated effect.",
+                    ))
+                }
+            },
+            "claimable_balance_clawed_back" => match rep {
+                Intermediate {
+                    account: Some(account),
+                    balance_id: Some(balance_id),
+                    ..
+                } => Kind::ClaimableBalance(claimable_balance::Kind::ClawedBack(
+                    claimable_balance::ClawedBack::new(account.into_owned(), balance_id.into_owned()),
+                )),
+                _ => {
+                    return Err(de::Error::custom(
+                        "Missing fields for claimable_balance_clawed_back effect.",
+                    ))
+                }
+            },
             "trade" => match rep {
                 Intermediate {
                     account: Some(account),
@@ -649,17 +1069,17 @@ impl<'de> Deserialize<'de> for Effect {
                     ..
                 } => {
                     let sold_asset =
-                        AssetIdentifier::new(&sold_asset_type, sold_asset_code, sold_asset_issuer)
+                        owned_asset(&sold_asset_type, sold_asset_code, sold_asset_issuer)
                             .map_err(de::Error::custom)?;
-                    let bought_asset = AssetIdentifier::new(
+                    let bought_asset = owned_asset(
                         &bought_asset_type,
                         bought_asset_code,
                         bought_asset_issuer,
                     ).map_err(de::Error::custom)?;
                     Kind::Trade(trade::Kind::Trade(trade::Trade::new(
-                        account,
+                        account.into_owned(),
                         offer_id,
-                        seller,
+                        seller.into_owned(),
                         sold_amount,
                         sold_asset,
                         bought_amount,
@@ -668,13 +1088,220 @@ impl<'de> Deserialize<'de> for Effect {
                 }
                 _ => return Err(de::Error::custom("Missing fields for trade effect.")),
             },
-            kind => return Err(de::Error::custom(format!("Unknown effect type: {}", kind))),
+            kind => match raw {
+                Some(raw) => Kind::Unknown {
+                    type_name: kind.to_string(),
+                    raw: raw.clone(),
+                },
+                None => return Err(de::Error::custom(format!("Unknown effect type: {}", kind))),
+            },
         };
 
         Ok(Effect {
-            id: rep.id,
-            paging_token: rep.paging_token,
+            id: rep.id.into_owned(),
+            paging_token: rep.paging_token.into_owned(),
+            created_at: rep.created_at,
             kind,
+            links: rep.links,
         })
     }
 }
+
+impl Serialize for Effect {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut rep = Intermediate {
+            id: Cow::Borrowed(&self.id),
+            paging_token: Cow::Borrowed(&self.paging_token),
+            created_at: self.created_at,
+            type_i: Some(self.type_i()),
+            links: self.links.clone(),
+            ..Intermediate::default()
+        };
+        rep.kind = match self.kind {
+            Kind::Account(ref account_kind) => match *account_kind {
+                account::Kind::Created(ref effect) => {
+                    rep.account = Some(effect.account().into());
+                    rep.starting_balance = Some(effect.starting_balance());
+                    "account_created"
+                }
+                account::Kind::Removed(ref effect) => {
+                    rep.account = Some(effect.account().into());
+                    "account_removed"
+                }
+                account::Kind::Credited(ref effect) => {
+                    rep.account = Some(effect.account().into());
+                    rep.amount = Some(effect.amount());
+                    set_asset(effect.asset(), &mut rep);
+                    "account_credited"
+                }
+                account::Kind::Debited(ref effect) => {
+                    rep.account = Some(effect.account().into());
+                    rep.amount = Some(effect.amount());
+                    set_asset(effect.asset(), &mut rep);
+                    "account_debited"
+                }
+                account::Kind::ThresholdsUpdated(ref effect) => {
+                    rep.account = Some(effect.account().into());
+                    rep.low_threshold = Some(effect.low());
+                    rep.med_threshold = Some(effect.med());
+                    rep.high_threshold = Some(effect.high());
+                    "account_thresholds_updated"
+                }
+                account::Kind::HomeDomainUpdated(ref effect) => {
+                    rep.account = Some(effect.account().into());
+                    rep.home_domain = Some(effect.home_domain().into());
+                    "account_home_domain_updated"
+                }
+                account::Kind::FlagsUpdated(ref effect) => {
+                    rep.account = Some(effect.account().into());
+                    rep.auth_required_flag = Some(effect.flags().is_auth_required());
+                    rep.auth_revokable_flag = Some(effect.flags().is_auth_revocable());
+                    "account_flags_updated"
+                }
+                account::Kind::InflationDestinationUpdated(ref effect) => {
+                    rep.account = Some(effect.account().into());
+                    "account_inflation_destination_updated"
+                }
+                account::Kind::SequenceBumped(ref effect) => {
+                    rep.account = Some(effect.account().into());
+                    rep.new_seq = Some(effect.new_seq());
+                    "sequence_bumped"
+                }
+            },
+            Kind::Signer(ref signer_kind) => {
+                let (name, account, public_key, weight) = match *signer_kind {
+                    signer::Kind::Created(ref effect) => (
+                        "signer_created",
+                        effect.account(),
+                        effect.public_key(),
+                        effect.weight(),
+                    ),
+                    signer::Kind::Removed(ref effect) => (
+                        "signer_removed",
+                        effect.account(),
+                        effect.public_key(),
+                        effect.weight(),
+                    ),
+                    signer::Kind::Updated(ref effect) => (
+                        "signer_updated",
+                        effect.account(),
+                        effect.public_key(),
+                        effect.weight(),
+                    ),
+                };
+                rep.account = Some(account.into());
+                rep.public_key = Some(public_key.into());
+                rep.weight = Some(weight);
+                name
+            }
+            Kind::Trustline(ref trustline_kind) => match *trustline_kind {
+                trustline::Kind::Created(ref effect) => {
+                    rep.account = Some(effect.account().into());
+                    rep.limit = Some(effect.limit());
+                    set_asset(effect.asset(), &mut rep);
+                    "trustline_created"
+                }
+                trustline::Kind::Removed(ref effect) => {
+                    rep.account = Some(effect.account().into());
+                    rep.limit = Some(effect.limit());
+                    set_asset(effect.asset(), &mut rep);
+                    "trustline_removed"
+                }
+                trustline::Kind::Updated(ref effect) => {
+                    rep.account = Some(effect.account().into());
+                    rep.limit = Some(effect.limit());
+                    set_asset(effect.asset(), &mut rep);
+                    "trustline_updated"
+                }
+                // Horizon names the issuer of the asset as the trustor in
+                // these two effects.
+                trustline::Kind::Authorized(ref effect) => {
+                    rep.account = Some(effect.account().into());
+                    rep.asset_type = Some(effect.asset().asset_type().into());
+                    rep.asset_code = effect.asset().asset_code().map(Cow::Owned);
+                    rep.trustor = effect.asset().asset_issuer().map(Cow::Owned);
+                    "trustline_authorized"
+                }
+                trustline::Kind::Deauthorized(ref effect) => {
+                    rep.account = Some(effect.account().into());
+                    rep.asset_type = Some(effect.asset().asset_type().into());
+                    rep.asset_code = effect.asset().asset_code().map(Cow::Owned);
+                    rep.trustor = effect.asset().asset_issuer().map(Cow::Owned);
+                    "trustline_deauthorized"
+                }
+                trustline::Kind::AuthorizedToMaintainLiabilities(ref effect) => {
+                    rep.account = Some(effect.account().into());
+                    rep.asset_type = Some(effect.asset().asset_type().into());
+                    rep.asset_code = effect.asset().asset_code().map(Cow::Owned);
+                    "trustline_authorized_to_maintain_liabilities"
+                }
+                trustline::Kind::FlagsUpdated(ref effect) => {
+                    rep.account = Some(effect.account().into());
+                    rep.trustor = Some(effect.trustor().into());
+                    set_asset(effect.asset(), &mut rep);
+                    rep.authorized_flag = effect.authorized();
+                    rep.authorized_to_maintain_liabilities_flag =
+                        effect.authorized_to_maintain_liabilities();
+                    rep.clawback_enabled_flag = effect.clawback_enabled();
+                    "trustline_flags_updated"
+                }
+            },
+            Kind::Trade(trade::Kind::Trade(ref effect)) => {
+                rep.account = Some(effect.account().into());
+                rep.offer_id = Some(effect.offer_id());
+                rep.seller = Some(effect.seller().into());
+                rep.sold_amount = Some(effect.sold_amount());
+                rep.sold_asset_type = Some(effect.sold_asset().asset_type().into());
+                rep.sold_asset_code = effect.sold_asset().asset_code().map(Cow::Owned);
+                rep.sold_asset_issuer = effect.sold_asset().asset_issuer().map(Cow::Owned);
+                rep.bought_amount = Some(effect.bought_amount());
+                rep.bought_asset_type = Some(effect.bought_asset().asset_type().into());
+                rep.bought_asset_code = effect.bought_asset().asset_code().map(Cow::Owned);
+                rep.bought_asset_issuer = effect.bought_asset().asset_issuer().map(Cow::Owned);
+                "trade"
+            }
+            Kind::Data(ref kind) => {
+                let (name, effect) = match *kind {
+                    data::Kind::Created(ref effect) => ("data_created", effect),
+                    data::Kind::Removed(ref effect) => ("data_removed", effect),
+                    data::Kind::Updated(ref effect) => ("data_updated", effect),
+                };
+                rep.account = Some(effect.account().into());
+                name
+            }
+            Kind::ClaimableBalance(claimable_balance::Kind::ClawedBack(ref effect)) => {
+                rep.account = Some(effect.account().into());
+                rep.balance_id = Some(effect.balance_id().into());
+                "claimable_balance_clawed_back"
+            }
+            // Unknown effects are passed through exactly as horizon sent them
+            Kind::Unknown { ref raw, .. } => return raw.serialize(s),
+        }.into();
+        rep.serialize(s)
+    }
+}
+
+fn set_asset<'a>(asset: &'a AssetIdentifier, rep: &mut Intermediate<'a>) {
+    rep.asset_type = Some(asset.asset_type().into());
+    if !asset.is_native() {
+        rep.asset_code = Some(asset.code().into());
+        rep.asset_issuer = Some(asset.issuer().into());
+    }
+}
+
+/// Builds an asset from the fields of an effect, allocating the strings it
+/// borrowed.
+fn owned_asset(
+    asset_type: &str,
+    asset_code: Option<Cow<str>>,
+    asset_issuer: Option<Cow<str>>,
+) -> Result<AssetIdentifier, String> {
+    AssetIdentifier::new(
+        asset_type,
+        asset_code.map(Cow::into_owned),
+        asset_issuer.map(Cow::into_owned),
+    )
+}
@@ -1,9 +1,53 @@
 use resources::{Amount, asset::Flags,
-                effect::{Effect, EffectKind, account::Kind as AccountKind,
+                effect::{Effect, EffectKind, EffectRef, account::Kind as AccountKind,
+                         claimable_balance::Kind as ClaimableBalanceKind,
                          data::Kind as DataKind, signer::Kind as SignerKind,
                          trade::Kind as TradeKind, trustline::Kind as TrustlineKind}};
 use serde_json;
 
+mod serializes_like_horizon {
+    use super::*;
+    use resources::serialize::assert_round_trips;
+
+    macro_rules! assert_round_trips_fixture {
+        ($name:ident) => {
+            #[test]
+            fn $name() {
+                assert_round_trips::<Effect>(include_str!(concat!(
+                    "../../../fixtures/effects/",
+                    stringify!($name),
+                    ".json"
+                )));
+            }
+        };
+    }
+
+    assert_round_trips_fixture!(account_created);
+    assert_round_trips_fixture!(account_credited);
+    assert_round_trips_fixture!(account_debited);
+    assert_round_trips_fixture!(account_flags_updated);
+    assert_round_trips_fixture!(account_home_domain_updated);
+    assert_round_trips_fixture!(account_inflation_destination_updated);
+    assert_round_trips_fixture!(account_removed);
+    assert_round_trips_fixture!(account_threshold_updated);
+    assert_round_trips_fixture!(claimable_balance_clawed_back);
+    assert_round_trips_fixture!(data_created);
+    assert_round_trips_fixture!(data_removed);
+    assert_round_trips_fixture!(data_updated);
+    assert_round_trips_fixture!(sequence_bumped);
+    assert_round_trips_fixture!(signer_created);
+    assert_round_trips_fixture!(signer_removed);
+    assert_round_trips_fixture!(signer_updated);
+    assert_round_trips_fixture!(trade);
+    assert_round_trips_fixture!(trustline_authorized);
+    assert_round_trips_fixture!(trustline_authorized_to_maintain_liabilities);
+    assert_round_trips_fixture!(trustline_created);
+    assert_round_trips_fixture!(trustline_deauthorized);
+    assert_round_trips_fixture!(trustline_flags_updated);
+    assert_round_trips_fixture!(trustline_removed);
+    assert_round_trips_fixture!(trustline_updated);
+}
+
 fn account_created_json() -> &'static str {
     include_str!("../../../fixtures/effects/account_created.json")
 }
@@ -146,6 +190,46 @@ fn it_parses_account_flags_updated_from_json() {
     }
 }
 
+fn account_inflation_destination_updated_json() -> &'static str {
+    include_str!("../../../fixtures/effects/account_inflation_destination_updated.json")
+}
+#[test]
+fn it_parses_account_inflation_destination_updated_from_json() {
+    let effect: Effect =
+        serde_json::from_str(&account_inflation_destination_updated_json()).unwrap();
+    assert!(effect.is_account_inflation_destination_updated());
+    assert_eq!(effect.type_i(), 7);
+    if let &EffectKind::Account(AccountKind::InflationDestinationUpdated(ref effect_details)) =
+        effect.kind()
+    {
+        assert_eq!(
+            effect_details.account(),
+            "GCGG3CIRBG2TTBR4HYZJ7JLDRFKZIYOAHFXRWLU62CA2QN52P2SUQNPJ"
+        );
+    } else {
+        panic!("Did not generate account inflation destination updated kind");
+    }
+}
+
+fn sequence_bumped_json() -> &'static str {
+    include_str!("../../../fixtures/effects/sequence_bumped.json")
+}
+#[test]
+fn it_parses_sequence_bumped_from_json() {
+    let effect: Effect = serde_json::from_str(&sequence_bumped_json()).unwrap();
+    assert!(effect.is_sequence_bumped());
+    assert_eq!(effect.type_i(), 43);
+    if let &EffectKind::Account(AccountKind::SequenceBumped(ref effect_details)) = effect.kind() {
+        assert_eq!(
+            effect_details.account(),
+            "GBH5VQMUXKQWV7XZT6N2JHZHF3N5FNPJRVQ7XNU6AVVHHSGRV7IDVPEC"
+        );
+        assert_eq!(effect_details.new_seq(), 86_524_787_647_590_400);
+    } else {
+        panic!("Did not generate sequence bumped kind");
+    }
+}
+
 mod data {
     use super::*;
 
@@ -371,6 +455,75 @@ fn it_parses_trustline_deauthorized_from_json() {
     }
 }
 
+fn trustline_authorized_to_maintain_liabilities_json() -> &'static str {
+    include_str!("../../../fixtures/effects/trustline_authorized_to_maintain_liabilities.json")
+}
+#[test]
+fn it_parses_trustline_authorized_to_maintain_liabilities_from_json() {
+    let effect: Effect =
+        serde_json::from_str(&trustline_authorized_to_maintain_liabilities_json()).unwrap();
+    assert!(effect.is_trustline_authorized_to_maintain_liabilities());
+    assert_eq!(effect.type_i(), 25);
+    if let &EffectKind::Trustline(TrustlineKind::AuthorizedToMaintainLiabilities(
+        ref effect_details,
+    )) = effect.kind()
+    {
+        assert_eq!(
+            effect_details.account(),
+            "GC23QF2HUE52AMXUFUH3AYJAXXGXXV2VHXYYR6EYXETPKDXZSAW67XO4"
+        );
+        assert_eq!(effect_details.asset().code(), "USD");
+        assert_eq!(
+            effect_details.asset().issuer(),
+            "GC23QF2HUE52AMXUFUH3AYJAXXGXXV2VHXYYR6EYXETPKDXZSAW67XO4"
+        );
+    } else {
+        panic!("Did not generate trustline authorized to maintain liabilities kind");
+    }
+}
+
+fn trustline_flags_updated_json() -> &'static str {
+    include_str!("../../../fixtures/effects/trustline_flags_updated.json")
+}
+#[test]
+fn it_parses_trustline_flags_updated_from_json() {
+    let effect: Effect = serde_json::from_str(&trustline_flags_updated_json()).unwrap();
+    assert!(effect.is_trustline_flags_updated());
+    assert_eq!(effect.type_i(), 26);
+    if let &EffectKind::Trustline(TrustlineKind::FlagsUpdated(ref effect_details)) = effect.kind() {
+        assert_eq!(
+            effect_details.trustor(),
+            "GBXGQJWVLWOYHFLVTKWV5FGHA3LNYY2JQKM7OAJAUEQFU6LPCSEFVXON"
+        );
+        assert_eq!(effect_details.asset().code(), "USD");
+        assert_eq!(effect_details.authorized(), Some(false));
+        assert_eq!(effect_details.authorized_to_maintain_liabilities(), None);
+        assert_eq!(effect_details.clawback_enabled(), Some(true));
+    } else {
+        panic!("Did not generate trustline flags updated kind");
+    }
+}
+
+fn claimable_balance_clawed_back_json() -> &'static str {
+    include_str!("../../../fixtures/effects/claimable_balance_clawed_back.json")
+}
+#[test]
+fn it_parses_claimable_balance_clawed_back_from_json() {
+    let effect: Effect = serde_json::from_str(&claimable_balance_clawed_back_json()).unwrap();
+    assert!(effect.is_claimable_balance_clawed_back());
+    assert_eq!(effect.type_i(), 80);
+    if let &EffectKind::ClaimableBalance(ClaimableBalanceKind::ClawedBack(ref effect_details)) =
+        effect.kind()
+    {
+        assert_eq!(
+            effect_details.balance_id(),
+            "00000000da0d57da7d4850e7fc10d2a9d0ebc731f7afb40574c03395b17d49149b91f5be"
+        );
+    } else {
+        panic!("Did not generate claimable balance clawed back kind");
+    }
+}
+
 fn trade_json() -> &'static str {
     include_str!("../../../fixtures/effects/trade.json")
 }
@@ -398,6 +551,25 @@ fn it_parses_trade_from_json() {
     }
 }
 
+#[test]
+fn it_keeps_unknown_effect_types_when_allowed() {
+    use resources::deserialize::from_slice;
+
+    let json = r#"{
+        "id": "0000000012884905985-0000000001",
+        "paging_token": "12884905985-1",
+        "account": "GA6U5X6WOPNKKDKQULBR7IDHDBAQKOWPHYEC7WSXHZBFEYFD3XVZAKOO",
+        "type": "contract_credited",
+        "type_i": 96
+    }"#;
+    assert!(serde_json::from_str::<Effect>(json).is_err());
+
+    let effect: Effect = from_slice(json.as_bytes(), true).unwrap();
+    assert!(effect.is_unknown());
+    assert_eq!(effect.type_i(), 96);
+    assert_eq!(effect.kind_name(), "contract_credited");
+}
+
 mod errors_on_missing_fields_for_effect_types {
     use super::*;
 
@@ -434,8 +606,41 @@ mod errors_on_missing_fields_for_effect_types {
     assert_err_on_missing_fields!(trustline_removed, 21);
     assert_err_on_missing_fields!(trustline_updated, 22);
     assert_err_on_missing_fields!(trustline_authorized, 23);
+    assert_err_on_missing_fields!(trustline_authorized_to_maintain_liabilities, 25);
+    assert_err_on_missing_fields!(trustline_flags_updated, 26);
     assert_err_on_missing_fields!(trade, 33);
     assert_err_on_missing_fields!(data_created, 40);
     assert_err_on_missing_fields!(data_removed, 41);
     assert_err_on_missing_fields!(data_updated, 42);
+    assert_err_on_missing_fields!(claimable_balance_clawed_back, 80);
+}
+
+#[test]
+fn it_reads_effects_by_reference_without_allocating_strings() {
+    let json = include_str!("../../../fixtures/effects/account_credited.json");
+    let effect: EffectRef = serde_json::from_str(json).unwrap();
+    assert_eq!(effect.type_name(), "account_credited");
+    let within = json.as_ptr() as usize..json.as_ptr() as usize + json.len();
+    assert!(within.contains(&(effect.id().as_ptr() as usize)));
+    assert!(within.contains(&(effect.account().unwrap().as_ptr() as usize)));
+    assert_eq!(
+        effect.into_owned().unwrap(),
+        serde_json::from_str::<Effect>(json).unwrap()
+    );
+}
+
+#[test]
+fn it_reads_the_account_of_every_kind_of_effect() {
+    use std::fs;
+    use std::path::Path;
+
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/effects");
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        let json = fs::read_to_string(&path).unwrap();
+        let effect: Effect = serde_json::from_str(&json).unwrap();
+        let by_ref: EffectRef = serde_json::from_str(&json).unwrap();
+        assert!(effect.account().is_some(), "{:?}", path);
+        assert_eq!(effect.account(), by_ref.account(), "{:?}", path);
+    }
 }
@@ -3,7 +3,7 @@ use resources::{Amount, AssetIdentifier};
 
 /// Enum representing all the different kinds of effects that represent
 /// changes made to an account.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
 pub enum Kind {
     /// An effect representing the fact that an trade occured
     Trade(Trade),
@@ -11,10 +11,10 @@ pub enum Kind {
 
 /// People on the Stellar network can make offers to buy or sell assets. When an offer is fully or
 /// partially fulfilled, a trade happens.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
 pub struct Trade {
     account: String,
-    offer_id: i64,
+    offer_id: u64,
     seller: String,
     sold_amount: Amount,
     sold_asset: AssetIdentifier,
@@ -26,7 +26,7 @@ impl Trade {
     /// Creates a new Trade
     pub fn new(
         account: String,
-        offer_id: i64,
+        offer_id: u64,
         seller: String,
         sold_amount: Amount,
         sold_asset: AssetIdentifier,
@@ -50,7 +50,7 @@ impl Trade {
     }
 
     /// The id of the offer which was used in executing the trade
-    pub fn offer_id(&self) -> i64 {
+    pub fn offer_id(&self) -> u64 {
         self.offer_id
     }
 
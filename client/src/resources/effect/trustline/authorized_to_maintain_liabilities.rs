@@ -0,0 +1,26 @@
+use resources::AssetIdentifier;
+/// This effect can be the result of an allow trust or set trust line flags
+/// operation and represents the fact that an account can keep its existing
+/// offers and balances of an asset but can't receive any more of it.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct AuthorizedToMaintainLiabilities {
+    account: String,
+    asset: AssetIdentifier,
+}
+
+impl AuthorizedToMaintainLiabilities {
+    /// Creates a new Trustline AuthorizedToMaintainLiabilities effect
+    pub fn new(account: String, asset: AssetIdentifier) -> AuthorizedToMaintainLiabilities {
+        AuthorizedToMaintainLiabilities { account, asset }
+    }
+
+    /// The public address of the account issuing the asset
+    pub fn account(&self) -> &String {
+        &self.account
+    }
+
+    /// The asset whose trustline is restricted.
+    pub fn asset(&self) -> &AssetIdentifier {
+        &self.asset
+    }
+}
@@ -1,7 +1,7 @@
 use resources::AssetIdentifier;
 /// This effect can be the result of a allow trust operation and represents
 /// the fact that an asset issuer will no longer allow an account to hold its assets.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
 pub struct Deauthorized {
     account: String,
     asset: AssetIdentifier,
@@ -0,0 +1,65 @@
+use resources::AssetIdentifier;
+/// This effect is the result of a set trust line flags operation and
+/// represents a change to the authorization or clawback flags of a trustline.
+/// Flags that weren't changed by the operation are `None`.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct FlagsUpdated {
+    account: String,
+    trustor: String,
+    asset: AssetIdentifier,
+    authorized: Option<bool>,
+    authorized_to_maintain_liabilities: Option<bool>,
+    clawback_enabled: Option<bool>,
+}
+
+impl FlagsUpdated {
+    /// Creates a new Trustline FlagsUpdated effect
+    pub fn new(
+        account: String,
+        trustor: String,
+        asset: AssetIdentifier,
+        authorized: Option<bool>,
+        authorized_to_maintain_liabilities: Option<bool>,
+        clawback_enabled: Option<bool>,
+    ) -> FlagsUpdated {
+        FlagsUpdated {
+            account,
+            trustor,
+            asset,
+            authorized,
+            authorized_to_maintain_liabilities,
+            clawback_enabled,
+        }
+    }
+
+    /// The public address of the account issuing the asset
+    pub fn account(&self) -> &String {
+        &self.account
+    }
+
+    /// The public address of the account holding the trustline
+    pub fn trustor(&self) -> &String {
+        &self.trustor
+    }
+
+    /// The asset of the trustline.
+    pub fn asset(&self) -> &AssetIdentifier {
+        &self.asset
+    }
+
+    /// The new value of the authorized flag, if it changed.
+    pub fn authorized(&self) -> Option<bool> {
+        self.authorized
+    }
+
+    /// The new value of the authorized to maintain liabilities flag, if it
+    /// changed.
+    pub fn authorized_to_maintain_liabilities(&self) -> Option<bool> {
+        self.authorized_to_maintain_liabilities
+    }
+
+    /// The new value of the clawback enabled flag, if it changed.
+    pub fn clawback_enabled(&self) -> Option<bool> {
+        self.clawback_enabled
+    }
+}
@@ -1,20 +1,24 @@
 //! Contains effects that pertain to changes in a trustline
 
 mod authorized;
+mod authorized_to_maintain_liabilities;
 mod created;
 mod deauthorized;
+mod flags_updated;
 mod removed;
 mod updated;
 
 pub use self::authorized::Authorized;
+pub use self::authorized_to_maintain_liabilities::AuthorizedToMaintainLiabilities;
 pub use self::created::Created;
 pub use self::deauthorized::Deauthorized;
+pub use self::flags_updated::FlagsUpdated;
 pub use self::removed::Removed;
 pub use self::updated::Updated;
 
 /// Enum representing all the different kinds of effects that represent
 /// changes made to an account.
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
 pub enum Kind {
     /// An effect representing the creation of a trustline as a result of an operation
     Created(Created),
@@ -26,4 +30,9 @@ pub enum Kind {
     Authorized(Authorized),
     /// An effect representing a trustline being deauthorized as a result of an allow trust operation
     Deauthorized(Deauthorized),
+    /// An effect representing a trustline being restricted to maintaining its existing liabilities
+    AuthorizedToMaintainLiabilities(AuthorizedToMaintainLiabilities),
+    /// An effect representing a change to the flags of a trustline as a result of a set trust line
+    /// flags operation
+    FlagsUpdated(FlagsUpdated),
 }
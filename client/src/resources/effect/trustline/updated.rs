@@ -1,7 +1,7 @@
 use resources::{Amount, AssetIdentifier};
 /// This effect can be the result of a change trust operation and represents
 /// the fact that a trustline has been updated between an asset and account
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
 pub struct Updated {
     account: String,
     limit: Amount,
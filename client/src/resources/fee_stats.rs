@@ -0,0 +1,256 @@
+use resources::{deserialize, serialize};
+
+/// Statistics about the fees of the transactions in the last few ledgers,
+/// used to decide what fee to offer when the network is busy. Fees are per
+/// operation, in stroops.
+///
+/// <https://www.stellar.org/developers/horizon/reference/endpoints/fee-stats.html>
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct FeeStats {
+    #[serde(serialize_with = "serialize::to_string",
+            deserialize_with = "deserialize::from_number_or_str")]
+    last_ledger: u32,
+    #[serde(serialize_with = "serialize::to_string",
+            deserialize_with = "deserialize::from_number_or_str")]
+    last_ledger_base_fee: u32,
+    ledger_capacity_usage: String,
+    fee_charged: FeeDistribution,
+    max_fee: FeeDistribution,
+}
+
+impl FeeStats {
+    /// Constructs fee stats where every fee was the given base fee, as on
+    /// a quiet network.
+    pub fn new(last_ledger: u32, base_fee: u32) -> Self {
+        FeeStats {
+            last_ledger,
+            last_ledger_base_fee: base_fee,
+            ledger_capacity_usage: "0".to_string(),
+            fee_charged: FeeDistribution::flat(base_fee.into()),
+            max_fee: FeeDistribution::flat(base_fee.into()),
+        }
+    }
+
+    /// Replaces the distribution of fees charged.
+    pub fn with_fee_charged(mut self, fee_charged: FeeDistribution) -> Self {
+        self.fee_charged = fee_charged;
+        self
+    }
+
+    /// The sequence of the last ledger the stats include.
+    pub fn last_ledger(&self) -> u32 {
+        self.last_ledger
+    }
+
+    /// The base fee of the last ledger, which is the least any transaction
+    /// can offer per operation.
+    pub fn last_ledger_base_fee(&self) -> u32 {
+        self.last_ledger_base_fee
+    }
+
+    /// How full recent ledgers were, from 0 to 1. Fees rise above the base
+    /// fee when ledgers are full.
+    pub fn ledger_capacity_usage(&self) -> f64 {
+        self.ledger_capacity_usage.parse().unwrap_or(0.0)
+    }
+
+    /// The fees transactions were actually charged, which is what it took
+    /// to get into a ledger.
+    pub fn fee_charged(&self) -> &FeeDistribution {
+        &self.fee_charged
+    }
+
+    /// The maximum fees transactions offered to pay.
+    pub fn max_fee(&self) -> &FeeDistribution {
+        &self.max_fee
+    }
+}
+
+/// The percentiles horizon reports fees at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Percentile {
+    /// The 10th percentile.
+    P10,
+    /// The 20th percentile.
+    P20,
+    /// The 30th percentile.
+    P30,
+    /// The 40th percentile.
+    P40,
+    /// The median.
+    P50,
+    /// The 60th percentile.
+    P60,
+    /// The 70th percentile.
+    P70,
+    /// The 80th percentile.
+    P80,
+    /// The 90th percentile.
+    P90,
+    /// The 95th percentile.
+    P95,
+    /// The 99th percentile.
+    P99,
+}
+
+/// A distribution of fees per operation, in stroops.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct FeeDistribution {
+    #[serde(serialize_with = "serialize::to_string",
+            deserialize_with = "deserialize::from_number_or_str")]
+    max: u64,
+    #[serde(serialize_with = "serialize::to_string",
+            deserialize_with = "deserialize::from_number_or_str")]
+    min: u64,
+    #[serde(serialize_with = "serialize::to_string",
+            deserialize_with = "deserialize::from_number_or_str")]
+    mode: u64,
+    #[serde(serialize_with = "serialize::to_string",
+            deserialize_with = "deserialize::from_number_or_str")]
+    p10: u64,
+    #[serde(serialize_with = "serialize::to_string",
+            deserialize_with = "deserialize::from_number_or_str")]
+    p20: u64,
+    #[serde(serialize_with = "serialize::to_string",
+            deserialize_with = "deserialize::from_number_or_str")]
+    p30: u64,
+    #[serde(serialize_with = "serialize::to_string",
+            deserialize_with = "deserialize::from_number_or_str")]
+    p40: u64,
+    #[serde(serialize_with = "serialize::to_string",
+            deserialize_with = "deserialize::from_number_or_str")]
+    p50: u64,
+    #[serde(serialize_with = "serialize::to_string",
+            deserialize_with = "deserialize::from_number_or_str")]
+    p60: u64,
+    #[serde(serialize_with = "serialize::to_string",
+            deserialize_with = "deserialize::from_number_or_str")]
+    p70: u64,
+    #[serde(serialize_with = "serialize::to_string",
+            deserialize_with = "deserialize::from_number_or_str")]
+    p80: u64,
+    #[serde(serialize_with = "serialize::to_string",
+            deserialize_with = "deserialize::from_number_or_str")]
+    p90: u64,
+    #[serde(serialize_with = "serialize::to_string",
+            deserialize_with = "deserialize::from_number_or_str")]
+    p95: u64,
+    #[serde(serialize_with = "serialize::to_string",
+            deserialize_with = "deserialize::from_number_or_str")]
+    p99: u64,
+}
+
+impl FeeDistribution {
+    /// Constructs a distribution where every fee was the same.
+    pub fn flat(fee: u64) -> Self {
+        FeeDistribution {
+            max: fee,
+            min: fee,
+            mode: fee,
+            p10: fee,
+            p20: fee,
+            p30: fee,
+            p40: fee,
+            p50: fee,
+            p60: fee,
+            p70: fee,
+            p80: fee,
+            p90: fee,
+            p95: fee,
+            p99: fee,
+        }
+    }
+
+    /// Replaces the fee at a percentile.
+    pub fn with_percentile(mut self, percentile: Percentile, fee: u64) -> Self {
+        *self.percentile_mut(percentile) = fee;
+        self
+    }
+
+    /// The highest fee.
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+
+    /// The lowest fee.
+    pub fn min(&self) -> u64 {
+        self.min
+    }
+
+    /// The most common fee.
+    pub fn mode(&self) -> u64 {
+        self.mode
+    }
+
+    /// The fee at a percentile.
+    pub fn percentile(&self, percentile: Percentile) -> u64 {
+        match percentile {
+            Percentile::P10 => self.p10,
+            Percentile::P20 => self.p20,
+            Percentile::P30 => self.p30,
+            Percentile::P40 => self.p40,
+            Percentile::P50 => self.p50,
+            Percentile::P60 => self.p60,
+            Percentile::P70 => self.p70,
+            Percentile::P80 => self.p80,
+            Percentile::P90 => self.p90,
+            Percentile::P95 => self.p95,
+            Percentile::P99 => self.p99,
+        }
+    }
+
+    fn percentile_mut(&mut self, percentile: Percentile) -> &mut u64 {
+        match percentile {
+            Percentile::P10 => &mut self.p10,
+            Percentile::P20 => &mut self.p20,
+            Percentile::P30 => &mut self.p30,
+            Percentile::P40 => &mut self.p40,
+            Percentile::P50 => &mut self.p50,
+            Percentile::P60 => &mut self.p60,
+            Percentile::P70 => &mut self.p70,
+            Percentile::P80 => &mut self.p80,
+            Percentile::P90 => &mut self.p90,
+            Percentile::P95 => &mut self.p95,
+            Percentile::P99 => &mut self.p99,
+        }
+    }
+}
+
+#[cfg(test)]
+mod fee_stats_tests {
+    use super::*;
+    use resources::serialize::assert_round_trips;
+    use serde_json;
+
+    fn fee_stats_json() -> &'static str {
+        include_str!("../../fixtures/fee_stats.json")
+    }
+
+    #[test]
+    fn it_parses_fee_stats() {
+        let stats: FeeStats = serde_json::from_str(fee_stats_json()).unwrap();
+        assert_eq!(stats.last_ledger(), 22606298);
+        assert_eq!(stats.last_ledger_base_fee(), 100);
+        assert_eq!(stats.ledger_capacity_usage(), 0.97);
+        assert_eq!(stats.fee_charged().mode(), 100);
+        assert_eq!(stats.fee_charged().percentile(Percentile::P50), 150);
+        assert_eq!(stats.fee_charged().percentile(Percentile::P90), 1200);
+        assert_eq!(stats.max_fee().max(), 1000000);
+        assert_eq!(stats.max_fee().min(), 100);
+    }
+
+    #[test]
+    fn it_serializes_like_horizon() {
+        assert_round_trips::<FeeStats>(fee_stats_json());
+    }
+
+    #[test]
+    fn it_builds_fee_stats() {
+        let stats = FeeStats::new(10, 100).with_fee_charged(
+            FeeDistribution::flat(100).with_percentile(Percentile::P90, 400),
+        );
+        assert_eq!(stats.fee_charged().percentile(Percentile::P90), 400);
+        assert_eq!(stats.fee_charged().percentile(Percentile::P50), 100);
+        assert_eq!(stats.max_fee().max(), 100);
+    }
+}
@@ -0,0 +1,137 @@
+//! Parses every fixture in `fixtures/` into the resource it samples, so
+//! fixtures refreshed from horizon with `refresh-fixtures` are checked
+//! against the resources as they are. New fixtures in the record
+//! directories are picked up on their own, and new top level fixtures have
+//! to be added to `singles` below.
+use super::offer::OfferSummary;
+use super::*;
+use history::HistoryArchiveState;
+use serde;
+use serde::de::DeserializeOwned;
+use serde_json;
+use std::fs;
+use std::path::{Path, PathBuf};
+use StellarError;
+
+type Parser = fn(&str) -> serde_json::Result<()>;
+
+fn parses<T: DeserializeOwned>(json: &str) -> serde_json::Result<()> {
+    serde_json::from_str::<T>(json).map(|_| ())
+}
+
+/// For fixtures of malformed records, which must keep failing to parse.
+fn rejects<T: DeserializeOwned>(json: &str) -> serde_json::Result<()> {
+    match serde_json::from_str::<T>(json) {
+        Ok(_) => Err(serde::de::Error::custom("malformed fixture parsed")),
+        Err(_) => Ok(()),
+    }
+}
+
+/// The fixtures at the top of `fixtures/` and what they sample.
+fn singles() -> Vec<(&'static str, Parser)> {
+    vec![
+        ("account.json", parses::<Account>),
+        ("asset.json", parses::<Asset>),
+        ("asset_stats.json", parses::<Asset>),
+        ("before_history_error.json", parses::<StellarError>),
+        ("fee_stats.json", parses::<FeeStats>),
+        ("invalid_asset.json", rejects::<Asset>),
+        ("ledger.json", parses::<Ledger>),
+        ("liquidity_pool.json", parses::<LiquidityPool>),
+        ("native_asset.json", parses::<AssetIdentifier>),
+        ("not_found_error.json", parses::<StellarError>),
+        ("offer.json", parses::<Offer>),
+        ("offer_summary.json", parses::<OfferSummary>),
+        ("orderbook.json", parses::<Orderbook>),
+        ("payment_path.json", parses::<PaymentPath>),
+        ("root.json", parses::<Root>),
+        ("submission.json", parses::<Submission>),
+        ("trade.json", parses::<Trade>),
+        ("trade_aggregation.json", parses::<TradeAggregation>),
+        ("transaction_failed_error.json", parses::<StellarError>),
+    ]
+}
+
+/// The directories of fixtures that each sample one kind of record.
+fn directories() -> Vec<(&'static str, Parser)> {
+    vec![
+        ("effects", parses::<Effect>),
+        ("history", parses::<HistoryArchiveState>),
+        ("operations", parses::<Operation>),
+        ("transactions", parses::<Transaction>),
+    ]
+}
+
+fn fixtures() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures")
+}
+
+fn json_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().map_or(false, |ext| ext == "json"))
+        .collect();
+    files.sort();
+    files
+}
+
+fn assert_parses(path: &Path, parse: Parser) {
+    let json = fs::read_to_string(path).unwrap();
+    if let Err(err) = parse(&json) {
+        panic!("{} no longer parses: {}", path.display(), err);
+    }
+}
+
+#[test]
+fn it_parses_every_single_fixture() {
+    for (name, parse) in singles() {
+        assert_parses(&fixtures().join(name), parse);
+    }
+}
+
+#[test]
+fn it_parses_every_record_fixture() {
+    for (dir, parse) in directories() {
+        let files = json_files(&fixtures().join(dir));
+        assert!(!files.is_empty(), "fixtures/{} is empty", dir);
+        for path in files {
+            assert_parses(&path, parse);
+        }
+    }
+}
+
+#[test]
+fn it_reads_every_operation_and_effect_fixture_by_reference() {
+    for path in json_files(&fixtures().join("operations")) {
+        let json = fs::read_to_string(&path).unwrap();
+        let operation: OperationRef = serde_json::from_str(&json).unwrap();
+        let owned: Operation = serde_json::from_str(&json).unwrap();
+        assert_eq!(operation.into_owned().unwrap(), owned, "{}", path.display());
+    }
+    for path in json_files(&fixtures().join("effects")) {
+        let json = fs::read_to_string(&path).unwrap();
+        let effect: EffectRef = serde_json::from_str(&json).unwrap();
+        let owned: Effect = serde_json::from_str(&json).unwrap();
+        assert_eq!(effect.into_owned().unwrap(), owned, "{}", path.display());
+    }
+}
+
+#[test]
+fn it_knows_what_every_fixture_samples() {
+    let singles: Vec<_> = singles().into_iter().map(|(name, _)| name).collect();
+    let directories: Vec<_> = directories().into_iter().map(|(dir, _)| dir).collect();
+    for entry in fs::read_dir(fixtures()).unwrap() {
+        let path = entry.unwrap().path();
+        let name = path.file_name().unwrap().to_str().unwrap();
+        if path.is_dir() {
+            assert!(
+                directories.contains(&name),
+                "fixtures/{} isn't checked",
+                name
+            );
+        } else {
+            assert!(singles.contains(&name), "fixtures/{} isn't checked", name);
+        }
+    }
+}
@@ -0,0 +1,261 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::str::FromStr;
+
+const TRANSACTION_BITS: u32 = 20;
+const OPERATION_BITS: u32 = 12;
+const TRANSACTION_MASK: u64 = (1 << TRANSACTION_BITS) - 1;
+const OPERATION_MASK: u64 = (1 << OPERATION_BITS) - 1;
+
+/// The id horizon gives an operation, which packs the ledger it closed in,
+/// the order of its transaction in that ledger and its own order in the
+/// transaction into one number. Transactions have the id of an operation
+/// ordered zero.
+///
+/// Ids order the same way the operations happened, so they can be used to
+/// sort and deduplicate records gathered from several pages or streams.
+///
+/// ## Example
+///
+/// ```
+/// use stellar_client::resources::OperationId;
+///
+/// let id: OperationId = "107449584845914113".parse().unwrap();
+/// assert_eq!(id.ledger(), 25_017_556);
+/// assert_eq!(id.transaction_order(), 16);
+/// assert_eq!(id.operation_order(), 1);
+/// assert_eq!(id.to_string(), "107449584845914113");
+/// assert!(id.transaction() < id);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OperationId {
+    ledger: u32,
+    transaction_order: u32,
+    operation_order: u32,
+}
+
+impl OperationId {
+    /// The id of an operation from its parts. Orders too large for their
+    /// place in the id are truncated.
+    pub fn new(ledger: u32, transaction_order: u32, operation_order: u32) -> OperationId {
+        OperationId {
+            ledger,
+            transaction_order: transaction_order & TRANSACTION_MASK as u32,
+            operation_order: operation_order & OPERATION_MASK as u32,
+        }
+    }
+
+    /// Unpacks an id as horizon returns it.
+    pub fn from_u64(id: u64) -> OperationId {
+        OperationId {
+            ledger: (id >> (TRANSACTION_BITS + OPERATION_BITS)) as u32,
+            transaction_order: ((id >> OPERATION_BITS) & TRANSACTION_MASK) as u32,
+            operation_order: (id & OPERATION_MASK) as u32,
+        }
+    }
+
+    /// Packs the id the way horizon returns it.
+    pub fn to_u64(&self) -> u64 {
+        u64::from(self.ledger) << (TRANSACTION_BITS + OPERATION_BITS)
+            | u64::from(self.transaction_order) << OPERATION_BITS
+            | u64::from(self.operation_order)
+    }
+
+    /// The sequence of the ledger the operation closed in.
+    pub fn ledger(&self) -> u32 {
+        self.ledger
+    }
+
+    /// The order of the operation's transaction in the ledger, starting
+    /// from one.
+    pub fn transaction_order(&self) -> u32 {
+        self.transaction_order
+    }
+
+    /// The order of the operation in its transaction, starting from one, or
+    /// zero for the id of a transaction.
+    pub fn operation_order(&self) -> u32 {
+        self.operation_order
+    }
+
+    /// The id of the transaction the operation is part of.
+    pub fn transaction(&self) -> OperationId {
+        OperationId::new(self.ledger, self.transaction_order, 0)
+    }
+}
+
+impl From<u64> for OperationId {
+    fn from(id: u64) -> OperationId {
+        OperationId::from_u64(id)
+    }
+}
+
+impl FromStr for OperationId {
+    type Err = ParseIdError;
+
+    fn from_str(s: &str) -> Result<OperationId, ParseIdError> {
+        s.parse()
+            .map(OperationId::from_u64)
+            .map_err(|_| ParseIdError::new())
+    }
+}
+
+impl fmt::Display for OperationId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_u64())
+    }
+}
+
+/// The id horizon gives a trade, which is the id of the operation that made
+/// the trade and the trade's place among the operation's trades, joined by
+/// a dash. Effects have ids of the same form. Ids order the way the trades
+/// happened.
+///
+/// ## Example
+///
+/// ```
+/// use stellar_client::resources::TradeId;
+///
+/// let first: TradeId = "107449584845914113-0".parse().unwrap();
+/// let second: TradeId = "107449584845914113-1".parse().unwrap();
+/// assert_eq!(first.operation().ledger(), 25_017_556);
+/// assert_eq!(second.index(), 1);
+/// assert!(first < second);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TradeId {
+    operation: OperationId,
+    index: u32,
+}
+
+impl TradeId {
+    /// The id of the trade at the index among the operation's trades.
+    pub fn new(operation: OperationId, index: u32) -> TradeId {
+        TradeId { operation, index }
+    }
+
+    /// The id of the operation that made the trade.
+    pub fn operation(&self) -> OperationId {
+        self.operation
+    }
+
+    /// The place of the trade among the operation's trades.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+}
+
+impl FromStr for TradeId {
+    type Err = ParseIdError;
+
+    fn from_str(s: &str) -> Result<TradeId, ParseIdError> {
+        let mut parts = s.splitn(2, '-');
+        let operation = parts.next().unwrap_or("").parse()?;
+        let index = parts
+            .next()
+            .ok_or_else(ParseIdError::new)?
+            .parse()
+            .map_err(|_| ParseIdError::new())?;
+        Ok(TradeId::new(operation, index))
+    }
+}
+
+impl fmt::Display for TradeId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.operation, self.index)
+    }
+}
+
+/// An error parsing an operation or trade id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseIdError {
+    _private: (),
+}
+
+impl ParseIdError {
+    fn new() -> ParseIdError {
+        ParseIdError { _private: () }
+    }
+}
+
+impl StdError for ParseIdError {
+    fn description(&self) -> &str {
+        "Failed to parse horizon id"
+    }
+}
+
+impl fmt::Display for ParseIdError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+#[cfg(test)]
+mod id_tests {
+    use super::*;
+
+    #[test]
+    fn it_unpacks_operation_ids() {
+        let id = OperationId::from_u64(107_449_584_845_914_113);
+        assert_eq!(id, OperationId::new(25_017_556, 16, 1));
+        assert_eq!(id.to_u64(), 107_449_584_845_914_113);
+        assert_eq!(id.transaction().to_u64(), 107_449_584_845_914_112);
+        assert_eq!(OperationId::from(12_884_905_985), OperationId::new(3, 1, 1));
+    }
+
+    #[test]
+    fn it_orders_operation_ids_as_numbers() {
+        let ids = [
+            OperationId::new(2, 1, 1),
+            OperationId::new(2, 1, 2),
+            OperationId::new(2, 2, 1),
+            OperationId::new(3, 1, 1),
+        ];
+        for pair in ids.windows(2) {
+            assert!(pair[0] < pair[1]);
+            assert!(pair[0].to_u64() < pair[1].to_u64());
+        }
+    }
+
+    #[test]
+    fn it_truncates_orders_that_do_not_fit() {
+        let id = OperationId::new(1, 1 << 20, 1 << 12);
+        assert_eq!(id.transaction_order(), 0);
+        assert_eq!(id.operation_order(), 0);
+    }
+
+    #[test]
+    fn it_parses_trade_and_effect_ids() {
+        let id: TradeId = "107449584845914113-2".parse().unwrap();
+        assert_eq!(id.operation(), OperationId::new(25_017_556, 16, 1));
+        assert_eq!(id.index(), 2);
+        assert_eq!(id.to_string(), "107449584845914113-2");
+
+        let effect: TradeId = "0000000012884905985-0000000001".parse().unwrap();
+        assert_eq!(effect, TradeId::new(OperationId::new(3, 1, 1), 1));
+    }
+
+    #[test]
+    fn it_orders_trades_by_operation_then_index() {
+        let mut ids: Vec<TradeId> = vec!["12884905986-0", "12884905985-10", "12884905985-2"]
+            .into_iter()
+            .map(|id| id.parse().unwrap())
+            .collect();
+        ids.sort();
+        ids.dedup();
+        let sorted: Vec<String> = ids.iter().map(TradeId::to_string).collect();
+        assert_eq!(
+            sorted,
+            vec!["12884905985-2", "12884905985-10", "12884905986-0"]
+        );
+    }
+
+    #[test]
+    fn it_rejects_malformed_ids() {
+        assert!("".parse::<OperationId>().is_err());
+        assert!("-1".parse::<OperationId>().is_err());
+        assert!("12884905985".parse::<TradeId>().is_err());
+        assert!("12884905985-".parse::<TradeId>().is_err());
+        assert!("abc-1".parse::<TradeId>().is_err());
+    }
+}
@@ -1,9 +1,10 @@
 use chrono::prelude::*;
-use resources::Amount;
+use resources::links::Links;
+use resources::{Amount, LedgerLinks};
 
 /// A ledger represents the state of the Stellar universe at a given point in time. It contains the list of all the accounts and balances, all the orders in the distributed exchange, and any other data that persists.
 /// The first ledger in the history of the network is called the genesis ledger.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct Ledger {
     id: String,
     paging_token: String,
@@ -18,9 +19,32 @@ pub struct Ledger {
     base_reserve_in_stroops: i64,
     max_tx_set_size: u32,
     protocol_version: u32,
+    #[serde(rename = "_links", default, skip_serializing_if = "Links::is_none")]
+    links: Links<LedgerLinks>,
 }
 
 impl Ledger {
+    /// Constructs an empty ledger closed at the given time, with the base
+    /// fee and reserve of the public network.
+    pub fn new(sequence: u32, closed_at: DateTime<Utc>) -> Ledger {
+        Ledger {
+            id: String::new(),
+            paging_token: (u64::from(sequence) << 32).to_string(),
+            hash: String::new(),
+            sequence,
+            transaction_count: 0,
+            operation_count: 0,
+            closed_at,
+            total_coins: Amount::new(0),
+            fee_pool: Amount::new(0),
+            base_fee_in_stroops: 100,
+            base_reserve_in_stroops: 5_000_000,
+            max_tx_set_size: 1000,
+            protocol_version: 10,
+            links: Links::none(),
+        }
+    }
+
     /// The unique identifier for this ledger
     pub fn id(&self) -> &String {
         &self.id
@@ -31,6 +55,12 @@ impl Ledger {
         &self.paging_token
     }
 
+    /// The links horizon sent with the ledger, to its transactions,
+    /// operations, payments and effects. `None` for a ledger built with `new`.
+    pub fn links(&self) -> Option<&LedgerLinks> {
+        self.links.get()
+    }
+
     /// A hex-encoded SHA-256 hash of the ledger’s XDR-encoded form.
     pub fn hash(&self) -> &String {
         &self.hash
@@ -100,6 +130,7 @@ impl Ledger {
 #[cfg(test)]
 mod ledger_tests {
     use super::*;
+    use resources::serialize::assert_round_trips;
     use serde_json;
 
     fn ledger_json() -> &'static str {
@@ -129,4 +160,9 @@ mod ledger_tests {
         assert_eq!(ledger.max_tx_set_size(), 50);
         assert_eq!(ledger.protocol_version(), 4);
     }
+
+    #[test]
+    fn it_serializes_like_horizon() {
+        assert_round_trips::<Ledger>(ledger_json());
+    }
 }
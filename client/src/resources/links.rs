@@ -0,0 +1,331 @@
+use http::Uri;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::hash::{Hash, Hasher};
+
+/// A link horizon sends with a resource in its `_links`, to related
+/// resources or the records either side of it.
+///
+/// <https://developers.stellar.org/api/horizon/structure/response-format>
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct Link {
+    href: String,
+    #[serde(default, skip_serializing_if = "is_false")]
+    templated: bool,
+}
+
+impl Link {
+    /// The href as horizon sent it, which may be an RFC 6570 template or
+    /// relative to the horizon server.
+    pub fn href(&self) -> &str {
+        &self.href
+    }
+
+    /// Whether the href is a template, such as the
+    /// `{?cursor,limit,order}` on links to collections.
+    pub fn is_templated(&self) -> bool {
+        self.templated
+    }
+
+    /// The uri the link points to. The optional query of a templated link
+    /// is left off, so a link to a collection gives its first page. `None`
+    /// for templates with variables that must be filled in, such as the
+    /// `{account_id}` of the root's account link, and hrefs that aren't
+    /// uris.
+    pub fn uri(&self) -> Option<Uri> {
+        let mut href = self.href.as_str();
+        if self.templated && href.ends_with('}') {
+            if let Some(query) = href.rfind("{?") {
+                href = &href[..query];
+            }
+        }
+        if href.contains('{') {
+            return None;
+        }
+        href.parse().ok()
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// The `_links` of a resource. Links point at the horizon server that sent
+/// them, so they're left out when resources are compared or hashed, and a
+/// resource fetched from any server equals one built by hand.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Links<T>(Option<T>);
+
+impl<T> Links<T> {
+    pub(crate) fn none() -> Links<T> {
+        Links(None)
+    }
+
+    pub(crate) fn is_none(&self) -> bool {
+        self.0.is_none()
+    }
+
+    pub(crate) fn get(&self) -> Option<&T> {
+        self.0.as_ref()
+    }
+}
+
+impl<T> PartialEq for Links<T> {
+    fn eq(&self, _: &Links<T>) -> bool {
+        true
+    }
+}
+
+impl<T> Eq for Links<T> {}
+
+impl<T> Hash for Links<T> {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+impl<T: Serialize> Serialize for Links<T> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(s)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Links<T> {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Links<T>, D::Error> {
+        Option::deserialize(d).map(Links)
+    }
+}
+
+macro_rules! links {
+    ($(
+        $(#[$doc:meta])*
+        pub struct $name:ident {
+            $($(#[$field_doc:meta])* $field:ident => $rel:tt,)*
+        }
+    )*) => {$(
+        $(#[$doc])*
+        #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+        pub struct $name {
+            $(
+                #[serde(rename = $rel, default, skip_serializing_if = "Option::is_none")]
+                $field: Option<Link>,
+            )*
+        }
+
+        impl $name {
+            $(
+                $(#[$field_doc])*
+                pub fn $field(&self) -> Option<&Link> {
+                    self.$field.as_ref()
+                }
+            )*
+        }
+    )*};
+}
+
+links! {
+    /// The links of an account.
+    pub struct AccountLinks {
+        /// The account itself.
+        self_link => "self",
+        /// The transactions the account took part in.
+        transactions => "transactions",
+        /// The operations the account took part in.
+        operations => "operations",
+        /// The payments the account sent or received.
+        payments => "payments",
+        /// The effects on the account.
+        effects => "effects",
+        /// The account's offers.
+        offers => "offers",
+        /// The account's trades.
+        trades => "trades",
+        /// A data entry of the account, templated by its key.
+        data => "data",
+    }
+
+    /// The links of an effect.
+    pub struct EffectLinks {
+        /// The operation that caused the effect.
+        operation => "operation",
+        /// The effects after this one.
+        precedes => "precedes",
+        /// The effects before this one.
+        succeeds => "succeeds",
+    }
+
+    /// The links of a ledger.
+    pub struct LedgerLinks {
+        /// The ledger itself.
+        self_link => "self",
+        /// The transactions applied in the ledger.
+        transactions => "transactions",
+        /// The operations applied in the ledger.
+        operations => "operations",
+        /// The payments made in the ledger.
+        payments => "payments",
+        /// The effects of the ledger's operations.
+        effects => "effects",
+    }
+
+    /// The links of a liquidity pool.
+    pub struct LiquidityPoolLinks {
+        /// The pool itself.
+        self_link => "self",
+        /// The transactions that touched the pool.
+        transactions => "transactions",
+        /// The operations that touched the pool.
+        operations => "operations",
+    }
+
+    /// The links of an offer.
+    pub struct OfferLinks {
+        /// The offer itself.
+        self_link => "self",
+        /// The account that made the offer.
+        offer_maker => "offer_maker",
+    }
+
+    /// The links of an operation.
+    pub struct OperationLinks {
+        /// The operation itself.
+        self_link => "self",
+        /// The transaction the operation is part of.
+        transaction => "transaction",
+        /// The effects of the operation.
+        effects => "effects",
+        /// The operations after this one.
+        precedes => "precedes",
+        /// The operations before this one.
+        succeeds => "succeeds",
+    }
+
+    /// The links of a trade.
+    pub struct TradeLinks {
+        /// The base account or liquidity pool of the trade.
+        base => "base",
+        /// The counter account or liquidity pool of the trade.
+        counter => "counter",
+        /// The operation that made the trade.
+        operation => "operation",
+    }
+
+    /// The links of a transaction.
+    pub struct TransactionLinks {
+        /// The transaction itself.
+        self_link => "self",
+        /// The source account of the transaction.
+        account => "account",
+        /// The ledger the transaction was applied in.
+        ledger => "ledger",
+        /// The operations of the transaction.
+        operations => "operations",
+        /// The effects of the transaction's operations.
+        effects => "effects",
+        /// The transactions after this one.
+        precedes => "precedes",
+        /// The transactions before this one.
+        succeeds => "succeeds",
+    }
+}
+
+#[cfg(test)]
+mod links_tests {
+    use super::*;
+    use resources::{Effect, Offer, Operation, Trade, Transaction};
+    use serde_json;
+
+    fn link(json: &str) -> Link {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn it_gives_the_uri_of_a_link() {
+        let link = link(r#"{ "href": "https://horizon.stellar.org/ledgers/16751283" }"#);
+        assert!(!link.is_templated());
+        assert_eq!(
+            link.uri().unwrap(),
+            "https://horizon.stellar.org/ledgers/16751283"
+        );
+    }
+
+    #[test]
+    fn it_leaves_off_the_query_template() {
+        let link = link(
+            r#"{
+                "href": "https://horizon.stellar.org/accounts/GA/operations{?cursor,limit,order}",
+                "templated": true
+            }"#,
+        );
+        assert_eq!(
+            link.uri().unwrap(),
+            "https://horizon.stellar.org/accounts/GA/operations"
+        );
+    }
+
+    #[test]
+    fn it_has_no_uri_for_templates_with_variables() {
+        let link = link(
+            r#"{
+                "href": "https://horizon.stellar.org/accounts/{account_id}",
+                "templated": true
+            }"#,
+        );
+        assert_eq!(link.uri(), None);
+    }
+
+    #[test]
+    fn it_serializes_like_horizon() {
+        let json = r#"{"href":"/operations/1/effects{?cursor,limit,order}","templated":true}"#;
+        assert_eq!(serde_json::to_string(&link(json)).unwrap(), json);
+        let json = r#"{"href":"/operations/1"}"#;
+        assert_eq!(serde_json::to_string(&link(json)).unwrap(), json);
+    }
+
+    #[test]
+    fn it_parses_the_links_of_resources() {
+        let transaction: Transaction = serde_json::from_str(include_str!(
+            "../../fixtures/transactions/transaction_memo_text.json"
+        ))
+        .unwrap();
+        let links = transaction.links().unwrap();
+        assert_eq!(
+            links.ledger().unwrap().uri().unwrap(),
+            "https://horizon.stellar.org/ledgers/16751283"
+        );
+        assert!(links.operations().unwrap().is_templated());
+        assert_eq!(
+            links.precedes().unwrap().href(),
+            "https://horizon.stellar.org/transactions?order=asc&cursor=71946212651044864"
+        );
+
+        let offer: Offer = serde_json::from_str(include_str!("../../fixtures/offer.json")).unwrap();
+        assert_eq!(
+            offer.links().unwrap().offer_maker().unwrap().href(),
+            "https://horizon-testnet.stellar.org/accounts/\
+             GCJ34JYMXNI7N55YREWAACMMZECOMTPIYDTFCQBWPUP7BLJQDDTVGUW4"
+        );
+
+        let trade: Trade = serde_json::from_str(include_str!("../../fixtures/trade.json")).unwrap();
+        assert!(trade.links().unwrap().operation().is_some());
+
+        let operation: Operation =
+            serde_json::from_str(include_str!("../../fixtures/operations/account_merge.json"))
+                .unwrap();
+        let links = operation.links().unwrap();
+        assert_eq!(
+            links.effects().unwrap().uri().unwrap(),
+            "/operations/799357838299137/effects"
+        );
+        assert!(links.transaction().is_some());
+
+        let effect: Effect =
+            serde_json::from_str(include_str!("../../fixtures/effects/trade.json")).unwrap();
+        assert!(effect.links().unwrap().operation().is_some());
+    }
+
+    #[test]
+    fn it_has_no_links_for_built_resources() {
+        let transaction = Transaction::new("abc", "GA", 1);
+        assert_eq!(transaction.links(), None);
+        let json = serde_json::to_value(&transaction).unwrap();
+        assert!(json.get("_links").is_none());
+    }
+}
@@ -0,0 +1,228 @@
+use resources::links::Links;
+use resources::operation::Reserve;
+use resources::{
+    deserialize, serialize, Amount, AssetIdentifier, LiquidityPoolLinks, PriceRatio, Rounding,
+};
+
+/// Basis points, which fees and slippage are given in.
+const BASIS_POINTS: u128 = 10_000;
+
+/// A pool of two assets that anyone holding its shares can trade against,
+/// priced by the ratio of its reserves.
+///
+/// <https://developers.stellar.org/api/horizon/resources/liquiditypools>
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct LiquidityPool {
+    id: String,
+    paging_token: String,
+    fee_bp: u32,
+    #[serde(rename = "type")]
+    pool_type: String,
+    #[serde(
+        serialize_with = "serialize::to_string",
+        deserialize_with = "deserialize::from_number_or_str"
+    )]
+    total_trustlines: u64,
+    total_shares: Amount,
+    reserves: Vec<Reserve>,
+    last_modified_ledger: u32,
+    #[serde(rename = "_links", default, skip_serializing_if = "Links::is_none")]
+    links: Links<LiquidityPoolLinks>,
+}
+
+impl LiquidityPool {
+    /// Constructs a constant product pool of the two reserves with the
+    /// usual 30 basis point fee.
+    pub fn new(id: &str, a: Reserve, b: Reserve, total_shares: Amount) -> LiquidityPool {
+        LiquidityPool {
+            id: id.to_string(),
+            paging_token: id.to_string(),
+            fee_bp: 30,
+            pool_type: "constant_product".to_string(),
+            total_trustlines: 0,
+            total_shares,
+            reserves: vec![a, b],
+            last_modified_ledger: 0,
+            links: Links::none(),
+        }
+    }
+
+    /// The id of the pool, the hex encoded hash of its parameters.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The paging token of the pool.
+    pub fn paging_token(&self) -> &str {
+        &self.paging_token
+    }
+
+    /// The links horizon sent with the pool, to its transactions and
+    /// operations. `None` for a pool built with `new`.
+    pub fn links(&self) -> Option<&LiquidityPoolLinks> {
+        self.links.get()
+    }
+
+    /// The fee charged on trades against the pool, in basis points.
+    pub fn fee_bp(&self) -> u32 {
+        self.fee_bp
+    }
+
+    /// The kind of pool, which is `constant_product` for every pool so far.
+    pub fn pool_type(&self) -> &str {
+        &self.pool_type
+    }
+
+    /// How many trust lines hold shares of the pool.
+    pub fn total_trustlines(&self) -> u64 {
+        self.total_trustlines
+    }
+
+    /// The number of shares issued.
+    pub fn total_shares(&self) -> Amount {
+        self.total_shares
+    }
+
+    /// The assets in the pool and how much of each it holds, in the order
+    /// the ledger keeps them.
+    pub fn reserves(&self) -> &[Reserve] {
+        &self.reserves
+    }
+
+    /// The reserve of the asset, if the pool holds it.
+    pub fn reserve(&self, asset: &AssetIdentifier) -> Option<&Reserve> {
+        self.reserves
+            .iter()
+            .find(|reserve| reserve.asset() == asset)
+    }
+
+    /// The last ledger the pool changed in.
+    pub fn last_modified_ledger(&self) -> u32 {
+        self.last_modified_ledger
+    }
+
+    /// How much of each reserve the shares are worth at the pool's current
+    /// reserves, rounded down as withdrawals are.
+    pub fn shares_value(&self, shares: Amount) -> Vec<Reserve> {
+        self.scaled_shares_value(shares, BASIS_POINTS)
+    }
+
+    /// The least of each reserve to accept for withdrawing the shares,
+    /// allowing the reserves to move against the withdrawal by the slippage
+    /// in basis points before it's submitted.
+    pub fn withdrawal_minimums(&self, shares: Amount, slippage_bp: u32) -> Vec<Reserve> {
+        let keep = BASIS_POINTS.saturating_sub(u128::from(slippage_bp));
+        self.scaled_shares_value(shares, keep)
+    }
+
+    fn scaled_shares_value(&self, shares: Amount, basis_points: u128) -> Vec<Reserve> {
+        let total = self.total_shares.stroops() as u128;
+        self.reserves
+            .iter()
+            .map(|reserve| {
+                let amount = if total == 0 {
+                    0
+                } else {
+                    reserve.amount().stroops() as u128 * shares.stroops() as u128 * basis_points
+                        / (total * BASIS_POINTS)
+                };
+                Reserve::new(reserve.asset().clone(), Amount::new(amount as i64))
+            })
+            .collect()
+    }
+
+    /// The lowest and highest price of the first reserve in terms of the
+    /// second to accept for a deposit, allowing the price to move by the
+    /// slippage in basis points before it's submitted. `None` for an empty
+    /// pool, which has no price yet, or a price too extreme for the ledger
+    /// to bound.
+    pub fn deposit_price_bounds(&self, slippage_bp: u32) -> Option<(PriceRatio, PriceRatio)> {
+        let (a, b) = match self.reserves.as_slice() {
+            [a, b] => (a.amount().stroops() as u128, b.amount().stroops() as u128),
+            _ => return None,
+        };
+        if a == 0 || b == 0 {
+            return None;
+        }
+        let slippage = u128::from(slippage_bp).min(BASIS_POINTS - 1);
+        let min = PriceRatio::fit(
+            a * (BASIS_POINTS - slippage),
+            b * BASIS_POINTS,
+            Rounding::Down,
+        )?;
+        let max = PriceRatio::fit(
+            a * (BASIS_POINTS + slippage),
+            b * BASIS_POINTS,
+            Rounding::Up,
+        )?;
+        Some((min, max))
+    }
+}
+
+#[cfg(test)]
+mod liquidity_pool_tests {
+    use super::*;
+    use resources::serialize::assert_round_trips;
+    use serde_json;
+
+    fn pool_json() -> &'static str {
+        include_str!("../../fixtures/liquidity_pool.json")
+    }
+
+    fn pool() -> LiquidityPool {
+        LiquidityPool::new(
+            "abcdef",
+            Reserve::new(AssetIdentifier::native(), Amount::new(1_000_000_000)),
+            Reserve::new(
+                AssetIdentifier::alphanum4(
+                    "USD",
+                    "GBBD47IF6LWK7P7MDEVSCWR7DPUWV3NY3DTQEVFL4NAT4AQH3ZLLFLA5",
+                ),
+                Amount::new(4_000_000_000),
+            ),
+            Amount::new(2_000_000_000),
+        )
+    }
+
+    #[test]
+    fn it_parses_a_liquidity_pool() {
+        let pool: LiquidityPool = serde_json::from_str(pool_json()).unwrap();
+        assert_eq!(pool.fee_bp(), 30);
+        assert_eq!(pool.total_trustlines(), 300);
+        assert_eq!(pool.total_shares(), Amount::new(50_000_000_000));
+        assert_eq!(pool.reserves().len(), 2);
+        let native = pool.reserve(&AssetIdentifier::native()).unwrap();
+        assert_eq!(native.amount(), Amount::new(10_000_000_005));
+        assert_eq!(pool.reserves()[1].asset().code(), "USDC");
+    }
+
+    #[test]
+    fn it_serializes_like_horizon() {
+        assert_round_trips::<LiquidityPool>(pool_json());
+    }
+
+    #[test]
+    fn it_values_shares() {
+        let value = pool().shares_value(Amount::new(1_000_000_000));
+        assert_eq!(value[0].amount(), Amount::new(500_000_000));
+        assert_eq!(value[1].amount(), Amount::new(2_000_000_000));
+        let minimums = pool().withdrawal_minimums(Amount::new(1_000_000_000), 100);
+        assert_eq!(minimums[0].amount(), Amount::new(495_000_000));
+        assert_eq!(minimums[1].amount(), Amount::new(1_980_000_000));
+    }
+
+    #[test]
+    fn it_bounds_deposit_prices() {
+        let (min, max) = pool().deposit_price_bounds(100).unwrap();
+        assert_eq!(min, PriceRatio::new(99, 400));
+        assert_eq!(max, PriceRatio::new(101, 400));
+
+        let empty = LiquidityPool::new(
+            "abcdef",
+            Reserve::new(AssetIdentifier::native(), Amount::new(0)),
+            Reserve::new(AssetIdentifier::native(), Amount::new(0)),
+            Amount::new(0),
+        );
+        assert_eq!(empty.deposit_price_bounds(100), None);
+    }
+}
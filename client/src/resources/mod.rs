@@ -2,20 +2,30 @@
 //! implements their deserialization from JSON into rust types.
 
 mod account;
+mod anchor_asset;
 mod amount;
 mod asset;
+mod balance;
 mod base64string;
 mod datum;
-mod deserialize;
+pub(crate) mod deserialize;
 
 /// An effect represents specific changes that occur in the ledger resulting from operations.
 pub mod effect;
+mod fee_stats;
+mod id;
 mod ledger;
+mod liquidity_pool;
+mod links;
 mod offer;
 /// An operation is an individual command that mutates the ledger.
 pub mod operation;
 mod orderbook;
 mod payment_path;
+mod resource;
+mod root;
+mod serialize;
+mod submission;
 mod trade;
 mod transaction;
 
@@ -24,16 +34,32 @@ mod transaction;
 /// A collection of data types and resources used within the stellar api.
 /// All the derives for XDR and JSON are implemented for the resources so that
 /// they can be used with a client. Either for reading or for writing.
-pub use self::account::Account;
-pub use self::amount::Amount;
-pub use self::asset::{Asset, AssetIdentifier, Flags};
+pub use self::account::{Account, AccountSigner, AccountThresholds};
+pub use self::amount::{Amount, ParseAmountError, SignedAmount};
+pub use self::anchor_asset::{AnchorAsset, ParseAnchorAssetError};
+pub use self::balance::Balance;
+pub use self::asset::{AccountCounts, Asset, AssetIdentifier, BalanceTotals, Flags};
+pub use self::base64string::Base64String;
 pub use self::datum::Datum;
-pub use self::effect::Effect;
+pub use self::effect::{Effect, EffectRef};
+pub use self::fee_stats::{FeeDistribution, FeeStats, Percentile};
+pub use self::id::{OperationId, ParseIdError, TradeId};
 pub use self::ledger::Ledger;
-pub use self::offer::Offer;
-pub use self::operation::{Operation, OperationKind};
+pub use self::liquidity_pool::LiquidityPool;
+pub use self::links::{
+    AccountLinks, EffectLinks, LedgerLinks, Link, LiquidityPoolLinks, OfferLinks, OperationLinks,
+    TradeLinks, TransactionLinks,
+};
+pub use self::offer::{Offer, ParsePriceError, PriceRatio, Rounding};
+pub use self::operation::{Operation, OperationCommon, OperationFilter, OperationKind, OperationRef};
 pub use self::orderbook::Orderbook;
 pub use self::payment_path::PaymentPath;
-pub use self::trade::{Seller as TradeSeller, Trade, TradeAggregation};
-pub use self::transaction::Memo;
+pub use self::resource::Resource;
+pub use self::root::Root;
+pub use self::submission::Submission;
+pub use self::trade::{Seller as TradeSeller, Trade, TradeAggregation, TradeRef};
+pub use self::transaction::{LedgerBounds, Memo, Preconditions, TimeBounds};
 pub use self::transaction::Transaction;
+
+#[cfg(test)]
+mod fixture_tests;
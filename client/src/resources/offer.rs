@@ -1,8 +1,23 @@
-use resources::{Amount, AssetIdentifier};
+use resources::links::Links;
+use resources::{deserialize, Amount, AssetIdentifier, OfferLinks};
+use std::error::Error as StdError;
 use std::fmt;
+use std::str::FromStr;
+
+/// Which way to round a conversion that doesn't come out to a whole stroop,
+/// or a price that doesn't fit the ledger exactly. Round in the direction
+/// that can't lose money: down for the most that may be spent, up for the
+/// least that must be received.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rounding {
+    /// Towards zero.
+    Down,
+    /// Away from zero.
+    Up,
+}
 
 /// The ratio between the asking and selling price
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Copy, Clone)]
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Copy, Clone, Hash)]
 pub struct PriceRatio {
     #[serde(rename = "n")]
     numerator: u64,
@@ -34,10 +49,420 @@ impl PriceRatio {
     pub fn denominator(&self) -> u64 {
         self.denominator
     }
+
+    /// The price of `buying` for `selling`, as close as the ledger's 32-bit
+    /// ratios can hold it and rounded the given way when they can't. `None`
+    /// if either amount is zero, or if the price is too far from one for the
+    /// ledger to hold a price on the right side of it.
+    ///
+    /// ```
+    /// use stellar_client::resources::{Amount, PriceRatio, Rounding};
+    ///
+    /// let price = PriceRatio::from_amounts(Amount::new(30), Amount::new(10), Rounding::Down);
+    /// assert_eq!(price, Some(PriceRatio::new(1, 3)));
+    /// ```
+    pub fn from_amounts(selling: Amount, buying: Amount, rounding: Rounding) -> Option<PriceRatio> {
+        PriceRatio::fit(
+            buying.stroops() as u128,
+            selling.stroops() as u128,
+            rounding,
+        )
+    }
+
+    /// The price the other way round, of the selling asset in terms of the
+    /// buying asset.
+    pub fn inverse(&self) -> PriceRatio {
+        PriceRatio::new(self.denominator, self.numerator)
+    }
+
+    /// What an amount of the selling asset is worth in the buying asset at
+    /// this price, rounded the given way to a whole stroop. `None` if the
+    /// price is zero or the result doesn't fit in an amount.
+    pub fn convert(&self, selling: Amount, rounding: Rounding) -> Option<Amount> {
+        multiply(selling, self.numerator, self.denominator, rounding)
+    }
+
+    /// The most of the selling asset that can be sold at this price for at
+    /// most `buying` of the buying asset, rounding down so the budget is
+    /// never overspent.
+    ///
+    /// ```
+    /// use stellar_client::resources::{Amount, PriceRatio};
+    ///
+    /// // At 3 of the buying asset for each 2 sold, a budget of 10 covers 6.67
+    /// let price = PriceRatio::new(3, 2);
+    /// assert_eq!(price.max_sell(Amount::new(10)), Some(Amount::new(6)));
+    /// ```
+    pub fn max_sell(&self, buying: Amount) -> Option<Amount> {
+        multiply(buying, self.denominator, self.numerator, Rounding::Down)
+    }
+
+    /// The least of the buying asset that selling `selling` must bring in
+    /// at this price, rounding up so a fill is never accepted below it.
+    ///
+    /// ```
+    /// use stellar_client::resources::{Amount, PriceRatio};
+    ///
+    /// let price = PriceRatio::new(3, 2);
+    /// assert_eq!(price.min_buy(Amount::new(5)), Some(Amount::new(8)));
+    /// ```
+    pub fn min_buy(&self, selling: Amount) -> Option<Amount> {
+        multiply(selling, self.numerator, self.denominator, Rounding::Up)
+    }
+
+    /// Fits a ratio into the 32-bit integers the ledger stores prices as,
+    /// rounding it the given way when it has to be approximated. `None` if
+    /// no price the ledger can hold is on the right side of it, such as a
+    /// ratio above `i32::MAX` rounded up.
+    pub(crate) fn fit(
+        numerator: u128,
+        denominator: u128,
+        rounding: Rounding,
+    ) -> Option<PriceRatio> {
+        if numerator == 0 || denominator == 0 {
+            return None;
+        }
+        let max = i32::MAX as u128;
+        let divisor = gcd(numerator, denominator);
+        let (numerator, denominator) = (numerator / divisor, denominator / divisor);
+        // Dividing both by the same scale keeps the price, and rounding one
+        // down and the other up moves it the right way.
+        let scale = numerator.max(denominator).div_ceil(max);
+        let (numerator, denominator) = if scale <= 1 {
+            (numerator, denominator)
+        } else if rounding == Rounding::Up {
+            (numerator.div_ceil(scale), denominator / scale)
+        } else {
+            (numerator / scale, denominator.div_ceil(scale))
+        };
+        if numerator == 0 || denominator == 0 {
+            return None;
+        }
+        let divisor = gcd(numerator, denominator);
+        Some(PriceRatio::new(
+            (numerator / divisor) as u64,
+            (denominator / divisor) as u64,
+        ))
+    }
+}
+
+/// `amount * numerator / denominator`, rounded to a whole stroop.
+fn multiply(
+    amount: Amount,
+    numerator: u64,
+    denominator: u64,
+    rounding: Rounding,
+) -> Option<Amount> {
+    if denominator == 0 {
+        return None;
+    }
+    let product = amount.stroops() as u128 * u128::from(numerator);
+    let denominator = u128::from(denominator);
+    let result = match rounding {
+        Rounding::Down => product / denominator,
+        Rounding::Up => product.div_ceil(denominator),
+    };
+    if result > i64::MAX as u128 {
+        return None;
+    }
+    Some(Amount::new(result as i64))
+}
+
+/// The error returned when parsing a string that isn't a price, which is
+/// either a ratio such as `3/2` or a decimal such as `1.5`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePriceError {
+    kind: ParsePriceErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ParsePriceErrorKind {
+    Invalid,
+    OutOfRange,
+}
+
+impl StdError for ParsePriceError {
+    fn description(&self) -> &str {
+        match self.kind {
+            ParsePriceErrorKind::Invalid => "Prices are a ratio like 3/2 or a decimal like 1.5.",
+            ParsePriceErrorKind::OutOfRange => {
+                "Prices must be above zero and fit in a ratio of 32-bit integers."
+            }
+        }
+    }
+}
+
+impl fmt::Display for ParsePriceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+/// Parses a price from a ratio such as `3/2` or a decimal such as `1.5`.
+/// Decimals are turned into the smallest equivalent ratio, which must fit in
+/// the 32-bit integers the ledger stores prices as.
+impl FromStr for PriceRatio {
+    type Err = ParsePriceError;
+
+    fn from_str(s: &str) -> Result<PriceRatio, ParsePriceError> {
+        let invalid = ParsePriceError {
+            kind: ParsePriceErrorKind::Invalid,
+        };
+        let out_of_range = ParsePriceError {
+            kind: ParsePriceErrorKind::OutOfRange,
+        };
+        let digits = |s: &str| -> Result<u64, ParsePriceError> {
+            if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid.clone());
+            }
+            s.parse().map_err(|_| out_of_range.clone())
+        };
+        let (numerator, denominator) = match s.find('/') {
+            Some(slash) => (digits(&s[..slash])?, digits(&s[slash + 1..])?),
+            None => {
+                let (whole, decimals) = match s.find('.') {
+                    Some(point) => (&s[..point], &s[point + 1..]),
+                    None => (s, ""),
+                };
+                if decimals.len() > 18 {
+                    return Err(out_of_range);
+                }
+                let whole = if whole.is_empty() && !decimals.is_empty() {
+                    "0"
+                } else {
+                    whole
+                };
+                let numerator = format!("{}{}", whole, decimals);
+                (digits(&numerator)?, 10u64.pow(decimals.len() as u32))
+            }
+        };
+        if numerator == 0 || denominator == 0 {
+            return Err(out_of_range);
+        }
+        let divisor = gcd(numerator.into(), denominator.into()) as u64;
+        let (numerator, denominator) = (numerator / divisor, denominator / divisor);
+        let max = i32::MAX as u64;
+        if numerator > max || denominator > max {
+            return Err(out_of_range);
+        }
+        Ok(PriceRatio::new(numerator, denominator))
+    }
+}
+
+fn gcd(a: u128, b: u128) -> u128 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[cfg(test)]
+mod price_ratio_tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_ratios_and_decimals() {
+        assert_eq!("3/2".parse::<PriceRatio>(), Ok(PriceRatio::new(3, 2)));
+        assert_eq!("6/4".parse::<PriceRatio>(), Ok(PriceRatio::new(3, 2)));
+        assert_eq!("1.5".parse::<PriceRatio>(), Ok(PriceRatio::new(3, 2)));
+        assert_eq!("2".parse::<PriceRatio>(), Ok(PriceRatio::new(2, 1)));
+        assert_eq!(".25".parse::<PriceRatio>(), Ok(PriceRatio::new(1, 4)));
+        assert_eq!(
+            "0.0000001".parse::<PriceRatio>(),
+            Ok(PriceRatio::new(1, 10_000_000))
+        );
+    }
+
+    /// Every price with a numerator and denominator up to 20, against every
+    /// amount up to 200 stroops.
+    fn small_prices_and_amounts() -> Vec<(u64, u64, i64)> {
+        let mut cases = Vec::new();
+        for n in 1..21 {
+            for d in 1..21 {
+                for amount in 0..201 {
+                    cases.push((n, d, amount));
+                }
+            }
+        }
+        cases
+    }
+
+    #[test]
+    fn it_rounds_max_sell_down() {
+        for (n, d, budget) in small_prices_and_amounts() {
+            let sold = PriceRatio::new(n, d)
+                .max_sell(Amount::new(budget))
+                .unwrap()
+                .stroops();
+            // Selling `sold` costs no more than the budget, and one stroop
+            // more would overspend it.
+            assert!(
+                sold * n as i64 <= budget * d as i64,
+                "{}/{} {}",
+                n,
+                d,
+                budget
+            );
+            assert!(
+                (sold + 1) * n as i64 > budget * d as i64,
+                "{}/{} {}",
+                n,
+                d,
+                budget
+            );
+        }
+    }
+
+    #[test]
+    fn it_rounds_min_buy_up() {
+        for (n, d, selling) in small_prices_and_amounts() {
+            let bought = PriceRatio::new(n, d)
+                .min_buy(Amount::new(selling))
+                .unwrap()
+                .stroops();
+            // `bought` is at least the price, and one stroop less is below it.
+            assert!(
+                bought * d as i64 >= selling * n as i64,
+                "{}/{} {}",
+                n,
+                d,
+                selling
+            );
+            assert!(
+                (bought - 1) * (d as i64) < selling * n as i64,
+                "{}/{} {}",
+                n,
+                d,
+                selling
+            );
+        }
+    }
+
+    #[test]
+    fn it_converts_exactly_when_it_can() {
+        let price = PriceRatio::new(3, 2);
+        assert_eq!(
+            price.convert(Amount::new(4), Rounding::Down),
+            Some(Amount::new(6))
+        );
+        assert_eq!(
+            price.convert(Amount::new(4), Rounding::Up),
+            Some(Amount::new(6))
+        );
+        assert_eq!(
+            price.convert(Amount::new(5), Rounding::Down),
+            Some(Amount::new(7))
+        );
+        assert_eq!(
+            price.convert(Amount::new(5), Rounding::Up),
+            Some(Amount::new(8))
+        );
+        assert_eq!(
+            price.inverse().convert(Amount::new(6), Rounding::Down),
+            Some(Amount::new(4))
+        );
+        assert_eq!(
+            PriceRatio::new(1, 0).convert(Amount::new(1), Rounding::Down),
+            None
+        );
+        assert_eq!(
+            PriceRatio::new(2, 1).convert(Amount::new(i64::MAX), Rounding::Down),
+            None
+        );
+    }
+
+    #[test]
+    fn it_rounds_prices_from_amounts_the_given_way() {
+        let amounts = [
+            1,
+            2,
+            3,
+            7,
+            10_000_000,
+            12_345_678_901,
+            i64::MAX / 3,
+            i64::MAX,
+        ];
+        for &selling in &amounts {
+            for &buying in &amounts {
+                let exact = (buying as u128, selling as u128);
+                let max = i32::MAX as u128;
+                let price = |rounding| {
+                    PriceRatio::from_amounts(Amount::new(selling), Amount::new(buying), rounding)
+                };
+                let fits = |price: &PriceRatio| {
+                    let (n, d) = (price.numerator(), price.denominator());
+                    n > 0 && d > 0 && u128::from(n) <= max && u128::from(d) <= max
+                };
+                // Rounding down only fails below the smallest price, 1/max.
+                match price(Rounding::Down) {
+                    Some(down) => {
+                        assert!(fits(&down));
+                        assert!(
+                            u128::from(down.numerator()) * exact.1
+                                <= exact.0 * u128::from(down.denominator()),
+                            "{} {}",
+                            selling,
+                            buying
+                        );
+                    }
+                    None => assert!(exact.0 * max < exact.1, "{} {}", selling, buying),
+                }
+                // Rounding up only fails above the largest price, max/1.
+                match price(Rounding::Up) {
+                    Some(up) => {
+                        assert!(fits(&up));
+                        assert!(
+                            u128::from(up.numerator()) * exact.1
+                                >= exact.0 * u128::from(up.denominator()),
+                            "{} {}",
+                            selling,
+                            buying
+                        );
+                    }
+                    None => assert!(exact.0 > exact.1 * max, "{} {}", selling, buying),
+                }
+            }
+        }
+        assert_eq!(
+            PriceRatio::from_amounts(Amount::new(40), Amount::new(60), Rounding::Up),
+            Some(PriceRatio::new(3, 2))
+        );
+        assert_eq!(
+            PriceRatio::from_amounts(Amount::new(0), Amount::new(60), Rounding::Up),
+            None
+        );
+    }
+
+    #[test]
+    fn it_fits_large_ratios_into_prices() {
+        let price = PriceRatio::fit(u128::from(u64::MAX), 3, Rounding::Down).unwrap();
+        assert!(price.numerator() <= i32::MAX as u64);
+        assert_eq!(price.denominator(), 1);
+        // Nothing the ledger holds is at or above this price, or at or below
+        // its inverse.
+        assert_eq!(PriceRatio::fit(u128::from(u64::MAX), 3, Rounding::Up), None);
+        assert_eq!(
+            PriceRatio::fit(3, u128::from(u64::MAX), Rounding::Down),
+            None
+        );
+    }
+
+    #[test]
+    fn it_rejects_prices_the_ledger_cant_hold() {
+        assert!("0".parse::<PriceRatio>().is_err());
+        assert!("1/0".parse::<PriceRatio>().is_err());
+        assert!("-1".parse::<PriceRatio>().is_err());
+        assert!("1.5/2".parse::<PriceRatio>().is_err());
+        assert!("abc".parse::<PriceRatio>().is_err());
+        assert!("3000000000".parse::<PriceRatio>().is_err());
+        assert!("0.0000000001".parse::<PriceRatio>().is_err());
+    }
 }
 
 /// Summary of an offer to be shown in an orderbook
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct OfferSummary {
     amount: Amount,
     #[serde(rename = "price_r")]
@@ -65,6 +490,7 @@ impl OfferSummary {
 #[cfg(test)]
 mod offer_summary_tests {
     use super::*;
+    use resources::serialize::assert_round_trips;
     use serde_json;
 
     fn offer_summary_json() -> &'static str {
@@ -78,12 +504,18 @@ mod offer_summary_tests {
         assert_eq!(offer_summary.price(), Amount::new(77200005));
         assert_eq!(offer_summary.amount(), Amount::new(120000000));
     }
+
+    #[test]
+    fn it_serializes_like_horizon() {
+        assert_round_trips::<OfferSummary>(offer_summary_json());
+    }
 }
 
 /// An offer being made for particular assets at a particular exchange rate.
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Offer {
-    id: i64,
+    #[serde(deserialize_with = "deserialize::from_number_or_str")]
+    id: u64,
     paging_token: String,
     seller: String,
     selling: AssetIdentifier,
@@ -92,11 +524,58 @@ pub struct Offer {
     #[serde(rename = "price_r")]
     price_ratio: PriceRatio,
     price: Amount,
+    #[serde(rename = "_links", default, skip_serializing_if = "Links::is_none")]
+    links: Links<OfferLinks>,
 }
 
 impl Offer {
+    /// An offer by `seller` to sell `amount` of `selling` for `buying` at the
+    /// given price. The paging token is the id and the decimal price is
+    /// worked out from the ratio, the way horizon reports them.
+    ///
+    /// ```
+    /// use stellar_client::resources::{Amount, AssetIdentifier, Offer, PriceRatio};
+    ///
+    /// let offer = Offer::new(
+    ///     121,
+    ///     "GCJ34JYMXNI7N55YREWAACMMZECOMTPIYDTFCQBWPUP7BLJQDDTVGUW4",
+    ///     AssetIdentifier::native(),
+    ///     AssetIdentifier::alphanum4("FOO", "GBAUUA74H4XOQYRSOW2RZUA4QL5PB37U3JS5NE3RTB2ELJVMIF5RLMAG"),
+    ///     Amount::new(100_000_000),
+    ///     PriceRatio::new(387, 50),
+    /// );
+    /// assert_eq!(offer.price(), Amount::new(77_400_000));
+    /// ```
+    pub fn new(
+        id: u64,
+        seller: &str,
+        selling: AssetIdentifier,
+        buying: AssetIdentifier,
+        amount: Amount,
+        price_ratio: PriceRatio,
+    ) -> Offer {
+        // A zero denominator is never sent by horizon, so rather than panic
+        // on one the price is left at zero.
+        let price = price_ratio
+            .numerator
+            .saturating_mul(10_000_000)
+            .checked_div(price_ratio.denominator)
+            .unwrap_or(0);
+        Offer {
+            id,
+            paging_token: id.to_string(),
+            seller: seller.to_string(),
+            selling,
+            buying,
+            amount,
+            price_ratio,
+            price: Amount::new(price as i64),
+            links: Links::none(),
+        }
+    }
+
     /// The id of the offer
-    pub fn id(&self) -> i64 {
+    pub fn id(&self) -> u64 {
         self.id
     }
 
@@ -105,6 +584,12 @@ impl Offer {
         &self.paging_token
     }
 
+    /// The links horizon sent with the offer, to itself and the account
+    /// that made it. `None` for an offer built with `new`.
+    pub fn links(&self) -> Option<&OfferLinks> {
+        self.links.get()
+    }
+
     /// The account id fo the account making this offer.
     pub fn seller(&self) -> &str {
         &self.seller
@@ -141,6 +626,7 @@ impl Offer {
 #[cfg(test)]
 mod offer_tests {
     use super::*;
+    use resources::serialize::assert_round_trips;
     use serde_json;
 
     fn offer_json() -> &'static str {
@@ -158,4 +644,24 @@ mod offer_tests {
         assert_eq!(offer.amount(), Amount::new(236_692_509));
         assert_eq!(offer.price(), Amount::new(77_400_000));
     }
+
+    #[test]
+    fn it_builds_the_same_offer_as_horizon_returns() {
+        let parsed: Offer = serde_json::from_str(&offer_json()).unwrap();
+        let issuer = "GBAUUA74H4XOQYRSOW2RZUA4QL5PB37U3JS5NE3RTB2ELJVMIF5RLMAG";
+        let built = Offer::new(
+            121,
+            "GCJ34JYMXNI7N55YREWAACMMZECOMTPIYDTFCQBWPUP7BLJQDDTVGUW4",
+            AssetIdentifier::alphanum4("BAR", issuer),
+            AssetIdentifier::alphanum4("FOO", issuer),
+            Amount::new(236_692_509),
+            PriceRatio::new(387, 50),
+        );
+        assert_eq!(built, parsed);
+    }
+
+    #[test]
+    fn it_serializes_like_horizon() {
+        assert_round_trips::<Offer>(offer_json());
+    }
 }
@@ -1,5 +1,5 @@
 /// Removes the account and transfers all remaining XLM to the destination account.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AccountMerge {
     account: String,
     into: String,
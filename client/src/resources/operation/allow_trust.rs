@@ -5,7 +5,7 @@ use resources::AssetIdentifier;
 ///
 ///Heads up! Unless the issuing account has AUTH_REVOCABLE_FLAG set than the “authorized” flag can
 ///only be set and never cleared.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AllowTrust {
     trustee: String,
     trustor: String,
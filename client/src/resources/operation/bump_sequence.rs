@@ -0,0 +1,19 @@
+/// Bumps the sequence number of the source account, invalidating any
+/// transactions it signed with a lower one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BumpSequence {
+    bump_to: i64,
+}
+
+impl BumpSequence {
+    /// Creates a new BumpSequence
+    pub fn new(bump_to: i64) -> BumpSequence {
+        BumpSequence { bump_to }
+    }
+
+    /// The sequence number the account was bumped to. Bumping to a number
+    /// lower than the account's sequence leaves it as it was.
+    pub fn bump_to(&self) -> i64 {
+        self.bump_to
+    }
+}
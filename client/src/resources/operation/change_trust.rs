@@ -2,7 +2,7 @@ use resources::{Amount, AssetIdentifier};
 
 /// Use “Change Trust” operation to create/update/delete a trust line from the source account to
 /// another. The issuer being trusted and the asset code are in the given Asset object.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ChangeTrust {
     trustee: String,
     trustor: String,
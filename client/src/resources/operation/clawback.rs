@@ -0,0 +1,36 @@
+use resources::{Amount, AssetIdentifier};
+
+/// Burns an amount of a credit asset from an account. Only the issuer of an
+/// asset with clawback enabled can perform a clawback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clawback {
+    from: String,
+    asset: AssetIdentifier,
+    amount: Amount,
+}
+
+impl Clawback {
+    /// Creates a new Clawback
+    pub fn new(from: String, asset: AssetIdentifier, amount: Amount) -> Clawback {
+        Clawback {
+            from,
+            asset,
+            amount,
+        }
+    }
+
+    /// The account the asset was clawed back from.
+    pub fn from(&self) -> &str {
+        &self.from
+    }
+
+    /// The asset that was clawed back.
+    pub fn asset(&self) -> &AssetIdentifier {
+        &self.asset
+    }
+
+    /// The amount that was clawed back.
+    pub fn amount(&self) -> Amount {
+        self.amount
+    }
+}
@@ -0,0 +1,18 @@
+/// Claws back an unclaimed claimable balance, returning it to the issuer of
+/// its asset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClawbackClaimableBalance {
+    balance_id: String,
+}
+
+impl ClawbackClaimableBalance {
+    /// Creates a new ClawbackClaimableBalance
+    pub fn new(balance_id: String) -> ClawbackClaimableBalance {
+        ClawbackClaimableBalance { balance_id }
+    }
+
+    /// The id of the claimable balance that was clawed back.
+    pub fn balance_id(&self) -> &str {
+        &self.balance_id
+    }
+}
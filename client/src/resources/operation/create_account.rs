@@ -1,7 +1,7 @@
 use resources::Amount;
 
 /// A create account operation represents a new account creation.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CreateAccount {
     account: String,
     funder: String,
@@ -3,9 +3,9 @@ use resources::{Amount, AssetIdentifier, offer::PriceRatio};
 /// “Create Passive Offer” operation creates an offer that won’t consume a counter offer that
 /// exactly matches this offer. This is useful for offers just used as 1:1 exchanges for path
 /// payments. Use Manage Offer to manage this offer after using this operation to create it.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CreatePassiveOffer {
-    offer_id: i64,
+    offer_id: u64,
     selling: AssetIdentifier,
     buying: AssetIdentifier,
     amount: Amount,
@@ -16,7 +16,7 @@ pub struct CreatePassiveOffer {
 impl CreatePassiveOffer {
     /// Creates a new CreatePassiveOffer
     pub fn new(
-        offer_id: i64,
+        offer_id: u64,
         selling: AssetIdentifier,
         buying: AssetIdentifier,
         amount: Amount,
@@ -33,7 +33,7 @@ impl CreatePassiveOffer {
         }
     }
     /// Offer ID.
-    pub fn offer_id(&self) -> i64 {
+    pub fn offer_id(&self) -> u64 {
         self.offer_id
     }
 
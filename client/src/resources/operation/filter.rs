@@ -0,0 +1,313 @@
+use super::{Operation, OperationKind as Kind};
+use error::Result;
+use resources::{Amount, AssetIdentifier};
+
+/// Picks out the operations a consumer cares about from iterators and
+/// streams of operations, such as payments of one asset above some amount.
+/// Each condition that is set has to hold for an operation to match, and an
+/// empty filter matches every operation.
+///
+/// ```
+/// use stellar_client::resources::{Amount, AssetIdentifier, Operation, OperationFilter};
+///
+/// let usd = AssetIdentifier::alphanum4(
+///     "USD",
+///     "GC23QF2HUE52AMXUFUH3AYJAXXGXXV2VHXYYR6EYXETPKDXZSAW67XO4",
+/// );
+/// let filter = OperationFilter::default()
+///     .with_kind(Operation::is_payment)
+///     .with_kind(Operation::is_path_payment)
+///     .with_asset(usd)
+///     .with_min_amount(Amount::new(1_000_000_000));
+/// ```
+///
+/// With `FilterOperations` in scope, `filter_operations` applies a filter to
+/// the operations an iterator or stream yields.
+#[derive(Debug, Clone, Default)]
+pub struct OperationFilter {
+    kinds: Vec<fn(&Operation) -> bool>,
+    asset: Option<AssetIdentifier>,
+    counterparty: Option<String>,
+    min_amount: Option<Amount>,
+    max_amount: Option<Amount>,
+}
+
+impl OperationFilter {
+    /// Only matches operations of the kind, given as one of the `is_` methods
+    /// of `Operation` such as `Operation::is_payment`. Operations of any of
+    /// the kinds given match.
+    pub fn with_kind(mut self, kind: fn(&Operation) -> bool) -> Self {
+        self.kinds.push(kind);
+        self
+    }
+
+    /// Only matches operations that move, trade, trust or claw back the
+    /// asset. Account creations and merges move the native asset.
+    pub fn with_asset(mut self, asset: AssetIdentifier) -> Self {
+        self.asset = Some(asset);
+        self
+    }
+
+    /// Only matches operations involving the account on either side, such
+    /// as the sender or receiver of a payment or the trustor of a trust line.
+    pub fn with_counterparty(mut self, account_id: &str) -> Self {
+        self.counterparty = Some(account_id.to_string());
+        self
+    }
+
+    /// Only matches operations with an amount of at least this much. The
+    /// amount is the one paid, offered, clawed back or given to a new
+    /// account, and operations without one don't match.
+    pub fn with_min_amount(mut self, amount: Amount) -> Self {
+        self.min_amount = Some(amount);
+        self
+    }
+
+    /// Only matches operations with an amount of at most this much, the
+    /// same as `with_min_amount` otherwise.
+    pub fn with_max_amount(mut self, amount: Amount) -> Self {
+        self.max_amount = Some(amount);
+        self
+    }
+
+    /// Returns whether the operation meets every condition of the filter.
+    pub fn matches(&self, operation: &Operation) -> bool {
+        if !self.kinds.is_empty() && !self.kinds.iter().any(|kind| kind(operation)) {
+            return false;
+        }
+        if let Some(ref asset) = self.asset {
+            if !assets(operation).contains(&asset) {
+                return false;
+            }
+        }
+        if let Some(ref counterparty) = self.counterparty {
+            if !accounts(operation).contains(&counterparty.as_str()) {
+                return false;
+            }
+        }
+        if self.min_amount.is_some() || self.max_amount.is_some() {
+            let amount = match amount(operation) {
+                Some(amount) => amount,
+                None => return false,
+            };
+            if self.min_amount.map_or(false, |min| amount < min) {
+                return false;
+            }
+            if self.max_amount.map_or(false, |max| amount > max) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// The assets an operation moves, trades, trusts or claws back.
+fn assets(operation: &Operation) -> Vec<&AssetIdentifier> {
+    const NATIVE: &AssetIdentifier = &AssetIdentifier::Native;
+    match *operation.kind() {
+        Kind::CreateAccount(_) | Kind::AccountMerge(_) => vec![NATIVE],
+        Kind::Payment(ref op) => vec![op.asset()],
+        Kind::PathPayment(ref op) => vec![op.source_asset(), op.destination_asset()],
+        Kind::PathPaymentStrictSend(ref op) => vec![op.source_asset(), op.destination_asset()],
+        Kind::ManageOffer(ref op) => vec![op.selling(), op.buying()],
+        Kind::CreatePassiveOffer(ref op) => vec![op.selling(), op.buying()],
+        Kind::ChangeTrust(ref op) => vec![op.asset()],
+        Kind::AllowTrust(ref op) => vec![op.asset()],
+        Kind::Clawback(ref op) => vec![op.asset()],
+        Kind::SetTrustLineFlags(ref op) => vec![op.asset()],
+        _ => Vec::new(),
+    }
+}
+
+/// The accounts on either side of an operation.
+fn accounts(operation: &Operation) -> Vec<&str> {
+    match *operation.kind() {
+        Kind::CreateAccount(ref op) => vec![op.funder(), op.account()],
+        Kind::Payment(ref op) => vec![op.from(), op.to()],
+        Kind::PathPayment(ref op) => vec![op.from(), op.to()],
+        Kind::PathPaymentStrictSend(ref op) => vec![op.from(), op.to()],
+        Kind::AccountMerge(ref op) => vec![op.account(), op.into()],
+        Kind::ChangeTrust(ref op) => vec![op.trustor(), op.trustee()],
+        Kind::AllowTrust(ref op) => vec![op.trustor(), op.trustee()],
+        Kind::Clawback(ref op) => vec![op.from()],
+        Kind::SetTrustLineFlags(ref op) => vec![op.trustor()],
+        _ => Vec::new(),
+    }
+}
+
+/// The amount paid, offered, clawed back or given to a new account.
+fn amount(operation: &Operation) -> Option<Amount> {
+    match *operation.kind() {
+        Kind::CreateAccount(ref op) => Some(op.starting_balance()),
+        Kind::Payment(ref op) => Some(op.amount()),
+        Kind::PathPayment(ref op) => Some(op.destination_amount()),
+        Kind::PathPaymentStrictSend(ref op) => Some(op.destination_amount()),
+        Kind::ManageOffer(ref op) => Some(op.amount()),
+        Kind::CreatePassiveOffer(ref op) => Some(op.amount()),
+        Kind::Clawback(ref op) => Some(op.amount()),
+        _ => None,
+    }
+}
+
+/// Filters the operations of any iterator of results, such as the `Iter`,
+/// `Stream` and `ReconnectingStream` of the sync client.
+///
+/// ```no_run
+/// use stellar_client::endpoint::operation;
+/// use stellar_client::resources::{Operation, OperationFilter};
+/// use stellar_client::resources::operation::FilterOperations;
+/// use stellar_client::sync::{Client, Iter};
+///
+/// let client = Client::horizon_test().unwrap();
+/// let payments = OperationFilter::default().with_kind(Operation::is_payment);
+/// for payment in Iter::new(&client, operation::All::default()).filter_operations(payments) {
+///     println!("{:?}", payment.unwrap());
+/// }
+/// ```
+pub trait FilterOperations: Sized {
+    /// Yields only the operations the filter matches. Errors are passed
+    /// through so that the consumer still sees them.
+    fn filter_operations(self, filter: OperationFilter) -> Filtered<Self>;
+}
+
+impl<I> FilterOperations for I
+where
+    I: Iterator<Item = Result<Operation>>,
+{
+    fn filter_operations(self, filter: OperationFilter) -> Filtered<Self> {
+        Filtered { iter: self, filter }
+    }
+}
+
+/// An iterator over the operations that an `OperationFilter` matches. See
+/// `FilterOperations::filter_operations`.
+#[derive(Debug)]
+pub struct Filtered<I> {
+    iter: I,
+    filter: OperationFilter,
+}
+
+impl<I> Iterator for Filtered<I>
+where
+    I: Iterator<Item = Result<Operation>>,
+{
+    type Item = Result<Operation>;
+
+    fn next(&mut self) -> Option<Result<Operation>> {
+        loop {
+            match self.iter.next()? {
+                Ok(ref operation) if !self.filter.matches(operation) => continue,
+                result => return Some(result),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod operation_filter_tests {
+    use super::*;
+    use error::Error;
+    use serde_json;
+
+    fn operation(json: &str) -> Operation {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn payment() -> Operation {
+        operation(include_str!("../../../fixtures/operations/payment.json"))
+    }
+
+    fn path_payment() -> Operation {
+        operation(include_str!(
+            "../../../fixtures/operations/path_payment.json"
+        ))
+    }
+
+    fn create_account() -> Operation {
+        operation(include_str!(
+            "../../../fixtures/operations/create_account.json"
+        ))
+    }
+
+    fn inflation() -> Operation {
+        operation(include_str!("../../../fixtures/operations/inflation.json"))
+    }
+
+    fn eur() -> AssetIdentifier {
+        AssetIdentifier::alphanum4(
+            "EUR",
+            "GCQPYGH4K57XBDENKKX55KDTWOTK5WDWRQOH2LHEDX3EKVIQRLMESGBG",
+        )
+    }
+
+    #[test]
+    fn it_matches_everything_when_empty() {
+        let filter = OperationFilter::default();
+        for op in &[payment(), path_payment(), create_account(), inflation()] {
+            assert!(filter.matches(op));
+        }
+    }
+
+    #[test]
+    fn it_matches_any_of_the_kinds() {
+        let filter = OperationFilter::default()
+            .with_kind(Operation::is_payment)
+            .with_kind(Operation::is_path_payment);
+        assert!(filter.matches(&payment()));
+        assert!(filter.matches(&path_payment()));
+        assert!(!filter.matches(&create_account()));
+        assert!(!filter.matches(&inflation()));
+    }
+
+    #[test]
+    fn it_matches_by_asset() {
+        let filter = OperationFilter::default().with_asset(eur());
+        assert!(filter.matches(&path_payment()));
+        assert!(!filter.matches(&payment()));
+        assert!(!filter.matches(&inflation()));
+
+        let filter = OperationFilter::default().with_asset(AssetIdentifier::native());
+        assert!(filter.matches(&payment()));
+        assert!(filter.matches(&create_account()));
+        assert!(!filter.matches(&path_payment()));
+    }
+
+    #[test]
+    fn it_matches_by_counterparty_on_either_side() {
+        let sender = OperationFilter::default()
+            .with_counterparty("GAKLBGHNHFQ3BMUYG5KU4BEWO6EYQHZHAXEWC33W34PH2RBHZDSQBD75");
+        let receiver = OperationFilter::default()
+            .with_counterparty("GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ");
+        assert!(sender.matches(&payment()));
+        assert!(receiver.matches(&payment()));
+        assert!(!sender.matches(&create_account()));
+        assert!(receiver.matches(&create_account()));
+        assert!(!receiver.matches(&inflation()));
+    }
+
+    #[test]
+    fn it_matches_amounts_within_the_range() {
+        let filter = OperationFilter::default()
+            .with_min_amount("100".parse().unwrap())
+            .with_max_amount("200".parse().unwrap());
+        assert!(filter.matches(&payment()));
+        assert!(!filter.matches(&path_payment()));
+        assert!(!filter.matches(&create_account()));
+        assert!(!filter.matches(&inflation()));
+    }
+
+    #[test]
+    fn it_filters_an_iterator_and_passes_errors_through() {
+        let operations = vec![
+            Ok(payment()),
+            Ok(create_account()),
+            Err(Error::BadUri),
+            Ok(path_payment()),
+        ];
+        let filter = OperationFilter::default().with_kind(Operation::is_payment);
+        let filtered: Vec<_> = operations.into_iter().filter_operations(filter).collect();
+        assert_eq!(filtered.len(), 2);
+        assert_eq!(filtered[0].as_ref().unwrap(), &payment());
+        assert!(filtered[1].is_err());
+    }
+}
@@ -0,0 +1,80 @@
+use super::Reserve;
+use resources::{Amount, PriceRatio};
+
+/// Deposits assets into a liquidity pool in exchange for pool shares. The
+/// deposit is bounded by the maximum reserves the depositor is willing to add
+/// and the range of prices they're willing to deposit at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiquidityPoolDeposit {
+    liquidity_pool_id: String,
+    reserves_max: Vec<Reserve>,
+    min_price: Amount,
+    min_price_ratio: PriceRatio,
+    max_price: Amount,
+    max_price_ratio: PriceRatio,
+    reserves_deposited: Vec<Reserve>,
+    shares_received: Amount,
+}
+
+impl LiquidityPoolDeposit {
+    /// Creates a new LiquidityPoolDeposit
+    pub fn new(
+        liquidity_pool_id: String,
+        reserves_max: Vec<Reserve>,
+        min_price: (Amount, PriceRatio),
+        max_price: (Amount, PriceRatio),
+        reserves_deposited: Vec<Reserve>,
+        shares_received: Amount,
+    ) -> LiquidityPoolDeposit {
+        LiquidityPoolDeposit {
+            liquidity_pool_id,
+            reserves_max,
+            min_price: min_price.0,
+            min_price_ratio: min_price.1,
+            max_price: max_price.0,
+            max_price_ratio: max_price.1,
+            reserves_deposited,
+            shares_received,
+        }
+    }
+
+    /// The id of the liquidity pool deposited into.
+    pub fn liquidity_pool_id(&self) -> &str {
+        &self.liquidity_pool_id
+    }
+
+    /// The most of each asset the depositor was willing to deposit.
+    pub fn reserves_max(&self) -> &[Reserve] {
+        &self.reserves_max
+    }
+
+    /// The lowest price the depositor was willing to deposit at.
+    pub fn min_price(&self) -> Amount {
+        self.min_price
+    }
+
+    /// The lowest price as a ratio.
+    pub fn min_price_ratio(&self) -> PriceRatio {
+        self.min_price_ratio
+    }
+
+    /// The highest price the depositor was willing to deposit at.
+    pub fn max_price(&self) -> Amount {
+        self.max_price
+    }
+
+    /// The highest price as a ratio.
+    pub fn max_price_ratio(&self) -> PriceRatio {
+        self.max_price_ratio
+    }
+
+    /// The amount of each asset actually deposited.
+    pub fn reserves_deposited(&self) -> &[Reserve] {
+        &self.reserves_deposited
+    }
+
+    /// The number of pool shares received for the deposit.
+    pub fn shares_received(&self) -> Amount {
+        self.shares_received
+    }
+}
@@ -0,0 +1,49 @@
+use super::Reserve;
+use resources::Amount;
+
+/// Redeems pool shares for a proportional amount of each of the liquidity
+/// pool's reserves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiquidityPoolWithdraw {
+    liquidity_pool_id: String,
+    reserves_min: Vec<Reserve>,
+    shares: Amount,
+    reserves_received: Vec<Reserve>,
+}
+
+impl LiquidityPoolWithdraw {
+    /// Creates a new LiquidityPoolWithdraw
+    pub fn new(
+        liquidity_pool_id: String,
+        reserves_min: Vec<Reserve>,
+        shares: Amount,
+        reserves_received: Vec<Reserve>,
+    ) -> LiquidityPoolWithdraw {
+        LiquidityPoolWithdraw {
+            liquidity_pool_id,
+            reserves_min,
+            shares,
+            reserves_received,
+        }
+    }
+
+    /// The id of the liquidity pool withdrawn from.
+    pub fn liquidity_pool_id(&self) -> &str {
+        &self.liquidity_pool_id
+    }
+
+    /// The least of each asset the withdrawer was willing to receive.
+    pub fn reserves_min(&self) -> &[Reserve] {
+        &self.reserves_min
+    }
+
+    /// The number of pool shares redeemed.
+    pub fn shares(&self) -> Amount {
+        self.shares
+    }
+
+    /// The amount of each asset actually received.
+    pub fn reserves_received(&self) -> &[Reserve] {
+        &self.reserves_received
+    }
+}
@@ -1,5 +1,5 @@
 /// Set, modify or delete a Data Entry (name/value pair) for an account.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ManageData {
     name: String,
     value: String,
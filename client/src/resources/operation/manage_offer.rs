@@ -2,9 +2,9 @@ use resources::{Amount, AssetIdentifier, offer::PriceRatio};
 
 /// A “Manage Offer” operation can create, update or delete an offer to trade assets in the Stellar
 /// network. It specifies an issuer, a price and amount of a given asset to buy or sell.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ManageOffer {
-    offer_id: i64,
+    offer_id: u64,
     selling: AssetIdentifier,
     buying: AssetIdentifier,
     amount: Amount,
@@ -15,7 +15,7 @@ pub struct ManageOffer {
 impl ManageOffer {
     /// Creates a new ManageOffer
     pub fn new(
-        offer_id: i64,
+        offer_id: u64,
         selling: AssetIdentifier,
         buying: AssetIdentifier,
         amount: Amount,
@@ -32,7 +32,7 @@ impl ManageOffer {
         }
     }
     /// Offer ID.
-    pub fn offer_id(&self) -> i64 {
+    pub fn offer_id(&self) -> u64 {
         self.offer_id
     }
 
@@ -1,27 +1,50 @@
-use super::deserialize;
-use resources::{Amount, AssetIdentifier, asset::Flags, offer::PriceRatio};
-use serde::{de, Deserialize, Deserializer};
+use super::{deserialize, serialize, links::Links};
+use chrono::{DateTime, Utc};
+use resources::{Amount, AssetIdentifier, OperationId, OperationLinks, Transaction, asset::Flags,
+                offer::PriceRatio};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{self, Value};
+use std::borrow::Cow;
+use std::fmt;
 mod account_merge;
 mod allow_trust;
+mod bump_sequence;
 mod change_trust;
+mod clawback;
+mod clawback_claimable_balance;
 mod create_account;
 mod create_passive_offer;
+mod filter;
+mod liquidity_pool_deposit;
+mod liquidity_pool_withdraw;
 mod manage_data;
 mod manage_offer;
 mod path_payment;
+mod path_payment_strict_send;
 mod payment;
+mod reserve;
 mod set_options;
+mod set_trust_line_flags;
 
 pub use self::account_merge::AccountMerge;
 pub use self::allow_trust::AllowTrust;
+pub use self::bump_sequence::BumpSequence;
 pub use self::change_trust::ChangeTrust;
+pub use self::clawback::Clawback;
+pub use self::clawback_claimable_balance::ClawbackClaimableBalance;
 pub use self::create_account::CreateAccount;
 pub use self::create_passive_offer::CreatePassiveOffer;
+pub use self::filter::{FilterOperations, Filtered, OperationFilter};
+pub use self::liquidity_pool_deposit::LiquidityPoolDeposit;
+pub use self::liquidity_pool_withdraw::LiquidityPoolWithdraw;
 pub use self::manage_data::ManageData;
 pub use self::manage_offer::ManageOffer;
 pub use self::path_payment::PathPayment;
+pub use self::path_payment_strict_send::PathPaymentStrictSend;
 pub use self::payment::Payment;
+pub use self::reserve::Reserve;
 pub use self::set_options::SetOptions;
+pub use self::set_trust_line_flags::{SetTrustLineFlags, TrustLineFlags};
 
 #[cfg(test)]
 mod test;
@@ -29,17 +52,21 @@ mod test;
 /// Operations are objects that represent a desired change to the ledger: payments, offers to
 /// exchange currency, changes made to account options, etc. Operations are submitted to the
 /// Stellar network grouped in a Transaction.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Operation {
     id: i64,
     paging_token: String,
     transaction_hash: String,
+    source_account: Option<String>,
+    created_at: Option<DateTime<Utc>>,
     kind: Kind,
+    links: Links<OperationLinks>,
+    transaction: Option<Box<Transaction>>,
 }
 
 /// Each operation type is representing by a kind and captures data specific to that
 /// type within it's newtype.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum OperationKind {
     /// A create account operation represents a new account creation.
     CreateAccount(CreateAccount),
@@ -50,6 +77,9 @@ pub enum OperationKind {
     /// type of payment starts as one type of asset and ends as another type of asset. There can be
     /// other assets that are traded into and out of along the path.
     PathPayment(PathPayment),
+    /// A path payment that sends an exact amount of the source asset and
+    /// receives at least a minimum amount of the destination asset.
+    PathPaymentStrictSend(PathPaymentStrictSend),
     /// A “Manage Offer” operation can create, update or delete an offer to trade assets in the Stellar
     /// network. It specifies an issuer, a price and amount of a given asset to buy or sell.
     ManageOffer(ManageOffer),
@@ -79,6 +109,26 @@ pub enum OperationKind {
     Inflation,
     /// Set, modify or delete a Data Entry (name/value pair) for an account.
     ManageData(ManageData),
+    /// Bumps the sequence number of the source account.
+    BumpSequence(BumpSequence),
+    /// Burns an amount of an asset from an account, performed by the issuer.
+    Clawback(Clawback),
+    /// Returns an unclaimed claimable balance to the issuer of its asset.
+    ClawbackClaimableBalance(ClawbackClaimableBalance),
+    /// Sets or clears the authorization and clawback flags of a trust line.
+    SetTrustLineFlags(SetTrustLineFlags),
+    /// Deposits assets into a liquidity pool in exchange for pool shares.
+    LiquidityPoolDeposit(LiquidityPoolDeposit),
+    /// Redeems pool shares for assets from a liquidity pool.
+    LiquidityPoolWithdraw(LiquidityPoolWithdraw),
+    /// An operation type this crate doesn't recognize yet. These are only
+    /// produced when the client is configured to allow unknown variants.
+    Unknown {
+        /// The type of the operation as horizon named it.
+        type_name: String,
+        /// The operation as it was returned by horizon.
+        raw: Value,
+    },
 }
 // Use inside file to be brief
 use self::OperationKind as Kind;
@@ -90,16 +140,47 @@ impl Operation {
         self.id
     }
 
+    /// The id of this operation split into its ledger, transaction order and
+    /// operation order, for ordering operations.
+    pub fn operation_id(&self) -> OperationId {
+        OperationId::from_u64(self.id as u64)
+    }
+
     /// A paging token suitable for use as a cursor parameter.
     pub fn paging_token(&self) -> &str {
         &self.paging_token
     }
 
+    /// The links horizon sent with the operation, to its transaction and
+    /// effects and the operations either side of it.
+    pub fn links(&self) -> Option<&OperationLinks> {
+        self.links.get()
+    }
+
     /// The hash for the transaction that the operation was part of
     pub fn transaction(&self) -> &str {
         &self.transaction_hash
     }
 
+    /// The transaction the operation was part of, when it was requested
+    /// with `join=transactions`. See `endpoint::Join`.
+    pub fn embedded_transaction(&self) -> Option<&Transaction> {
+        self.transaction.as_ref().map(|transaction| &**transaction)
+    }
+
+    /// The account the operation was applied to, which is the source of its
+    /// transaction unless the operation names another. `None` if horizon
+    /// left it out, as older versions did.
+    pub fn source_account(&self) -> Option<&str> {
+        self.source_account.as_ref().map(String::as_str)
+    }
+
+    /// When the ledger the operation was applied in closed, if horizon
+    /// said.
+    pub fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
     /// Specifies the type of operation, See “Types” section below for reference.
     pub fn type_i(&self) -> u32 {
         match self.kind {
@@ -114,6 +195,17 @@ impl Operation {
             Kind::AccountMerge(_) => 8,
             Kind::Inflation => 9,
             Kind::ManageData(_) => 10,
+            Kind::BumpSequence(_) => 11,
+            Kind::PathPaymentStrictSend(_) => 13,
+            Kind::Clawback(_) => 19,
+            Kind::ClawbackClaimableBalance(_) => 20,
+            Kind::SetTrustLineFlags(_) => 21,
+            Kind::LiquidityPoolDeposit(_) => 22,
+            Kind::LiquidityPoolWithdraw(_) => 23,
+            Kind::Unknown { ref raw, .. } => raw.get("type_i")
+                .and_then(Value::as_u64)
+                .map(|type_i| type_i as u32)
+                .unwrap_or(u32::MAX),
         }
     }
 
@@ -128,6 +220,7 @@ impl Operation {
             Kind::CreateAccount(_) => "Create Account",
             Kind::Payment(_) => "Payment",
             Kind::PathPayment(_) => "Path Payment",
+            Kind::PathPaymentStrictSend(_) => "Path Payment Strict Send",
             Kind::ManageOffer(_) => "Manage Offer",
             Kind::CreatePassiveOffer(_) => "Create Passive Offer",
             Kind::SetOptions(_) => "Set Options",
@@ -136,6 +229,13 @@ impl Operation {
             Kind::AccountMerge(_) => "Account Merge",
             Kind::Inflation => "Inflation",
             Kind::ManageData(_) => "Manage Data",
+            Kind::BumpSequence(_) => "Bump Sequence",
+            Kind::Clawback(_) => "Clawback",
+            Kind::ClawbackClaimableBalance(_) => "Clawback Claimable Balance",
+            Kind::SetTrustLineFlags(_) => "Set Trust Line Flags",
+            Kind::LiquidityPoolDeposit(_) => "Liquidity Pool Deposit",
+            Kind::LiquidityPoolWithdraw(_) => "Liquidity Pool Withdraw",
+            Kind::Unknown { ref type_name, .. } => type_name,
         }
     }
 
@@ -163,6 +263,14 @@ impl Operation {
         }
     }
 
+    /// Returns true if the operation is a strict send path payment operation
+    pub fn is_path_payment_strict_send(&self) -> bool {
+        match self.kind {
+            Kind::PathPaymentStrictSend(_) => true,
+            _ => false,
+        }
+    }
+
     /// Returns true if the operation is a manage offer operation
     pub fn is_manage_offer(&self) -> bool {
         match self.kind {
@@ -226,60 +334,465 @@ impl Operation {
             _ => false,
         }
     }
+
+    /// Returns true if the operation is a bump sequence operation
+    pub fn is_bump_sequence(&self) -> bool {
+        match self.kind {
+            Kind::BumpSequence(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the operation is a clawback operation
+    pub fn is_clawback(&self) -> bool {
+        match self.kind {
+            Kind::Clawback(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the operation is a clawback claimable balance operation
+    pub fn is_clawback_claimable_balance(&self) -> bool {
+        match self.kind {
+            Kind::ClawbackClaimableBalance(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the operation is a set trust line flags operation
+    pub fn is_set_trust_line_flags(&self) -> bool {
+        match self.kind {
+            Kind::SetTrustLineFlags(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the operation is a liquidity pool deposit operation
+    pub fn is_liquidity_pool_deposit(&self) -> bool {
+        match self.kind {
+            Kind::LiquidityPoolDeposit(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the operation is a liquidity pool withdraw operation
+    pub fn is_liquidity_pool_withdraw(&self) -> bool {
+        match self.kind {
+            Kind::LiquidityPoolWithdraw(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the operation is of a type this crate doesn't recognize
+    pub fn is_unknown(&self) -> bool {
+        match self.kind {
+            Kind::Unknown { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+/// An operation that borrows its strings from the json it was deserialized
+/// from, for reading pages of operations without allocating a string for
+/// every field of every record. It exposes the fields ingestion reads most,
+/// and `into_owned` finishes parsing it into an `Operation`.
+///
+/// Strings are only borrowed when deserializing from a borrowed source such
+/// as `serde_json::from_str` or `from_slice`, and only when they have no
+/// escapes to undo.
+///
+/// ```
+/// # extern crate serde_json;
+/// # extern crate stellar_client;
+/// # fn main() {
+/// use stellar_client::endpoint::Records;
+/// use stellar_client::resources::OperationRef;
+///
+/// let body = r#"{ "_embedded": { "records": [{
+///     "id": "77309415424",
+///     "paging_token": "77309415424",
+///     "transaction_hash": "3b5c8f31c4b8a1e2d64b4c55a1a6a0fbf8e5f3c4e1d9a3b6c7d2e0f1a2b3c4d5",
+///     "type": "payment",
+///     "type_i": 1,
+///     "from": "GBIA4FH6TV64KSPDAJCNUQSM7PFL4ILGUVJDPCLUOPJ7ONMKBBVUQHRO",
+///     "to": "GCZ4ZBX5YJAQJCOZEIE4SNUQJMRWDIPLTHGCCUWZBOKXX7KANJDQ2BIH",
+///     "asset_type": "native",
+///     "amount": "10.0000000"
+/// }] } }"#;
+/// let page: Records<OperationRef> = serde_json::from_str(body).unwrap();
+/// let payment = &page.records()[0];
+/// assert_eq!(payment.type_name(), "payment");
+/// assert_eq!(payment.to(), Some("GCZ4ZBX5YJAQJCOZEIE4SNUQJMRWDIPLTHGCCUWZBOKXX7KANJDQ2BIH"));
+/// assert!(payment.clone().into_owned().unwrap().is_payment());
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct OperationRef<'a> {
+    rep: Intermediate<'a>,
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for OperationRef<'a> {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Intermediate::deserialize(d).map(|rep| OperationRef { rep })
+    }
+}
+
+impl<'a> OperationRef<'a> {
+    /// The canonical id of this operation.
+    pub fn id(&self) -> i64 {
+        self.rep.id
+    }
+
+    /// The id of this operation split into its ledger, transaction order and
+    /// operation order, for ordering operations.
+    pub fn operation_id(&self) -> OperationId {
+        OperationId::from_u64(self.rep.id as u64)
+    }
+
+    /// A paging token suitable for use as a cursor parameter.
+    pub fn paging_token(&self) -> &str {
+        &self.rep.paging_token
+    }
+
+    /// The hash for the transaction that the operation was part of
+    pub fn transaction(&self) -> &str {
+        &self.rep.transaction_hash
+    }
+
+    /// The transaction the operation was part of, when it was requested
+    /// with `join=transactions`.
+    pub fn embedded_transaction(&self) -> Option<&Transaction> {
+        self.rep.transaction.as_ref().map(|transaction| &**transaction)
+    }
+
+    /// The account the operation was applied to, if horizon said.
+    pub fn source_account(&self) -> Option<&str> {
+        borrowed(&self.rep.source_account)
+    }
+
+    /// When the ledger the operation was applied in closed, if horizon
+    /// said.
+    pub fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.rep.created_at
+    }
+
+    /// The type of the operation as horizon names it, such as `payment`.
+    /// Types this crate doesn't recognize are read like any other.
+    pub fn type_name(&self) -> &str {
+        &self.rep.kind
+    }
+
+    /// The numeric type of the operation, when horizon includes it.
+    pub fn type_i(&self) -> Option<u32> {
+        self.rep.type_i
+    }
+
+    /// The account created, merged or sending a create account operation.
+    pub fn account(&self) -> Option<&str> {
+        borrowed(&self.rep.account)
+    }
+
+    /// The account that funded a created account.
+    pub fn funder(&self) -> Option<&str> {
+        borrowed(&self.rep.funder)
+    }
+
+    /// The account sending a payment, or clawed back from.
+    pub fn from(&self) -> Option<&str> {
+        borrowed(&self.rep.from)
+    }
+
+    /// The account receiving a payment.
+    pub fn to(&self) -> Option<&str> {
+        borrowed(&self.rep.to)
+    }
+
+    /// The account an account was merged into.
+    pub fn into(&self) -> Option<&str> {
+        borrowed(&self.rep.into)
+    }
+
+    /// The account trusting an asset in trust operations.
+    pub fn trustor(&self) -> Option<&str> {
+        borrowed(&self.rep.trustor)
+    }
+
+    /// The issuer of the asset in trust operations.
+    pub fn trustee(&self) -> Option<&str> {
+        borrowed(&self.rep.trustee)
+    }
+
+    /// The type of the asset paid, trusted or clawed back.
+    pub fn asset_type(&self) -> Option<&str> {
+        borrowed(&self.rep.asset_type)
+    }
+
+    /// The code of the asset paid, trusted or clawed back, unless native.
+    pub fn asset_code(&self) -> Option<&str> {
+        borrowed(&self.rep.asset_code)
+    }
+
+    /// The issuer of the asset paid, trusted or clawed back, unless native.
+    pub fn asset_issuer(&self) -> Option<&str> {
+        borrowed(&self.rep.asset_issuer)
+    }
+
+    /// The amount paid, offered or clawed back.
+    pub fn amount(&self) -> Option<Amount> {
+        self.rep.amount
+    }
+
+    /// The id of the offer managed.
+    pub fn offer_id(&self) -> Option<u64> {
+        self.rep.offer_id
+    }
+
+    /// Parses the operation into its kind, allocating the strings it
+    /// borrowed. This fails for operations missing the fields of their kind
+    /// and for types this crate doesn't recognize.
+    pub fn into_owned(self) -> Result<Operation, serde_json::Error> {
+        self.rep.into_operation(None)
+    }
+}
+
+fn borrowed<'b>(field: &'b Option<Cow<str>>) -> Option<&'b str> {
+    field.as_ref().map(|field| field.as_ref())
+}
+
+/// The fields every operation has whatever its kind, for pipelines that
+/// handle operations generically. Both `Operation` and the borrowing
+/// `OperationRef` implement it.
+///
+/// ```
+/// use stellar_client::resources::{Operation, OperationCommon};
+///
+/// fn by_source<'a, T: OperationCommon>(operations: &'a [T], account_id: &str) -> Vec<&'a str> {
+///     operations
+///         .iter()
+///         .filter(|op| op.source_account() == Some(account_id))
+///         .map(|op| op.transaction_hash())
+///         .collect()
+/// }
+/// # let operations: Vec<Operation> = Vec::new();
+/// # assert!(by_source(&operations, "GA").is_empty());
+/// ```
+pub trait OperationCommon {
+    /// A paging token suitable for use as a cursor parameter.
+    fn paging_token(&self) -> &str;
+
+    /// The hash of the transaction the operation was part of.
+    fn transaction_hash(&self) -> &str;
+
+    /// The account the operation was applied to, if horizon said.
+    fn source_account(&self) -> Option<&str>;
+
+    /// When the ledger the operation was applied in closed, if horizon
+    /// said.
+    fn created_at(&self) -> Option<DateTime<Utc>>;
+}
+
+impl OperationCommon for Operation {
+    fn paging_token(&self) -> &str {
+        Operation::paging_token(self)
+    }
+
+    fn transaction_hash(&self) -> &str {
+        self.transaction()
+    }
+
+    fn source_account(&self) -> Option<&str> {
+        Operation::source_account(self)
+    }
+
+    fn created_at(&self) -> Option<DateTime<Utc>> {
+        Operation::created_at(self)
+    }
+}
+
+impl<'a> OperationCommon for OperationRef<'a> {
+    fn paging_token(&self) -> &str {
+        OperationRef::paging_token(self)
+    }
+
+    fn transaction_hash(&self) -> &str {
+        self.transaction()
+    }
+
+    fn source_account(&self) -> Option<&str> {
+        OperationRef::source_account(self)
+    }
+
+    fn created_at(&self) -> Option<DateTime<Utc>> {
+        OperationRef::created_at(self)
+    }
 }
 
 /// Represents the actual structure of the json api. This allows us to parse
 /// directly from the captured json into our own types.
-#[derive(Debug, Deserialize, Clone)]
-struct Intermediate {
-    #[serde(deserialize_with = "deserialize::from_str")]
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+struct Intermediate<'a> {
+    #[serde(serialize_with = "serialize::to_string", deserialize_with = "deserialize::from_str")]
     id: i64,
-    paging_token: String,
-    transaction_hash: String,
-    #[serde(rename = "type")]
-    kind: String,
-    account: Option<String>,
-    funder: Option<String>,
+    #[serde(borrow)]
+    paging_token: Cow<'a, str>,
+    #[serde(borrow)]
+    transaction_hash: Cow<'a, str>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    source_account: Option<Cow<'a, str>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created_at: Option<DateTime<Utc>>,
+    #[serde(borrow, rename = "type")]
+    kind: Cow<'a, str>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    type_i: Option<u32>,
+    #[serde(rename = "_links", default, skip_serializing_if = "Links::is_none")]
+    links: Links<OperationLinks>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    transaction: Option<Cow<'a, Transaction>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    account: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    funder: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     starting_balance: Option<Amount>,
-    from: Option<String>,
-    to: Option<String>,
-    asset_type: Option<String>,
-    asset_code: Option<String>,
-    asset_issuer: Option<String>,
-    source_asset_type: Option<String>,
-    source_asset_code: Option<String>,
-    source_asset_issuer: Option<String>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    from: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    to: Option<Cow<'a, str>>,
+    #[serde(default, deserialize_with = "deserialize::optional_from_number_or_str",
+            serialize_with = "serialize::optional_to_string", skip_serializing_if = "Option::is_none")]
+    to_muxed_id: Option<u64>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    asset_type: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    asset_code: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    asset_issuer: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    source_asset_type: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    source_asset_code: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    source_asset_issuer: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     amount: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     source_amount: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     source_max: Option<Amount>,
-    buying_asset_type: Option<String>,
-    buying_asset_code: Option<String>,
-    buying_asset_issuer: Option<String>,
-    selling_asset_type: Option<String>,
-    selling_asset_code: Option<String>,
-    selling_asset_issuer: Option<String>,
-    offer_id: Option<i64>,
-    #[serde(rename = "price_r")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    destination_min: Option<Amount>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    buying_asset_type: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    buying_asset_code: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    buying_asset_issuer: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    selling_asset_type: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    selling_asset_code: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    selling_asset_issuer: Option<Cow<'a, str>>,
+    #[serde(default, skip_serializing_if = "Option::is_none",
+            deserialize_with = "deserialize::optional_from_number_or_str")]
+    offer_id: Option<u64>,
+    #[serde(rename = "price_r", skip_serializing_if = "Option::is_none")]
     price_ratio: Option<PriceRatio>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     price: Option<Amount>,
-    signer_key: Option<String>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    signer_key: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     signer_weight: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     master_key_weight: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     low_threshold: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     med_threshold: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     high_threshold: Option<u32>,
-    home_domain: Option<String>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    home_domain: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     set_flags: Option<Vec<u32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     set_flags_s: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     clear_flags: Option<Vec<u32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     clear_flags_s: Option<Vec<String>>,
-    trustor: Option<String>,
-    trustee: Option<String>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    trustor: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    trustee: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     authorize: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     limit: Option<Amount>,
-    into: Option<String>,
-    name: Option<String>,
-    value: Option<String>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    into: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    name: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    value: Option<Cow<'a, str>>,
+    #[serde(default, deserialize_with = "deserialize::optional_from_number_or_str",
+            serialize_with = "serialize::optional_to_string", skip_serializing_if = "Option::is_none")]
+    bump_to: Option<i64>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    balance_id: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    liquidity_pool_id: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reserves_max: Option<Vec<Reserve>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_price: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_price_r: Option<PriceRatio>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_price: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_price_r: Option<PriceRatio>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reserves_deposited: Option<Vec<Reserve>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shares_received: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reserves_min: Option<Vec<Reserve>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shares: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reserves_received: Option<Vec<Reserve>>,
 }
 
 impl<'de> Deserialize<'de> for Operation {
@@ -287,23 +800,61 @@ impl<'de> Deserialize<'de> for Operation {
     where
         D: Deserializer<'de>,
     {
-        let rep = Intermediate::deserialize(d)?;
+        d.deserialize_newtype_struct(deserialize::UNKNOWN_VARIANTS, OperationVisitor)
+    }
+}
+
+/// Deserializes an operation, rejecting unknown types when visited as a newtype
+/// struct and keeping them when `deserialize::UnknownVariants` visits it as
+/// `Some`.
+struct OperationVisitor;
+
+impl<'de> de::Visitor<'de> for OperationVisitor {
+    type Value = Operation;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an operation")
+    }
 
-        let kind: Kind = match rep.kind.as_str() {
+    fn visit_newtype_struct<D>(self, d: D) -> Result<Operation, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Intermediate::deserialize(d)?.into_operation(None)
+    }
+
+    fn visit_some<D>(self, d: D) -> Result<Operation, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = Value::deserialize(d)?;
+        Intermediate::deserialize(&raw)
+            .map_err(de::Error::custom)?
+            .into_operation(Some(&raw))
+    }
+}
+
+impl<'a> Intermediate<'a> {
+    /// Builds the operation from the fields of its kind, allocating the
+    /// strings it borrowed. Unknown kinds keep the raw json when it's given
+    /// and fail otherwise.
+    fn into_operation<E: de::Error>(self, raw: Option<&Value>) -> Result<Operation, E> {
+        let rep = self;
+        let kind: Kind = match rep.kind.as_ref() {
             "create_account" => match rep {
                 Intermediate {
                     account: Some(account),
                     funder: Some(funder),
                     starting_balance: Some(starting_balance),
                     ..
-                } => Kind::CreateAccount(CreateAccount::new(account, funder, starting_balance)),
+                } => Kind::CreateAccount(CreateAccount::new(account.into_owned(), funder.into_owned(), starting_balance)),
                 _ => {
                     return Err(de::Error::custom(
                         "Missing fields for create_account operation.",
                     ))
                 }
             },
-            "path_payment" => match rep {
+            "path_payment" | "path_payment_strict_receive" => match rep {
                 Intermediate {
                     from: Some(from),
                     to: Some(to),
@@ -315,24 +866,29 @@ impl<'de> Deserialize<'de> for Operation {
                     source_asset_issuer,
                     source_asset_type: Some(source_asset_type),
                     source_max: Some(source_max),
+                    to_muxed_id,
                     ..
                 } => {
                     let destination_asset_identifier =
-                        AssetIdentifier::new(&asset_type, asset_code, asset_issuer)
+                        owned_asset(&asset_type, asset_code, asset_issuer)
                             .map_err(de::Error::custom)?;
-                    let source_asset_identifier = AssetIdentifier::new(
+                    let source_asset_identifier = owned_asset(
                         &source_asset_type,
                         source_asset_code,
                         source_asset_issuer,
                     ).map_err(de::Error::custom)?;
-                    Kind::PathPayment(PathPayment::new(
-                        from,
-                        to,
+                    let op = PathPayment::new(
+                        from.into_owned(),
+                        to.into_owned(),
                         destination_asset_identifier,
                         amount,
                         source_asset_identifier,
                         source_max,
-                    ))
+                    );
+                    Kind::PathPayment(match to_muxed_id {
+                        Some(id) => op.with_to_muxed_id(id),
+                        None => op,
+                    })
                 }
                 _ => {
                     return Err(de::Error::custom(
@@ -340,6 +896,50 @@ impl<'de> Deserialize<'de> for Operation {
                     ))
                 }
             },
+            "path_payment_strict_send" => match rep {
+                Intermediate {
+                    from: Some(from),
+                    to: Some(to),
+                    asset_code,
+                    asset_issuer,
+                    asset_type: Some(asset_type),
+                    amount: Some(amount),
+                    destination_min: Some(destination_min),
+                    source_asset_code,
+                    source_asset_issuer,
+                    source_asset_type: Some(source_asset_type),
+                    source_amount: Some(source_amount),
+                    to_muxed_id,
+                    ..
+                } => {
+                    let destination_asset_identifier =
+                        owned_asset(&asset_type, asset_code, asset_issuer)
+                            .map_err(de::Error::custom)?;
+                    let source_asset_identifier = owned_asset(
+                        &source_asset_type,
+                        source_asset_code,
+                        source_asset_issuer,
+                    ).map_err(de::Error::custom)?;
+                    let op = PathPaymentStrictSend::new(
+                        from.into_owned(),
+                        to.into_owned(),
+                        destination_asset_identifier,
+                        amount,
+                        destination_min,
+                        source_asset_identifier,
+                        source_amount,
+                    );
+                    Kind::PathPaymentStrictSend(match to_muxed_id {
+                        Some(id) => op.with_to_muxed_id(id),
+                        None => op,
+                    })
+                }
+                _ => {
+                    return Err(de::Error::custom(
+                        "Missing fields for path_payment_strict_send operation.",
+                    ))
+                }
+            },
             "payment" => match rep {
                 Intermediate {
                     from: Some(from),
@@ -348,12 +948,17 @@ impl<'de> Deserialize<'de> for Operation {
                     asset_issuer,
                     asset_type: Some(asset_type),
                     amount: Some(amount),
+                    to_muxed_id,
                     ..
                 } => {
                     let asset_identifier =
-                        AssetIdentifier::new(&asset_type, asset_code, asset_issuer)
+                        owned_asset(&asset_type, asset_code, asset_issuer)
                             .map_err(de::Error::custom)?;
-                    Kind::Payment(Payment::new(from, to, asset_identifier, amount))
+                    let op = Payment::new(from.into_owned(), to.into_owned(), asset_identifier, amount);
+                    Kind::Payment(match to_muxed_id {
+                        Some(id) => op.with_to_muxed_id(id),
+                        None => op,
+                    })
                 }
                 _ => return Err(de::Error::custom("Missing fields for payment operation.")),
             },
@@ -371,13 +976,13 @@ impl<'de> Deserialize<'de> for Operation {
                     price: Some(price),
                     ..
                 } => {
-                    let buying_asset_identifier = AssetIdentifier::new(
+                    let buying_asset_identifier = owned_asset(
                         &buying_asset_type,
                         buying_asset_code,
                         buying_asset_issuer,
                     ).map_err(de::Error::custom)?;
 
-                    let selling_asset_identifier = AssetIdentifier::new(
+                    let selling_asset_identifier = owned_asset(
                         &selling_asset_type,
                         selling_asset_code,
                         selling_asset_issuer,
@@ -411,13 +1016,13 @@ impl<'de> Deserialize<'de> for Operation {
                     price: Some(price),
                     ..
                 } => {
-                    let buying_asset_identifier = AssetIdentifier::new(
+                    let buying_asset_identifier = owned_asset(
                         &buying_asset_type,
                         buying_asset_code,
                         buying_asset_issuer,
                     ).map_err(de::Error::custom)?;
 
-                    let selling_asset_identifier = AssetIdentifier::new(
+                    let selling_asset_identifier = owned_asset(
                         &selling_asset_type,
                         selling_asset_code,
                         selling_asset_issuer,
@@ -471,11 +1076,11 @@ impl<'de> Deserialize<'de> for Operation {
                         None => None,
                     };
                     Kind::SetOptions(SetOptions::new(
-                        signer_key,
+                        signer_key.into_owned(),
                         signer_weight,
                         master_key_weight,
                         (low, med, high),
-                        home_domain,
+                        home_domain.into_owned(),
                         set_flags,
                         clear_flags,
                     ))
@@ -496,9 +1101,9 @@ impl<'de> Deserialize<'de> for Operation {
                     trustee: Some(trustee),
                     ..
                 } => {
-                    let asset = AssetIdentifier::new(&asset_type, asset_code, asset_issuer)
+                    let asset = owned_asset(&asset_type, asset_code, asset_issuer)
                         .map_err(de::Error::custom)?;
-                    Kind::ChangeTrust(ChangeTrust::new(trustee, trustor, asset, limit))
+                    Kind::ChangeTrust(ChangeTrust::new(trustee.into_owned(), trustor.into_owned(), asset, limit))
                 }
                 _ => {
                     return Err(de::Error::custom(
@@ -516,9 +1121,9 @@ impl<'de> Deserialize<'de> for Operation {
                     trustee: Some(trustee),
                     ..
                 } => {
-                    let asset = AssetIdentifier::new(&asset_type, asset_code, asset_issuer)
+                    let asset = owned_asset(&asset_type, asset_code, asset_issuer)
                         .map_err(de::Error::custom)?;
-                    Kind::AllowTrust(AllowTrust::new(trustee, trustor, asset, authorize))
+                    Kind::AllowTrust(AllowTrust::new(trustee.into_owned(), trustor.into_owned(), asset, authorize))
                 }
                 _ => {
                     return Err(de::Error::custom(
@@ -531,7 +1136,7 @@ impl<'de> Deserialize<'de> for Operation {
                     account: Some(account),
                     into: Some(into),
                     ..
-                } => Kind::AccountMerge(AccountMerge::new(account, into)),
+                } => Kind::AccountMerge(AccountMerge::new(account.into_owned(), into.into_owned())),
                 _ => {
                     return Err(de::Error::custom(
                         "Missing fields for account_merge operation.",
@@ -543,7 +1148,7 @@ impl<'de> Deserialize<'de> for Operation {
                     name: Some(name),
                     value: Some(value),
                     ..
-                } => Kind::ManageData(ManageData::new(name, value)),
+                } => Kind::ManageData(ManageData::new(name.into_owned(), value.into_owned())),
                 _ => {
                     return Err(de::Error::custom(
                         "Missing fields for manage_data operation.",
@@ -551,13 +1156,375 @@ impl<'de> Deserialize<'de> for Operation {
                 }
             },
             "inflation" => Kind::Inflation,
-            _ => return Err(de::Error::custom("Unknown operation type.")),
+            "bump_sequence" => match rep {
+                Intermediate {
+                    bump_to: Some(bump_to),
+                    ..
+                } => Kind::BumpSequence(BumpSequence::new(bump_to)),
+                _ => {
+                    return Err(de::Error::custom(
+                        "Missing fields for bump_sequence operation.",
+                    ))
+                }
+            },
+            "liquidity_pool_deposit" => match rep {
+                Intermediate {
+                    liquidity_pool_id: Some(liquidity_pool_id),
+                    reserves_max: Some(reserves_max),
+                    min_price: Some(min_price),
+                    min_price_r: Some(min_price_r),
+                    max_price: Some(max_price),
+                    max_price_r: Some(max_price_r),
+                    reserves_deposited: Some(reserves_deposited),
+                    shares_received: Some(shares_received),
+                    ..
+                } => Kind::LiquidityPoolDeposit(LiquidityPoolDeposit::new(
+                    liquidity_pool_id.into_owned(),
+                    reserves_max,
+                    (min_price, min_price_r),
+                    (max_price, max_price_r),
+                    reserves_deposited,
+                    shares_received,
+                )),
+                _ => {
+                    return Err(de::Error::custom(
+                        "Missing fields for liquidity_pool_deposit operation.",
+                    ))
+                }
+            },
+            "liquidity_pool_withdraw" => match rep {
+                Intermediate {
+                    liquidity_pool_id: Some(liquidity_pool_id),
+                    reserves_min: Some(reserves_min),
+                    shares: Some(shares),
+                    reserves_received: Some(reserves_received),
+                    ..
+                } => Kind::LiquidityPoolWithdraw(LiquidityPoolWithdraw::new(
+                    liquidity_pool_id.into_owned(),
+                    reserves_min,
+                    shares,
+                    reserves_received,
+                )),
+                _ => {
+                    return Err(de::Error::custom(
+                        "Missing fields for liquidity_pool_withdraw operation.",
+                    ))
+                }
+            },
+            "clawback" => match rep {
+                Intermediate {
+                    from: Some(from),
+                    asset_code,
+                    asset_issuer,
+                    asset_type: Some(asset_type),
+                    amount: Some(amount),
+                    ..
+                } => {
+                    let asset = owned_asset(&asset_type, asset_code, asset_issuer)
+                        .map_err(de::Error::custom)?;
+                    Kind::Clawback(Clawback::new(from.into_owned(), asset, amount))
+                }
+                _ => return Err(de::Error::custom("Missing fields for clawback operation.")),
+            },
+            "clawback_claimable_balance" => match rep {
+                Intermediate {
+                    balance_id: Some(balance_id),
+                    ..
+                } => Kind::ClawbackClaimableBalance(ClawbackClaimableBalance::new(balance_id.into_owned())),
+                _ => {
+                    return Err(de::Error::custom(
+                        "Missing fields for clawback_claimable_balance operation.",
+                    ))
+                }
+            },
+            "set_trust_line_flags" => match rep {
+                Intermediate {
+                    trustor: Some(trustor),
+                    asset_code,
+                    asset_issuer,
+                    asset_type: Some(asset_type),
+                    set_flags_s,
+                    clear_flags_s,
+                    ..
+                } => {
+                    let asset = owned_asset(&asset_type, asset_code, asset_issuer)
+                        .map_err(de::Error::custom)?;
+                    Kind::SetTrustLineFlags(SetTrustLineFlags::new(
+                        trustor.into_owned(),
+                        asset,
+                        set_flags_s.map(|names| TrustLineFlags::from_names(&names)),
+                        clear_flags_s.map(|names| TrustLineFlags::from_names(&names)),
+                    ))
+                }
+                _ => {
+                    return Err(de::Error::custom(
+                        "Missing fields for set_trust_line_flags operation.",
+                    ))
+                }
+            },
+            type_name => match raw {
+                Some(raw) => Kind::Unknown {
+                    type_name: type_name.to_string(),
+                    raw: raw.clone(),
+                },
+                None => return Err(de::Error::custom("Unknown operation type.")),
+            },
         };
         Ok(Operation {
             id: rep.id,
-            paging_token: rep.paging_token,
-            transaction_hash: rep.transaction_hash,
+            paging_token: rep.paging_token.into_owned(),
+            transaction_hash: rep.transaction_hash.into_owned(),
+            source_account: rep.source_account.map(Cow::into_owned),
+            created_at: rep.created_at,
             kind,
+            links: rep.links,
+            transaction: rep.transaction.map(|transaction| Box::new(transaction.into_owned())),
         })
     }
 }
+
+impl Serialize for Operation {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut rep = Intermediate {
+            id: self.id,
+            paging_token: Cow::Borrowed(&self.paging_token),
+            transaction_hash: Cow::Borrowed(&self.transaction_hash),
+            source_account: self.source_account.as_ref().map(|s| Cow::Borrowed(s.as_str())),
+            created_at: self.created_at,
+            type_i: Some(self.type_i()),
+            links: self.links.clone(),
+            transaction: self.transaction.as_ref().map(|transaction| Cow::Borrowed(&**transaction)),
+            ..Intermediate::default()
+        };
+        rep.kind = match self.kind {
+            Kind::CreateAccount(ref op) => {
+                rep.account = Some(op.account().into());
+                rep.funder = Some(op.funder().into());
+                rep.starting_balance = Some(op.starting_balance());
+                "create_account"
+            }
+            Kind::Payment(ref op) => {
+                rep.from = Some(op.from().into());
+                rep.to = Some(op.to().into());
+                rep.to_muxed_id = op.to_muxed_id();
+                set_asset(op.asset(), &mut rep.asset_type, &mut rep.asset_code, &mut rep.asset_issuer);
+                rep.amount = Some(op.amount());
+                "payment"
+            }
+            Kind::PathPayment(ref op) => {
+                rep.from = Some(op.from().into());
+                rep.to = Some(op.to().into());
+                rep.to_muxed_id = op.to_muxed_id();
+                set_asset(
+                    op.destination_asset(),
+                    &mut rep.asset_type,
+                    &mut rep.asset_code,
+                    &mut rep.asset_issuer,
+                );
+                rep.amount = Some(op.destination_amount());
+                set_asset(
+                    op.source_asset(),
+                    &mut rep.source_asset_type,
+                    &mut rep.source_asset_code,
+                    &mut rep.source_asset_issuer,
+                );
+                rep.source_max = Some(op.source_max());
+                "path_payment"
+            }
+            Kind::PathPaymentStrictSend(ref op) => {
+                rep.from = Some(op.from().into());
+                rep.to = Some(op.to().into());
+                rep.to_muxed_id = op.to_muxed_id();
+                set_asset(
+                    op.destination_asset(),
+                    &mut rep.asset_type,
+                    &mut rep.asset_code,
+                    &mut rep.asset_issuer,
+                );
+                rep.amount = Some(op.destination_amount());
+                rep.destination_min = Some(op.destination_min());
+                set_asset(
+                    op.source_asset(),
+                    &mut rep.source_asset_type,
+                    &mut rep.source_asset_code,
+                    &mut rep.source_asset_issuer,
+                );
+                rep.source_amount = Some(op.source_amount());
+                "path_payment_strict_send"
+            }
+            Kind::ManageOffer(ref op) => {
+                rep.offer_id = Some(op.offer_id());
+                set_offer_assets(op.selling(), op.buying(), &mut rep);
+                rep.amount = Some(op.amount());
+                rep.price_ratio = Some(*op.price_ratio());
+                rep.price = Some(op.price());
+                "manage_offer"
+            }
+            Kind::CreatePassiveOffer(ref op) => {
+                rep.offer_id = Some(op.offer_id());
+                set_offer_assets(op.selling(), op.buying(), &mut rep);
+                rep.amount = Some(op.amount());
+                rep.price_ratio = Some(*op.price_ratio());
+                rep.price = Some(op.price());
+                "create_passive_offer"
+            }
+            Kind::SetOptions(ref op) => {
+                rep.signer_key = Some(op.signer_key().into());
+                rep.signer_weight = Some(op.signer_weight());
+                rep.master_key_weight = Some(op.master_key_weight());
+                rep.low_threshold = Some(op.low_threshold());
+                rep.med_threshold = Some(op.med_threshold());
+                rep.high_threshold = Some(op.high_threshold());
+                rep.home_domain = Some(op.home_domain().into());
+                if let Some(flags) = op.set_flags() {
+                    let values = account_flag_values(flags);
+                    rep.set_flags = Some(values.iter().map(|&(value, _)| value).collect());
+                    rep.set_flags_s = Some(values.iter().map(|&(_, name)| name.to_string()).collect());
+                }
+                if let Some(flags) = op.clear_flags() {
+                    let values = account_flag_values(flags);
+                    rep.clear_flags = Some(values.iter().map(|&(value, _)| value).collect());
+                    rep.clear_flags_s = Some(values.iter().map(|&(_, name)| name.to_string()).collect());
+                }
+                "set_options"
+            }
+            Kind::ChangeTrust(ref op) => {
+                rep.trustee = Some(op.trustee().into());
+                rep.trustor = Some(op.trustor().into());
+                set_asset(op.asset(), &mut rep.asset_type, &mut rep.asset_code, &mut rep.asset_issuer);
+                rep.limit = Some(op.limit());
+                "change_trust"
+            }
+            Kind::AllowTrust(ref op) => {
+                rep.trustee = Some(op.trustee().into());
+                rep.trustor = Some(op.trustor().into());
+                set_asset(op.asset(), &mut rep.asset_type, &mut rep.asset_code, &mut rep.asset_issuer);
+                rep.authorize = Some(op.authorize());
+                "allow_trust"
+            }
+            Kind::AccountMerge(ref op) => {
+                rep.account = Some(op.account().into());
+                rep.into = Some(op.into().into());
+                "account_merge"
+            }
+            Kind::Inflation => "inflation",
+            Kind::ManageData(ref op) => {
+                rep.name = Some(op.name().into());
+                rep.value = Some(op.value().into());
+                "manage_data"
+            }
+            Kind::BumpSequence(ref op) => {
+                rep.bump_to = Some(op.bump_to());
+                "bump_sequence"
+            }
+            Kind::Clawback(ref op) => {
+                rep.from = Some(op.from().into());
+                set_asset(op.asset(), &mut rep.asset_type, &mut rep.asset_code, &mut rep.asset_issuer);
+                rep.amount = Some(op.amount());
+                "clawback"
+            }
+            Kind::ClawbackClaimableBalance(ref op) => {
+                rep.balance_id = Some(op.balance_id().into());
+                "clawback_claimable_balance"
+            }
+            Kind::SetTrustLineFlags(ref op) => {
+                rep.trustor = Some(op.trustor().into());
+                set_asset(op.asset(), &mut rep.asset_type, &mut rep.asset_code, &mut rep.asset_issuer);
+                if let Some(flags) = op.set_flags() {
+                    let values = flags.to_values();
+                    rep.set_flags = Some(values.iter().map(|&(value, _)| value).collect());
+                    rep.set_flags_s = Some(values.iter().map(|&(_, name)| name.to_string()).collect());
+                }
+                if let Some(flags) = op.clear_flags() {
+                    let values = flags.to_values();
+                    rep.clear_flags = Some(values.iter().map(|&(value, _)| value).collect());
+                    rep.clear_flags_s = Some(values.iter().map(|&(_, name)| name.to_string()).collect());
+                }
+                "set_trust_line_flags"
+            }
+            Kind::LiquidityPoolDeposit(ref op) => {
+                rep.liquidity_pool_id = Some(op.liquidity_pool_id().into());
+                rep.reserves_max = Some(op.reserves_max().to_vec());
+                rep.min_price = Some(op.min_price());
+                rep.min_price_r = Some(op.min_price_ratio());
+                rep.max_price = Some(op.max_price());
+                rep.max_price_r = Some(op.max_price_ratio());
+                rep.reserves_deposited = Some(op.reserves_deposited().to_vec());
+                rep.shares_received = Some(op.shares_received());
+                "liquidity_pool_deposit"
+            }
+            Kind::LiquidityPoolWithdraw(ref op) => {
+                rep.liquidity_pool_id = Some(op.liquidity_pool_id().into());
+                rep.reserves_min = Some(op.reserves_min().to_vec());
+                rep.shares = Some(op.shares());
+                rep.reserves_received = Some(op.reserves_received().to_vec());
+                "liquidity_pool_withdraw"
+            }
+            // Unknown operations are passed through exactly as horizon sent them
+            Kind::Unknown { ref raw, .. } => return raw.serialize(s),
+        }.into();
+        rep.serialize(s)
+    }
+}
+
+fn set_asset<'a>(
+    asset: &'a AssetIdentifier,
+    asset_type: &mut Option<Cow<'a, str>>,
+    asset_code: &mut Option<Cow<'a, str>>,
+    asset_issuer: &mut Option<Cow<'a, str>>,
+) {
+    *asset_type = Some(asset.asset_type().into());
+    if !asset.is_native() {
+        *asset_code = Some(asset.code().into());
+        *asset_issuer = Some(asset.issuer().into());
+    }
+}
+
+/// Builds an asset from the fields of an operation, allocating the strings
+/// it borrowed.
+fn owned_asset(
+    asset_type: &str,
+    asset_code: Option<Cow<str>>,
+    asset_issuer: Option<Cow<str>>,
+) -> Result<AssetIdentifier, String> {
+    AssetIdentifier::new(
+        asset_type,
+        asset_code.map(Cow::into_owned),
+        asset_issuer.map(Cow::into_owned),
+    )
+}
+
+fn set_offer_assets<'a>(
+    selling: &'a AssetIdentifier,
+    buying: &'a AssetIdentifier,
+    rep: &mut Intermediate<'a>,
+) {
+    set_asset(
+        selling,
+        &mut rep.selling_asset_type,
+        &mut rep.selling_asset_code,
+        &mut rep.selling_asset_issuer,
+    );
+    set_asset(
+        buying,
+        &mut rep.buying_asset_type,
+        &mut rep.buying_asset_code,
+        &mut rep.buying_asset_issuer,
+    );
+}
+
+/// The account flags as horizon lists them, each as a pair of its value and
+/// name.
+fn account_flag_values(flags: Flags) -> Vec<(u32, &'static str)> {
+    let mut values = Vec::new();
+    if flags.is_auth_required() {
+        values.push((1, "auth_required_flag"));
+    }
+    if flags.is_auth_revocable() {
+        values.push((2, "auth_revocable_flag"));
+    }
+    values
+}
@@ -3,7 +3,7 @@ use resources::{Amount, AssetIdentifier};
 /// A path payment operation represents a payment from one account to another through a path. This
 /// type of payment starts as one type of asset and ends as another type of asset. There can be
 /// other assets that are traded into and out of along the path.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PathPayment {
     from: String,
     to: String,
@@ -11,6 +11,7 @@ pub struct PathPayment {
     destination_amount: Amount,
     source_asset: AssetIdentifier,
     source_max: Amount,
+    to_muxed_id: Option<u64>,
 }
 
 impl PathPayment {
@@ -30,8 +31,16 @@ impl PathPayment {
             destination_amount,
             source_asset,
             source_max,
+            to_muxed_id: None,
         }
     }
+
+    /// Sets the id of the multiplexed account the payment was sent to.
+    pub fn with_to_muxed_id(mut self, id: u64) -> PathPayment {
+        self.to_muxed_id = Some(id);
+        self
+    }
+
     /// Sender of a payment.
     pub fn from(&self) -> &str {
         &self.from
@@ -42,6 +51,12 @@ impl PathPayment {
         &self.to
     }
 
+    /// The id of the multiplexed account at the destination, when the
+    /// payment was sent to an `M...` address.
+    pub fn to_muxed_id(&self) -> Option<u64> {
+        self.to_muxed_id
+    }
+
     /// Asset at the destination of payment path.
     pub fn destination_asset(&self) -> &AssetIdentifier {
         &self.destination_asset
@@ -0,0 +1,87 @@
+use resources::{Amount, AssetIdentifier};
+
+/// A path payment that sends an exact amount of the source asset, converting
+/// it along the path into at least a minimum amount of the destination
+/// asset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathPaymentStrictSend {
+    from: String,
+    to: String,
+    destination_asset: AssetIdentifier,
+    destination_amount: Amount,
+    destination_min: Amount,
+    source_asset: AssetIdentifier,
+    source_amount: Amount,
+    to_muxed_id: Option<u64>,
+}
+
+impl PathPaymentStrictSend {
+    /// Creates a new PathPaymentStrictSend
+    pub fn new(
+        from: String,
+        to: String,
+        destination_asset: AssetIdentifier,
+        destination_amount: Amount,
+        destination_min: Amount,
+        source_asset: AssetIdentifier,
+        source_amount: Amount,
+    ) -> PathPaymentStrictSend {
+        PathPaymentStrictSend {
+            from,
+            to,
+            destination_asset,
+            destination_amount,
+            destination_min,
+            source_asset,
+            source_amount,
+            to_muxed_id: None,
+        }
+    }
+
+    /// Sets the id of the multiplexed account the payment was sent to.
+    pub fn with_to_muxed_id(mut self, id: u64) -> PathPaymentStrictSend {
+        self.to_muxed_id = Some(id);
+        self
+    }
+
+    /// Sender of a payment.
+    pub fn from(&self) -> &str {
+        &self.from
+    }
+
+    /// Destination of a payment.
+    pub fn to(&self) -> &str {
+        &self.to
+    }
+
+    /// The id of the multiplexed account at the destination, when the
+    /// payment was sent to an `M...` address.
+    pub fn to_muxed_id(&self) -> Option<u64> {
+        self.to_muxed_id
+    }
+
+    /// Asset at the destination of payment path.
+    pub fn destination_asset(&self) -> &AssetIdentifier {
+        &self.destination_asset
+    }
+
+    /// Amount received.
+    pub fn destination_amount(&self) -> Amount {
+        self.destination_amount
+    }
+
+    /// The least the sender would accept being received.
+    pub fn destination_min(&self) -> Amount {
+        self.destination_min
+    }
+
+    /// Asset at the source of payment path.
+    pub fn source_asset(&self) -> &AssetIdentifier {
+        &self.source_asset
+    }
+
+    /// Amount sent.
+    pub fn source_amount(&self) -> Amount {
+        self.source_amount
+    }
+}
@@ -2,12 +2,13 @@ use resources::{Amount, AssetIdentifier};
 
 /// A payment operation represents a payment from one account to another. This payment can be
 /// either a simple native asset payment or a fiat asset payment.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Payment {
     from: String,
     to: String,
     asset: AssetIdentifier,
     amount: Amount,
+    to_muxed_id: Option<u64>,
 }
 
 impl Payment {
@@ -18,9 +19,16 @@ impl Payment {
             to,
             asset,
             amount,
+            to_muxed_id: None,
         }
     }
 
+    /// Sets the id of the multiplexed account the payment was sent to.
+    pub fn with_to_muxed_id(mut self, id: u64) -> Payment {
+        self.to_muxed_id = Some(id);
+        self
+    }
+
     /// The public address of the account making a payment.
     pub fn from(&self) -> &str {
         &self.from
@@ -31,6 +39,13 @@ impl Payment {
         &self.to
     }
 
+    /// The id of the multiplexed account receiving the payment, when it was
+    /// sent to an `M...` address. Exchanges use it to tell apart deposits
+    /// to a shared account.
+    pub fn to_muxed_id(&self) -> Option<u64> {
+        self.to_muxed_id
+    }
+
     /// The asset being sent in the payment
     pub fn asset(&self) -> &AssetIdentifier {
         &self.asset
@@ -0,0 +1,56 @@
+use resources::{Amount, AssetIdentifier};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// An amount of one of the assets in a liquidity pool, as deposited into or
+/// withdrawn from the pool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reserve {
+    asset: AssetIdentifier,
+    amount: Amount,
+}
+
+impl Reserve {
+    /// Creates a new Reserve
+    pub fn new(asset: AssetIdentifier, amount: Amount) -> Reserve {
+        Reserve { asset, amount }
+    }
+
+    /// The asset of the reserve.
+    pub fn asset(&self) -> &AssetIdentifier {
+        &self.asset
+    }
+
+    /// The amount of the asset.
+    pub fn amount(&self) -> Amount {
+        self.amount
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Intermediate {
+    asset: String,
+    amount: Amount,
+}
+
+impl<'de> Deserialize<'de> for Reserve {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let rep = Intermediate::deserialize(d)?;
+        let asset = AssetIdentifier::from_canonical(&rep.asset).map_err(de::Error::custom)?;
+        Ok(Reserve::new(asset, rep.amount))
+    }
+}
+
+impl Serialize for Reserve {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Intermediate {
+            asset: self.asset.to_canonical(),
+            amount: self.amount,
+        }.serialize(s)
+    }
+}
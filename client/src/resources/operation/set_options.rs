@@ -10,7 +10,7 @@ use resources::asset::Flags;
 /// Set the account’s inflation destination.
 /// Add new signers to the account.
 /// Set home domain.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SetOptions {
     signer_key: String,
     signer_weight: u8,
@@ -21,7 +21,7 @@ pub struct SetOptions {
     clear_flags: Option<Flags>,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Thresholds {
     low: u32,
     med: u32,
@@ -0,0 +1,114 @@
+use resources::AssetIdentifier;
+
+/// Updates the authorization and clawback flags of a trust line. This is
+/// called by the issuer of the asset and replaces allow trust.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetTrustLineFlags {
+    trustor: String,
+    asset: AssetIdentifier,
+    set_flags: Option<TrustLineFlags>,
+    clear_flags: Option<TrustLineFlags>,
+}
+
+impl SetTrustLineFlags {
+    /// Creates a new SetTrustLineFlags
+    pub fn new(
+        trustor: String,
+        asset: AssetIdentifier,
+        set_flags: Option<TrustLineFlags>,
+        clear_flags: Option<TrustLineFlags>,
+    ) -> SetTrustLineFlags {
+        SetTrustLineFlags {
+            trustor,
+            asset,
+            set_flags,
+            clear_flags,
+        }
+    }
+
+    /// The account holding the trust line.
+    pub fn trustor(&self) -> &str {
+        &self.trustor
+    }
+
+    /// The asset of the trust line.
+    pub fn asset(&self) -> &AssetIdentifier {
+        &self.asset
+    }
+
+    /// The flags that were set on the trust line.
+    pub fn set_flags(&self) -> Option<TrustLineFlags> {
+        self.set_flags
+    }
+
+    /// The flags that were cleared from the trust line.
+    pub fn clear_flags(&self) -> Option<TrustLineFlags> {
+        self.clear_flags
+    }
+}
+
+/// The flags an issuer can set or clear on a trust line.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TrustLineFlags {
+    authorized: bool,
+    authorized_to_maintain_liabilities: bool,
+    clawback_enabled: bool,
+}
+
+impl TrustLineFlags {
+    /// Creates a new set of trust line flags
+    pub fn new(
+        authorized: bool,
+        authorized_to_maintain_liabilities: bool,
+        clawback_enabled: bool,
+    ) -> TrustLineFlags {
+        TrustLineFlags {
+            authorized,
+            authorized_to_maintain_liabilities,
+            clawback_enabled,
+        }
+    }
+
+    /// Builds the flags from the names horizon lists them by, such as
+    /// `authorized` and `clawback_enabled`.
+    pub(crate) fn from_names(names: &[String]) -> TrustLineFlags {
+        TrustLineFlags::new(
+            names.iter().any(|e| e == "authorized"),
+            names
+                .iter()
+                .any(|e| e == "authorized_to_maintain_liabilities"),
+            names.iter().any(|e| e == "clawback_enabled"),
+        )
+    }
+
+    /// The flags as horizon lists them, each as a pair of its value and name.
+    pub(crate) fn to_values(&self) -> Vec<(u32, &'static str)> {
+        let mut values = Vec::new();
+        if self.authorized {
+            values.push((1, "authorized"));
+        }
+        if self.authorized_to_maintain_liabilities {
+            values.push((2, "authorized_to_maintain_liabilities"));
+        }
+        if self.clawback_enabled {
+            values.push((4, "clawback_enabled"));
+        }
+        values
+    }
+
+    /// The trust line is fully authorized to hold and transact the asset.
+    pub fn is_authorized(&self) -> bool {
+        self.authorized
+    }
+
+    /// The trust line can maintain its existing offers and balances but
+    /// can't receive the asset.
+    pub fn is_authorized_to_maintain_liabilities(&self) -> bool {
+        self.authorized_to_maintain_liabilities
+    }
+
+    /// The issuer can claw back the asset from the trust line.
+    pub fn is_clawback_enabled(&self) -> bool {
+        self.clawback_enabled
+    }
+}
@@ -1,4 +1,6 @@
-use resources::{Amount, Operation, OperationKind, asset::Flags};
+use resources::{Amount, AssetIdentifier, Memo, Operation, OperationCommon, OperationId, OperationKind,
+                OperationRef, PriceRatio, asset::Flags};
+use super::TrustLineFlags;
 use serde_json;
 
 fn account_merge_json() -> &'static str {
@@ -49,6 +51,49 @@ mod errors_on_missing_fields_for_type {
     assert_err_on_missing_fields!(account_merge, 8);
     // Inflation (id 9) is infallible as it has no fields.
     assert_err_on_missing_fields!(manage_data, 10);
+    assert_err_on_missing_fields!(clawback, 19);
+    assert_err_on_missing_fields!(clawback_claimable_balance, 20);
+    assert_err_on_missing_fields!(set_trust_line_flags, 21);
+    assert_err_on_missing_fields!(liquidity_pool_deposit, 22);
+    assert_err_on_missing_fields!(liquidity_pool_withdraw, 23);
+}
+
+mod serializes_like_horizon {
+    use super::*;
+    use resources::serialize::assert_round_trips;
+
+    macro_rules! assert_round_trips_fixture {
+        ($name:ident) => {
+            #[test]
+            fn $name() {
+                assert_round_trips::<Operation>(include_str!(concat!(
+                    "../../../fixtures/operations/",
+                    stringify!($name),
+                    ".json"
+                )));
+            }
+        };
+    }
+
+    assert_round_trips_fixture!(account_merge);
+    assert_round_trips_fixture!(allow_trust);
+    assert_round_trips_fixture!(bump_sequence);
+    assert_round_trips_fixture!(change_trust);
+    assert_round_trips_fixture!(clawback);
+    assert_round_trips_fixture!(clawback_claimable_balance);
+    assert_round_trips_fixture!(create_account);
+    assert_round_trips_fixture!(create_passive_offer);
+    assert_round_trips_fixture!(inflation);
+    assert_round_trips_fixture!(liquidity_pool_deposit);
+    assert_round_trips_fixture!(liquidity_pool_withdraw);
+    assert_round_trips_fixture!(manage_data);
+    assert_round_trips_fixture!(manage_offer);
+    assert_round_trips_fixture!(path_payment);
+    assert_round_trips_fixture!(path_payment_strict_send);
+    assert_round_trips_fixture!(payment);
+    assert_round_trips_fixture!(payment_joined);
+    assert_round_trips_fixture!(set_options);
+    assert_round_trips_fixture!(set_trust_line_flags);
 }
 
 #[test]
@@ -56,6 +101,7 @@ fn it_parses_account_merge_from_json() {
     let operation: Operation = serde_json::from_str(&account_merge_json()).unwrap();
     assert!(operation.is_account_merge());
     assert_eq!(operation.type_i(), 8);
+    assert_eq!(operation.operation_id(), OperationId::new(186_115, 1, 1));
     if let &OperationKind::AccountMerge(ref account_details) = operation.kind() {
         assert_eq!(
             account_details.account(),
@@ -179,6 +225,22 @@ fn it_parses_inflation_from_json() {
     assert_eq!(operation.type_i(), 9);
 }
 
+fn bump_sequence_json() -> &'static str {
+    include_str!("../../../fixtures/operations/bump_sequence.json")
+}
+
+#[test]
+fn it_parses_bump_sequence_from_json() {
+    let operation: Operation = serde_json::from_str(&bump_sequence_json()).unwrap();
+    assert!(operation.is_bump_sequence());
+    assert_eq!(operation.type_i(), 11);
+    if let &OperationKind::BumpSequence(ref account_details) = operation.kind() {
+        assert_eq!(account_details.bump_to(), 86_524_787_647_590_400);
+    } else {
+        panic!("Did not generate bump sequence kind");
+    }
+}
+
 fn manage_data_json() -> &'static str {
     include_str!("../../../fixtures/operations/manage_data.json")
 }
@@ -247,6 +309,39 @@ fn it_parses_a_path_payment_from_json() {
     }
 }
 
+#[test]
+fn it_parses_a_strict_receive_path_payment_as_a_path_payment() {
+    let json = path_payment_json().replace(
+        r#""type": "path_payment""#,
+        r#""type": "path_payment_strict_receive""#,
+    );
+    let operation: Operation = serde_json::from_str(&json).unwrap();
+    assert!(operation.is_path_payment());
+    assert_eq!(operation.type_i(), 2);
+}
+
+#[test]
+fn it_parses_a_strict_send_path_payment_from_json() {
+    let operation: Operation = serde_json::from_str(include_str!(
+        "../../../fixtures/operations/path_payment_strict_send.json"
+    )).unwrap();
+    assert!(operation.is_path_payment_strict_send());
+    assert_eq!(operation.type_i(), 13);
+    if let &OperationKind::PathPaymentStrictSend(ref details) = operation.kind() {
+        assert_eq!(
+            details.to(),
+            "GA5WBPYA5Y4WAEHXWR2UKO2UO4BUGHUQ74EUPKON2QHV4WRHOIRNKKH2"
+        );
+        assert_eq!(details.destination_asset().code(), "EUR");
+        assert_eq!(details.destination_amount(), Amount::new(102_000_000));
+        assert_eq!(details.destination_min(), Amount::new(100_000_000));
+        assert_eq!(details.source_asset().code(), "USD");
+        assert_eq!(details.source_amount(), Amount::new(100_000_000));
+    } else {
+        panic!("Did not generate path payment strict send kind");
+    }
+}
+
 fn payment_json() -> &'static str {
     include_str!("../../../fixtures/operations/payment.json")
 }
@@ -267,9 +362,47 @@ fn it_parses_a_payment_from_json() {
         );
         assert_eq!(account_details.asset().code(), "XLM");
         assert_eq!(account_details.amount(), Amount::new(2_000_000_000));
+        assert_eq!(account_details.to_muxed_id(), None);
+    } else {
+        panic!("Did not generate payment kind");
+    }
+}
+
+#[test]
+fn it_parses_the_transaction_joined_to_a_payment() {
+    let json = include_str!("../../../fixtures/operations/payment_joined.json");
+    let operation: Operation = serde_json::from_str(json).unwrap();
+    assert!(operation.is_payment());
+    let transaction = operation.embedded_transaction().unwrap();
+    assert_eq!(transaction.hash(), operation.transaction());
+    assert_eq!(transaction.memo(), &Memo::Text("hello".to_string()));
+    assert_eq!(
+        transaction.source_account(),
+        "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3"
+    );
+
+    let borrowed: OperationRef = serde_json::from_str(json).unwrap();
+    assert_eq!(borrowed.embedded_transaction(), Some(transaction));
+    assert_eq!(borrowed.into_owned().unwrap(), operation);
+
+    let unjoined: Operation = serde_json::from_str(&payment_json()).unwrap();
+    assert_eq!(unjoined.embedded_transaction(), None);
+}
+
+#[test]
+fn it_parses_a_payment_to_a_muxed_account() {
+    let json = payment_json().replace(
+        r#""type_i": 1,"#,
+        r#""type_i": 1, "to_muxed_id": "420","#,
+    );
+    let operation: Operation = serde_json::from_str(&json).unwrap();
+    if let &OperationKind::Payment(ref payment) = operation.kind() {
+        assert_eq!(payment.to_muxed_id(), Some(420));
     } else {
         panic!("Did not generate payment kind");
     }
+    let value = serde_json::to_value(&operation).unwrap();
+    assert_eq!(value["to_muxed_id"], "420");
 }
 
 fn set_options_json() -> &'static str {
@@ -301,3 +434,232 @@ fn it_parses_a_set_options_from_json() {
         panic!("Did not generate set options kind");
     }
 }
+
+fn clawback_json() -> &'static str {
+    include_str!("../../../fixtures/operations/clawback.json")
+}
+
+#[test]
+fn it_parses_clawback_from_json() {
+    let operation: Operation = serde_json::from_str(&clawback_json()).unwrap();
+    assert!(operation.is_clawback());
+    assert_eq!(operation.type_i(), 19);
+    if let &OperationKind::Clawback(ref details) = operation.kind() {
+        assert_eq!(
+            details.from(),
+            "GBXGQJWVLWOYHFLVTKWV5FGHA3LNYY2JQKM7OAJAUEQFU6LPCSEFVXON"
+        );
+        assert_eq!(details.asset().code(), "USD");
+        assert_eq!(details.amount(), Amount::new(125_000_000));
+    } else {
+        panic!("Did not generate clawback kind");
+    }
+}
+
+fn clawback_claimable_balance_json() -> &'static str {
+    include_str!("../../../fixtures/operations/clawback_claimable_balance.json")
+}
+
+#[test]
+fn it_parses_clawback_claimable_balance_from_json() {
+    let operation: Operation = serde_json::from_str(&clawback_claimable_balance_json()).unwrap();
+    assert!(operation.is_clawback_claimable_balance());
+    assert_eq!(operation.type_i(), 20);
+    if let &OperationKind::ClawbackClaimableBalance(ref details) = operation.kind() {
+        assert_eq!(
+            details.balance_id(),
+            "00000000da0d57da7d4850e7fc10d2a9d0ebc731f7afb40574c03395b17d49149b91f5be"
+        );
+    } else {
+        panic!("Did not generate clawback claimable balance kind");
+    }
+}
+
+fn set_trust_line_flags_json() -> &'static str {
+    include_str!("../../../fixtures/operations/set_trust_line_flags.json")
+}
+
+#[test]
+fn it_parses_set_trust_line_flags_from_json() {
+    let operation: Operation = serde_json::from_str(&set_trust_line_flags_json()).unwrap();
+    assert!(operation.is_set_trust_line_flags());
+    assert_eq!(operation.type_i(), 21);
+    if let &OperationKind::SetTrustLineFlags(ref details) = operation.kind() {
+        assert_eq!(
+            details.trustor(),
+            "GBXGQJWVLWOYHFLVTKWV5FGHA3LNYY2JQKM7OAJAUEQFU6LPCSEFVXON"
+        );
+        assert_eq!(details.asset().code(), "USD");
+        assert_eq!(
+            details.set_flags(),
+            Some(TrustLineFlags::new(false, false, true))
+        );
+        assert_eq!(
+            details.clear_flags(),
+            Some(TrustLineFlags::new(true, false, false))
+        );
+    } else {
+        panic!("Did not generate set trust line flags kind");
+    }
+}
+
+fn liquidity_pool_deposit_json() -> &'static str {
+    include_str!("../../../fixtures/operations/liquidity_pool_deposit.json")
+}
+
+#[test]
+fn it_parses_liquidity_pool_deposit_from_json() {
+    let operation: Operation = serde_json::from_str(&liquidity_pool_deposit_json()).unwrap();
+    assert!(operation.is_liquidity_pool_deposit());
+    assert_eq!(operation.type_i(), 22);
+    if let &OperationKind::LiquidityPoolDeposit(ref details) = operation.kind() {
+        assert_eq!(
+            details.liquidity_pool_id(),
+            "abcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890"
+        );
+        assert_eq!(details.reserves_max()[0].asset(), &AssetIdentifier::native());
+        assert_eq!(details.reserves_max()[0].amount(), Amount::new(10_000_000_000));
+        assert_eq!(
+            details.reserves_deposited()[1].asset(),
+            &AssetIdentifier::alphanum4(
+                "USD",
+                "GC23QF2HUE52AMXUFUH3AYJAXXGXXV2VHXYYR6EYXETPKDXZSAW67XO4"
+            )
+        );
+        assert_eq!(details.min_price(), Amount::new(4_000_000));
+        assert_eq!(details.min_price_ratio(), PriceRatio::new(2, 5));
+        assert_eq!(details.max_price_ratio(), PriceRatio::new(3, 5));
+        assert_eq!(details.shares_received(), Amount::new(6_950_853_544));
+    } else {
+        panic!("Did not generate liquidity pool deposit kind");
+    }
+}
+
+fn liquidity_pool_withdraw_json() -> &'static str {
+    include_str!("../../../fixtures/operations/liquidity_pool_withdraw.json")
+}
+
+#[test]
+fn it_parses_liquidity_pool_withdraw_from_json() {
+    let operation: Operation = serde_json::from_str(&liquidity_pool_withdraw_json()).unwrap();
+    assert!(operation.is_liquidity_pool_withdraw());
+    assert_eq!(operation.type_i(), 23);
+    if let &OperationKind::LiquidityPoolWithdraw(ref details) = operation.kind() {
+        assert_eq!(details.shares(), Amount::new(2_000_000_000));
+        assert_eq!(details.reserves_min().len(), 2);
+        assert_eq!(
+            details.reserves_received()[0].amount(),
+            Amount::new(2_828_427_124)
+        );
+    } else {
+        panic!("Did not generate liquidity pool withdraw kind");
+    }
+}
+
+fn unknown_json() -> &'static str {
+    r#"{
+        "id": "12884905985",
+        "paging_token": "12884905985",
+        "transaction_hash": "3389e9f0f1a65f19736cacf544c2e825313e8447f569233bb8db39aa607c8889",
+        "type_i": 24,
+        "type": "invoke_host_function",
+        "function": "HostFunctionTypeHostFunctionTypeInvokeContract"
+    }"#
+}
+
+#[test]
+fn it_rejects_unknown_operation_types_by_default() {
+    assert!(serde_json::from_str::<Operation>(unknown_json()).is_err());
+}
+
+#[test]
+fn it_keeps_unknown_operation_types_when_allowed() {
+    use resources::deserialize::from_slice;
+
+    let operation: Operation = from_slice(unknown_json().as_bytes(), true).unwrap();
+    assert!(operation.is_unknown());
+    assert_eq!(operation.type_i(), 24);
+    assert_eq!(operation.kind_name(), "invoke_host_function");
+    if let &OperationKind::Unknown { ref raw, .. } = operation.kind() {
+        assert_eq!(
+            raw["function"],
+            "HostFunctionTypeHostFunctionTypeInvokeContract"
+        );
+    } else {
+        panic!("Did not generate unknown kind");
+    }
+}
+
+#[test]
+fn it_keeps_unknown_operation_types_within_pages() {
+    use endpoint::Records;
+    use resources::deserialize::from_slice;
+
+    let json = format!(
+        r#"{{"_embedded":{{"records":[{},{}]}}}}"#,
+        payment_json(),
+        unknown_json()
+    );
+    assert!(from_slice::<Records<Operation>>(json.as_bytes(), false).is_err());
+    let page: Records<Operation> = from_slice(json.as_bytes(), true).unwrap();
+    assert!(!page.records()[0].is_unknown());
+    assert!(page.records()[1].is_unknown());
+}
+
+#[test]
+fn it_reads_operations_by_reference_without_allocating_strings() {
+    let json = payment_json();
+    let operation: OperationRef = serde_json::from_str(json).unwrap();
+    assert_eq!(operation.type_name(), "payment");
+    assert_eq!(operation.type_i(), Some(1));
+    let within = json.as_ptr() as usize..json.as_ptr() as usize + json.len();
+    assert!(within.contains(&(operation.paging_token().as_ptr() as usize)));
+    assert!(within.contains(&(operation.transaction().as_ptr() as usize)));
+    assert!(within.contains(&(operation.from().unwrap().as_ptr() as usize)));
+    assert!(within.contains(&(operation.to().unwrap().as_ptr() as usize)));
+    assert_eq!(operation.funder(), None);
+    assert_eq!(
+        operation.into_owned().unwrap(),
+        serde_json::from_str::<Operation>(json).unwrap()
+    );
+}
+
+#[test]
+fn it_reads_unknown_operation_types_by_reference() {
+    let operation: OperationRef = serde_json::from_str(unknown_json()).unwrap();
+    assert_eq!(operation.type_name(), "invoke_host_function");
+    assert_eq!(operation.type_i(), Some(24));
+    assert!(operation.into_owned().is_err());
+}
+
+#[test]
+fn it_reads_common_fields_of_any_operation() {
+    fn common<T: OperationCommon>(operation: &T) -> (&str, &str, Option<&str>) {
+        (
+            operation.paging_token(),
+            operation.transaction_hash(),
+            operation.source_account(),
+        )
+    }
+
+    let json = include_str!("../../../fixtures/operations/clawback.json");
+    let operation: Operation = serde_json::from_str(json).unwrap();
+    let borrowed: OperationRef = serde_json::from_str(json).unwrap();
+    assert_eq!(common(&operation), common(&borrowed));
+    assert_eq!(
+        common(&operation),
+        (
+            "3553549968576513",
+            "a3d3e4b8b2e1cbd3f4ad0e5d6c9ad14bd0c3e6cca94d5e4f9e82b8a3d37f4c36",
+            Some("GC23QF2HUE52AMXUFUH3AYJAXXGXXV2VHXYYR6EYXETPKDXZSAW67XO4"),
+        )
+    );
+    assert_eq!(
+        OperationCommon::created_at(&operation).unwrap().to_rfc3339(),
+        "2021-05-06T18:21:12+00:00"
+    );
+
+    let operation: Operation = serde_json::from_str(payment_json()).unwrap();
+    assert_eq!(operation.source_account(), None);
+    assert_eq!(operation.created_at(), None);
+}
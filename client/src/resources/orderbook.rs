@@ -5,7 +5,7 @@ use resources::{AssetIdentifier, offer::OfferSummary};
 /// The asset pairs are refered to as a base and counter.
 ///
 /// <https://www.stellar.org/developers/horizon/reference/resources/orderbook.html>
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Orderbook {
     bids: Vec<OfferSummary>,
     asks: Vec<OfferSummary>,
@@ -43,6 +43,7 @@ impl Orderbook {
 #[cfg(test)]
 mod orderbook_tests {
     use super::*;
+    use resources::serialize::assert_round_trips;
     use resources::Amount;
     use serde_json;
 
@@ -64,4 +65,9 @@ mod orderbook_tests {
         assert_eq!(orderbook.base().code(), "XLM".to_string());
         assert_eq!(orderbook.counter().code(), "FOO".to_string());
     }
+
+    #[test]
+    fn it_serializes_like_horizon() {
+        assert_round_trips::<Orderbook>(orderbook_json());
+    }
 }
@@ -1,10 +1,10 @@
 use super::{amount::Amount, asset::AssetIdentifier};
-use serde::{de, Deserialize, Deserializer};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 /// A path resource contains information about a payment path. A path can be used by code to
 /// populate necessary fields on path payment operation, such as path and sendMax.  The
 /// also describes assets this path hops through.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PaymentPath {
     path: Vec<AssetIdentifier>,
     source_amount: Amount,
@@ -57,16 +57,20 @@ impl PaymentPath {
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 struct IntermediatePaymentPath {
     path: Vec<AssetIdentifier>,
     destination_amount: Amount,
     destination_asset_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     destination_asset_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     destination_asset_issuer: Option<String>,
     source_amount: Amount,
     source_asset_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     source_asset_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     source_asset_issuer: Option<String>,
 }
 
@@ -96,9 +100,29 @@ impl<'de> Deserialize<'de> for PaymentPath {
     }
 }
 
+impl Serialize for PaymentPath {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        IntermediatePaymentPath {
+            path: self.path.clone(),
+            destination_amount: self.destination_amount,
+            destination_asset_type: self.destination_asset.asset_type().to_string(),
+            destination_asset_code: self.destination_asset.asset_code(),
+            destination_asset_issuer: self.destination_asset.asset_issuer(),
+            source_amount: self.source_amount,
+            source_asset_type: self.source_asset.asset_type().to_string(),
+            source_asset_code: self.source_asset.asset_code(),
+            source_asset_issuer: self.source_asset.asset_issuer(),
+        }.serialize(s)
+    }
+}
+
 #[cfg(test)]
 mod payment_path_tests {
     use super::*;
+    use resources::serialize::assert_round_trips;
     use serde_json;
 
     fn payment_path_json() -> &'static str {
@@ -114,4 +138,9 @@ mod payment_path_tests {
         assert_eq!(payment_path.destination_asset().code(), "EUR");
         assert_eq!(payment_path.source_asset().code(), "USD");
     }
+
+    #[test]
+    fn it_serializes_like_horizon() {
+        assert_round_trips::<PaymentPath>(payment_path_json());
+    }
 }
@@ -0,0 +1,211 @@
+use chrono::{DateTime, Utc};
+use resources::{
+    Account, Asset, Effect, Ledger, LiquidityPool, Offer, Operation, Trade, Transaction,
+};
+use std::borrow::Cow;
+
+/// The fields every resource horizon lists in pages has, for storage and
+/// deduplication layers over `Records<T>` that don't want code for each
+/// type. Payment paths and trade aggregations are listed too but have no
+/// identity of their own, so they don't implement it.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use stellar_client::resources::{Resource, Transaction};
+///
+/// fn index<T: Resource>(store: &mut HashMap<String, T>, records: Vec<T>) {
+///     for record in records {
+///         store.insert(record.id().into_owned(), record);
+///     }
+/// }
+///
+/// let mut store = HashMap::new();
+/// let transaction = Transaction::new("abc", "GA", 1);
+/// index(&mut store, vec![transaction.clone(), transaction]);
+/// assert_eq!(store.len(), 1);
+/// ```
+pub trait Resource {
+    /// The id horizon gives the resource, formatted as it appears in urls.
+    fn id(&self) -> Cow<str>;
+
+    /// A paging token suitable for use as a cursor parameter.
+    fn paging_token(&self) -> Cow<str>;
+
+    /// When the resource was created, for resources that record it. This is
+    /// the close time of the ledger it was created in.
+    fn created_at(&self) -> Option<DateTime<Utc>> {
+        None
+    }
+}
+
+/// Accounts are paged by their id.
+impl Resource for Account {
+    fn id(&self) -> Cow<str> {
+        Cow::Borrowed(Account::id(self))
+    }
+
+    fn paging_token(&self) -> Cow<str> {
+        Cow::Borrowed(Account::id(self))
+    }
+}
+
+/// Assets have no id, so their paging token of code, issuer and type is
+/// used as one.
+impl Resource for Asset {
+    fn id(&self) -> Cow<str> {
+        self.paging_token()
+    }
+
+    fn paging_token(&self) -> Cow<str> {
+        let asset = self.identifier();
+        if asset.is_native() {
+            return Cow::Borrowed(asset.asset_type());
+        }
+        Cow::Owned(format!(
+            "{}_{}_{}",
+            asset.code(),
+            asset.issuer(),
+            asset.asset_type()
+        ))
+    }
+}
+
+impl Resource for Effect {
+    fn id(&self) -> Cow<str> {
+        Cow::Borrowed(Effect::id(self))
+    }
+
+    fn paging_token(&self) -> Cow<str> {
+        Cow::Borrowed(Effect::paging_token(self))
+    }
+
+    fn created_at(&self) -> Option<DateTime<Utc>> {
+        Effect::created_at(self)
+    }
+}
+
+impl Resource for Ledger {
+    fn id(&self) -> Cow<str> {
+        Cow::Borrowed(Ledger::id(self))
+    }
+
+    fn paging_token(&self) -> Cow<str> {
+        Cow::Borrowed(Ledger::paging_token(self))
+    }
+
+    fn created_at(&self) -> Option<DateTime<Utc>> {
+        Some(self.closed_at())
+    }
+}
+
+impl Resource for LiquidityPool {
+    fn id(&self) -> Cow<str> {
+        Cow::Borrowed(LiquidityPool::id(self))
+    }
+
+    fn paging_token(&self) -> Cow<str> {
+        Cow::Borrowed(LiquidityPool::paging_token(self))
+    }
+}
+
+impl Resource for Offer {
+    fn id(&self) -> Cow<str> {
+        Cow::Owned(Offer::id(self).to_string())
+    }
+
+    fn paging_token(&self) -> Cow<str> {
+        Cow::Borrowed(Offer::paging_token(self))
+    }
+}
+
+impl Resource for Operation {
+    fn id(&self) -> Cow<str> {
+        Cow::Owned(Operation::id(self).to_string())
+    }
+
+    fn paging_token(&self) -> Cow<str> {
+        Cow::Borrowed(Operation::paging_token(self))
+    }
+
+    fn created_at(&self) -> Option<DateTime<Utc>> {
+        Operation::created_at(self)
+    }
+}
+
+impl Resource for Trade {
+    fn id(&self) -> Cow<str> {
+        Cow::Borrowed(Trade::id(self))
+    }
+
+    fn paging_token(&self) -> Cow<str> {
+        Cow::Borrowed(Trade::paging_token(self))
+    }
+
+    fn created_at(&self) -> Option<DateTime<Utc>> {
+        Some(self.closed_at())
+    }
+}
+
+impl Resource for Transaction {
+    fn id(&self) -> Cow<str> {
+        Cow::Borrowed(Transaction::id(self))
+    }
+
+    fn paging_token(&self) -> Cow<str> {
+        Cow::Borrowed(Transaction::paging_token(self))
+    }
+
+    fn created_at(&self) -> Option<DateTime<Utc>> {
+        Some(Transaction::created_at(self))
+    }
+}
+
+#[cfg(test)]
+mod resource_tests {
+    use super::*;
+    use serde::de::DeserializeOwned;
+    use serde_json;
+
+    fn fields<T: Resource + DeserializeOwned>(
+        json: &str,
+    ) -> (String, String, Option<DateTime<Utc>>) {
+        let resource: T = serde_json::from_str(json).unwrap();
+        (
+            resource.id().into_owned(),
+            resource.paging_token().into_owned(),
+            resource.created_at(),
+        )
+    }
+
+    #[test]
+    fn it_reads_the_fields_of_any_resource() {
+        let (id, paging_token, created_at) =
+            fields::<Ledger>(include_str!("../../fixtures/ledger.json"));
+        assert_eq!(
+            id,
+            "eee9e6e02899365ecae4c37e52db7d99e2d130baf4ec1856d311bb546df1d0ad"
+        );
+        assert_eq!(paging_token, "300042120331264");
+        assert!(created_at.is_some());
+
+        let (id, paging_token, created_at) =
+            fields::<Offer>(include_str!("../../fixtures/offer.json"));
+        assert_eq!(id, paging_token);
+        assert_eq!(created_at, None);
+
+        let (id, paging_token, _) = fields::<Asset>(include_str!("../../fixtures/asset.json"));
+        assert_eq!(id, paging_token);
+        assert_eq!(
+            paging_token,
+            "USD_GBAUUA74H4XOQYRSOW2RZUA4QL5PB37U3JS5NE3RTB2ELJVMIF5RLMAG_credit_alphanum4"
+        );
+
+        let (_, _, created_at) = fields::<Operation>(include_str!(
+            "../../fixtures/operations/liquidity_pool_deposit.json"
+        ));
+        assert_eq!(
+            created_at.unwrap().to_rfc3339(),
+            "2021-11-18T03:47:47+00:00"
+        );
+    }
+}
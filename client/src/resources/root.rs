@@ -0,0 +1,122 @@
+use chrono::{DateTime, Utc};
+
+/// What a horizon server says about itself and the network it serves: the
+/// versions it runs, the protocol in force and how far it has ingested.
+///
+/// <https://developers.stellar.org/api/horizon/resources/get-root>
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Root {
+    horizon_version: String,
+    core_version: String,
+    #[serde(default)]
+    ingest_latest_ledger: u32,
+    history_latest_ledger: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    history_latest_ledger_closed_at: Option<DateTime<Utc>>,
+    history_elder_ledger: u32,
+    core_latest_ledger: u32,
+    network_passphrase: String,
+    current_protocol_version: u32,
+    #[serde(default)]
+    supported_protocol_version: u32,
+    core_supported_protocol_version: u32,
+}
+
+impl Root {
+    /// The version of horizon the server runs.
+    pub fn horizon_version(&self) -> &str {
+        &self.horizon_version
+    }
+
+    /// The version of stellar-core horizon follows.
+    pub fn core_version(&self) -> &str {
+        &self.core_version
+    }
+
+    /// The latest ledger horizon has ingested.
+    pub fn ingest_latest_ledger(&self) -> u32 {
+        self.ingest_latest_ledger
+    }
+
+    /// The latest ledger whose history horizon serves.
+    pub fn history_latest_ledger(&self) -> u32 {
+        self.history_latest_ledger
+    }
+
+    /// When the latest ledger horizon serves closed, if horizon says.
+    pub fn history_latest_ledger_closed_at(&self) -> Option<DateTime<Utc>> {
+        self.history_latest_ledger_closed_at
+    }
+
+    /// The oldest ledger whose history horizon serves. Requests for older
+    /// history fail with a before history error.
+    pub fn history_elder_ledger(&self) -> u32 {
+        self.history_elder_ledger
+    }
+
+    /// The latest ledger stellar-core has closed.
+    pub fn core_latest_ledger(&self) -> u32 {
+        self.core_latest_ledger
+    }
+
+    /// How many ledgers horizon's history is behind stellar-core. A healthy
+    /// server is at most a ledger or two behind.
+    pub fn ledger_lag(&self) -> u32 {
+        self.core_latest_ledger
+            .saturating_sub(self.history_latest_ledger)
+    }
+
+    /// The passphrase of the network, which transactions are signed for.
+    pub fn network_passphrase(&self) -> &str {
+        &self.network_passphrase
+    }
+
+    /// The protocol version the network runs.
+    pub fn current_protocol_version(&self) -> u32 {
+        self.current_protocol_version
+    }
+
+    /// The newest protocol version horizon supports.
+    pub fn supported_protocol_version(&self) -> u32 {
+        self.supported_protocol_version
+    }
+
+    /// The newest protocol version stellar-core supports, which the network
+    /// can vote to upgrade to.
+    pub fn core_supported_protocol_version(&self) -> u32 {
+        self.core_supported_protocol_version
+    }
+}
+
+#[cfg(test)]
+mod root_tests {
+    use super::*;
+    use resources::serialize::assert_round_trips;
+    use serde_json;
+
+    fn root_json() -> &'static str {
+        include_str!("../../fixtures/root.json")
+    }
+
+    #[test]
+    fn it_parses_the_root() {
+        let root: Root = serde_json::from_str(root_json()).unwrap();
+        assert!(root.horizon_version().starts_with("2.27.0"));
+        assert_eq!(
+            root.network_passphrase(),
+            "Test SDF Network ; September 2015"
+        );
+        assert_eq!(root.current_protocol_version(), 19);
+        assert_eq!(root.history_latest_ledger(), 1234567);
+        assert_eq!(root.ledger_lag(), 3);
+        assert_eq!(
+            root.history_latest_ledger_closed_at().unwrap().to_rfc3339(),
+            "2023-10-02T15:04:05+00:00"
+        );
+    }
+
+    #[test]
+    fn it_serializes_like_horizon() {
+        assert_round_trips::<Root>(root_json());
+    }
+}
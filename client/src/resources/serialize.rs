@@ -0,0 +1,100 @@
+use serde::Serializer;
+use std::fmt::Display;
+
+/// The inverse of `deserialize::from_str`. Serializes a value as a string so
+/// that integers horizon represents as strings round trip to the same json.
+pub(crate) fn to_string<T, S>(value: &T, s: S) -> Result<S::Ok, S::Error>
+where
+    T: Display,
+    S: Serializer,
+{
+    s.collect_str(value)
+}
+
+/// The same as `to_string` for optional fields, which should also be skipped
+/// when they are `None`.
+pub(crate) fn optional_to_string<T, S>(value: &Option<T>, s: S) -> Result<S::Ok, S::Error>
+where
+    T: Display,
+    S: Serializer,
+{
+    match *value {
+        Some(ref value) => s.collect_str(value),
+        None => s.serialize_none(),
+    }
+}
+
+/// Asserts that a resource parsed from a horizon fixture serializes back to
+/// the same json. Every field written must be in the fixture with the same
+/// value, though fields the resource doesn't keep, such as the links of a
+/// page, may be left out, and amounts may be written with more decimal
+/// places than the fixture gives. The output must also parse back to a resource
+/// equal to the one parsed from the fixture, so no field it keeps is lost.
+#[cfg(test)]
+pub(crate) fn assert_round_trips<T>(fixture: &str)
+where
+    T: ::serde::Serialize + ::serde::de::DeserializeOwned + PartialEq + ::std::fmt::Debug,
+{
+    use resources::Amount;
+    use serde_json::{self, Value};
+
+    fn assert_same_json(actual: &Value, expected: &Value, path: &str) {
+        match (actual, expected) {
+            (&Value::Object(ref actual), &Value::Object(ref expected)) => {
+                for (key, value) in actual {
+                    let path = format!("{}/{}", path, key);
+                    match expected.get(key) {
+                        Some(expected) => assert_same_json(value, expected, &path),
+                        None => panic!("{} is not in the fixture", path),
+                    }
+                }
+            }
+            (&Value::Array(ref actual), &Value::Array(ref expected)) => {
+                assert_eq!(actual.len(), expected.len(), "length of {}", path);
+                for (index, (actual, expected)) in actual.iter().zip(expected).enumerate() {
+                    assert_same_json(actual, expected, &format!("{}/{}", path, index));
+                }
+            }
+            (&Value::String(ref actual), &Value::String(ref expected))
+                if actual.parse::<Amount>().is_ok() =>
+            {
+                assert_eq!(
+                    actual.parse::<Amount>().ok(),
+                    expected.parse::<Amount>().ok(),
+                    "{} differs from the fixture",
+                    path
+                );
+            }
+            _ => assert_eq!(actual, expected, "{} differs from the fixture", path),
+        }
+    }
+
+    let resource: T = serde_json::from_str(fixture).unwrap();
+    let actual = serde_json::to_value(&resource).unwrap();
+    let expected: Value = serde_json::from_str(fixture).unwrap();
+    assert_same_json(&actual, &expected, "");
+
+    let reparsed: T = serde_json::from_value(actual).unwrap();
+    assert_eq!(reparsed, resource);
+}
+
+#[cfg(test)]
+mod to_string_tests {
+    use resources::deserialize;
+    use serde_json;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Sequenced {
+        #[serde(serialize_with = "super::to_string",
+                deserialize_with = "deserialize::from_number_or_str")]
+        sequence: i64,
+    }
+
+    #[test]
+    fn it_round_trips_integers_as_strings() {
+        let json = r#"{"sequence":"2394452857640034"}"#;
+        let sequenced: Sequenced = serde_json::from_str(json).unwrap();
+        assert_eq!(sequenced.sequence, 2394452857640034);
+        assert_eq!(serde_json::to_string(&sequenced).unwrap(), json);
+    }
+}
@@ -0,0 +1,92 @@
+use error::{Error, Result};
+use resources::Transaction;
+use stellar_error::{ResultCodes, StellarError};
+
+/// The response horizon gives once a submitted transaction has been included
+/// in a ledger. Funding an account with friendbot returns the same shape.
+///
+/// <https://www.stellar.org/developers/horizon/reference/endpoints/transactions-create.html>
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    hash: String,
+    ledger: u32,
+    envelope_xdr: String,
+    result_xdr: String,
+    result_meta_xdr: String,
+}
+
+impl Submission {
+    /// The submission of a transaction horizon already has, as submitting
+    /// it would have returned. A transaction that failed fails with the
+    /// `transaction_failed` error submitting it would have, with its result
+    /// codes read from the result xdr.
+    pub fn from_transaction(transaction: Transaction) -> Result<Submission> {
+        if !transaction.successful() {
+            let codes = ResultCodes::from_result_xdr(transaction.result_xdr());
+            return Err(Error::BadResponse(StellarError::transaction_failed(codes)));
+        }
+        Ok(Submission {
+            hash: transaction.hash().clone(),
+            ledger: transaction.ledger(),
+            envelope_xdr: transaction.envelope_xdr().clone(),
+            result_xdr: transaction.result_xdr().clone(),
+            result_meta_xdr: transaction.result_meta_xdr().clone(),
+        })
+    }
+
+    /// A hex-encoded SHA-256 hash of the transaction's XDR-encoded form.
+    pub fn hash(&self) -> &str {
+        &self.hash
+    }
+
+    /// Sequence number of the ledger the transaction was applied in.
+    pub fn ledger(&self) -> u32 {
+        self.ledger
+    }
+
+    /// A base64 encoded string of the TransactionEnvelope xdr that was
+    /// submitted.
+    pub fn envelope_xdr(&self) -> &str {
+        &self.envelope_xdr
+    }
+
+    /// A base64 encoded string of the TransactionResult xdr.
+    pub fn result_xdr(&self) -> &str {
+        &self.result_xdr
+    }
+
+    /// A base64 encoded string of the TransactionMeta xdr.
+    pub fn result_meta_xdr(&self) -> &str {
+        &self.result_meta_xdr
+    }
+}
+
+#[cfg(test)]
+mod submission_tests {
+    use super::*;
+    use resources::serialize::assert_round_trips;
+    use serde_json;
+
+    fn submission_json() -> &'static str {
+        include_str!("../../fixtures/submission.json")
+    }
+
+    #[test]
+    fn it_parses_a_submission_from_json() {
+        let submission: Submission = serde_json::from_str(&submission_json()).unwrap();
+        assert_eq!(
+            submission.hash(),
+            "648da0d47aa3b3b20afd4499a68f89b6d10ead8b1f38858e99b1d94b6fef6e69"
+        );
+        assert_eq!(submission.ledger(), 16751283);
+        assert_eq!(
+            submission.result_xdr(),
+            "AAAAAAAAAGQAAAAAAAAAAQAAAAAAAAABAAAAAAAAAAA="
+        );
+    }
+
+    #[test]
+    fn it_serializes_like_horizon() {
+        assert_round_trips::<Submission>(submission_json());
+    }
+}
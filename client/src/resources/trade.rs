@@ -1,14 +1,18 @@
-use super::{amount::Amount, asset::AssetIdentifier, offer::PriceRatio};
+use super::{deserialize, serialize, amount::Amount, asset::AssetIdentifier, offer::PriceRatio};
+use super::links::{Links, TradeLinks};
+use super::id::{ParseIdError, TradeId};
 use chrono::prelude::*;
-use serde::{de, Deserialize, Deserializer};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json;
+use std::borrow::Cow;
 
 /// A trade represents an offer that was fulfilled between two assets and accounts.
 ///
 /// <https://www.stellar.org/developers/horizon/reference/resources/trade.html>
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Trade {
     id: String,
-    offer_id: String,
+    offer_id: u64,
     paging_token: String,
     ledger_close_time: DateTime<Utc>,
     base_account: String,
@@ -19,10 +23,11 @@ pub struct Trade {
     counter_asset: AssetIdentifier,
     price: PriceRatio,
     seller: Seller,
+    links: Links<TradeLinks>,
 }
 
 /// The seller involved in the trade.
-#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+#[derive(Eq, PartialEq, Debug, Copy, Clone, Hash)]
 pub enum Seller {
     /// The base created the sell offer.
     Base,
@@ -59,7 +64,14 @@ impl<'de> Deserialize<'de> for Trade {
     where
         D: Deserializer<'de>,
     {
-        let rep: TradeIntermediate = TradeIntermediate::deserialize(d)?;
+        TradeIntermediate::deserialize(d)?.into_trade()
+    }
+}
+
+impl<'a> TradeIntermediate<'a> {
+    /// Builds the trade, allocating the strings it borrowed.
+    fn into_trade<E: de::Error>(self) -> Result<Trade, E> {
+        let rep = self;
         let seller = if rep.base_is_seller {
             Seller::Base
         } else {
@@ -67,32 +79,33 @@ impl<'de> Deserialize<'de> for Trade {
         };
         let base_asset = AssetIdentifier::new(
             &rep.base_asset_type,
-            rep.base_asset_code,
-            rep.base_asset_issuer,
+            rep.base_asset_code.map(Cow::into_owned),
+            rep.base_asset_issuer.map(Cow::into_owned),
         ).map_err(|err| de::Error::custom(&err))?;
         let counter_asset = AssetIdentifier::new(
             &rep.counter_asset_type,
-            rep.counter_asset_code,
-            rep.counter_asset_issuer,
+            rep.counter_asset_code.map(Cow::into_owned),
+            rep.counter_asset_issuer.map(Cow::into_owned),
         ).map_err(|err| de::Error::custom(&err))?;
         Ok(Trade {
-            id: rep.id,
-            paging_token: rep.paging_token,
+            id: rep.id.into_owned(),
+            paging_token: rep.paging_token.into_owned(),
             ledger_close_time: rep.ledger_close_time,
             offer_id: rep.offer_id,
-            base_account: rep.base_account,
+            base_account: rep.base_account.into_owned(),
             base_asset,
             base_amount: rep.base_amount,
-            counter_account: rep.counter_account,
+            counter_account: rep.counter_account.into_owned(),
             counter_asset,
             counter_amount: rep.counter_amount,
             price: PriceRatio::from(rep.price),
             seller,
+            links: rep.links,
         })
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 struct Price {
     n: u64,
     d: u64,
@@ -104,28 +117,78 @@ impl From<Price> for PriceRatio {
     }
 }
 
-#[derive(Deserialize, Debug)]
-struct TradeIntermediate {
-    id: String,
-    paging_token: String,
+impl From<PriceRatio> for Price {
+    fn from(price: PriceRatio) -> Price {
+        Price {
+            n: price.numerator(),
+            d: price.denominator(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct TradeIntermediate<'a> {
+    #[serde(borrow)]
+    id: Cow<'a, str>,
+    #[serde(borrow)]
+    paging_token: Cow<'a, str>,
     ledger_close_time: DateTime<Utc>,
-    offer_id: String,
-    base_account: String,
+    #[serde(serialize_with = "serialize::to_string",
+            deserialize_with = "deserialize::from_number_or_str")]
+    offer_id: u64,
+    #[serde(borrow)]
+    base_account: Cow<'a, str>,
     base_amount: Amount,
-    base_asset_type: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    base_asset_code: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    base_asset_issuer: Option<String>,
-    counter_account: String,
+    #[serde(borrow)]
+    base_asset_type: Cow<'a, str>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    base_asset_code: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    base_asset_issuer: Option<Cow<'a, str>>,
+    #[serde(borrow)]
+    counter_account: Cow<'a, str>,
     counter_amount: Amount,
-    counter_asset_type: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    counter_asset_code: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    counter_asset_issuer: Option<String>,
+    #[serde(borrow)]
+    counter_asset_type: Cow<'a, str>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    counter_asset_code: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "deserialize::optional_cow_str",
+            skip_serializing_if = "Option::is_none")]
+    counter_asset_issuer: Option<Cow<'a, str>>,
     base_is_seller: bool,
     price: Price,
+    #[serde(rename = "_links", default, skip_serializing_if = "Links::is_none")]
+    links: Links<TradeLinks>,
+}
+
+impl Serialize for Trade {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        TradeIntermediate {
+            id: Cow::Borrowed(&self.id),
+            paging_token: Cow::Borrowed(&self.paging_token),
+            ledger_close_time: self.ledger_close_time,
+            offer_id: self.offer_id,
+            base_account: Cow::Borrowed(&self.base_account),
+            base_amount: self.base_amount,
+            base_asset_type: self.base_asset.asset_type().into(),
+            base_asset_code: self.base_asset.asset_code().map(Cow::Owned),
+            base_asset_issuer: self.base_asset.asset_issuer().map(Cow::Owned),
+            counter_account: Cow::Borrowed(&self.counter_account),
+            counter_amount: self.counter_amount,
+            counter_asset_type: self.counter_asset.asset_type().into(),
+            counter_asset_code: self.counter_asset.asset_code().map(Cow::Owned),
+            counter_asset_issuer: self.counter_asset.asset_issuer().map(Cow::Owned),
+            base_is_seller: self.seller.is_base(),
+            price: Price::from(self.price),
+            links: self.links.clone(),
+        }.serialize(s)
+    }
 }
 
 impl Trade {
@@ -139,14 +202,26 @@ impl Trade {
         &self.paging_token
     }
 
+    /// The links horizon sent with the trade, to its base and counter
+    /// accounts or pools and the operation that made it.
+    pub fn links(&self) -> Option<&TradeLinks> {
+        self.links.get()
+    }
+
+    /// The id of the trade split into the operation that made it and its
+    /// place among the operation's trades, for ordering trades.
+    pub fn trade_id(&self) -> Result<TradeId, ParseIdError> {
+        self.id.parse()
+    }
+
     /// The closing time of the trade on the ledger
     pub fn closed_at(&self) -> DateTime<Utc> {
         self.ledger_close_time
     }
 
     /// The id of the offer involved in the trade.
-    pub fn offer_id(&self) -> &str {
-        &self.offer_id
+    pub fn offer_id(&self) -> u64 {
+        self.offer_id
     }
 
     /// The base account of the trade that received the counter asset.
@@ -199,9 +274,133 @@ impl Trade {
     }
 }
 
+/// A trade that borrows its strings from the json it was deserialized from,
+/// for reading pages of trades without allocating a string for every field
+/// of every record. `into_owned` finishes parsing it into a `Trade`.
+///
+/// Strings are only borrowed when deserializing from a borrowed source such
+/// as `serde_json::from_str` or `from_slice`, and only when they have no
+/// escapes to undo.
+#[derive(Debug, Clone)]
+pub struct TradeRef<'a> {
+    rep: TradeIntermediate<'a>,
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for TradeRef<'a> {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        TradeIntermediate::deserialize(d).map(|rep| TradeRef { rep })
+    }
+}
+
+impl<'a> TradeRef<'a> {
+    /// The id of the trade.
+    pub fn id(&self) -> &str {
+        &self.rep.id
+    }
+
+    /// A paging_token suitable for use as a cursor parameter.
+    pub fn paging_token(&self) -> &str {
+        &self.rep.paging_token
+    }
+
+    /// The id of the trade split into the operation that made it and its
+    /// place among the operation's trades, for ordering trades.
+    pub fn trade_id(&self) -> Result<TradeId, ParseIdError> {
+        self.rep.id.parse()
+    }
+
+    /// The closing time of the trade on the ledger
+    pub fn closed_at(&self) -> DateTime<Utc> {
+        self.rep.ledger_close_time
+    }
+
+    /// The id of the offer involved in the trade.
+    pub fn offer_id(&self) -> u64 {
+        self.rep.offer_id
+    }
+
+    /// The base account of the trade that received the counter asset.
+    pub fn base_account(&self) -> &str {
+        &self.rep.base_account
+    }
+
+    /// The type of the asset offered by the base party.
+    pub fn base_asset_type(&self) -> &str {
+        &self.rep.base_asset_type
+    }
+
+    /// The code of the asset offered by the base party, unless native.
+    pub fn base_asset_code(&self) -> Option<&str> {
+        borrowed(&self.rep.base_asset_code)
+    }
+
+    /// The issuer of the asset offered by the base party, unless native.
+    pub fn base_asset_issuer(&self) -> Option<&str> {
+        borrowed(&self.rep.base_asset_issuer)
+    }
+
+    /// The amount of the base asset exchanged.
+    pub fn base_amount(&self) -> Amount {
+        self.rep.base_amount
+    }
+
+    /// The counter account of the trade that received the base asset.
+    pub fn counter_account(&self) -> &str {
+        &self.rep.counter_account
+    }
+
+    /// The type of the asset offered by the counter party.
+    pub fn counter_asset_type(&self) -> &str {
+        &self.rep.counter_asset_type
+    }
+
+    /// The code of the asset offered by the counter party, unless native.
+    pub fn counter_asset_code(&self) -> Option<&str> {
+        borrowed(&self.rep.counter_asset_code)
+    }
+
+    /// The issuer of the asset offered by the counter party, unless native.
+    pub fn counter_asset_issuer(&self) -> Option<&str> {
+        borrowed(&self.rep.counter_asset_issuer)
+    }
+
+    /// The amount of the counter asset exchanged.
+    pub fn counter_amount(&self) -> Amount {
+        self.rep.counter_amount
+    }
+
+    /// The original offer price.
+    pub fn price(&self) -> PriceRatio {
+        PriceRatio::from(self.rep.price)
+    }
+
+    /// Which party is the seller
+    pub fn seller(&self) -> Seller {
+        if self.rep.base_is_seller {
+            Seller::Base
+        } else {
+            Seller::Counter
+        }
+    }
+
+    /// Parses the trade's assets, allocating the strings it borrowed. This
+    /// fails for assets of types this crate doesn't recognize.
+    pub fn into_owned(self) -> Result<Trade, serde_json::Error> {
+        self.rep.into_trade()
+    }
+}
+
+fn borrowed<'b>(field: &'b Option<Cow<str>>) -> Option<&'b str> {
+    field.as_ref().map(|field| field.as_ref())
+}
+
 #[cfg(test)]
 mod trade_tests {
     use super::*;
+    use resources::serialize::assert_round_trips;
     use serde_json;
 
     fn trade_json() -> &'static str {
@@ -212,9 +411,10 @@ mod trade_tests {
     fn it_parses_into_a_trade() {
         let trade: Trade = serde_json::from_str(&trade_json()).unwrap();
         assert_eq!(trade.id(), "68836918321750017-0");
+        assert_eq!(trade.trade_id().unwrap().operation().ledger(), 16_027_344);
         assert_eq!(trade.paging_token(), "68836918321750017-0");
         assert_eq!(trade.closed_at(), Utc.ymd(2018, 2, 2).and_hms(0, 20, 10));
-        assert_eq!(trade.offer_id(), "695254");
+        assert_eq!(trade.offer_id(), 695254);
         assert_eq!(
             trade.base_account(),
             "GBZXCJIUEPDXGHMS64UBJHUVKV6ETWYOVHADLTBXJNJFUC7A7RU5B3GN"
@@ -234,11 +434,34 @@ mod trade_tests {
             "GBZXCJIUEPDXGHMS64UBJHUVKV6ETWYOVHADLTBXJNJFUC7A7RU5B3GN"
         )
     }
+
+    #[test]
+    fn it_serializes_like_horizon() {
+        assert_round_trips::<Trade>(trade_json());
+    }
+
+    #[test]
+    fn it_reads_trades_by_reference_without_allocating_strings() {
+        let json = trade_json();
+        let trade: TradeRef = serde_json::from_str(json).unwrap();
+        let within = json.as_ptr() as usize..json.as_ptr() as usize + json.len();
+        assert!(within.contains(&(trade.id().as_ptr() as usize)));
+        assert!(within.contains(&(trade.base_account().as_ptr() as usize)));
+        assert_eq!(trade.base_asset_type(), "native");
+        assert_eq!(trade.base_asset_code(), None);
+        assert_eq!(trade.counter_asset_code(), Some("SLT"));
+        assert_eq!(trade.price(), PriceRatio::new(10, 61));
+        assert!(trade.seller().is_base());
+        assert_eq!(
+            trade.into_owned().unwrap(),
+            serde_json::from_str::<Trade>(json).unwrap()
+        );
+    }
 }
 
 /// The aggregation of trades for a specifc base/counter pair of assets over a given
 /// time period.
-#[derive(Clone, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct TradeAggregation {
     // Several fields are omitted since they don't seem to be in the actual response from horizon.
     // Or they don't seem to make sense.
@@ -304,11 +527,52 @@ impl TradeAggregation {
     pub fn close(&self) -> Amount {
         self.close
     }
+
+    /// The same segment seen from the other side of the pair, with the base
+    /// and counter volumes swapped and each price inverted, so the high is
+    /// the inverse of the low and the average is base volume over counter
+    /// volume. Used to turn around aggregations requested with
+    /// `trade::Aggregations::canonical` when it reports them inverted.
+    pub fn inverted(&self) -> TradeAggregation {
+        let avg = if self.counter_volume.stroops() > 0 {
+            ratio(self.base_volume, self.counter_volume)
+        } else {
+            invert(self.avg)
+        };
+        TradeAggregation {
+            timestamp: self.timestamp,
+            trade_count: self.trade_count,
+            base_volume: self.counter_volume,
+            counter_volume: self.base_volume,
+            avg,
+            high: invert(self.low),
+            low: invert(self.high),
+            open: invert(self.open),
+            close: invert(self.close),
+        }
+    }
+}
+
+/// The price of one over a price, rounded to the nearest stroop. A price of
+/// zero stays zero.
+fn invert(price: Amount) -> Amount {
+    ratio(Amount::new(10_000_000), price)
+}
+
+/// The amount of `n` per unit of `d`, rounded to the nearest stroop.
+fn ratio(n: Amount, d: Amount) -> Amount {
+    let (n, d) = (i128::from(n.stroops()), i128::from(d.stroops()));
+    if d == 0 {
+        return Amount::new(0);
+    }
+    let stroops = (n * 10_000_000 + d / 2) / d;
+    Amount::new(stroops.min(i128::from(i64::MAX)) as i64)
 }
 
 #[cfg(test)]
 mod trade_aggregation_tests {
     use super::*;
+    use resources::serialize::assert_round_trips;
     use serde_json;
 
     fn trade_aggregation_json() -> &'static str {
@@ -331,4 +595,24 @@ mod trade_aggregation_tests {
         assert_eq!(trade_agg.open(), Amount::new(1724138));
         assert_eq!(trade_agg.close(), Amount::new(1506024));
     }
+
+    #[test]
+    fn it_serializes_like_horizon() {
+        assert_round_trips::<TradeAggregation>(trade_aggregation_json());
+    }
+
+    #[test]
+    fn it_inverts_the_pair() {
+        let trade_agg: TradeAggregation = serde_json::from_str(&trade_aggregation_json()).unwrap();
+        let inverted = trade_agg.inverted();
+        assert_eq!(inverted.started_at(), trade_agg.started_at());
+        assert_eq!(inverted.count(), 26);
+        assert_eq!(inverted.base_volume(), Amount::new(50856410385));
+        assert_eq!(inverted.counter_volume(), Amount::new(275750201596));
+        assert_eq!(inverted.average(), Amount::new(54221326));
+        assert_eq!(inverted.high(), Amount::new(66400004));
+        assert_eq!(inverted.low(), Amount::new(52199995));
+        assert_eq!(inverted.open(), Amount::new(57999998));
+        assert_eq!(inverted.close(), Amount::new(66400004));
+    }
 }
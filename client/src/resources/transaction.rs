@@ -1,12 +1,14 @@
-use super::{deserialize, amount::Amount};
+use super::{deserialize, serialize, amount::Amount, links::{Links, TransactionLinks}};
 use chrono::prelude::*;
+use transaction::TransactionEnvelope;
+use xdr;
 
 /// Memos are a useful source for adding meta data to a transaction.
 /// A consists of a type and content (unless memo type is none).
 ///
 /// To learn more about the concept of memos in the Stellar network, take a look at the Stellar memo description here:
 /// <https://www.stellar.org/developers/guides/concepts/transactions.html#memo>
-#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase", tag = "memo_type", content = "memo")]
 pub enum Memo {
     ///  A string encoded using either ASCII or UTF-8, up to 28-bytes long.
@@ -21,21 +23,156 @@ pub enum Memo {
     None,
 }
 
+impl Memo {
+    /// The type of memo as horizon names it: “text”, “id”, “hash”,
+    /// “return” or “none”.
+    pub fn memo_type(&self) -> &'static str {
+        match *self {
+            Memo::Text(_) => "text",
+            Memo::Id(_) => "id",
+            Memo::Hash(_) => "hash",
+            Memo::Return(_) => "return",
+            Memo::None => "none",
+        }
+    }
+}
+
+/// The window of time, in seconds since the unix epoch, in which a
+/// transaction is valid.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimeBounds {
+    #[serde(default, skip_serializing_if = "Option::is_none",
+            serialize_with = "serialize::optional_to_string",
+            deserialize_with = "deserialize::optional_from_number_or_str")]
+    min_time: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none",
+            serialize_with = "serialize::optional_to_string",
+            deserialize_with = "deserialize::optional_from_number_or_str")]
+    max_time: Option<u64>,
+}
+
+impl TimeBounds {
+    /// The earliest time the transaction is valid, where zero means any time.
+    pub fn min_time(&self) -> u64 {
+        self.min_time.unwrap_or(0)
+    }
+
+    /// The latest time the transaction is valid, or zero if there is no limit.
+    pub fn max_time(&self) -> u64 {
+        self.max_time.unwrap_or(0)
+    }
+}
+
+/// The range of ledgers a transaction is valid in.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LedgerBounds {
+    #[serde(default)]
+    min_ledger: u32,
+    #[serde(default)]
+    max_ledger: u32,
+}
+
+impl LedgerBounds {
+    /// The first ledger the transaction is valid in.
+    pub fn min_ledger(&self) -> u32 {
+        self.min_ledger
+    }
+
+    /// The ledger before which the transaction must be applied, or zero if
+    /// there is no limit.
+    pub fn max_ledger(&self) -> u32 {
+        self.max_ledger
+    }
+}
+
+/// The conditions a transaction must meet to be valid, beyond its sequence
+/// number and fee.
+///
+/// <https://github.com/stellar/stellar-protocol/blob/master/core/cap-0021.md>
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Preconditions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timebounds: Option<TimeBounds>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ledgerbounds: Option<LedgerBounds>,
+    #[serde(default, skip_serializing_if = "Option::is_none",
+            serialize_with = "serialize::optional_to_string",
+            deserialize_with = "deserialize::optional_from_number_or_str")]
+    min_account_sequence: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none",
+            serialize_with = "serialize::optional_to_string",
+            deserialize_with = "deserialize::optional_from_number_or_str")]
+    min_account_sequence_age: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    min_account_sequence_ledger_gap: Option<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    extra_signers: Vec<String>,
+}
+
+impl Preconditions {
+    /// The window of time the transaction is valid in.
+    pub fn time_bounds(&self) -> Option<TimeBounds> {
+        self.timebounds
+    }
+
+    /// The range of ledgers the transaction is valid in.
+    pub fn ledger_bounds(&self) -> Option<LedgerBounds> {
+        self.ledgerbounds
+    }
+
+    /// The lowest sequence number the source account may have for the
+    /// transaction to be valid.
+    pub fn min_account_sequence(&self) -> Option<i64> {
+        self.min_account_sequence
+    }
+
+    /// How many seconds the source account's sequence number must have been
+    /// unchanged for.
+    pub fn min_account_sequence_age(&self) -> Option<u64> {
+        self.min_account_sequence_age
+    }
+
+    /// How many ledgers must have closed since the source account's sequence
+    /// number last changed.
+    pub fn min_account_sequence_ledger_gap(&self) -> Option<u32> {
+        self.min_account_sequence_ledger_gap
+    }
+
+    /// Signers, as strkeys, that must sign the transaction besides those of
+    /// the source account.
+    pub fn extra_signers(&self) -> &[String] {
+        &self.extra_signers
+    }
+}
+
 /// Transactions are the basic unit of change in the Stellar Network.
 /// A transaction is a grouping of operations.
 ///
 /// To learn more about the concept of transactions in the Stellar network, take a look at the Stellar transactions concept guide.
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Transaction {
     id: String,
     paging_token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    successful: Option<bool>,
     hash: String,
     ledger: u32,
     created_at: DateTime<Utc>,
     source_account: String,
-    #[serde(deserialize_with = "deserialize::from_str")]
-    source_account_sequence: u64,
-    fee_paid: i64,
+    #[serde(serialize_with = "serialize::to_string",
+            deserialize_with = "deserialize::from_number_or_str")]
+    source_account_sequence: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none",
+            deserialize_with = "deserialize::optional_from_number_or_str")]
+    fee_paid: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none",
+            serialize_with = "serialize::optional_to_string",
+            deserialize_with = "deserialize::optional_from_number_or_str")]
+    fee_charged: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none",
+            serialize_with = "serialize::optional_to_string",
+            deserialize_with = "deserialize::optional_from_number_or_str")]
+    max_fee: Option<i64>,
     operation_count: u32,
     envelope_xdr: String,
     result_xdr: String,
@@ -43,9 +180,120 @@ pub struct Transaction {
     fee_meta_xdr: String,
     #[serde(flatten)]
     memo: Memo,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    memo_bytes: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    signatures: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    valid_after: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    valid_before: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    preconditions: Option<Preconditions>,
+    #[serde(rename = "_links", default, skip_serializing_if = "Links::is_none")]
+    links: Links<TransactionLinks>,
 }
 
 impl Transaction {
+    /// A transaction with the given hash, sent from the given account and
+    /// sequence number. It has no memo, operations, fee, or xdr and was
+    /// created at the unix epoch in ledger zero until the `with_` methods
+    /// say otherwise, which is handy for tests and mock servers.
+    pub fn new(hash: &str, source_account: &str, source_account_sequence: i64) -> Transaction {
+        Transaction {
+            id: hash.to_string(),
+            paging_token: String::new(),
+            successful: None,
+            hash: hash.to_string(),
+            ledger: 0,
+            created_at: Utc.timestamp(0, 0),
+            source_account: source_account.to_string(),
+            source_account_sequence,
+            fee_paid: None,
+            fee_charged: None,
+            max_fee: None,
+            operation_count: 0,
+            envelope_xdr: String::new(),
+            result_xdr: String::new(),
+            result_meta_xdr: String::new(),
+            fee_meta_xdr: String::new(),
+            memo: Memo::None,
+            memo_bytes: None,
+            signatures: Vec::new(),
+            valid_after: None,
+            valid_before: None,
+            preconditions: None,
+            links: Links::none(),
+        }
+    }
+
+    /// Sets the paging token used as a cursor.
+    pub fn with_paging_token(mut self, paging_token: &str) -> Transaction {
+        self.paging_token = paging_token.to_string();
+        self
+    }
+
+    /// Marks the transaction as having failed, or succeeded.
+    pub fn with_successful(mut self, successful: bool) -> Transaction {
+        self.successful = Some(successful);
+        self
+    }
+
+    /// Sets the ledger the transaction was applied in and when.
+    pub fn with_ledger(mut self, ledger: u32, created_at: DateTime<Utc>) -> Transaction {
+        self.ledger = ledger;
+        self.created_at = created_at;
+        self
+    }
+
+    /// Sets the fee paid, in stroops.
+    pub fn with_fee_paid(mut self, fee_paid: i64) -> Transaction {
+        self.fee_paid = Some(fee_paid);
+        self
+    }
+
+    /// Sets the fee charged and the most the source account offered to pay,
+    /// in stroops, as newer horizon versions report them.
+    pub fn with_fees(mut self, fee_charged: i64, max_fee: i64) -> Transaction {
+        self.fee_charged = Some(fee_charged);
+        self.max_fee = Some(max_fee);
+        self
+    }
+
+    /// Sets the base64 encoded signatures on the envelope.
+    pub fn with_signatures(mut self, signatures: Vec<String>) -> Transaction {
+        self.signatures = signatures;
+        self
+    }
+
+    /// Sets the number of operations in the transaction.
+    pub fn with_operation_count(mut self, operation_count: u32) -> Transaction {
+        self.operation_count = operation_count;
+        self
+    }
+
+    /// Sets the base64 encoded envelope, result, result meta, and fee meta
+    /// xdr, in that order.
+    pub fn with_xdr(
+        mut self,
+        envelope_xdr: &str,
+        result_xdr: &str,
+        result_meta_xdr: &str,
+        fee_meta_xdr: &str,
+    ) -> Transaction {
+        self.envelope_xdr = envelope_xdr.to_string();
+        self.result_xdr = result_xdr.to_string();
+        self.result_meta_xdr = result_meta_xdr.to_string();
+        self.fee_meta_xdr = fee_meta_xdr.to_string();
+        self
+    }
+
+    /// Sets the memo.
+    pub fn with_memo(mut self, memo: Memo) -> Transaction {
+        self.memo = memo;
+        self
+    }
+
     /// The canonical id of this transaction, suitable for use as the :id parameter for url
     /// templates that require a transaction’s ID.
     pub fn id(&self) -> &String {
@@ -58,11 +306,26 @@ impl Transaction {
         &self.paging_token
     }
 
+    /// The links horizon sent with the transaction, to its source account,
+    /// ledger, operations and effects and the transactions either side of
+    /// it. `None` for a transaction built with `new`.
+    pub fn links(&self) -> Option<&TransactionLinks> {
+        self.links.get()
+    }
+
     /// A hex-encoded SHA-256 hash of the transaction’s XDR-encoded form.
     pub fn hash(&self) -> &String {
         &self.hash
     }
 
+    /// Whether the transaction succeeded. A failed transaction still made it
+    /// into a ledger and was charged its fee, but none of its operations
+    /// took effect. `result_xdr` says why it failed. Older versions of
+    /// horizon only kept transactions that succeeded, and leave this out.
+    pub fn successful(&self) -> bool {
+        self.successful.unwrap_or(true)
+    }
+
     /// Sequence number of the ledger in which this transaction was applied.
     pub fn ledger(&self) -> u32 {
         self.ledger
@@ -80,20 +343,33 @@ impl Transaction {
 
     /// The current sequence number that can be used when submitting a transaction
     /// from the transaction signing account
-    pub fn source_account_sequence(&self) -> u64 {
+    pub fn source_account_sequence(&self) -> i64 {
         self.source_account_sequence
     }
 
     /// The fee paid by the source account of this transaction when the transaction was applied to
-    /// the ledger.
+    /// the ledger. Newer horizon versions call this the fee charged.
     pub fn fee_paid(&self) -> i64 {
-        self.fee_paid
+        self.fee_paid.or(self.fee_charged).unwrap_or(0)
+    }
+
+    /// The fee charged when the transaction was applied to the ledger, in
+    /// stroops. This is the same as `fee_paid`, which older horizon versions
+    /// return instead.
+    pub fn fee_charged(&self) -> i64 {
+        self.fee_charged.or(self.fee_paid).unwrap_or(0)
+    }
+
+    /// The most the source account offered to pay for the transaction, in
+    /// stroops. `None` on horizon versions that don't return it.
+    pub fn max_fee(&self) -> Option<i64> {
+        self.max_fee
     }
 
     /// The fee represented as an amount.  This is useful when subtracting fees from
     /// other amounts in your model
     pub fn fee_as_amount(&self) -> Amount {
-        Amount::new(self.fee_paid)
+        Amount::new(self.fee_paid())
     }
 
     /// The number of operations that are contained within this transaction.
@@ -106,6 +382,14 @@ impl Transaction {
         &self.envelope_xdr
     }
 
+    /// Decodes the envelope, giving the operations, memo and signatures
+    /// exactly as they were signed rather than as horizon summarizes them.
+    /// It's decoded on each call, and the network passphrase is needed to
+    /// hash it since horizon doesn't say which network it serves.
+    pub fn envelope(&self, network_passphrase: &str) -> Result<TransactionEnvelope, xdr::Error> {
+        TransactionEnvelope::from_base64(&self.envelope_xdr, network_passphrase)
+    }
+
     /// A base64 encoded string of the raw TransactionResultPair xdr struct for this transaction
     pub fn result_xdr(&self) -> &String {
         &self.result_xdr
@@ -126,11 +410,47 @@ impl Transaction {
     pub fn memo(&self) -> &Memo {
         &self.memo
     }
+
+    /// The type of the memo, such as “text” or “none”.
+    pub fn memo_type(&self) -> &'static str {
+        self.memo.memo_type()
+    }
+
+    /// The base64 encoded bytes of a text memo, which keep memos that aren't
+    /// valid utf-8 intact.
+    pub fn memo_bytes(&self) -> Option<&str> {
+        self.memo_bytes.as_ref().map(String::as_str)
+    }
+
+    /// The base64 encoded signatures on the envelope, in the order they
+    /// were attached.
+    pub fn signatures(&self) -> &[String] {
+        &self.signatures
+    }
+
+    /// The earliest time the transaction was valid, if it had time bounds.
+    pub fn valid_after(&self) -> Option<DateTime<Utc>> {
+        self.valid_after
+    }
+
+    /// The time the transaction stopped being valid, if it had time bounds
+    /// with a limit.
+    pub fn valid_before(&self) -> Option<DateTime<Utc>> {
+        self.valid_before
+    }
+
+    /// The preconditions the transaction had to meet, on horizon versions
+    /// that return them.
+    pub fn preconditions(&self) -> Option<&Preconditions> {
+        self.preconditions.as_ref()
+    }
 }
 
 #[cfg(test)]
 mod transaction_tests {
     use super::*;
+    use network::Network;
+    use resources::serialize::assert_round_trips;
     use serde_json;
 
     fn transaction_json() -> &'static str {
@@ -265,9 +585,124 @@ mod transaction_tests {
         }
     }
 
+    #[test]
+    fn it_parses_whether_the_transaction_succeeded() {
+        let old: Transaction = serde_json::from_str(transaction_json()).unwrap();
+        assert!(old.successful());
+        let failed: Transaction = serde_json::from_str(include_str!(
+            "../../fixtures/transactions/transaction_failed.json"
+        )).unwrap();
+        assert!(!failed.successful());
+        let built = Transaction::new(failed.hash(), failed.source_account(), 1);
+        assert!(!built.with_successful(false).successful());
+    }
+
     #[test]
     fn it_parses_memo_none() {
         let transaction: Transaction = serde_json::from_str(&transaction_json_memo_none()).unwrap();
         assert_eq!(transaction.memo(), &Memo::None);
     }
+
+    #[test]
+    fn it_builds_the_same_transaction_as_horizon_returns() {
+        let parsed: Transaction = serde_json::from_str(&transaction_json()).unwrap();
+        let built = Transaction::new(
+            parsed.hash(),
+            "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3",
+            2394452857640034,
+        ).with_paging_token("71946212651044864")
+            .with_ledger(16751283, Utc.ymd(2018, 3, 10).and_hms(23, 16, 42))
+            .with_fee_paid(100)
+            .with_operation_count(1)
+            .with_signatures(parsed.signatures().to_vec())
+            .with_xdr(
+                parsed.envelope_xdr(),
+                parsed.result_xdr(),
+                parsed.result_meta_xdr(),
+                parsed.fee_meta_xdr(),
+            );
+        assert_ne!(built, parsed);
+        assert_eq!(built.with_memo(Memo::Text("hello".to_string())), parsed);
+    }
+
+    fn transaction_json_preconditions() -> &'static str {
+        include_str!("../../fixtures/transactions/transaction_preconditions.json")
+    }
+
+    #[test]
+    fn it_serializes_like_horizon() {
+        assert_round_trips::<Transaction>(transaction_json());
+        assert_round_trips::<Transaction>(transaction_json_preconditions());
+    }
+
+    #[test]
+    fn it_parses_fees_and_signatures() {
+        let transaction: Transaction =
+            serde_json::from_str(transaction_json_preconditions()).unwrap();
+        assert_eq!(transaction.fee_charged(), 200);
+        assert_eq!(transaction.fee_paid(), 200);
+        assert_eq!(transaction.max_fee(), Some(1000));
+        assert_eq!(transaction.memo_type(), "text");
+        assert_eq!(transaction.memo_bytes(), Some("aW52b2ljZSA0Mg=="));
+        assert_eq!(transaction.signatures().len(), 2);
+
+        let old: Transaction = serde_json::from_str(transaction_json()).unwrap();
+        assert_eq!(old.fee_charged(), 100);
+        assert_eq!(old.max_fee(), None);
+        assert_eq!(old.signatures().len(), 1);
+        assert_eq!(old.preconditions(), None);
+    }
+
+    #[test]
+    fn it_parses_preconditions() {
+        let transaction: Transaction =
+            serde_json::from_str(transaction_json_preconditions()).unwrap();
+        assert_eq!(
+            transaction.valid_after(),
+            Some(Utc.ymd(1970, 1, 1).and_hms(0, 0, 0))
+        );
+        assert_eq!(
+            transaction.valid_before(),
+            Some(Utc.ymd(2022, 6, 12).and_hms(10, 53, 12))
+        );
+        let preconditions = transaction.preconditions().unwrap();
+        let time_bounds = preconditions.time_bounds().unwrap();
+        assert_eq!(time_bounds.min_time(), 0);
+        assert_eq!(time_bounds.max_time(), 1655031192);
+        let ledger_bounds = preconditions.ledger_bounds().unwrap();
+        assert_eq!(ledger_bounds.min_ledger(), 1769000);
+        assert_eq!(ledger_bounds.max_ledger(), 1770000);
+        assert_eq!(
+            preconditions.min_account_sequence(),
+            Some(7598893461475328)
+        );
+        assert_eq!(preconditions.min_account_sequence_age(), Some(60));
+        assert_eq!(preconditions.min_account_sequence_ledger_gap(), Some(5));
+        assert_eq!(
+            preconditions.extra_signers(),
+            &["GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3".to_string()]
+        );
+    }
+
+    #[test]
+    fn it_decodes_the_envelope() {
+        let transaction: Transaction = serde_json::from_str(&transaction_json()).unwrap();
+        let envelope = transaction.envelope(Network::PUBLIC).unwrap();
+        assert_eq!(envelope.hash_hex(), *transaction.hash());
+        assert_eq!(envelope.source_account(), transaction.source_account());
+        assert_eq!(envelope.sequence(), transaction.source_account_sequence());
+        assert_eq!(envelope.fee(), 100);
+        assert_eq!(envelope.signatures().len(), 1);
+        let operations = envelope.operations();
+        assert_eq!(operations.len(), 1);
+        assert_eq!(operations[0].name(), "payment");
+        assert_eq!(operations[0].amount(), Some(Amount::new(999_900_000)));
+        assert_eq!(envelope.memo(), &Memo::Text(String::new()));
+    }
+
+    #[test]
+    fn it_names_memo_types() {
+        assert_eq!(Memo::Id(1).memo_type(), "id");
+        assert_eq!(Memo::None.memo_type(), "none");
+    }
 }
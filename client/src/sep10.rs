@@ -0,0 +1,717 @@
+//! Server side of SEP-10 web authentication, for anchors running an auth
+//! server on this crate.
+//!
+//! A wallet proves it controls an account by signing a challenge
+//! transaction the server built. The challenge is never submitted. It has a
+//! sequence number of zero, is only valid for a few minutes and carries a
+//! random nonce in a `manage_data` operation, so a signed challenge can't be
+//! replayed. Once signed, the server checks the signatures against the
+//! account's signers on horizon. See
+//! [SEP-10](https://github.com/stellar/stellar-protocol/blob/master/ecosystem/sep-0010.md).
+//!
+//! ```no_run
+//! # extern crate stellar_client;
+//! # extern crate tokio_core;
+//! use stellar_client::async::Client;
+//! use stellar_client::keypair::Keypair;
+//! use stellar_client::network::Network;
+//! use stellar_client::sep10::AuthServer;
+//! use tokio_core::reactor::Core;
+//!
+//! # fn main() {
+//! let server = AuthServer::new(
+//!     Keypair::random(),
+//!     Network::TESTNET,
+//!     "example.com",
+//!     "auth.example.com",
+//! );
+//! let challenge = server
+//!     .challenge("GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3")
+//!     .unwrap();
+//!
+//! // The wallet signs the challenge and sends it back.
+//! # let signed = challenge.clone();
+//!
+//! let mut core = Core::new().unwrap();
+//! let client = Client::horizon_test(&core.handle()).unwrap();
+//! let verified = core.run(server.verify(&client, &signed)).unwrap();
+//! println!("{} signed in", verified.account_id());
+//! # }
+//! ```
+use base64;
+use chrono::Utc;
+use client::async::Client;
+use endpoint::account;
+use error::Error as ClientError;
+use futures::{future, Future};
+use keypair::{Keypair, PublicKey};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use resources::{Account, AccountSigner};
+use std::error::Error as StdError;
+use std::fmt;
+use strkey::{self, Version};
+use transaction::{self, Operation, TransactionBuilder, TransactionEnvelope};
+use xdr::{self, Reader};
+
+/// How long a challenge is valid for unless the server says otherwise.
+pub const DEFAULT_TIMEOUT: u64 = 300;
+
+/// The number of random bytes in a challenge's nonce, which is base64
+/// encoded into the 64 bytes a data entry can hold.
+const NONCE_LEN: usize = 48;
+
+const ENVELOPE_TYPE_TX: i32 = 2;
+const MANAGE_DATA: i32 = 10;
+
+/// An auth server's signing key and the domains it serves, which builds
+/// challenges and verifies them once the wallet has signed them.
+#[derive(Debug, Clone)]
+pub struct AuthServer {
+    keypair: Keypair,
+    network_passphrase: String,
+    home_domain: String,
+    web_auth_domain: String,
+    timeout: u64,
+}
+
+impl AuthServer {
+    /// An auth server signing challenges with the keypair, which should be
+    /// the `SIGNING_KEY` in the home domain's stellar.toml. The web auth
+    /// domain is the domain the auth server itself is hosted on.
+    pub fn new(
+        keypair: Keypair,
+        network_passphrase: &str,
+        home_domain: &str,
+        web_auth_domain: &str,
+    ) -> AuthServer {
+        AuthServer {
+            keypair,
+            network_passphrase: network_passphrase.to_string(),
+            home_domain: home_domain.to_string(),
+            web_auth_domain: web_auth_domain.to_string(),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Sets how many seconds a challenge is valid for after it's built.
+    /// Defaults to five minutes.
+    pub fn with_timeout(mut self, timeout: u64) -> AuthServer {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The account id of the server's signing key.
+    pub fn account_id(&self) -> String {
+        self.keypair.account_id()
+    }
+
+    /// Builds a challenge for the account and signs it with the server's
+    /// key, returning it encoded as base64 XDR to send to the wallet.
+    pub fn challenge(&self, account_id: &str) -> Result<String, Error> {
+        self.challenge_at(account_id, now())
+    }
+
+    fn challenge_at(&self, account_id: &str, now: u64) -> Result<String, Error> {
+        PublicKey::from_account_id(account_id).map_err(|_| Error::invalid("client account"))?;
+        let mut nonce = [0; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+        let nonce = base64::encode(&nonce[..]);
+
+        let mut envelope =
+            TransactionBuilder::new(&self.account_id(), -1, &self.network_passphrase)
+                .with_time_bounds(now, now + self.timeout)
+                .with_operation(
+                    Operation::manage_data(&self.auth_key(), Some(nonce.as_bytes()))
+                        .with_source_account(account_id),
+                )
+                .with_operation(
+                    Operation::manage_data(
+                        "web_auth_domain",
+                        Some(self.web_auth_domain.as_bytes()),
+                    )
+                    .with_source_account(&self.account_id()),
+                )
+                .build()?;
+        envelope
+            .sign(&self.keypair)
+            .map_err(|_| Error::invalid("server key"))?;
+        Ok(envelope.to_base64()?)
+    }
+
+    /// Checks a challenge the wallet signed against the signers of its
+    /// account on horizon, resolving to the account that signed in.
+    ///
+    /// The challenge has to be one this server built and signed, and still
+    /// be within its time bounds. The signatures of the account's signers
+    /// have to add up to its medium threshold, and the challenge can't be
+    /// signed by any other key. An account that doesn't exist yet only has
+    /// its master key, which has to have signed.
+    pub fn verify(
+        &self,
+        client: &Client,
+        challenge: &str,
+    ) -> Box<dyn Future<Item = Verified, Error = Error>> {
+        let (envelope, account_id) = match self.read_at(challenge, now()) {
+            Ok(read) => read,
+            Err(err) => return Box::new(future::err(err)),
+        };
+        let server_account = self.account_id();
+        let verified = client
+            .request(account::Details::new(&account_id))
+            .then(move |result| {
+                let account = match result {
+                    Ok(account) => account,
+                    Err(ClientError::BadResponse(ref err)) if err.is_not_found() => {
+                        Account::new(&account_id, 0)
+                            .with_signer(AccountSigner::ed25519(&account_id, 1))
+                    }
+                    Err(err) => return Err(Error::from(err)),
+                };
+                verify_signers(&envelope, &account, &server_account)
+            });
+        Box::new(verified)
+    }
+
+    /// Reads a challenge, checking that it is one this server built and
+    /// signed and that it's still valid. Returns the envelope and the
+    /// account it challenges.
+    fn read_at(&self, challenge: &str, now: u64) -> Result<(TransactionEnvelope, String), Error> {
+        let envelope = TransactionEnvelope::from_base64(challenge, &self.network_passphrase)?;
+        let tx = Challenge::read(challenge)?;
+        let server_account = self.account_id();
+
+        if tx.source_account != server_account {
+            return Err(Error::invalid("source account"));
+        }
+        if tx.sequence != 0 {
+            return Err(Error::invalid("sequence number"));
+        }
+        match tx.time_bounds {
+            Some((min_time, max_time)) if max_time != 0 => {
+                if now < min_time || now > max_time {
+                    return Err(Error::expired());
+                }
+            }
+            _ => return Err(Error::invalid("time bounds")),
+        }
+
+        let (first, rest) = match tx.operations.split_first() {
+            Some(split) => split,
+            None => return Err(Error::invalid("operations")),
+        };
+        let account_id = match first.source_account {
+            Some(ref source) if source.starts_with('G') => source.clone(),
+            _ => return Err(Error::invalid("client account")),
+        };
+        if first.name != self.auth_key() {
+            return Err(Error::invalid("home domain"));
+        }
+        match first.value {
+            Some(ref nonce) if nonce.len() == 64 => match base64::decode(nonce) {
+                Ok(ref decoded) if decoded.len() == NONCE_LEN => (),
+                _ => return Err(Error::invalid("nonce")),
+            },
+            _ => return Err(Error::invalid("nonce")),
+        }
+        for operation in rest {
+            if operation.source_account.as_ref() != Some(&server_account) {
+                return Err(Error::invalid("operation source account"));
+            }
+            if operation.name == "web_auth_domain"
+                && operation.value.as_ref().map(Vec::as_slice)
+                    != Some(self.web_auth_domain.as_bytes())
+            {
+                return Err(Error::invalid("web auth domain"));
+            }
+        }
+
+        let hash = envelope.hash();
+        let public_key = self.keypair.public_key();
+        if !envelope.signatures().iter().any(|signature| {
+            signature.hint() == public_key.signature_hint()
+                && public_key.verify(&hash, signature.signature())
+        }) {
+            return Err(Error::invalid("server signature"));
+        }
+        Ok((envelope, account_id))
+    }
+
+    /// The name of the data entry holding the nonce.
+    fn auth_key(&self) -> String {
+        format!("{} auth", self.home_domain)
+    }
+}
+
+/// Adds up the weight of the account's signers that signed the challenge.
+fn verify_signers(
+    envelope: &TransactionEnvelope,
+    account: &Account,
+    server_account: &str,
+) -> Result<Verified, Error> {
+    let hash = envelope.hash();
+    let mut unmatched: Vec<_> = envelope.signatures().iter().collect();
+    let mut signers = Vec::new();
+    let mut weight = 0;
+    for key in Some(server_account).into_iter().chain(
+        account
+            .signers()
+            .iter()
+            .filter(|signer| signer.is_ed25519())
+            .map(AccountSigner::key),
+    ) {
+        let public_key = match PublicKey::from_account_id(key) {
+            Ok(public_key) => public_key,
+            Err(_) => continue,
+        };
+        let signed = unmatched.iter().position(|signature| {
+            signature.hint() == public_key.signature_hint()
+                && public_key.verify(&hash, signature.signature())
+        });
+        if let Some(index) = signed {
+            unmatched.remove(index);
+            if key != server_account {
+                weight += u32::from(account.signer(key).map_or(0, AccountSigner::weight));
+                signers.push(key.to_string());
+            }
+        }
+    }
+    if !unmatched.is_empty() {
+        return Err(Error::invalid("signatures"));
+    }
+    let threshold = u32::from(account.thresholds().med()).max(1);
+    if weight < threshold {
+        return Err(Error::insufficient_weight(weight, threshold));
+    }
+    Ok(Verified {
+        account_id: account.account_id().to_string(),
+        signers,
+        weight,
+    })
+}
+
+/// An account that signed in by signing a challenge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verified {
+    account_id: String,
+    signers: Vec<String>,
+    weight: u32,
+}
+
+impl Verified {
+    /// The account that signed in.
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    /// The keys of the account's signers that signed the challenge.
+    pub fn signers(&self) -> &[String] {
+        &self.signers
+    }
+
+    /// The total weight of the signatures.
+    pub fn weight(&self) -> u32 {
+        self.weight
+    }
+}
+
+/// The parts of a challenge transaction that are checked.
+struct Challenge {
+    source_account: String,
+    sequence: i64,
+    time_bounds: Option<(u64, u64)>,
+    operations: Vec<ManageData>,
+}
+
+struct ManageData {
+    source_account: Option<String>,
+    name: String,
+    value: Option<Vec<u8>>,
+}
+
+impl Challenge {
+    /// Reads the transaction out of an envelope encoded as base64 XDR. Only
+    /// transactions made up of `manage_data` operations are challenges.
+    fn read(challenge: &str) -> Result<Challenge, Error> {
+        let bytes = base64::decode(challenge.trim()).map_err(xdr::Error::from)?;
+        let mut r = Reader::new(&bytes);
+        match r.read_i32()? {
+            ENVELOPE_TYPE_TX => (),
+            v => return Err(xdr::Error::unknown_discriminant("EnvelopeType", v).into()),
+        }
+        let source_account = muxed_account(&mut r)?;
+        r.read_u32()?;
+        let sequence = r.read_i64()?;
+        let time_bounds = match r.read_i32()? {
+            0 => None,
+            1 => Some((r.read_u64()?, r.read_u64()?)),
+            _ => return Err(Error::invalid("preconditions")),
+        };
+        match r.read_i32()? {
+            0 => (),
+            _ => return Err(Error::invalid("memo")),
+        }
+        let mut operations = Vec::new();
+        for _ in 0..r.read_len(100)? {
+            let source_account = if r.read_optional()? {
+                Some(muxed_account(&mut r)?)
+            } else {
+                None
+            };
+            if r.read_i32()? != MANAGE_DATA {
+                return Err(Error::invalid("operation type"));
+            }
+            let name = r.read_string(64)?;
+            let value = if r.read_optional()? {
+                Some(r.read_var(64)?.to_vec())
+            } else {
+                None
+            };
+            operations.push(ManageData {
+                source_account,
+                name,
+                value,
+            });
+        }
+        Ok(Challenge {
+            source_account,
+            sequence,
+            time_bounds,
+            operations,
+        })
+    }
+}
+
+/// Reads an account, which challenges only allow as a plain ed25519 key.
+fn muxed_account(r: &mut Reader) -> Result<String, Error> {
+    match r.read_i32()? {
+        0 => Ok(strkey::encode(Version::AccountId, r.read_fixed(32)?)),
+        _ => Err(Error::invalid("account")),
+    }
+}
+
+fn now() -> u64 {
+    Utc::now().timestamp() as u64
+}
+
+/// An error building or verifying a challenge.
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+}
+
+impl Error {
+    fn invalid(part: &'static str) -> Error {
+        Error {
+            kind: ErrorKind::Invalid(part),
+        }
+    }
+
+    fn expired() -> Error {
+        Error {
+            kind: ErrorKind::Expired,
+        }
+    }
+
+    fn insufficient_weight(weight: u32, threshold: u32) -> Error {
+        Error {
+            kind: ErrorKind::InsufficientWeight { weight, threshold },
+        }
+    }
+
+    /// The underlying kind of error.
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+}
+
+/// The reasons building or verifying a challenge can fail.
+#[derive(Debug)]
+pub enum ErrorKind {
+    /// A part of the challenge, such as its sequence number or nonce, isn't
+    /// what this server builds.
+    Invalid(&'static str),
+    /// The challenge is outside its time bounds.
+    Expired,
+    /// The signatures of the account's signers don't add up to its medium
+    /// threshold.
+    InsufficientWeight {
+        /// The weight of the signatures.
+        weight: u32,
+        /// The weight needed.
+        threshold: u32,
+    },
+    /// The challenge couldn't be built.
+    Transaction(transaction::Error),
+    /// The challenge couldn't be encoded or decoded.
+    Xdr(xdr::Error),
+    /// The account couldn't be fetched from horizon.
+    Horizon(ClientError),
+}
+
+impl From<transaction::Error> for Error {
+    fn from(inner: transaction::Error) -> Error {
+        Error {
+            kind: ErrorKind::Transaction(inner),
+        }
+    }
+}
+
+impl From<xdr::Error> for Error {
+    fn from(inner: xdr::Error) -> Error {
+        Error {
+            kind: ErrorKind::Xdr(inner),
+        }
+    }
+}
+
+impl From<ClientError> for Error {
+    fn from(inner: ClientError) -> Error {
+        Error {
+            kind: ErrorKind::Horizon(inner),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn description(&self) -> &str {
+        match self.kind {
+            ErrorKind::Invalid(_) => "The challenge is invalid",
+            ErrorKind::Expired => "The challenge has expired",
+            ErrorKind::InsufficientWeight { .. } => "The challenge isn't signed with enough weight",
+            ErrorKind::Transaction(ref inner) => inner.description(),
+            ErrorKind::Xdr(ref inner) => inner.description(),
+            ErrorKind::Horizon(ref inner) => inner.description(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ErrorKind::Invalid(part) => write!(f, "The challenge has an invalid {}", part),
+            ErrorKind::InsufficientWeight { weight, threshold } => write!(
+                f,
+                "The challenge is signed with a weight of {} where {} is needed",
+                weight, threshold
+            ),
+            ErrorKind::Transaction(ref inner) => write!(f, "{}", inner),
+            ErrorKind::Xdr(ref inner) => write!(f, "{}", inner),
+            ErrorKind::Horizon(ref inner) => write!(f, "{}", inner),
+            _ => f.write_str(self.description()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod sep10_tests {
+    use super::*;
+    use client::sync::test_server::TestServer;
+    use network::Network;
+    use tokio_core::reactor::Core;
+
+    const NOW: u64 = 1_500_000_000;
+
+    fn server() -> AuthServer {
+        let keypair =
+            Keypair::from_secret_seed("SBGWSG6BTNCKCOB3DIFBGCVMUPQFYPA2G4O34RMTB343OYPXU5DJDVMN")
+                .unwrap();
+        AuthServer::new(keypair, Network::TESTNET, "example.com", "auth.example.com")
+    }
+
+    fn sign(challenge: &str, signers: &[&Keypair]) -> String {
+        let mut envelope = TransactionEnvelope::from_base64(challenge, Network::TESTNET).unwrap();
+        for signer in signers {
+            envelope.sign(*signer).unwrap();
+        }
+        envelope.to_base64().unwrap()
+    }
+
+    /// Horizon's view of the account, with the given signers and medium
+    /// threshold.
+    fn account_json(account: &Keypair, signers: &[(&Keypair, u8)], med: u8) -> String {
+        let signers: Vec<_> = signers
+            .iter()
+            .map(|&(signer, weight)| AccountSigner::ed25519(&signer.account_id(), weight))
+            .collect();
+        format!(
+            r#"{{
+                "id": "{id}",
+                "account_id": "{id}",
+                "sequence": "1",
+                "subentry_count": 0,
+                "thresholds": {{ "low_threshold": 0, "med_threshold": {med}, "high_threshold": 0 }},
+                "balances": [],
+                "signers": {signers},
+                "data": {{}}
+            }}"#,
+            id = account.account_id(),
+            med = med,
+            signers = ::serde_json::to_string(&signers).unwrap()
+        )
+    }
+
+    fn verify(status: &'static str, body: &str, signed: &str) -> Result<Verified, Error> {
+        let server = TestServer::start(status, body.to_string());
+        let mut core = Core::new().unwrap();
+        let client = Client::new(server.uri(), &core.handle()).unwrap();
+        core.run(self::server().verify(&client, signed))
+    }
+
+    fn invalid_part(result: Result<(TransactionEnvelope, String), Error>) -> &'static str {
+        match result.map_err(|err| err.kind) {
+            Err(ErrorKind::Invalid(part)) => part,
+            other => panic!("expected an invalid challenge, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_builds_a_challenge_it_can_read() {
+        let client = Keypair::random();
+        let challenge = server().challenge_at(&client.account_id(), NOW).unwrap();
+        let tx = Challenge::read(&challenge).unwrap();
+        assert_eq!(tx.source_account, server().account_id());
+        assert_eq!(tx.sequence, 0);
+        assert_eq!(tx.time_bounds, Some((NOW, NOW + DEFAULT_TIMEOUT)));
+        assert_eq!(tx.operations.len(), 2);
+        assert_eq!(tx.operations[0].name, "example.com auth");
+        assert_eq!(tx.operations[0].source_account, Some(client.account_id()));
+        assert_eq!(tx.operations[0].value.as_ref().unwrap().len(), 64);
+        assert_eq!(tx.operations[1].name, "web_auth_domain");
+        assert_eq!(
+            tx.operations[1].value.as_ref().map(Vec::as_slice),
+            Some(&b"auth.example.com"[..])
+        );
+
+        let (_, account_id) = server().read_at(&challenge, NOW + 10).unwrap();
+        assert_eq!(account_id, client.account_id());
+    }
+
+    #[test]
+    fn it_uses_a_new_nonce_for_each_challenge() {
+        let client = Keypair::random().account_id();
+        assert_ne!(
+            server().challenge_at(&client, NOW).unwrap(),
+            server().challenge_at(&client, NOW).unwrap()
+        );
+    }
+
+    #[test]
+    fn it_rejects_expired_challenges() {
+        let challenge = server()
+            .with_timeout(60)
+            .challenge_at(&Keypair::random().account_id(), NOW)
+            .unwrap();
+        match server()
+            .read_at(&challenge, NOW + 61)
+            .map_err(|err| err.kind)
+        {
+            Err(ErrorKind::Expired) => (),
+            other => panic!("expected an expired challenge, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_rejects_challenges_from_other_servers() {
+        let other = AuthServer::new(
+            Keypair::random(),
+            Network::TESTNET,
+            "example.com",
+            "auth.example.com",
+        );
+        let challenge = other
+            .challenge_at(&Keypair::random().account_id(), NOW)
+            .unwrap();
+        assert_eq!(
+            invalid_part(server().read_at(&challenge, NOW)),
+            "source account"
+        );
+
+        let other = AuthServer::new(
+            server().keypair.clone(),
+            Network::TESTNET,
+            "example.org",
+            "auth.example.com",
+        );
+        let challenge = other
+            .challenge_at(&Keypair::random().account_id(), NOW)
+            .unwrap();
+        assert_eq!(
+            invalid_part(server().read_at(&challenge, NOW)),
+            "home domain"
+        );
+    }
+
+    #[test]
+    fn it_rejects_challenges_the_server_did_not_sign() {
+        let client = Keypair::random().account_id();
+        let challenge = TransactionBuilder::new(&server().account_id(), -1, Network::TESTNET)
+            .with_time_bounds(NOW, NOW + 300)
+            .with_operation(
+                Operation::manage_data("example.com auth", Some(&[b'A'; 64][..]))
+                    .with_source_account(&client),
+            )
+            .build()
+            .unwrap()
+            .to_base64()
+            .unwrap();
+        assert_eq!(
+            invalid_part(server().read_at(&challenge, NOW)),
+            "server signature"
+        );
+    }
+
+    #[test]
+    fn it_verifies_the_signature_of_the_master_key() {
+        let client = Keypair::random();
+        let challenge = server().challenge(&client.account_id()).unwrap();
+        let signed = sign(&challenge, &[&client]);
+        let body = account_json(&client, &[(&client, 1)], 0);
+        let verified = verify("200 OK", &body, &signed).unwrap();
+        assert_eq!(verified.account_id(), client.account_id());
+        assert_eq!(verified.signers(), &[client.account_id()]);
+        assert_eq!(verified.weight(), 1);
+    }
+
+    #[test]
+    fn it_needs_the_medium_threshold_of_the_account() {
+        let client = Keypair::random();
+        let cosigner = Keypair::random();
+        let body = account_json(&client, &[(&client, 1), (&cosigner, 1)], 2);
+        let challenge = server().challenge(&client.account_id()).unwrap();
+
+        match verify("200 OK", &body, &sign(&challenge, &[&client])).map_err(|err| err.kind) {
+            Err(ErrorKind::InsufficientWeight {
+                weight: 1,
+                threshold: 2,
+            }) => (),
+            other => panic!("expected too little weight, got {:?}", other),
+        }
+        let verified = verify("200 OK", &body, &sign(&challenge, &[&client, &cosigner])).unwrap();
+        assert_eq!(verified.weight(), 2);
+    }
+
+    #[test]
+    fn it_rejects_signatures_of_keys_that_are_not_signers() {
+        let client = Keypair::random();
+        let body = account_json(&client, &[(&client, 1)], 0);
+        let challenge = server().challenge(&client.account_id()).unwrap();
+        let signed = sign(&challenge, &[&client, &Keypair::random()]);
+        match verify("200 OK", &body, &signed).map_err(|err| err.kind) {
+            Err(ErrorKind::Invalid("signatures")) => (),
+            other => panic!("expected an invalid signature, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_verifies_accounts_that_do_not_exist_by_their_master_key() {
+        let client = Keypair::random();
+        let not_found = include_str!("../fixtures/before_history_error.json")
+            .replace("before_history", "not_found");
+        let challenge = server().challenge(&client.account_id()).unwrap();
+
+        let verified = verify("404 Not Found", &not_found, &sign(&challenge, &[&client])).unwrap();
+        assert_eq!(verified.account_id(), client.account_id());
+        assert!(verify("404 Not Found", &not_found, &challenge).is_err());
+    }
+}
@@ -0,0 +1,664 @@
+//! A client for the quote servers of anchors, which price exchanges between
+//! stellar assets and off chain assets such as fiat for on and off ramps.
+//! Quotes are requested ahead of a SEP-6, SEP-24 or SEP-31 transfer and then
+//! referenced by id so the rate is honored.
+//!
+//! The endpoints are requested with a client pointed at the `ANCHOR_QUOTE_SERVER`
+//! from the anchor's stellar.toml. Asking for a firm quote needs a SEP-10
+//! token, given with `with_token`. See
+//! [SEP-38](https://github.com/stellar/stellar-protocol/blob/master/ecosystem/sep-0038.md).
+//!
+//! ```no_run
+//! use stellar_client::resources::{AnchorAsset, Amount};
+//! use stellar_client::sep38::{self, QuoteContext};
+//! use stellar_client::sync::Client;
+//!
+//! let client = Client::new("https://anchor.example.com/sep38").unwrap();
+//! let usd = AnchorAsset::iso4217("USD");
+//! let usdc: AnchorAsset = "stellar:USDC:GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN"
+//!     .parse()
+//!     .unwrap();
+//!
+//! let quote = client
+//!     .request(
+//!         sep38::PostQuote::selling(QuoteContext::Sep24, usd, Amount::new(1_000_000_000), usdc)
+//!             .with_token("eyJhbGciOi..."),
+//!     )
+//!     .unwrap();
+//! println!("{} until {}", quote.price(), quote.expires_at());
+//! ```
+use chrono::prelude::*;
+use endpoint::{push_param, Body, IntoRequest};
+use error::Result;
+use http::{header, request, Request, Uri};
+use resources::{Amount, AnchorAsset};
+use std::str::FromStr;
+use uri;
+
+/// The transfer a quote is requested for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuoteContext {
+    /// A deposit or withdrawal through SEP-6.
+    Sep6,
+    /// An interactive deposit or withdrawal through SEP-24.
+    Sep24,
+    /// A cross border payment through SEP-31.
+    Sep31,
+}
+
+/// A way an anchor delivers an off chain asset to, or takes it from, the
+/// user, such as a wire transfer or cash pickup.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DeliveryMethod {
+    name: String,
+    description: String,
+}
+
+impl DeliveryMethod {
+    /// The name passed back to the anchor when asking for prices and quotes.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// A description of the method to show to users.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+/// An asset an anchor quotes prices for.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct InfoAsset {
+    asset: AnchorAsset,
+    #[serde(default)]
+    sell_delivery_methods: Vec<DeliveryMethod>,
+    #[serde(default)]
+    buy_delivery_methods: Vec<DeliveryMethod>,
+    #[serde(default)]
+    country_codes: Vec<String>,
+}
+
+impl InfoAsset {
+    /// The asset.
+    pub fn asset(&self) -> &AnchorAsset {
+        &self.asset
+    }
+
+    /// The ways the user can deliver the asset when selling it.
+    pub fn sell_delivery_methods(&self) -> &[DeliveryMethod] {
+        &self.sell_delivery_methods
+    }
+
+    /// The ways the user can receive the asset when buying it.
+    pub fn buy_delivery_methods(&self) -> &[DeliveryMethod] {
+        &self.buy_delivery_methods
+    }
+
+    /// The ISO 3166 codes of the countries the asset is offered in. Empty
+    /// when the anchor doesn't restrict it.
+    pub fn country_codes(&self) -> &[String] {
+        &self.country_codes
+    }
+}
+
+/// The assets an anchor quotes prices for.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Info {
+    assets: Vec<InfoAsset>,
+}
+
+impl Info {
+    /// The assets, any pair of which may be exchanged.
+    pub fn assets(&self) -> &[InfoAsset] {
+        &self.assets
+    }
+
+    /// Looks up one of the assets.
+    pub fn asset(&self, asset: &AnchorAsset) -> Option<&InfoAsset> {
+        self.assets.iter().find(|info| &info.asset == asset)
+    }
+}
+
+/// The indicative price of buying an asset with the asset being sold.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BuyAsset {
+    asset: AnchorAsset,
+    price: String,
+    decimals: u8,
+}
+
+impl BuyAsset {
+    /// The asset that can be bought.
+    pub fn asset(&self) -> &AnchorAsset {
+        &self.asset
+    }
+
+    /// The number of sell asset units paid for one unit of this asset, fees
+    /// included. Anchors give prices as decimal strings with as many digits
+    /// as they like, so it's left as given.
+    pub fn price(&self) -> &str {
+        &self.price
+    }
+
+    /// The number of decimals the asset is quoted in.
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+}
+
+/// The indicative prices of the assets that can be bought with an asset.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Prices {
+    buy_assets: Vec<BuyAsset>,
+}
+
+impl Prices {
+    /// The assets that can be bought, with their prices.
+    pub fn buy_assets(&self) -> &[BuyAsset] {
+        &self.buy_assets
+    }
+}
+
+/// The fee an anchor charges for a quote.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct QuoteFee {
+    total: Amount,
+    asset: AnchorAsset,
+}
+
+impl QuoteFee {
+    /// The total fee.
+    pub fn total(&self) -> Amount {
+        self.total
+    }
+
+    /// The asset the fee is charged in.
+    pub fn asset(&self) -> &AnchorAsset {
+        &self.asset
+    }
+}
+
+/// A firm quote, which the anchor honors until it expires.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Quote {
+    id: String,
+    expires_at: DateTime<Utc>,
+    total_price: String,
+    price: String,
+    sell_asset: AnchorAsset,
+    sell_amount: Amount,
+    buy_asset: AnchorAsset,
+    buy_amount: Amount,
+    fee: Option<QuoteFee>,
+}
+
+impl Quote {
+    /// The id to reference the quote by when starting the transfer.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// When the anchor stops honoring the quote.
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+
+    /// The number of sell asset units paid for one unit of the buy asset,
+    /// fees included.
+    pub fn total_price(&self) -> &str {
+        &self.total_price
+    }
+
+    /// The number of sell asset units paid for one unit of the buy asset,
+    /// before fees.
+    pub fn price(&self) -> &str {
+        &self.price
+    }
+
+    /// The asset being sold.
+    pub fn sell_asset(&self) -> &AnchorAsset {
+        &self.sell_asset
+    }
+
+    /// The amount being sold.
+    pub fn sell_amount(&self) -> Amount {
+        self.sell_amount
+    }
+
+    /// The asset being bought.
+    pub fn buy_asset(&self) -> &AnchorAsset {
+        &self.buy_asset
+    }
+
+    /// The amount being bought.
+    pub fn buy_amount(&self) -> Amount {
+        self.buy_amount
+    }
+
+    /// The fee, if the anchor itemizes it.
+    pub fn fee(&self) -> Option<&QuoteFee> {
+        self.fee.as_ref()
+    }
+}
+
+/// Adds a SEP-10 token to a request, when there is one.
+fn authorize(builder: &mut request::Builder, token: &Option<String>) {
+    if let Some(ref token) = *token {
+        builder.header(header::AUTHORIZATION, format!("Bearer {}", token).as_str());
+    }
+}
+
+/// Lists the assets the anchor quotes prices for.
+#[derive(Debug, Clone, Default)]
+pub struct GetInfo {
+    token: Option<String>,
+}
+
+impl GetInfo {
+    /// Authenticates the request with a SEP-10 token. Anchors may list more
+    /// assets to users they know.
+    pub fn with_token(self, token: &str) -> Self {
+        Self {
+            token: Some(token.to_string()),
+        }
+    }
+}
+
+impl IntoRequest for GetInfo {
+    type Response = Info;
+
+    fn into_request(self, host: &str) -> Result<Request<Body>> {
+        let uri = Uri::from_str(&format!("{}/info", host))?;
+        let mut builder = Request::get(uri);
+        authorize(&mut builder, &self.token);
+        Ok(builder.body(Body::None)?)
+    }
+}
+
+/// Fetches indicative prices for the assets that can be bought with an
+/// amount of an asset. The prices aren't honored; ask for a quote for that.
+#[derive(Debug, Clone)]
+pub struct GetPrices {
+    sell_asset: AnchorAsset,
+    sell_amount: Amount,
+    sell_delivery_method: Option<String>,
+    buy_delivery_method: Option<String>,
+    country_code: Option<String>,
+    token: Option<String>,
+}
+
+impl GetPrices {
+    /// Prices for selling the amount of the asset.
+    pub fn new(sell_asset: AnchorAsset, sell_amount: Amount) -> Self {
+        Self {
+            sell_asset,
+            sell_amount,
+            sell_delivery_method: None,
+            buy_delivery_method: None,
+            country_code: None,
+            token: None,
+        }
+    }
+
+    /// How the user delivers the asset being sold, when it's off chain.
+    pub fn with_sell_delivery_method(self, method: &str) -> Self {
+        Self {
+            sell_delivery_method: Some(method.to_string()),
+            ..self
+        }
+    }
+
+    /// How the user receives the assets being bought, when they're off chain.
+    pub fn with_buy_delivery_method(self, method: &str) -> Self {
+        Self {
+            buy_delivery_method: Some(method.to_string()),
+            ..self
+        }
+    }
+
+    /// The ISO 3166 code of the country the user is in.
+    pub fn with_country_code(self, country_code: &str) -> Self {
+        Self {
+            country_code: Some(country_code.to_string()),
+            ..self
+        }
+    }
+
+    /// Authenticates the request with a SEP-10 token.
+    pub fn with_token(self, token: &str) -> Self {
+        Self {
+            token: Some(token.to_string()),
+            ..self
+        }
+    }
+}
+
+impl IntoRequest for GetPrices {
+    type Response = Prices;
+
+    fn into_request(self, host: &str) -> Result<Request<Body>> {
+        let mut uri = format!("{}/prices", host);
+        push_param(
+            &mut uri,
+            "sell_asset",
+            &uri::percent_encode(&self.sell_asset.to_string()),
+        );
+        push_param(&mut uri, "sell_amount", &self.sell_amount.to_string());
+        let optional = [
+            ("sell_delivery_method", &self.sell_delivery_method),
+            ("buy_delivery_method", &self.buy_delivery_method),
+            ("country_code", &self.country_code),
+        ];
+        for &(key, value) in &optional {
+            if let Some(ref value) = *value {
+                push_param(&mut uri, key, &uri::percent_encode(value));
+            }
+        }
+        let uri = Uri::from_str(&uri)?;
+        let mut builder = Request::get(uri);
+        authorize(&mut builder, &self.token);
+        Ok(builder.body(Body::None)?)
+    }
+}
+
+/// Asks the anchor for a firm quote. Exactly one of the amounts is given,
+/// and the anchor works out the other. Needs a SEP-10 token.
+#[derive(Debug, Clone, Serialize)]
+pub struct PostQuote {
+    context: QuoteContext,
+    sell_asset: AnchorAsset,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sell_amount: Option<Amount>,
+    buy_asset: AnchorAsset,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    buy_amount: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expire_after: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sell_delivery_method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    buy_delivery_method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    country_code: Option<String>,
+    #[serde(skip)]
+    token: Option<String>,
+}
+
+impl PostQuote {
+    fn new(context: QuoteContext, sell_asset: AnchorAsset, buy_asset: AnchorAsset) -> Self {
+        Self {
+            context,
+            sell_asset,
+            sell_amount: None,
+            buy_asset,
+            buy_amount: None,
+            expire_after: None,
+            sell_delivery_method: None,
+            buy_delivery_method: None,
+            country_code: None,
+            token: None,
+        }
+    }
+
+    /// A quote for selling an amount of one asset for another.
+    pub fn selling(
+        context: QuoteContext,
+        sell_asset: AnchorAsset,
+        sell_amount: Amount,
+        buy_asset: AnchorAsset,
+    ) -> Self {
+        Self {
+            sell_amount: Some(sell_amount),
+            ..Self::new(context, sell_asset, buy_asset)
+        }
+    }
+
+    /// A quote for buying an amount of one asset with another.
+    pub fn buying(
+        context: QuoteContext,
+        sell_asset: AnchorAsset,
+        buy_asset: AnchorAsset,
+        buy_amount: Amount,
+    ) -> Self {
+        Self {
+            buy_amount: Some(buy_amount),
+            ..Self::new(context, sell_asset, buy_asset)
+        }
+    }
+
+    /// Asks for the quote to be honored until at least the given time.
+    /// Anchors may refuse, or price the longer commitment in.
+    pub fn with_expire_after(self, expire_after: DateTime<Utc>) -> Self {
+        Self {
+            expire_after: Some(expire_after),
+            ..self
+        }
+    }
+
+    /// How the user delivers the asset being sold, when it's off chain.
+    pub fn with_sell_delivery_method(self, method: &str) -> Self {
+        Self {
+            sell_delivery_method: Some(method.to_string()),
+            ..self
+        }
+    }
+
+    /// How the user receives the asset being bought, when it's off chain.
+    pub fn with_buy_delivery_method(self, method: &str) -> Self {
+        Self {
+            buy_delivery_method: Some(method.to_string()),
+            ..self
+        }
+    }
+
+    /// The ISO 3166 code of the country the user is in.
+    pub fn with_country_code(self, country_code: &str) -> Self {
+        Self {
+            country_code: Some(country_code.to_string()),
+            ..self
+        }
+    }
+
+    /// Authenticates the request with a SEP-10 token.
+    pub fn with_token(self, token: &str) -> Self {
+        Self {
+            token: Some(token.to_string()),
+            ..self
+        }
+    }
+}
+
+impl IntoRequest for PostQuote {
+    type Response = Quote;
+
+    fn into_request(self, host: &str) -> Result<Request<Body>> {
+        let uri = Uri::from_str(&format!("{}/quote", host))?;
+        let body = Body::json(&self)?;
+        let mut builder = Request::post(uri);
+        authorize(&mut builder, &self.token);
+        Ok(builder.body(body)?)
+    }
+}
+
+/// Fetches a quote the anchor gave earlier. Needs the SEP-10 token of the
+/// user the quote was given to.
+#[derive(Debug, Clone)]
+pub struct GetQuote {
+    id: String,
+    token: Option<String>,
+}
+
+impl GetQuote {
+    /// Fetches the quote with the id.
+    pub fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            token: None,
+        }
+    }
+
+    /// Authenticates the request with a SEP-10 token.
+    pub fn with_token(self, token: &str) -> Self {
+        Self {
+            token: Some(token.to_string()),
+            ..self
+        }
+    }
+}
+
+impl IntoRequest for GetQuote {
+    type Response = Quote;
+
+    fn into_request(self, host: &str) -> Result<Request<Body>> {
+        let uri = format!("{}/quote/{}", host, uri::percent_encode(&self.id));
+        let uri = Uri::from_str(&uri)?;
+        let mut builder = Request::get(uri);
+        authorize(&mut builder, &self.token);
+        Ok(builder.body(Body::None)?)
+    }
+}
+
+#[cfg(test)]
+mod sep38_tests {
+    use super::*;
+    use resources::AssetIdentifier;
+    use serde_json;
+
+    const HOST: &str = "https://anchor.example.com/sep38";
+    const ISSUER: &str = "GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN";
+
+    const INFO: &str = r#"{
+  "assets": [
+    {
+      "asset": "stellar:USDC:GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN"
+    },
+    {
+      "asset": "iso4217:BRL",
+      "country_codes": ["BRA"],
+      "sell_delivery_methods": [
+        {"name": "PIX", "description": "Send BRL via PIX."}
+      ],
+      "buy_delivery_methods": [
+        {"name": "PIX", "description": "Receive BRL via PIX."}
+      ]
+    }
+  ]
+}"#;
+
+    const QUOTE: &str = r#"{
+  "id": "de762cda-a193-4961-861e-57b31fed6eb3",
+  "expires_at": "2021-04-30T07:42:23Z",
+  "total_price": "5.42",
+  "price": "5.00",
+  "sell_asset": "iso4217:BRL",
+  "sell_amount": "542",
+  "buy_asset": "stellar:USDC:GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN",
+  "buy_amount": "100",
+  "fee": {"total": "42.00", "asset": "iso4217:BRL"}
+}"#;
+
+    fn usdc() -> AnchorAsset {
+        AnchorAsset::stellar(AssetIdentifier::alphanum4("USDC", ISSUER))
+    }
+
+    #[test]
+    fn it_parses_info() {
+        let info: Info = serde_json::from_str(INFO).unwrap();
+        assert_eq!(info.assets().len(), 2);
+        assert!(info
+            .asset(&usdc())
+            .unwrap()
+            .sell_delivery_methods()
+            .is_empty());
+        let brl = info.asset(&AnchorAsset::iso4217("BRL")).unwrap();
+        assert_eq!(brl.country_codes(), &["BRA".to_string()]);
+        assert_eq!(brl.buy_delivery_methods()[0].name(), "PIX");
+    }
+
+    #[test]
+    fn it_parses_prices() {
+        let prices: Prices = serde_json::from_str(&format!(
+            r#"{{"buy_assets": [{{"asset": "stellar:USDC:{}", "price": "5.42", "decimals": 7}}]}}"#,
+            ISSUER
+        ))
+        .unwrap();
+        let buy = &prices.buy_assets()[0];
+        assert_eq!(buy.asset(), &usdc());
+        assert_eq!(buy.price(), "5.42");
+        assert_eq!(buy.decimals(), 7);
+    }
+
+    #[test]
+    fn it_parses_a_quote() {
+        let quote: Quote = serde_json::from_str(QUOTE).unwrap();
+        assert_eq!(quote.id(), "de762cda-a193-4961-861e-57b31fed6eb3");
+        assert_eq!(quote.expires_at(), Utc.ymd(2021, 4, 30).and_hms(7, 42, 23));
+        assert_eq!(quote.sell_asset(), &AnchorAsset::iso4217("BRL"));
+        assert_eq!(quote.sell_amount(), Amount::new(5_420_000_000));
+        assert_eq!(quote.buy_asset(), &usdc());
+        assert_eq!(quote.buy_amount(), Amount::new(1_000_000_000));
+        assert_eq!(quote.fee().unwrap().total(), Amount::new(420_000_000));
+    }
+
+    #[test]
+    fn it_can_make_an_info_uri() {
+        let request = GetInfo::default().into_request(HOST).unwrap();
+        assert_eq!(request.uri(), "https://anchor.example.com/sep38/info");
+        assert!(request.headers().get(header::AUTHORIZATION).is_none());
+    }
+
+    #[test]
+    fn it_can_make_a_prices_uri() {
+        let request = GetPrices::new(usdc(), Amount::new(1_000_000_000))
+            .with_buy_delivery_method("PIX")
+            .with_country_code("BRA")
+            .into_request(HOST)
+            .unwrap();
+        assert_eq!(
+            request.uri(),
+            &*format!(
+                "{}/prices?sell_asset=stellar%3AUSDC%3A{}&sell_amount=100.0000000\
+                 &buy_delivery_method=PIX&country_code=BRA",
+                HOST, ISSUER
+            )
+        );
+    }
+
+    #[test]
+    fn it_posts_a_quote_as_json() {
+        let request = PostQuote::buying(
+            QuoteContext::Sep31,
+            AnchorAsset::iso4217("BRL"),
+            usdc(),
+            Amount::new(1_000_000_000),
+        )
+        .with_token("token")
+        .into_request(HOST)
+        .unwrap();
+        assert_eq!(request.method(), "POST");
+        assert_eq!(request.uri(), "https://anchor.example.com/sep38/quote");
+        assert_eq!(request.headers()[header::AUTHORIZATION], "Bearer token");
+        let body: serde_json::Value =
+            serde_json::from_str(request.body().as_str().unwrap()).unwrap();
+        assert_eq!(body["context"], "sep31");
+        assert_eq!(body["sell_asset"], "iso4217:BRL");
+        assert_eq!(body["buy_asset"], &*format!("stellar:USDC:{}", ISSUER));
+        assert_eq!(body["buy_amount"], "100.0000000");
+        assert!(body.get("sell_amount").is_none());
+        assert!(body.get("token").is_none());
+    }
+
+    #[test]
+    fn it_can_make_a_quote_uri() {
+        let request = GetQuote::new("de762cda")
+            .with_token("token")
+            .into_request(HOST)
+            .unwrap();
+        assert_eq!(
+            request.uri(),
+            "https://anchor.example.com/sep38/quote/de762cda"
+        );
+        assert_eq!(request.headers()[header::AUTHORIZATION], "Bearer token");
+    }
+}
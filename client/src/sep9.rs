@@ -0,0 +1,442 @@
+//! The standard KYC and AML fields anchors ask customers for, so that
+//! integrations name and check them the same way. SEP-12 customer uploads
+//! and the interactive flows of SEP-24 both use these names. See
+//! [SEP-9](https://github.com/stellar/stellar-protocol/blob/master/ecosystem/sep-0009.md).
+//!
+//! ```
+//! use stellar_client::endpoint::Body;
+//! use stellar_client::sep9::{Field, KycFields};
+//!
+//! let mut kyc = KycFields::default();
+//! kyc.insert(Field::FirstName, "Ada").unwrap();
+//! kyc.insert(Field::BirthDate, "1815-12-10").unwrap();
+//! assert!(kyc.insert(Field::AddressCountryCode, "England").is_err());
+//!
+//! let body = Body::form(kyc.text_fields());
+//! assert_eq!(body.as_str(), Some("first_name=Ada&birth_date=1815-12-10"));
+//! ```
+use chrono::NaiveDate;
+use serde::de;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::error::Error as StdError;
+use std::fmt;
+use std::net::IpAddr;
+
+/// What a field holds, which decides how its values are checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FieldKind {
+    /// Free text.
+    Text,
+    /// A date as `YYYY-MM-DD`.
+    Date,
+    /// An ISO 3166-1 alpha-3 country code, such as `USA`.
+    CountryCode,
+    /// An ISO 639-1 language code, such as `en`.
+    LanguageCode,
+    /// A phone number in E.164 format, such as `+14155550100`.
+    PhoneNumber,
+    /// An email address.
+    Email,
+    /// An IPv4 or IPv6 address.
+    IpAddress,
+    /// A whole number.
+    Number,
+    /// One of `male`, `female` or `other`.
+    Sex,
+    /// A file, such as a photo of an id, which is uploaded rather than typed.
+    Binary,
+}
+
+macro_rules! fields {
+    ($($(#[$doc:meta])* $variant:ident => $name:expr, $kind:ident;)*) => {
+        /// A standard KYC field.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub enum Field {
+            $($(#[$doc])* $variant,)*
+        }
+
+        impl Field {
+            /// Every standard field, people's before organizations'.
+            pub const ALL: &'static [Field] = &[$(Field::$variant,)*];
+
+            /// The name anchors use for the field.
+            pub fn name(&self) -> &'static str {
+                match *self {
+                    $(Field::$variant => $name,)*
+                }
+            }
+
+            /// What the field holds.
+            pub fn kind(&self) -> FieldKind {
+                match *self {
+                    $(Field::$variant => FieldKind::$kind,)*
+                }
+            }
+        }
+    };
+}
+
+fields! {
+    /// The family or last name.
+    FamilyName => "family_name", Text;
+    /// The last name, the same as `FamilyName`.
+    LastName => "last_name", Text;
+    /// The given or first name.
+    GivenName => "given_name", Text;
+    /// The first name, the same as `GivenName`.
+    FirstName => "first_name", Text;
+    /// Middle names.
+    AdditionalName => "additional_name", Text;
+    /// The country of the current address.
+    AddressCountryCode => "address_country_code", CountryCode;
+    /// The state or province of the current address.
+    StateOrProvince => "state_or_province", Text;
+    /// The city of the current address.
+    City => "city", Text;
+    /// The postal code of the current address.
+    PostalCode => "postal_code", Text;
+    /// The full current address, as a multi line string.
+    Address => "address", Text;
+    /// A mobile phone number.
+    MobileNumber => "mobile_number", PhoneNumber;
+    /// An email address.
+    EmailAddress => "email_address", Email;
+    /// The date of birth.
+    BirthDate => "birth_date", Date;
+    /// The place of birth.
+    BirthPlace => "birth_place", Text;
+    /// The country of birth.
+    BirthCountryCode => "birth_country_code", CountryCode;
+    /// The name of the customer's bank.
+    BankName => "bank_name", Text;
+    /// The type of bank account, such as checking or savings.
+    BankAccountType => "bank_account_type", Text;
+    /// The number of the bank account.
+    BankAccountNumber => "bank_account_number", Text;
+    /// The routing number or BIC of the bank.
+    BankNumber => "bank_number", Text;
+    /// The phone number of the bank.
+    BankPhoneNumber => "bank_phone_number", PhoneNumber;
+    /// The branch of the bank.
+    BankBranchNumber => "bank_branch_number", Text;
+    /// The tax id, such as a social security number.
+    TaxId => "tax_id", Text;
+    /// The name of the tax id, such as `SSN`.
+    TaxIdName => "tax_id_name", Text;
+    /// The occupation as an ISCO code.
+    Occupation => "occupation", Number;
+    /// The name of the employer.
+    EmployerName => "employer_name", Text;
+    /// The address of the employer.
+    EmployerAddress => "employer_address", Text;
+    /// The preferred language.
+    LanguageCode => "language_code", LanguageCode;
+    /// The type of id, such as `passport`.
+    IdType => "id_type", Text;
+    /// The country that issued the id.
+    IdCountryCode => "id_country_code", CountryCode;
+    /// When the id was issued.
+    IdIssueDate => "id_issue_date", Date;
+    /// When the id expires.
+    IdExpirationDate => "id_expiration_date", Date;
+    /// The number of the id.
+    IdNumber => "id_number", Text;
+    /// A photo of the front of the id.
+    PhotoIdFront => "photo_id_front", Binary;
+    /// A photo of the back of the id.
+    PhotoIdBack => "photo_id_back", Binary;
+    /// A notary's approval of the photo id.
+    NotaryApprovalOfPhotoId => "notary_approval_of_photo_id", Binary;
+    /// The ip address of the customer's computer.
+    IpAddress => "ip_address", IpAddress;
+    /// A photo of a utility bill or bank statement with the address.
+    PhotoProofResidence => "photo_proof_residence", Binary;
+    /// The customer's sex.
+    Sex => "sex", Sex;
+    /// A photo of a proof of income.
+    ProofOfIncome => "proof_of_income", Binary;
+    /// A video or image proving the customer is present.
+    ProofOfLiveness => "proof_of_liveness", Binary;
+    /// The referral code of whoever referred the customer.
+    ReferralId => "referral_id", Text;
+    /// The full name of the organization.
+    OrganizationName => "organization.name", Text;
+    /// The VAT number of the organization.
+    OrganizationVatNumber => "organization.VAT_number", Text;
+    /// The registration number of the organization.
+    OrganizationRegistrationNumber => "organization.registration_number", Text;
+    /// When the organization was registered.
+    OrganizationRegistrationDate => "organization.registration_date", Date;
+    /// The registered address of the organization.
+    OrganizationRegisteredAddress => "organization.registered_address", Text;
+    /// The number of shareholders of the organization.
+    OrganizationNumberOfShareholders => "organization.number_of_shareholders", Number;
+    /// The name of a shareholder.
+    OrganizationShareholderName => "organization.shareholder_name", Text;
+    /// The organization's incorporation documents.
+    OrganizationPhotoIncorporationDoc => "organization.photo_incorporation_doc", Binary;
+    /// A photo of a proof of the organization's address.
+    OrganizationPhotoProofAddress => "organization.photo_proof_address", Binary;
+    /// The country of the organization's address.
+    OrganizationAddressCountryCode => "organization.address_country_code", CountryCode;
+    /// The state or province of the organization's address.
+    OrganizationStateOrProvince => "organization.state_or_province", Text;
+    /// The city of the organization's address.
+    OrganizationCity => "organization.city", Text;
+    /// The postal code of the organization's address.
+    OrganizationPostalCode => "organization.postal_code", Text;
+    /// The name of the organization's director.
+    OrganizationDirectorName => "organization.director_name", Text;
+    /// The organization's website.
+    OrganizationWebsite => "organization.website", Text;
+    /// The organization's email address.
+    OrganizationEmail => "organization.email", Email;
+    /// The organization's phone number.
+    OrganizationPhone => "organization.phone", PhoneNumber;
+}
+
+impl Field {
+    /// Looks up a field by the name anchors use for it.
+    pub fn from_name(name: &str) -> Option<Field> {
+        Field::ALL
+            .iter()
+            .find(|field| field.name() == name)
+            .cloned()
+    }
+
+    /// Checks that a value is what the field holds. Files can't be checked
+    /// this way, so binary fields reject every text value.
+    pub fn validate(&self, value: &str) -> Result<(), Error> {
+        let valid = !value.is_empty()
+            && match self.kind() {
+                FieldKind::Text => true,
+                FieldKind::Date => NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok(),
+                FieldKind::CountryCode => {
+                    value.len() == 3 && value.bytes().all(|b| b.is_ascii_uppercase())
+                }
+                FieldKind::LanguageCode => {
+                    value.len() == 2 && value.bytes().all(|b| b.is_ascii_lowercase())
+                }
+                FieldKind::PhoneNumber => match value.strip_prefix('+') {
+                    Some(digits) => {
+                        !digits.is_empty()
+                            && digits.len() <= 15
+                            && digits.bytes().all(|b| b.is_ascii_digit())
+                    }
+                    None => false,
+                },
+                FieldKind::Email => match value.find('@') {
+                    Some(at) => at > 0 && at + 1 < value.len() && !value[at + 1..].contains('@'),
+                    None => false,
+                },
+                FieldKind::IpAddress => value.parse::<IpAddr>().is_ok(),
+                FieldKind::Number => value.bytes().all(|b| b.is_ascii_digit()),
+                FieldKind::Sex => value == "male" || value == "female" || value == "other",
+                FieldKind::Binary => false,
+            };
+        if valid {
+            Ok(())
+        } else {
+            Err(Error { field: *self })
+        }
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Serialize for Field {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D>(d: D) -> Result<Field, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let name = String::deserialize(d)?;
+        Field::from_name(&name)
+            .ok_or_else(|| de::Error::custom(format!("Unknown KYC field {}", name)))
+    }
+}
+
+/// An error for a value that isn't what its field holds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    field: Field,
+}
+
+impl Error {
+    /// The field the value was given for.
+    pub fn field(&self) -> Field {
+        self.field
+    }
+}
+
+impl StdError for Error {
+    fn description(&self) -> &str {
+        match self.field.kind() {
+            FieldKind::Text => "The value is empty",
+            FieldKind::Date => "The value is not a date formatted as YYYY-MM-DD",
+            FieldKind::CountryCode => "The value is not an ISO 3166-1 alpha-3 country code",
+            FieldKind::LanguageCode => "The value is not an ISO 639-1 language code",
+            FieldKind::PhoneNumber => "The value is not a phone number in E.164 format",
+            FieldKind::Email => "The value is not an email address",
+            FieldKind::IpAddress => "The value is not an ip address",
+            FieldKind::Number => "The value is not a number",
+            FieldKind::Sex => "The value is not one of male, female or other",
+            FieldKind::Binary => "The field holds a file rather than text",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid {}: {}", self.field, self.description())
+    }
+}
+
+/// A customer's KYC fields, checked as they're added. Text and files are
+/// kept apart since anchors want the files last in uploads.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KycFields {
+    text: BTreeMap<Field, String>,
+    files: BTreeMap<Field, Vec<u8>>,
+}
+
+impl KycFields {
+    /// Sets a text field, replacing any earlier value, once it's checked.
+    pub fn insert(&mut self, field: Field, value: &str) -> Result<(), Error> {
+        field.validate(value)?;
+        self.text.insert(field, value.to_string());
+        Ok(())
+    }
+
+    /// Sets a binary field to the contents of a file.
+    pub fn insert_file(&mut self, field: Field, contents: Vec<u8>) -> Result<(), Error> {
+        if field.kind() != FieldKind::Binary || contents.is_empty() {
+            return Err(Error { field });
+        }
+        self.files.insert(field, contents);
+        Ok(())
+    }
+
+    /// The value of a text field.
+    pub fn get(&self, field: Field) -> Option<&str> {
+        self.text.get(&field).map(String::as_str)
+    }
+
+    /// The contents of a binary field.
+    pub fn file(&self, field: Field) -> Option<&[u8]> {
+        self.files.get(&field).map(Vec::as_slice)
+    }
+
+    /// The text fields by name, in the order of `Field::ALL`, ready for a
+    /// form or json body.
+    pub fn text_fields<'a>(&'a self) -> impl Iterator<Item = (&'a str, &'a str)> + 'a {
+        self.text
+            .iter()
+            .map(|(field, value)| (field.name(), value.as_str()))
+    }
+
+    /// The binary fields by name, in the order of `Field::ALL`.
+    pub fn files<'a>(&'a self) -> impl Iterator<Item = (&'a str, &'a [u8])> + 'a {
+        self.files
+            .iter()
+            .map(|(field, contents)| (field.name(), contents.as_slice()))
+    }
+
+    /// Whether no fields are set.
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty() && self.files.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod sep9_tests {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn it_names_every_field_uniquely() {
+        for field in Field::ALL {
+            assert_eq!(Field::from_name(field.name()), Some(*field));
+        }
+        assert_eq!(Field::from_name("favorite_color"), None);
+        assert_eq!(
+            Field::OrganizationVatNumber.name(),
+            "organization.VAT_number"
+        );
+    }
+
+    #[test]
+    fn it_validates_values_by_kind() {
+        assert!(Field::BirthDate.validate("1990-02-28").is_ok());
+        assert!(Field::BirthDate.validate("1990-02-30").is_err());
+        assert!(Field::BirthDate.validate("02/28/1990").is_err());
+        assert!(Field::IdCountryCode.validate("BRA").is_ok());
+        assert!(Field::IdCountryCode.validate("BR").is_err());
+        assert!(Field::LanguageCode.validate("pt").is_ok());
+        assert!(Field::LanguageCode.validate("PT").is_err());
+        assert!(Field::MobileNumber.validate("+5511987654321").is_ok());
+        assert!(Field::MobileNumber.validate("11 98765-4321").is_err());
+        assert!(Field::MobileNumber.validate("+").is_err());
+        assert!(Field::EmailAddress.validate("ada@example.com").is_ok());
+        assert!(Field::EmailAddress.validate("ada@").is_err());
+        assert!(Field::IpAddress.validate("2001:db8::1").is_ok());
+        assert!(Field::Occupation.validate("2512").is_ok());
+        assert!(Field::Occupation.validate("engineer").is_err());
+        assert!(Field::Sex.validate("other").is_ok());
+        assert!(Field::FirstName.validate("").is_err());
+        assert!(Field::PhotoIdFront.validate("photo.jpg").is_err());
+    }
+
+    #[test]
+    fn it_keeps_text_and_files_apart() {
+        let mut kyc = KycFields::default();
+        assert!(kyc.is_empty());
+        kyc.insert(Field::LastName, "Lovelace").unwrap();
+        kyc.insert(Field::FirstName, "Ada").unwrap();
+        kyc.insert_file(Field::PhotoIdFront, vec![0xff, 0xd8])
+            .unwrap();
+        assert!(kyc.insert_file(Field::FirstName, vec![1]).is_err());
+
+        let text: Vec<_> = kyc.text_fields().collect();
+        assert_eq!(text, vec![("last_name", "Lovelace"), ("first_name", "Ada")]);
+        assert_eq!(kyc.file(Field::PhotoIdFront), Some(&[0xff, 0xd8][..]));
+        assert_eq!(kyc.files().count(), 1);
+    }
+
+    #[test]
+    fn it_reports_the_invalid_field() {
+        let mut kyc = KycFields::default();
+        let error = kyc.insert(Field::BirthDate, "yesterday").unwrap_err();
+        assert_eq!(error.field(), Field::BirthDate);
+        assert_eq!(
+            error.to_string(),
+            "Invalid birth_date: The value is not a date formatted as YYYY-MM-DD"
+        );
+        assert_eq!(kyc.get(Field::BirthDate), None);
+    }
+
+    #[test]
+    fn it_serializes_fields_by_name() {
+        let fields: Vec<Field> =
+            serde_json::from_str(r#"["first_name", "organization.city"]"#).unwrap();
+        assert_eq!(fields, vec![Field::FirstName, Field::OrganizationCity]);
+        assert_eq!(
+            serde_json::to_string(&Field::IdNumber).unwrap(),
+            r#""id_number""#
+        );
+        assert!(serde_json::from_str::<Field>(r#""shoe_size""#).is_err());
+    }
+}
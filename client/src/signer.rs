@@ -0,0 +1,240 @@
+//! The signer abstraction used when signing transactions.
+//!
+//! Anything that can produce an ed25519 signature over a transaction hash can
+//! act as a signer. The local `Keypair` is the simplest, but the trait is
+//! designed so that hardware wallets such as a Ledger or Trezor can be plugged
+//! in from an external crate without the secret ever leaving the device.
+//!
+//! ```
+//! use stellar_client::keypair::Keypair;
+//! use stellar_client::signer::Signer;
+//!
+//! let keypair = Keypair::random();
+//! let signer: &dyn Signer = &keypair;
+//! let signature = signer.sign_decorated(&[0; 32]).unwrap();
+//! assert_eq!(signature.hint(), keypair.public_key().signature_hint());
+//! ```
+//!
+//! Signers backed by a remote service, such as a KMS or network attached HSM,
+//! implement `AsyncSigner` instead and return a future of the signature.
+use futures::Future;
+use keypair::{self, Keypair, PublicKey};
+use std::error::Error as StdError;
+use std::fmt;
+
+/// Produces signatures over transaction hashes.
+pub trait Signer {
+    /// The public key that signatures will verify against.
+    fn public_key(&self) -> PublicKey;
+
+    /// Signs the 32 byte hash of a transaction, returning the 64 byte
+    /// ed25519 signature.
+    fn sign_hash(&self, hash: &[u8; 32]) -> Result<Vec<u8>, Error>;
+
+    /// Signs the hash and pairs the signature with the hint of the public
+    /// key, ready to be attached to an envelope.
+    fn sign_decorated(&self, hash: &[u8; 32]) -> Result<DecoratedSignature, Error> {
+        let signature = self.sign_hash(hash)?;
+        Ok(DecoratedSignature::new(
+            self.public_key().signature_hint(),
+            signature,
+        ))
+    }
+}
+
+impl Signer for Keypair {
+    fn public_key(&self) -> PublicKey {
+        *Keypair::public_key(self)
+    }
+
+    fn sign_hash(&self, hash: &[u8; 32]) -> Result<Vec<u8>, Error> {
+        self.sign(hash).map_err(Error::from)
+    }
+}
+
+impl<'a, S: Signer + ?Sized> Signer for &'a S {
+    fn public_key(&self) -> PublicKey {
+        (**self).public_key()
+    }
+
+    fn sign_hash(&self, hash: &[u8; 32]) -> Result<Vec<u8>, Error> {
+        (**self).sign_hash(hash)
+    }
+}
+
+/// The future returned by an `AsyncSigner`, resolving to the raw signature.
+pub type SignatureFuture = Box<dyn Future<Item = Vec<u8>, Error = Error> + Send>;
+
+/// Produces signatures over transaction hashes without blocking, for signers
+/// that have to make a round trip to a remote service.
+pub trait AsyncSigner {
+    /// The public key that signatures will verify against.
+    fn public_key(&self) -> PublicKey;
+
+    /// Requests a signature of the 32 byte hash of a transaction.
+    fn sign_hash_async(&self, hash: [u8; 32]) -> SignatureFuture;
+}
+
+impl<'a, S: AsyncSigner + ?Sized> AsyncSigner for &'a S {
+    fn public_key(&self) -> PublicKey {
+        (**self).public_key()
+    }
+
+    fn sign_hash_async(&self, hash: [u8; 32]) -> SignatureFuture {
+        (**self).sign_hash_async(hash)
+    }
+}
+
+/// A signature along with the last four bytes of the signer's public key,
+/// as it is attached to a transaction envelope.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecoratedSignature {
+    hint: [u8; 4],
+    signature: Vec<u8>,
+}
+
+impl DecoratedSignature {
+    /// Constructs a decorated signature from a hint and signature.
+    pub fn new(hint: [u8; 4], signature: Vec<u8>) -> DecoratedSignature {
+        DecoratedSignature { hint, signature }
+    }
+
+    /// The last four bytes of the signer's public key.
+    pub fn hint(&self) -> [u8; 4] {
+        self.hint
+    }
+
+    /// The raw signature.
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+}
+
+/// An error that occurs while producing a signature.
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+}
+
+impl Error {
+    /// Constructs an error for a signer that has no secret to sign with.
+    pub fn missing_secret() -> Error {
+        Error {
+            kind: ErrorKind::MissingSecret,
+        }
+    }
+
+    /// Constructs an error for a signature that was declined, such as by the
+    /// user of a hardware wallet.
+    pub fn rejected() -> Error {
+        Error {
+            kind: ErrorKind::Rejected,
+        }
+    }
+
+    /// Constructs an error for a failure to communicate with a signing
+    /// device or service.
+    pub fn device(message: &str) -> Error {
+        Error {
+            kind: ErrorKind::Device(message.to_string()),
+        }
+    }
+
+    /// Constructs an error for a signature that doesn't verify against the
+    /// signer's public key.
+    pub fn invalid_signature() -> Error {
+        Error {
+            kind: ErrorKind::InvalidSignature,
+        }
+    }
+
+    /// The underlying kind of error.
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+}
+
+/// The reasons that signing can fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The signer has no secret to sign with.
+    MissingSecret,
+    /// The signature was declined.
+    Rejected,
+    /// The signing device or service could not be reached or failed.
+    Device(String),
+    /// The signature returned doesn't verify against the public key.
+    InvalidSignature,
+}
+
+impl From<keypair::Error> for Error {
+    fn from(inner: keypair::Error) -> Error {
+        match inner {
+            keypair::Error::MissingSecret => Error::missing_secret(),
+            keypair::Error::InvalidKey(inner) => Error::device(inner.description()),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn description(&self) -> &str {
+        match self.kind {
+            ErrorKind::MissingSecret => "The signer does not have a secret to sign with",
+            ErrorKind::Rejected => "The signature was rejected",
+            ErrorKind::Device(ref message) => message,
+            ErrorKind::InvalidSignature => "The signature does not match the public key",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+#[cfg(test)]
+mod signer_tests {
+    use super::*;
+
+    struct Declines(PublicKey);
+
+    impl Signer for Declines {
+        fn public_key(&self) -> PublicKey {
+            self.0
+        }
+
+        fn sign_hash(&self, _: &[u8; 32]) -> Result<Vec<u8>, Error> {
+            Err(Error::rejected())
+        }
+    }
+
+    #[test]
+    fn it_signs_with_a_keypair() {
+        let keypair = Keypair::random();
+        let signature = keypair.sign_decorated(&[1; 32]).unwrap();
+        assert!(keypair.verify(&[1; 32], signature.signature()));
+        assert_eq!(signature.hint(), keypair.public_key().signature_hint());
+    }
+
+    #[test]
+    fn it_surfaces_errors_from_other_signers() {
+        let signer = Declines(*Keypair::random().public_key());
+        let signer: &dyn Signer = &signer;
+        assert_eq!(
+            signer.sign_decorated(&[0; 32]).unwrap_err().kind(),
+            &ErrorKind::Rejected
+        );
+    }
+
+    #[test]
+    fn it_errors_without_a_secret() {
+        let keypair = Keypair::from_account_id(
+            "GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ6",
+        ).unwrap();
+        assert_eq!(
+            keypair.sign_hash(&[0; 32]).unwrap_err().kind(),
+            &ErrorKind::MissingSecret
+        );
+    }
+}
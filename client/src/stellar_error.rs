@@ -1,6 +1,7 @@
 use serde::{de, Deserialize, Deserializer};
 use std::error::Error;
 use std::{fmt, str::FromStr};
+use xdr::{self, Node};
 
 /// A resource for the stellar horizon API specific error codes.
 /// These errors adhere to the [Problem Details Standard](https://tools.ietf.org/html/draft-ietf-appsawg-http-problem-00)
@@ -13,6 +14,98 @@ pub struct StellarError {
     status: u16,
     detail: String,
     instance: Option<String>,
+    result_codes: Option<ResultCodes>,
+}
+
+/// The result codes horizon gives for a transaction that failed, naming
+/// what went wrong with the transaction and with each of its operations.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct ResultCodes {
+    transaction: String,
+    #[serde(default)]
+    operations: Vec<String>,
+}
+
+impl ResultCodes {
+    /// The result code of the transaction, such as `tx_insufficient_fee`.
+    pub fn transaction(&self) -> &str {
+        &self.transaction
+    }
+
+    /// The result code of each operation, such as `op_underfunded`. Empty
+    /// when the transaction failed before its operations were applied.
+    pub fn operations(&self) -> &[String] {
+        &self.operations
+    }
+
+    /// The result codes of a base64 encoded `TransactionResult`, named the
+    /// way horizon names them, such as `tx_failed` and `op_underfunded`.
+    /// The operation codes of a fee bump are those of its inner transaction.
+    /// `None` if the result doesn't decode.
+    pub fn from_result_xdr(result_xdr: &str) -> Option<ResultCodes> {
+        let decoded = xdr::decode_as(xdr::Kind::TransactionResult, result_xdr).ok()?;
+        let mut result = decoded.root().get("result")?;
+        let transaction = transaction_code(result.arm()?);
+        // A fee bump carries the result of its inner transaction.
+        if let Some(inner) = result.get("result").and_then(|inner| inner.get("result")) {
+            result = inner;
+        }
+        let operations = match *result {
+            Node::Union(_, ref operations) => operations
+                .elements()
+                .unwrap_or(&[])
+                .iter()
+                .map(operation_code)
+                .collect::<Option<_>>()?,
+            _ => Vec::new(),
+        };
+        Some(ResultCodes {
+            transaction,
+            operations,
+        })
+    }
+}
+
+/// Horizon's name for a transaction result code, `tx_bad_seq` for
+/// `txBAD_SEQ`.
+fn transaction_code(code: &str) -> String {
+    match code {
+        "txBAD_MIN_SEQ_AGE_OR_GAP" => "tx_bad_minseq_age_or_gap".to_string(),
+        _ => format!("tx_{}", code[2..].to_lowercase()),
+    }
+}
+
+/// Horizon's name for the result code of an operation, `op_underfunded` for
+/// a payment that was `PAYMENT_UNDERFUNDED`.
+fn operation_code(result: &Node) -> Option<String> {
+    let (arm, inner) = match *result {
+        Node::Union(ref arm, ref inner) => (arm, inner),
+        _ => return None,
+    };
+    if arm != "opINNER" {
+        return Some(match arm.as_str() {
+            "opNO_ACCOUNT" => "op_no_source_account".to_string(),
+            _ => format!("op_{}", arm[2..].to_lowercase()),
+        });
+    }
+    let (kind, code) = match **inner {
+        Node::Union(ref kind, ref code) => (kind.as_str(), code.arm()?),
+        _ => return None,
+    };
+    // Passive offers share their result codes with sell offers.
+    let prefix = match kind {
+        "CREATE_PASSIVE_SELL_OFFER" => "MANAGE_SELL_OFFER",
+        _ => kind,
+    };
+    if !code.starts_with(prefix) || code.len() <= prefix.len() {
+        return None;
+    }
+    Some(format!("op_{}", code[prefix.len() + 1..].to_lowercase()))
+}
+
+#[derive(Deserialize)]
+struct Extras {
+    result_codes: Option<ResultCodes>,
 }
 
 #[derive(Deserialize)]
@@ -24,6 +117,7 @@ struct Intermediate {
     status: u16,
     detail: String,
     instance: Option<String>,
+    extras: Option<Extras>,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy)]
@@ -68,6 +162,7 @@ impl<'de> Deserialize<'de> for StellarError {
             status: int.status,
             detail: int.detail,
             instance: int.instance,
+            result_codes: int.extras.and_then(|extras| extras.result_codes),
         })
     }
 }
@@ -96,6 +191,22 @@ impl FromStr for Kind {
 }
 
 impl StellarError {
+    /// The error horizon returns when a submitted transaction fails, for a
+    /// transaction found to have failed some other way.
+    pub(crate) fn transaction_failed(result_codes: Option<ResultCodes>) -> StellarError {
+        StellarError {
+            kind: Kind::TransactionFailed,
+            url: "https://stellar.org/horizon-errors/transaction_failed".to_string(),
+            title: "Transaction Failed".to_string(),
+            status: 400,
+            detail: "The transaction failed when submitted to the stellar network. The result \
+                     codes give further details."
+                .to_string(),
+            instance: None,
+            result_codes,
+        }
+    }
+
     /// Returns the kind of error that was returned from stellar.
     pub fn kind(&self) -> Kind {
         self.kind
@@ -106,6 +217,16 @@ impl StellarError {
         &self.url
     }
 
+    /// The result codes of a failed transaction, if horizon gave any.
+    pub fn result_codes(&self) -> Option<&ResultCodes> {
+        self.result_codes.as_ref()
+    }
+
+    /// The result code of a failed transaction, such as `tx_bad_seq`.
+    pub fn transaction_result_code(&self) -> Option<&str> {
+        self.result_codes.as_ref().map(ResultCodes::transaction)
+    }
+
     /// If Horizon cannot understand a request due to invalid parameters, it will return a
     /// bad_request error. This is analogous to the HTTP 400 Error.
     ///
@@ -259,6 +380,37 @@ mod asset_identifier_tests {
         );
     }
 
+    #[test]
+    fn it_parses_result_codes() {
+        let failed: StellarError =
+            serde_json::from_str(include_str!("../fixtures/transaction_failed_error.json"))
+                .unwrap();
+        assert!(failed.is_transaction_failed());
+        assert_eq!(failed.transaction_result_code(), Some("tx_insufficient_fee"));
+        assert!(failed.result_codes().unwrap().operations().is_empty());
+
+        let before_history: StellarError = serde_json::from_str(&before_history_json()).unwrap();
+        assert_eq!(before_history.result_codes(), None);
+    }
+
+    #[test]
+    fn it_names_result_codes_as_horizon_does() {
+        let failed = ResultCodes::from_result_xdr("AAAAAAAAAGT/////AAAAAQAAAAAAAAAB/////gAAAAA=")
+            .unwrap();
+        assert_eq!(failed.transaction(), "tx_failed");
+        assert_eq!(failed.operations(), &["op_underfunded".to_string()]);
+
+        let insufficient_fee = ResultCodes::from_result_xdr("AAAAAAAAAGT////3AAAAAA==").unwrap();
+        assert_eq!(insufficient_fee.transaction(), "tx_insufficient_fee");
+        assert!(insufficient_fee.operations().is_empty());
+
+        let succeeded = ResultCodes::from_result_xdr("AAAAAAAAAGQAAAAAAAAAAQAAAAAAAAABAAAAAAAAAAA=")
+            .unwrap();
+        assert_eq!(succeeded.transaction(), "tx_success");
+        assert_eq!(succeeded.operations(), &["op_success".to_string()]);
+        assert_eq!(ResultCodes::from_result_xdr("AAAA"), None);
+    }
+
     #[test]
     fn it_will_deserialize_unknown_errors() {
         let kind: Kind = serde_json::from_str("\"bad type\"").unwrap();
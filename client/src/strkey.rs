@@ -0,0 +1,211 @@
+//! Encoding and decoding of stellar's "strkey" format.
+//!
+//! Keys on the stellar network are passed around as base32 strings with a
+//! version byte prefix and a CRC16 checksum suffix. An account id starts with
+//! a `G`, a secret seed with an `S` and so on. This module converts between
+//! those strings and the raw bytes that appear inside of XDR.
+use std::error::Error as StdError;
+use std::fmt;
+use zeroize::Zeroizing;
+
+const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// The kind of key being encoded. Each kind has its own version byte which
+/// determines the leading character of the encoded string.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Version {
+    /// An ed25519 public key, encoded with a leading `G`.
+    AccountId,
+    /// A multiplexed account, encoded with a leading `M`.
+    MuxedAccount,
+    /// An ed25519 secret seed, encoded with a leading `S`.
+    Seed,
+    /// The hash of a pre-authorized transaction, encoded with a leading `T`.
+    PreAuthTx,
+    /// A sha256 hash signer, encoded with a leading `X`.
+    Sha256Hash,
+    /// An ed25519 signed payload signer, encoded with a leading `P`.
+    SignedPayload,
+}
+
+impl Version {
+    fn byte(self) -> u8 {
+        match self {
+            Version::AccountId => 6 << 3,
+            Version::MuxedAccount => 12 << 3,
+            Version::Seed => 18 << 3,
+            Version::PreAuthTx => 19 << 3,
+            Version::Sha256Hash => 23 << 3,
+            Version::SignedPayload => 15 << 3,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Option<Version> {
+        match byte {
+            b if b == Version::AccountId.byte() => Some(Version::AccountId),
+            b if b == Version::MuxedAccount.byte() => Some(Version::MuxedAccount),
+            b if b == Version::Seed.byte() => Some(Version::Seed),
+            b if b == Version::PreAuthTx.byte() => Some(Version::PreAuthTx),
+            b if b == Version::Sha256Hash.byte() => Some(Version::Sha256Hash),
+            b if b == Version::SignedPayload.byte() => Some(Version::SignedPayload),
+            _ => None,
+        }
+    }
+}
+
+/// Encodes the payload as a strkey of the given version.
+///
+/// ```
+/// use stellar_client::strkey::{self, Version};
+///
+/// let key = strkey::encode(Version::AccountId, &[0; 32]);
+/// assert_eq!(key, "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF");
+/// ```
+pub fn encode(version: Version, payload: &[u8]) -> String {
+    // Seeds pass through here, so the copy is zeroed once encoded.
+    let mut bytes = Zeroizing::new(Vec::with_capacity(payload.len() + 3));
+    bytes.push(version.byte());
+    bytes.extend_from_slice(payload);
+    let checksum = crc16(&bytes);
+    bytes.push((checksum & 0xff) as u8);
+    bytes.push((checksum >> 8) as u8);
+    base32_encode(&bytes)
+}
+
+/// Decodes a strkey into its version and payload, verifying the checksum
+/// along the way. The bytes decoded on the way to the payload are zeroed, but
+/// the payload itself is the caller's to zero if it's a secret.
+pub fn decode(key: &str) -> Result<(Version, Vec<u8>), DecodeError> {
+    let bytes = base32_decode(key).ok_or(DecodeError::InvalidEncoding)?;
+    if bytes.len() < 3 {
+        return Err(DecodeError::InvalidLength);
+    }
+    let (body, checksum) = bytes.split_at(bytes.len() - 2);
+    let expected = crc16(body);
+    if checksum[0] != (expected & 0xff) as u8 || checksum[1] != (expected >> 8) as u8 {
+        return Err(DecodeError::InvalidChecksum);
+    }
+    let version = Version::from_byte(body[0]).ok_or(DecodeError::InvalidVersion)?;
+    Ok((version, body[1..].to_vec()))
+}
+
+/// Decodes a strkey, requiring it to be of a particular version.
+pub fn decode_version(version: Version, key: &str) -> Result<Vec<u8>, DecodeError> {
+    match decode(key)? {
+        (actual, payload) if actual == version => Ok(payload),
+        _ => Err(DecodeError::InvalidVersion),
+    }
+}
+
+/// The reasons a strkey could fail to decode.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DecodeError {
+    /// The string contains characters outside of the base32 alphabet.
+    InvalidEncoding,
+    /// The decoded bytes are too short to be a key.
+    InvalidLength,
+    /// The checksum at the end of the key does not match its contents.
+    InvalidChecksum,
+    /// The version byte is unknown or not the one that was expected.
+    InvalidVersion,
+}
+
+impl StdError for DecodeError {
+    fn description(&self) -> &str {
+        match *self {
+            DecodeError::InvalidEncoding => "The key is not valid base32",
+            DecodeError::InvalidLength => "The key is too short",
+            DecodeError::InvalidChecksum => "The checksum of the key does not match",
+            DecodeError::InvalidVersion => "The key has an unexpected version byte",
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+// CRC16-XModem, as used by the stellar strkey format.
+fn crc16(bytes: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for byte in bytes {
+        crc ^= u16::from(*byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity((bytes.len() * 8).div_ceil(5));
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for byte in bytes {
+        buffer = (buffer << 8) | u32::from(*byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+fn base32_decode(s: &str) -> Option<Zeroizing<Vec<u8>>> {
+    let mut out = Zeroizing::new(Vec::with_capacity(s.len() * 5 / 8));
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in s.bytes() {
+        let value = ALPHABET.iter().position(|a| *a == c)? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod strkey_tests {
+    use super::*;
+
+    const ACCOUNT: &str = "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3";
+
+    #[test]
+    fn it_round_trips_an_account_id() {
+        let payload = decode_version(Version::AccountId, ACCOUNT).unwrap();
+        assert_eq!(payload.len(), 32);
+        assert_eq!(encode(Version::AccountId, &payload), ACCOUNT);
+    }
+
+    #[test]
+    fn it_rejects_a_bad_checksum() {
+        let key = "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF4";
+        assert_eq!(decode(key).unwrap_err(), DecodeError::InvalidChecksum);
+    }
+
+    #[test]
+    fn it_rejects_the_wrong_version() {
+        assert_eq!(
+            decode_version(Version::Seed, ACCOUNT).unwrap_err(),
+            DecodeError::InvalidVersion
+        );
+    }
+
+    #[test]
+    fn it_rejects_invalid_characters() {
+        assert_eq!(decode("G1!").unwrap_err(), DecodeError::InvalidEncoding);
+    }
+}
@@ -0,0 +1,218 @@
+//! Helpers that keep local state in step with what horizon reports.
+//!
+//! An `OfferTracker` holds the open offers of a single account. Feed it the
+//! offers from `sync::Client::stream` with an `account::Offers` endpoint and
+//! it reports each offer as created, updated, or removed, which is what a
+//! market making bot needs to keep an eye on its own inventory.
+//!
+//! ```no_run
+//! use stellar_client::endpoint::{account, Cursor, Position};
+//! use stellar_client::sync::{Client, Iter};
+//! use stellar_client::tracking::{OfferEvent, OfferTracker};
+//!
+//! let account_id = "GATLAI2D7SSH6PE3HXTDPTRM4RE5VRK6HGA63K5EWP75PSANCZRFDNB5";
+//! let client = Client::horizon_test().unwrap();
+//!
+//! // Start with the offers that are open right now
+//! let mut tracker = OfferTracker::new(account_id);
+//! let open = Iter::new(&client, account::Offers::new(account_id));
+//! tracker.reconcile(open.map(Result::unwrap));
+//!
+//! // Then follow changes as they happen
+//! let stream = client
+//!     .stream(account::Offers::new(account_id).with_cursor(Position::Now))
+//!     .unwrap();
+//! for offer in stream {
+//!     match tracker.apply(offer.unwrap()) {
+//!         Some(OfferEvent::Created(offer)) => println!("opened {}", offer.id()),
+//!         Some(OfferEvent::Updated { current, .. }) => println!("changed {}", current.id()),
+//!         Some(OfferEvent::Removed(offer)) => println!("closed {}", offer.id()),
+//!         None => {}
+//!     }
+//! }
+//! ```
+use resources::{Amount, Offer};
+use std::collections::HashMap;
+
+/// A change to the set of open offers held by an `OfferTracker`.
+#[derive(Debug, Clone)]
+pub enum OfferEvent {
+    /// An offer that wasn't being tracked was opened.
+    Created(Offer),
+    /// The amount or price of an open offer changed, such as when it is
+    /// partially filled.
+    Updated {
+        /// The offer as it was before the change.
+        previous: Offer,
+        /// The offer as it is now.
+        current: Offer,
+    },
+    /// An offer was filled or cancelled.
+    Removed(Offer),
+}
+
+/// Keeps the set of open offers made by an account.
+#[derive(Debug, Clone)]
+pub struct OfferTracker {
+    account_id: String,
+    offers: HashMap<u64, Offer>,
+}
+
+impl OfferTracker {
+    /// Starts tracking the offers of the given account, without any open
+    /// offers.
+    pub fn new(account_id: &str) -> OfferTracker {
+        OfferTracker {
+            account_id: account_id.to_string(),
+            offers: HashMap::new(),
+        }
+    }
+
+    /// The account whose offers are tracked.
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    /// The open offer with the given id.
+    pub fn get(&self, id: u64) -> Option<&Offer> {
+        self.offers.get(&id)
+    }
+
+    /// The open offers, in no particular order.
+    pub fn offers(&self) -> Vec<&Offer> {
+        self.offers.values().collect()
+    }
+
+    /// The number of open offers.
+    pub fn len(&self) -> usize {
+        self.offers.len()
+    }
+
+    /// Returns true if the account has no open offers.
+    pub fn is_empty(&self) -> bool {
+        self.offers.is_empty()
+    }
+
+    /// Records the latest state of an offer, such as one pushed by a
+    /// stream, and returns the change it represents. Offers with no amount
+    /// left are removed. Nothing is returned for offers of other accounts or
+    /// offers that haven't changed.
+    pub fn apply(&mut self, offer: Offer) -> Option<OfferEvent> {
+        if offer.seller() != self.account_id {
+            return None;
+        }
+        if offer.amount() == Amount::new(0) {
+            return self.offers.remove(&offer.id()).map(OfferEvent::Removed);
+        }
+        match self.offers.insert(offer.id(), offer.clone()) {
+            None => Some(OfferEvent::Created(offer)),
+            Some(ref previous) if is_unchanged(previous, &offer) => None,
+            Some(previous) => Some(OfferEvent::Updated {
+                previous,
+                current: offer,
+            }),
+        }
+    }
+
+    /// Replaces the tracked offers with a complete list of the account's
+    /// open offers, such as a freshly fetched page. Horizon doesn't always
+    /// push offers once they are gone, so reconciling after reconnecting is
+    /// how filled and cancelled offers are noticed.
+    pub fn reconcile<I>(&mut self, offers: I) -> Vec<OfferEvent>
+    where
+        I: IntoIterator<Item = Offer>,
+    {
+        let mut events = Vec::new();
+        let mut open = HashMap::new();
+        for offer in offers {
+            if offer.seller() != self.account_id || offer.amount() == Amount::new(0) {
+                continue;
+            }
+            let id = offer.id();
+            match self.offers.remove(&id) {
+                None => events.push(OfferEvent::Created(offer.clone())),
+                Some(ref previous) if is_unchanged(previous, &offer) => {}
+                Some(previous) => events.push(OfferEvent::Updated {
+                    previous,
+                    current: offer.clone(),
+                }),
+            }
+            open.insert(id, offer);
+        }
+        events.extend(self.offers.drain().map(|(_, offer)| OfferEvent::Removed(offer)));
+        self.offers = open;
+        events
+    }
+}
+
+fn is_unchanged(previous: &Offer, current: &Offer) -> bool {
+    previous.amount() == current.amount() && previous.price_ratio() == current.price_ratio()
+}
+
+#[cfg(test)]
+mod offer_tracker_tests {
+    use super::*;
+    use serde_json;
+
+    const SELLER: &str = "GCJ34JYMXNI7N55YREWAACMMZECOMTPIYDTFCQBWPUP7BLJQDDTVGUW4";
+
+    fn offer(id: u64, amount: &str) -> Offer {
+        let json = include_str!("../fixtures/offer.json")
+            .replace("\"id\": 121", &format!("\"id\": {}", id))
+            .replace("\"23.6692509\"", &format!("\"{}\"", amount));
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn it_reports_created_updated_and_removed_offers() {
+        let mut tracker = OfferTracker::new(SELLER);
+        match tracker.apply(offer(1, "10")) {
+            Some(OfferEvent::Created(ref offer)) => assert_eq!(offer.id(), 1),
+            ref event => panic!("Unexpected event {:?}", event),
+        }
+        assert!(tracker.apply(offer(1, "10")).is_none());
+        match tracker.apply(offer(1, "4")) {
+            Some(OfferEvent::Updated {
+                ref previous,
+                ref current,
+            }) => {
+                assert_eq!(previous.amount(), Amount::new(100_000_000));
+                assert_eq!(current.amount(), Amount::new(40_000_000));
+            }
+            ref event => panic!("Unexpected event {:?}", event),
+        }
+        match tracker.apply(offer(1, "0")) {
+            Some(OfferEvent::Removed(ref offer)) => assert_eq!(offer.id(), 1),
+            ref event => panic!("Unexpected event {:?}", event),
+        }
+        assert!(tracker.is_empty());
+    }
+
+    #[test]
+    fn it_ignores_offers_from_other_accounts() {
+        let mut tracker =
+            OfferTracker::new("GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3");
+        assert!(tracker.apply(offer(1, "10")).is_none());
+        assert!(tracker.is_empty());
+    }
+
+    #[test]
+    fn it_removes_offers_missing_from_a_reconciled_list() {
+        let mut tracker = OfferTracker::new(SELLER);
+        tracker.apply(offer(1, "10"));
+        tracker.apply(offer(2, "10"));
+
+        let events = tracker.reconcile(vec![offer(2, "10"), offer(3, "5")]);
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().any(|event| match *event {
+            OfferEvent::Created(ref offer) => offer.id() == 3,
+            _ => false,
+        }));
+        assert!(events.iter().any(|event| match *event {
+            OfferEvent::Removed(ref offer) => offer.id() == 1,
+            _ => false,
+        }));
+        assert_eq!(tracker.len(), 2);
+        assert!(tracker.get(1).is_none());
+    }
+}
@@ -0,0 +1,184 @@
+use super::{Error, FeeStrategy, Operation, TransactionEnvelope};
+use resources::{Account, FeeStats, Memo};
+use xdr::{self, ToXdr, Writer};
+
+/// The maximum number of operations allowed in a single transaction.
+pub const MAX_OPERATIONS: usize = 100;
+
+/// The default fee, in stroops, paid for each operation.
+pub const BASE_FEE: u32 = 100;
+
+/// Builds a transaction from a source account and a list of operations.
+///
+/// ```
+/// use stellar_client::keypair::Keypair;
+/// use stellar_client::network::Network;
+/// use stellar_client::resources::{Amount, AssetIdentifier};
+/// use stellar_client::transaction::{Operation, TransactionBuilder};
+///
+/// let keypair = Keypair::random();
+/// let mut envelope =
+///     TransactionBuilder::new(&keypair.account_id(), 1, Network::TESTNET)
+///         .with_operation(Operation::payment(
+///             "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3",
+///             AssetIdentifier::native(),
+///             Amount::new(10_000_000),
+///         ))
+///         .build()
+///         .unwrap();
+/// envelope.sign(&keypair).unwrap();
+/// assert_eq!(envelope.signatures().len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TransactionBuilder {
+    source_account: String,
+    sequence: i64,
+    network_passphrase: String,
+    base_fee: u32,
+    memo: Memo,
+    time_bounds: Option<(u64, u64)>,
+    operations: Vec<Operation>,
+}
+
+impl TransactionBuilder {
+    /// Starts a transaction from the source account whose current sequence
+    /// number is given. The transaction will use the next sequence number.
+    pub fn new(source_account: &str, sequence: i64, network_passphrase: &str) -> Self {
+        TransactionBuilder {
+            source_account: source_account.to_string(),
+            sequence,
+            network_passphrase: network_passphrase.to_string(),
+            base_fee: BASE_FEE,
+            memo: Memo::None,
+            time_bounds: None,
+            operations: Vec::new(),
+        }
+    }
+
+    /// Starts a transaction from an account fetched from horizon.
+    pub fn from_account(account: &Account, network_passphrase: &str) -> Self {
+        Self::new(account.id(), account.sequence(), network_passphrase)
+    }
+
+    /// Sets the fee, in stroops, paid for each operation.
+    pub fn with_base_fee(mut self, base_fee: u32) -> Self {
+        self.base_fee = base_fee;
+        self
+    }
+
+    /// Sets the fee per operation to the one the strategy picks from the
+    /// network's recent fee stats.
+    pub fn with_fee_strategy<S>(self, strategy: &S, stats: &FeeStats) -> Self
+    where
+        S: FeeStrategy + ?Sized,
+    {
+        let base_fee = strategy.base_fee(stats);
+        self.with_base_fee(base_fee)
+    }
+
+    /// The fee, in stroops, paid for each operation.
+    pub fn base_fee(&self) -> u32 {
+        self.base_fee
+    }
+
+    /// Attaches a memo to the transaction.
+    pub fn with_memo(mut self, memo: Memo) -> Self {
+        self.memo = memo;
+        self
+    }
+
+    /// Restricts the transaction to be valid only between the two unix
+    /// timestamps. A max time of zero means there is no upper bound.
+    pub fn with_time_bounds(mut self, min_time: u64, max_time: u64) -> Self {
+        self.time_bounds = Some((min_time, max_time));
+        self
+    }
+
+    /// Adds an operation to the transaction.
+    pub fn with_operation(mut self, operation: Operation) -> Self {
+        self.operations.push(operation);
+        self
+    }
+
+    /// The operations added so far.
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    /// The total fee the transaction will offer to pay.
+    pub fn fee(&self) -> Option<u32> {
+        self.base_fee.checked_mul(self.operations.len() as u32)
+    }
+
+    /// Builds the unsigned transaction envelope.
+    pub fn build(&self) -> Result<TransactionEnvelope, Error> {
+        if self.operations.is_empty() {
+            return Err(Error::no_operations());
+        }
+        if self.operations.len() > MAX_OPERATIONS {
+            return Err(Error::too_many_operations(self.operations.len()));
+        }
+        let fee = self.fee().ok_or_else(Error::fee_overflow)?;
+        let sequence = self
+            .sequence
+            .checked_add(1)
+            .ok_or_else(Error::sequence_overflow)?;
+
+        let mut w = Writer::new();
+        w.write_account_id(&self.source_account)?;
+        w.write_u32(fee);
+        w.write_i64(sequence);
+        match self.time_bounds {
+            Some((min_time, max_time)) => {
+                w.write_i32(1);
+                w.write_u64(min_time);
+                w.write_u64(max_time);
+            }
+            None => w.write_i32(0),
+        }
+        write_memo(&mut w, &self.memo)?;
+        w.write_u32(self.operations.len() as u32);
+        for operation in &self.operations {
+            operation.to_xdr(&mut w)?;
+        }
+        w.write_i32(0);
+        TransactionEnvelope::new(&self.network_passphrase, w.into_bytes()).map_err(Error::from)
+    }
+}
+
+fn write_memo(w: &mut Writer, memo: &Memo) -> Result<(), xdr::Error> {
+    match *memo {
+        Memo::None => w.write_i32(0),
+        Memo::Text(ref text) => {
+            w.write_i32(1);
+            w.write_string(text, 28)?;
+        }
+        Memo::Id(id) => {
+            w.write_i32(2);
+            w.write_u64(id as u64);
+        }
+        Memo::Hash(ref hash) => {
+            w.write_i32(3);
+            w.write_fixed(&decode_hash(hash, "memo hash")?);
+        }
+        Memo::Return(ref hash) => {
+            w.write_i32(4);
+            w.write_fixed(&decode_hash(hash, "memo hash")?);
+        }
+    }
+    Ok(())
+}
+
+/// Decodes a hex encoded 32 byte hash, such as a memo hash or a liquidity
+/// pool id, naming the value in the error if it isn't one.
+pub(super) fn decode_hash(hex: &str, name: &'static str) -> Result<Vec<u8>, xdr::Error> {
+    if hex.len() != 64 || !hex.is_char_boundary(0) || !hex.is_ascii() {
+        return Err(xdr::Error::invalid_value(name));
+    }
+    (0..32)
+        .map(|i| {
+            u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+                .map_err(|_| xdr::Error::invalid_value(name))
+        })
+        .collect()
+}
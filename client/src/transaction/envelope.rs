@@ -0,0 +1,321 @@
+use super::{Error, Operation, MAX_OPERATIONS};
+use futures::Future;
+use network;
+use resources::Memo;
+use sha2::{Digest, Sha256};
+use signer::{self, AsyncSigner, DecoratedSignature, Signer};
+use base64;
+use xdr::{self, Reader, ToXdr, TryFromXdr, Writer};
+
+const ENVELOPE_TYPE_TX_V0: i32 = 0;
+const ENVELOPE_TYPE_TX: i32 = 2;
+
+/// The parts of a transaction, decoded from the bytes that get signed.
+#[derive(Debug, Clone, PartialEq)]
+struct Body {
+    source_account: String,
+    fee: u32,
+    sequence: i64,
+    time_bounds: Option<(u64, u64)>,
+    ledger_bounds: Option<(u32, u32)>,
+    min_sequence: Option<i64>,
+    min_sequence_age: u64,
+    min_sequence_ledger_gap: u32,
+    extra_signers: Vec<String>,
+    memo: Memo,
+    operations: Vec<Operation>,
+}
+
+fn time_bounds(r: &mut Reader) -> Result<(u64, u64), xdr::Error> {
+    Ok((r.read_u64()?, r.read_u64()?))
+}
+
+fn memo(r: &mut Reader) -> Result<Memo, xdr::Error> {
+    match r.read_i32()? {
+        0 => Ok(Memo::None),
+        // Text memos aren't required to be utf-8, which horizon also
+        // papers over.
+        1 => Ok(Memo::Text(
+            String::from_utf8_lossy(r.read_var(28)?).into_owned(),
+        )),
+        2 => Ok(Memo::Id(r.read_u64()? as i64)),
+        3 => Ok(Memo::Hash(xdr::hex(r.read_fixed(32)?))),
+        4 => Ok(Memo::Return(xdr::hex(r.read_fixed(32)?))),
+        v => Err(xdr::Error::unknown_discriminant("MemoType", v)),
+    }
+}
+
+impl TryFromXdr for Body {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self, xdr::Error> {
+        let source_account = xdr::muxed_account(r)?;
+        let fee = r.read_u32()?;
+        let sequence = r.read_i64()?;
+        let mut body = Body {
+            source_account,
+            fee,
+            sequence,
+            time_bounds: None,
+            ledger_bounds: None,
+            min_sequence: None,
+            min_sequence_age: 0,
+            min_sequence_ledger_gap: 0,
+            extra_signers: Vec::new(),
+            memo: Memo::None,
+            operations: Vec::new(),
+        };
+        match r.read_i32()? {
+            0 => {}
+            1 => body.time_bounds = Some(time_bounds(r)?),
+            2 => {
+                body.time_bounds = xdr::optional(r, time_bounds)?;
+                body.ledger_bounds = xdr::optional(r, |r| Ok((r.read_u32()?, r.read_u32()?)))?;
+                body.min_sequence = xdr::optional(r, |r| r.read_i64())?;
+                body.min_sequence_age = r.read_u64()?;
+                body.min_sequence_ledger_gap = r.read_u32()?;
+                body.extra_signers = xdr::array(r, 2, xdr::signer_key)?;
+            }
+            v => return Err(xdr::Error::unknown_discriminant("PreconditionType", v)),
+        }
+        body.memo = memo(r)?;
+        body.operations = xdr::array(r, MAX_OPERATIONS, Operation::try_from_xdr)?;
+        match r.read_i32()? {
+            0 => Ok(body),
+            v => Err(xdr::Error::unknown_discriminant("ext", v)),
+        }
+    }
+}
+
+/// A built transaction along with the signatures collected for it. The
+/// envelope is what gets submitted to the network.
+///
+/// The parts of the transaction, such as its operations and memo, are
+/// decoded from the bytes that are hashed and signed, so an envelope from
+/// elsewhere can be audited before it's signed or submitted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionEnvelope {
+    network_passphrase: String,
+    tx: Vec<u8>,
+    body: Body,
+    signatures: Vec<DecoratedSignature>,
+}
+
+impl TransactionEnvelope {
+    pub(super) fn new(
+        network_passphrase: &str,
+        tx: Vec<u8>,
+    ) -> Result<TransactionEnvelope, xdr::Error> {
+        let body = Body::try_from_xdr(&mut Reader::new(&tx))?;
+        Ok(TransactionEnvelope {
+            network_passphrase: network_passphrase.to_string(),
+            tx,
+            body,
+            signatures: Vec::new(),
+        })
+    }
+
+    /// Decodes an envelope encoded as base64 XDR, such as one passed around
+    /// to collect signatures from co-signers or the `envelope_xdr` of a
+    /// transaction from horizon. The network passphrase isn't part of the
+    /// XDR, so it has to be given to hash the transaction.
+    ///
+    /// Envelopes of the older type without preconditions are upgraded to
+    /// the current type, which hashes the same, so they encode differently
+    /// but keep their signatures. Fee bump envelopes aren't supported.
+    pub fn from_base64(
+        xdr: &str,
+        network_passphrase: &str,
+    ) -> Result<TransactionEnvelope, xdr::Error> {
+        let bytes = base64::decode(xdr.trim())?;
+        let bytes = match Reader::new(&bytes).read_i32()? {
+            // The older type starts with a bare ed25519 key and has optional
+            // time bounds where the current one has preconditions. Its
+            // discriminant doubles as the key's type, and that of the time
+            // bounds as the one for time bound preconditions, so the bytes
+            // read as a transaction of the current type as they are.
+            ENVELOPE_TYPE_TX_V0 => bytes,
+            ENVELOPE_TYPE_TX => bytes[4..].to_vec(),
+            v => return Err(xdr::Error::unknown_discriminant("EnvelopeType", v)),
+        };
+        let mut r = Reader::new(&bytes);
+        let body = Body::try_from_xdr(&mut r)?;
+        let tx = bytes[..bytes.len() - r.remaining()].to_vec();
+
+        let mut envelope = TransactionEnvelope {
+            network_passphrase: network_passphrase.to_string(),
+            tx,
+            body,
+            signatures: Vec::new(),
+        };
+        for _ in 0..r.read_len(20)? {
+            let mut hint = [0; 4];
+            hint.copy_from_slice(r.read_fixed(4)?);
+            let signature = r.read_var(64)?.to_vec();
+            envelope.add_signature(DecoratedSignature::new(hint, signature));
+        }
+        r.finish()?;
+        Ok(envelope)
+    }
+
+    /// The account that pays the fee and whose sequence number is used,
+    /// as a `G` address or, if the transaction names a muxed account, an
+    /// `M` address.
+    pub fn source_account(&self) -> &str {
+        &self.body.source_account
+    }
+
+    /// The most the transaction may be charged, in stroops.
+    pub fn fee(&self) -> u32 {
+        self.body.fee
+    }
+
+    /// The sequence number the transaction uses.
+    pub fn sequence(&self) -> i64 {
+        self.body.sequence
+    }
+
+    /// The unix timestamps the transaction is valid between, if it has time
+    /// bounds. A max time of zero means there is no upper bound.
+    pub fn time_bounds(&self) -> Option<(u64, u64)> {
+        self.body.time_bounds
+    }
+
+    /// The ledgers the transaction is valid between, if it has ledger
+    /// bounds. A max ledger of zero means there is no upper bound.
+    pub fn ledger_bounds(&self) -> Option<(u32, u32)> {
+        self.body.ledger_bounds
+    }
+
+    /// The lowest sequence number the source account may have for the
+    /// transaction to be valid, when it isn't the one just before.
+    pub fn min_sequence(&self) -> Option<i64> {
+        self.body.min_sequence
+    }
+
+    /// How many seconds the source account's sequence number must have
+    /// gone unchanged for.
+    pub fn min_sequence_age(&self) -> u64 {
+        self.body.min_sequence_age
+    }
+
+    /// How many ledgers the source account's sequence number must have
+    /// gone unchanged for.
+    pub fn min_sequence_ledger_gap(&self) -> u32 {
+        self.body.min_sequence_ledger_gap
+    }
+
+    /// The strkey encoded signers that must sign the transaction on top of
+    /// the usual thresholds.
+    pub fn extra_signers(&self) -> &[String] {
+        &self.body.extra_signers
+    }
+
+    /// The memo attached to the transaction.
+    pub fn memo(&self) -> &Memo {
+        &self.body.memo
+    }
+
+    /// The operations of the transaction, in the order they're applied.
+    pub fn operations(&self) -> &[Operation] {
+        &self.body.operations
+    }
+
+    /// The passphrase of the network the transaction is signed for.
+    pub fn network_passphrase(&self) -> &str {
+        &self.network_passphrase
+    }
+
+    /// The hash of the transaction, which is what signers sign. It commits
+    /// to the network so that a transaction can't be replayed on another.
+    pub fn hash(&self) -> [u8; 32] {
+        let mut writer = Writer::new();
+        writer.write_fixed(&network::network_id(&self.network_passphrase));
+        writer.write_i32(ENVELOPE_TYPE_TX);
+        writer.write_fixed(&self.tx);
+
+        let mut hash = [0; 32];
+        hash.copy_from_slice(&Sha256::digest(writer.as_bytes()));
+        hash
+    }
+
+    /// The hex encoded hash of the transaction, as horizon displays it.
+    pub fn hash_hex(&self) -> String {
+        xdr::hex(&self.hash())
+    }
+
+    /// Signs the transaction with the signer, adding the signature to the
+    /// envelope.
+    pub fn sign(&mut self, signer: &dyn Signer) -> Result<(), signer::Error> {
+        let signature = signer.sign_decorated(&self.hash())?;
+        self.add_signature(signature);
+        Ok(())
+    }
+
+    /// Requests a signature from a remote signer, resolving to the envelope
+    /// with the signature added. The signature is checked against the
+    /// signer's public key before it's accepted.
+    pub fn sign_async(
+        self,
+        signer: &dyn AsyncSigner,
+    ) -> Box<dyn Future<Item = TransactionEnvelope, Error = signer::Error> + Send> {
+        let hash = self.hash();
+        let public_key = signer.public_key();
+        let mut envelope = self;
+        Box::new(signer.sign_hash_async(hash).and_then(move |signature| {
+            if !public_key.verify(&hash, &signature) {
+                return Err(signer::Error::invalid_signature());
+            }
+            envelope.add_signature(DecoratedSignature::new(
+                public_key.signature_hint(),
+                signature,
+            ));
+            Ok(envelope)
+        }))
+    }
+
+    /// Adds the signatures from another copy of this envelope, such as one
+    /// that was sent off to be signed by a remote service. Fails if the other
+    /// envelope is for a different transaction.
+    pub fn merge_signatures(&mut self, other: &TransactionEnvelope) -> Result<(), Error> {
+        if self.network_passphrase != other.network_passphrase || self.tx != other.tx {
+            return Err(Error::transaction_mismatch());
+        }
+        for signature in &other.signatures {
+            self.add_signature(signature.clone());
+        }
+        Ok(())
+    }
+
+    /// Adds a signature that was produced elsewhere, such as by a co-signer.
+    /// A signature that is already present is not added twice.
+    pub fn add_signature(&mut self, signature: DecoratedSignature) {
+        if !self.signatures.contains(&signature) {
+            self.signatures.push(signature);
+        }
+    }
+
+    /// The signatures collected so far.
+    pub fn signatures(&self) -> &[DecoratedSignature] {
+        &self.signatures
+    }
+
+    /// Encodes the envelope as base64 XDR, ready for submission.
+    pub fn to_base64(&self) -> Result<String, xdr::Error> {
+        ToXdr::to_base64(self)
+    }
+}
+
+impl ToXdr for TransactionEnvelope {
+    fn to_xdr(&self, w: &mut Writer) -> Result<(), xdr::Error> {
+        if self.signatures.len() > 20 {
+            return Err(xdr::Error::invalid_length(self.signatures.len(), 20));
+        }
+        w.write_i32(ENVELOPE_TYPE_TX);
+        w.write_fixed(&self.tx);
+        w.write_u32(self.signatures.len() as u32);
+        for signature in &self.signatures {
+            w.write_fixed(&signature.hint());
+            w.write_var(signature.signature(), 64)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,172 @@
+use resources::{FeeStats, Percentile};
+
+/// Picks the fee to offer per operation, in stroops, from the network's
+/// recent fee stats, fetched with `endpoint::fee::Stats`. Offering more than
+/// the base fee gets a transaction into a ledger when the network is busy,
+/// since full ledgers take the transactions offering the most.
+///
+/// ```
+/// use stellar_client::network::Network;
+/// use stellar_client::resources::{FeeDistribution, FeeStats, Percentile};
+/// use stellar_client::transaction::{CappedFee, PercentileFee, TransactionBuilder};
+///
+/// let stats = FeeStats::new(1000, 100).with_fee_charged(
+///     FeeDistribution::flat(100).with_percentile(Percentile::P90, 5000),
+/// );
+/// let strategy = CappedFee::new(PercentileFee::new(Percentile::P90).with_multiplier(1.5), 2000);
+/// let builder = TransactionBuilder::new(
+///     "GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ6",
+///     1,
+///     Network::TESTNET,
+/// ).with_fee_strategy(&strategy, &stats);
+/// assert_eq!(builder.base_fee(), 2000);
+/// ```
+pub trait FeeStrategy {
+    /// The fee per operation to offer given the recent fee stats.
+    fn base_fee(&self, stats: &FeeStats) -> u32;
+}
+
+/// Always offers the same fee, whatever the network is charging.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedFee {
+    fee: u32,
+}
+
+impl FixedFee {
+    /// Offers the given fee per operation.
+    pub fn new(fee: u32) -> Self {
+        FixedFee { fee }
+    }
+}
+
+impl FeeStrategy for FixedFee {
+    fn base_fee(&self, _stats: &FeeStats) -> u32 {
+        self.fee
+    }
+}
+
+/// Offers what a percentile of recent transactions were charged, scaled by a
+/// multiplier. The median gets a transaction in about as quickly as most,
+/// while the 90th percentile with some headroom holds up under surge
+/// pricing. It never offers less than the last ledger's base fee.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PercentileFee {
+    percentile: Percentile,
+    multiplier: f64,
+}
+
+impl PercentileFee {
+    /// Offers the fee charged at the given percentile.
+    pub fn new(percentile: Percentile) -> Self {
+        PercentileFee {
+            percentile,
+            multiplier: 1.0,
+        }
+    }
+
+    /// Scales the fee, such as by 1.5 to outbid a rising market.
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+}
+
+impl FeeStrategy for PercentileFee {
+    fn base_fee(&self, stats: &FeeStats) -> u32 {
+        let charged = stats.fee_charged().percentile(self.percentile) as f64;
+        let fee = (charged * self.multiplier).ceil();
+        let fee = if fee >= f64::from(u32::MAX) {
+            u32::MAX
+        } else if fee > 0.0 {
+            fee as u32
+        } else {
+            0
+        };
+        fee.max(stats.last_ledger_base_fee())
+    }
+}
+
+/// Limits the fee another strategy picks, so that surge pricing can't run
+/// up the bill. A cap below the network's base fee makes transactions fail.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CappedFee<S> {
+    strategy: S,
+    max: u32,
+}
+
+impl<S: FeeStrategy> CappedFee<S> {
+    /// Offers the fee the strategy picks, but at most `max`.
+    pub fn new(strategy: S, max: u32) -> Self {
+        CappedFee { strategy, max }
+    }
+}
+
+impl<S: FeeStrategy> FeeStrategy for CappedFee<S> {
+    fn base_fee(&self, stats: &FeeStats) -> u32 {
+        self.strategy.base_fee(stats).min(self.max)
+    }
+}
+
+impl<'a, S: FeeStrategy + ?Sized> FeeStrategy for &'a S {
+    fn base_fee(&self, stats: &FeeStats) -> u32 {
+        (**self).base_fee(stats)
+    }
+}
+
+impl<S: FeeStrategy + ?Sized> FeeStrategy for Box<S> {
+    fn base_fee(&self, stats: &FeeStats) -> u32 {
+        (**self).base_fee(stats)
+    }
+}
+
+#[cfg(test)]
+mod fee_strategy_tests {
+    use super::*;
+    use resources::FeeDistribution;
+
+    fn surge() -> FeeStats {
+        FeeStats::new(1000, 100).with_fee_charged(
+            FeeDistribution::flat(100)
+                .with_percentile(Percentile::P50, 150)
+                .with_percentile(Percentile::P90, 1201),
+        )
+    }
+
+    #[test]
+    fn it_offers_a_fixed_fee() {
+        assert_eq!(FixedFee::new(300).base_fee(&surge()), 300);
+    }
+
+    #[test]
+    fn it_offers_a_percentile() {
+        assert_eq!(PercentileFee::new(Percentile::P50).base_fee(&surge()), 150);
+        let strategy = PercentileFee::new(Percentile::P90).with_multiplier(1.5);
+        assert_eq!(strategy.base_fee(&surge()), 1802);
+    }
+
+    #[test]
+    fn it_offers_at_least_the_base_fee() {
+        let strategy = PercentileFee::new(Percentile::P50).with_multiplier(0.1);
+        assert_eq!(strategy.base_fee(&surge()), 100);
+    }
+
+    #[test]
+    fn it_saturates_huge_fees() {
+        let stats = FeeStats::new(1000, 100).with_fee_charged(FeeDistribution::flat(u64::MAX));
+        assert_eq!(
+            PercentileFee::new(Percentile::P99).base_fee(&stats),
+            u32::MAX
+        );
+    }
+
+    #[test]
+    fn it_caps_another_strategy() {
+        let strategy = CappedFee::new(PercentileFee::new(Percentile::P90), 1000);
+        assert_eq!(strategy.base_fee(&surge()), 1000);
+        let strategy = CappedFee::new(PercentileFee::new(Percentile::P50), 1000);
+        assert_eq!(strategy.base_fee(&surge()), 150);
+
+        let boxed: Box<dyn FeeStrategy> = Box::new(strategy);
+        assert_eq!(boxed.base_fee(&surge()), 150);
+    }
+}
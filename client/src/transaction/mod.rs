@@ -0,0 +1,450 @@
+//! Building and signing transactions.
+//!
+//! A transaction is assembled with a `TransactionBuilder`, which produces an
+//! unsigned `TransactionEnvelope`. Signatures are then added to the envelope
+//! with anything that implements `Signer`, such as a local `Keypair` or a
+//! hardware wallet, before it is encoded for submission. Remote signers are
+//! supported through `TransactionEnvelope::sign_async`.
+use std::error::Error as StdError;
+use std::fmt;
+use xdr;
+
+mod builder;
+mod envelope;
+mod fee;
+mod operation;
+mod signers;
+
+pub use self::builder::{TransactionBuilder, BASE_FEE, MAX_OPERATIONS};
+pub use self::envelope::TransactionEnvelope;
+pub use self::fee::{CappedFee, FeeStrategy, FixedFee, PercentileFee};
+pub use self::operation::{Operation, SetOptions};
+pub use self::signers::{SignerChange, SignerReport, SignerSetup};
+
+/// An error that occurs while building a transaction.
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+}
+
+impl Error {
+    /// Constructs an error for a transaction without any operations.
+    pub fn no_operations() -> Error {
+        Error {
+            kind: ErrorKind::NoOperations,
+        }
+    }
+
+    /// Constructs an error for a transaction with more operations than the
+    /// network allows.
+    pub fn too_many_operations(count: usize) -> Error {
+        Error {
+            kind: ErrorKind::TooManyOperations(count),
+        }
+    }
+
+    /// Constructs an error for a fee that doesn't fit in 32 bits.
+    pub fn fee_overflow() -> Error {
+        Error {
+            kind: ErrorKind::FeeOverflow,
+        }
+    }
+
+    /// Constructs an error for a source account whose sequence number is
+    /// already the highest there is, so no transaction can follow it.
+    pub fn sequence_overflow() -> Error {
+        Error {
+            kind: ErrorKind::SequenceOverflow,
+        }
+    }
+
+    /// Constructs an error for merging signatures from an envelope of a
+    /// different transaction.
+    pub fn transaction_mismatch() -> Error {
+        Error {
+            kind: ErrorKind::TransactionMismatch,
+        }
+    }
+
+    /// Constructs an error for signer changes that would leave the ed25519
+    /// keys of an account with less weight than its high threshold.
+    pub fn locked_out(weight: u32, high: u8) -> Error {
+        Error {
+            kind: ErrorKind::LockedOut(weight, high),
+        }
+    }
+
+    /// Constructs an error for a signer weight or threshold above 255.
+    pub fn weight_out_of_range(value: u32) -> Error {
+        Error {
+            kind: ErrorKind::WeightOutOfRange(value),
+        }
+    }
+
+    /// The underlying kind of error.
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+}
+
+/// The reasons that building a transaction can fail.
+#[derive(Debug)]
+pub enum ErrorKind {
+    /// The transaction has no operations.
+    NoOperations,
+    /// The transaction has more than `MAX_OPERATIONS` operations.
+    TooManyOperations(usize),
+    /// The total fee is too large.
+    FeeOverflow,
+    /// The source account's sequence number has no successor.
+    SequenceOverflow,
+    /// The envelopes being merged are for different transactions.
+    TransactionMismatch,
+    /// The signers of an account would have the given weight, which is less
+    /// than the given high threshold, locking the account out.
+    LockedOut(u32, u8),
+    /// A signer weight or threshold is above 255, the largest the network
+    /// allows.
+    WeightOutOfRange(u32),
+    /// A value in the transaction could not be encoded.
+    Xdr(xdr::Error),
+}
+
+impl From<xdr::Error> for Error {
+    fn from(inner: xdr::Error) -> Error {
+        Error {
+            kind: ErrorKind::Xdr(inner),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn description(&self) -> &str {
+        match self.kind {
+            ErrorKind::NoOperations => "The transaction has no operations",
+            ErrorKind::TooManyOperations(_) => "The transaction has too many operations",
+            ErrorKind::FeeOverflow => "The transaction fee is too large",
+            ErrorKind::SequenceOverflow => "The account's sequence number can't be bumped",
+            ErrorKind::TransactionMismatch => "The envelopes are for different transactions",
+            ErrorKind::LockedOut(_, _) => "The account would be locked out",
+            ErrorKind::WeightOutOfRange(_) => "A signer weight or threshold is above 255",
+            ErrorKind::Xdr(ref inner) => inner.description(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ErrorKind::TooManyOperations(count) => write!(
+                f,
+                "The transaction has {} operations where at most {} are allowed",
+                count, MAX_OPERATIONS
+            ),
+            ErrorKind::LockedOut(weight, high) => write!(
+                f,
+                "The account's signers would have a weight of {} where the high threshold is {}",
+                weight, high
+            ),
+            ErrorKind::WeightOutOfRange(value) => write!(
+                f,
+                "A signer weight or threshold of {} is above the largest of 255",
+                value
+            ),
+            ErrorKind::Xdr(ref inner) => write!(f, "{}", inner),
+            _ => f.write_str(self.description()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod transaction_builder_tests {
+    use super::*;
+    use futures::{future, Future};
+    use keypair::{Keypair, PublicKey};
+    use network::Network;
+    use signer::{self, AsyncSigner, SignatureFuture, Signer};
+    use resources::{Amount, AssetIdentifier, Memo, PriceRatio};
+    use base64;
+    use xdr::{Kind, Writer};
+
+    const SEED: &str = "SBGWSG6BTNCKCOB3DIFBGCVMUPQFYPA2G4O34RMTB343OYPXU5DJDVMN";
+    const DESTINATION: &str = "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3";
+
+    fn builder() -> TransactionBuilder {
+        let keypair = Keypair::from_secret_seed(SEED).unwrap();
+        TransactionBuilder::new(&keypair.account_id(), 41, Network::TESTNET)
+    }
+
+    #[test]
+    fn it_builds_an_envelope_the_inspector_can_read() {
+        let envelope = builder()
+            .with_memo(Memo::Text("hello".to_string()))
+            .with_operation(Operation::payment(
+                DESTINATION,
+                AssetIdentifier::native(),
+                Amount::new(10_000_000),
+            ))
+            .with_operation(Operation::manage_sell_offer(
+                AssetIdentifier::alphanum4("USD", DESTINATION),
+                AssetIdentifier::native(),
+                Amount::new(5),
+                PriceRatio::new(1, 2),
+            ).with_offer_id(7))
+            .with_operation(Operation::set_options(
+                SetOptions::default()
+                    .with_home_domain("example.com")
+                    .with_signer(DESTINATION, 1),
+            ))
+            .build()
+            .unwrap();
+
+        let decoded = xdr::decode_as(Kind::TransactionEnvelope, &envelope.to_base64().unwrap())
+            .unwrap();
+        let tx = decoded.root().get("tx").unwrap();
+        assert_eq!(tx.get("fee").and_then(xdr::Node::as_i64), Some(300));
+        assert_eq!(tx.get("seqNum").and_then(xdr::Node::as_i64), Some(42));
+        let operations = tx.get("operations").and_then(xdr::Node::elements).unwrap();
+        assert_eq!(operations[1].get("body").unwrap().arm(), Some("MANAGE_SELL_OFFER"));
+        assert_eq!(
+            operations[2].get("body").and_then(|b| b.get("homeDomain")),
+            Some(&xdr::Node::Optional(Some(Box::new(xdr::Node::Text(
+                "example.com".to_string()
+            )))))
+        );
+    }
+
+    #[test]
+    fn it_builds_liquidity_pool_operations() {
+        let pool_id = "dd7b1ab831c273310ddbec6f97870aa83c2fbd78ce22aded37ecbf4f3380fac7";
+        let envelope = builder()
+            .with_operation(Operation::change_trust_pool_share(
+                AssetIdentifier::native(),
+                AssetIdentifier::alphanum4("USD", DESTINATION),
+                30,
+            ))
+            .with_operation(Operation::liquidity_pool_deposit(
+                pool_id,
+                Amount::new(10),
+                Amount::new(40),
+                PriceRatio::new(99, 400),
+                PriceRatio::new(101, 400),
+            ))
+            .with_operation(Operation::liquidity_pool_withdraw(
+                pool_id,
+                Amount::new(5),
+                Amount::new(1),
+                Amount::new(4),
+            ))
+            .build()
+            .unwrap();
+
+        let decoded = xdr::decode_as(Kind::TransactionEnvelope, &envelope.to_base64().unwrap())
+            .unwrap();
+        let tx = decoded.root().get("tx").unwrap();
+        let operations = tx.get("operations").and_then(xdr::Node::elements).unwrap();
+        let arms: Vec<_> = operations
+            .iter()
+            .map(|op| op.get("body").unwrap().arm().unwrap())
+            .collect();
+        assert_eq!(
+            arms,
+            vec!["CHANGE_TRUST", "LIQUIDITY_POOL_DEPOSIT", "LIQUIDITY_POOL_WITHDRAW"]
+        );
+
+        let invalid = builder()
+            .with_operation(Operation::liquidity_pool_withdraw(
+                "abc",
+                Amount::new(5),
+                Amount::new(1),
+                Amount::new(4),
+            ))
+            .build();
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn it_signs_the_hash_of_the_transaction() {
+        let keypair = Keypair::from_secret_seed(SEED).unwrap();
+        let mut envelope = builder()
+            .with_operation(Operation::bump_sequence(100))
+            .build()
+            .unwrap();
+        envelope.sign(&keypair).unwrap();
+        envelope.sign(&keypair).unwrap();
+
+        assert_eq!(envelope.signatures().len(), 1);
+        assert!(keypair.verify(&envelope.hash(), envelope.signatures()[0].signature()));
+    }
+
+    struct Remote(Keypair);
+
+    impl AsyncSigner for Remote {
+        fn public_key(&self) -> PublicKey {
+            *self.0.public_key()
+        }
+
+        fn sign_hash_async(&self, hash: [u8; 32]) -> SignatureFuture {
+            Box::new(future::result(self.0.sign_hash(&hash)))
+        }
+    }
+
+    struct Forgetful(Keypair);
+
+    impl AsyncSigner for Forgetful {
+        fn public_key(&self) -> PublicKey {
+            *self.0.public_key()
+        }
+
+        fn sign_hash_async(&self, _: [u8; 32]) -> SignatureFuture {
+            Box::new(future::result(self.0.sign_hash(&[0; 32])))
+        }
+    }
+
+    #[test]
+    fn it_merges_signatures_from_a_remote_signer() {
+        let local = Keypair::from_secret_seed(SEED).unwrap();
+        let remote = Remote(Keypair::random());
+        let mut envelope = builder()
+            .with_operation(Operation::bump_sequence(100))
+            .build()
+            .unwrap();
+
+        let signed = envelope.clone().sign_async(&remote).wait().unwrap();
+        envelope.sign(&local).unwrap();
+        envelope.merge_signatures(&signed).unwrap();
+
+        assert_eq!(envelope.signatures().len(), 2);
+        assert!(remote.0.verify(&envelope.hash(), envelope.signatures()[1].signature()));
+    }
+
+    #[test]
+    fn it_rejects_remote_signatures_that_dont_verify() {
+        let envelope = builder()
+            .with_operation(Operation::bump_sequence(100))
+            .build()
+            .unwrap();
+        let error = envelope
+            .sign_async(&Forgetful(Keypair::random()))
+            .wait()
+            .unwrap_err();
+        assert_eq!(error.kind(), &signer::ErrorKind::InvalidSignature);
+    }
+
+    #[test]
+    fn it_wont_merge_different_transactions() {
+        let mut envelope = builder()
+            .with_operation(Operation::bump_sequence(100))
+            .build()
+            .unwrap();
+        let other = builder()
+            .with_operation(Operation::bump_sequence(101))
+            .build()
+            .unwrap();
+        match *envelope.merge_signatures(&other).unwrap_err().kind() {
+            ErrorKind::TransactionMismatch => {}
+            ref kind => panic!("Unexpected error {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn it_decodes_the_envelopes_it_encodes() {
+        let keypair = Keypair::from_secret_seed(SEED).unwrap();
+        let mut envelope = builder()
+            .with_operation(Operation::set_options(
+                SetOptions::default()
+                    .with_signer(DESTINATION, 1)
+                    .with_thresholds(1, 2, 2),
+            ))
+            .build()
+            .unwrap();
+        let unsigned = envelope.to_base64().unwrap();
+        envelope.sign(&keypair).unwrap();
+
+        let decoded =
+            TransactionEnvelope::from_base64(&envelope.to_base64().unwrap(), Network::TESTNET)
+                .unwrap();
+        assert_eq!(decoded, envelope);
+        let mut unsigned =
+            TransactionEnvelope::from_base64(&unsigned, Network::TESTNET).unwrap();
+        assert!(unsigned.signatures().is_empty());
+        unsigned.merge_signatures(&decoded).unwrap();
+        assert_eq!(unsigned, envelope);
+    }
+
+    #[test]
+    fn it_rejects_envelopes_with_trailing_bytes() {
+        let envelope = builder()
+            .with_operation(Operation::bump_sequence(100))
+            .build()
+            .unwrap();
+        let mut bytes = base64::decode(&envelope.to_base64().unwrap()).unwrap();
+        bytes.extend_from_slice(&[0; 4]);
+        let encoded = base64::encode(&bytes);
+        assert!(TransactionEnvelope::from_base64(&encoded, Network::TESTNET).is_err());
+    }
+
+    #[test]
+    fn it_keeps_operations_it_cant_build() {
+        let keypair = Keypair::from_secret_seed(SEED).unwrap();
+        let mut w = Writer::new();
+        w.write_i32(2);
+        w.write_account_id(&keypair.account_id()).unwrap();
+        w.write_u32(200);
+        w.write_i64(42);
+        w.write_i32(0);
+        w.write_i32(0);
+        w.write_u32(2);
+        // An inflation operation, which has no body.
+        w.write_i32(0);
+        w.write_i32(9);
+        // A payment to a muxed account.
+        w.write_i32(0);
+        w.write_i32(1);
+        w.write_i32(0x100);
+        w.write_u64(7);
+        w.write_fixed(&keypair.public_key().as_bytes()[..]);
+        w.write_i32(0);
+        w.write_i64(10_000_000);
+        w.write_i32(0);
+        w.write_u32(0);
+        let encoded = base64::encode(w.as_bytes());
+
+        let envelope = TransactionEnvelope::from_base64(&encoded, Network::TESTNET).unwrap();
+        assert_eq!(envelope.fee(), 200);
+        assert_eq!(envelope.sequence(), 42);
+        let operations = envelope.operations();
+        assert_eq!(operations[0].name(), "inflation");
+        assert_eq!(operations[1].name(), "payment");
+        assert!(operations[1].destination().unwrap().starts_with('M'));
+        assert_eq!(envelope.to_base64().unwrap(), encoded);
+    }
+
+    #[test]
+    fn it_requires_operations() {
+        match *builder().build().unwrap_err().kind() {
+            ErrorKind::NoOperations => {}
+            ref kind => panic!("Unexpected error {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn it_rejects_sequence_numbers_without_a_successor() {
+        let keypair = Keypair::from_secret_seed(SEED).unwrap();
+        let result = TransactionBuilder::new(&keypair.account_id(), i64::MAX, Network::TESTNET)
+            .with_operation(Operation::account_merge(DESTINATION))
+            .build();
+        match *result.unwrap_err().kind() {
+            ErrorKind::SequenceOverflow => {}
+            ref kind => panic!("Unexpected error {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn it_rejects_invalid_accounts() {
+        let result = builder()
+            .with_operation(Operation::account_merge("GABC"))
+            .build();
+        assert!(result.is_err());
+    }
+}
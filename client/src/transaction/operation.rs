@@ -0,0 +1,759 @@
+use super::builder::decode_hash;
+use resources::{Amount, AssetIdentifier, PriceRatio};
+use strkey::{self, Version};
+use xdr::{self, Error, Reader, ToXdr, TryFromXdr, Writer};
+
+type Result<T> = ::std::result::Result<T, Error>;
+
+/// The names horizon gives each type of operation, indexed by its XDR
+/// discriminant.
+const NAMES: [&str; 24] = [
+    "create_account",
+    "payment",
+    "path_payment_strict_receive",
+    "manage_sell_offer",
+    "create_passive_sell_offer",
+    "set_options",
+    "change_trust",
+    "allow_trust",
+    "account_merge",
+    "inflation",
+    "manage_data",
+    "bump_sequence",
+    "manage_buy_offer",
+    "path_payment_strict_send",
+    "create_claimable_balance",
+    "claim_claimable_balance",
+    "begin_sponsoring_future_reserves",
+    "end_sponsoring_future_reserves",
+    "revoke_sponsorship",
+    "clawback",
+    "clawback_claimable_balance",
+    "set_trust_line_flags",
+    "liquidity_pool_deposit",
+    "liquidity_pool_withdraw",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Body {
+    CreateAccount {
+        destination: String,
+        starting_balance: Amount,
+    },
+    Payment {
+        destination: String,
+        asset: AssetIdentifier,
+        amount: Amount,
+    },
+    ManageSellOffer {
+        selling: AssetIdentifier,
+        buying: AssetIdentifier,
+        amount: Amount,
+        price: PriceRatio,
+        offer_id: u64,
+    },
+    SetOptions(SetOptions),
+    ChangeTrust {
+        asset: AssetIdentifier,
+        limit: Option<Amount>,
+    },
+    ChangeTrustPoolShare {
+        asset_a: AssetIdentifier,
+        asset_b: AssetIdentifier,
+        fee_bp: u32,
+        limit: Option<Amount>,
+    },
+    AccountMerge {
+        destination: String,
+    },
+    ManageData {
+        name: String,
+        value: Option<Vec<u8>>,
+    },
+    BumpSequence {
+        bump_to: i64,
+    },
+    ManageBuyOffer {
+        selling: AssetIdentifier,
+        buying: AssetIdentifier,
+        amount: Amount,
+        price: PriceRatio,
+        offer_id: u64,
+    },
+    LiquidityPoolDeposit {
+        pool_id: String,
+        max_amount_a: Amount,
+        max_amount_b: Amount,
+        min_price: PriceRatio,
+        max_price: PriceRatio,
+    },
+    LiquidityPoolWithdraw {
+        pool_id: String,
+        amount: Amount,
+        min_amount_a: Amount,
+        min_amount_b: Amount,
+    },
+    /// An operation decoded from XDR that the builder can't make, kept as
+    /// its raw bytes, type included, so it's encoded exactly as it was.
+    Other {
+        kind: i32,
+        xdr: Vec<u8>,
+    },
+}
+
+/// A single operation to be included in a transaction.
+///
+/// ```
+/// use stellar_client::resources::{Amount, AssetIdentifier};
+/// use stellar_client::transaction::Operation;
+///
+/// let payment = Operation::payment(
+///     "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3",
+///     AssetIdentifier::native(),
+///     Amount::new(10_000_000),
+/// );
+/// assert_eq!(payment.name(), "payment");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Operation {
+    source_account: Option<String>,
+    body: Body,
+}
+
+impl Operation {
+    fn new(body: Body) -> Operation {
+        Operation {
+            source_account: None,
+            body,
+        }
+    }
+
+    /// Creates and funds a new account.
+    pub fn create_account(destination: &str, starting_balance: Amount) -> Operation {
+        Operation::new(Body::CreateAccount {
+            destination: destination.to_string(),
+            starting_balance,
+        })
+    }
+
+    /// Sends an amount of an asset to another account.
+    pub fn payment(destination: &str, asset: AssetIdentifier, amount: Amount) -> Operation {
+        Operation::new(Body::Payment {
+            destination: destination.to_string(),
+            asset,
+            amount,
+        })
+    }
+
+    /// Creates an offer to sell an amount of one asset for another at a
+    /// price given in terms of the buying asset. Use `with_offer_id` to
+    /// update or, with a zero amount, cancel an existing offer.
+    pub fn manage_sell_offer(
+        selling: AssetIdentifier,
+        buying: AssetIdentifier,
+        amount: Amount,
+        price: PriceRatio,
+    ) -> Operation {
+        Operation::new(Body::ManageSellOffer {
+            selling,
+            buying,
+            amount,
+            price,
+            offer_id: 0,
+        })
+    }
+
+    /// Creates an offer to buy an amount of one asset with another at a
+    /// price given in terms of the selling asset. Use `with_offer_id` to
+    /// update or, with a zero amount, cancel an existing offer.
+    pub fn manage_buy_offer(
+        selling: AssetIdentifier,
+        buying: AssetIdentifier,
+        buy_amount: Amount,
+        price: PriceRatio,
+    ) -> Operation {
+        Operation::new(Body::ManageBuyOffer {
+            selling,
+            buying,
+            amount: buy_amount,
+            price,
+            offer_id: 0,
+        })
+    }
+
+    /// Changes the options of an account, such as its signers or flags.
+    pub fn set_options(options: SetOptions) -> Operation {
+        Operation::new(Body::SetOptions(options))
+    }
+
+    /// Creates or updates a trust line. Without a limit the maximum limit
+    /// is used, and a limit of zero removes the trust line.
+    pub fn change_trust(asset: AssetIdentifier) -> Operation {
+        Operation::new(Body::ChangeTrust { asset, limit: None })
+    }
+
+    /// Creates or updates a trust line to the shares of the constant product
+    /// pool of the two assets, which has to exist before depositing into
+    /// the pool. The assets must be in the order the ledger keeps them,
+    /// which is the order horizon lists a pool's reserves in.
+    pub fn change_trust_pool_share(
+        asset_a: AssetIdentifier,
+        asset_b: AssetIdentifier,
+        fee_bp: u32,
+    ) -> Operation {
+        Operation::new(Body::ChangeTrustPoolShare {
+            asset_a,
+            asset_b,
+            fee_bp,
+            limit: None,
+        })
+    }
+
+    /// Deposits up to the maximum amounts of a pool's two assets in return
+    /// for pool shares, as long as the price of the first asset in terms of
+    /// the second is within the bounds.
+    pub fn liquidity_pool_deposit(
+        pool_id: &str,
+        max_amount_a: Amount,
+        max_amount_b: Amount,
+        min_price: PriceRatio,
+        max_price: PriceRatio,
+    ) -> Operation {
+        Operation::new(Body::LiquidityPoolDeposit {
+            pool_id: pool_id.to_string(),
+            max_amount_a,
+            max_amount_b,
+            min_price,
+            max_price,
+        })
+    }
+
+    /// Redeems an amount of pool shares for the pool's assets, failing if
+    /// less than the minimum of either would be received.
+    pub fn liquidity_pool_withdraw(
+        pool_id: &str,
+        amount: Amount,
+        min_amount_a: Amount,
+        min_amount_b: Amount,
+    ) -> Operation {
+        Operation::new(Body::LiquidityPoolWithdraw {
+            pool_id: pool_id.to_string(),
+            amount,
+            min_amount_a,
+            min_amount_b,
+        })
+    }
+
+    /// Merges the source account into the destination, transferring its
+    /// native balance and removing it from the ledger.
+    pub fn account_merge(destination: &str) -> Operation {
+        Operation::new(Body::AccountMerge {
+            destination: destination.to_string(),
+        })
+    }
+
+    /// Sets a data entry on the account, or removes it when the value is none.
+    pub fn manage_data(name: &str, value: Option<&[u8]>) -> Operation {
+        Operation::new(Body::ManageData {
+            name: name.to_string(),
+            value: value.map(|v| v.to_vec()),
+        })
+    }
+
+    /// Bumps the sequence number of the source account.
+    pub fn bump_sequence(bump_to: i64) -> Operation {
+        Operation::new(Body::BumpSequence { bump_to })
+    }
+
+    /// Sets the account the operation applies to, when it differs from the
+    /// source of the transaction.
+    pub fn with_source_account(mut self, source_account: &str) -> Operation {
+        self.source_account = Some(source_account.to_string());
+        self
+    }
+
+    /// Sets the id of the offer that a manage offer operation updates. Has
+    /// no effect on other operations.
+    pub fn with_offer_id(mut self, id: u64) -> Operation {
+        match self.body {
+            Body::ManageSellOffer {
+                ref mut offer_id, ..
+            }
+            | Body::ManageBuyOffer {
+                ref mut offer_id, ..
+            } => *offer_id = id,
+            _ => {}
+        }
+        self
+    }
+
+    /// Sets the limit of a change trust operation. Has no effect on other
+    /// operations.
+    pub fn with_limit(mut self, amount: Amount) -> Operation {
+        match self.body {
+            Body::ChangeTrust { ref mut limit, .. }
+            | Body::ChangeTrustPoolShare { ref mut limit, .. } => *limit = Some(amount),
+            _ => {}
+        }
+        self
+    }
+
+    /// The account the operation applies to, if set.
+    pub fn source_account(&self) -> Option<&str> {
+        self.source_account.as_ref().map(|s| &**s)
+    }
+
+    /// The account that receives the payment, the new account or the
+    /// merged balance, for operations that have one.
+    pub fn destination(&self) -> Option<&str> {
+        match self.body {
+            Body::CreateAccount {
+                ref destination, ..
+            }
+            | Body::Payment {
+                ref destination, ..
+            }
+            | Body::AccountMerge { ref destination } => Some(destination),
+            _ => None,
+        }
+    }
+
+    /// The asset paid or trusted, for operations with a single asset.
+    pub fn asset(&self) -> Option<&AssetIdentifier> {
+        match self.body {
+            Body::Payment { ref asset, .. } | Body::ChangeTrust { ref asset, .. } => Some(asset),
+            _ => None,
+        }
+    }
+
+    /// The amount funded, paid, offered or withdrawn, for operations with a
+    /// single amount. For a buy offer this is the amount to buy.
+    pub fn amount(&self) -> Option<Amount> {
+        match self.body {
+            Body::CreateAccount {
+                starting_balance, ..
+            } => Some(starting_balance),
+            Body::Payment { amount, .. }
+            | Body::ManageSellOffer { amount, .. }
+            | Body::ManageBuyOffer { amount, .. }
+            | Body::LiquidityPoolWithdraw { amount, .. } => Some(amount),
+            _ => None,
+        }
+    }
+
+    /// The name of the operation as horizon reports it.
+    pub fn name(&self) -> &'static str {
+        match self.body {
+            Body::CreateAccount { .. } => "create_account",
+            Body::Payment { .. } => "payment",
+            Body::ManageSellOffer { .. } => "manage_sell_offer",
+            Body::SetOptions(_) => "set_options",
+            Body::ChangeTrust { .. } | Body::ChangeTrustPoolShare { .. } => "change_trust",
+            Body::AccountMerge { .. } => "account_merge",
+            Body::ManageData { .. } => "manage_data",
+            Body::BumpSequence { .. } => "bump_sequence",
+            Body::ManageBuyOffer { .. } => "manage_buy_offer",
+            Body::LiquidityPoolDeposit { .. } => "liquidity_pool_deposit",
+            Body::LiquidityPoolWithdraw { .. } => "liquidity_pool_withdraw",
+            Body::Other { kind, .. } => NAMES[kind as usize],
+        }
+    }
+}
+
+fn write_amount(w: &mut Writer, amount: Amount) {
+    w.write_i64(amount.stroops())
+}
+
+fn write_muxed_account(w: &mut Writer, account_id: &str) -> Result<()> {
+    match strkey::decode(account_id) {
+        Ok((Version::MuxedAccount, ref payload)) if payload.len() == 40 => {
+            w.write_i32(0x100);
+            w.write_fixed(&payload[32..]);
+            w.write_fixed(&payload[..32]);
+            Ok(())
+        }
+        _ => w.write_account_id(account_id),
+    }
+}
+
+fn read_amount(r: &mut Reader) -> Result<Amount> {
+    r.read_i64().map(Amount::new)
+}
+
+// The builder writes the largest possible limit when none is given.
+fn read_limit(r: &mut Reader) -> Result<Option<Amount>> {
+    match r.read_i64()? {
+        limit if limit == i64::MAX => Ok(None),
+        limit => Ok(Some(Amount::new(limit))),
+    }
+}
+
+fn read_pool_id(r: &mut Reader) -> Result<String> {
+    Ok(xdr::hex(r.read_fixed(32)?))
+}
+
+fn write_signer_key(w: &mut Writer, key: &str) -> Result<()> {
+    let (version, payload) = strkey::decode(key).map_err(|_| Error::invalid_value("signer key"))?;
+    let discriminant = match version {
+        Version::AccountId => 0,
+        Version::PreAuthTx => 1,
+        Version::Sha256Hash => 2,
+        Version::SignedPayload => 3,
+        _ => return Err(Error::invalid_value("signer key")),
+    };
+    if payload.len() < 32 {
+        return Err(Error::invalid_value("signer key"));
+    }
+    w.write_i32(discriminant);
+    // Signed payloads are already encoded as the key followed by the
+    // length prefixed, padded payload.
+    w.write_fixed(&payload);
+    Ok(())
+}
+
+impl ToXdr for Operation {
+    fn to_xdr(&self, w: &mut Writer) -> Result<()> {
+        w.write_optional(self.source_account.as_ref(), |w, source| {
+            write_muxed_account(w, source)
+        })?;
+        match self.body {
+            Body::CreateAccount {
+                ref destination,
+                starting_balance,
+            } => {
+                w.write_i32(0);
+                w.write_account_id(destination)?;
+                write_amount(w, starting_balance);
+            }
+            Body::Payment {
+                ref destination,
+                ref asset,
+                amount,
+            } => {
+                w.write_i32(1);
+                write_muxed_account(w, destination)?;
+                asset.to_xdr(w)?;
+                write_amount(w, amount);
+            }
+            Body::ManageSellOffer {
+                ref selling,
+                ref buying,
+                amount,
+                ref price,
+                offer_id,
+            } => {
+                w.write_i32(3);
+                selling.to_xdr(w)?;
+                buying.to_xdr(w)?;
+                write_amount(w, amount);
+                price.to_xdr(w)?;
+                w.write_u64(offer_id);
+            }
+            Body::SetOptions(ref options) => {
+                w.write_i32(5);
+                options.to_xdr(w)?;
+            }
+            Body::ChangeTrust { ref asset, limit } => {
+                w.write_i32(6);
+                asset.to_xdr(w)?;
+                w.write_i64(limit.map(|l| l.stroops()).unwrap_or_else(i64::max_value));
+            }
+            Body::ChangeTrustPoolShare {
+                ref asset_a,
+                ref asset_b,
+                fee_bp,
+                limit,
+            } => {
+                w.write_i32(6);
+                // A pool share asset, holding the parameters of a constant
+                // product pool.
+                w.write_i32(3);
+                w.write_i32(0);
+                asset_a.to_xdr(w)?;
+                asset_b.to_xdr(w)?;
+                w.write_i32(fee_bp as i32);
+                w.write_i64(limit.map(|l| l.stroops()).unwrap_or_else(i64::max_value));
+            }
+            Body::AccountMerge { ref destination } => {
+                w.write_i32(8);
+                write_muxed_account(w, destination)?;
+            }
+            Body::ManageData {
+                ref name,
+                ref value,
+            } => {
+                w.write_i32(10);
+                w.write_string(name, 64)?;
+                w.write_optional(value.as_ref(), |w, value| w.write_var(value, 64))?;
+            }
+            Body::BumpSequence { bump_to } => {
+                w.write_i32(11);
+                w.write_i64(bump_to);
+            }
+            Body::ManageBuyOffer {
+                ref selling,
+                ref buying,
+                amount,
+                ref price,
+                offer_id,
+            } => {
+                w.write_i32(12);
+                selling.to_xdr(w)?;
+                buying.to_xdr(w)?;
+                write_amount(w, amount);
+                price.to_xdr(w)?;
+                w.write_u64(offer_id);
+            }
+            Body::LiquidityPoolDeposit {
+                ref pool_id,
+                max_amount_a,
+                max_amount_b,
+                ref min_price,
+                ref max_price,
+            } => {
+                w.write_i32(22);
+                w.write_fixed(&decode_hash(pool_id, "liquidity pool id")?);
+                write_amount(w, max_amount_a);
+                write_amount(w, max_amount_b);
+                min_price.to_xdr(w)?;
+                max_price.to_xdr(w)?;
+            }
+            Body::LiquidityPoolWithdraw {
+                ref pool_id,
+                amount,
+                min_amount_a,
+                min_amount_b,
+            } => {
+                w.write_i32(23);
+                w.write_fixed(&decode_hash(pool_id, "liquidity pool id")?);
+                write_amount(w, amount);
+                write_amount(w, min_amount_a);
+                write_amount(w, min_amount_b);
+            }
+            Body::Other { ref xdr, .. } => w.write_fixed(xdr),
+        }
+        Ok(())
+    }
+}
+
+impl TryFromXdr for Operation {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        let source_account = xdr::optional(r, xdr::muxed_account)?;
+        let start = r.clone();
+        let body = match r.read_i32()? {
+            0 => Body::CreateAccount {
+                destination: xdr::account_id(r)?,
+                starting_balance: read_amount(r)?,
+            },
+            1 => Body::Payment {
+                destination: xdr::muxed_account(r)?,
+                asset: AssetIdentifier::try_from_xdr(r)?,
+                amount: read_amount(r)?,
+            },
+            3 => Body::ManageSellOffer {
+                selling: AssetIdentifier::try_from_xdr(r)?,
+                buying: AssetIdentifier::try_from_xdr(r)?,
+                amount: read_amount(r)?,
+                price: PriceRatio::try_from_xdr(r)?,
+                offer_id: r.read_u64()?,
+            },
+            5 => Body::SetOptions(SetOptions::try_from_xdr(r)?),
+            6 => {
+                let mut line = r.clone();
+                if line.read_i32()? == 3 {
+                    *r = line;
+                    match r.read_i32()? {
+                        0 => {}
+                        v => return Err(Error::unknown_discriminant("LiquidityPoolType", v)),
+                    }
+                    Body::ChangeTrustPoolShare {
+                        asset_a: AssetIdentifier::try_from_xdr(r)?,
+                        asset_b: AssetIdentifier::try_from_xdr(r)?,
+                        fee_bp: r.read_u32()?,
+                        limit: read_limit(r)?,
+                    }
+                } else {
+                    Body::ChangeTrust {
+                        asset: AssetIdentifier::try_from_xdr(r)?,
+                        limit: read_limit(r)?,
+                    }
+                }
+            }
+            8 => Body::AccountMerge {
+                destination: xdr::muxed_account(r)?,
+            },
+            10 => Body::ManageData {
+                name: r.read_string(64)?,
+                value: xdr::optional(r, |r| r.read_var(64).map(|v| v.to_vec()))?,
+            },
+            11 => Body::BumpSequence {
+                bump_to: r.read_i64()?,
+            },
+            12 => Body::ManageBuyOffer {
+                selling: AssetIdentifier::try_from_xdr(r)?,
+                buying: AssetIdentifier::try_from_xdr(r)?,
+                amount: read_amount(r)?,
+                price: PriceRatio::try_from_xdr(r)?,
+                offer_id: r.read_u64()?,
+            },
+            22 => Body::LiquidityPoolDeposit {
+                pool_id: read_pool_id(r)?,
+                max_amount_a: read_amount(r)?,
+                max_amount_b: read_amount(r)?,
+                min_price: PriceRatio::try_from_xdr(r)?,
+                max_price: PriceRatio::try_from_xdr(r)?,
+            },
+            23 => Body::LiquidityPoolWithdraw {
+                pool_id: read_pool_id(r)?,
+                amount: read_amount(r)?,
+                min_amount_a: read_amount(r)?,
+                min_amount_b: read_amount(r)?,
+            },
+            kind => {
+                let mut end = start.clone();
+                xdr::skip_operation_body(&mut end)?;
+                let mut bytes = start;
+                let xdr = bytes
+                    .read_fixed(bytes.remaining() - end.remaining())?
+                    .to_vec();
+                *r = end;
+                Body::Other { kind, xdr }
+            }
+        };
+        Ok(Operation {
+            source_account,
+            body,
+        })
+    }
+}
+
+/// The changes made by a set options operation. Any option that is left
+/// unset is not changed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SetOptions {
+    inflation_dest: Option<String>,
+    clear_flags: Option<u32>,
+    set_flags: Option<u32>,
+    master_weight: Option<u32>,
+    low_threshold: Option<u32>,
+    med_threshold: Option<u32>,
+    high_threshold: Option<u32>,
+    home_domain: Option<String>,
+    signer: Option<(String, u32)>,
+}
+
+impl SetOptions {
+    /// Sets the account that receives the account's inflation votes.
+    pub fn with_inflation_dest(mut self, account_id: &str) -> SetOptions {
+        self.inflation_dest = Some(account_id.to_string());
+        self
+    }
+
+    /// Sets the given account flags.
+    pub fn with_set_flags(mut self, flags: u32) -> SetOptions {
+        self.set_flags = Some(flags);
+        self
+    }
+
+    /// Clears the given account flags.
+    pub fn with_clear_flags(mut self, flags: u32) -> SetOptions {
+        self.clear_flags = Some(flags);
+        self
+    }
+
+    /// Sets the weight of the account's master key.
+    pub fn with_master_weight(mut self, weight: u32) -> SetOptions {
+        self.master_weight = Some(weight);
+        self
+    }
+
+    /// Sets the low, medium and high thresholds of the account.
+    pub fn with_thresholds(mut self, low: u32, med: u32, high: u32) -> SetOptions {
+        self.low_threshold = Some(low);
+        self.med_threshold = Some(med);
+        self.high_threshold = Some(high);
+        self
+    }
+
+    /// Sets the home domain of the account.
+    pub fn with_home_domain(mut self, home_domain: &str) -> SetOptions {
+        self.home_domain = Some(home_domain.to_string());
+        self
+    }
+
+    /// Adds, updates or, with a weight of zero, removes a signer. The key is
+    /// a strkey encoded ed25519 key, pre-authorized transaction or hash.
+    pub fn with_signer(mut self, key: &str, weight: u32) -> SetOptions {
+        self.signer = Some((key.to_string(), weight));
+        self
+    }
+
+    /// The weight the master key is set to, if it is changed.
+    pub fn master_weight(&self) -> Option<u32> {
+        self.master_weight
+    }
+
+    /// The low threshold the account is set to, if it is changed.
+    pub fn low_threshold(&self) -> Option<u32> {
+        self.low_threshold
+    }
+
+    /// The medium threshold the account is set to, if it is changed.
+    pub fn med_threshold(&self) -> Option<u32> {
+        self.med_threshold
+    }
+
+    /// The high threshold the account is set to, if it is changed.
+    pub fn high_threshold(&self) -> Option<u32> {
+        self.high_threshold
+    }
+
+    /// The signer that is added, updated or removed and its new weight, if
+    /// one is.
+    pub fn signer(&self) -> Option<(&str, u32)> {
+        self.signer
+            .as_ref()
+            .map(|&(ref key, weight)| (key.as_str(), weight))
+    }
+}
+
+impl TryFromXdr for SetOptions {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        fn read_u32(r: &mut Reader) -> Result<u32> {
+            r.read_u32()
+        }
+        Ok(SetOptions {
+            inflation_dest: xdr::optional(r, xdr::account_id)?,
+            clear_flags: xdr::optional(r, read_u32)?,
+            set_flags: xdr::optional(r, read_u32)?,
+            master_weight: xdr::optional(r, read_u32)?,
+            low_threshold: xdr::optional(r, read_u32)?,
+            med_threshold: xdr::optional(r, read_u32)?,
+            high_threshold: xdr::optional(r, read_u32)?,
+            home_domain: xdr::optional(r, |r| r.read_string(32))?,
+            signer: xdr::optional(r, |r| Ok((xdr::signer_key(r)?, r.read_u32()?)))?,
+        })
+    }
+}
+
+impl ToXdr for SetOptions {
+    fn to_xdr(&self, w: &mut Writer) -> Result<()> {
+        fn write_u32(w: &mut Writer, value: &u32) -> Result<()> {
+            w.write_u32(*value);
+            Ok(())
+        }
+        w.write_optional(self.inflation_dest.as_ref(), |w, d| w.write_account_id(d))?;
+        w.write_optional(self.clear_flags.as_ref(), write_u32)?;
+        w.write_optional(self.set_flags.as_ref(), write_u32)?;
+        w.write_optional(self.master_weight.as_ref(), write_u32)?;
+        w.write_optional(self.low_threshold.as_ref(), write_u32)?;
+        w.write_optional(self.med_threshold.as_ref(), write_u32)?;
+        w.write_optional(self.high_threshold.as_ref(), write_u32)?;
+        w.write_optional(self.home_domain.as_ref(), |w, d| w.write_string(d, 32))?;
+        w.write_optional(self.signer.as_ref(), |w, &(ref key, weight)| {
+            write_signer_key(w, key)?;
+            w.write_u32(weight);
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,412 @@
+use super::{Error, SetOptions};
+use resources::{Account, AccountSigner, AccountThresholds};
+
+/// The signers of an account and the thresholds their weights are measured
+/// against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignerSetup {
+    account_id: String,
+    signers: Vec<AccountSigner>,
+    thresholds: AccountThresholds,
+}
+
+impl SignerSetup {
+    /// The signers and thresholds an account has now. The master key is the
+    /// signer whose key is the account id, as horizon lists it.
+    pub fn of(account: &Account) -> SignerSetup {
+        SignerSetup {
+            account_id: account.account_id().clone(),
+            signers: account.signers().to_vec(),
+            thresholds: account.thresholds(),
+        }
+    }
+
+    /// The setup after a set options operation on the account. Fails with
+    /// `ErrorKind::WeightOutOfRange` for a weight or threshold above 255,
+    /// which the network would reject.
+    pub fn apply(&self, options: &SetOptions) -> Result<SignerSetup, Error> {
+        match out_of_range(options) {
+            Some(value) => Err(Error::weight_out_of_range(value)),
+            None => Ok(self.applied(options)),
+        }
+    }
+
+    /// Applies an operation whose weights and thresholds are known to fit.
+    fn applied(&self, options: &SetOptions) -> SignerSetup {
+        let mut setup = self.clone();
+        if let Some(weight) = options.master_weight() {
+            let account_id = self.account_id.clone();
+            setup.set_weight(&account_id, weight as u8);
+        }
+        if let Some((key, weight)) = options.signer() {
+            setup.set_weight(key, weight as u8);
+        }
+        let low = options
+            .low_threshold()
+            .map_or(self.thresholds.low(), |low| low as u8);
+        let med = options
+            .med_threshold()
+            .map_or(self.thresholds.med(), |med| med as u8);
+        let high = options
+            .high_threshold()
+            .map_or(self.thresholds.high(), |high| high as u8);
+        setup.thresholds = AccountThresholds::new(low, med, high);
+        setup
+    }
+
+    /// The signers, with the master key among them.
+    pub fn signers(&self) -> &[AccountSigner] {
+        &self.signers
+    }
+
+    /// The weight of the master key.
+    pub fn master_weight(&self) -> u8 {
+        self.weight(&self.account_id)
+    }
+
+    /// The weight of a key, or zero if it isn't a signer.
+    pub fn weight(&self, key: &str) -> u8 {
+        self.signers
+            .iter()
+            .find(|signer| signer.key() == key)
+            .map_or(0, AccountSigner::weight)
+    }
+
+    /// The thresholds.
+    pub fn thresholds(&self) -> AccountThresholds {
+        self.thresholds
+    }
+
+    /// The combined weight of every signer.
+    pub fn total_weight(&self) -> u32 {
+        self.signers
+            .iter()
+            .map(|signer| u32::from(signer.weight()))
+            .sum()
+    }
+
+    /// The combined weight of the ed25519 keys, the signers that can sign
+    /// any transaction. Pre-authorized transactions only ever authorize the
+    /// one transaction, and hashes need their preimage revealed.
+    pub fn signing_weight(&self) -> u32 {
+        self.signers
+            .iter()
+            .filter(|signer| signer.is_ed25519())
+            .map(|signer| u32::from(signer.weight()))
+            .sum()
+    }
+
+    /// Whether the ed25519 keys together can meet a threshold. A threshold
+    /// of zero still needs a signature of some weight.
+    pub fn can_meet(&self, threshold: u8) -> bool {
+        self.signing_weight() >= u32::from(threshold.max(1))
+    }
+
+    /// Adds, reweighs or, with a weight of zero, removes a signer. The
+    /// master key is kept at a weight of zero, as horizon lists it.
+    fn set_weight(&mut self, key: &str, weight: u8) {
+        let is_master = key == self.account_id;
+        match self.signers.iter().position(|signer| signer.key() == key) {
+            Some(index) if weight == 0 && !is_master => {
+                self.signers.remove(index);
+            }
+            Some(index) => {
+                let signer = AccountSigner::new(key, weight);
+                self.signers[index] = signer;
+            }
+            None if weight == 0 => {}
+            None => self.signers.push(AccountSigner::new(key, weight)),
+        }
+    }
+}
+
+/// The first weight or threshold an operation sets above 255.
+fn out_of_range(options: &SetOptions) -> Option<u32> {
+    options
+        .master_weight()
+        .into_iter()
+        .chain(options.signer().map(|(_, weight)| weight))
+        .chain(options.low_threshold())
+        .chain(options.med_threshold())
+        .chain(options.high_threshold())
+        .find(|&value| value > u32::from(u8::MAX))
+}
+
+/// How a signer of the account changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignerChange {
+    /// A new signer with its weight.
+    Added(AccountSigner),
+    /// A signer that is removed, with the weight it had.
+    Removed(AccountSigner),
+    /// A signer whose weight changes.
+    Reweighted {
+        /// The key of the signer.
+        key: String,
+        /// The weight it had.
+        from: u8,
+        /// The weight it will have.
+        to: u8,
+    },
+}
+
+/// What a series of set options operations would do to the signers and
+/// thresholds of an account, worked out before the transaction is built,
+/// so that changes that would leave the account unable to sign for itself
+/// can be caught.
+///
+/// ```
+/// use stellar_client::resources::{Account, AccountSigner, AccountThresholds};
+/// use stellar_client::transaction::{SetOptions, SignerReport};
+///
+/// let account_id = "GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ6";
+/// let cosigner = "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3";
+/// let account = Account::new(account_id, 1)
+///     .with_signer(AccountSigner::ed25519(account_id, 1))
+///     .with_thresholds(AccountThresholds::new(0, 0, 0));
+///
+/// let report = SignerReport::simulate(
+///     &account,
+///     &[
+///         SetOptions::default().with_signer(cosigner, 1),
+///         SetOptions::default().with_thresholds(1, 2, 3),
+///     ],
+/// );
+/// assert!(report.is_locked_out());
+/// assert!(report.check().is_err());
+///
+/// let report = SignerReport::simulate(
+///     &account,
+///     &[
+///         SetOptions::default().with_signer(cosigner, 1),
+///         SetOptions::default().with_thresholds(1, 2, 2),
+///     ],
+/// );
+/// assert!(!report.is_locked_out());
+/// assert_eq!(report.after().thresholds(), AccountThresholds::new(1, 2, 2));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignerReport {
+    before: SignerSetup,
+    after: SignerSetup,
+    out_of_range: Option<u32>,
+}
+
+impl SignerReport {
+    /// Applies the operations to the account's signers and thresholds in
+    /// order, as the network would. A weight or threshold above 255 fails
+    /// the whole transaction, so the signers are then left as they are and
+    /// the value is reported by `out_of_range`.
+    pub fn simulate(account: &Account, changes: &[SetOptions]) -> SignerReport {
+        let before = SignerSetup::of(account);
+        let mut after = before.clone();
+        for options in changes {
+            if let Some(value) = out_of_range(options) {
+                return SignerReport {
+                    after: before.clone(),
+                    before,
+                    out_of_range: Some(value),
+                };
+            }
+            after = after.applied(options);
+        }
+        SignerReport {
+            before,
+            after,
+            out_of_range: None,
+        }
+    }
+
+    /// The signers and thresholds as they are.
+    pub fn before(&self) -> &SignerSetup {
+        &self.before
+    }
+
+    /// The signers and thresholds as they would be.
+    pub fn after(&self) -> &SignerSetup {
+        &self.after
+    }
+
+    /// The signers that would be added, removed or reweighted, in the order
+    /// of the signers before and then of the ones added.
+    pub fn changes(&self) -> Vec<SignerChange> {
+        let mut changes = Vec::new();
+        for signer in self.before.signers() {
+            match self
+                .after
+                .signers()
+                .iter()
+                .find(|s| s.key() == signer.key())
+            {
+                None => changes.push(SignerChange::Removed(signer.clone())),
+                Some(after) if after.weight() != signer.weight() => {
+                    changes.push(SignerChange::Reweighted {
+                        key: signer.key().to_string(),
+                        from: signer.weight(),
+                        to: after.weight(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        for signer in self.after.signers() {
+            if !self
+                .before
+                .signers()
+                .iter()
+                .any(|s| s.key() == signer.key())
+            {
+                changes.push(SignerChange::Added(signer.clone()));
+            }
+        }
+        changes
+    }
+
+    /// The first weight or threshold above 255, which would fail the
+    /// transaction.
+    pub fn out_of_range(&self) -> Option<u32> {
+        self.out_of_range
+    }
+
+    /// Whether the thresholds would change.
+    pub fn thresholds_changed(&self) -> bool {
+        self.before.thresholds() != self.after.thresholds()
+    }
+
+    /// Whether the account would be left unable to meet its high threshold,
+    /// and so unable to ever change its signers again.
+    pub fn is_locked_out(&self) -> bool {
+        !self.after.can_meet(self.after.thresholds().high())
+    }
+
+    /// Fails with `ErrorKind::WeightOutOfRange` if a weight or threshold is
+    /// above 255, or `ErrorKind::LockedOut` if the changes would lock the
+    /// account out.
+    pub fn check(&self) -> Result<(), Error> {
+        if let Some(value) = self.out_of_range {
+            return Err(Error::weight_out_of_range(value));
+        }
+        if self.is_locked_out() {
+            return Err(Error::locked_out(
+                self.after.signing_weight(),
+                self.after.thresholds().high(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod signer_report_tests {
+    use super::*;
+    use transaction::ErrorKind;
+
+    const ACCOUNT: &str = "GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ6";
+    const COSIGNER: &str = "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3";
+    const PREAUTH: &str = "TBU2RRGLXH3E5CQHTD3ODLDF2BWDCYUSSBLLZ5GNW7JXHDIYKXZWHXL7";
+
+    fn account() -> Account {
+        Account::new(ACCOUNT, 1)
+            .with_signer(AccountSigner::ed25519(ACCOUNT, 1))
+            .with_signer(AccountSigner::ed25519(COSIGNER, 1))
+            .with_thresholds(AccountThresholds::new(1, 1, 2))
+    }
+
+    #[test]
+    fn it_reports_the_signers_that_change() {
+        let report = SignerReport::simulate(
+            &account(),
+            &[
+                SetOptions::default().with_signer(COSIGNER, 0),
+                SetOptions::default().with_signer(PREAUTH, 1),
+                SetOptions::default().with_master_weight(2),
+            ],
+        );
+        assert_eq!(
+            report.changes(),
+            vec![
+                SignerChange::Reweighted {
+                    key: ACCOUNT.to_string(),
+                    from: 1,
+                    to: 2,
+                },
+                SignerChange::Removed(AccountSigner::ed25519(COSIGNER, 1)),
+                SignerChange::Added(AccountSigner::new(PREAUTH, 1)),
+            ]
+        );
+        assert!(!report.thresholds_changed());
+        assert_eq!(report.after().master_weight(), 2);
+        assert_eq!(report.after().total_weight(), 3);
+        assert_eq!(report.after().signing_weight(), 2);
+        assert!(!report.is_locked_out());
+    }
+
+    #[test]
+    fn it_keeps_the_master_key_at_zero_weight() {
+        let report =
+            SignerReport::simulate(&account(), &[SetOptions::default().with_master_weight(0)]);
+        assert_eq!(report.after().signers().len(), 2);
+        assert_eq!(report.after().master_weight(), 0);
+        assert!(report.is_locked_out());
+    }
+
+    #[test]
+    fn it_does_not_count_pre_authorized_transactions_towards_signing() {
+        let report = SignerReport::simulate(
+            &account(),
+            &[
+                SetOptions::default().with_signer(PREAUTH, 5),
+                SetOptions::default().with_thresholds(1, 1, 3),
+            ],
+        );
+        assert_eq!(report.after().total_weight(), 7);
+        assert!(report.is_locked_out());
+        match *report.check().unwrap_err().kind() {
+            ErrorKind::LockedOut(2, 3) => {}
+            ref kind => panic!("Unexpected error {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn it_needs_a_signature_for_a_zero_threshold() {
+        let report = SignerReport::simulate(
+            &account(),
+            &[
+                SetOptions::default().with_thresholds(0, 0, 0),
+                SetOptions::default().with_signer(COSIGNER, 0),
+                SetOptions::default().with_master_weight(0),
+            ],
+        );
+        assert!(report.thresholds_changed());
+        assert!(report.is_locked_out());
+    }
+
+    #[test]
+    fn it_reports_weights_above_the_largest_the_network_allows() {
+        let report = SignerReport::simulate(
+            &account(),
+            &[
+                SetOptions::default().with_signer(PREAUTH, 1),
+                SetOptions::default()
+                    .with_master_weight(255)
+                    .with_thresholds(1, 1, 300),
+            ],
+        );
+        assert_eq!(report.out_of_range(), Some(300));
+        assert_eq!(report.after(), report.before());
+        assert!(report.changes().is_empty());
+        match *report.check().unwrap_err().kind() {
+            ErrorKind::WeightOutOfRange(300) => {}
+            ref kind => panic!("Unexpected error {:?}", kind),
+        }
+
+        let setup = SignerSetup::of(&account());
+        let options = SetOptions::default().with_signer(COSIGNER, 1_000);
+        match *setup.apply(&options).unwrap_err().kind() {
+            ErrorKind::WeightOutOfRange(1_000) => {}
+            ref kind => panic!("Unexpected error {:?}", kind),
+        }
+        let options = SetOptions::default().with_master_weight(255);
+        assert_eq!(setup.apply(&options).unwrap().master_weight(), 255);
+    }
+}
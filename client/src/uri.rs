@@ -116,6 +116,21 @@ fn split_path(uri: &http::Uri) -> Vec<&str> {
     uri.path().split('/').filter(|v| !v.is_empty()).collect()
 }
 
+/// Percent encodes a value for a query string or form body. Only unreserved
+/// characters are left as they are, so base64 padding and slashes survive.
+pub(crate) fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
 /// An error that occurs when converting from a uri to Self
 #[derive(Debug)]
 pub struct Error {
@@ -223,6 +238,12 @@ impl fmt::Display for Error {
 mod tests {
     use super::*;
 
+    #[test]
+    fn it_percent_encodes_reserved_characters() {
+        assert_eq!(percent_encode("AAAA+b/c=="), "AAAA%2Bb%2Fc%3D%3D");
+        assert_eq!(percent_encode("a-b.c_d~"), "a-b.c_d~");
+    }
+
     #[test]
     fn query_params_from_uri() {
         let no_path = "http://www.google.com".parse::<http::Uri>().unwrap();
@@ -0,0 +1,561 @@
+//! Pays out withdrawals from an exchange's hot wallet in as few
+//! transactions as possible.
+//!
+//! A `WithdrawalBatch` collects payment requests and packs them into
+//! transactions of up to `MAX_OPERATIONS` payments each. A transaction has a
+//! single memo, so only withdrawals with the same memo share one. The
+//! transactions can be sourced from channel accounts, which pay the fees and
+//! provide the sequence numbers while the hot wallet provides the funds, so
+//! that one rejected transaction doesn't hold up the sequence of the rest.
+//! Each transaction is signed and submitted with a `ResubmitPolicy`, and the
+//! report says how each withdrawal fared.
+//!
+//! A submission that times out or loses its connection may still have made
+//! it into a ledger. Those are submitted again with the client's submission
+//! check on, which looks the transaction up first so it isn't paid twice.
+//! Withdrawals whose outcome still isn't known are reported as `unknown`
+//! rather than `failed`, and shouldn't be paid again until they're looked
+//! up.
+//!
+//! ```no_run
+//! use stellar_client::keypair::Keypair;
+//! use stellar_client::network::Network;
+//! use stellar_client::resources::{Amount, AssetIdentifier, Memo};
+//! use stellar_client::sync::{Client, ResubmitPolicy};
+//! use stellar_client::withdrawal::{Channel, Withdrawal, WithdrawalBatch};
+//!
+//! let client = Client::horizon_test().unwrap();
+//! let hot_wallet = Keypair::random();
+//! let channel = Keypair::random();
+//!
+//! let report = WithdrawalBatch::new(&hot_wallet.account_id(), Network::TESTNET)
+//!     .with_withdrawal(
+//!         Withdrawal::new(
+//!             "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3",
+//!             AssetIdentifier::native(),
+//!             Amount::new(10_000_000),
+//!         ).with_memo(Memo::Id(42)),
+//!     )
+//!     .with_policy(ResubmitPolicy::new(3))
+//!     .submit(&client, &hot_wallet, &[Channel::new(&channel)]);
+//! for index in report.failed() {
+//!     println!("withdrawal {} failed", index);
+//! }
+//! for index in report.unknown() {
+//!     println!("withdrawal {} may have been paid", index);
+//! }
+//! ```
+use client::sync::{Client, ResubmitError, ResubmitErrorKind, ResubmitPolicy};
+use endpoint::account;
+use error::Error;
+use resources::{Amount, AssetIdentifier, Memo, Submission};
+use signer::Signer;
+use std::collections::HashMap;
+use std::fmt;
+use transaction::{Operation, TransactionBuilder, MAX_OPERATIONS};
+
+/// A request to pay out of the hot wallet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Withdrawal {
+    destination: String,
+    asset: AssetIdentifier,
+    amount: Amount,
+    memo: Memo,
+}
+
+impl Withdrawal {
+    /// A withdrawal of an amount of an asset to the destination, without a
+    /// memo.
+    pub fn new(destination: &str, asset: AssetIdentifier, amount: Amount) -> Withdrawal {
+        Withdrawal {
+            destination: destination.to_string(),
+            asset,
+            amount,
+            memo: Memo::None,
+        }
+    }
+
+    /// Sets the memo the destination needs, such as the id of the customer
+    /// at another exchange.
+    pub fn with_memo(mut self, memo: Memo) -> Withdrawal {
+        self.memo = memo;
+        self
+    }
+
+    /// The account being paid.
+    pub fn destination(&self) -> &str {
+        &self.destination
+    }
+
+    /// The asset being paid.
+    pub fn asset(&self) -> &AssetIdentifier {
+        &self.asset
+    }
+
+    /// The amount being paid.
+    pub fn amount(&self) -> Amount {
+        self.amount
+    }
+
+    /// The memo of the transaction paying it.
+    pub fn memo(&self) -> &Memo {
+        &self.memo
+    }
+}
+
+/// An account that sources transactions for the hot wallet, paying their
+/// fees from its own balance.
+#[derive(Clone, Copy)]
+pub struct Channel<'a> {
+    signer: &'a dyn Signer,
+}
+
+impl<'a> Channel<'a> {
+    /// A channel account signed for by the signer.
+    pub fn new(signer: &'a dyn Signer) -> Channel<'a> {
+        Channel { signer }
+    }
+
+    /// The channel account.
+    pub fn account_id(&self) -> String {
+        self.signer.public_key().account_id()
+    }
+}
+
+impl<'a> fmt::Debug for Channel<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Channel")
+            .field("account_id", &self.account_id())
+            .finish()
+    }
+}
+
+/// Withdrawals to be paid out together.
+#[derive(Debug, Clone)]
+pub struct WithdrawalBatch {
+    source_account: String,
+    network_passphrase: String,
+    withdrawals: Vec<Withdrawal>,
+    max_operations: usize,
+    policy: ResubmitPolicy,
+    max_retries: u32,
+}
+
+impl WithdrawalBatch {
+    /// Starts a batch paid from the hot wallet on the network. Transactions
+    /// are resubmitted with a higher fee at most 3 times, and submitted
+    /// again at most 3 times when horizon doesn't say what became of them.
+    pub fn new(source_account: &str, network_passphrase: &str) -> WithdrawalBatch {
+        WithdrawalBatch {
+            source_account: source_account.to_string(),
+            network_passphrase: network_passphrase.to_string(),
+            withdrawals: Vec::new(),
+            max_operations: MAX_OPERATIONS,
+            policy: ResubmitPolicy::new(3),
+            max_retries: 3,
+        }
+    }
+
+    /// Adds a withdrawal to the batch.
+    pub fn with_withdrawal(mut self, withdrawal: Withdrawal) -> WithdrawalBatch {
+        self.withdrawals.push(withdrawal);
+        self
+    }
+
+    /// Caps the number of payments in each transaction, which is at most
+    /// `MAX_OPERATIONS`. Smaller transactions lose fewer withdrawals when
+    /// one fails.
+    pub fn with_max_operations(mut self, max_operations: usize) -> WithdrawalBatch {
+        self.max_operations = max_operations.clamp(1, MAX_OPERATIONS);
+        self
+    }
+
+    /// Sets how transactions are resubmitted when their fee is too low.
+    pub fn with_policy(mut self, policy: ResubmitPolicy) -> WithdrawalBatch {
+        self.policy = policy;
+        self
+    }
+
+    /// Sets how many times a transaction is submitted again when submitting
+    /// it failed without saying whether it made it into a ledger, such as
+    /// when the request timed out.
+    pub fn with_max_retries(mut self, max_retries: u32) -> WithdrawalBatch {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// The withdrawals, in the order they were added. Reports refer to them
+    /// by their index here.
+    pub fn withdrawals(&self) -> &[Withdrawal] {
+        &self.withdrawals
+    }
+
+    /// Groups the withdrawals into transactions, as the indices of the
+    /// withdrawals in each. Withdrawals keep their order, and each group
+    /// shares a memo.
+    pub fn plan(&self) -> Vec<Vec<usize>> {
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        for (index, withdrawal) in self.withdrawals.iter().enumerate() {
+            let open = groups.iter_mut().find(|group| {
+                group.len() < self.max_operations
+                    && self.withdrawals[group[0]].memo == withdrawal.memo
+            });
+            match open {
+                Some(group) => group.push(index),
+                None => groups.push(vec![index]),
+            }
+        }
+        groups
+    }
+
+    /// Builds, signs and submits a transaction for each group of the plan,
+    /// taking turns among the channels. Without channels the hot wallet
+    /// sources the transactions itself. Failing transactions don't stop the
+    /// rest from being submitted.
+    ///
+    /// A transaction whose submission failed without an answer from horizon
+    /// is submitted again with the client's submission check on, so that
+    /// one that made it into a ledger is found rather than paid again.
+    pub fn submit(
+        &self,
+        client: &Client,
+        source_signer: &dyn Signer,
+        channels: &[Channel],
+    ) -> WithdrawalReport {
+        let own = [Channel::new(source_signer)];
+        let channels = if channels.is_empty() {
+            &own[..]
+        } else {
+            channels
+        };
+        // Sequence numbers are fetched the first time a channel is used and
+        // counted locally after that. A failed transaction may or may not
+        // have used its sequence number, so the channel is fetched again.
+        let mut sequences: HashMap<String, i64> = HashMap::new();
+
+        let mut batches = Vec::new();
+        let mut uncertain = Vec::new();
+        for (turn, group) in self.plan().into_iter().enumerate() {
+            let channel = channels[turn % channels.len()];
+            let channel_id = channel.account_id();
+            let (result, unknown) = self.submit_group(
+                client,
+                source_signer,
+                channel,
+                &channel_id,
+                &group,
+                &mut sequences,
+            );
+            match result {
+                Ok(_) => {
+                    *sequences
+                        .get_mut(&channel_id)
+                        .expect("The sequence was fetched") += 1
+                }
+                Err(_) => {
+                    sequences.remove(&channel_id);
+                }
+            }
+            batches.push((group, result));
+            uncertain.push(unknown);
+        }
+        WithdrawalReport { batches, uncertain }
+    }
+
+    fn submit_group(
+        &self,
+        client: &Client,
+        source_signer: &dyn Signer,
+        channel: Channel,
+        channel_id: &str,
+        group: &[usize],
+        sequences: &mut HashMap<String, i64>,
+    ) -> (Result<Submission, ResubmitError>, bool) {
+        let sequence = match sequences.get(channel_id) {
+            Some(&sequence) => sequence,
+            None => match client.request(account::Details::new(channel_id)) {
+                Ok(account) => account.sequence(),
+                Err(err) => return (Err(err.into()), false),
+            },
+        };
+        sequences.insert(channel_id.to_string(), sequence);
+
+        let sourced = channel_id != self.source_account;
+        let mut builder = TransactionBuilder::new(channel_id, sequence, &self.network_passphrase)
+            .with_memo(self.withdrawals[group[0]].memo.clone());
+        for &index in group {
+            let withdrawal = &self.withdrawals[index];
+            let payment = Operation::payment(
+                &withdrawal.destination,
+                withdrawal.asset.clone(),
+                withdrawal.amount,
+            );
+            builder = builder.with_operation(if sourced {
+                payment.with_source_account(&self.source_account)
+            } else {
+                payment
+            });
+        }
+        let own = [source_signer];
+        let both = [channel.signer, source_signer];
+        let signers: &[&dyn Signer] = if sourced { &both } else { &own };
+
+        let mut result = self.policy.submit(client, &builder, signers);
+        let mut retries = 0;
+        let mut unknown = false;
+        // Building again gives the same envelope, which the check finds if
+        // an earlier attempt made it into a ledger.
+        let checked = client.clone().with_submission_check(true);
+        loop {
+            match result {
+                Err(ref err) if is_ambiguous(err) => unknown = true,
+                // An earlier attempt may have used up the sequence number.
+                Err(ref err) if unknown && is_bad_seq(err) => return (result, true),
+                _ => return (result, false),
+            }
+            if retries >= self.max_retries {
+                return (result, true);
+            }
+            retries += 1;
+            result = self.policy.submit(&checked, &builder, signers);
+        }
+    }
+}
+
+/// Whether a submission failed without horizon saying what became of the
+/// transaction, so it may or may not be in a ledger.
+fn is_ambiguous(err: &ResubmitError) -> bool {
+    match *err.kind() {
+        ResubmitErrorKind::Request(Error::Reqwest(_))
+        | ResubmitErrorKind::Request(Error::Io(_))
+        | ResubmitErrorKind::Request(Error::ServerError)
+        | ResubmitErrorKind::Request(Error::Deserialize(_))
+        | ResubmitErrorKind::Request(Error::JsonParseError(_)) => true,
+        _ => false,
+    }
+}
+
+fn is_bad_seq(err: &ResubmitError) -> bool {
+    match *err.kind() {
+        ResubmitErrorKind::Request(Error::BadResponse(ref err)) => {
+            err.transaction_result_code() == Some("tx_bad_seq")
+        }
+        _ => false,
+    }
+}
+
+/// How each transaction of a `WithdrawalBatch` fared.
+#[derive(Debug)]
+pub struct WithdrawalReport {
+    batches: Vec<(Vec<usize>, Result<Submission, ResubmitError>)>,
+    uncertain: Vec<bool>,
+}
+
+impl WithdrawalReport {
+    /// The transactions submitted, as the indices of their withdrawals and
+    /// the result of submitting them.
+    pub fn batches(&self) -> &[(Vec<usize>, Result<Submission, ResubmitError>)] {
+        &self.batches
+    }
+
+    /// The result of the transaction that carried the withdrawal with the
+    /// index, or `None` for an index that isn't in the batch.
+    pub fn outcome(&self, index: usize) -> Option<Result<&Submission, &ResubmitError>> {
+        self.batches
+            .iter()
+            .find(|&(group, _)| group.contains(&index))
+            .map(|(_, result)| result.as_ref())
+    }
+
+    /// The indices of the withdrawals that were paid.
+    pub fn succeeded(&self) -> Vec<usize> {
+        self.indices(Some(true))
+    }
+
+    /// The indices of the withdrawals that weren't paid, and can be tried
+    /// again.
+    pub fn failed(&self) -> Vec<usize> {
+        self.indices(Some(false))
+    }
+
+    /// The indices of the withdrawals that may or may not have been paid,
+    /// because submitting them failed without horizon saying what became of
+    /// the transaction. Look the transaction up, or check the destination's
+    /// payments, before paying them again.
+    pub fn unknown(&self) -> Vec<usize> {
+        self.indices(None)
+    }
+
+    /// Whether the withdrawal with the index was paid, `None` if that isn't
+    /// known or the index isn't in the batch.
+    pub fn paid(&self, index: usize) -> Option<bool> {
+        self.batches
+            .iter()
+            .position(|&(ref group, _)| group.contains(&index))
+            .and_then(|batch| self.settled(batch))
+    }
+
+    fn settled(&self, batch: usize) -> Option<bool> {
+        if self.uncertain[batch] {
+            None
+        } else {
+            Some(self.batches[batch].1.is_ok())
+        }
+    }
+
+    fn indices(&self, paid: Option<bool>) -> Vec<usize> {
+        let mut indices: Vec<usize> = self
+            .batches
+            .iter()
+            .enumerate()
+            .filter(|&(batch, _)| self.settled(batch) == paid)
+            .flat_map(|(_, &(ref group, _))| group.iter().cloned())
+            .collect();
+        indices.sort();
+        indices
+    }
+}
+
+#[cfg(test)]
+mod withdrawal_tests {
+    use super::*;
+    use client::sync::test_server::TestServer;
+    use keypair::Keypair;
+    use network::Network;
+
+    const DESTINATION: &str = "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3";
+    const ACCOUNT: &str = include_str!("../fixtures/account.json");
+    const SUBMISSION: &str = include_str!("../fixtures/submission.json");
+    const REJECTED: &str = include_str!("../fixtures/transaction_failed_error.json");
+    const APPLIED: &str = include_str!("../fixtures/transactions/transaction_memo_none.json");
+    const TIMEOUT: &str = r#"{"title": "Timeout"}"#;
+
+    fn withdrawal(memo: Memo) -> Withdrawal {
+        Withdrawal::new(
+            DESTINATION,
+            AssetIdentifier::native(),
+            Amount::new(10_000_000),
+        )
+        .with_memo(memo)
+    }
+
+    fn batch(hot_wallet: &Keypair, memos: Vec<Memo>) -> WithdrawalBatch {
+        memos.into_iter().fold(
+            WithdrawalBatch::new(&hot_wallet.account_id(), Network::TESTNET),
+            |batch, memo| batch.with_withdrawal(withdrawal(memo)),
+        )
+    }
+
+    #[test]
+    fn it_groups_withdrawals_by_memo() {
+        let hot_wallet = Keypair::random();
+        let batch = batch(
+            &hot_wallet,
+            vec![
+                Memo::None,
+                Memo::Id(1),
+                Memo::None,
+                Memo::Id(2),
+                Memo::Id(1),
+            ],
+        );
+        assert_eq!(batch.plan(), vec![vec![0, 2], vec![1, 4], vec![3]]);
+    }
+
+    #[test]
+    fn it_respects_the_operation_limit() {
+        let hot_wallet = Keypair::random();
+        let batch = batch(&hot_wallet, vec![Memo::None; 250]);
+        let sizes: Vec<usize> = batch.plan().iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![100, 100, 50]);
+        let sizes: Vec<usize> = batch
+            .with_max_operations(120)
+            .plan()
+            .iter()
+            .map(Vec::len)
+            .collect();
+        assert_eq!(sizes, vec![100, 100, 50]);
+    }
+
+    #[test]
+    fn it_counts_sequences_locally_per_channel() {
+        let server = TestServer::sequence(vec![
+            ("200 OK", ACCOUNT),
+            ("200 OK", SUBMISSION),
+            ("200 OK", ACCOUNT),
+            ("200 OK", SUBMISSION),
+        ]);
+        let client = Client::new(server.uri()).unwrap();
+        let hot_wallet = Keypair::random();
+        let channels = [Keypair::random(), Keypair::random()];
+        let channels = [Channel::new(&channels[0]), Channel::new(&channels[1])];
+
+        let report = batch(&hot_wallet, vec![Memo::None; 5])
+            .with_max_operations(1)
+            .submit(&client, &hot_wallet, &channels);
+        assert_eq!(report.succeeded(), vec![0, 1, 2, 3, 4]);
+        assert!(report.failed().is_empty());
+        assert_eq!(report.outcome(3).unwrap().unwrap().ledger(), 16_751_283);
+        assert!(report.outcome(5).is_none());
+        // Each channel is fetched before its first transaction only
+        assert_eq!(server.hits(), 7);
+    }
+
+    #[test]
+    fn it_reports_failed_transactions_and_refetches_the_channel() {
+        let server = TestServer::sequence(vec![
+            ("200 OK", ACCOUNT),
+            ("400 Bad Request", REJECTED),
+            ("200 OK", ACCOUNT),
+            ("200 OK", SUBMISSION),
+        ]);
+        let client = Client::new(server.uri()).unwrap();
+        let hot_wallet = Keypair::random();
+
+        let report = batch(&hot_wallet, vec![Memo::Id(1), Memo::Id(2), Memo::Id(1)])
+            .with_policy(ResubmitPolicy::new(0))
+            .submit(&client, &hot_wallet, &[]);
+        assert_eq!(report.failed(), vec![0, 2]);
+        assert_eq!(report.succeeded(), vec![1]);
+        assert!(report.unknown().is_empty());
+        assert!(report.outcome(2).unwrap().is_err());
+        assert_eq!(report.paid(2), Some(false));
+        assert_eq!(server.hits(), 4);
+    }
+
+    #[test]
+    fn it_looks_up_a_transaction_that_timed_out_before_submitting_it_again() {
+        let server = TestServer::sequence(vec![
+            ("200 OK", ACCOUNT),
+            ("504 Gateway Timeout", TIMEOUT),
+            ("200 OK", APPLIED),
+        ]);
+        let client = Client::new(server.uri()).unwrap();
+        let hot_wallet = Keypair::random();
+
+        let report = batch(&hot_wallet, vec![Memo::None]).submit(&client, &hot_wallet, &[]);
+        assert_eq!(report.succeeded(), vec![0]);
+        assert_eq!(report.paid(0), Some(true));
+        // The retry found the transaction rather than submitting it again.
+        assert_eq!(server.hits(), 3);
+    }
+
+    #[test]
+    fn it_reports_withdrawals_it_could_not_settle_as_unknown() {
+        let server = TestServer::sequence(vec![
+            ("200 OK", ACCOUNT),
+            ("504 Gateway Timeout", TIMEOUT),
+        ]);
+        let client = Client::new(server.uri()).unwrap();
+        let hot_wallet = Keypair::random();
+
+        let report = batch(&hot_wallet, vec![Memo::None, Memo::None])
+            .with_max_retries(0)
+            .submit(&client, &hot_wallet, &[]);
+        assert!(report.failed().is_empty());
+        assert!(report.succeeded().is_empty());
+        assert_eq!(report.unknown(), vec![0, 1]);
+        assert_eq!(report.paid(1), None);
+        assert_eq!(server.hits(), 2);
+    }
+}
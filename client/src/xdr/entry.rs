@@ -0,0 +1,1040 @@
+//! Typed representations of the entries that make up the ledger. These decode
+//! directly from XDR so that consumers of history archive buckets or captive
+//! core metadata can work with this crate's resources instead of writing
+//! bindings of their own.
+use super::{Error, Reader, ToXdr, TryFromXdr, Writer};
+use resources::{Amount, AssetIdentifier, PriceRatio};
+use strkey::{self, Version};
+
+type Result<T> = ::std::result::Result<T, Error>;
+
+pub(crate) fn account_id(r: &mut Reader) -> Result<String> {
+    match r.read_i32()? {
+        0 => Ok(strkey::encode(Version::AccountId, r.read_fixed(32)?)),
+        v => Err(Error::unknown_discriminant("PublicKeyType", v)),
+    }
+}
+
+/// Reads a muxed account, giving an `M` address for one with an id and a
+/// `G` address otherwise.
+pub(crate) fn muxed_account(r: &mut Reader) -> Result<String> {
+    match r.read_i32()? {
+        0 => Ok(strkey::encode(Version::AccountId, r.read_fixed(32)?)),
+        0x100 => {
+            let id = r.read_fixed(8)?;
+            let mut payload = r.read_fixed(32)?.to_vec();
+            payload.extend_from_slice(id);
+            Ok(strkey::encode(Version::MuxedAccount, &payload))
+        }
+        v => Err(Error::unknown_discriminant("CryptoKeyType", v)),
+    }
+}
+
+pub(crate) fn optional<T, F>(r: &mut Reader, f: F) -> Result<Option<T>>
+where
+    F: FnOnce(&mut Reader) -> Result<T>,
+{
+    if r.read_optional()? {
+        f(r).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+pub(crate) fn array<T, F>(r: &mut Reader, max: usize, f: F) -> Result<Vec<T>>
+where
+    F: Fn(&mut Reader) -> Result<T>,
+{
+    let len = r.read_len(max)?;
+    let mut values = Vec::with_capacity(len);
+    for _ in 0..len {
+        values.push(f(r)?);
+    }
+    Ok(values)
+}
+
+/// Lowercase hex of the bytes, as hashes and signature hints are shown.
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn amount(r: &mut Reader) -> Result<Amount> {
+    match r.read_i64()? {
+        value if value < 0 => Err(Error::invalid_value("amount")),
+        value => Ok(Amount::new(value)),
+    }
+}
+
+pub(crate) fn ext_v0(r: &mut Reader) -> Result<()> {
+    match r.read_i32()? {
+        0 => Ok(()),
+        v => Err(Error::unknown_discriminant("ext", v)),
+    }
+}
+
+fn asset_code(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+impl TryFromXdr for AssetIdentifier {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        match r.read_i32()? {
+            0 => Ok(AssetIdentifier::native()),
+            1 => {
+                let code = asset_code(r.read_fixed(4)?);
+                Ok(AssetIdentifier::alphanum4(&code, &account_id(r)?))
+            }
+            2 => {
+                let code = asset_code(r.read_fixed(12)?);
+                Ok(AssetIdentifier::alphanum12(&code, &account_id(r)?))
+            }
+            v => Err(Error::unknown_discriminant("AssetType", v)),
+        }
+    }
+}
+
+impl ToXdr for AssetIdentifier {
+    fn to_xdr(&self, w: &mut Writer) -> Result<()> {
+        let (discriminant, len) = match *self {
+            AssetIdentifier::Native => {
+                w.write_i32(0);
+                return Ok(());
+            }
+            AssetIdentifier::CreditAlphanum4(_) => (1, 4),
+            AssetIdentifier::CreditAlphanum12(_) => (2, 12),
+        };
+        let code = self.code().as_bytes();
+        if code.is_empty() || code.len() > len {
+            return Err(Error::invalid_value("asset code"));
+        }
+        let mut padded = vec![0; len];
+        padded[..code.len()].copy_from_slice(code);
+        w.write_i32(discriminant);
+        w.write_fixed(&padded);
+        w.write_account_id(self.issuer())
+    }
+}
+
+impl ToXdr for PriceRatio {
+    fn to_xdr(&self, w: &mut Writer) -> Result<()> {
+        let max = i32::MAX as u64;
+        if self.numerator() > max || self.denominator() > max || self.denominator() == 0 {
+            return Err(Error::invalid_value("price"));
+        }
+        w.write_i32(self.numerator() as i32);
+        w.write_i32(self.denominator() as i32);
+        Ok(())
+    }
+}
+
+impl TryFromXdr for PriceRatio {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        let n = r.read_i32()?;
+        let d = r.read_i32()?;
+        if n < 0 || d <= 0 {
+            return Err(Error::invalid_value("price"));
+        }
+        Ok(PriceRatio::new(n as u64, d as u64))
+    }
+}
+
+/// An asset held by a trust line. Besides the usual credit assets a trust
+/// line can hold shares of a liquidity pool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrustLineAsset {
+    /// A native or credit asset.
+    Asset(AssetIdentifier),
+    /// Shares in the liquidity pool with the given hex encoded id.
+    PoolShare(String),
+}
+
+impl TryFromXdr for TrustLineAsset {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        let mut peek = r.clone();
+        if peek.read_i32()? == 3 {
+            r.read_i32()?;
+            Ok(TrustLineAsset::PoolShare(hex(r.read_fixed(32)?)))
+        } else {
+            AssetIdentifier::try_from_xdr(r).map(TrustLineAsset::Asset)
+        }
+    }
+}
+
+/// The amounts reserved for open offers on an account or trust line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Liabilities {
+    buying: Amount,
+    selling: Amount,
+}
+
+impl Liabilities {
+    /// The amount committed to buying through open offers.
+    pub fn buying(&self) -> Amount {
+        self.buying
+    }
+
+    /// The amount committed to selling through open offers.
+    pub fn selling(&self) -> Amount {
+        self.selling
+    }
+}
+
+impl TryFromXdr for Liabilities {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        Ok(Liabilities {
+            buying: amount(r)?,
+            selling: amount(r)?,
+        })
+    }
+}
+
+/// A signer on an account. The key is encoded as a strkey, so an ed25519 key
+/// starts with a `G`, a pre-authorized transaction with a `T` and so on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signer {
+    key: String,
+    weight: u32,
+}
+
+impl Signer {
+    /// The strkey encoded signer key.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// The weight this signer contributes towards thresholds.
+    pub fn weight(&self) -> u32 {
+        self.weight
+    }
+}
+
+pub(crate) fn signer_key(r: &mut Reader) -> Result<String> {
+    match r.read_i32()? {
+        0 => Ok(strkey::encode(Version::AccountId, r.read_fixed(32)?)),
+        1 => Ok(strkey::encode(Version::PreAuthTx, r.read_fixed(32)?)),
+        2 => Ok(strkey::encode(Version::Sha256Hash, r.read_fixed(32)?)),
+        3 => {
+            let mut payload = r.read_fixed(32)?.to_vec();
+            let signed = r.read_var(64)?;
+            let len = signed.len() as u32;
+            payload.extend_from_slice(&[(len >> 24) as u8, (len >> 16) as u8, (len >> 8) as u8, len as u8]);
+            payload.extend_from_slice(signed);
+            while payload.len() % 4 != 0 {
+                payload.push(0);
+            }
+            Ok(strkey::encode(Version::SignedPayload, &payload))
+        }
+        v => Err(Error::unknown_discriminant("SignerKeyType", v)),
+    }
+}
+
+impl TryFromXdr for Signer {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        Ok(Signer {
+            key: signer_key(r)?,
+            weight: r.read_u32()?,
+        })
+    }
+}
+
+/// The state of an account in the ledger.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountEntry {
+    account_id: String,
+    balance: Amount,
+    seq_num: i64,
+    num_sub_entries: u32,
+    inflation_dest: Option<String>,
+    flags: u32,
+    home_domain: String,
+    thresholds: [u8; 4],
+    signers: Vec<Signer>,
+    liabilities: Option<Liabilities>,
+    num_sponsored: u32,
+    num_sponsoring: u32,
+    signer_sponsoring_ids: Vec<Option<String>>,
+}
+
+impl AccountEntry {
+    /// The account's public key.
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    /// The native balance of the account.
+    pub fn balance(&self) -> Amount {
+        self.balance
+    }
+
+    /// The current sequence number of the account.
+    pub fn sequence(&self) -> i64 {
+        self.seq_num
+    }
+
+    /// The number of trust lines, offers, signers and data entries the account owns.
+    pub fn num_sub_entries(&self) -> u32 {
+        self.num_sub_entries
+    }
+
+    /// The account that receives this account's inflation votes, if set.
+    pub fn inflation_dest(&self) -> Option<&str> {
+        self.inflation_dest.as_ref().map(|s| &**s)
+    }
+
+    /// The raw flags bitmask of the account.
+    pub fn flags(&self) -> u32 {
+        self.flags
+    }
+
+    /// Returns true if trust lines must be authorized by this issuer.
+    pub fn is_auth_required(&self) -> bool {
+        self.flags & 0x1 != 0
+    }
+
+    /// Returns true if this issuer can revoke authorization of trust lines.
+    pub fn is_auth_revocable(&self) -> bool {
+        self.flags & 0x2 != 0
+    }
+
+    /// Returns true if the account's flags can no longer be changed.
+    pub fn is_auth_immutable(&self) -> bool {
+        self.flags & 0x4 != 0
+    }
+
+    /// Returns true if this issuer can claw back its assets.
+    pub fn is_auth_clawback_enabled(&self) -> bool {
+        self.flags & 0x8 != 0
+    }
+
+    /// The home domain of the account.
+    pub fn home_domain(&self) -> &str {
+        &self.home_domain
+    }
+
+    /// The weight of the account's master key.
+    pub fn master_weight(&self) -> u8 {
+        self.thresholds[0]
+    }
+
+    /// The low, medium and high thresholds of the account.
+    pub fn thresholds(&self) -> (u8, u8, u8) {
+        (self.thresholds[1], self.thresholds[2], self.thresholds[3])
+    }
+
+    /// The additional signers on the account.
+    pub fn signers(&self) -> &[Signer] {
+        &self.signers
+    }
+
+    /// The liabilities of the account, if it has ever had open offers.
+    pub fn liabilities(&self) -> Option<Liabilities> {
+        self.liabilities
+    }
+
+    /// The number of reserves being paid for by other accounts.
+    pub fn num_sponsored(&self) -> u32 {
+        self.num_sponsored
+    }
+
+    /// The number of reserves this account is paying for other accounts.
+    pub fn num_sponsoring(&self) -> u32 {
+        self.num_sponsoring
+    }
+
+    /// The sponsors of each signer, in the same order as the signers.
+    pub fn signer_sponsoring_ids(&self) -> &[Option<String>] {
+        &self.signer_sponsoring_ids
+    }
+}
+
+impl TryFromXdr for AccountEntry {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        let mut entry = AccountEntry {
+            account_id: account_id(r)?,
+            balance: amount(r)?,
+            seq_num: r.read_i64()?,
+            num_sub_entries: r.read_u32()?,
+            inflation_dest: optional(r, account_id)?,
+            flags: r.read_u32()?,
+            home_domain: r.read_string(32)?,
+            thresholds: {
+                let bytes = r.read_fixed(4)?;
+                [bytes[0], bytes[1], bytes[2], bytes[3]]
+            },
+            signers: array(r, 20, Signer::try_from_xdr)?,
+            liabilities: None,
+            num_sponsored: 0,
+            num_sponsoring: 0,
+            signer_sponsoring_ids: Vec::new(),
+        };
+        match r.read_i32()? {
+            0 => {}
+            1 => {
+                entry.liabilities = Some(Liabilities::try_from_xdr(r)?);
+                match r.read_i32()? {
+                    0 => {}
+                    2 => {
+                        entry.num_sponsored = r.read_u32()?;
+                        entry.num_sponsoring = r.read_u32()?;
+                        entry.signer_sponsoring_ids =
+                            array(r, 20, |r| optional(r, account_id))?;
+                        match r.read_i32()? {
+                            0 => {}
+                            3 => {
+                                ext_v0(r)?;
+                                // Sequence ledger and time are not yet exposed.
+                                r.read_u32()?;
+                                r.read_u64()?;
+                            }
+                            v => return Err(Error::unknown_discriminant("AccountEntryExtensionV2", v)),
+                        }
+                    }
+                    v => return Err(Error::unknown_discriminant("AccountEntryExtensionV1", v)),
+                }
+            }
+            v => return Err(Error::unknown_discriminant("AccountEntry", v)),
+        }
+        Ok(entry)
+    }
+}
+
+/// The state of a trust line between an account and an asset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrustLineEntry {
+    account_id: String,
+    asset: TrustLineAsset,
+    balance: Amount,
+    limit: Amount,
+    flags: u32,
+    liabilities: Option<Liabilities>,
+    liquidity_pool_use_count: i32,
+}
+
+impl TrustLineEntry {
+    /// The account that holds the trust line.
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    /// The asset that is trusted.
+    pub fn asset(&self) -> &TrustLineAsset {
+        &self.asset
+    }
+
+    /// The amount of the asset held.
+    pub fn balance(&self) -> Amount {
+        self.balance
+    }
+
+    /// The maximum amount of the asset the account is willing to hold.
+    pub fn limit(&self) -> Amount {
+        self.limit
+    }
+
+    /// The raw flags bitmask of the trust line.
+    pub fn flags(&self) -> u32 {
+        self.flags
+    }
+
+    /// Returns true if the issuer has authorized the trust line.
+    pub fn is_authorized(&self) -> bool {
+        self.flags & 0x1 != 0
+    }
+
+    /// Returns true if the trust line may only maintain existing liabilities.
+    pub fn is_authorized_to_maintain_liabilities(&self) -> bool {
+        self.flags & 0x2 != 0
+    }
+
+    /// Returns true if the issuer can claw back from this trust line.
+    pub fn is_clawback_enabled(&self) -> bool {
+        self.flags & 0x4 != 0
+    }
+
+    /// The liabilities of the trust line, if it has ever had open offers.
+    pub fn liabilities(&self) -> Option<Liabilities> {
+        self.liabilities
+    }
+
+    /// The number of liquidity pools that use this trust line.
+    pub fn liquidity_pool_use_count(&self) -> i32 {
+        self.liquidity_pool_use_count
+    }
+}
+
+impl TryFromXdr for TrustLineEntry {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        let mut entry = TrustLineEntry {
+            account_id: account_id(r)?,
+            asset: TrustLineAsset::try_from_xdr(r)?,
+            balance: amount(r)?,
+            limit: amount(r)?,
+            flags: r.read_u32()?,
+            liabilities: None,
+            liquidity_pool_use_count: 0,
+        };
+        match r.read_i32()? {
+            0 => {}
+            1 => {
+                entry.liabilities = Some(Liabilities::try_from_xdr(r)?);
+                match r.read_i32()? {
+                    0 => {}
+                    2 => {
+                        entry.liquidity_pool_use_count = r.read_i32()?;
+                        ext_v0(r)?;
+                    }
+                    v => return Err(Error::unknown_discriminant("TrustLineEntryV1", v)),
+                }
+            }
+            v => return Err(Error::unknown_discriminant("TrustLineEntry", v)),
+        }
+        Ok(entry)
+    }
+}
+
+/// An open offer on the distributed exchange.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OfferEntry {
+    seller_id: String,
+    offer_id: i64,
+    selling: AssetIdentifier,
+    buying: AssetIdentifier,
+    amount: Amount,
+    price: PriceRatio,
+    flags: u32,
+}
+
+impl OfferEntry {
+    /// The account that created the offer.
+    pub fn seller_id(&self) -> &str {
+        &self.seller_id
+    }
+
+    /// The unique id of the offer.
+    pub fn offer_id(&self) -> i64 {
+        self.offer_id
+    }
+
+    /// The asset being sold.
+    pub fn selling(&self) -> &AssetIdentifier {
+        &self.selling
+    }
+
+    /// The asset being bought.
+    pub fn buying(&self) -> &AssetIdentifier {
+        &self.buying
+    }
+
+    /// The amount of the selling asset that is offered.
+    pub fn amount(&self) -> Amount {
+        self.amount
+    }
+
+    /// The price of one unit of selling in terms of buying.
+    pub fn price(&self) -> PriceRatio {
+        self.price
+    }
+
+    /// Returns true if the offer is passive.
+    pub fn is_passive(&self) -> bool {
+        self.flags & 0x1 != 0
+    }
+}
+
+impl TryFromXdr for OfferEntry {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        let entry = OfferEntry {
+            seller_id: account_id(r)?,
+            offer_id: r.read_i64()?,
+            selling: AssetIdentifier::try_from_xdr(r)?,
+            buying: AssetIdentifier::try_from_xdr(r)?,
+            amount: amount(r)?,
+            price: PriceRatio::try_from_xdr(r)?,
+            flags: r.read_u32()?,
+        };
+        ext_v0(r)?;
+        Ok(entry)
+    }
+}
+
+/// A key value pair attached to an account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataEntry {
+    account_id: String,
+    name: String,
+    value: Vec<u8>,
+}
+
+impl DataEntry {
+    /// The account the data is attached to.
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    /// The key of the data entry.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The raw value of the data entry.
+    pub fn value(&self) -> &[u8] {
+        &self.value
+    }
+}
+
+impl TryFromXdr for DataEntry {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        let entry = DataEntry {
+            account_id: account_id(r)?,
+            name: r.read_string(64)?,
+            value: r.read_var(64)?.to_vec(),
+        };
+        ext_v0(r)?;
+        Ok(entry)
+    }
+}
+
+/// The condition under which a claimant may claim a balance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClaimPredicate {
+    /// The balance can always be claimed.
+    Unconditional,
+    /// All of the predicates must be satisfied.
+    And(Vec<ClaimPredicate>),
+    /// Any of the predicates must be satisfied.
+    Or(Vec<ClaimPredicate>),
+    /// The predicate must not be satisfied.
+    Not(Option<Box<ClaimPredicate>>),
+    /// The balance must be claimed before the unix timestamp.
+    BeforeAbsoluteTime(i64),
+    /// The balance must be claimed within this many seconds of its creation.
+    BeforeRelativeTime(i64),
+}
+
+impl TryFromXdr for ClaimPredicate {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        match r.read_i32()? {
+            0 => Ok(ClaimPredicate::Unconditional),
+            1 => array(r, 2, ClaimPredicate::try_from_xdr).map(ClaimPredicate::And),
+            2 => array(r, 2, ClaimPredicate::try_from_xdr).map(ClaimPredicate::Or),
+            3 => optional(r, ClaimPredicate::try_from_xdr)
+                .map(|p| ClaimPredicate::Not(p.map(Box::new))),
+            4 => r.read_i64().map(ClaimPredicate::BeforeAbsoluteTime),
+            5 => r.read_i64().map(ClaimPredicate::BeforeRelativeTime),
+            v => Err(Error::unknown_discriminant("ClaimPredicateType", v)),
+        }
+    }
+}
+
+/// An account that can claim a claimable balance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Claimant {
+    destination: String,
+    predicate: ClaimPredicate,
+}
+
+impl Claimant {
+    /// The account that may claim the balance.
+    pub fn destination(&self) -> &str {
+        &self.destination
+    }
+
+    /// The condition under which it may be claimed.
+    pub fn predicate(&self) -> &ClaimPredicate {
+        &self.predicate
+    }
+}
+
+impl TryFromXdr for Claimant {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        match r.read_i32()? {
+            0 => Ok(Claimant {
+                destination: account_id(r)?,
+                predicate: ClaimPredicate::try_from_xdr(r)?,
+            }),
+            v => Err(Error::unknown_discriminant("ClaimantType", v)),
+        }
+    }
+}
+
+// Claimable balance ids are displayed by horizon with their type prefixed.
+pub(crate) fn claimable_balance_id(r: &mut Reader) -> Result<String> {
+    match r.read_i32()? {
+        0 => Ok(format!("00000000{}", hex(r.read_fixed(32)?))),
+        v => Err(Error::unknown_discriminant("ClaimableBalanceIDType", v)),
+    }
+}
+
+/// A balance that is waiting to be claimed by one of its claimants.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClaimableBalanceEntry {
+    balance_id: String,
+    claimants: Vec<Claimant>,
+    asset: AssetIdentifier,
+    amount: Amount,
+    flags: u32,
+}
+
+impl ClaimableBalanceEntry {
+    /// The id of the balance as horizon displays it.
+    pub fn balance_id(&self) -> &str {
+        &self.balance_id
+    }
+
+    /// The accounts that may claim the balance.
+    pub fn claimants(&self) -> &[Claimant] {
+        &self.claimants
+    }
+
+    /// The asset of the balance.
+    pub fn asset(&self) -> &AssetIdentifier {
+        &self.asset
+    }
+
+    /// The amount of the balance.
+    pub fn amount(&self) -> Amount {
+        self.amount
+    }
+
+    /// Returns true if the issuer can claw back the balance.
+    pub fn is_clawback_enabled(&self) -> bool {
+        self.flags & 0x1 != 0
+    }
+}
+
+impl TryFromXdr for ClaimableBalanceEntry {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        let mut entry = ClaimableBalanceEntry {
+            balance_id: claimable_balance_id(r)?,
+            claimants: array(r, 10, Claimant::try_from_xdr)?,
+            asset: AssetIdentifier::try_from_xdr(r)?,
+            amount: amount(r)?,
+            flags: 0,
+        };
+        match r.read_i32()? {
+            0 => {}
+            1 => {
+                ext_v0(r)?;
+                entry.flags = r.read_u32()?;
+            }
+            v => return Err(Error::unknown_discriminant("ClaimableBalanceEntry", v)),
+        }
+        Ok(entry)
+    }
+}
+
+/// A constant product liquidity pool.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiquidityPoolEntry {
+    liquidity_pool_id: String,
+    asset_a: AssetIdentifier,
+    asset_b: AssetIdentifier,
+    fee: i32,
+    reserve_a: Amount,
+    reserve_b: Amount,
+    total_pool_shares: Amount,
+    pool_shares_trust_line_count: i64,
+}
+
+impl LiquidityPoolEntry {
+    /// The hex encoded id of the pool.
+    pub fn liquidity_pool_id(&self) -> &str {
+        &self.liquidity_pool_id
+    }
+
+    /// The first asset of the pool.
+    pub fn asset_a(&self) -> &AssetIdentifier {
+        &self.asset_a
+    }
+
+    /// The second asset of the pool.
+    pub fn asset_b(&self) -> &AssetIdentifier {
+        &self.asset_b
+    }
+
+    /// The fee charged on trades, in basis points.
+    pub fn fee_bp(&self) -> i32 {
+        self.fee
+    }
+
+    /// The amount of the first asset in the pool.
+    pub fn reserve_a(&self) -> Amount {
+        self.reserve_a
+    }
+
+    /// The amount of the second asset in the pool.
+    pub fn reserve_b(&self) -> Amount {
+        self.reserve_b
+    }
+
+    /// The total number of pool shares issued.
+    pub fn total_pool_shares(&self) -> Amount {
+        self.total_pool_shares
+    }
+
+    /// The number of trust lines holding shares in the pool.
+    pub fn pool_shares_trust_line_count(&self) -> i64 {
+        self.pool_shares_trust_line_count
+    }
+}
+
+impl TryFromXdr for LiquidityPoolEntry {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        let liquidity_pool_id = hex(r.read_fixed(32)?);
+        match r.read_i32()? {
+            0 => Ok(LiquidityPoolEntry {
+                liquidity_pool_id,
+                asset_a: AssetIdentifier::try_from_xdr(r)?,
+                asset_b: AssetIdentifier::try_from_xdr(r)?,
+                fee: r.read_i32()?,
+                reserve_a: amount(r)?,
+                reserve_b: amount(r)?,
+                total_pool_shares: amount(r)?,
+                pool_shares_trust_line_count: r.read_i64()?,
+            }),
+            v => Err(Error::unknown_discriminant("LiquidityPoolType", v)),
+        }
+    }
+}
+
+/// The data held by a ledger entry.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LedgerEntryData {
+    /// An account.
+    Account(AccountEntry),
+    /// A trust line.
+    Trustline(TrustLineEntry),
+    /// An offer.
+    Offer(OfferEntry),
+    /// A data entry on an account.
+    Data(DataEntry),
+    /// A claimable balance.
+    ClaimableBalance(ClaimableBalanceEntry),
+    /// A liquidity pool.
+    LiquidityPool(LiquidityPoolEntry),
+}
+
+impl TryFromXdr for LedgerEntryData {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        match r.read_i32()? {
+            0 => AccountEntry::try_from_xdr(r).map(LedgerEntryData::Account),
+            1 => TrustLineEntry::try_from_xdr(r).map(LedgerEntryData::Trustline),
+            2 => OfferEntry::try_from_xdr(r).map(LedgerEntryData::Offer),
+            3 => DataEntry::try_from_xdr(r).map(LedgerEntryData::Data),
+            4 => ClaimableBalanceEntry::try_from_xdr(r).map(LedgerEntryData::ClaimableBalance),
+            5 => LiquidityPoolEntry::try_from_xdr(r).map(LedgerEntryData::LiquidityPool),
+            v => Err(Error::unknown_discriminant("LedgerEntryType", v)),
+        }
+    }
+}
+
+/// A single entry in the ledger along with when it was last modified.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LedgerEntry {
+    last_modified_ledger_seq: u32,
+    data: LedgerEntryData,
+    sponsoring_id: Option<String>,
+}
+
+impl LedgerEntry {
+    /// The sequence of the ledger that last modified this entry.
+    pub fn last_modified_ledger_seq(&self) -> u32 {
+        self.last_modified_ledger_seq
+    }
+
+    /// The data of the entry.
+    pub fn data(&self) -> &LedgerEntryData {
+        &self.data
+    }
+
+    /// The account paying the reserve for this entry, if it is sponsored.
+    pub fn sponsoring_id(&self) -> Option<&str> {
+        self.sponsoring_id.as_ref().map(|s| &**s)
+    }
+
+    /// The key that uniquely identifies this entry in the ledger.
+    pub fn key(&self) -> LedgerKey {
+        match self.data {
+            LedgerEntryData::Account(ref e) => LedgerKey::Account {
+                account_id: e.account_id.clone(),
+            },
+            LedgerEntryData::Trustline(ref e) => LedgerKey::Trustline {
+                account_id: e.account_id.clone(),
+                asset: e.asset.clone(),
+            },
+            LedgerEntryData::Offer(ref e) => LedgerKey::Offer {
+                seller_id: e.seller_id.clone(),
+                offer_id: e.offer_id,
+            },
+            LedgerEntryData::Data(ref e) => LedgerKey::Data {
+                account_id: e.account_id.clone(),
+                name: e.name.clone(),
+            },
+            LedgerEntryData::ClaimableBalance(ref e) => LedgerKey::ClaimableBalance {
+                balance_id: e.balance_id.clone(),
+            },
+            LedgerEntryData::LiquidityPool(ref e) => LedgerKey::LiquidityPool {
+                liquidity_pool_id: e.liquidity_pool_id.clone(),
+            },
+        }
+    }
+}
+
+impl TryFromXdr for LedgerEntry {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        let last_modified_ledger_seq = r.read_u32()?;
+        let data = LedgerEntryData::try_from_xdr(r)?;
+        let sponsoring_id = match r.read_i32()? {
+            0 => None,
+            1 => {
+                let sponsor = optional(r, account_id)?;
+                ext_v0(r)?;
+                sponsor
+            }
+            v => return Err(Error::unknown_discriminant("LedgerEntryExt", v)),
+        };
+        Ok(LedgerEntry {
+            last_modified_ledger_seq,
+            data,
+            sponsoring_id,
+        })
+    }
+}
+
+/// The fields that uniquely identify an entry in the ledger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LedgerKey {
+    /// Identifies an account.
+    Account {
+        /// The account's public key.
+        account_id: String,
+    },
+    /// Identifies a trust line.
+    Trustline {
+        /// The account holding the trust line.
+        account_id: String,
+        /// The asset being trusted.
+        asset: TrustLineAsset,
+    },
+    /// Identifies an offer.
+    Offer {
+        /// The account that created the offer.
+        seller_id: String,
+        /// The id of the offer.
+        offer_id: i64,
+    },
+    /// Identifies a data entry.
+    Data {
+        /// The account the data is attached to.
+        account_id: String,
+        /// The key of the data entry.
+        name: String,
+    },
+    /// Identifies a claimable balance.
+    ClaimableBalance {
+        /// The id of the balance.
+        balance_id: String,
+    },
+    /// Identifies a liquidity pool.
+    LiquidityPool {
+        /// The hex encoded id of the pool.
+        liquidity_pool_id: String,
+    },
+}
+
+impl TryFromXdr for LedgerKey {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        match r.read_i32()? {
+            0 => Ok(LedgerKey::Account {
+                account_id: account_id(r)?,
+            }),
+            1 => Ok(LedgerKey::Trustline {
+                account_id: account_id(r)?,
+                asset: TrustLineAsset::try_from_xdr(r)?,
+            }),
+            2 => Ok(LedgerKey::Offer {
+                seller_id: account_id(r)?,
+                offer_id: r.read_i64()?,
+            }),
+            3 => Ok(LedgerKey::Data {
+                account_id: account_id(r)?,
+                name: r.read_string(64)?,
+            }),
+            4 => Ok(LedgerKey::ClaimableBalance {
+                balance_id: claimable_balance_id(r)?,
+            }),
+            5 => Ok(LedgerKey::LiquidityPool {
+                liquidity_pool_id: hex(r.read_fixed(32)?),
+            }),
+            v => Err(Error::unknown_discriminant("LedgerEntryType", v)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod ledger_entry_tests {
+    use super::*;
+
+    // An account entry taken from the result meta of a testnet transaction.
+    const ACCOUNT_ENTRY: &str = "AP+aswAAAAAAAAAAfYea3UlYx+oB1SUN7JS071XzM+onRosbwqtnJe/vxAYAKrPQlwBTPgAIgb4AAtRiAAAAAAAAAAAAAAAAAAAAAAEAAAAAAAAAAAAAAAAAAAA=";
+
+    // A trust line for a self issued ABC with liabilities and a pool use count.
+    const TRUSTLINE_ENTRY: &str = "AAAAZAAAAAEAAAAAfYea3UlYx+oB1SUN7JS071XzM+onRosbwqtnJe/vxAYAAAABQUJDAAAAAAB9h5rdSVjH6gHVJQ3slLTvVfMz6idGixvCq2cl7+/EBgAAAAA7msoAf/////////8AAAABAAAAAQAAAAAF9eEAAAAAAAAAAAAAAAACAAAAAgAAAAAAAAAA";
+
+    #[test]
+    fn it_decodes_an_account_entry() {
+        let entry = LedgerEntry::try_from_base64(ACCOUNT_ENTRY).unwrap();
+        assert_eq!(entry.last_modified_ledger_seq(), 16_751_283);
+        assert_eq!(entry.sponsoring_id(), None);
+        match *entry.data() {
+            LedgerEntryData::Account(ref account) => {
+                assert_eq!(
+                    account.account_id(),
+                    "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3"
+                );
+                assert_eq!(account.balance(), Amount::new(12_019_657_489_797_950));
+                assert_eq!(account.sequence(), 2_394_452_857_640_034);
+                assert_eq!(account.master_weight(), 1);
+                assert_eq!(account.thresholds(), (0, 0, 0));
+                assert!(account.signers().is_empty());
+                assert_eq!(account.liabilities(), None);
+            }
+            _ => panic!("Expected an account entry"),
+        }
+        assert_eq!(
+            entry.key(),
+            LedgerKey::Account {
+                account_id: "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3"
+                    .to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn it_decodes_a_trustline_entry() {
+        let entry = LedgerEntry::try_from_base64(TRUSTLINE_ENTRY).unwrap();
+        match *entry.data() {
+            LedgerEntryData::Trustline(ref line) => {
+                assert_eq!(
+                    line.asset(),
+                    &TrustLineAsset::Asset(AssetIdentifier::alphanum4(
+                        "ABC",
+                        "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3"
+                    ))
+                );
+                assert_eq!(line.balance(), Amount::new(1_000_000_000));
+                assert!(line.is_authorized());
+                assert!(!line.is_clawback_enabled());
+                assert_eq!(line.liabilities().unwrap().buying(), Amount::new(100_000_000));
+                assert_eq!(line.liquidity_pool_use_count(), 2);
+            }
+            _ => panic!("Expected a trust line entry"),
+        }
+    }
+
+    #[test]
+    fn it_errors_on_truncated_entries() {
+        assert!(LedgerEntry::try_from_base64(&ACCOUNT_ENTRY[..40]).is_err());
+    }
+}
@@ -0,0 +1,944 @@
+//! Decoders that walk the stellar XDR schema and produce a generic node tree.
+//! Each function mirrors a definition from the stellar-core `.x` files and
+//! uses the same field and arm names so the output can be compared against
+//! the laboratory's viewer.
+use super::{Error, Node, Reader};
+use strkey::{self, Version};
+
+type Result<T> = ::std::result::Result<T, Error>;
+
+macro_rules! fields {
+    ($($name:expr => $value:expr),* $(,)*) => {
+        Node::Struct(vec![$(($name, $value)),*])
+    };
+}
+
+fn arm(name: &str, node: Node) -> Node {
+    Node::Union(name.to_string(), Box::new(node))
+}
+
+fn uint32(r: &mut Reader) -> Result<Node> {
+    r.read_u32().map(|v| Node::UInt(u64::from(v)))
+}
+
+fn int32(r: &mut Reader) -> Result<Node> {
+    r.read_i32().map(|v| Node::Int(i64::from(v)))
+}
+
+fn uint64(r: &mut Reader) -> Result<Node> {
+    r.read_u64().map(Node::UInt)
+}
+
+fn int64(r: &mut Reader) -> Result<Node> {
+    r.read_i64().map(Node::Int)
+}
+
+fn hash(r: &mut Reader) -> Result<Node> {
+    r.read_fixed(32).map(|b| Node::Bytes(b.to_vec()))
+}
+
+fn string(r: &mut Reader, max: usize) -> Result<Node> {
+    r.read_string(max).map(Node::Text)
+}
+
+fn optional<F>(r: &mut Reader, f: F) -> Result<Node>
+where
+    F: FnOnce(&mut Reader) -> Result<Node>,
+{
+    if r.read_optional()? {
+        Ok(Node::Optional(Some(Box::new(f(r)?))))
+    } else {
+        Ok(Node::Optional(None))
+    }
+}
+
+fn array<F>(r: &mut Reader, max: usize, f: F) -> Result<Node>
+where
+    F: Fn(&mut Reader) -> Result<Node>,
+{
+    let len = r.read_len(max)?;
+    let mut nodes = Vec::with_capacity(len);
+    for _ in 0..len {
+        nodes.push(f(r)?);
+    }
+    Ok(Node::Array(nodes))
+}
+
+fn ext_v0(r: &mut Reader) -> Result<Node> {
+    match r.read_i32()? {
+        0 => Ok(arm("v0", Node::Void)),
+        v => Err(Error::unknown_discriminant("ext", v)),
+    }
+}
+
+fn ed25519(r: &mut Reader, version: Version) -> Result<Node> {
+    r.read_fixed(32)
+        .map(|key| Node::Text(strkey::encode(version, key)))
+}
+
+fn asset_code(bytes: &[u8]) -> Node {
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    Node::Text(String::from_utf8_lossy(&bytes[..end]).into_owned())
+}
+
+fn account_id(r: &mut Reader) -> Result<Node> {
+    match r.read_i32()? {
+        0 => ed25519(r, Version::AccountId),
+        v => Err(Error::unknown_discriminant("PublicKeyType", v)),
+    }
+}
+
+fn muxed_account(r: &mut Reader) -> Result<Node> {
+    match r.read_i32()? {
+        0 => ed25519(r, Version::AccountId),
+        0x100 => {
+            let id = r.read_fixed(8)?;
+            let key = r.read_fixed(32)?;
+            let mut payload = key.to_vec();
+            payload.extend_from_slice(id);
+            Ok(Node::Text(strkey::encode(Version::MuxedAccount, &payload)))
+        }
+        v => Err(Error::unknown_discriminant("CryptoKeyType", v)),
+    }
+}
+
+fn signer_key(r: &mut Reader) -> Result<Node> {
+    match r.read_i32()? {
+        0 => ed25519(r, Version::AccountId),
+        1 => ed25519(r, Version::PreAuthTx),
+        2 => ed25519(r, Version::Sha256Hash),
+        3 => {
+            let key = r.read_fixed(32)?;
+            let payload = r.read_var(64)?;
+            Ok(fields! {
+                "ed25519" => Node::Text(strkey::encode(Version::AccountId, key)),
+                "payload" => Node::Bytes(payload.to_vec()),
+            })
+        }
+        v => Err(Error::unknown_discriminant("SignerKeyType", v)),
+    }
+}
+
+fn signer(r: &mut Reader) -> Result<Node> {
+    Ok(fields! {
+        "key" => signer_key(r)?,
+        "weight" => uint32(r)?,
+    })
+}
+
+fn asset(r: &mut Reader) -> Result<Node> {
+    match r.read_i32()? {
+        0 => Ok(arm("ASSET_TYPE_NATIVE", Node::Void)),
+        1 => Ok(arm(
+            "ASSET_TYPE_CREDIT_ALPHANUM4",
+            fields! {
+                "assetCode" => asset_code(r.read_fixed(4)?),
+                "issuer" => account_id(r)?,
+            },
+        )),
+        2 => Ok(arm(
+            "ASSET_TYPE_CREDIT_ALPHANUM12",
+            fields! {
+                "assetCode" => asset_code(r.read_fixed(12)?),
+                "issuer" => account_id(r)?,
+            },
+        )),
+        v => Err(Error::unknown_discriminant("AssetType", v)),
+    }
+}
+
+// The pool share arm of the trust line and change trust assets. Either
+// carries the credit assets as the standard asset does.
+fn pool_share_asset<F>(r: &mut Reader, pool: F) -> Result<Node>
+where
+    F: FnOnce(&mut Reader) -> Result<Node>,
+{
+    let mut peek = r.clone();
+    match peek.read_i32()? {
+        3 => {
+            r.read_i32()?;
+            Ok(arm("ASSET_TYPE_POOL_SHARE", pool(r)?))
+        }
+        _ => asset(r),
+    }
+}
+
+fn liquidity_pool_parameters(r: &mut Reader) -> Result<Node> {
+    match r.read_i32()? {
+        0 => Ok(arm(
+            "LIQUIDITY_POOL_CONSTANT_PRODUCT",
+            fields! {
+                "assetA" => asset(r)?,
+                "assetB" => asset(r)?,
+                "fee" => int32(r)?,
+            },
+        )),
+        v => Err(Error::unknown_discriminant("LiquidityPoolType", v)),
+    }
+}
+
+fn change_trust_asset(r: &mut Reader) -> Result<Node> {
+    pool_share_asset(r, liquidity_pool_parameters)
+}
+
+fn trust_line_asset(r: &mut Reader) -> Result<Node> {
+    pool_share_asset(r, hash)
+}
+
+fn price(r: &mut Reader) -> Result<Node> {
+    Ok(fields! {
+        "n" => int32(r)?,
+        "d" => int32(r)?,
+    })
+}
+
+fn memo(r: &mut Reader) -> Result<Node> {
+    match r.read_i32()? {
+        0 => Ok(arm("MEMO_NONE", Node::Void)),
+        1 => Ok(arm("MEMO_TEXT", string(r, 28)?)),
+        2 => Ok(arm("MEMO_ID", uint64(r)?)),
+        3 => Ok(arm("MEMO_HASH", hash(r)?)),
+        4 => Ok(arm("MEMO_RETURN", hash(r)?)),
+        v => Err(Error::unknown_discriminant("MemoType", v)),
+    }
+}
+
+fn time_bounds(r: &mut Reader) -> Result<Node> {
+    Ok(fields! {
+        "minTime" => uint64(r)?,
+        "maxTime" => uint64(r)?,
+    })
+}
+
+fn ledger_bounds(r: &mut Reader) -> Result<Node> {
+    Ok(fields! {
+        "minLedger" => uint32(r)?,
+        "maxLedger" => uint32(r)?,
+    })
+}
+
+fn preconditions(r: &mut Reader) -> Result<Node> {
+    match r.read_i32()? {
+        0 => Ok(arm("PRECOND_NONE", Node::Void)),
+        1 => Ok(arm("PRECOND_TIME", time_bounds(r)?)),
+        2 => Ok(arm(
+            "PRECOND_V2",
+            fields! {
+                "timeBounds" => optional(r, time_bounds)?,
+                "ledgerBounds" => optional(r, ledger_bounds)?,
+                "minSeqNum" => optional(r, int64)?,
+                "minSeqAge" => uint64(r)?,
+                "minSeqLedgerGap" => uint32(r)?,
+                "extraSigners" => array(r, 2, signer_key)?,
+            },
+        )),
+        v => Err(Error::unknown_discriminant("PreconditionType", v)),
+    }
+}
+
+fn claim_predicate(r: &mut Reader) -> Result<Node> {
+    match r.read_i32()? {
+        0 => Ok(arm("CLAIM_PREDICATE_UNCONDITIONAL", Node::Void)),
+        1 => Ok(arm("CLAIM_PREDICATE_AND", array(r, 2, claim_predicate)?)),
+        2 => Ok(arm("CLAIM_PREDICATE_OR", array(r, 2, claim_predicate)?)),
+        3 => Ok(arm("CLAIM_PREDICATE_NOT", optional(r, claim_predicate)?)),
+        4 => Ok(arm("CLAIM_PREDICATE_BEFORE_ABSOLUTE_TIME", int64(r)?)),
+        5 => Ok(arm("CLAIM_PREDICATE_BEFORE_RELATIVE_TIME", int64(r)?)),
+        v => Err(Error::unknown_discriminant("ClaimPredicateType", v)),
+    }
+}
+
+fn claimant(r: &mut Reader) -> Result<Node> {
+    match r.read_i32()? {
+        0 => Ok(arm(
+            "CLAIMANT_TYPE_V0",
+            fields! {
+                "destination" => account_id(r)?,
+                "predicate" => claim_predicate(r)?,
+            },
+        )),
+        v => Err(Error::unknown_discriminant("ClaimantType", v)),
+    }
+}
+
+fn claimable_balance_id(r: &mut Reader) -> Result<Node> {
+    match r.read_i32()? {
+        0 => Ok(arm("CLAIMABLE_BALANCE_ID_TYPE_V0", hash(r)?)),
+        v => Err(Error::unknown_discriminant("ClaimableBalanceIDType", v)),
+    }
+}
+
+fn ledger_key(r: &mut Reader) -> Result<Node> {
+    match r.read_i32()? {
+        0 => Ok(arm("ACCOUNT", fields! { "accountID" => account_id(r)? })),
+        1 => Ok(arm(
+            "TRUSTLINE",
+            fields! {
+                "accountID" => account_id(r)?,
+                "asset" => trust_line_asset(r)?,
+            },
+        )),
+        2 => Ok(arm(
+            "OFFER",
+            fields! {
+                "sellerID" => account_id(r)?,
+                "offerID" => int64(r)?,
+            },
+        )),
+        3 => Ok(arm(
+            "DATA",
+            fields! {
+                "accountID" => account_id(r)?,
+                "dataName" => string(r, 64)?,
+            },
+        )),
+        4 => Ok(arm(
+            "CLAIMABLE_BALANCE",
+            fields! { "balanceID" => claimable_balance_id(r)? },
+        )),
+        5 => Ok(arm(
+            "LIQUIDITY_POOL",
+            fields! { "liquidityPoolID" => hash(r)? },
+        )),
+        v => Err(Error::unknown_discriminant("LedgerEntryType", v)),
+    }
+}
+
+const OPERATION_TYPES: [&str; 24] = [
+    "CREATE_ACCOUNT",
+    "PAYMENT",
+    "PATH_PAYMENT_STRICT_RECEIVE",
+    "MANAGE_SELL_OFFER",
+    "CREATE_PASSIVE_SELL_OFFER",
+    "SET_OPTIONS",
+    "CHANGE_TRUST",
+    "ALLOW_TRUST",
+    "ACCOUNT_MERGE",
+    "INFLATION",
+    "MANAGE_DATA",
+    "BUMP_SEQUENCE",
+    "MANAGE_BUY_OFFER",
+    "PATH_PAYMENT_STRICT_SEND",
+    "CREATE_CLAIMABLE_BALANCE",
+    "CLAIM_CLAIMABLE_BALANCE",
+    "BEGIN_SPONSORING_FUTURE_RESERVES",
+    "END_SPONSORING_FUTURE_RESERVES",
+    "REVOKE_SPONSORSHIP",
+    "CLAWBACK",
+    "CLAWBACK_CLAIMABLE_BALANCE",
+    "SET_TRUST_LINE_FLAGS",
+    "LIQUIDITY_POOL_DEPOSIT",
+    "LIQUIDITY_POOL_WITHDRAW",
+];
+
+fn operation_type(r: &mut Reader) -> Result<(i32, &'static str)> {
+    let kind = r.read_i32()?;
+    if kind >= 0 && (kind as usize) < OPERATION_TYPES.len() {
+        Ok((kind, OPERATION_TYPES[kind as usize]))
+    } else {
+        Err(Error::unknown_discriminant("OperationType", kind))
+    }
+}
+
+pub(super) fn operation_body(r: &mut Reader) -> Result<Node> {
+    let (kind, name) = operation_type(r)?;
+    let body = match kind {
+        0 => fields! {
+            "destination" => account_id(r)?,
+            "startingBalance" => int64(r)?,
+        },
+        1 => fields! {
+            "destination" => muxed_account(r)?,
+            "asset" => asset(r)?,
+            "amount" => int64(r)?,
+        },
+        2 => fields! {
+            "sendAsset" => asset(r)?,
+            "sendMax" => int64(r)?,
+            "destination" => muxed_account(r)?,
+            "destAsset" => asset(r)?,
+            "destAmount" => int64(r)?,
+            "path" => array(r, 5, asset)?,
+        },
+        3 => fields! {
+            "selling" => asset(r)?,
+            "buying" => asset(r)?,
+            "amount" => int64(r)?,
+            "price" => price(r)?,
+            "offerID" => int64(r)?,
+        },
+        4 => fields! {
+            "selling" => asset(r)?,
+            "buying" => asset(r)?,
+            "amount" => int64(r)?,
+            "price" => price(r)?,
+        },
+        5 => fields! {
+            "inflationDest" => optional(r, account_id)?,
+            "clearFlags" => optional(r, uint32)?,
+            "setFlags" => optional(r, uint32)?,
+            "masterWeight" => optional(r, uint32)?,
+            "lowThreshold" => optional(r, uint32)?,
+            "medThreshold" => optional(r, uint32)?,
+            "highThreshold" => optional(r, uint32)?,
+            "homeDomain" => optional(r, |r| string(r, 32))?,
+            "signer" => optional(r, signer)?,
+        },
+        6 => fields! {
+            "line" => change_trust_asset(r)?,
+            "limit" => int64(r)?,
+        },
+        7 => fields! {
+            "trustor" => account_id(r)?,
+            "asset" => match r.read_i32()? {
+                1 => arm("ASSET_TYPE_CREDIT_ALPHANUM4", asset_code(r.read_fixed(4)?)),
+                2 => arm("ASSET_TYPE_CREDIT_ALPHANUM12", asset_code(r.read_fixed(12)?)),
+                v => return Err(Error::unknown_discriminant("AssetCode", v)),
+            },
+            "authorize" => uint32(r)?,
+        },
+        8 => muxed_account(r)?,
+        9 | 17 => Node::Void,
+        10 => fields! {
+            "dataName" => string(r, 64)?,
+            "dataValue" => optional(r, |r| r.read_var(64).map(|b| Node::Bytes(b.to_vec())))?,
+        },
+        11 => fields! { "bumpTo" => int64(r)? },
+        12 => fields! {
+            "selling" => asset(r)?,
+            "buying" => asset(r)?,
+            "buyAmount" => int64(r)?,
+            "price" => price(r)?,
+            "offerID" => int64(r)?,
+        },
+        13 => fields! {
+            "sendAsset" => asset(r)?,
+            "sendAmount" => int64(r)?,
+            "destination" => muxed_account(r)?,
+            "destAsset" => asset(r)?,
+            "destMin" => int64(r)?,
+            "path" => array(r, 5, asset)?,
+        },
+        14 => fields! {
+            "asset" => asset(r)?,
+            "amount" => int64(r)?,
+            "claimants" => array(r, 10, claimant)?,
+        },
+        15 | 20 => fields! { "balanceID" => claimable_balance_id(r)? },
+        16 => fields! { "sponsoredID" => account_id(r)? },
+        18 => match r.read_i32()? {
+            0 => arm("REVOKE_SPONSORSHIP_LEDGER_ENTRY", ledger_key(r)?),
+            1 => arm(
+                "REVOKE_SPONSORSHIP_SIGNER",
+                fields! {
+                    "accountID" => account_id(r)?,
+                    "signerKey" => signer_key(r)?,
+                },
+            ),
+            v => return Err(Error::unknown_discriminant("RevokeSponsorshipType", v)),
+        },
+        19 => fields! {
+            "asset" => asset(r)?,
+            "from" => muxed_account(r)?,
+            "amount" => int64(r)?,
+        },
+        21 => fields! {
+            "trustor" => account_id(r)?,
+            "asset" => asset(r)?,
+            "clearFlags" => uint32(r)?,
+            "setFlags" => uint32(r)?,
+        },
+        22 => fields! {
+            "liquidityPoolID" => hash(r)?,
+            "maxAmountA" => int64(r)?,
+            "maxAmountB" => int64(r)?,
+            "minPrice" => price(r)?,
+            "maxPrice" => price(r)?,
+        },
+        23 => fields! {
+            "liquidityPoolID" => hash(r)?,
+            "amount" => int64(r)?,
+            "minAmountA" => int64(r)?,
+            "minAmountB" => int64(r)?,
+        },
+        _ => unreachable!(),
+    };
+    Ok(arm(name, body))
+}
+
+fn operation(r: &mut Reader) -> Result<Node> {
+    Ok(fields! {
+        "sourceAccount" => optional(r, muxed_account)?,
+        "body" => operation_body(r)?,
+    })
+}
+
+fn decorated_signature(r: &mut Reader) -> Result<Node> {
+    Ok(fields! {
+        "hint" => Node::Bytes(r.read_fixed(4)?.to_vec()),
+        "signature" => Node::Bytes(r.read_var(64)?.to_vec()),
+    })
+}
+
+fn transaction_v0(r: &mut Reader) -> Result<Node> {
+    Ok(fields! {
+        "sourceAccountEd25519" => ed25519(r, Version::AccountId)?,
+        "fee" => uint32(r)?,
+        "seqNum" => int64(r)?,
+        "timeBounds" => optional(r, time_bounds)?,
+        "memo" => memo(r)?,
+        "operations" => array(r, 100, operation)?,
+        "ext" => ext_v0(r)?,
+    })
+}
+
+/// Transaction
+pub(super) fn transaction(r: &mut Reader) -> Result<Node> {
+    Ok(fields! {
+        "sourceAccount" => muxed_account(r)?,
+        "fee" => uint32(r)?,
+        "seqNum" => int64(r)?,
+        "cond" => preconditions(r)?,
+        "memo" => memo(r)?,
+        "operations" => array(r, 100, operation)?,
+        "ext" => ext_v0(r)?,
+    })
+}
+
+fn transaction_v1_envelope(r: &mut Reader) -> Result<Node> {
+    Ok(fields! {
+        "tx" => transaction(r)?,
+        "signatures" => array(r, 20, decorated_signature)?,
+    })
+}
+
+fn fee_bump_transaction(r: &mut Reader) -> Result<Node> {
+    Ok(fields! {
+        "feeSource" => muxed_account(r)?,
+        "fee" => int64(r)?,
+        "innerTx" => match r.read_i32()? {
+            2 => arm("ENVELOPE_TYPE_TX", transaction_v1_envelope(r)?),
+            v => return Err(Error::unknown_discriminant("EnvelopeType", v)),
+        },
+        "ext" => ext_v0(r)?,
+    })
+}
+
+/// TransactionEnvelope
+pub(super) fn transaction_envelope(r: &mut Reader) -> Result<Node> {
+    match r.read_i32()? {
+        0 => Ok(arm(
+            "ENVELOPE_TYPE_TX_V0",
+            fields! {
+                "tx" => transaction_v0(r)?,
+                "signatures" => array(r, 20, decorated_signature)?,
+            },
+        )),
+        2 => Ok(arm("ENVELOPE_TYPE_TX", transaction_v1_envelope(r)?)),
+        5 => Ok(arm(
+            "ENVELOPE_TYPE_TX_FEE_BUMP",
+            fields! {
+                "tx" => fee_bump_transaction(r)?,
+                "signatures" => array(r, 20, decorated_signature)?,
+            },
+        )),
+        v => Err(Error::unknown_discriminant("EnvelopeType", v)),
+    }
+}
+
+fn claim_atom(r: &mut Reader) -> Result<Node> {
+    match r.read_i32()? {
+        0 => Ok(arm(
+            "CLAIM_ATOM_TYPE_V0",
+            fields! {
+                "sellerEd25519" => ed25519(r, Version::AccountId)?,
+                "offerID" => int64(r)?,
+                "assetSold" => asset(r)?,
+                "amountSold" => int64(r)?,
+                "assetBought" => asset(r)?,
+                "amountBought" => int64(r)?,
+            },
+        )),
+        1 => Ok(arm(
+            "CLAIM_ATOM_TYPE_ORDER_BOOK",
+            fields! {
+                "sellerID" => account_id(r)?,
+                "offerID" => int64(r)?,
+                "assetSold" => asset(r)?,
+                "amountSold" => int64(r)?,
+                "assetBought" => asset(r)?,
+                "amountBought" => int64(r)?,
+            },
+        )),
+        2 => Ok(arm(
+            "CLAIM_ATOM_TYPE_LIQUIDITY_POOL",
+            fields! {
+                "liquidityPoolID" => hash(r)?,
+                "assetSold" => asset(r)?,
+                "amountSold" => int64(r)?,
+                "assetBought" => asset(r)?,
+                "amountBought" => int64(r)?,
+            },
+        )),
+        v => Err(Error::unknown_discriminant("ClaimAtomType", v)),
+    }
+}
+
+fn offer_entry(r: &mut Reader) -> Result<Node> {
+    Ok(fields! {
+        "sellerID" => account_id(r)?,
+        "offerID" => int64(r)?,
+        "selling" => asset(r)?,
+        "buying" => asset(r)?,
+        "amount" => int64(r)?,
+        "price" => price(r)?,
+        "flags" => uint32(r)?,
+        "ext" => ext_v0(r)?,
+    })
+}
+
+// Result code names for each operation, indexed by the negated code.
+fn result_codes(kind: i32) -> &'static [&'static str] {
+    const MANAGE_OFFER: &[&str] = &[
+        "SUCCESS", "MALFORMED", "SELL_NO_TRUST", "BUY_NO_TRUST", "SELL_NOT_AUTHORIZED",
+        "BUY_NOT_AUTHORIZED", "LINE_FULL", "UNDERFUNDED", "CROSS_SELF", "SELL_NO_ISSUER",
+        "BUY_NO_ISSUER", "NOT_FOUND", "LOW_RESERVE",
+    ];
+    match kind {
+        0 => &["SUCCESS", "MALFORMED", "UNDERFUNDED", "LOW_RESERVE", "ALREADY_EXIST"],
+        1 => &[
+            "SUCCESS", "MALFORMED", "UNDERFUNDED", "SRC_NO_TRUST", "SRC_NOT_AUTHORIZED",
+            "NO_DESTINATION", "NO_TRUST", "NOT_AUTHORIZED", "LINE_FULL", "NO_ISSUER",
+        ],
+        2 => &[
+            "SUCCESS", "MALFORMED", "UNDERFUNDED", "SRC_NO_TRUST", "SRC_NOT_AUTHORIZED",
+            "NO_DESTINATION", "NO_TRUST", "NOT_AUTHORIZED", "LINE_FULL", "NO_ISSUER",
+            "TOO_FEW_OFFERS", "OFFER_CROSS_SELF", "OVER_SENDMAX",
+        ],
+        3 | 4 | 12 => MANAGE_OFFER,
+        5 => &[
+            "SUCCESS", "LOW_RESERVE", "TOO_MANY_SIGNERS", "BAD_FLAGS", "INVALID_INFLATION",
+            "CANT_CHANGE", "UNKNOWN_FLAG", "THRESHOLD_OUT_OF_RANGE", "BAD_SIGNER",
+            "INVALID_HOME_DOMAIN", "AUTH_REVOCABLE_REQUIRED",
+        ],
+        6 => &[
+            "SUCCESS", "MALFORMED", "NO_ISSUER", "INVALID_LIMIT", "LOW_RESERVE",
+            "SELF_NOT_ALLOWED", "TRUST_LINE_MISSING", "CANNOT_DELETE",
+            "NOT_AUTH_MAINTAIN_LIABILITIES",
+        ],
+        7 => &[
+            "SUCCESS", "MALFORMED", "NO_TRUST_LINE", "TRUST_NOT_REQUIRED", "CANT_REVOKE",
+            "SELF_NOT_ALLOWED", "LOW_RESERVE",
+        ],
+        8 => &[
+            "SUCCESS", "MALFORMED", "NO_ACCOUNT", "IMMUTABLE_SET", "HAS_SUB_ENTRIES",
+            "SEQNUM_TOO_FAR", "DEST_FULL", "IS_SPONSOR",
+        ],
+        9 => &["SUCCESS", "NOT_TIME"],
+        10 => &[
+            "SUCCESS", "NOT_SUPPORTED_YET", "NAME_NOT_FOUND", "LOW_RESERVE", "INVALID_NAME",
+        ],
+        11 => &["SUCCESS", "BAD_SEQ"],
+        13 => &[
+            "SUCCESS", "MALFORMED", "UNDERFUNDED", "SRC_NO_TRUST", "SRC_NOT_AUTHORIZED",
+            "NO_DESTINATION", "NO_TRUST", "NOT_AUTHORIZED", "LINE_FULL", "NO_ISSUER",
+            "TOO_FEW_OFFERS", "OFFER_CROSS_SELF", "UNDER_DESTMIN",
+        ],
+        14 => &[
+            "SUCCESS", "MALFORMED", "LOW_RESERVE", "NO_TRUST", "NOT_AUTHORIZED", "UNDERFUNDED",
+        ],
+        15 => &[
+            "SUCCESS", "DOES_NOT_EXIST", "CANNOT_CLAIM", "LINE_FULL", "NO_TRUST",
+            "NOT_AUTHORIZED",
+        ],
+        16 => &["SUCCESS", "MALFORMED", "ALREADY_SPONSORED", "RECURSIVE"],
+        17 => &["SUCCESS", "NOT_SPONSORED"],
+        18 => &[
+            "SUCCESS", "DOES_NOT_EXIST", "NOT_SPONSOR", "LOW_RESERVE", "ONLY_TRANSFERABLE",
+            "MALFORMED",
+        ],
+        19 => &["SUCCESS", "MALFORMED", "NOT_CLAWBACK_ENABLED", "NO_TRUST", "UNDERFUNDED"],
+        20 => &["SUCCESS", "DOES_NOT_EXIST", "NOT_ISSUER", "NOT_CLAWBACK_ENABLED"],
+        21 => &[
+            "SUCCESS", "MALFORMED", "NO_TRUST_LINE", "CANT_REVOKE", "INVALID_STATE",
+            "LOW_RESERVE",
+        ],
+        22 => &[
+            "SUCCESS", "MALFORMED", "NO_TRUST", "NOT_AUTHORIZED", "UNDERFUNDED", "LINE_FULL",
+            "BAD_PRICE", "POOL_FULL",
+        ],
+        23 => &[
+            "SUCCESS", "MALFORMED", "NO_TRUST", "UNDERFUNDED", "LINE_FULL", "UNDER_MINIMUM",
+        ],
+        _ => &[],
+    }
+}
+
+fn operation_inner_result(r: &mut Reader) -> Result<Node> {
+    let (kind, name) = operation_type(r)?;
+    // Passive offers share their result type with sell offers.
+    let prefix = if kind == 4 { OPERATION_TYPES[3] } else { name };
+    let code = r.read_i32()?;
+    let codes = result_codes(kind);
+    if code > 0 || (-code) as usize >= codes.len() {
+        return Err(Error::unknown_discriminant("OperationResultCode", code));
+    }
+    let code_name = format!("{}_{}", prefix, codes[(-code) as usize]);
+    let value = match (kind, code) {
+        (2, 0) | (13, 0) => fields! {
+            "offers" => array(r, u32::MAX as usize, claim_atom)?,
+            "last" => fields! {
+                "destination" => account_id(r)?,
+                "asset" => asset(r)?,
+                "amount" => int64(r)?,
+            },
+        },
+        (2, -9) | (13, -9) => fields! { "noIssuer" => asset(r)? },
+        (3, 0) | (4, 0) | (12, 0) => fields! {
+            "offersClaimed" => array(r, u32::MAX as usize, claim_atom)?,
+            "offer" => match r.read_i32()? {
+                0 => arm("MANAGE_OFFER_CREATED", offer_entry(r)?),
+                1 => arm("MANAGE_OFFER_UPDATED", offer_entry(r)?),
+                2 => arm("MANAGE_OFFER_DELETED", Node::Void),
+                v => return Err(Error::unknown_discriminant("ManageOfferEffect", v)),
+            },
+        },
+        (8, 0) => fields! { "sourceAccountBalance" => int64(r)? },
+        (9, 0) => fields! {
+            "payouts" => array(r, u32::MAX as usize, |r| Ok(fields! {
+                "destination" => account_id(r)?,
+                "amount" => int64(r)?,
+            }))?,
+        },
+        (14, 0) => fields! { "balanceID" => claimable_balance_id(r)? },
+        _ => Node::Void,
+    };
+    Ok(arm(name, arm(&code_name, value)))
+}
+
+fn operation_result(r: &mut Reader) -> Result<Node> {
+    match r.read_i32()? {
+        0 => Ok(arm("opINNER", operation_inner_result(r)?)),
+        -1 => Ok(arm("opBAD_AUTH", Node::Void)),
+        -2 => Ok(arm("opNO_ACCOUNT", Node::Void)),
+        -3 => Ok(arm("opNOT_SUPPORTED", Node::Void)),
+        -4 => Ok(arm("opTOO_MANY_SUBENTRIES", Node::Void)),
+        -5 => Ok(arm("opEXCEEDED_WORK_LIMIT", Node::Void)),
+        -6 => Ok(arm("opTOO_MANY_SPONSORING", Node::Void)),
+        v => Err(Error::unknown_discriminant("OperationResultCode", v)),
+    }
+}
+
+const TRANSACTION_RESULT_CODES: [&str; 17] = [
+    "txSUCCESS",
+    "txFAILED",
+    "txTOO_EARLY",
+    "txTOO_LATE",
+    "txMISSING_OPERATION",
+    "txBAD_SEQ",
+    "txBAD_AUTH",
+    "txINSUFFICIENT_BALANCE",
+    "txNO_ACCOUNT",
+    "txINSUFFICIENT_FEE",
+    "txBAD_AUTH_EXTRA",
+    "txINTERNAL_ERROR",
+    "txNOT_SUPPORTED",
+    "txFEE_BUMP_INNER_FAILED",
+    "txBAD_SPONSORSHIP",
+    "txBAD_MIN_SEQ_AGE_OR_GAP",
+    "txMALFORMED",
+];
+
+fn transaction_result_code(code: i32) -> Result<&'static str> {
+    match code {
+        1 => Ok("txFEE_BUMP_INNER_SUCCESS"),
+        c if c <= 0 && ((-c) as usize) < TRANSACTION_RESULT_CODES.len() => {
+            Ok(TRANSACTION_RESULT_CODES[(-c) as usize])
+        }
+        c => Err(Error::unknown_discriminant("TransactionResultCode", c)),
+    }
+}
+
+fn inner_transaction_result(r: &mut Reader) -> Result<Node> {
+    Ok(fields! {
+        "feeCharged" => int64(r)?,
+        "result" => {
+            let code = r.read_i32()?;
+            let name = transaction_result_code(code)?;
+            match code {
+                0 | -1 => arm(name, array(r, 100, operation_result)?),
+                1 | -13 => return Err(Error::unknown_discriminant("InnerTransactionResult", code)),
+                _ => arm(name, Node::Void),
+            }
+        },
+        "ext" => ext_v0(r)?,
+    })
+}
+
+/// TransactionResult
+pub(super) fn transaction_result(r: &mut Reader) -> Result<Node> {
+    Ok(fields! {
+        "feeCharged" => int64(r)?,
+        "result" => {
+            let code = r.read_i32()?;
+            let name = transaction_result_code(code)?;
+            match code {
+                0 | -1 => arm(name, array(r, 100, operation_result)?),
+                1 | -13 => arm(name, fields! {
+                    "transactionHash" => hash(r)?,
+                    "result" => inner_transaction_result(r)?,
+                }),
+                _ => arm(name, Node::Void),
+            }
+        },
+        "ext" => ext_v0(r)?,
+    })
+}
+
+fn liabilities(r: &mut Reader) -> Result<Node> {
+    Ok(fields! {
+        "buying" => int64(r)?,
+        "selling" => int64(r)?,
+    })
+}
+
+fn account_entry_ext(r: &mut Reader) -> Result<Node> {
+    match r.read_i32()? {
+        0 => Ok(arm("v0", Node::Void)),
+        1 => Ok(arm(
+            "v1",
+            fields! {
+                "liabilities" => liabilities(r)?,
+                "ext" => match r.read_i32()? {
+                    0 => arm("v0", Node::Void),
+                    2 => arm("v2", fields! {
+                        "numSponsored" => uint32(r)?,
+                        "numSponsoring" => uint32(r)?,
+                        "signerSponsoringIDs" => array(r, 20, |r| optional(r, account_id))?,
+                        "ext" => match r.read_i32()? {
+                            0 => arm("v0", Node::Void),
+                            3 => arm("v3", fields! {
+                                "ext" => ext_v0(r)?,
+                                "seqLedger" => uint32(r)?,
+                                "seqTime" => uint64(r)?,
+                            }),
+                            v => return Err(Error::unknown_discriminant("AccountEntryExtensionV2", v)),
+                        },
+                    }),
+                    v => return Err(Error::unknown_discriminant("AccountEntryExtensionV1", v)),
+                },
+            },
+        )),
+        v => Err(Error::unknown_discriminant("AccountEntry", v)),
+    }
+}
+
+fn account_entry(r: &mut Reader) -> Result<Node> {
+    Ok(fields! {
+        "accountID" => account_id(r)?,
+        "balance" => int64(r)?,
+        "seqNum" => int64(r)?,
+        "numSubEntries" => uint32(r)?,
+        "inflationDest" => optional(r, account_id)?,
+        "flags" => uint32(r)?,
+        "homeDomain" => string(r, 32)?,
+        "thresholds" => Node::Bytes(r.read_fixed(4)?.to_vec()),
+        "signers" => array(r, 20, signer)?,
+        "ext" => account_entry_ext(r)?,
+    })
+}
+
+fn trust_line_entry(r: &mut Reader) -> Result<Node> {
+    Ok(fields! {
+        "accountID" => account_id(r)?,
+        "asset" => trust_line_asset(r)?,
+        "balance" => int64(r)?,
+        "limit" => int64(r)?,
+        "flags" => uint32(r)?,
+        "ext" => match r.read_i32()? {
+            0 => arm("v0", Node::Void),
+            1 => arm("v1", fields! {
+                "liabilities" => liabilities(r)?,
+                "ext" => match r.read_i32()? {
+                    0 => arm("v0", Node::Void),
+                    2 => arm("v2", fields! {
+                        "liquidityPoolUseCount" => int32(r)?,
+                        "ext" => ext_v0(r)?,
+                    }),
+                    v => return Err(Error::unknown_discriminant("TrustLineEntryV1", v)),
+                },
+            }),
+            v => return Err(Error::unknown_discriminant("TrustLineEntry", v)),
+        },
+    })
+}
+
+fn data_entry(r: &mut Reader) -> Result<Node> {
+    Ok(fields! {
+        "accountID" => account_id(r)?,
+        "dataName" => string(r, 64)?,
+        "dataValue" => Node::Bytes(r.read_var(64)?.to_vec()),
+        "ext" => ext_v0(r)?,
+    })
+}
+
+fn claimable_balance_entry(r: &mut Reader) -> Result<Node> {
+    Ok(fields! {
+        "balanceID" => claimable_balance_id(r)?,
+        "claimants" => array(r, 10, claimant)?,
+        "asset" => asset(r)?,
+        "amount" => int64(r)?,
+        "ext" => match r.read_i32()? {
+            0 => arm("v0", Node::Void),
+            1 => arm("v1", fields! {
+                "ext" => ext_v0(r)?,
+                "flags" => uint32(r)?,
+            }),
+            v => return Err(Error::unknown_discriminant("ClaimableBalanceEntry", v)),
+        },
+    })
+}
+
+fn liquidity_pool_entry(r: &mut Reader) -> Result<Node> {
+    Ok(fields! {
+        "liquidityPoolID" => hash(r)?,
+        "body" => match r.read_i32()? {
+            0 => arm("LIQUIDITY_POOL_CONSTANT_PRODUCT", fields! {
+                "params" => fields! {
+                    "assetA" => asset(r)?,
+                    "assetB" => asset(r)?,
+                    "fee" => int32(r)?,
+                },
+                "reserveA" => int64(r)?,
+                "reserveB" => int64(r)?,
+                "totalPoolShares" => int64(r)?,
+                "poolSharesTrustLineCount" => int64(r)?,
+            }),
+            v => return Err(Error::unknown_discriminant("LiquidityPoolType", v)),
+        },
+    })
+}
+
+/// LedgerEntry
+pub(super) fn ledger_entry(r: &mut Reader) -> Result<Node> {
+    Ok(fields! {
+        "lastModifiedLedgerSeq" => uint32(r)?,
+        "data" => match r.read_i32()? {
+            0 => arm("ACCOUNT", account_entry(r)?),
+            1 => arm("TRUSTLINE", trust_line_entry(r)?),
+            2 => arm("OFFER", offer_entry(r)?),
+            3 => arm("DATA", data_entry(r)?),
+            4 => arm("CLAIMABLE_BALANCE", claimable_balance_entry(r)?),
+            5 => arm("LIQUIDITY_POOL", liquidity_pool_entry(r)?),
+            v => return Err(Error::unknown_discriminant("LedgerEntryType", v)),
+        },
+        "ext" => match r.read_i32()? {
+            0 => arm("v0", Node::Void),
+            1 => arm("v1", fields! {
+                "sponsoringID" => optional(r, account_id)?,
+                "ext" => ext_v0(r)?,
+            }),
+            v => return Err(Error::unknown_discriminant("LedgerEntryExt", v)),
+        },
+    })
+}
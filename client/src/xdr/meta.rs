@@ -0,0 +1,444 @@
+use super::{Error, LedgerEntry, LedgerEntryData, LedgerKey, Reader, TrustLineAsset, TryFromXdr};
+use super::entry::{array, ext_v0, optional};
+use resources::{Amount, AssetIdentifier};
+
+type Result<T> = ::std::result::Result<T, Error>;
+
+const UNBOUNDED: usize = u32::MAX as usize;
+
+/// Contract values nested deeper than this are refused rather than risk the
+/// stack on a hostile blob.
+const MAX_SC_VAL_DEPTH: usize = 128;
+
+/// A single change made to the ledger while applying a transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LedgerEntryChange {
+    /// An entry was created.
+    Created(LedgerEntry),
+    /// An entry was updated, holding its new state.
+    Updated(LedgerEntry),
+    /// An entry was removed.
+    Removed(LedgerKey),
+    /// The state of an entry before it was updated or removed.
+    State(LedgerEntry),
+}
+
+impl LedgerEntryChange {
+    /// The key of the entry that changed.
+    pub fn key(&self) -> LedgerKey {
+        match *self {
+            LedgerEntryChange::Created(ref entry)
+            | LedgerEntryChange::Updated(ref entry)
+            | LedgerEntryChange::State(ref entry) => entry.key(),
+            LedgerEntryChange::Removed(ref key) => key.clone(),
+        }
+    }
+}
+
+impl TryFromXdr for LedgerEntryChange {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        match r.read_i32()? {
+            0 => LedgerEntry::try_from_xdr(r).map(LedgerEntryChange::Created),
+            1 => LedgerEntry::try_from_xdr(r).map(LedgerEntryChange::Updated),
+            2 => LedgerKey::try_from_xdr(r).map(LedgerEntryChange::Removed),
+            3 => LedgerEntry::try_from_xdr(r).map(LedgerEntryChange::State),
+            v => Err(Error::unknown_discriminant("LedgerEntryChangeType", v)),
+        }
+    }
+}
+
+fn changes(r: &mut Reader) -> Result<Vec<LedgerEntryChange>> {
+    array(r, UNBOUNDED, LedgerEntryChange::try_from_xdr)
+}
+
+/// The changes a transaction made to the ledger, as found in a transaction's
+/// `result_meta_xdr`. Changes are grouped by the operation that made them,
+/// with the changes made by the transaction itself, such as bumping the
+/// sequence number, kept separately.
+///
+/// From protocol 20 soroban transactions also carry their contract events
+/// and return value in the meta. Those are read past rather than decoded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionMeta {
+    tx_changes_before: Vec<LedgerEntryChange>,
+    operations: Vec<Vec<LedgerEntryChange>>,
+    tx_changes_after: Vec<LedgerEntryChange>,
+}
+
+impl TransactionMeta {
+    /// The changes made by the transaction before its operations were applied.
+    pub fn tx_changes_before(&self) -> &[LedgerEntryChange] {
+        &self.tx_changes_before
+    }
+
+    /// The changes made by each operation, in the order they were applied.
+    pub fn operations(&self) -> &[Vec<LedgerEntryChange>] {
+        &self.operations
+    }
+
+    /// The changes made by the transaction after its operations were applied.
+    pub fn tx_changes_after(&self) -> &[LedgerEntryChange] {
+        &self.tx_changes_after
+    }
+
+    /// Every change in the order it was applied.
+    pub fn changes(&self) -> Vec<&LedgerEntryChange> {
+        self.tx_changes_before
+            .iter()
+            .chain(self.operations.iter().flat_map(|op| op.iter()))
+            .chain(self.tx_changes_after.iter())
+            .collect()
+    }
+
+    /// The net change to each balance touched by the transaction. Accounts
+    /// contribute their native balance and trust lines the balance of their
+    /// asset. Balances that end up where they started are omitted.
+    pub fn balance_changes(&self) -> Vec<BalanceChange> {
+        let mut balances: Vec<BalanceChange> = Vec::new();
+        for change in self.changes() {
+            let (key, amount) = match *change {
+                LedgerEntryChange::Created(ref entry)
+                | LedgerEntryChange::Updated(ref entry)
+                | LedgerEntryChange::State(ref entry) => match balance_of(entry) {
+                    Some(amount) => (entry.key(), amount),
+                    None => continue,
+                },
+                LedgerEntryChange::Removed(ref key) => (key.clone(), Amount::new(0)),
+            };
+            let position = balances.iter().position(|b| b.key == key);
+            match (position, change) {
+                (Some(i), _) => balances[i].after = amount,
+                (None, &LedgerEntryChange::Created(_)) => balances.push(BalanceChange {
+                    key,
+                    before: Amount::new(0),
+                    after: amount,
+                }),
+                (None, &LedgerEntryChange::State(_)) => balances.push(BalanceChange {
+                    key,
+                    before: amount,
+                    after: amount,
+                }),
+                // Updates and removals are always preceded by the state of
+                // the entry, so without one there is nothing to compare to.
+                (None, _) => {}
+            }
+        }
+        balances.retain(|b| b.before != b.after);
+        balances
+    }
+}
+
+fn balance_of(entry: &LedgerEntry) -> Option<Amount> {
+    match *entry.data() {
+        LedgerEntryData::Account(ref account) => Some(account.balance()),
+        LedgerEntryData::Trustline(ref line) => Some(line.balance()),
+        _ => None,
+    }
+}
+
+impl TryFromXdr for TransactionMeta {
+    fn try_from_xdr(r: &mut Reader) -> Result<Self> {
+        let mut meta = TransactionMeta {
+            tx_changes_before: Vec::new(),
+            operations: Vec::new(),
+            tx_changes_after: Vec::new(),
+        };
+        match r.read_i32()? {
+            0 => {
+                meta.operations = array(r, UNBOUNDED, changes)?;
+            }
+            1 => {
+                meta.tx_changes_before = changes(r)?;
+                meta.operations = array(r, UNBOUNDED, changes)?;
+            }
+            2 => {
+                meta.tx_changes_before = changes(r)?;
+                meta.operations = array(r, UNBOUNDED, changes)?;
+                meta.tx_changes_after = changes(r)?;
+            }
+            3 => {
+                ext_v0(r)?;
+                meta.tx_changes_before = changes(r)?;
+                meta.operations = array(r, UNBOUNDED, changes)?;
+                meta.tx_changes_after = changes(r)?;
+                optional(r, skip_soroban_meta)?;
+            }
+            v => return Err(Error::unknown_discriminant("TransactionMeta", v)),
+        }
+        Ok(meta)
+    }
+}
+
+/// Reads past the events, return value and diagnostics of a soroban
+/// transaction.
+fn skip_soroban_meta(r: &mut Reader) -> Result<()> {
+    match r.read_i32()? {
+        0 => {}
+        1 => {
+            ext_v0(r)?;
+            // The non-refundable, refundable and rent fees that were charged.
+            r.read_fixed(24)?;
+        }
+        v => return Err(Error::unknown_discriminant("SorobanTransactionMetaExt", v)),
+    }
+    array(r, UNBOUNDED, skip_contract_event)?;
+    skip_sc_val(r, 0)?;
+    array(r, UNBOUNDED, |r| {
+        // Whether the event was emitted by a call that succeeded.
+        r.read_bool()?;
+        skip_contract_event(r)
+    })?;
+    Ok(())
+}
+
+fn skip_contract_event(r: &mut Reader) -> Result<()> {
+    ext_v0(r)?;
+    optional(r, |r| r.read_fixed(32).map(|_| ()))?;
+    r.read_i32()?;
+    match r.read_i32()? {
+        0 => {
+            array(r, UNBOUNDED, |r| skip_sc_val(r, 0))?;
+            skip_sc_val(r, 0)
+        }
+        v => Err(Error::unknown_discriminant("ContractEventBody", v)),
+    }
+}
+
+fn skip_sc_map(r: &mut Reader, depth: usize) -> Result<()> {
+    array(r, UNBOUNDED, |r| {
+        skip_sc_val(r, depth)?;
+        skip_sc_val(r, depth)
+    })?;
+    Ok(())
+}
+
+fn skip_sc_val(r: &mut Reader, depth: usize) -> Result<()> {
+    if depth > MAX_SC_VAL_DEPTH {
+        return Err(Error::invalid_value("SCVal depth"));
+    }
+    match r.read_i32()? {
+        // Void and the key of a contract instance.
+        1 | 19 => {}
+        // Booleans and 32 bit integers.
+        0 | 3 | 4 => {
+            r.read_fixed(4)?;
+        }
+        // Errors, 64 bit integers, time points, durations and nonce keys.
+        2 | 5 | 6 | 7 | 8 | 20 => {
+            r.read_fixed(8)?;
+        }
+        9 | 10 => {
+            r.read_fixed(16)?;
+        }
+        11 | 12 => {
+            r.read_fixed(32)?;
+        }
+        // Bytes, strings and symbols.
+        13..=15 => {
+            r.read_var(UNBOUNDED)?;
+        }
+        16 => {
+            optional(r, |r| {
+                array(r, UNBOUNDED, |r| skip_sc_val(r, depth + 1)).map(|_| ())
+            })?;
+        }
+        17 => {
+            optional(r, |r| skip_sc_map(r, depth + 1))?;
+        }
+        18 => skip_sc_address(r)?,
+        21 => {
+            match r.read_i32()? {
+                0 => {
+                    r.read_fixed(32)?;
+                }
+                1 => {}
+                v => return Err(Error::unknown_discriminant("ContractExecutable", v)),
+            }
+            optional(r, |r| skip_sc_map(r, depth + 1))?;
+        }
+        v => return Err(Error::unknown_discriminant("SCValType", v)),
+    }
+    Ok(())
+}
+
+fn skip_sc_address(r: &mut Reader) -> Result<()> {
+    let len = match r.read_i32()? {
+        // An account's key type and key.
+        0 => 36,
+        1 | 4 => 32,
+        // A muxed account's id and key.
+        2 => 40,
+        // A claimable balance's id type and hash.
+        3 => 36,
+        v => return Err(Error::unknown_discriminant("SCAddressType", v)),
+    };
+    r.read_fixed(len)?;
+    Ok(())
+}
+
+/// The change to a single balance over the course of a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceChange {
+    key: LedgerKey,
+    before: Amount,
+    after: Amount,
+}
+
+impl BalanceChange {
+    /// The account holding the balance.
+    pub fn account_id(&self) -> &str {
+        match self.key {
+            LedgerKey::Account { ref account_id } | LedgerKey::Trustline { ref account_id, .. } => {
+                account_id
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// The asset of the balance.
+    pub fn asset(&self) -> TrustLineAsset {
+        match self.key {
+            LedgerKey::Trustline { ref asset, .. } => asset.clone(),
+            _ => TrustLineAsset::Asset(AssetIdentifier::native()),
+        }
+    }
+
+    /// The balance before the transaction was applied.
+    pub fn before(&self) -> Amount {
+        self.before
+    }
+
+    /// The balance after the transaction was applied.
+    pub fn after(&self) -> Amount {
+        self.after
+    }
+
+    /// The signed change to the balance in stroops.
+    pub fn delta(&self) -> i64 {
+        self.after.stroops() - self.before.stroops()
+    }
+}
+
+#[cfg(test)]
+mod meta_tests {
+    use super::*;
+    use base64;
+    use resources::Transaction;
+    use serde_json;
+    use xdr::Writer;
+
+    fn meta() -> TransactionMeta {
+        let json = include_str!("../../fixtures/transactions/transaction_memo_text.json");
+        let transaction: Transaction = serde_json::from_str(&json).unwrap();
+        TransactionMeta::try_from_base64(transaction.result_meta_xdr()).unwrap()
+    }
+
+    #[test]
+    fn it_decodes_the_changes_of_each_operation() {
+        let meta = meta();
+        assert!(meta.tx_changes_before().is_empty());
+        assert_eq!(meta.operations().len(), 1);
+        assert_eq!(meta.operations()[0].len(), 4);
+        match meta.operations()[0][0] {
+            LedgerEntryChange::State(_) => {}
+            ref change => panic!("Expected a state change, got {:?}", change),
+        }
+        assert_eq!(meta.operations()[0][0].key(), meta.operations()[0][1].key());
+    }
+
+    fn meta_v3() -> TransactionMeta {
+        let json = include_str!("../../fixtures/transactions/transaction_meta_v3.json");
+        let transaction: Transaction = serde_json::from_str(&json).unwrap();
+        TransactionMeta::try_from_base64(transaction.result_meta_xdr()).unwrap()
+    }
+
+    #[test]
+    fn it_decodes_protocol_20_meta() {
+        assert_eq!(meta_v3(), meta());
+    }
+
+    #[test]
+    fn it_reads_past_soroban_meta() {
+        let json = include_str!("../../fixtures/transactions/transaction_meta_v3.json");
+        let transaction: Transaction = serde_json::from_str(&json).unwrap();
+        let mut bytes = base64::decode(transaction.result_meta_xdr()).unwrap();
+        bytes.truncate(bytes.len() - 4);
+
+        let mut w = Writer::new();
+        w.write_optional(Some(()), |w, _| {
+            // The fees charged.
+            w.write_i32(1);
+            w.write_i32(0);
+            w.write_fixed(&[0; 24]);
+            // A transfer event, topped by its name and the sender.
+            w.write_u32(1);
+            w.write_i32(0);
+            w.write_optional(Some([7; 32]), |w, id| {
+                w.write_fixed(&id);
+                Ok(())
+            })?;
+            w.write_i32(1);
+            w.write_i32(0);
+            w.write_u32(2);
+            w.write_i32(15);
+            w.write_string("transfer", 32)?;
+            w.write_i32(18);
+            w.write_i32(1);
+            w.write_fixed(&[8; 32]);
+            w.write_i32(10);
+            w.write_fixed(&[0; 16]);
+            // A return value of a map holding a vector.
+            w.write_i32(17);
+            w.write_optional(Some(()), |w, _| {
+                w.write_u32(1);
+                w.write_i32(14);
+                w.write_string("ok", 32)?;
+                w.write_i32(16);
+                w.write_optional(Some(()), |w, _| {
+                    w.write_u32(1);
+                    w.write_i32(0);
+                    w.write_bool(true);
+                    Ok(())
+                })
+            })?;
+            // No diagnostic events.
+            w.write_u32(0);
+            Ok(())
+        })
+        .unwrap();
+        bytes.extend_from_slice(w.as_bytes());
+
+        let decoded = TransactionMeta::try_from_base64(&base64::encode(&bytes)).unwrap();
+        assert_eq!(decoded, meta());
+        bytes.push(0);
+        assert!(TransactionMeta::try_from_base64(&base64::encode(&bytes)).is_err());
+    }
+
+    #[test]
+    fn it_refuses_contract_values_nested_too_deeply() {
+        let mut w = Writer::new();
+        for _ in 0..MAX_SC_VAL_DEPTH + 2 {
+            w.write_i32(16);
+            w.write_optional(Some(()), |w, _| {
+                w.write_u32(1);
+                Ok(())
+            })
+            .unwrap();
+        }
+        w.write_i32(1);
+        assert!(skip_sc_val(&mut Reader::new(w.as_bytes()), 0).is_err());
+    }
+
+    #[test]
+    fn it_computes_balance_changes() {
+        let changes = meta().balance_changes();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(
+            changes[0].account_id(),
+            "GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3"
+        );
+        assert_eq!(changes[0].asset(), TrustLineAsset::Asset(AssetIdentifier::native()));
+        assert_eq!(changes[0].delta(), -999_900_000);
+        assert_eq!(changes[1].delta(), 999_900_000);
+    }
+}
@@ -0,0 +1,383 @@
+//! Offline inspection of the XDR blobs returned by horizon.
+//!
+//! Horizon returns several fields, such as a transaction's `envelope_xdr` and
+//! `result_xdr`, as base64 encoded XDR. This module can decode those blobs into
+//! a generic tree and print them in a human readable form, much like the XDR
+//! viewer in the stellar laboratory.
+//!
+//! ```
+//! use stellar_client::xdr::{self, Kind};
+//!
+//! let result = xdr::decode("AAAAAAAAAGQAAAAAAAAAAQAAAAAAAAABAAAAAAAAAAA=").unwrap();
+//! assert_eq!(result.kind(), Kind::TransactionResult);
+//! println!("{}", result);
+//! ```
+use base64;
+use std::error::Error as StdError;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+mod entry;
+mod inspect;
+mod meta;
+mod node;
+mod reader;
+mod writer;
+
+pub use self::entry::{AccountEntry, ClaimPredicate, ClaimableBalanceEntry, Claimant, DataEntry,
+                      LedgerEntry, LedgerEntryData, LedgerKey, Liabilities, LiquidityPoolEntry,
+                      OfferEntry, Signer, TrustLineAsset, TrustLineEntry};
+pub use self::entry::hex;
+pub(crate) use self::entry::{account_id, array, muxed_account, optional, signer_key};
+pub use self::meta::{BalanceChange, LedgerEntryChange, TransactionMeta};
+pub use self::node::Node;
+pub use self::reader::Reader;
+pub use self::writer::{ToXdr, Writer};
+
+/// Decodes a typed value directly from XDR. This is the typed counterpart to
+/// the inspector, for callers that know what they are decoding.
+///
+/// ```
+/// use stellar_client::xdr::{LedgerEntry, TryFromXdr};
+///
+/// let xdr = "AAAAZAAAAAMAAAAAfYea3UlYx+oB1SUN7JS071XzM+onRosbwqtnJe/vxAYAAAAEbmFtZQAAAAV2YWx1ZQAAAAAAAAAAAAAA";
+/// let entry = LedgerEntry::try_from_base64(xdr).unwrap();
+/// assert_eq!(entry.last_modified_ledger_seq(), 100);
+/// ```
+pub trait TryFromXdr
+where
+    Self: Sized,
+{
+    /// Reads the value from the reader, leaving any following bytes unread.
+    fn try_from_xdr(reader: &mut Reader) -> Result<Self, Error>;
+
+    /// Decodes the value from a base64 blob that must contain nothing else.
+    fn try_from_base64(xdr: &str) -> Result<Self, Error> {
+        let bytes = base64::decode(xdr.trim())?;
+        let mut reader = Reader::new(&bytes);
+        let value = Self::try_from_xdr(&mut reader)?;
+        reader.finish()?;
+        Ok(value)
+    }
+}
+
+/// Reads past the body of an operation, checking that it is well formed.
+/// Operations without a typed counterpart are kept as their raw bytes when
+/// decoding a transaction, so this is how their end is found.
+pub(crate) fn skip_operation_body(reader: &mut Reader) -> Result<(), Error> {
+    inspect::operation_body(reader).map(|_| ())
+}
+
+/// The top level XDR types that the inspector knows how to decode.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Kind {
+    /// A transaction along with its signatures.
+    TransactionEnvelope,
+    /// The result of applying a transaction to the ledger.
+    TransactionResult,
+    /// A single entry in the ledger such as an account or trust line.
+    LedgerEntry,
+}
+
+// The order in which kinds are attempted when auto-detecting a blob.
+const DETECTION_ORDER: [Kind; 3] = [
+    Kind::TransactionEnvelope,
+    Kind::TransactionResult,
+    Kind::LedgerEntry,
+];
+
+impl Kind {
+    /// The name of the type as it appears in the XDR definitions.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Kind::TransactionEnvelope => "TransactionEnvelope",
+            Kind::TransactionResult => "TransactionResult",
+            Kind::LedgerEntry => "LedgerEntry",
+        }
+    }
+
+    fn decode(&self, reader: &mut Reader) -> Result<Node, Error> {
+        match *self {
+            Kind::TransactionEnvelope => inspect::transaction_envelope(reader),
+            Kind::TransactionResult => inspect::transaction_result(reader),
+            Kind::LedgerEntry => inspect::ledger_entry(reader),
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Kind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Kind, Error> {
+        match s.to_lowercase().as_str() {
+            "transactionenvelope" | "envelope" => Ok(Kind::TransactionEnvelope),
+            "transactionresult" | "result" => Ok(Kind::TransactionResult),
+            "ledgerentry" | "entry" => Ok(Kind::LedgerEntry),
+            _ => Err(Error::unrecognized()),
+        }
+    }
+}
+
+/// A blob that has been decoded into a tree of nodes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decoded {
+    kind: Kind,
+    root: Node,
+}
+
+impl Decoded {
+    /// The type that the blob was decoded as.
+    pub fn kind(&self) -> Kind {
+        self.kind
+    }
+
+    /// The root of the decoded tree.
+    pub fn root(&self) -> &Node {
+        &self.root
+    }
+
+    /// Reads a value of the given kind, leaving any following bytes unread.
+    pub(crate) fn read(kind: Kind, reader: &mut Reader) -> Result<Decoded, Error> {
+        let root = kind.decode(reader)?;
+        Ok(Decoded { kind, root })
+    }
+}
+
+impl fmt::Display for Decoded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.root.fmt_tree(f, self.kind.name(), 0)
+    }
+}
+
+/// Decodes a base64 blob, detecting which of the known kinds it is. A kind
+/// only matches if it consumes every byte of the blob.
+pub fn decode(xdr: &str) -> Result<Decoded, Error> {
+    let bytes = base64::decode(xdr.trim())?;
+    DETECTION_ORDER
+        .iter()
+        .filter_map(|kind| decode_bytes(*kind, &bytes).ok())
+        .next()
+        .ok_or_else(Error::unrecognized)
+}
+
+/// Decodes a base64 blob as a specific kind.
+pub fn decode_as(kind: Kind, xdr: &str) -> Result<Decoded, Error> {
+    let bytes = base64::decode(xdr.trim())?;
+    decode_bytes(kind, &bytes)
+}
+
+fn decode_bytes(kind: Kind, bytes: &[u8]) -> Result<Decoded, Error> {
+    let mut reader = Reader::new(bytes);
+    let decoded = Decoded::read(kind, &mut reader)?;
+    reader.finish()?;
+    Ok(decoded)
+}
+
+/// An error that occurs while decoding XDR.
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+}
+
+impl Error {
+    /// Constructs an error for when the bytes end before the value does.
+    pub fn unexpected_eof() -> Error {
+        Error {
+            kind: ErrorKind::UnexpectedEof,
+        }
+    }
+
+    /// Constructs an error for bytes left over after decoding a value.
+    pub fn trailing_bytes(count: usize) -> Error {
+        Error {
+            kind: ErrorKind::TrailingBytes(count),
+        }
+    }
+
+    /// Constructs an error for a variable length value that exceeds its bound.
+    pub fn invalid_length(len: usize, max: usize) -> Error {
+        Error {
+            kind: ErrorKind::InvalidLength(len, max),
+        }
+    }
+
+    /// Constructs an error for an enum or union discriminant that is not known.
+    pub fn unknown_discriminant(name: &'static str, value: i32) -> Error {
+        Error {
+            kind: ErrorKind::UnknownDiscriminant(name, value),
+        }
+    }
+
+    /// Constructs an error for a value that decoded but is out of range, such
+    /// as a negative amount.
+    pub fn invalid_value(name: &'static str) -> Error {
+        Error {
+            kind: ErrorKind::InvalidValue(name),
+        }
+    }
+
+    /// Constructs an error for a file that couldn't be decompressed, such as
+    /// a corrupt history archive file.
+    pub fn gzip(inner: io::Error) -> Error {
+        Error {
+            kind: ErrorKind::Gzip(inner),
+        }
+    }
+
+    /// Constructs an error for a blob that doesn't match any known kind.
+    pub fn unrecognized() -> Error {
+        Error {
+            kind: ErrorKind::Unrecognized,
+        }
+    }
+
+    /// The underlying kind of error.
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+}
+
+/// The reasons that decoding XDR can fail.
+#[derive(Debug)]
+pub enum ErrorKind {
+    /// The input was not valid base64.
+    Base64(base64::DecodeError),
+    /// The bytes ran out before the value was fully decoded.
+    UnexpectedEof,
+    /// Bytes were left over after the value was decoded.
+    TrailingBytes(usize),
+    /// A variable length value was longer than the schema allows.
+    InvalidLength(usize, usize),
+    /// An enum or union had a discriminant that isn't in the schema.
+    UnknownDiscriminant(&'static str, i32),
+    /// A value was outside of the range its type allows.
+    InvalidValue(&'static str),
+    /// The blob could not be decoded as any of the known kinds.
+    Unrecognized,
+    /// The gzipped file holding the XDR could not be decompressed.
+    Gzip(io::Error),
+}
+
+impl From<base64::DecodeError> for Error {
+    fn from(inner: base64::DecodeError) -> Error {
+        Error {
+            kind: ErrorKind::Base64(inner),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn description(&self) -> &str {
+        match self.kind {
+            ErrorKind::Base64(ref inner) => inner.description(),
+            ErrorKind::UnexpectedEof => "The XDR ended before the value was complete",
+            ErrorKind::TrailingBytes(_) => "The XDR has bytes left over after decoding",
+            ErrorKind::InvalidLength(_, _) => "The XDR has a value longer than allowed",
+            ErrorKind::UnknownDiscriminant(_, _) => "The XDR has an unknown discriminant",
+            ErrorKind::InvalidValue(_) => "The XDR has a value that is out of range",
+            ErrorKind::Unrecognized => "The XDR could not be decoded as any known type",
+            ErrorKind::Gzip(_) => "The XDR could not be decompressed",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ErrorKind::Base64(ref inner) => write!(f, "{}", inner),
+            ErrorKind::TrailingBytes(count) => {
+                write!(f, "The XDR has {} bytes left over after decoding", count)
+            }
+            ErrorKind::InvalidLength(len, max) => write!(
+                f,
+                "The XDR has a value of length {} where at most {} is allowed",
+                len, max
+            ),
+            ErrorKind::UnknownDiscriminant(name, value) => {
+                write!(f, "The XDR has an unknown discriminant {} for {}", value, name)
+            }
+            ErrorKind::InvalidValue(name) => write!(f, "The XDR has an out of range {}", name),
+            ErrorKind::Gzip(ref inner) => write!(f, "The XDR could not be decompressed: {}", inner),
+            _ => f.write_str(self.description()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod xdr_tests {
+    use super::*;
+    use resources::Transaction;
+    use serde_json;
+
+    fn transaction() -> Transaction {
+        let json = include_str!("../../fixtures/transactions/transaction_memo_text.json");
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn it_detects_and_decodes_an_envelope() {
+        let decoded = decode(transaction().envelope_xdr()).unwrap();
+        assert_eq!(decoded.kind(), Kind::TransactionEnvelope);
+
+        let root = decoded.root();
+        assert_eq!(root.arm(), Some("ENVELOPE_TYPE_TX_V0"));
+        let tx = root.get("tx").unwrap();
+        assert_eq!(
+            tx.get("sourceAccountEd25519").and_then(Node::as_text),
+            Some("GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3")
+        );
+        assert_eq!(tx.get("fee").and_then(Node::as_i64), Some(100));
+
+        let operations = tx.get("operations").and_then(Node::elements).unwrap();
+        assert_eq!(operations.len(), 1);
+        let body = operations[0].get("body").unwrap();
+        assert_eq!(body.arm(), Some("PAYMENT"));
+        assert_eq!(body.get("amount").and_then(Node::as_i64), Some(999_900_000));
+    }
+
+    #[test]
+    fn it_detects_and_decodes_a_result() {
+        let decoded = decode(transaction().result_xdr()).unwrap();
+        assert_eq!(decoded.kind(), Kind::TransactionResult);
+        assert_eq!(
+            format!("{}", decoded),
+            "TransactionResult:
+  feeCharged: 100
+  result: [txSUCCESS]
+    [0]: [opINNER] [PAYMENT] PAYMENT_SUCCESS
+  ext: v0
+"
+        );
+    }
+
+    #[test]
+    fn it_can_decode_as_a_specific_kind() {
+        let xdr = transaction().result_xdr().to_string();
+        assert!(decode_as(Kind::TransactionResult, &xdr).is_ok());
+        assert!(decode_as(Kind::LedgerEntry, &xdr).is_err());
+    }
+
+    #[test]
+    fn it_errors_on_unrecognized_blobs() {
+        assert!(decode("AAAA").is_err());
+        assert!(decode("not base64!").is_err());
+    }
+
+    #[test]
+    fn it_parses_kind_names() {
+        assert_eq!(Kind::from_str("envelope").unwrap(), Kind::TransactionEnvelope);
+        assert_eq!(
+            Kind::from_str("TransactionResult").unwrap(),
+            Kind::TransactionResult
+        );
+        assert_eq!(Kind::from_str("entry").unwrap(), Kind::LedgerEntry);
+        assert!(Kind::from_str("ledger").is_err());
+    }
+}
@@ -0,0 +1,227 @@
+use std::fmt;
+
+const INDENT: &str = "  ";
+
+/// A generic, self describing representation of a decoded XDR value. The
+/// inspector decodes blobs into a tree of nodes which can then be walked or
+/// printed without needing a rust type for every structure on the network.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    /// A structure of named fields, in the order they were decoded.
+    Struct(Vec<(&'static str, Node)>),
+    /// A union with the name of the arm that was selected and its value.
+    Union(String, Box<Node>),
+    /// A fixed or variable length array of values.
+    Array(Vec<Node>),
+    /// An optional value that may or may not be present.
+    Optional(Option<Box<Node>>),
+    /// A signed integer.
+    Int(i64),
+    /// An unsigned integer.
+    UInt(u64),
+    /// A boolean value.
+    Bool(bool),
+    /// A string, or a value that has a natural string representation such as
+    /// an account id or asset code.
+    Text(String),
+    /// Opaque binary data, displayed as hex.
+    Bytes(Vec<u8>),
+    /// The absence of a value, typically the arm of a union.
+    Void,
+}
+
+impl Node {
+    /// Looks up a field by name. Unions and present optionals are looked
+    /// through so that `envelope.get("tx")` works regardless of the envelope
+    /// version that was decoded.
+    pub fn get(&self, field: &str) -> Option<&Node> {
+        match *self {
+            Node::Struct(ref fields) => fields
+                .iter()
+                .find(|&&(name, _)| name == field)
+                .map(|&(_, ref node)| node),
+            Node::Union(_, ref inner) => inner.get(field),
+            Node::Optional(Some(ref inner)) => inner.get(field),
+            _ => None,
+        }
+    }
+
+    /// The name of the selected arm if this node is a union.
+    pub fn arm(&self) -> Option<&str> {
+        match *self {
+            Node::Union(ref arm, _) => Some(arm),
+            _ => None,
+        }
+    }
+
+    /// The elements of the node if it is an array.
+    pub fn elements(&self) -> Option<&[Node]> {
+        match *self {
+            Node::Array(ref nodes) => Some(nodes),
+            _ => None,
+        }
+    }
+
+    /// Returns the text of the node if it is a text node.
+    pub fn as_text(&self) -> Option<&str> {
+        match *self {
+            Node::Text(ref text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of the node if it is any kind of integer that fits
+    /// inside of an i64.
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Node::Int(value) => Some(value),
+            Node::UInt(value) if value <= i64::MAX as u64 => Some(value as i64),
+            _ => None,
+        }
+    }
+
+    fn is_leaf(&self) -> bool {
+        match *self {
+            Node::Struct(ref fields) => fields.is_empty(),
+            Node::Array(ref nodes) => nodes.is_empty(),
+            Node::Union(_, ref inner) => inner.is_leaf(),
+            Node::Optional(Some(ref inner)) => inner.is_leaf(),
+            _ => true,
+        }
+    }
+
+    fn fmt_leaf(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Node::Struct(_) => write!(f, "{{}}"),
+            Node::Array(_) => write!(f, "[]"),
+            Node::Union(ref arm, ref inner) => match **inner {
+                Node::Void => write!(f, "{}", arm),
+                ref inner => {
+                    write!(f, "[{}] ", arm)?;
+                    inner.fmt_leaf(f)
+                }
+            },
+            Node::Optional(Some(ref inner)) => inner.fmt_leaf(f),
+            Node::Optional(None) => write!(f, "none"),
+            Node::Int(value) => write!(f, "{}", value),
+            Node::UInt(value) => write!(f, "{}", value),
+            Node::Bool(value) => write!(f, "{}", value),
+            Node::Text(ref text) => write!(f, "{}", text),
+            Node::Bytes(ref bytes) => {
+                for byte in bytes {
+                    write!(f, "{:02x}", byte)?;
+                }
+                Ok(())
+            }
+            Node::Void => write!(f, "void"),
+        }
+    }
+
+    /// Writes the node as an indented tree with the given label.
+    pub(crate) fn fmt_tree(&self, f: &mut fmt::Formatter, label: &str, depth: usize) -> fmt::Result {
+        for _ in 0..depth {
+            f.write_str(INDENT)?;
+        }
+        if self.is_leaf() {
+            write!(f, "{}: ", label)?;
+            self.fmt_leaf(f)?;
+            return writeln!(f);
+        }
+        match *self {
+            Node::Struct(ref fields) => {
+                writeln!(f, "{}:", label)?;
+                for &(name, ref node) in fields {
+                    node.fmt_tree(f, name, depth + 1)?;
+                }
+                Ok(())
+            }
+            Node::Array(ref nodes) => {
+                writeln!(f, "{}: [{}]", label, nodes.len())?;
+                for (index, node) in nodes.iter().enumerate() {
+                    node.fmt_tree(f, &format!("[{}]", index), depth + 1)?;
+                }
+                Ok(())
+            }
+            Node::Union(ref arm, ref inner) => {
+                writeln!(f, "{}: [{}]", label, arm)?;
+                inner.fmt_children(f, depth + 1)
+            }
+            Node::Optional(Some(ref inner)) => inner.fmt_tree(f, label, depth),
+            _ => unreachable!(),
+        }
+    }
+
+    fn fmt_children(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        match *self {
+            Node::Struct(ref fields) => {
+                for &(name, ref node) in fields {
+                    node.fmt_tree(f, name, depth)?;
+                }
+                Ok(())
+            }
+            Node::Array(ref nodes) => {
+                for (index, node) in nodes.iter().enumerate() {
+                    node.fmt_tree(f, &format!("[{}]", index), depth)?;
+                }
+                Ok(())
+            }
+            Node::Union(ref arm, ref inner) => {
+                inner.fmt_tree(f, arm, depth)
+            }
+            Node::Optional(Some(ref inner)) => inner.fmt_children(f, depth),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_leaf() {
+            self.fmt_leaf(f)
+        } else {
+            self.fmt_children(f, 0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod node_tests {
+    use super::*;
+
+    fn sample() -> Node {
+        Node::Struct(vec![
+            ("fee", Node::UInt(100)),
+            (
+                "memo",
+                Node::Union("MEMO_TEXT".to_string(), Box::new(Node::Text("hi".to_string()))),
+            ),
+            (
+                "signatures",
+                Node::Array(vec![Node::Struct(vec![("hint", Node::Bytes(vec![0, 255]))])]),
+            ),
+            ("timeBounds", Node::Optional(None)),
+        ])
+    }
+
+    #[test]
+    fn it_prints_an_indented_tree() {
+        assert_eq!(
+            format!("{}", sample()),
+            "fee: 100
+memo: [MEMO_TEXT] hi
+signatures: [1]
+  [0]:
+    hint: 00ff
+timeBounds: none
+"
+        );
+    }
+
+    #[test]
+    fn it_can_look_up_fields() {
+        let node = sample();
+        assert_eq!(node.get("fee").and_then(Node::as_i64), Some(100));
+        assert_eq!(node.get("memo").and_then(Node::arm), Some("MEMO_TEXT"));
+        assert!(node.get("missing").is_none());
+    }
+}
@@ -0,0 +1,157 @@
+use super::Error;
+
+/// A cursor over a buffer of XDR encoded bytes. Each read consumes the value
+/// along with any padding needed to stay on a four byte boundary.
+#[derive(Debug, Clone)]
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Creates a reader positioned at the start of the bytes.
+    pub fn new(bytes: &'a [u8]) -> Reader<'a> {
+        Reader { bytes, position: 0 }
+    }
+
+    /// Returns true if every byte has been consumed.
+    pub fn is_empty(&self) -> bool {
+        self.position >= self.bytes.len()
+    }
+
+    /// The number of bytes that have not yet been read.
+    pub fn remaining(&self) -> usize {
+        self.bytes.len().saturating_sub(self.position)
+    }
+
+    /// Errors if there are bytes that have not been consumed. This is useful
+    /// when decoding an entire blob as a single value.
+    pub fn finish(&self) -> Result<(), Error> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::trailing_bytes(self.remaining()))
+        }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.remaining() < len {
+            return Err(Error::unexpected_eof());
+        }
+        let bytes = &self.bytes[self.position..self.position + len];
+        self.position += len;
+        Ok(bytes)
+    }
+
+    /// Reads an unsigned 32 bit integer.
+    pub fn read_u32(&mut self) -> Result<u32, Error> {
+        let b = self.take(4)?;
+        Ok((u32::from(b[0]) << 24) | (u32::from(b[1]) << 16) | (u32::from(b[2]) << 8)
+            | u32::from(b[3]))
+    }
+
+    /// Reads a signed 32 bit integer.
+    pub fn read_i32(&mut self) -> Result<i32, Error> {
+        self.read_u32().map(|v| v as i32)
+    }
+
+    /// Reads an unsigned 64 bit integer.
+    pub fn read_u64(&mut self) -> Result<u64, Error> {
+        let high = u64::from(self.read_u32()?);
+        let low = u64::from(self.read_u32()?);
+        Ok((high << 32) | low)
+    }
+
+    /// Reads a signed 64 bit integer.
+    pub fn read_i64(&mut self) -> Result<i64, Error> {
+        self.read_u64().map(|v| v as i64)
+    }
+
+    /// Reads a boolean, which is encoded as a 32 bit integer of 0 or 1.
+    pub fn read_bool(&mut self) -> Result<bool, Error> {
+        match self.read_u32()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            other => Err(Error::unknown_discriminant("bool", other as i32)),
+        }
+    }
+
+    /// Reads the presence marker of an optional value.
+    pub fn read_optional(&mut self) -> Result<bool, Error> {
+        self.read_bool()
+    }
+
+    /// Reads fixed length opaque data.
+    pub fn read_fixed(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let bytes = self.take(len)?;
+        self.skip_padding(len)?;
+        Ok(bytes)
+    }
+
+    /// Reads variable length opaque data no longer than `max` bytes.
+    pub fn read_var(&mut self, max: usize) -> Result<&'a [u8], Error> {
+        let len = self.read_u32()? as usize;
+        if len > max {
+            return Err(Error::invalid_length(len, max));
+        }
+        self.read_fixed(len)
+    }
+
+    /// Reads a string no longer than `max` bytes. Invalid utf8 is replaced
+    /// rather than rejected since strings on the network are not validated.
+    pub fn read_string(&mut self, max: usize) -> Result<String, Error> {
+        self.read_var(max)
+            .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    /// Reads the length of a variable length array no larger than `max`.
+    pub fn read_len(&mut self, max: usize) -> Result<usize, Error> {
+        let len = self.read_u32()? as usize;
+        if len > max || len > self.remaining() {
+            return Err(Error::invalid_length(len, max));
+        }
+        Ok(len)
+    }
+
+    fn skip_padding(&mut self, len: usize) -> Result<(), Error> {
+        let padding = (4 - len % 4) % 4;
+        self.take(padding).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod reader_tests {
+    use super::*;
+
+    #[test]
+    fn it_reads_integers() {
+        let bytes = [0, 0, 0, 1, 255, 255, 255, 255, 0, 0, 0, 1, 0, 0, 0, 2];
+        let mut reader = Reader::new(&bytes);
+        assert_eq!(reader.read_u32().unwrap(), 1);
+        assert_eq!(reader.read_i32().unwrap(), -1);
+        assert_eq!(reader.read_u64().unwrap(), (1 << 32) + 2);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn it_reads_padded_strings() {
+        let bytes = [0, 0, 0, 5, b'h', b'e', b'l', b'l', b'o', 0, 0, 0];
+        let mut reader = Reader::new(&bytes);
+        assert_eq!(reader.read_string(28).unwrap(), "hello");
+        assert!(reader.finish().is_ok());
+    }
+
+    #[test]
+    fn it_errors_on_overlong_values() {
+        let bytes = [0, 0, 0, 5, b'h', b'e', b'l', b'l', b'o', 0, 0, 0];
+        let mut reader = Reader::new(&bytes);
+        assert!(reader.read_string(4).is_err());
+    }
+
+    #[test]
+    fn it_errors_when_out_of_bytes() {
+        let bytes = [0, 0, 1];
+        let mut reader = Reader::new(&bytes);
+        assert!(reader.read_u32().is_err());
+    }
+}
@@ -0,0 +1,154 @@
+use super::Error;
+use strkey::{self, Version};
+
+/// A buffer that values are encoded into as XDR. Each write pads the value
+/// to a four byte boundary, mirroring the `Reader`.
+#[derive(Debug, Clone, Default)]
+pub struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    /// Creates an empty writer.
+    pub fn new() -> Writer {
+        Writer::default()
+    }
+
+    /// The bytes that have been written so far.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Consumes the writer, returning the encoded bytes.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Writes an unsigned 32 bit integer.
+    pub fn write_u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&[
+            (value >> 24) as u8,
+            (value >> 16) as u8,
+            (value >> 8) as u8,
+            value as u8,
+        ]);
+    }
+
+    /// Writes a signed 32 bit integer.
+    pub fn write_i32(&mut self, value: i32) {
+        self.write_u32(value as u32)
+    }
+
+    /// Writes an unsigned 64 bit integer.
+    pub fn write_u64(&mut self, value: u64) {
+        self.write_u32((value >> 32) as u32);
+        self.write_u32(value as u32);
+    }
+
+    /// Writes a signed 64 bit integer.
+    pub fn write_i64(&mut self, value: i64) {
+        self.write_u64(value as u64)
+    }
+
+    /// Writes a boolean as a 32 bit integer of 0 or 1.
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_u32(value as u32)
+    }
+
+    /// Writes fixed length opaque data.
+    pub fn write_fixed(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+        let padding = (4 - bytes.len() % 4) % 4;
+        self.bytes.extend_from_slice(&[0; 3][..padding]);
+    }
+
+    /// Writes variable length opaque data, which may be no longer than `max`.
+    pub fn write_var(&mut self, bytes: &[u8], max: usize) -> Result<(), Error> {
+        if bytes.len() > max {
+            return Err(Error::invalid_length(bytes.len(), max));
+        }
+        self.write_u32(bytes.len() as u32);
+        self.write_fixed(bytes);
+        Ok(())
+    }
+
+    /// Writes a string, which may be no longer than `max` bytes.
+    pub fn write_string(&mut self, value: &str, max: usize) -> Result<(), Error> {
+        self.write_var(value.as_bytes(), max)
+    }
+
+    /// Writes an optional value, using the closure to write it if present.
+    pub fn write_optional<T, F>(&mut self, value: Option<T>, f: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut Writer, T) -> Result<(), Error>,
+    {
+        match value {
+            Some(value) => {
+                self.write_bool(true);
+                f(self, value)
+            }
+            None => {
+                self.write_bool(false);
+                Ok(())
+            }
+        }
+    }
+
+    /// Writes a strkey encoded account id as an ed25519 public key.
+    pub fn write_account_id(&mut self, account_id: &str) -> Result<(), Error> {
+        let key = strkey::decode_version(Version::AccountId, account_id)
+            .map_err(|_| Error::invalid_value("account id"))?;
+        if key.len() != 32 {
+            return Err(Error::invalid_value("account id"));
+        }
+        self.write_i32(0);
+        self.write_fixed(&key);
+        Ok(())
+    }
+}
+
+/// Encodes a value as XDR. This is the inverse of `TryFromXdr`.
+pub trait ToXdr {
+    /// Writes the value to the writer.
+    fn to_xdr(&self, writer: &mut Writer) -> Result<(), Error>;
+
+    /// Encodes the value as a base64 blob.
+    fn to_base64(&self) -> Result<String, Error> {
+        let mut writer = Writer::new();
+        self.to_xdr(&mut writer)?;
+        Ok(::base64::encode(writer.as_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod writer_tests {
+    use super::*;
+    use xdr::Reader;
+
+    #[test]
+    fn it_writes_values_the_reader_can_read() {
+        let mut writer = Writer::new();
+        writer.write_u32(1);
+        writer.write_i64(-2);
+        writer.write_string("hello", 28).unwrap();
+        writer
+            .write_account_id("GB6YPGW5JFMMP2QB2USQ33EUWTXVL4ZT5ITUNCY3YKVWOJPP57CANOF3")
+            .unwrap();
+        let bytes = writer.into_bytes();
+        assert_eq!(bytes.len(), 4 + 8 + 12 + 36);
+
+        let mut reader = Reader::new(&bytes);
+        assert_eq!(reader.read_u32().unwrap(), 1);
+        assert_eq!(reader.read_i64().unwrap(), -2);
+        assert_eq!(reader.read_string(28).unwrap(), "hello");
+        assert_eq!(reader.read_i32().unwrap(), 0);
+        reader.read_fixed(32).unwrap();
+        assert!(reader.finish().is_ok());
+    }
+
+    #[test]
+    fn it_errors_on_overlong_values() {
+        assert!(Writer::new().write_string("hello", 4).is_err());
+        assert!(Writer::new().write_account_id("GABC").is_err());
+    }
+}
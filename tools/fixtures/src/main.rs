@@ -0,0 +1,343 @@
+#![deny(warnings)]
+//! Pulls live samples of each resource from horizon and writes them over the
+//! fixtures in `client/fixtures`, reporting any sample the client's resources
+//! can no longer parse.
+//!
+//! Run it from the workspace with `cargo run -p stellar-fixtures`, review the
+//! diff and run the client's tests, which parse every fixture. Fixtures that
+//! can't be sampled from a live server, such as errors, submissions and
+//! payment paths, are left as they are.
+extern crate clap;
+extern crate http;
+extern crate serde;
+extern crate serde_json;
+extern crate stellar_client;
+
+use clap::{App, Arg, ArgMatches};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use stellar_client::endpoint::{
+    account, asset, effect, fee, ledger, liquidity_pool, operation, orderbook, root, trade,
+    transaction, Body, Cursor, Direction, IntoRequest, Limit, Order,
+};
+use stellar_client::resources::{
+    Account, Asset, Effect, FeeStats, Ledger, LiquidityPool, Offer, Operation, Orderbook, Root,
+    Trade, TradeAggregation, Transaction,
+};
+use stellar_client::sync::Client;
+
+const PAGE_SIZE: u32 = 200;
+
+/// Requests an endpoint without parsing the response into its resource, so
+/// samples that have drifted from the resource are still fetched.
+#[derive(Debug, Clone)]
+struct AsJson<E>(E);
+
+impl<E: IntoRequest> IntoRequest for AsJson<E> {
+    type Response = Value;
+
+    fn into_request(self, host: &str) -> stellar_client::Result<http::Request<Body>> {
+        self.0.into_request(host)
+    }
+}
+
+type Check = fn(&Value) -> serde_json::Result<()>;
+
+fn check<T: DeserializeOwned>(sample: &Value) -> serde_json::Result<()> {
+    serde_json::from_value::<T>(sample.clone()).map(|_| ())
+}
+
+/// A live sample of a resource, and where it goes in the fixtures.
+struct Sample {
+    path: String,
+    json: Value,
+    check: Check,
+}
+
+impl Sample {
+    fn new(path: &str, json: Value, check: Check) -> Sample {
+        Sample {
+            path: path.to_string(),
+            json,
+            check,
+        }
+    }
+}
+
+fn main() {
+    let matches = App::new("refresh-fixtures")
+        .about("Refreshes the client's test fixtures from a live horizon server.")
+        .arg(
+            Arg::with_name("horizon")
+                .long("horizon")
+                .takes_value(true)
+                .default_value("https://horizon-testnet.stellar.org")
+                .help("The horizon server to sample."),
+        )
+        .arg(
+            Arg::with_name("out")
+                .long("out")
+                .takes_value(true)
+                .help("The fixtures directory to write to. Defaults to the client's."),
+        )
+        .arg(
+            Arg::with_name("pages")
+                .long("pages")
+                .takes_value(true)
+                .default_value("5")
+                .help("How many pages of operations, effects and transactions to search for each type."),
+        )
+        .arg(
+            Arg::with_name("check")
+                .long("check")
+                .help("Only reports samples that no longer parse, without writing fixtures."),
+        )
+        .arg(
+            Arg::with_name("new")
+                .long("new")
+                .help("Also writes fixtures for operation, effect and memo types that don't have one yet."),
+        )
+        .get_matches();
+
+    if let Err(err) = run(&matches) {
+        eprintln!("{}", err);
+        process::exit(2);
+    }
+}
+
+fn run(matches: &ArgMatches) -> Result<(), String> {
+    let horizon = matches.value_of("horizon").unwrap();
+    let client = Client::new(horizon).map_err(|err| format!("Bad horizon url: {}", err))?;
+    let out = matches
+        .value_of("out")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("../../client/fixtures"));
+    let pages = matches
+        .value_of("pages")
+        .unwrap()
+        .parse()
+        .map_err(|_| "--pages must be a number".to_string())?;
+
+    let mut samples = singles(&client)?;
+    samples.extend(keyed(
+        &client,
+        operation::All::default(),
+        pages,
+        "type",
+        "operations/",
+        check::<Operation>,
+    )?);
+    samples.extend(keyed(
+        &client,
+        effect::All::default(),
+        pages,
+        "type",
+        "effects/",
+        check::<Effect>,
+    )?);
+    samples.extend(keyed(
+        &client,
+        transaction::All::default(),
+        pages,
+        "memo_type",
+        "transactions/transaction_memo_",
+        check::<Transaction>,
+    )?);
+
+    let mut drifted = 0;
+    for sample in samples {
+        let path = out.join(&sample.path);
+        if let Err(err) = (sample.check)(&sample.json) {
+            drifted += 1;
+            println!("drifted  {}: {}", sample.path, err);
+        }
+        if matches.is_present("check") {
+            continue;
+        }
+        if !path.exists() && !matches.is_present("new") {
+            println!("skipped  {} (no fixture yet)", sample.path);
+            continue;
+        }
+        let mut json = serde_json::to_string_pretty(&sample.json).unwrap();
+        json.push('\n');
+        fs::write(&path, json)
+            .map_err(|err| format!("Failed to write {}: {}", path.display(), err))?;
+        println!("wrote    {}", sample.path);
+    }
+
+    if drifted > 0 {
+        return Err(format!("{} samples no longer parse", drifted));
+    }
+    Ok(())
+}
+
+/// Samples the resources that have one fixture each.
+fn singles(client: &Client) -> Result<Vec<Sample>, String> {
+    let mut samples = Vec::new();
+
+    let ledgers = fetch(
+        client,
+        ledger::All::default()
+            .with_order(Direction::Desc)
+            .with_limit(1),
+    )?;
+    if let Some(ledger) = first(&ledgers) {
+        samples.push(Sample::new("ledger.json", ledger, check::<Ledger>));
+    }
+
+    let assets = fetch(client, asset::All::default().with_limit(1))?;
+    if let Some(asset) = first(&assets) {
+        samples.push(Sample::new("asset.json", asset.clone(), check::<Asset>));
+        samples.push(Sample::new("asset_stats.json", asset, check::<Asset>));
+    }
+
+    samples.push(Sample::new(
+        "fee_stats.json",
+        fetch(client, fee::Stats)?,
+        check::<FeeStats>,
+    ));
+
+    samples.push(Sample::new(
+        "root.json",
+        fetch(client, root::Details)?,
+        check::<Root>,
+    ));
+
+    let pools = fetch(client, liquidity_pool::All::default().with_limit(1))?;
+    if let Some(pool) = first(&pools) {
+        samples.push(Sample::new(
+            "liquidity_pool.json",
+            pool,
+            check::<LiquidityPool>,
+        ));
+    }
+
+    // The latest trade gives a market and an account that are known to be
+    // active, to sample the rest from.
+    let trades = fetch(
+        client,
+        trade::All::default()
+            .with_order(Direction::Desc)
+            .with_limit(1),
+    )?;
+    let trade = match first(&trades) {
+        Some(trade) => trade,
+        None => return Ok(samples),
+    };
+    samples.push(Sample::new("trade.json", trade.clone(), check::<Trade>));
+    let trade: Trade = match serde_json::from_value(trade) {
+        Ok(trade) => trade,
+        Err(_) => return Ok(samples),
+    };
+
+    let aggregations = fetch(
+        client,
+        trade::Aggregations::new(trade.base_asset(), trade.counter_asset())
+            .with_resolution(3_600_000)
+            .with_order(Direction::Desc)
+            .with_limit(1),
+    )?;
+    if let Some(aggregation) = first(&aggregations) {
+        samples.push(Sample::new(
+            "trade_aggregation.json",
+            aggregation,
+            check::<TradeAggregation>,
+        ));
+    }
+
+    samples.push(Sample::new(
+        "orderbook.json",
+        fetch(
+            client,
+            orderbook::Details::for_asset_pair(
+                trade.base_asset().clone(),
+                trade.counter_asset().clone(),
+            ),
+        )?,
+        check::<Orderbook>,
+    ));
+
+    samples.push(Sample::new(
+        "account.json",
+        fetch(client, account::Details::new(trade.base_account()))?,
+        check::<Account>,
+    ));
+
+    let offers = fetch(
+        client,
+        account::Offers::new(trade.base_account()).with_limit(1),
+    )?;
+    if let Some(offer) = first(&offers) {
+        samples.push(Sample::new("offer.json", offer, check::<Offer>));
+    }
+
+    Ok(samples)
+}
+
+/// Searches recent pages of records for one sample of each kind, telling
+/// kinds apart by the given field.
+fn keyed<E>(
+    client: &Client,
+    endpoint: E,
+    pages: usize,
+    field: &str,
+    prefix: &str,
+    check: Check,
+) -> Result<Vec<Sample>, String>
+where
+    E: IntoRequest + Cursor + Limit + Order + Clone,
+{
+    let mut kinds = BTreeMap::new();
+    let mut cursor: Option<String> = None;
+    for _ in 0..pages {
+        let mut page = endpoint
+            .clone()
+            .with_order(Direction::Desc)
+            .with_limit(PAGE_SIZE);
+        if let Some(ref cursor) = cursor {
+            page = page.with_cursor(cursor);
+        }
+        let page = fetch(client, page)?;
+        let records = records(&page);
+        for record in records {
+            if let Some(kind) = record.get(field).and_then(Value::as_str) {
+                kinds
+                    .entry(kind.to_string())
+                    .or_insert_with(|| record.clone());
+            }
+        }
+        let token = records
+            .last()
+            .and_then(|last| last.get("paging_token"))
+            .and_then(Value::as_str);
+        cursor = match token {
+            Some(token) => Some(token.to_string()),
+            None => break,
+        };
+    }
+    Ok(kinds
+        .into_iter()
+        .map(|(kind, json)| Sample::new(&format!("{}{}.json", prefix, kind), json, check))
+        .collect())
+}
+
+fn fetch<E: IntoRequest>(client: &Client, endpoint: E) -> Result<Value, String> {
+    client
+        .request(AsJson(endpoint))
+        .map_err(|err| format!("Failed to sample horizon: {:?}", err))
+}
+
+fn records(page: &Value) -> &[Value] {
+    page.pointer("/_embedded/records")
+        .and_then(Value::as_array)
+        .map(|records| records.as_slice())
+        .unwrap_or(&[])
+}
+
+fn first(page: &Value) -> Option<Value> {
+    records(page).first().cloned()
+}